@@ -1,16 +1,16 @@
+use crate::SimdFloat;
 use nalgebra_sparse::CsrMatrix;
-use wide::f64x4;
 
-pub struct Matrix {
+pub struct Matrix<T> {
     pub indptr: Vec<usize>,
     pub indices: Vec<usize>,
-    pub data: Vec<f64x4>,
+    pub data: Vec<T>,
     pub size: usize,
 }
 
-impl Matrix {
+impl<T: SimdFloat> Matrix<T> {
     pub fn from_sparse_matrix(a: &CsrMatrix<f64>) -> Self {
-        let data = a.values().iter().map(|&v| f64x4::splat(v)).collect();
+        let data = a.values().iter().map(|&v| T::splat(v)).collect();
         let indptr = a.row_offsets().to_vec();
         let indices = a.col_indices().to_vec();
 
@@ -21,12 +21,24 @@ impl Matrix {
             size: a.nrows(),
         }
     }
+
+    /// Set the value of the element at `row` and `column`.
+    ///
+    /// Panics if the element is not in the sparsity pattern of the matrix.
+    pub fn set_value(&mut self, row: usize, column: usize, value: T) {
+        let start = self.indptr[row];
+        let end = self.indptr[row + 1];
+        let offset = self.indices[start..end]
+            .binary_search(&column)
+            .expect("Element is not in the sparsity pattern of the matrix.");
+        self.data[start + offset] = value;
+    }
 }
 
 /// Compute `out = Ax`
-pub fn matrix_vector_product(matrix: &Matrix, x: &[f64x4], out: &mut [f64x4]) {
+pub fn matrix_vector_product<T: SimdFloat>(matrix: &Matrix<T>, x: &[T], out: &mut [T]) {
     for (row, o) in out.iter_mut().enumerate().take(matrix.size) {
-        let mut val = f64x4::splat(0.0);
+        let mut val = T::splat(0.0);
 
         let first_index = matrix.indptr[row];
         let last_index = matrix.indptr[row + 1];
@@ -41,25 +53,25 @@ pub fn matrix_vector_product(matrix: &Matrix, x: &[f64x4], out: &mut [f64x4]) {
 }
 
 /// Return dot product of x and y
-pub fn dot_product(x: &[f64x4], y: &[f64x4]) -> f64x4 {
-    x.iter().zip(y.iter()).map(|(&a, &b)| a * b).sum()
+pub fn dot_product<T: SimdFloat>(x: &[T], y: &[T]) -> T {
+    x.iter().zip(y.iter()).fold(T::splat(0.0), |acc, (&a, &b)| acc + a * b)
 }
 
 /// `x = x*xscale + y*yscale`
-pub fn vector_update(x: &mut [f64x4], y: &[f64x4], xscale: f64x4, yscale: f64x4) {
+pub fn vector_update<T: SimdFloat>(x: &mut [T], y: &[T], xscale: T, yscale: T) {
     for i in 0..x.len() {
         x[i] = xscale * x[i] + yscale * y[i];
     }
 }
 
 /// `x = scalar`
-pub fn vector_set(x: &mut [f64x4], scalar: f64x4) {
+pub fn vector_set<T: SimdFloat>(x: &mut [T], scalar: T) {
     x.iter_mut().for_each(|a| *a = scalar)
 }
 
 /// return max(x)
-pub fn vector_norm(x: &[f64x4]) -> f64x4 {
-    x.iter().map(|&a| a * a).sum::<f64x4>().sqrt()
+pub fn vector_norm<T: SimdFloat>(x: &[T]) -> T {
+    dot_product(x, x).sqrt()
 }
 
 /// Compute the right-hand side of the system of primal normal equations
@@ -67,18 +79,18 @@ pub fn vector_norm(x: &[f64x4]) -> f64x4 {
 /// `rhs = -(b - A.dot(x) - mu/y - A.dot(x * (c - At.dot(y) + mu/x)/z))`
 ///
 #[allow(clippy::too_many_arguments)]
-pub fn normal_eqn_rhs(
-    a: &Matrix,  // Sparse A matrix
-    at: &Matrix, // Sparse transpose of A matrix
-    x: &[f64x4],
-    z: &[f64x4],
-    y: &[f64x4],
-    b: &[f64x4],
-    c: &[f64x4],
-    mu: f64x4,
+pub fn normal_eqn_rhs<T: SimdFloat>(
+    a: &Matrix<T>,  // Sparse A matrix
+    at: &Matrix<T>, // Sparse transpose of A matrix
+    x: &[T],
+    z: &[T],
+    y: &[T],
+    b: &[T],
+    c: &[T],
+    mu: T,
     wsize: usize,
-    tmp: &mut [f64x4], // work array size of x
-    out: &mut [f64x4], // work array size of b
+    tmp: &mut [T], // work array size of x
+    out: &mut [T], // work array size of b
 ) {
     // Calculate tmp = At.dot(y)
     matrix_vector_product(at, y, tmp);
@@ -93,7 +105,7 @@ pub fn normal_eqn_rhs(
     // Compute out = -(b - A.dot(x) - mu/y -out)
     for row in 0..a.size {
         // The mu/y term is only applied to rows where w is defined.
-        let mut val = if row < wsize { mu / y[row] } else { f64x4::splat(0.0) };
+        let mut val = if row < wsize { mu / y[row] } else { T::splat(0.0) };
 
         let first_index = a.indptr[row];
         let last_index = a.indptr[row + 1];
@@ -111,17 +123,17 @@ pub fn normal_eqn_rhs(
 ///
 /// `normr = || b - A.dot(x) - w || / max(|| b ||, 1)`
 ///
-pub fn primal_feasibility(
-    a: &Matrix, // Sparse A matrix
-    x: &[f64x4],
-    w: &[f64x4],
-    b: &[f64x4],
-) -> f64x4 {
+pub fn primal_feasibility<T: SimdFloat>(
+    a: &Matrix<T>, // Sparse A matrix
+    x: &[T],
+    w: &[T],
+    b: &[T],
+) -> T {
     // Compute ||x||
-    let normx: f64x4 = x.iter().map(|&a| a * a).sum();
+    let normx = dot_product(x, x);
 
     // Compute primal feasibility
-    let mut normr = f64x4::splat(0.0);
+    let mut normr = T::splat(0.0);
     for row in 0..a.size {
         let mut val = b[row];
 
@@ -140,21 +152,21 @@ pub fn primal_feasibility(
         normr += val * val;
     }
 
-    normr.sqrt() / (1.0 + normx.sqrt())
+    normr.sqrt() / (T::splat(1.0) + normx.sqrt())
 }
 
 /// Calculate dual-feasibility
 ///     `norms = || c - AT.dot(y) + z || / max(|| c ||, 1)`
 ///
-pub fn dual_feasibility(
-    at: &Matrix, // Sparse A matrix
-    y: &[f64x4],
-    c: &[f64x4],
-    z: &[f64x4],
-) -> f64x4 {
-    let normy: f64x4 = y.iter().map(|&a| a * a).sum();
-
-    let mut norms = f64x4::splat(0.0);
+pub fn dual_feasibility<T: SimdFloat>(
+    at: &Matrix<T>, // Sparse A matrix
+    y: &[T],
+    c: &[T],
+    z: &[T],
+) -> T {
+    let normy = dot_product(y, y);
+
+    let mut norms = T::splat(0.0);
     // Compute primal feasibility
     for row in 0..at.size {
         let mut val = z[row];
@@ -171,7 +183,7 @@ pub fn dual_feasibility(
         norms += val * val;
     }
 
-    norms.sqrt() / (1.0 + normy.sqrt())
+    norms.sqrt() / (T::splat(1.0) + normy.sqrt())
 }
 
 /// Compute the path step changes given known dy and return maximum value of theta.
@@ -183,25 +195,25 @@ pub fn dual_feasibility(
 ///     dw = (mu - w*dy)/y - w
 ///
 #[allow(clippy::too_many_arguments)]
-pub fn compute_dx_dz_dw(
-    at: &Matrix, // Sparse A matrix
-    x: &[f64x4],
-    z: &[f64x4],
-    y: &[f64x4],
-    w: &[f64x4],
-    c: &[f64x4],
-    dy: &[f64x4],
-    mu: f64x4,
-    dx: &mut [f64x4],
-    dz: &mut [f64x4],
-    dw: &mut [f64x4],
-) -> f64x4 {
-    let mut theta_xz = f64x4::splat(0.0);
-    let mut theta_wy = f64x4::splat(0.0);
+pub fn compute_dx_dz_dw<T: SimdFloat>(
+    at: &Matrix<T>, // Sparse A matrix
+    x: &[T],
+    z: &[T],
+    y: &[T],
+    w: &[T],
+    c: &[T],
+    dy: &[T],
+    mu: T,
+    dx: &mut [T],
+    dz: &mut [T],
+    dw: &mut [T],
+) -> T {
+    let mut theta_xz = T::splat(0.0);
+    let mut theta_wy = T::splat(0.0);
 
     for row in 0..at.size {
-        let mut val = f64x4::splat(0.0);
-        let mut val2 = f64x4::splat(0.0);
+        let mut val = T::splat(0.0);
+        let mut val2 = T::splat(0.0);
 
         let first_index = at.indptr[row];
         let last_index = at.indptr[row + 1];
@@ -1,5 +1,6 @@
 mod common;
 mod path_following_direct;
+mod simd;
 
 use crate::path_following_direct::{normal_eqn_init, normal_eqn_step};
 use common::{Matrix, dual_feasibility, primal_feasibility};
@@ -8,46 +9,48 @@ use nalgebra_sparse::CsrMatrix;
 use path_following_direct::ANormIndices;
 use path_following_direct::LDecompositionIndices;
 use path_following_direct::{LIndices, LTIndices};
+pub use simd::SimdFloat;
 use std::f64;
 use std::fmt::Debug;
 use std::num::NonZeroUsize;
-use wide::f64x4;
 
-struct PathData {
-    x: Vec<f64x4>,
-    z: Vec<f64x4>,
-    y: Vec<f64x4>,
-    w: Vec<f64x4>,
+struct PathData<T> {
+    x: Vec<T>,
+    z: Vec<T>,
+    y: Vec<T>,
+    w: Vec<T>,
 }
 
-impl PathData {
+impl<T: SimdFloat> PathData<T> {
     pub fn new(num_rows: usize, num_cols: usize, num_inequality_constraints: usize) -> Self {
         Self {
-            x: (0..num_cols).map(|_| f64x4::splat(0.0)).collect(),
-            z: (0..num_cols).map(|_| f64x4::splat(0.0)).collect(),
-            y: (0..num_rows).map(|_| f64x4::splat(0.0)).collect(),
-            w: (0..num_inequality_constraints).map(|_| f64x4::splat(0.0)).collect(),
+            x: vec![T::splat(0.0); num_cols],
+            z: vec![T::splat(0.0); num_cols],
+            y: vec![T::splat(0.0); num_rows],
+            w: vec![T::splat(0.0); num_inequality_constraints],
         }
     }
 }
 
-pub struct PathFollowingDirectSimdData {
-    a: Matrix,
-    at: Matrix,
+pub struct PathFollowingDirectSimdData<T> {
+    a: Matrix<T>,
+    at: Matrix<T>,
     a_norm_ptr: ANormIndices,
     l_decomp_ptr: LDecompositionIndices,
     l_ptr: LIndices,
     lt_ptr: LTIndices,
-    l_data: Vec<f64x4>,
+    l_data: Vec<T>,
 
-    path_buffers: PathData,
-    delta_path_buffers: PathData,
+    path_buffers: PathData<T>,
+    delta_path_buffers: PathData<T>,
 
-    tmp: Vec<f64x4>,
-    rhs: Vec<f64x4>,
+    tmp: Vec<T>,
+    rhs: Vec<T>,
+    residual: Vec<T>,
+    correction: Vec<T>,
 }
 
-impl PathFollowingDirectSimdData {
+impl<T: SimdFloat> PathFollowingDirectSimdData<T> {
     pub fn from_data(a: &CsrMatrix<f64>, num_inequality_constraints: usize) -> Self {
         let num_rows = a.nrows();
         let num_cols = a.ncols();
@@ -78,14 +81,16 @@ impl PathFollowingDirectSimdData {
         // println!("ltmap: {}", normal_indices.ltmap.len());
 
         // Require ldata for every SIMD lane
-        let l_data: Vec<f64x4> = (0..normal_indices.lindices.len()).map(|_| f64x4::splat(0.0)).collect();
+        let l_data = vec![T::splat(0.0); normal_indices.lindices.len()];
 
         let path_buffers = PathData::new(num_rows, num_cols, num_inequality_constraints);
         let delta_path_buffers = PathData::new(num_rows, num_cols, num_inequality_constraints);
 
         // Work buffers
-        let tmp = (0..num_cols).map(|_| f64x4::splat(0.0)).collect();
-        let rhs = (0..num_rows).map(|_| f64x4::splat(0.0)).collect();
+        let tmp = vec![T::splat(0.0); num_cols];
+        let rhs = vec![T::splat(0.0); num_rows];
+        let residual = vec![T::splat(0.0); num_rows];
+        let correction = vec![T::splat(0.0); num_rows];
 
         Self {
             a: a_buffers,
@@ -99,32 +104,39 @@ impl PathFollowingDirectSimdData {
             delta_path_buffers,
             tmp,
             rhs,
+            residual,
+            correction,
         }
     }
 }
 
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub struct Tolerances {
-    pub primal_feasibility: f64x4,
-    pub dual_feasibility: f64x4,
-    pub optimality: f64x4,
+    pub primal_feasibility: f64,
+    pub dual_feasibility: f64,
+    pub optimality: f64,
 }
 
 impl Default for Tolerances {
     fn default() -> Self {
         Self {
-            primal_feasibility: f64x4::splat(1e-8),
-            dual_feasibility: f64x4::splat(1e-8),
-            optimality: f64x4::splat(1e-8),
+            primal_feasibility: 1e-8,
+            dual_feasibility: 1e-8,
+            optimality: 1e-8,
         }
     }
 }
 
-pub struct PathFollowingDirectSimdSolver {
-    buffers: PathFollowingDirectSimdData,
+/// A path-following interior point method solving one LP in each SIMD lane of `T`.
+///
+/// The LPs share the sparsity pattern of the constraint matrix, but have different right-hand
+/// sides and objectives. The values of the coefficients may also differ between the LPs (see
+/// [`PathFollowingDirectSimdSolver::set_coefficient`]).
+pub struct PathFollowingDirectSimdSolver<T> {
+    buffers: PathFollowingDirectSimdData<T>,
 }
 
-impl PathFollowingDirectSimdSolver {
+impl<T: SimdFloat> PathFollowingDirectSimdSolver<T> {
     pub fn from_data(
         num_rows: usize,
         num_cols: usize,
@@ -141,13 +153,28 @@ impl PathFollowingDirectSimdSolver {
         Self { buffers }
     }
 
+    /// Set the coefficient of `column` in `row` of the constraint matrix, with a value for each LP.
+    ///
+    /// Only the values of the existing elements can be changed; panics if the element is not in
+    /// the sparsity pattern of the matrix given when the solver was created.
+    pub fn set_coefficient(&mut self, row: usize, column: usize, value: T) {
+        self.buffers.a.set_value(row, column, value);
+        self.buffers.at.set_value(column, row, value);
+    }
+
+    /// Solve the LPs with right-hand sides `b` and objective coefficients `c`.
+    ///
+    /// Each solution of the normal equations is improved with `refinement_steps` steps of
+    /// iterative refinement. This recovers some of the accuracy lost when factorising the
+    /// ill-conditioned normal matrix in single precision.
     pub fn solve(
         &mut self,
-        b: &[f64x4],
-        c: &[f64x4],
+        b: &[T],
+        c: &[T],
         tolerances: &Tolerances,
         max_iterations: NonZeroUsize,
-    ) -> &[f64x4] {
+        refinement_steps: usize,
+    ) -> &[T] {
         normal_eqn_init(
             &mut self.buffers.path_buffers.x,
             &mut self.buffers.path_buffers.z,
@@ -155,7 +182,7 @@ impl PathFollowingDirectSimdSolver {
             &mut self.buffers.path_buffers.w,
         );
 
-        let delta = f64x4::splat(0.1);
+        let delta = T::splat(0.1);
         let mut iter = 0;
 
         let last_iteration = loop {
@@ -183,7 +210,10 @@ impl PathFollowingDirectSimdSolver {
                 &mut self.buffers.delta_path_buffers.w,
                 &mut self.buffers.tmp,
                 &mut self.buffers.rhs,
+                &mut self.buffers.residual,
+                &mut self.buffers.correction,
                 tolerances,
+                refinement_steps,
             );
 
             if status.all() {
@@ -1,8 +1,9 @@
 use super::{Matrix, dual_feasibility, primal_feasibility};
-use crate::Tolerances;
-use crate::common::{compute_dx_dz_dw, dot_product, normal_eqn_rhs, vector_norm, vector_set, vector_update};
+use crate::common::{
+    compute_dx_dz_dw, dot_product, matrix_vector_product, normal_eqn_rhs, vector_norm, vector_set, vector_update,
+};
+use crate::{SimdFloat, Tolerances};
 use ipm_common::SparseNormalCholeskyIndices;
-use wide::{CmpLt, f64x4};
 
 pub struct ANormIndices {
     indptr: Vec<usize>,
@@ -70,18 +71,21 @@ impl LTIndices {
     }
 }
 
+/// The value used in place of a pivot that has broken down in the Cholesky decomposition.
+const PIVOT_REPLACEMENT: f64 = 1e30;
+
 /// Compute the Cholesky decomposition of the normal matrix
 #[allow(clippy::too_many_arguments)]
-pub fn normal_matrix_cholesky_decomposition(
-    a: &Matrix,
+pub fn normal_matrix_cholesky_decomposition<T: SimdFloat>(
+    a: &Matrix<T>,
     a_norm_ptr: &ANormIndices,
     l_decomp_ptr: &LDecompositionIndices,
-    x: &[f64x4],
-    z: &[f64x4],
-    y: &[f64x4],
-    w: &[f64x4],
+    x: &[T],
+    z: &[T],
+    y: &[T],
+    w: &[T],
     l_ptr: &LIndices,
-    l_data: &mut [f64x4],
+    l_data: &mut [T],
 ) {
     let mut l_entry = 0;
     for row in 0..a.size {
@@ -96,7 +100,7 @@ pub fn normal_matrix_cholesky_decomposition(
             let mut val = if (row == col) && (row < w.len()) {
                 w[row] / y[row]
             } else {
-                f64x4::splat(0.0)
+                T::splat(0.0)
             };
 
             let ind_start = a_norm_ptr.indptr[l_entry];
@@ -106,6 +110,7 @@ pub fn normal_matrix_cholesky_decomposition(
                 let xind = a_norm_ptr.indices[ind];
                 val += a.data[a_norm_ptr.indptr_i[ind]] * a.data[a_norm_ptr.indptr_j[ind]] * x[xind] / z[xind];
             }
+            let diag = val;
             // Now remove the previous L entries
             let ind_start = l_decomp_ptr.indptr[l_entry];
             let ind_end = l_decomp_ptr.indptr[l_entry + 1];
@@ -115,7 +120,10 @@ pub fn normal_matrix_cholesky_decomposition(
             }
 
             if row == col {
-                val = val.abs().sqrt();
+                // A pivot lost to cancellation is replaced with a large value, which removes that
+                // row from the step. This is only expected with single precision.
+                let breakdown = val.abs().simd_lt(diag.abs() * T::splat(T::EPSILON));
+                val = breakdown.blend(T::splat(PIVOT_REPLACEMENT), val.abs().sqrt());
             } else {
                 val /= l_data[l_ptr.diag_indptr[col]];
             }
@@ -125,12 +133,47 @@ pub fn normal_matrix_cholesky_decomposition(
     }
 }
 
+/// Compute the product of the normal matrix and `v`.
+///
+/// `out = A.dot(x/z * At.dot(v)) + w/y * v`
+///
+#[allow(clippy::too_many_arguments)]
+fn normal_matrix_vector_product<T: SimdFloat>(
+    a: &Matrix<T>,
+    at: &Matrix<T>,
+    x: &[T],
+    z: &[T],
+    y: &[T],
+    w: &[T],
+    v: &[T],
+    tmp: &mut [T], // work array size of x
+    out: &mut [T],
+) {
+    matrix_vector_product(at, v, tmp);
+    for row in 0..at.size {
+        tmp[row] *= x[row] / z[row];
+    }
+    matrix_vector_product(a, tmp, out);
+
+    // The w/y term is only applied to rows where w is defined.
+    for row in 0..w.len() {
+        out[row] += w[row] / y[row] * v[row];
+    }
+}
+
 ///  Solve a system Ax = b for x given the decomposition of A as L.
 ///
 /// L is a lower triangular matrix. Entries are stored such that the lth
 /// entry of L is the i(i + 1)/2 + j entry in dense i, j  coordinates.
 ///
-fn cholesky_solve(a_size: usize, l_ptr: &LIndices, lt_ptr: &LTIndices, l_data: &[f64x4], b: &[f64x4], x: &mut [f64x4]) {
+fn cholesky_solve<T: SimdFloat>(
+    a_size: usize,
+    l_ptr: &LIndices,
+    lt_ptr: &LTIndices,
+    l_data: &[T],
+    b: &[T],
+    x: &mut [T],
+) {
     // Forward substitution
     for i in 0..a_size {
         x[i] = b[i];
@@ -167,29 +210,32 @@ fn cholesky_solve(a_size: usize, l_ptr: &LIndices, lt_ptr: &LTIndices, l_data: &
 
 /// Perform a single step of the path-following algorithm.
 #[allow(clippy::too_many_arguments)]
-pub fn normal_eqn_step(
-    a: &Matrix,  // Sparse A matrix
-    at: &Matrix, // Sparse transpose of A matrix
+pub fn normal_eqn_step<T: SimdFloat>(
+    a: &Matrix<T>,  // Sparse A matrix
+    at: &Matrix<T>, // Sparse transpose of A matrix
     a_norm_ptr: &ANormIndices,
     l_decomp_ptr: &LDecompositionIndices,
     l_ptr: &LIndices,
     lt_ptr: &LTIndices,
-    l_data: &mut [f64x4],
-    x: &mut [f64x4],
-    z: &mut [f64x4],
-    y: &mut [f64x4],
-    w: &mut [f64x4],
-    b: &[f64x4],
-    c: &[f64x4],
-    delta: f64x4,
-    dx: &mut [f64x4],
-    dz: &mut [f64x4],
-    dy: &mut [f64x4],
-    dw: &mut [f64x4],
-    tmp: &mut [f64x4],
-    tmp2: &mut [f64x4],
+    l_data: &mut [T],
+    x: &mut [T],
+    z: &mut [T],
+    y: &mut [T],
+    w: &mut [T],
+    b: &[T],
+    c: &[T],
+    delta: T,
+    dx: &mut [T],
+    dz: &mut [T],
+    dy: &mut [T],
+    dw: &mut [T],
+    tmp: &mut [T],
+    tmp2: &mut [T],
+    residual: &mut [T],
+    correction: &mut [T],
     tolerances: &Tolerances,
-) -> f64x4 {
+    refinement_steps: usize,
+) -> T {
     // printf("%d %d", gid, wsize);
 
     // Compute feasibilities
@@ -199,9 +245,9 @@ pub fn normal_eqn_step(
     // Compute optimality
     let mut gamma = dot_product(z, x) + dot_product(w, y);
 
-    let mu = delta * gamma / (at.size + w.len()) as f64;
+    let mu = delta * gamma / T::splat((at.size + w.len()) as f64);
     // update relative tolerance
-    gamma /= 1.0 + vector_norm(x) + vector_norm(y);
+    gamma /= T::splat(1.0) + vector_norm(x) + vector_norm(y);
 
     let is_nan = gamma.is_nan();
     if is_nan.any() {
@@ -214,9 +260,9 @@ pub fn normal_eqn_step(
     // }
     // #endif
 
-    let status = normr.simd_lt(tolerances.primal_feasibility)
-        & norms.simd_lt(tolerances.dual_feasibility)
-        & gamma.simd_lt(tolerances.optimality);
+    let status = normr.simd_lt(T::splat(tolerances.primal_feasibility))
+        & norms.simd_lt(T::splat(tolerances.dual_feasibility))
+        & gamma.simd_lt(T::splat(tolerances.optimality));
 
     if status.all() {
         // Feasible and optimal; no further work!
@@ -233,6 +279,18 @@ pub fn normal_eqn_step(
     //   3. Solve system directly
     cholesky_solve(a.size, l_ptr, lt_ptr, l_data, tmp2, dy);
 
+    //   4. Iteratively refine the solution to reduce the error from the decomposition
+    for _ in 0..refinement_steps {
+        normal_matrix_vector_product(a, at, x, z, y, w, dy, tmp, residual);
+        for (r, b) in residual.iter_mut().zip(tmp2.iter()) {
+            *r = *b - *r;
+        }
+        cholesky_solve(a.size, l_ptr, lt_ptr, l_data, residual, correction);
+        for (d, c) in dy.iter_mut().zip(correction.iter()) {
+            *d += *c;
+        }
+    }
+
     // Calculate dx and dz
     //     dx = (c - AT.dot(y) - AT.dot(dy) + mu/x)*x/z
     //     dz = (mu - z*dx)/x - z
@@ -243,26 +301,26 @@ pub fn normal_eqn_step(
     // println!("dx: {:?}, dz: {:?}, dy: {:?}, dw: {:?}", dx, dz, dy, dw);
     // println!("Theta: {:?}", theta);
 
-    theta = (0.9995 / theta).min(f64x4::splat(1.0));
+    theta = (T::splat(0.9995) / theta).min(T::splat(1.0));
     // if (gid == 0) {
     //     printf("%d theta: %g", gid, theta);
     // }
 
     // println!("Theta: {:?}", theta);
     // Set theta to zero for lanes that have completed (status == True)
-    theta = status.blend(f64x4::splat(0.0), theta);
+    theta = status.blend(T::splat(0.0), theta);
 
-    vector_update(x, dx, f64x4::splat(1.0), theta);
-    vector_update(z, dz, f64x4::splat(1.0), theta);
-    vector_update(y, dy, f64x4::splat(1.0), theta);
-    vector_update(w, dw, f64x4::splat(1.0), theta);
+    vector_update(x, dx, T::splat(1.0), theta);
+    vector_update(z, dz, T::splat(1.0), theta);
+    vector_update(y, dy, T::splat(1.0), theta);
+    vector_update(w, dw, T::splat(1.0), theta);
 
     status
 }
 
-pub fn normal_eqn_init(x: &mut [f64x4], z: &mut [f64x4], y: &mut [f64x4], w: &mut [f64x4]) {
-    vector_set(x, f64x4::splat(1000.0));
-    vector_set(z, f64x4::splat(1000.0));
-    vector_set(y, f64x4::splat(1000.0));
-    vector_set(w, f64x4::splat(1000.0));
+pub fn normal_eqn_init<T: SimdFloat>(x: &mut [T], z: &mut [T], y: &mut [T], w: &mut [T]) {
+    vector_set(x, T::splat(1000.0));
+    vector_set(z, T::splat(1000.0));
+    vector_set(y, T::splat(1000.0));
+    vector_set(w, T::splat(1000.0));
 }
@@ -0,0 +1,136 @@
+use std::fmt::Debug;
+use std::ops::{Add, AddAssign, BitAnd, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use wide::{CmpLt, f32x4, f32x8, f32x16, f64x4};
+
+/// A SIMD vector of floating point values; one value for each LP solved together.
+///
+/// Masks use the same type, with all bits of a lane set if the condition is true.
+pub trait SimdFloat:
+    Copy
+    + Debug
+    + PartialEq
+    + Send
+    + Sync
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + BitAnd<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+    + AddAssign
+    + SubAssign
+    + MulAssign
+    + DivAssign
+{
+    /// The number of lanes.
+    const LANES: usize;
+    /// The machine epsilon of the values in each lane.
+    const EPSILON: f64;
+
+    /// Create a vector with all lanes set to `value`.
+    fn splat(value: f64) -> Self;
+    /// Create a vector from `values`. If there are fewer values than lanes the last value is
+    /// repeated in the remaining lanes.
+    fn from_f64_slice(values: &[f64]) -> Self;
+    /// The value of the given lane.
+    fn extract(&self, lane: usize) -> f64;
+
+    fn sqrt(self) -> Self;
+    fn abs(self) -> Self;
+    fn max(self, rhs: Self) -> Self;
+    fn min(self, rhs: Self) -> Self;
+    fn is_nan(self) -> Self;
+    fn simd_lt(self, rhs: Self) -> Self;
+    /// Whether all lanes of the mask are set.
+    fn all(self) -> bool;
+    /// Whether any lanes of the mask are set.
+    fn any(self) -> bool;
+    /// Select lanes from `t` where the mask is set, and from `f` otherwise.
+    fn blend(self, t: Self, f: Self) -> Self;
+}
+
+macro_rules! impl_simd_float {
+    ($t:ty, $s:ty, $n:literal) => {
+        impl SimdFloat for $t {
+            const LANES: usize = $n;
+            const EPSILON: f64 = <$s>::EPSILON as f64;
+
+            fn splat(value: f64) -> Self {
+                <$t>::splat(value as $s)
+            }
+
+            fn from_f64_slice(values: &[f64]) -> Self {
+                if values.is_empty() {
+                    panic!("Cannot create a SIMD vector from an empty slice.")
+                } else if values.len() > $n {
+                    panic!("Slice is larger than the number of SIMD lanes.")
+                }
+
+                let last = values[values.len() - 1];
+                let mut array = [0.0; $n];
+                for (lane, a) in array.iter_mut().enumerate() {
+                    *a = values.get(lane).copied().unwrap_or(last) as $s;
+                }
+                <$t>::new(array)
+            }
+
+            fn extract(&self, lane: usize) -> f64 {
+                self.as_array()[lane] as f64
+            }
+
+            fn sqrt(self) -> Self {
+                <$t>::sqrt(self)
+            }
+
+            fn abs(self) -> Self {
+                <$t>::abs(self)
+            }
+
+            fn max(self, rhs: Self) -> Self {
+                <$t>::max(self, rhs)
+            }
+
+            fn min(self, rhs: Self) -> Self {
+                <$t>::min(self, rhs)
+            }
+
+            fn is_nan(self) -> Self {
+                <$t>::is_nan(self)
+            }
+
+            fn simd_lt(self, rhs: Self) -> Self {
+                CmpLt::simd_lt(self, rhs)
+            }
+
+            fn all(self) -> bool {
+                <$t>::all(self)
+            }
+
+            fn any(self) -> bool {
+                <$t>::any(self)
+            }
+
+            fn blend(self, t: Self, f: Self) -> Self {
+                <$t>::blend(self, t, f)
+            }
+        }
+    };
+}
+
+impl_simd_float!(f64x4, f64, 4);
+impl_simd_float!(f32x4, f32, 4);
+impl_simd_float!(f32x8, f32, 8);
+impl_simd_float!(f32x16, f32, 16);
+
+#[cfg(test)]
+mod tests {
+    use super::SimdFloat;
+    use wide::f32x8;
+
+    #[test]
+    fn test_from_f64_slice() {
+        let v = <f32x8 as SimdFloat>::from_f64_slice(&[1.0, 2.0, 3.0]);
+        let lanes: Vec<f64> = (0..8).map(|l| v.extract(l)).collect();
+        assert_eq!(lanes, vec![1.0, 2.0, 3.0, 3.0, 3.0, 3.0, 3.0, 3.0]);
+    }
+}
@@ -0,0 +1,137 @@
+use anyhow::{Context, Result, bail};
+use clap::{Args, ValueEnum};
+use pywr_core::solvers::{SimplexStrategy, SolverOptionValue, SolverOptions};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// Configuration for the `run` command.
+///
+/// This can be loaded from a TOML or JSON file. Any options given on the command line take
+/// precedence over those in the file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RunConfig {
+    /// The number of threads to use in parallel simulation.
+    pub threads: Option<usize>,
+    /// Ignore the feature requirements of a solver.
+    #[serde(default)]
+    pub ignore_feature_requirements: bool,
+    /// Retry any failed solve with the default fallback solvers of the solver.
+    #[serde(default)]
+    pub fallback: bool,
+    /// Retry any failed solve with each of these solvers in turn. This overrides the default
+    /// fallback solvers.
+    pub fallback_chain: Option<Vec<FallbackStep>>,
+    /// The number of time-steps in the rolling-horizon look-ahead of the LP solvers.
+    pub horizon: Option<usize>,
+    /// The number of scenarios solved together by the SIMD IPM solvers. By default this is
+    /// chosen from the features of the CPU.
+    pub lanes: Option<usize>,
+    /// Path to write a CSV profile of the timings of every solve. Solvers that solve all scenarios
+    /// together (the IPM solvers) are not profiled.
+    pub profile: Option<PathBuf>,
+    /// The number of the slowest solves to report at the end of a profiled run.
+    pub profile_slowest: Option<usize>,
+    /// Tuning options for the solver.
+    #[serde(default)]
+    pub solver: SolverOptions,
+}
+
+/// A solver to retry a failed solve with.
+///
+/// The `-retry` steps solve again with presolve and scaling disabled (or, for the `simplex`
+/// solver, with more frequent refactorisation).
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum FallbackStep {
+    #[cfg(feature = "clp")]
+    Clp,
+    #[cfg(feature = "clp")]
+    ClpRetry,
+    Simplex,
+    SimplexRetry,
+    #[cfg(feature = "highs")]
+    Highs,
+    #[cfg(feature = "highs")]
+    HighsRetry,
+    #[cfg(feature = "cbc")]
+    Cbc,
+    #[cfg(feature = "cbc")]
+    CbcRetry,
+    #[cfg(feature = "microlp")]
+    Microlp,
+}
+
+impl RunConfig {
+    /// Load the configuration from a file. The format is determined from the file extension.
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let data = std::fs::read_to_string(path).with_context(|| format!("Failed to read file: {path:?}"))?;
+
+        let config = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => {
+                toml::from_str(&data).with_context(|| format!("Failed to deserialise TOML run config: {path:?}"))?
+            }
+            Some("json") => serde_json::from_str(&data)
+                .with_context(|| format!("Failed to deserialise JSON run config: {path:?}"))?,
+            _ => bail!("Run config file must have a `.toml` or `.json` extension: {path:?}"),
+        };
+
+        Ok(config)
+    }
+}
+
+/// Solver tuning options for the command line.
+#[derive(Args, Debug, Default)]
+pub struct SolverOptionArgs {
+    /// Primal feasibility tolerance.
+    #[arg(long)]
+    primal_tolerance: Option<f64>,
+    /// Dual feasibility tolerance.
+    #[arg(long)]
+    dual_tolerance: Option<f64>,
+    /// Whether to presolve the LP.
+    #[arg(long)]
+    presolve: Option<bool>,
+    /// The simplex algorithm to use (auto, primal or dual).
+    #[arg(long)]
+    simplex_strategy: Option<SimplexStrategy>,
+    /// Time limit, in seconds, for each solve.
+    #[arg(long)]
+    time_limit: Option<f64>,
+    /// Iteration limit for each solve.
+    #[arg(long)]
+    iteration_limit: Option<usize>,
+    /// A named option passed directly to the solver, given as `NAME=VALUE`. May be repeated.
+    #[arg(long = "solver-option", value_name = "NAME=VALUE", value_parser = parse_solver_option)]
+    solver_options: Vec<(String, SolverOptionValue)>,
+}
+
+impl SolverOptionArgs {
+    pub fn to_options(&self) -> SolverOptions {
+        SolverOptions {
+            primal_tolerance: self.primal_tolerance,
+            dual_tolerance: self.dual_tolerance,
+            presolve: self.presolve,
+            simplex_strategy: self.simplex_strategy,
+            time_limit: self.time_limit,
+            iteration_limit: self.iteration_limit,
+            solver_options: self.solver_options.iter().cloned().collect(),
+        }
+    }
+}
+
+fn parse_solver_option(s: &str) -> Result<(String, SolverOptionValue), String> {
+    let (name, value) = s
+        .split_once('=')
+        .ok_or_else(|| format!("Invalid solver option `{s}`; expected `NAME=VALUE`."))?;
+
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(format!("Invalid solver option `{s}`; the name must not be empty."));
+    }
+
+    // Parsing a `SolverOptionValue` is infallible.
+    let value = value.trim().parse().unwrap();
+
+    Ok((name.to_string(), value))
+}
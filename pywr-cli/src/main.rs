@@ -1,21 +1,32 @@
+mod config;
 mod tracing;
 
+use crate::config::{FallbackStep, RunConfig, SolverOptionArgs};
 use crate::tracing::setup_tracing;
 use ::tracing::info;
 use anyhow::{Context, Result, bail};
 use clap::{Parser, Subcommand, ValueEnum};
+use pywr_core::models::ModelTimings;
+use pywr_core::recorders::{merge_csv_long_outputs, merge_csv_wide_outputs};
+use pywr_core::scenario::ScenarioShard;
 #[cfg(feature = "cbc")]
 use pywr_core::solvers::{CbcSolver, CbcSolverSettings, CbcSolverSettingsBuilder};
 #[cfg(feature = "ipm-ocl")]
 use pywr_core::solvers::{ClIpmF32Solver, ClIpmF64Solver, ClIpmSolverSettings, ClIpmSolverSettingsBuilder};
+#[cfg(feature = "clp")]
 use pywr_core::solvers::{ClpSolver, ClpSolverSettings, ClpSolverSettingsBuilder};
+use pywr_core::solvers::{
+    FallbackChain, FallbackChainSettings, FallbackChainStep, FallbackSolver, FallbackSolverSettings, SimplexSolver,
+    SimplexSolverSettings, SimplexSolverSettingsBuilder, SolveProfile, SolverOptionValue, SolverOptions,
+};
 #[cfg(feature = "highs")]
 use pywr_core::solvers::{HighsSolver, HighsSolverSettings, HighsSolverSettingsBuilder};
 #[cfg(feature = "microlp")]
 use pywr_core::solvers::{MicroLpSolver, MicroLpSolverSettings, MicroLpSolverSettingsBuilder};
 #[cfg(feature = "ipm-simd")]
-use pywr_core::solvers::{SimdIpmF64Solver, SimdIpmSolverSettings, SimdIpmSolverSettingsBuilder};
+use pywr_core::solvers::{SimdIpmF32Solver, SimdIpmF64Solver, SimdIpmSolverSettings, SimdIpmSolverSettingsBuilder};
 use pywr_core::test_utils::make_random_model;
+use pywr_schema::model::ScenarioDomain;
 use pywr_schema::{ComponentConversionError, ModelSchema, MultiNetworkModelSchema, NetworkSchema};
 use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
@@ -23,9 +34,11 @@ use schemars::schema_for;
 use std::fmt::{Display, Formatter};
 use std::path::{Path, PathBuf};
 
-#[derive(Copy, Clone, ValueEnum)]
+#[derive(Copy, Clone, PartialEq, ValueEnum)]
 enum Solver {
+    #[cfg(feature = "clp")]
     Clp,
+    Simplex,
     #[cfg(feature = "highs")]
     Highs,
     #[cfg(feature = "cbc")]
@@ -36,6 +49,8 @@ enum Solver {
     CLIPMF64,
     #[cfg(feature = "ipm-simd")]
     IpmSimd,
+    #[cfg(feature = "ipm-simd")]
+    IpmSimdF32,
     #[cfg(feature = "microlp")]
     Microlp,
 }
@@ -43,7 +58,9 @@ enum Solver {
 impl Display for Solver {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
+            #[cfg(feature = "clp")]
             Solver::Clp => write!(f, "clp"),
+            Solver::Simplex => write!(f, "simplex"),
             #[cfg(feature = "highs")]
             Solver::Highs => write!(f, "highs"),
             #[cfg(feature = "cbc")]
@@ -54,12 +71,28 @@ impl Display for Solver {
             Solver::CLIPMF64 => write!(f, "clipmf64"),
             #[cfg(feature = "ipm-simd")]
             Solver::IpmSimd => write!(f, "ipm-simd"),
+            #[cfg(feature = "ipm-simd")]
+            Solver::IpmSimdF32 => write!(f, "ipm-simd-f32"),
             #[cfg(feature = "microlp")]
             Solver::Microlp => write!(f, "microlp"),
         }
     }
 }
 
+impl Default for Solver {
+    /// CLP when it is available, otherwise the pure Rust simplex solver.
+    fn default() -> Self {
+        #[cfg(feature = "clp")]
+        {
+            Solver::Clp
+        }
+        #[cfg(not(feature = "clp"))]
+        {
+            Solver::Simplex
+        }
+    }
+}
+
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
@@ -90,24 +123,60 @@ enum Commands {
         /// Path to Pywr model JSON.
         model: PathBuf,
         /// Solver to use.
-        #[arg(short, long, default_value_t=Solver::Clp)]
+        #[arg(short, long, default_value_t=Solver::default())]
         solver: Solver,
         #[arg(short, long)]
         data_path: Option<PathBuf>,
         #[arg(short, long)]
         output_path: Option<PathBuf>,
-        /// The number of threads to use in parallel simulation.
-        #[arg(short, long, default_value_t = 1)]
-        threads: usize,
+        /// The number of threads to use in parallel simulation [default: 1].
+        #[arg(short, long)]
+        threads: Option<usize>,
         /// Ignore the feature requirements of a solver.
         #[arg(short, long, default_value_t = false)]
         ignore_feature_requirements: bool,
+        /// Path to a TOML or JSON run configuration file. Options given on the command line
+        /// override those in the file.
+        #[arg(short, long)]
+        config: Option<PathBuf>,
+        /// Retry any failed solve with presolve and scaling disabled (or, for the `simplex` solver,
+        /// more frequent refactorisation). When built with HiGHS, a failed CLP solve is first
+        /// retried with HiGHS.
+        #[arg(long, default_value_t = false)]
+        fallback: bool,
+        /// Retry any failed solve with each of these solvers in turn (e.g. `highs,clp-retry`),
+        /// instead of the default fallbacks. A `-retry` step solves again with presolve and
+        /// scaling disabled. Only supported by the LP solvers.
+        #[arg(long, value_delimiter = ',')]
+        fallback_chain: Option<Vec<FallbackStep>>,
+        /// Only run the given shard of the scenarios, given as `INDEX/COUNT` (e.g. `0/4`). Outputs
+        /// use the global scenario indices so that the shards can be combined with `merge`.
+        #[arg(long)]
+        shard: Option<ScenarioShard>,
+        /// Optimise each time-step over a look-ahead of this many time-steps (including the
+        /// current one) using forecast parameter values. Only supported by the LP solvers.
+        #[arg(long)]
+        horizon: Option<usize>,
+        /// The number of scenarios solved together (4, 8 or 16) by the SIMD IPM solvers. By
+        /// default this is chosen from the features of the CPU.
+        #[arg(long)]
+        lanes: Option<usize>,
+        /// Write the timings and iterations of every solve to this CSV file. The slowest solves
+        /// are also reported at the end of the run. Solvers that solve all scenarios together
+        /// (the IPM solvers) are not profiled.
+        #[arg(long)]
+        profile: Option<PathBuf>,
+        /// The number of the slowest solves to report at the end of a profiled run (default 10).
+        #[arg(long)]
+        profile_slowest: Option<usize>,
+        #[command(flatten)]
+        solver_options: SolverOptionArgs,
     },
     RunMulti {
         /// Path to Pywr model JSON.
         model: PathBuf,
         /// Solver to use.
-        #[arg(short, long, default_value_t=Solver::Clp)]
+        #[arg(short, long, default_value_t=Solver::default())]
         solver: Solver,
         #[arg(short, long)]
         data_path: Option<PathBuf>,
@@ -116,13 +185,28 @@ enum Commands {
         /// The number of threads to use in parallel simulation.
         #[arg(short, long, default_value_t = 1)]
         threads: usize,
+        /// Only run the given shard of the scenarios, given as `INDEX/COUNT` (e.g. `0/4`). Outputs
+        /// use the global scenario indices so that the shards can be combined with `merge`.
+        #[arg(long)]
+        shard: Option<ScenarioShard>,
+    },
+    /// Merge the outputs of the shards of a sharded run into a single output.
+    ///
+    /// CSV (wide or long format) and HDF5 outputs are supported; the format is determined from
+    /// the file extension of the output.
+    Merge {
+        /// Path to the merged output.
+        output: PathBuf,
+        /// Paths to the output of each shard.
+        #[arg(required = true)]
+        inputs: Vec<PathBuf>,
     },
     RunRandom {
         num_systems: usize,
         density: usize,
         num_scenarios: usize,
         /// Solver to use.
-        #[arg(short, long, default_value_t=Solver::Clp)]
+        #[arg(short, long, default_value_t=Solver::default())]
         solver: Solver,
     },
     ExportSchema {
@@ -149,21 +233,48 @@ fn main() -> Result<()> {
             output_path,
             threads,
             ignore_feature_requirements,
-        } => run(
-            model,
-            solver,
-            data_path.as_deref(),
-            output_path.as_deref(),
-            *threads,
-            *ignore_feature_requirements,
-        ),
+            config,
+            fallback,
+            fallback_chain,
+            shard,
+            horizon,
+            lanes,
+            profile,
+            profile_slowest,
+            solver_options,
+        } => {
+            let mut run_config = match config {
+                Some(path) => RunConfig::from_path(path)?,
+                None => RunConfig::default(),
+            };
+            run_config.threads = threads.or(run_config.threads);
+            run_config.ignore_feature_requirements |= *ignore_feature_requirements;
+            run_config.fallback |= *fallback;
+            run_config.fallback_chain = fallback_chain.clone().or(run_config.fallback_chain);
+            run_config.horizon = horizon.or(run_config.horizon);
+            run_config.lanes = lanes.or(run_config.lanes);
+            run_config.profile = profile.clone().or(run_config.profile);
+            run_config.profile_slowest = profile_slowest.or(run_config.profile_slowest);
+            run_config.solver.merge(solver_options.to_options());
+
+            run(
+                model,
+                solver,
+                data_path.as_deref(),
+                output_path.as_deref(),
+                *shard,
+                &run_config,
+            )?
+        }
         Commands::RunMulti {
             model,
             solver,
             data_path,
             output_path,
             threads: _,
-        } => run_multi(model, solver, data_path.as_deref(), output_path.as_deref()),
+            shard,
+        } => run_multi(model, solver, data_path.as_deref(), output_path.as_deref(), *shard),
+        Commands::Merge { output, inputs } => merge(output, inputs)?,
         Commands::RunRandom {
             num_systems,
             density,
@@ -271,16 +382,39 @@ fn run(
     solver: &Solver,
     data_path: Option<&Path>,
     output_path: Option<&Path>,
-    threads: usize,
-    ignore_feature_requirements: bool,
-) {
+    shard: Option<ScenarioShard>,
+    config: &RunConfig,
+) -> Result<()> {
+    let threads = config.threads.unwrap_or(1);
+    let horizon = config.horizon.unwrap_or(1);
+    let ignore_feature_requirements = config.ignore_feature_requirements;
+    let options = &config.solver;
+
+    #[cfg(feature = "ipm-simd")]
+    let supports_lanes = matches!(solver, Solver::IpmSimd | Solver::IpmSimdF32);
+    #[cfg(not(feature = "ipm-simd"))]
+    let supports_lanes = false;
+    if config.lanes.is_some() && !supports_lanes {
+        ::tracing::warn!("Setting the number of lanes is not supported by the `{solver}` solver and will be ignored.");
+    }
+
     let data = std::fs::read_to_string(path).unwrap();
     let data_path = data_path.or_else(|| path.parent());
-    let schema_v2: ModelSchema = serde_json::from_str(data.as_str()).unwrap();
+    let mut schema_v2: ModelSchema = serde_json::from_str(data.as_str()).unwrap();
+    apply_shard(&mut schema_v2.scenarios, shard);
 
     let model = schema_v2.build_model(data_path, output_path).unwrap();
 
-    match *solver {
+    let mut timings = ModelTimings::new_with_component_timings(model.network());
+    if config.profile.is_some() {
+        let num_slowest_solves = config
+            .profile_slowest
+            .unwrap_or(SolveProfile::DEFAULT_NUM_SLOWEST_SOLVES);
+        timings = timings.with_solve_profile(SolveProfile::new(num_slowest_solves));
+    }
+
+    let result = match *solver {
+        #[cfg(feature = "clp")]
         Solver::Clp => {
             let mut settings_builder = ClpSolverSettingsBuilder::default();
             if threads > 1 {
@@ -290,8 +424,34 @@ fn run(
             if ignore_feature_requirements {
                 settings_builder = settings_builder.ignore_feature_requirements();
             }
-            let settings = settings_builder.build();
-            model.run::<ClpSolver>(&settings)
+            let settings = settings_builder.options(options.clone()).horizon(horizon).build();
+
+            match fallback_chain(solver, config) {
+                Some(chain) => {
+                    let settings = FallbackSolverSettings::new(settings, chain);
+                    model.run_with_timings::<FallbackSolver<ClpSolver, FallbackChain>>(&settings, timings)
+                }
+                None => model.run_with_timings::<ClpSolver>(&settings, timings),
+            }
+        }
+        Solver::Simplex => {
+            let mut settings_builder = SimplexSolverSettingsBuilder::default();
+            if threads > 1 {
+                settings_builder = settings_builder.parallel();
+                settings_builder = settings_builder.threads(threads);
+            }
+            if ignore_feature_requirements {
+                settings_builder = settings_builder.ignore_feature_requirements();
+            }
+            let settings = settings_builder.options(options.clone()).horizon(horizon).build();
+
+            match fallback_chain(solver, config) {
+                Some(chain) => {
+                    let settings = FallbackSolverSettings::new(settings, chain);
+                    model.run_with_timings::<FallbackSolver<SimplexSolver, FallbackChain>>(&settings, timings)
+                }
+                None => model.run_with_timings::<SimplexSolver>(&settings, timings),
+            }
         }
         #[cfg(feature = "cbc")]
         Solver::Cbc => {
@@ -303,8 +463,15 @@ fn run(
             if ignore_feature_requirements {
                 settings_builder = settings_builder.ignore_feature_requirements();
             }
-            let settings = settings_builder.build();
-            model.run::<CbcSolver>(&settings)
+            let settings = settings_builder.options(options.clone()).horizon(horizon).build();
+
+            match fallback_chain(solver, config) {
+                Some(chain) => {
+                    let settings = FallbackSolverSettings::new(settings, chain);
+                    model.run_with_timings::<FallbackSolver<CbcSolver, FallbackChain>>(&settings, timings)
+                }
+                None => model.run_with_timings::<CbcSolver>(&settings, timings),
+            }
         }
         #[cfg(feature = "highs")]
         Solver::Highs => {
@@ -316,8 +483,15 @@ fn run(
             if ignore_feature_requirements {
                 settings_builder = settings_builder.ignore_feature_requirements();
             }
-            let settings = settings_builder.build();
-            model.run::<HighsSolver>(&settings)
+            let settings = settings_builder.options(options.clone()).horizon(horizon).build();
+
+            match fallback_chain(solver, config) {
+                Some(chain) => {
+                    let settings = FallbackSolverSettings::new(settings, chain);
+                    model.run_with_timings::<FallbackSolver<HighsSolver, FallbackChain>>(&settings, timings)
+                }
+                None => model.run_with_timings::<HighsSolver>(&settings, timings),
+            }
         }
         #[cfg(feature = "ipm-ocl")]
         Solver::CLIPMF32 => {
@@ -330,6 +504,7 @@ fn run(
                 settings_builder = settings_builder.ignore_feature_requirements();
             }
 
+            warn_unsupported_options(solver, config);
             let settings = settings_builder.build();
             model.run_multi_scenario::<ClIpmF32Solver>(&settings)
         }
@@ -344,6 +519,7 @@ fn run(
                 settings_builder = settings_builder.ignore_feature_requirements();
             }
 
+            warn_unsupported_options(solver, config);
             let settings = settings_builder.build();
             model.run_multi_scenario::<ClIpmF64Solver>(&settings)
         }
@@ -358,9 +534,33 @@ fn run(
                 settings_builder = settings_builder.ignore_feature_requirements();
             }
 
+            if let Some(lanes) = config.lanes {
+                settings_builder = settings_builder.lanes(lanes);
+            }
+
+            warn_unsupported_options(solver, config);
             let settings = settings_builder.build();
             model.run_multi_scenario::<SimdIpmF64Solver>(&settings)
         }
+        #[cfg(feature = "ipm-simd")]
+        Solver::IpmSimdF32 => {
+            let mut settings_builder = SimdIpmSolverSettingsBuilder::default();
+            if threads > 1 {
+                settings_builder = settings_builder.parallel();
+                settings_builder = settings_builder.threads(threads);
+            }
+            if ignore_feature_requirements {
+                settings_builder = settings_builder.ignore_feature_requirements();
+            }
+
+            if let Some(lanes) = config.lanes {
+                settings_builder = settings_builder.lanes(lanes);
+            }
+
+            warn_unsupported_options(solver, config);
+            let settings = settings_builder.build();
+            model.run_multi_scenario::<SimdIpmF32Solver>(&settings)
+        }
         #[cfg(feature = "microlp")]
         Solver::Microlp => {
             let mut settings_builder = MicroLpSolverSettingsBuilder::default();
@@ -371,23 +571,211 @@ fn run(
             if ignore_feature_requirements {
                 settings_builder = settings_builder.ignore_feature_requirements();
             }
+            warn_unsupported_options(solver, config);
             let settings = settings_builder.build();
-            model.run::<MicroLpSolver>(&settings)
+            model.run_with_timings::<MicroLpSolver>(&settings, timings)
         }
     }
     .unwrap();
+
+    if let Some(profile_path) = &config.profile {
+        match result.timings.solve_profile() {
+            Some(profile) => profile
+                .write_csv(profile_path)
+                .with_context(|| format!("Failed to write the solve profile: {profile_path:?}"))?,
+            None => ::tracing::warn!("Solve profiles are not supported by the `{solver}` solver."),
+        }
+    }
+
+    Ok(())
+}
+
+/// Options for retrying a failed solve; presolve is disabled and the named solver option (usually
+/// scaling) is overridden.
+fn retry_options(options: &SolverOptions, name: &str, value: SolverOptionValue) -> SolverOptions {
+    let mut retry = options.clone();
+    retry.presolve = Some(false);
+    retry.solver_options.insert(name.to_string(), value);
+    retry
+}
+
+/// Warn that the tuning options, look-ahead and fallbacks are ignored by a solver that does not
+/// support them.
+#[cfg(any(feature = "ipm-ocl", feature = "ipm-simd", feature = "microlp"))]
+fn warn_unsupported_options(solver: &Solver, config: &RunConfig) {
+    if !config.solver.is_empty() {
+        ::tracing::warn!("Solver options are not supported by the `{solver}` solver and will be ignored.");
+    }
+    if config.horizon.unwrap_or(1) > 1 {
+        ::tracing::warn!("A look-ahead horizon is not supported by the `{solver}` solver and will be ignored.");
+    }
+    if config.fallback || config.fallback_chain.is_some() {
+        ::tracing::warn!("Fallback solvers are not supported by the `{solver}` solver and will be ignored.");
+    }
+}
+
+/// The fallback solvers of `solver` to retry a failed solve with, if any.
+///
+/// A configured chain is used in preference to the default fallbacks of the solver. Steps that
+/// use the same solver as `solver` share its options; other solvers use their default options.
+fn fallback_chain(solver: &Solver, config: &RunConfig) -> Option<FallbackChainSettings> {
+    let steps = match &config.fallback_chain {
+        Some(steps) => steps.clone(),
+        None if config.fallback => default_fallback_chain(solver),
+        None => return None,
+    };
+
+    let horizon = config.horizon.unwrap_or(1);
+    let options = |step_solver: Solver| {
+        if step_solver == *solver {
+            config.solver.clone()
+        } else {
+            SolverOptions::default()
+        }
+    };
+
+    let steps = steps
+        .into_iter()
+        .map(|step| match step {
+            #[cfg(feature = "clp")]
+            FallbackStep::Clp => FallbackChainStep::new::<ClpSolver>(
+                ClpSolverSettingsBuilder::default()
+                    .horizon(horizon)
+                    .options(options(Solver::Clp))
+                    .build(),
+            ),
+            #[cfg(feature = "clp")]
+            FallbackStep::ClpRetry => FallbackChainStep::new::<ClpSolver>(
+                ClpSolverSettingsBuilder::default()
+                    .horizon(horizon)
+                    .options(retry_options(
+                        &options(Solver::Clp),
+                        "scaling",
+                        SolverOptionValue::Int(0),
+                    ))
+                    .build(),
+            ),
+            FallbackStep::Simplex => FallbackChainStep::new::<SimplexSolver>(
+                SimplexSolverSettingsBuilder::default()
+                    .horizon(horizon)
+                    .options(options(Solver::Simplex))
+                    .build(),
+            ),
+            // There is no scaling to disable; retry with more frequent refactorisation instead
+            FallbackStep::SimplexRetry => FallbackChainStep::new::<SimplexSolver>(
+                SimplexSolverSettingsBuilder::default()
+                    .horizon(horizon)
+                    .options(retry_options(
+                        &options(Solver::Simplex),
+                        "refactor_frequency",
+                        SolverOptionValue::Int(20),
+                    ))
+                    .build(),
+            ),
+            #[cfg(feature = "highs")]
+            FallbackStep::Highs => FallbackChainStep::new::<HighsSolver>(
+                HighsSolverSettingsBuilder::default()
+                    .horizon(horizon)
+                    .options(options(Solver::Highs))
+                    .build(),
+            ),
+            #[cfg(feature = "highs")]
+            FallbackStep::HighsRetry => FallbackChainStep::new::<HighsSolver>(
+                HighsSolverSettingsBuilder::default()
+                    .horizon(horizon)
+                    .options(retry_options(
+                        &options(Solver::Highs),
+                        "simplex_scale_strategy",
+                        SolverOptionValue::Int(0),
+                    ))
+                    .build(),
+            ),
+            #[cfg(feature = "cbc")]
+            FallbackStep::Cbc => FallbackChainStep::new::<CbcSolver>(
+                CbcSolverSettingsBuilder::default()
+                    .horizon(horizon)
+                    .options(options(Solver::Cbc))
+                    .build(),
+            ),
+            #[cfg(feature = "cbc")]
+            FallbackStep::CbcRetry => FallbackChainStep::new::<CbcSolver>(
+                CbcSolverSettingsBuilder::default()
+                    .horizon(horizon)
+                    .options(retry_options(
+                        &options(Solver::Cbc),
+                        "scaling",
+                        SolverOptionValue::String("off".to_string()),
+                    ))
+                    .build(),
+            ),
+            #[cfg(feature = "microlp")]
+            FallbackStep::Microlp => {
+                FallbackChainStep::new::<MicroLpSolver>(MicroLpSolverSettingsBuilder::default().build())
+            }
+        })
+        .collect();
+
+    let chain = FallbackChainSettings::new(steps);
+    if config.ignore_feature_requirements {
+        Some(chain.ignore_feature_requirements())
+    } else {
+        Some(chain)
+    }
+}
+
+/// The fallback solvers used by `--fallback` for each solver.
+fn default_fallback_chain(solver: &Solver) -> Vec<FallbackStep> {
+    match solver {
+        #[cfg(feature = "clp")]
+        Solver::Clp => vec![
+            #[cfg(feature = "highs")]
+            FallbackStep::Highs,
+            FallbackStep::ClpRetry,
+        ],
+        Solver::Simplex => vec![FallbackStep::SimplexRetry],
+        #[cfg(feature = "highs")]
+        Solver::Highs => vec![FallbackStep::HighsRetry],
+        #[cfg(feature = "cbc")]
+        Solver::Cbc => vec![FallbackStep::CbcRetry],
+        #[allow(unreachable_patterns)]
+        _ => Vec::new(),
+    }
+}
+
+/// Set the shard of the scenarios to run, if any.
+fn apply_shard(scenarios: &mut Option<ScenarioDomain>, shard: Option<ScenarioShard>) {
+    if let Some(shard) = shard {
+        let domain = scenarios.get_or_insert_with(|| ScenarioDomain {
+            groups: Vec::new(),
+            combinations: None,
+            shard: None,
+        });
+        domain.shard = Some(pywr_schema::model::ScenarioShard {
+            index: shard.index(),
+            count: shard.count(),
+        });
+    }
 }
 
-fn run_multi(path: &Path, solver: &Solver, data_path: Option<&Path>, output_path: Option<&Path>) {
+fn run_multi(
+    path: &Path,
+    solver: &Solver,
+    data_path: Option<&Path>,
+    output_path: Option<&Path>,
+    shard: Option<ScenarioShard>,
+) {
     let data = std::fs::read_to_string(path).unwrap();
     let data_path = data_path.or_else(|| path.parent());
 
-    let schema_v2: MultiNetworkModelSchema = serde_json::from_str(data.as_str()).unwrap();
+    let mut schema_v2: MultiNetworkModelSchema = serde_json::from_str(data.as_str()).unwrap();
+    apply_shard(&mut schema_v2.scenarios, shard);
 
     let model = schema_v2.build_model(data_path, output_path).unwrap();
 
     match *solver {
+        #[cfg(feature = "clp")]
         Solver::Clp => model.run::<ClpSolver>(&ClpSolverSettings::default()),
+        Solver::Simplex => model.run::<SimplexSolver>(&SimplexSolverSettings::default()),
         #[cfg(feature = "highs")]
         Solver::Highs => model.run::<HighsSolver>(&HighsSolverSettings::default()),
         #[cfg(feature = "cbc")]
@@ -398,18 +786,44 @@ fn run_multi(path: &Path, solver: &Solver, data_path: Option<&Path>, output_path
         Solver::CLIPMF64 => model.run_multi_scenario::<ClIpmF64Solver>(&ClIpmSolverSettings::default()),
         #[cfg(feature = "ipm-simd")]
         Solver::IpmSimd => model.run_multi_scenario::<SimdIpmF64Solver>(&SimdIpmSolverSettings::default()),
+        #[cfg(feature = "ipm-simd")]
+        Solver::IpmSimdF32 => model.run_multi_scenario::<SimdIpmF32Solver>(&SimdIpmSolverSettings::default()),
         #[cfg(feature = "microlp")]
         Solver::Microlp => model.run::<MicroLpSolver>(&MicroLpSolverSettings::default()),
     }
     .unwrap();
 }
 
+fn merge(output: &Path, inputs: &[PathBuf]) -> Result<()> {
+    info!("Merging {} files into: {}", inputs.len(), output.display());
+
+    match output.extension().and_then(|ext| ext.to_str()) {
+        Some("csv") => {
+            // The long format has a single header row starting with the time of the period.
+            let first =
+                std::fs::read_to_string(&inputs[0]).with_context(|| format!("Failed to read file: {:?}", inputs[0]))?;
+            if first.starts_with("time_start") {
+                merge_csv_long_outputs(inputs, output)?;
+            } else {
+                merge_csv_wide_outputs(inputs, output)?;
+            }
+        }
+        #[cfg(feature = "hdf5")]
+        Some("h5" | "hdf5") => pywr_core::recorders::merge_hdf5_outputs(inputs, output)?,
+        _ => bail!("Unsupported output format for merging: {output:?}"),
+    }
+
+    Ok(())
+}
+
 fn run_random(num_systems: usize, density: usize, num_scenarios: usize, solver: &Solver) {
     let mut rng = ChaCha8Rng::seed_from_u64(0);
     let model = make_random_model(num_systems, density, num_scenarios, &mut rng).unwrap();
 
     match *solver {
+        #[cfg(feature = "clp")]
         Solver::Clp => model.run::<ClpSolver>(&ClpSolverSettings::default()),
+        Solver::Simplex => model.run::<SimplexSolver>(&SimplexSolverSettings::default()),
         #[cfg(feature = "highs")]
         Solver::Highs => model.run::<HighsSolver>(&HighsSolverSettings::default()),
         #[cfg(feature = "cbc")]
@@ -420,6 +834,8 @@ fn run_random(num_systems: usize, density: usize, num_scenarios: usize, solver:
         Solver::CLIPMF64 => model.run_multi_scenario::<ClIpmF64Solver>(&ClIpmSolverSettings::default()),
         #[cfg(feature = "ipm-simd")]
         Solver::IpmSimd => model.run_multi_scenario::<SimdIpmF64Solver>(&SimdIpmSolverSettings::default()),
+        #[cfg(feature = "ipm-simd")]
+        Solver::IpmSimdF32 => model.run_multi_scenario::<SimdIpmF32Solver>(&SimdIpmSolverSettings::default()),
         #[cfg(feature = "microlp")]
         Solver::Microlp => model.run::<MicroLpSolver>(&MicroLpSolverSettings::default()),
     }
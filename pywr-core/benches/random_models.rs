@@ -7,7 +7,7 @@
 /// Benchmarks test the performance the solvers with different sized models (numbers of
 /// systems and density of transfers between them), numbers of scenarios (which vary the
 /// input flows) and number of CPU threads.
-use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use criterion::{BatchSize, BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
 use pywr_core::models::ModelTimings;
 #[cfg(feature = "ipm-ocl")]
 use pywr_core::solvers::{ClIpmF64Solver, ClIpmSolverSettings, ClIpmSolverSettingsBuilder};
@@ -15,8 +15,10 @@ use pywr_core::solvers::{ClpSolver, ClpSolverSettings, ClpSolverSettingsBuilder}
 #[cfg(feature = "highs")]
 use pywr_core::solvers::{HighsSolver, HighsSolverSettings};
 #[cfg(feature = "ipm-simd")]
-use pywr_core::solvers::{SimdIpmF64Solver, SimdIpmSolverSettings, SimdIpmSolverSettingsBuilder};
-use pywr_core::test_utils::make_random_model;
+use pywr_core::solvers::{SimdIpmF32Solver, SimdIpmF64Solver, SimdIpmSolverSettings, SimdIpmSolverSettingsBuilder};
+#[cfg(feature = "simplex")]
+use pywr_core::solvers::{SimplexSolver, SimplexSolverSettings};
+use pywr_core::test_utils::{make_random_model, make_random_model_with_dynamic_values};
 use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
 #[cfg(feature = "ipm-ocl")]
@@ -104,6 +106,25 @@ fn random_benchmark(
                                 },
                             );
                         }
+                        #[cfg(feature = "ipm-simd")]
+                        SolverSetting::IpmSimdF32(settings) => {
+                            let parameter_string =
+                                format!("ipm-simd-f32 * {n_sys} * {density} * {n_sc} * {}", &setup.name);
+
+                            group.bench_with_input(
+                                BenchmarkId::new("random-model", parameter_string),
+                                &(n_sys, density, n_sc),
+                                |b, _n| {
+                                    // Do the setup here outside of the time-step loop
+                                    let mut state = model
+                                        .setup_multi_scenario::<SimdIpmF32Solver>(settings)
+                                        .expect("Failed to setup the model.");
+                                    let mut timings = ModelTimings::new_with_component_timings(model.network());
+
+                                    b.iter(|| model.run_multi_scenario_with_state(&mut state, settings, &mut timings))
+                                },
+                            );
+                        }
                         #[cfg(feature = "ipm-ocl")]
                         SolverSetting::IpmOcl(settings) => {
                             let parameter_string =
@@ -139,6 +160,8 @@ enum SolverSetting {
     Highs(HighsSolverSettings),
     #[cfg(feature = "ipm-simd")]
     IpmSimdF64x4(SimdIpmSolverSettings),
+    #[cfg(feature = "ipm-simd")]
+    IpmSimdF32(SimdIpmSolverSettings),
     #[cfg(feature = "ipm-ocl")]
     IpmOcl(ClIpmSolverSettings),
 }
@@ -164,6 +187,11 @@ fn default_solver_setups() -> Vec<SolverSetup> {
             setting: SolverSetting::IpmSimdF64x4(SimdIpmSolverSettings::default()),
             name: "default".to_string(),
         },
+        #[cfg(feature = "ipm-simd")]
+        SolverSetup {
+            setting: SolverSetting::IpmSimdF32(SimdIpmSolverSettings::default()),
+            name: "default".to_string(),
+        },
         #[cfg(feature = "ipm-ocl")]
         SolverSetup {
             setting: SolverSetting::IpmOcl(ClIpmSolverSettings::default()),
@@ -186,6 +214,113 @@ fn bench_system_size(c: &mut Criterion) {
     )
 }
 
+/// Single thread benchmarks of complete runs of large networks
+///
+/// Only the inflows of these networks change between time-steps; the costs and the remaining
+/// constraints are constant. Updating the LP each time-step, rather than solving it, is therefore
+/// a significant part of the run time. Each iteration sets up a new state (outside the timed
+/// section) so that every iteration runs all the time-steps. The `clp-dynamic` baseline solves
+/// the same networks with their constant values given by non-constant parameters, so that the
+/// whole objective and the output rows are updated each time-step.
+fn bench_large_networks(c: &mut Criterion) {
+    let mut group = c.benchmark_group("random-models-large");
+    group.sample_size(10);
+
+    for n_sys in [100, 200, 400] {
+        for density in [1, 2] {
+            let mut rng = ChaCha8Rng::seed_from_u64(0);
+            let model = make_random_model(n_sys, density, 1, &mut rng).unwrap();
+            let num_timesteps = model.domain().time().timesteps().len();
+            group.throughput(Throughput::Elements(num_timesteps as u64));
+
+            let settings = ClpSolverSettings::default();
+            group.bench_with_input(
+                BenchmarkId::new("random-model", format!("clp * {n_sys} * {density}")),
+                &(n_sys, density),
+                |b, _n| {
+                    b.iter_batched(
+                        || model.setup::<ClpSolver>(&settings).expect("Failed to setup the model."),
+                        |mut state| {
+                            let mut timings = ModelTimings::new_with_component_timings(model.network());
+                            model.run_with_state(&mut state, &settings, &mut timings)
+                        },
+                        BatchSize::LargeInput,
+                    )
+                },
+            );
+
+            let mut rng = ChaCha8Rng::seed_from_u64(0);
+            let dynamic_model = make_random_model_with_dynamic_values(n_sys, density, 1, &mut rng).unwrap();
+            group.bench_with_input(
+                BenchmarkId::new("random-model", format!("clp-dynamic * {n_sys} * {density}")),
+                &(n_sys, density),
+                |b, _n| {
+                    b.iter_batched(
+                        || {
+                            dynamic_model
+                                .setup::<ClpSolver>(&settings)
+                                .expect("Failed to setup the model.")
+                        },
+                        |mut state| {
+                            let mut timings = ModelTimings::new_with_component_timings(dynamic_model.network());
+                            dynamic_model.run_with_state(&mut state, &settings, &mut timings)
+                        },
+                        BatchSize::LargeInput,
+                    )
+                },
+            );
+
+            #[cfg(feature = "highs")]
+            {
+                let settings = HighsSolverSettings::default();
+                group.bench_with_input(
+                    BenchmarkId::new("random-model", format!("highs * {n_sys} * {density}")),
+                    &(n_sys, density),
+                    |b, _n| {
+                        b.iter_batched(
+                            || {
+                                model
+                                    .setup::<HighsSolver>(&settings)
+                                    .expect("Failed to setup the model.")
+                            },
+                            |mut state| {
+                                let mut timings = ModelTimings::new_with_component_timings(model.network());
+                                model.run_with_state(&mut state, &settings, &mut timings)
+                            },
+                            BatchSize::LargeInput,
+                        )
+                    },
+                );
+            }
+
+            #[cfg(feature = "simplex")]
+            {
+                let settings = SimplexSolverSettings::default();
+                group.bench_with_input(
+                    BenchmarkId::new("random-model", format!("simplex * {n_sys} * {density}")),
+                    &(n_sys, density),
+                    |b, _n| {
+                        b.iter_batched(
+                            || {
+                                model
+                                    .setup::<SimplexSolver>(&settings)
+                                    .expect("Failed to setup the model.")
+                            },
+                            |mut state| {
+                                let mut timings = ModelTimings::new_with_component_timings(model.network());
+                                model.run_with_state(&mut state, &settings, &mut timings)
+                            },
+                            BatchSize::LargeInput,
+                        )
+                    },
+                );
+            }
+        }
+    }
+
+    group.finish();
+}
+
 /// Single thread small scenario benchmarks
 fn bench_scenarios(c: &mut Criterion) {
     let scenarios: Vec<usize> = vec![1, 2, 4, 6, 8, 10, 12, 24, 48, 64];
@@ -358,6 +493,16 @@ fn bench_hyper_scenarios(c: &mut Criterion) {
             ),
             name: "default".to_string(),
         },
+        #[cfg(feature = "ipm-simd")]
+        SolverSetup {
+            setting: SolverSetting::IpmSimdF32(
+                SimdIpmSolverSettingsBuilder::default()
+                    .parallel()
+                    .threads(N_THREADS)
+                    .build(),
+            ),
+            name: "default".to_string(),
+        },
         #[cfg(feature = "ipm-ocl")]
         SolverSetup {
             setting: SolverSetting::IpmOcl(
@@ -384,6 +529,7 @@ fn bench_hyper_scenarios(c: &mut Criterion) {
 criterion_group!(
     benches,
     bench_system_size,
+    bench_large_networks,
     bench_scenarios,
     bench_threads,
     bench_hyper_scenarios,
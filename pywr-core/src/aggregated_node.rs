@@ -80,20 +80,32 @@ pub enum Factors {
     Ratio { factors: Vec<MetricF64> },
     /// Linear combination of node flows. The factors can be positive or negative, and a
     /// right-hand side (rhs) value can be provided. There should be the same number of
-    /// factors as nodes. Currently only two nodes are supported.
+    /// factors as nodes. Currently only two nodes are supported. The combination is either
+    /// fixed at, or limited to, the right-hand side depending on `rhs_constraint`.
     Coefficients {
         factors: Vec<MetricF64>,
         rhs: Option<MetricF64>,
+        rhs_constraint: RhsConstraint,
     },
 }
 
+/// How the right-hand side of coefficient factors constrains the linear combination of node flows.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub enum RhsConstraint {
+    /// The linear combination is equal to the right-hand side.
+    #[default]
+    Equal,
+    /// The linear combination is less than or equal to the right-hand side.
+    UpperBound,
+}
+
 impl Factors {
     /// Returns true if all factors and any right-hands sides are constant
     #[must_use]
     pub fn is_constant(&self) -> bool {
         match self {
             Self::Proportion { factors } | Self::Ratio { factors } => factors.iter().all(MetricF64::is_constant),
-            Self::Coefficients { factors, rhs } => {
+            Self::Coefficients { factors, rhs, .. } => {
                 factors.iter().all(MetricF64::is_constant) && rhs.as_ref().is_none_or(MetricF64::is_constant)
             }
         }
@@ -149,6 +161,18 @@ impl Relationship {
         Relationship::Factored(Factors::Coefficients {
             factors: factors.to_vec(),
             rhs,
+            rhs_constraint: RhsConstraint::Equal,
+        })
+    }
+
+    /// Coefficient factors whose linear combination of node flows is limited to, rather than
+    /// fixed at, the right-hand side.
+    #[must_use]
+    pub fn new_coefficient_upper_bound_factors(factors: &[MetricF64], rhs: Option<MetricF64>) -> Self {
+        Relationship::Factored(Factors::Coefficients {
+            factors: factors.to_vec(),
+            rhs,
+            rhs_constraint: RhsConstraint::UpperBound,
         })
     }
 
@@ -194,11 +218,22 @@ pub struct NodeFactorPair<'a> {
     node0: NodeFactor<'a>,
     node1: NodeFactor<'a>,
     rhs: f64,
+    rhs_constraint: RhsConstraint,
 }
 
 impl<'a> NodeFactorPair<'a> {
     fn new(node0: NodeFactor<'a>, node1: NodeFactor<'a>, rhs: f64) -> Self {
-        Self { node0, node1, rhs }
+        Self {
+            node0,
+            node1,
+            rhs,
+            rhs_constraint: RhsConstraint::Equal,
+        }
+    }
+
+    fn with_rhs_constraint(mut self, rhs_constraint: RhsConstraint) -> Self {
+        self.rhs_constraint = rhs_constraint;
+        self
     }
 
     #[must_use]
@@ -223,6 +258,11 @@ impl<'a> NodeFactorPair<'a> {
     pub fn rhs(&self) -> f64 {
         self.rhs
     }
+
+    #[must_use]
+    pub fn rhs_constraint(&self) -> RhsConstraint {
+        self.rhs_constraint
+    }
 }
 
 /// A constant node factor. If the factor is non-constant, the factor value here is `None`.
@@ -244,11 +284,22 @@ pub struct NodeConstFactorPair<'a> {
     node0: NodeConstFactor<'a>,
     node1: NodeConstFactor<'a>,
     rhs: f64,
+    rhs_constraint: RhsConstraint,
 }
 
 impl<'a> NodeConstFactorPair<'a> {
     fn new(node0: NodeConstFactor<'a>, node1: NodeConstFactor<'a>, rhs: f64) -> Self {
-        Self { node0, node1, rhs }
+        Self {
+            node0,
+            node1,
+            rhs,
+            rhs_constraint: RhsConstraint::Equal,
+        }
+    }
+
+    fn with_rhs_constraint(mut self, rhs_constraint: RhsConstraint) -> Self {
+        self.rhs_constraint = rhs_constraint;
+        self
     }
 
     #[must_use]
@@ -273,6 +324,11 @@ impl<'a> NodeConstFactorPair<'a> {
     pub fn rhs(&self) -> f64 {
         self.rhs
     }
+
+    #[must_use]
+    pub fn rhs_constraint(&self) -> RhsConstraint {
+        self.rhs_constraint
+    }
 }
 
 impl AggregatedNode {
@@ -400,10 +456,12 @@ impl AggregatedNode {
                 Factors::Ratio { factors } => {
                     get_const_norm_ratio_factor_pairs(factors, &self.nodes, values).map_err(ConstantFactorError::Ratio)
                 }
-                Factors::Coefficients { factors, rhs } => {
-                    get_const_coefficient_factor_pairs(factors, &self.nodes, rhs.as_ref(), values)
-                        .map_err(ConstantFactorError::Coefficient)
-                }
+                Factors::Coefficients {
+                    factors,
+                    rhs,
+                    rhs_constraint,
+                } => get_const_coefficient_factor_pairs(factors, &self.nodes, rhs.as_ref(), *rhs_constraint, values)
+                    .map_err(ConstantFactorError::Coefficient),
             };
             Some(pairs)
         } else {
@@ -433,10 +491,12 @@ impl AggregatedNode {
                 Factors::Ratio { factors } => {
                     get_norm_ratio_factor_pairs(factors, &self.nodes, model, state).map_err(FactorError::Ratio)
                 }
-                Factors::Coefficients { factors, rhs } => {
-                    get_coefficient_factor_pairs(factors, &self.nodes, rhs.as_ref(), model, state)
-                        .map_err(FactorError::Coefficient)
-                }
+                Factors::Coefficients {
+                    factors,
+                    rhs,
+                    rhs_constraint,
+                } => get_coefficient_factor_pairs(factors, &self.nodes, rhs.as_ref(), *rhs_constraint, model, state)
+                    .map_err(FactorError::Coefficient),
             };
             Some(pairs)
         } else {
@@ -448,6 +508,18 @@ impl AggregatedNode {
         self.flow_constraints.min_flow = value;
     }
 
+    /// Returns true if no minimum flow constraint is defined.
+    #[must_use]
+    pub fn is_min_flow_unconstrained(&self) -> bool {
+        self.flow_constraints.is_min_flow_unconstrained()
+    }
+
+    /// Returns true if neither a minimum nor a maximum flow constraint is defined.
+    #[must_use]
+    pub fn is_flow_unconstrained(&self) -> bool {
+        self.flow_constraints.is_min_flow_unconstrained() && self.flow_constraints.is_max_flow_unconstrained()
+    }
+
     /// Get the min flow constraint value.
     ///
     /// # Errors
@@ -484,6 +556,22 @@ impl AggregatedNode {
         }
     }
 
+    /// Get the min and max flow bounds as a tuple if they are both constant.
+    ///
+    /// # Errors
+    ///
+    /// Any error when attempting to retrieve a constant value will be returned.
+    /// See [`MetricF64::try_get_constant_value`] for more information.
+    pub fn get_const_flow_bounds(
+        &self,
+        values: &ConstParameterValues,
+    ) -> Result<Option<(f64, f64)>, ConstantMetricF64Error> {
+        let min_flow = self.flow_constraints.get_const_min_flow(values)?;
+        let max_flow = self.flow_constraints.get_const_max_flow(values)?;
+
+        Ok(min_flow.zip(max_flow))
+    }
+
     #[must_use]
     pub fn default_metric(&self) -> MetricF64 {
         MetricF64::AggregatedNodeInFlow(self.index())
@@ -808,11 +896,12 @@ pub enum CoefficientFactorError {
 ///
 /// The number of node indices and factors should be equal. The factors correspond to each of the
 /// node indices. The `rhs` value is the right-hand side of the ratio equation. The same right-hand side is used
-/// for all factor pairs.
+/// for all factor pairs, and `rhs_constraint` defines whether it is an equality or an upper bound.
 fn get_coefficient_factor_pairs<'a>(
     factors: &[MetricF64],
     nodes: &'a [Vec<NodeIndex>],
     rhs: Option<&MetricF64>,
+    rhs_constraint: RhsConstraint,
     model: &Network,
     state: &State,
 ) -> Result<Vec<NodeFactorPair<'a>>, CoefficientFactorError> {
@@ -835,11 +924,14 @@ fn get_coefficient_factor_pairs<'a>(
         None => 0.0,
     };
 
-    Ok(vec![NodeFactorPair::new(
-        NodeFactor::new(nodes[0].as_slice(), f0),
-        NodeFactor::new(nodes[1].as_slice(), f1),
-        rhs,
-    )])
+    Ok(vec![
+        NodeFactorPair::new(
+            NodeFactor::new(nodes[0].as_slice(), f0),
+            NodeFactor::new(nodes[1].as_slice(), f1),
+            rhs,
+        )
+        .with_rhs_constraint(rhs_constraint),
+    ])
 }
 
 #[derive(Debug, Error)]
@@ -859,6 +951,7 @@ fn get_const_coefficient_factor_pairs<'a>(
     factors: &[MetricF64],
     nodes: &'a [Vec<NodeIndex>],
     rhs: Option<&MetricF64>,
+    rhs_constraint: RhsConstraint,
     values: &ConstParameterValues,
 ) -> Result<Vec<NodeConstFactorPair<'a>>, ConstantCoefficientFactorError> {
     if factors.len() != nodes.len() {
@@ -882,23 +975,28 @@ fn get_const_coefficient_factor_pairs<'a>(
         None => 0.0,
     };
 
-    Ok(vec![NodeConstFactorPair::new(
-        NodeConstFactor::new(nodes[0].as_slice(), f0),
-        NodeConstFactor::new(nodes[1].as_slice(), f1),
-        rhs,
-    )])
+    Ok(vec![
+        NodeConstFactorPair::new(
+            NodeConstFactor::new(nodes[0].as_slice(), f0),
+            NodeConstFactor::new(nodes[1].as_slice(), f1),
+            rhs,
+        )
+        .with_rhs_constraint(rhs_constraint),
+    ])
 }
 
 #[cfg(test)]
 mod tests {
     use crate::aggregated_node::Relationship;
     use crate::metric::MetricF64;
-    use crate::models::Model;
+    use crate::models::{Model, ModelDomain};
     use crate::network::Network;
-    use crate::parameters::MonthlyProfileParameter;
+    use crate::node::StorageInitialVolume;
+    use crate::parameters::{Array2Parameter, MonthlyProfileParameter};
     use crate::recorders::AssertionF64Recorder;
+    use crate::scenario::{ScenarioDomainBuilder, ScenarioGroupBuilder};
     use crate::state::ParameterReturnValue;
-    use crate::test_utils::{default_time_domain, run_all_solvers};
+    use crate::test_utils::{default_time_domain, default_timestepper, run_all_solvers};
     use ndarray::Array2;
 
     /// Test the factors forcing a simple ratio of flow
@@ -946,7 +1044,59 @@ mod tests {
 
         let model = Model::new(default_time_domain().into(), network);
 
-        run_all_solvers(&model, &["ipm-simd", "ipm-ocl"], &[], &[]);
+        run_all_solvers(&model, &["ipm-ocl"], &[], &[]);
+    }
+
+    /// Test coefficient factors that limit, rather than fix, a linear combination of flows
+    ///
+    /// The model has a single input that diverges to two links and respective output nodes. The
+    /// flow of link-0 less the flow of link-1 is limited to 2.0, which does not prevent all of the
+    /// flow going to output-1. An equality would instead force a flow of 6.0 to output-0.
+    #[test]
+    fn test_coefficient_upper_bound_factors() {
+        let mut network = Network::default();
+
+        let input_node = network.add_input_node("input", None).unwrap();
+        let link_node0 = network.add_link_node("link", Some("0")).unwrap();
+        let output_node0 = network.add_output_node("output", Some("0")).unwrap();
+
+        network.connect_nodes(input_node, link_node0).unwrap();
+        network.connect_nodes(link_node0, output_node0).unwrap();
+
+        let link_node1 = network.add_link_node("link", Some("1")).unwrap();
+        let output_node1 = network.add_output_node("output", Some("1")).unwrap();
+
+        network.connect_nodes(input_node, link_node1).unwrap();
+        network.connect_nodes(link_node1, output_node1).unwrap();
+
+        let input = network.get_mut_node_by_name("input", None).unwrap();
+        input.set_max_flow_constraint(Some(10.0.into())).unwrap();
+
+        let relationship = Some(Relationship::new_coefficient_upper_bound_factors(
+            &[1.0.into(), (-1.0).into()],
+            Some(2.0.into()),
+        ));
+
+        network
+            .add_aggregated_node("agg-node", None, &[vec![link_node0], vec![link_node1]], relationship)
+            .unwrap();
+
+        let output = network.get_mut_node_by_name("output", Some("1")).unwrap();
+        output.set_cost(Some((-10.0).into()));
+
+        let expected = Array2::from_elem((366, 10), 0.0);
+        let recorder =
+            AssertionF64Recorder::new("link-0-flow", MetricF64::NodeOutFlow(link_node0), expected, None, None);
+        network.add_recorder(Box::new(recorder)).unwrap();
+
+        let expected = Array2::from_elem((366, 10), 10.0);
+        let recorder =
+            AssertionF64Recorder::new("link-1-flow", MetricF64::NodeOutFlow(link_node1), expected, None, None);
+        network.add_recorder(Box::new(recorder)).unwrap();
+
+        let model = Model::new(default_time_domain().into(), network);
+
+        run_all_solvers(&model, &["ipm-ocl"], &[], &[]);
     }
 
     /// Test the factors forcing a simple ratio of flow that varies over time
@@ -1000,7 +1150,124 @@ mod tests {
 
         let model = Model::new(default_time_domain().into(), network);
 
-        run_all_solvers(&model, &["cbc", "ipm-simd", "ipm-ocl"], &[], &[]);
+        run_all_solvers(&model, &["cbc", "ipm-ocl"], &[], &[]);
+    }
+
+    /// Test ratio factors that differ between the scenarios
+    ///
+    /// The model has a single input that diverges to two links and respective output nodes. The
+    /// ratio of the flow of link-0 to link-1 is one more than the scenario index.
+    #[test]
+    fn test_scenario_factors() {
+        let scenario_group = ScenarioGroupBuilder::new("test-scenario", 10).build().unwrap();
+        let scenario_builder = ScenarioDomainBuilder::default().with_group(scenario_group).unwrap();
+        let domain = ModelDomain::try_from(default_timestepper(), scenario_builder).unwrap();
+
+        let mut network = Network::default();
+
+        let input_node = network.add_input_node("input", None).unwrap();
+        let link_node0 = network.add_link_node("link", Some("0")).unwrap();
+        let output_node0 = network.add_output_node("output", Some("0")).unwrap();
+
+        network.connect_nodes(input_node, link_node0).unwrap();
+        network.connect_nodes(link_node0, output_node0).unwrap();
+
+        let link_node1 = network.add_link_node("link", Some("1")).unwrap();
+        let output_node1 = network.add_output_node("output", Some("1")).unwrap();
+
+        network.connect_nodes(input_node, link_node1).unwrap();
+        network.connect_nodes(link_node1, output_node1).unwrap();
+
+        let scenario_factors = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0];
+        let factors = Array2::from_shape_fn((366, 10), |(_, j)| scenario_factors[j]);
+        let factors = Array2Parameter::new("factors".into(), factors, 0, None);
+        let factors_idx = network.add_simple_parameter(Box::new(factors)).unwrap();
+
+        let relationship = Some(Relationship::new_ratio_factors(&[
+            factors_idx.into_metric_f64(ParameterReturnValue::Before),
+            1.0.into(),
+        ]));
+
+        network
+            .add_aggregated_node("agg-node", None, &[vec![link_node0], vec![link_node1]], relationship)
+            .unwrap();
+
+        // Setup a demand on output-0
+        let output_node = network.get_mut_node_by_name("output", Some("0")).unwrap();
+        output_node.set_max_flow_constraint(Some(100.0.into())).unwrap();
+        output_node.set_cost(Some((-10.0).into()));
+
+        let expected = Array2::from_elem((366, 10), 100.0);
+        let recorder =
+            AssertionF64Recorder::new("link-0-flow", MetricF64::NodeOutFlow(link_node0), expected, None, None);
+        network.add_recorder(Box::new(recorder)).unwrap();
+
+        let expected = Array2::from_shape_fn((366, 10), |(_, j)| 100.0 / scenario_factors[j]);
+        let recorder =
+            AssertionF64Recorder::new("link-1-flow", MetricF64::NodeOutFlow(link_node1), expected, None, None);
+        network.add_recorder(Box::new(recorder)).unwrap();
+
+        let model = Model::new(domain, network);
+
+        run_all_solvers(&model, &["cbc", "ipm-ocl"], &[], &[]);
+    }
+
+    /// Test coefficient factors that vary over time and include a storage node
+    ///
+    /// The model has a fixed inflow to a storage node that releases to a demand. The storage node
+    /// contributes its net inflow (inflow minus release) to the constraint, and the release edge is
+    /// shared by the storage and demand nodes. The constraint `demand - (inflow - demand) = 0`
+    /// requires the release to be half of the inflow.
+    #[test]
+    fn test_dynamic_coefficient_factors_with_storage() {
+        let mut network = Network::default();
+
+        let input_node = network.add_input_node("input", None).unwrap();
+        let storage_node = network
+            .add_storage_node(
+                "storage",
+                None,
+                StorageInitialVolume::Absolute(0.0),
+                None,
+                Some(10000.0.into()),
+            )
+            .unwrap();
+        let output_node = network.add_output_node("output", None).unwrap();
+
+        network.connect_nodes(input_node, storage_node).unwrap();
+        network.connect_nodes(storage_node, output_node).unwrap();
+
+        let input = network.get_mut_node_by_name("input", None).unwrap();
+        input.set_min_flow_constraint(Some(10.0.into())).unwrap();
+        input.set_max_flow_constraint(Some(10.0.into())).unwrap();
+
+        let output = network.get_mut_node_by_name("output", None).unwrap();
+        output.set_max_flow_constraint(Some(100.0.into())).unwrap();
+        output.set_cost(Some((-10.0).into()));
+
+        let factor_profile = MonthlyProfileParameter::new("factor-profile".into(), [-1.0; 12], None);
+        let factor_profile_idx = network.add_simple_parameter(Box::new(factor_profile)).unwrap();
+
+        let relationship = Some(Relationship::new_coefficient_factors(
+            &[
+                1.0.into(),
+                factor_profile_idx.into_metric_f64(ParameterReturnValue::Before),
+            ],
+            None,
+        ));
+
+        network
+            .add_aggregated_node("agg-node", None, &[vec![output_node], vec![storage_node]], relationship)
+            .unwrap();
+
+        let expected = Array2::from_elem((366, 10), 5.0);
+        let recorder =
+            AssertionF64Recorder::new("output-flow", MetricF64::NodeInFlow(output_node), expected, None, None);
+        network.add_recorder(Box::new(recorder)).unwrap();
+
+        let model = Model::new(default_time_domain().into(), network);
+
+        run_all_solvers(&model, &["cbc", "ipm-ocl"], &[], &[]);
     }
 
     /// Test mutual exclusive flows
@@ -1139,4 +1406,71 @@ mod tests {
 
         run_all_solvers(&model, &["clp", "ipm-ocl", "ipm-simd"], &[], &[]);
     }
+
+    /// A model with an aggregated node over two links with a minimum flow. The flows are
+    /// asserted to within `epsilon`.
+    ///
+    /// The links have a positive cost, so the only flow is that required by the minimum flow of the
+    /// aggregated node, which is all through the cheaper link.
+    fn min_flow_model(epsilon: Option<f64>) -> Model {
+        let mut network = Network::default();
+
+        let input_node = network.add_input_node("input", None).unwrap();
+
+        for (i, cost) in [1.0, 2.0].into_iter().enumerate() {
+            let sub_name = i.to_string();
+            let link_node = network.add_link_node("link", Some(&sub_name)).unwrap();
+            let output_node = network.add_output_node("output", Some(&sub_name)).unwrap();
+
+            network.connect_nodes(input_node, link_node).unwrap();
+            network.connect_nodes(link_node, output_node).unwrap();
+
+            let node = network.get_mut_node_by_name("link", Some(&sub_name)).unwrap();
+            node.set_cost(Some(cost.into()));
+            node.set_max_flow_constraint(Some(10.0.into())).unwrap();
+        }
+
+        let link_node0 = network.get_node_index_by_name("link", Some("0")).unwrap();
+        let link_node1 = network.get_node_index_by_name("link", Some("1")).unwrap();
+        network
+            .add_aggregated_node("agg-node", None, &[vec![link_node0], vec![link_node1]], None)
+            .unwrap();
+        network
+            .set_aggregated_node_min_flow("agg-node", None, Some(5.0.into()))
+            .unwrap();
+
+        for (sub_name, flow) in [("0", 5.0), ("1", 0.0)] {
+            let idx = network.get_node_by_name("link", Some(sub_name)).unwrap().index();
+            let expected = Array2::from_elem((366, 10), flow);
+            let recorder = AssertionF64Recorder::new(
+                &format!("link-{sub_name}-flow"),
+                MetricF64::NodeOutFlow(idx),
+                expected,
+                None,
+                epsilon,
+            );
+            network.add_recorder(Box::new(recorder)).unwrap();
+        }
+
+        Model::new(default_time_domain().into(), network)
+    }
+
+    /// Test the minimum flow of an aggregated node
+    #[test]
+    fn test_min_flow() {
+        let model = min_flow_model(None);
+        // The IPM's solution is only accurate to its tolerance and is tested below.
+        run_all_solvers(&model, &["ipm-ocl"], &["ipm-simd"], &[]);
+    }
+
+    /// Test the minimum flow of an aggregated node with the SIMD IPM to within its tolerance.
+    #[cfg(feature = "ipm-simd")]
+    #[test]
+    fn test_min_flow_ipm_simd() {
+        let model = min_flow_model(Some(1e-6));
+        let settings = crate::solvers::SimdIpmSolverSettings::default();
+        model
+            .run_multi_scenario::<crate::solvers::SimdIpmF64Solver>(&settings)
+            .unwrap();
+    }
 }
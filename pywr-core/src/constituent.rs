@@ -0,0 +1,463 @@
+//! Transport of conservative water quality constituents.
+//!
+//! A [`Constituent`] (e.g. salinity or nitrate) is carried with the flows of the network. The
+//! transport calculation is performed after each solve using the edge flows in the network state.
+//! Concentrations are defined at input nodes; these blend at nodes with multiple inflows and
+//! fully mix with the stored volume in storage nodes. The calculated concentrations are
+//! available as metrics (see [`crate::metric::MetricF64::NodeConcentration`]) and therefore
+//! can be used by parameters in subsequent time-steps.
+use crate::NodeIndex;
+use crate::metric::{MetricF64, MetricF64Error};
+use crate::network::{Network, NetworkError};
+use crate::node::Node;
+use crate::state::{ConstituentState, NetworkStateError, State, StateError};
+use crate::timestep::Timestep;
+use std::collections::VecDeque;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::ops::{Deref, DerefMut};
+use thiserror::Error;
+
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Debug, Hash)]
+pub struct ConstituentIndex(usize);
+
+impl Deref for ConstituentIndex {
+    type Target = usize;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Display for ConstituentIndex {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ConstituentError {
+    #[error("Node index not found: {0}")]
+    NodeIndexNotFound(NodeIndex),
+    #[error("Constituent index not found: {0}")]
+    ConstituentIndexNotFound(ConstituentIndex),
+    #[error("Error calculating input concentration for node `{name}`: {source}")]
+    InputConcentrationError {
+        name: String,
+        #[source]
+        source: MetricF64Error,
+    },
+    #[error("Network state error: {0}")]
+    NetworkStateError(#[from] NetworkStateError),
+    #[error("State error: {0}")]
+    StateError(#[from] StateError),
+}
+
+#[derive(Default)]
+pub struct ConstituentVec {
+    constituents: Vec<Constituent>,
+}
+
+impl Deref for ConstituentVec {
+    type Target = Vec<Constituent>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.constituents
+    }
+}
+
+impl DerefMut for ConstituentVec {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.constituents
+    }
+}
+
+impl ConstituentVec {
+    pub fn get(&self, index: &ConstituentIndex) -> Option<&Constituent> {
+        self.constituents.get(index.0)
+    }
+
+    pub fn get_mut(&mut self, index: &ConstituentIndex) -> Option<&mut Constituent> {
+        self.constituents.get_mut(index.0)
+    }
+
+    pub fn push_new(&mut self, builder: ConstituentBuilder) -> Result<ConstituentIndex, NetworkError> {
+        if self.constituents.iter().any(|c| c.name() == builder.name) {
+            return Err(NetworkError::ConstituentAlreadyExists(builder.name.clone()));
+        }
+
+        let index = ConstituentIndex(self.constituents.len());
+        self.constituents.push(builder.build(index));
+        Ok(index)
+    }
+}
+
+/// Builder for creating a [`Constituent`].
+pub struct ConstituentBuilder {
+    name: String,
+    input_concentrations: Vec<(NodeIndex, MetricF64)>,
+    initial_concentrations: Vec<(NodeIndex, f64)>,
+}
+
+impl ConstituentBuilder {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            input_concentrations: Vec::new(),
+            initial_concentrations: Vec::new(),
+        }
+    }
+
+    /// Set the concentration of the flow leaving an input node.
+    ///
+    /// Input nodes without a concentration are assumed to supply flow with a concentration of zero.
+    pub fn input_concentration(mut self, node: NodeIndex, concentration: MetricF64) -> Self {
+        self.input_concentrations.push((node, concentration));
+        self
+    }
+
+    /// Set the initial concentration of the volume in a storage node.
+    pub fn initial_concentration(mut self, node: NodeIndex, concentration: f64) -> Self {
+        self.initial_concentrations.push((node, concentration));
+        self
+    }
+
+    pub fn build(self, index: ConstituentIndex) -> Constituent {
+        Constituent {
+            index,
+            name: self.name,
+            input_concentrations: self.input_concentrations,
+            initial_concentrations: self.initial_concentrations,
+        }
+    }
+}
+
+/// A conservative constituent transported with the flows in the network.
+pub struct Constituent {
+    index: ConstituentIndex,
+    name: String,
+    input_concentrations: Vec<(NodeIndex, MetricF64)>,
+    initial_concentrations: Vec<(NodeIndex, f64)>,
+}
+
+impl Constituent {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn index(&self) -> ConstituentIndex {
+        self.index
+    }
+
+    /// Set the concentration of the flow leaving an input node, replacing any existing value.
+    pub fn set_input_concentration(&mut self, node: NodeIndex, concentration: MetricF64) {
+        match self.input_concentrations.iter_mut().find(|(idx, _)| *idx == node) {
+            Some((_, c)) => *c = concentration,
+            None => self.input_concentrations.push((node, concentration)),
+        }
+    }
+
+    /// Create the initial state for this constituent.
+    ///
+    /// All concentrations are zero except for storage nodes with an initial concentration.
+    pub fn default_state(&self, num_nodes: usize) -> ConstituentState {
+        let mut concentrations = vec![0.0; num_nodes];
+        for (node_index, concentration) in &self.initial_concentrations {
+            if let Some(c) = concentrations.get_mut(*node_index.deref()) {
+                *c = *concentration;
+            }
+        }
+        ConstituentState::new(concentrations)
+    }
+
+    /// Update the concentrations of this constituent using the flows of the current time-step.
+    ///
+    /// This must be called after the solve, once the network state contains the flows and
+    /// volumes for `timestep`. Nodes are processed in `order` (see [`transport_order`]) such
+    /// that upstream concentrations are resolved before they are blended downstream. Flow from
+    /// a node that has not yet been processed (i.e. within a cycle) uses its concentration from
+    /// the previous time-step.
+    pub fn transport(
+        &self,
+        network: &Network,
+        order: &[NodeIndex],
+        timestep: &Timestep,
+        state: &mut State,
+    ) -> Result<(), ConstituentError> {
+        let network_state = state.get_network_state();
+        let previous = network_state
+            .get_constituent_state(&self.index)
+            .ok_or(ConstituentError::ConstituentIndexNotFound(self.index))?;
+
+        let mut concentrations = previous.concentrations().to_vec();
+
+        for node_index in order {
+            let node = network
+                .get_node(node_index)
+                .ok_or(ConstituentError::NodeIndexNotFound(*node_index))?;
+
+            let concentration = match node {
+                Node::Input(_) => match self.input_concentrations.iter().find(|(idx, _)| idx == node_index) {
+                    Some((_, metric)) => metric.get_value(network, state).map_err(|source| {
+                        ConstituentError::InputConcentrationError {
+                            name: node.name().to_string(),
+                            source,
+                        }
+                    })?,
+                    None => 0.0,
+                },
+                Node::Link(_) | Node::Output(_) => {
+                    let in_flow = network_state.get_node_in_flow(node_index)?;
+                    if in_flow > 0.0 {
+                        self.incoming_mass(network, node, &concentrations, state)? / in_flow
+                    } else {
+                        0.0
+                    }
+                }
+                Node::Storage(_) => {
+                    // Fully mixed; the inflow mixes with the volume at the start of the
+                    // time-step before any outflow is released.
+                    let dt = timestep.days();
+                    let volume = network_state.get_node_volume(node_index)?;
+                    let in_flow = network_state.get_node_in_flow(node_index)?;
+                    let out_flow = network_state.get_node_out_flow(node_index)?;
+                    let mixed_volume = volume + out_flow * dt;
+                    let start_volume = mixed_volume - in_flow * dt;
+
+                    let previous_concentration = concentrations[*node_index.deref()];
+                    let mass = previous_concentration * start_volume
+                        + self.incoming_mass(network, node, &concentrations, state)? * dt;
+
+                    if mixed_volume > 0.0 {
+                        mass / mixed_volume
+                    } else {
+                        previous_concentration
+                    }
+                }
+            };
+
+            concentrations[*node_index.deref()] = concentration;
+        }
+
+        state
+            .get_mut_network_state()
+            .set_constituent_concentrations(&self.index, concentrations)?;
+
+        Ok(())
+    }
+
+    /// The total mass flux into `node` from its upstream nodes.
+    fn incoming_mass(
+        &self,
+        network: &Network,
+        node: &Node,
+        concentrations: &[f64],
+        state: &State,
+    ) -> Result<f64, ConstituentError> {
+        let mut mass = 0.0;
+        if let Ok(edges) = node.get_incoming_edges() {
+            for edge_index in edges {
+                let edge = network
+                    .get_edge(edge_index)
+                    .expect("Edge not found; this is a bug and should not be possible.");
+                let flow = state.get_network_state().get_edge_flow(edge_index)?;
+                mass += flow * concentrations[*edge.from_node_index().deref()];
+            }
+        }
+        Ok(mass)
+    }
+}
+
+/// Compute the order in which nodes are processed by the transport calculation.
+///
+/// This is a topological ordering of the network's nodes. Any nodes that are part of a cycle
+/// are appended to the end of the order in index order.
+pub fn transport_order(network: &Network) -> Vec<NodeIndex> {
+    let nodes = network.nodes();
+    let mut in_degree: Vec<usize> = nodes
+        .iter()
+        .map(|n| n.get_incoming_edges().map(|e| e.len()).unwrap_or(0))
+        .collect();
+
+    let mut queue: VecDeque<NodeIndex> = nodes
+        .iter()
+        .filter(|n| in_degree[*n.index().deref()] == 0)
+        .map(|n| n.index())
+        .collect();
+
+    let mut order = Vec::with_capacity(nodes.len());
+    let mut visited = vec![false; nodes.len()];
+
+    while let Some(node_index) = queue.pop_front() {
+        visited[*node_index.deref()] = true;
+        order.push(node_index);
+
+        let node = nodes.get(&node_index).expect("Node index from the network must exist.");
+        if let Ok(edges) = node.get_outgoing_edges() {
+            for edge_index in edges {
+                let to_node_index = network
+                    .get_edge(edge_index)
+                    .expect("Edge not found; this is a bug and should not be possible.")
+                    .to_node_index();
+                let d = &mut in_degree[*to_node_index.deref()];
+                *d -= 1;
+                if *d == 0 {
+                    queue.push_back(to_node_index);
+                }
+            }
+        }
+    }
+
+    // Nodes in cycles are never released by the above.
+    order.extend(nodes.iter().map(|n| n.index()).filter(|idx| !visited[*idx.deref()]));
+
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::constituent::ConstituentBuilder;
+    use crate::metric::MetricF64;
+    use crate::models::Model;
+    use crate::network::Network;
+    use crate::node::StorageInitialVolume;
+    use crate::recorders::AssertionF64Recorder;
+    use crate::test_utils::{default_time_domain, run_all_solvers};
+    use ndarray::Array2;
+
+    /// Two inputs of different concentrations blending at a link node.
+    #[test]
+    fn test_blending() {
+        let mut network = Network::default();
+
+        let input1 = network.add_input_node("input1", None).unwrap();
+        let input2 = network.add_input_node("input2", None).unwrap();
+        let link = network.add_link_node("link", None).unwrap();
+        let output = network.add_output_node("output", None).unwrap();
+
+        network.connect_nodes(input1, link).unwrap();
+        network.connect_nodes(input2, link).unwrap();
+        network.connect_nodes(link, output).unwrap();
+
+        let node = network.get_mut_node_by_name("input1", None).unwrap();
+        node.set_max_flow_constraint(Some(3.0.into())).unwrap();
+        let node = network.get_mut_node_by_name("input2", None).unwrap();
+        node.set_max_flow_constraint(Some(1.0.into())).unwrap();
+        let node = network.get_mut_node_by_name("output", None).unwrap();
+        node.set_max_flow_constraint(Some(10.0.into())).unwrap();
+        node.set_cost(Some((-10.0).into()));
+
+        let builder = ConstituentBuilder::new("salinity")
+            .input_concentration(input1, 100.0.into())
+            .input_concentration(input2, 500.0.into());
+        let salinity = network.add_constituent(builder).unwrap();
+
+        let domain = default_time_domain().into();
+        let num_timesteps = 15;
+
+        let expected = Array2::from_elem((num_timesteps, 1), 200.0);
+        let recorder = AssertionF64Recorder::new(
+            "link-concentration",
+            MetricF64::NodeConcentration {
+                node: link,
+                constituent: salinity,
+            },
+            expected,
+            None,
+            None,
+        );
+        network.add_recorder(Box::new(recorder)).unwrap();
+
+        let expected = Array2::from_elem((num_timesteps, 1), 800.0);
+        let recorder = AssertionF64Recorder::new(
+            "output-mass",
+            MetricF64::NodeMass {
+                node: output,
+                constituent: salinity,
+            },
+            expected,
+            None,
+            Some(1e-3),
+        );
+        network.add_recorder(Box::new(recorder)).unwrap();
+
+        let model = Model::new(domain, network);
+        run_all_solvers(&model, &[], &[], &[]);
+    }
+
+    /// An input flowing into a fully mixed storage node.
+    #[test]
+    fn test_storage_mixing() {
+        let mut network = Network::default();
+
+        let input = network.add_input_node("input", None).unwrap();
+        let storage = network
+            .add_storage_node(
+                "reservoir",
+                None,
+                StorageInitialVolume::Absolute(100.0),
+                None,
+                Some(100.0.into()),
+            )
+            .unwrap();
+        let output = network.add_output_node("output", None).unwrap();
+
+        network.connect_nodes(input, storage).unwrap();
+        network.connect_nodes(storage, output).unwrap();
+
+        let node = network.get_mut_node_by_name("input", None).unwrap();
+        node.set_max_flow_constraint(Some(10.0.into())).unwrap();
+        let node = network.get_mut_node_by_name("reservoir", None).unwrap();
+        node.set_cost(Some((-1.0).into()));
+        let node = network.get_mut_node_by_name("output", None).unwrap();
+        node.set_max_flow_constraint(Some(10.0.into())).unwrap();
+        node.set_cost(Some((-10.0).into()));
+
+        let builder = ConstituentBuilder::new("nitrate")
+            .input_concentration(input, 10.0.into())
+            .initial_concentration(storage, 0.0);
+        let nitrate = network.add_constituent(builder).unwrap();
+
+        // The reservoir remains full; each day 10 units of concentration 10 mix with the
+        // 100 units in storage before 10 units are released.
+        let num_timesteps = 15;
+        let mut c = 0.0;
+        let expected = Array2::from_shape_fn((num_timesteps, 1), |(i, _)| {
+            if i == 0 {
+                c = 0.0;
+            }
+            c = (c * 100.0 + 10.0 * 10.0) / 110.0;
+            c
+        });
+
+        let recorder = AssertionF64Recorder::new(
+            "reservoir-concentration",
+            MetricF64::NodeConcentration {
+                node: storage,
+                constituent: nitrate,
+            },
+            expected.clone(),
+            None,
+            Some(1e-3),
+        );
+        network.add_recorder(Box::new(recorder)).unwrap();
+
+        let expected = expected.mapv(|c| c * 100.0);
+        let recorder = AssertionF64Recorder::new(
+            "reservoir-mass",
+            MetricF64::NodeMass {
+                node: storage,
+                constituent: nitrate,
+            },
+            expected,
+            None,
+            Some(1e-3),
+        );
+        network.add_recorder(Box::new(recorder)).unwrap();
+
+        let domain = default_time_domain().into();
+        let model = Model::new(domain, network);
+
+        run_all_solvers(&model, &[], &[], &[]);
+    }
+}
@@ -1,7 +1,7 @@
 use crate::NodeIndex;
 use crate::network::Network;
 use crate::node::{NodeError, NodeVec};
-use crate::state::State;
+use crate::state::{ConstParameterValues, State};
 use std::fmt::{Display, Formatter};
 use std::ops::{Deref, DerefMut};
 use thiserror::Error;
@@ -67,7 +67,7 @@ impl Edge {
             .ok_or(EdgeError::FromNodeIndexNotFound(self.from_node_index))?;
         let to_node = nodes
             .get(&self.to_node_index)
-            .ok_or(EdgeError::ToNodeIndexNotFound(self.from_node_index))?;
+            .ok_or(EdgeError::ToNodeIndexNotFound(self.to_node_index))?;
 
         let from_cost = from_node
             .get_outgoing_cost(model, state)
@@ -78,6 +78,25 @@ impl Edge {
 
         Ok(from_cost + to_cost)
     }
+
+    /// Return the cost of the edge if it is constant.
+    pub fn const_cost(&self, nodes: &NodeVec, values: &ConstParameterValues) -> Result<Option<f64>, EdgeError> {
+        let from_node = nodes
+            .get(&self.from_node_index)
+            .ok_or(EdgeError::FromNodeIndexNotFound(self.from_node_index))?;
+        let to_node = nodes
+            .get(&self.to_node_index)
+            .ok_or(EdgeError::ToNodeIndexNotFound(self.to_node_index))?;
+
+        let from_cost = from_node
+            .get_const_outgoing_cost(values)
+            .map_err(|e| EdgeError::NodeError(Box::new(e)))?;
+        let to_cost = to_node
+            .get_const_incoming_cost(values)
+            .map_err(|e| EdgeError::NodeError(Box::new(e)))?;
+
+        Ok(from_cost.zip(to_cost).map(|(f, t)| f + t))
+    }
 }
 
 #[derive(Default)]
@@ -5,6 +5,7 @@ pub use network::NetworkError;
 pub mod agg_funcs;
 pub mod aggregated_node;
 mod aggregated_storage_node;
+pub mod constituent;
 pub mod edge;
 pub mod metric;
 pub mod models;
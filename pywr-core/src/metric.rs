@@ -1,9 +1,10 @@
 use crate::aggregated_node::AggregatedNodeIndex;
 use crate::aggregated_storage_node::AggregatedStorageNodeIndex;
+use crate::constituent::ConstituentIndex;
 use crate::edge::EdgeIndex;
 use crate::models::MultiNetworkTransferIndex;
 use crate::network::Network;
-use crate::node::{NodeError, NodeIndex};
+use crate::node::{Node, NodeError, NodeIndex};
 use crate::parameters::{ConstParameterIndex, GeneralParameterIndex, ParameterIndex, SimpleParameterIndex};
 use crate::state::{
     ConstParameterValues, MultiValue, NetworkStateError, ParameterReturnValue, SimpleParameterValues, State, StateError,
@@ -199,6 +200,22 @@ pub enum MetricF64 {
     VirtualStorageVolume(VirtualStorageIndex),
     VirtualStorageProportionalVolume(VirtualStorageIndex),
     VirtualStorageMaxVolume(VirtualStorageIndex),
+    /// The concentration of a constituent at a node.
+    ///
+    /// Concentrations are updated after the solve. Therefore, parameters evaluated before the
+    /// solve see the concentration from the previous time-step.
+    NodeConcentration {
+        node: NodeIndex,
+        constituent: ConstituentIndex,
+    },
+    /// The mass of a constituent at a node.
+    ///
+    /// For storage nodes this is the mass held in storage. For all other nodes this is the mass
+    /// flux through the node (i.e. concentration multiplied by flow).
+    NodeMass {
+        node: NodeIndex,
+        constituent: ConstituentIndex,
+    },
     MultiNodeInFlow {
         indices: Vec<NodeIndex>,
         name: String,
@@ -341,6 +358,21 @@ impl MetricF64 {
                     .sum::<Result<_, _>>()?;
                 Ok(flow)
             }
+            MetricF64::NodeConcentration { node, constituent } => {
+                Ok(state.get_network_state().get_node_concentration(node, constituent)?)
+            }
+            MetricF64::NodeMass { node, constituent } => {
+                let network_state = state.get_network_state();
+                let concentration = network_state.get_node_concentration(node, constituent)?;
+
+                let quantity = match network.get_node(node).ok_or(MetricF64Error::NodeIndexNotFound(*node))? {
+                    Node::Storage(_) => network_state.get_node_volume(node)?,
+                    Node::Input(_) => network_state.get_node_out_flow(node)?,
+                    Node::Link(_) | Node::Output(_) => network_state.get_node_in_flow(node)?,
+                };
+
+                Ok(concentration * quantity)
+            }
             MetricF64::InterNetworkTransfer(idx) => Ok(state.get_inter_network_transfer_value(*idx)?),
             MetricF64::Simple(s) => Ok(s.get_value(&state.get_simple_parameter_values())?),
         }
@@ -16,7 +16,9 @@ use crate::solvers::{ClpSolver, build_clp_settings_py};
 use crate::solvers::{HighsSolver, build_highs_settings_py};
 use crate::solvers::{MultiStateSolver, Solver, SolverSettings};
 #[cfg(all(feature = "ipm-simd", feature = "pyo3"))]
-use crate::solvers::{SimdIpmF64Solver, build_ipm_simd_settings_py};
+use crate::solvers::{SimdIpmF32Solver, SimdIpmF64Solver, build_ipm_simd_settings_py};
+#[cfg(all(feature = "simplex", feature = "pyo3"))]
+use crate::solvers::{SimplexSolver, build_simplex_settings_py};
 use crate::state::StateError;
 use crate::timestep::Timestep;
 #[cfg(feature = "pyo3")]
@@ -488,12 +490,12 @@ impl MultiNetworkModel {
     where
         S: Solver,
     {
-        let timestep = self
-            .domain
-            .time
-            .timesteps()
+        let timesteps = self.domain.time.timesteps();
+        let timestep = timesteps
             .get(state.current_time_step_idx)
             .ok_or(MultiNetworkModelStepError::EndOfTimesteps)?;
+        // The following time-steps are available to solvers with a look-ahead horizon
+        let forecast_timesteps = &timesteps[state.current_time_step_idx + 1..];
 
         let scenario_indices = self.domain.scenarios.indices();
 
@@ -512,7 +514,14 @@ impl MultiNetworkModel {
             // Perform sub-model step
             entry
                 .network
-                .step(timestep, scenario_indices, sub_model_solvers, sub_model_states, timing)
+                .step(
+                    timestep,
+                    forecast_timesteps,
+                    scenario_indices,
+                    sub_model_solvers,
+                    sub_model_states,
+                    timing,
+                )
                 .unwrap();
 
             let sub_model_recorder_states = state.recorder_states.get_mut(idx).unwrap();
@@ -776,7 +785,7 @@ impl MultiNetworkModel {
     }
 
     /// Run a model using the specified solver unlocking the GIL
-    #[cfg(any(feature = "clp", feature = "highs"))]
+    #[cfg(any(feature = "clp", feature = "highs", feature = "simplex"))]
     #[cfg(feature = "pyo3")]
     fn run_allowing_threads_py<S>(
         &self,
@@ -816,17 +825,35 @@ impl MultiNetworkModel {
     fn run_py(
         &self,
         #[cfg_attr(
-            not(any(feature = "clp", feature = "highs", feature = "ipm-simd", feature = "ipm-ocl")),
+            not(any(
+                feature = "clp",
+                feature = "highs",
+                feature = "ipm-simd",
+                feature = "ipm-ocl",
+                feature = "simplex"
+            )),
             allow(unused_variables)
         )]
         py: Python<'_>,
         #[cfg_attr(
-            not(any(feature = "clp", feature = "highs", feature = "ipm-simd", feature = "ipm-ocl")),
+            not(any(
+                feature = "clp",
+                feature = "highs",
+                feature = "ipm-simd",
+                feature = "ipm-ocl",
+                feature = "simplex"
+            )),
             allow(unused_variables)
         )]
         solver_name: &str,
         #[cfg_attr(
-            not(any(feature = "clp", feature = "highs", feature = "ipm-simd", feature = "ipm-ocl")),
+            not(any(
+                feature = "clp",
+                feature = "highs",
+                feature = "ipm-simd",
+                feature = "ipm-ocl",
+                feature = "simplex"
+            )),
             allow(unused_variables)
         )]
         solver_kwargs: Option<&Bound<'_, PyDict>>,
@@ -847,11 +874,21 @@ impl MultiNetworkModel {
                 let settings = build_highs_settings_py(solver_kwargs)?;
                 self.run_allowing_threads_py::<HighsSolver>(py, &settings)
             }
+            #[cfg(feature = "simplex")]
+            "simplex" => {
+                let settings = build_simplex_settings_py(solver_kwargs)?;
+                self.run_allowing_threads_py::<SimplexSolver>(py, &settings)
+            }
             #[cfg(feature = "ipm-simd")]
             "ipm-simd" => {
                 let settings = build_ipm_simd_settings_py(solver_kwargs)?;
                 self.run_multi_allowing_threads_py::<SimdIpmF64Solver>(py, &settings)
             }
+            #[cfg(feature = "ipm-simd")]
+            "ipm-simd-f32" => {
+                let settings = build_ipm_simd_settings_py(solver_kwargs)?;
+                self.run_multi_allowing_threads_py::<SimdIpmF32Solver>(py, &settings)
+            }
             #[cfg(feature = "ipm-ocl")]
             "clipm-f32" => self.run_multi_allowing_threads_py::<ClIpmF32Solver>(py, &ClIpmSolverSettings::default()),
 
@@ -12,9 +12,11 @@ use crate::solvers::{ClIpmF32Solver, ClIpmF64Solver, ClIpmSolverSettings};
 use crate::solvers::{ClpSolver, build_clp_settings_py};
 #[cfg(all(feature = "highs", feature = "pyo3"))]
 use crate::solvers::{HighsSolver, build_highs_settings_py};
-use crate::solvers::{MultiStateSolver, Solver, SolverFeatures, SolverSettings};
+use crate::solvers::{MultiStateSolver, SolveProfile, Solver, SolverFeatures, SolverSettings};
 #[cfg(all(feature = "ipm-simd", feature = "pyo3"))]
 use crate::solvers::{SimdIpmF64Solver, build_ipm_simd_settings_py};
+#[cfg(all(feature = "simplex", feature = "pyo3"))]
+use crate::solvers::{SimplexSolver, build_simplex_settings_py};
 use crate::timestep::Timestep;
 #[cfg(feature = "pyo3")]
 use pyo3::{Bound, PyErr, PyResult, Python, exceptions::PyRuntimeError, pyclass, pymethods, types::PyDict};
@@ -114,6 +116,12 @@ impl ModelTimings {
         }
     }
 
+    /// Record the timings of every solve in the given (empty) [`SolveProfile`].
+    pub fn with_solve_profile(mut self, profile: SolveProfile) -> Self {
+        self.network_timings = self.network_timings.with_solve_profile(profile);
+        self
+    }
+
     fn finish(&mut self) {
         self.run_duration = self.run_duration.finish();
     }
@@ -126,6 +134,16 @@ impl ModelTimings {
         self.run_duration.print_table();
         self.network_timings.print_table(total_duration, network);
     }
+
+    /// The number of solves that were completed by a fallback solver.
+    pub fn fallback_solves(&self) -> usize {
+        self.network_timings.fallback_solves()
+    }
+
+    /// The timings of each solve, if profiling is enabled.
+    pub fn solve_profile(&self) -> Option<&SolveProfile> {
+        self.network_timings.solve_profile()
+    }
 }
 
 #[cfg(feature = "pyo3")]
@@ -142,6 +160,13 @@ impl ModelTimings {
         self.run_duration.speed()
     }
 
+    /// The number of solves that were completed by a fallback solver.
+    #[getter]
+    #[pyo3(name = "fallback_solves")]
+    fn fallback_solves_py(&self) -> usize {
+        self.fallback_solves()
+    }
+
     fn __repr__(&self) -> String {
         format!(
             "<ModelTimings completed in {:.2} seconds with speed {:.2} time-steps/second>",
@@ -301,12 +326,12 @@ impl Model {
     where
         S: Solver,
     {
-        let timestep = self
-            .domain
-            .time
-            .timesteps()
+        let timesteps = self.domain.time.timesteps();
+        let timestep = timesteps
             .get(state.current_time_step_idx)
             .ok_or(ModelStepError::EndOfTimesteps)?;
+        // The following time-steps are available to solvers with a look-ahead horizon
+        let forecast_timesteps = &timesteps[state.current_time_step_idx + 1..];
 
         let scenario_indices = self.domain.scenarios.indices();
         debug!("Starting timestep {:?}", timestep);
@@ -318,8 +343,14 @@ impl Model {
             Some(pool) => {
                 // State is mutated in-place
                 pool.install(|| {
-                    self.network
-                        .step_par(timestep, scenario_indices, solvers, network_state, timings)
+                    self.network.step_par(
+                        timestep,
+                        forecast_timesteps,
+                        scenario_indices,
+                        solvers,
+                        network_state,
+                        timings,
+                    )
                 })
                 .map_err(|source| ModelStepError::NetworkStepError {
                     timestep: *timestep,
@@ -328,7 +359,14 @@ impl Model {
             }
             None => self
                 .network
-                .step(timestep, scenario_indices, solvers, network_state, timings)
+                .step(
+                    timestep,
+                    forecast_timesteps,
+                    scenario_indices,
+                    solvers,
+                    network_state,
+                    timings,
+                )
                 .map_err(|source| ModelStepError::NetworkStepError {
                     timestep: *timestep,
                     source: Box::new(source),
@@ -474,9 +512,23 @@ impl Model {
         S: Solver,
         <S as Solver>::Settings: SolverSettings,
     {
-        let mut state = self.setup::<S>(settings)?;
+        self.run_with_timings::<S>(settings, ModelTimings::new_with_component_timings(&self.network))
+    }
 
-        let mut timings = ModelTimings::new_with_component_timings(&self.network);
+    /// Run a model through the given time-steps, collecting timing information in `timings`.
+    ///
+    /// This is the same as [`Model::run`], but allows the collection of timings to be configured;
+    /// for example, to record every solve using [`ModelTimings::with_solve_profile`].
+    pub fn run_with_timings<S>(
+        &self,
+        settings: &S::Settings,
+        mut timings: ModelTimings,
+    ) -> Result<ModelResult, ModelRunError>
+    where
+        S: Solver,
+        <S as Solver>::Settings: SolverSettings,
+    {
+        let mut state = self.setup::<S>(settings)?;
 
         self.run_with_state::<S>(&mut state, settings, &mut timings)?;
 
@@ -576,7 +628,7 @@ impl Model {
     }
 
     /// Run a model using the specified solver unlocking the GIL
-    #[cfg(any(feature = "clp", feature = "highs"))]
+    #[cfg(any(feature = "clp", feature = "highs", feature = "simplex"))]
     #[cfg(feature = "pyo3")]
     fn run_allowing_threads_py<S>(&self, py: Python<'_>, settings: &S::Settings) -> Result<ModelResult, PyErr>
     where
@@ -608,17 +660,35 @@ impl Model {
     fn run_py(
         &self,
         #[cfg_attr(
-            not(any(feature = "clp", feature = "highs", feature = "ipm-simd", feature = "ipm-ocl")),
+            not(any(
+                feature = "clp",
+                feature = "highs",
+                feature = "ipm-simd",
+                feature = "ipm-ocl",
+                feature = "simplex"
+            )),
             allow(unused_variables)
         )]
         py: Python<'_>,
         #[cfg_attr(
-            not(any(feature = "clp", feature = "highs", feature = "ipm-simd", feature = "ipm-ocl")),
+            not(any(
+                feature = "clp",
+                feature = "highs",
+                feature = "ipm-simd",
+                feature = "ipm-ocl",
+                feature = "simplex"
+            )),
             allow(unused_variables)
         )]
         solver_name: &str,
         #[cfg_attr(
-            not(any(feature = "clp", feature = "highs", feature = "ipm-simd", feature = "ipm-ocl")),
+            not(any(
+                feature = "clp",
+                feature = "highs",
+                feature = "ipm-simd",
+                feature = "ipm-ocl",
+                feature = "simplex"
+            )),
             allow(unused_variables)
         )]
         solver_kwargs: Option<&Bound<'_, PyDict>>,
@@ -639,6 +709,11 @@ impl Model {
                 let settings = build_highs_settings_py(solver_kwargs)?;
                 self.run_allowing_threads_py::<HighsSolver>(py, &settings)
             }
+            #[cfg(feature = "simplex")]
+            "simplex" => {
+                let settings = build_simplex_settings_py(solver_kwargs)?;
+                self.run_allowing_threads_py::<SimplexSolver>(py, &settings)
+            }
             #[cfg(feature = "ipm-simd")]
             "ipm-simd" => {
                 let settings = build_ipm_simd_settings_py(solver_kwargs)?;
@@ -1,13 +1,17 @@
 use crate::aggregated_node::{AggregatedNode, AggregatedNodeIndex, AggregatedNodeVec, Relationship};
 use crate::aggregated_storage_node::{AggregatedStorageNode, AggregatedStorageNodeIndex, AggregatedStorageNodeVec};
+use crate::constituent::{
+    Constituent, ConstituentBuilder, ConstituentError, ConstituentIndex, ConstituentVec, transport_order,
+};
 use crate::edge::{Edge, EdgeIndex, EdgeVec};
 use crate::metric::{MetricF64, SimpleMetricF64};
 use crate::models::ModelDomain;
-use crate::node::{Node, NodeError, NodeVec, StorageInitialVolume};
+use crate::node::{Node, NodeError, NodeVec, SemiContinuousFlow, StorageInitialVolume};
 use crate::parameters::{
     GeneralParameterIndex, GeneralParameterType, ParameterCalculationError, ParameterCollection,
     ParameterCollectionConstCalculationError, ParameterCollectionError, ParameterCollectionSetupError,
-    ParameterCollectionSimpleCalculationError, ParameterIndex, ParameterName, ParameterStates, VariableConfig,
+    ParameterCollectionSimpleCalculationError, ParameterIndex, ParameterName, ParameterStateCloneError,
+    ParameterStates, VariableConfig,
 };
 use crate::recorders::{
     MetricSet, MetricSetIndex, MetricSetSaveError, MetricSetState, RecorderAggregationError, RecorderFinalResult,
@@ -15,7 +19,8 @@ use crate::recorders::{
 };
 use crate::scenario::ScenarioIndex;
 use crate::solvers::{
-    MultiStateSolver, Solver, SolverFeatures, SolverSettings, SolverSetupError, SolverSolveError, SolverTimings,
+    Forecast, MultiStateSolver, SolveProfile, Solver, SolverFeatures, SolverSettings, SolverSetupError,
+    SolverSolveError, SolverTimings,
 };
 use crate::state::{MultiValue, SetStateError, State, StateBuilder};
 use crate::timestep::Timestep;
@@ -34,7 +39,7 @@ use std::sync::Arc;
 use std::time::Duration;
 use std::time::Instant;
 use thiserror::Error;
-use tracing::info;
+use tracing::{info, warn};
 
 #[derive(Copy, Clone)]
 pub enum RunDuration {
@@ -212,6 +217,8 @@ pub struct NetworkTimings {
     component_timings: ComponentTimings,
     recorder_saving: Duration,
     solve: SolverTimings,
+    /// The timings of each solve, if profiling is enabled.
+    solve_profile: Option<SolveProfile>,
 }
 
 impl NetworkTimings {
@@ -220,6 +227,7 @@ impl NetworkTimings {
             component_timings: ComponentTimings::new_with_components(network.resolve_order.len()),
             recorder_saving: Duration::ZERO,
             solve: SolverTimings::default(),
+            solve_profile: None,
         }
     }
 
@@ -228,9 +236,26 @@ impl NetworkTimings {
             component_timings: ComponentTimings::new_without_components(),
             recorder_saving: Duration::ZERO,
             solve: SolverTimings::default(),
+            solve_profile: None,
         }
     }
 
+    /// Record the timings of every solve in the given (empty) [`SolveProfile`].
+    pub fn with_solve_profile(mut self, profile: SolveProfile) -> Self {
+        self.solve_profile = Some(profile);
+        self
+    }
+
+    /// The number of solves that were completed by a fallback solver.
+    pub fn fallback_solves(&self) -> usize {
+        self.solve.fallback_solves
+    }
+
+    /// The timings of each solve, if profiling is enabled.
+    pub fn solve_profile(&self) -> Option<&SolveProfile> {
+        self.solve_profile.as_ref()
+    }
+
     /// Print a summary of the timings to the log.
     pub fn print_table(&self, total_duration: f64, network: &Network) {
         info!(
@@ -288,6 +313,13 @@ impl NetworkTimings {
             100.0 * not_counted / total_duration,
         );
 
+        if self.solve.fallback_solves > 0 {
+            warn!(
+                "{} solve(s) failed with the primary solver and were completed by a fallback solver.",
+                self.solve.fallback_solves
+            );
+        }
+
         if let Some(slowest) = self.component_timings.slowest_components(10, &network.resolve_order) {
             info!("Slowest components:");
             info!(
@@ -305,6 +337,10 @@ impl NetworkTimings {
                 );
             }
         }
+
+        if let Some(profile) = &self.solve_profile {
+            profile.print_slowest_solves();
+        }
     }
 }
 
@@ -343,6 +379,8 @@ pub struct NetworkState {
     parameter_internal_states: Vec<ParameterStates>,
     // Metric set states by scenario
     metric_set_internal_states: Vec<Vec<MetricSetState>>,
+    // The order in which nodes are processed when transporting constituents
+    transport_order: Vec<NodeIndex>,
 }
 
 impl NetworkState {
@@ -391,6 +429,8 @@ pub enum NetworkSetupError {
 pub enum NetworkStepError {
     #[error("Aggregated node index not found: {0}")]
     AggregatedNodeIndexNotFound(AggregatedNodeIndex),
+    #[error("Error copying parameter states for forecasts: `{0}`")]
+    ForecastParameterStateError(#[from] ParameterStateCloneError),
     #[error("Error saving recorder `{}`: `{}`", .0.name, .0.source)]
     RecorderSaveError(#[from] NetworkRecorderSaveError),
     #[error("Error solving time-step: `{0}`")]
@@ -455,6 +495,12 @@ pub enum NetworkStepError {
         #[source]
         source: MetricSetSaveError,
     },
+    #[error("Error transporting constituent `{name}`: `{source}`")]
+    ConstituentTransportError {
+        name: String,
+        #[source]
+        source: ConstituentError,
+    },
 }
 
 #[derive(Debug, Error)]
@@ -540,6 +586,10 @@ pub enum NetworkError {
         #[source]
         source: parameters::VariableParameterError,
     },
+    #[error("Constituent with name `{0}` already exists")]
+    ConstituentAlreadyExists(String),
+    #[error("Constituent with index `{0}` not found")]
+    ConstituentIndexNotFound(ConstituentIndex),
 }
 
 #[derive(Error, Debug)]
@@ -623,6 +673,7 @@ pub struct Network {
     aggregated_nodes: AggregatedNodeVec,
     aggregated_storage_nodes: AggregatedStorageNodeVec,
     virtual_storage_nodes: VirtualStorageVec,
+    constituents: ConstituentVec,
     parameters: ParameterCollection,
     metric_sets: Vec<MetricSet>,
     resolve_order: Vec<ComponentType>,
@@ -654,6 +705,10 @@ impl Network {
         &self.virtual_storage_nodes
     }
 
+    pub fn constituents(&self) -> &ConstituentVec {
+        &self.constituents
+    }
+
     /// Setup the network and create the initial state for each scenario.
     pub fn setup_network(
         &self,
@@ -671,8 +726,15 @@ impl Network {
 
             let initial_virtual_storage_states = self.virtual_storage_nodes.iter().map(|n| n.default_state()).collect();
 
+            let initial_constituent_states = self
+                .constituents
+                .iter()
+                .map(|c| c.default_state(self.nodes.len()))
+                .collect();
+
             let state_builder = StateBuilder::new(initial_node_states, self.edges.len())
                 .with_virtual_storage_states(initial_virtual_storage_states)
+                .with_constituent_states(initial_constituent_states)
                 .with_parameters(&self.parameters)
                 .with_inter_network_transfers(num_inter_network_transfers);
 
@@ -691,10 +753,18 @@ impl Network {
             parameter_internal_states.push(internal_states);
         }
 
+        // The topology of the network is fixed, so the transport order is only computed once.
+        let transport_order = if self.constituents.is_empty() {
+            Vec::new()
+        } else {
+            transport_order(self)
+        };
+
         Ok(NetworkState {
             states,
             parameter_internal_states,
             metric_set_internal_states,
+            transport_order,
         })
     }
 
@@ -825,9 +895,13 @@ impl Network {
     }
 
     /// Perform a single timestep mutating the current state.
+    ///
+    /// The `forecast_timesteps` are the time-steps following `timestep`. These are only used if
+    /// the solvers have a look-ahead horizon; see [`Solver::horizon`].
     pub fn step<S>(
         &self,
         timestep: &Timestep,
+        forecast_timesteps: &[Timestep],
         scenario_indices: &[ScenarioIndex],
         solvers: &mut [Box<S>],
         state: &mut NetworkState,
@@ -836,6 +910,8 @@ impl Network {
     where
         S: Solver,
     {
+        let transport_order = &state.transport_order;
+
         scenario_indices
             .iter()
             .zip(state.states.iter_mut())
@@ -859,8 +935,23 @@ impl Network {
                     timings.component_timings.total += start_p_calc.elapsed();
 
                     // Solve determines the new network state
-                    let solve_timings = solver.solve(self, timestep, current_state)?;
+                    let solve_timings = if solver.horizon() > 1 {
+                        let start_forecast = Instant::now();
+                        let forecasts = self.compute_forecasts(
+                            &forecast_timesteps[..forecast_timesteps.len().min(solver.horizon() - 1)],
+                            scenario_index,
+                            current_state,
+                            p_internal_states,
+                        )?;
+                        timings.component_timings.total += start_forecast.elapsed();
+                        solver.solve_with_forecasts(self, timestep, current_state, &forecasts)?
+                    } else {
+                        solver.solve(self, timestep, current_state)?
+                    };
                     // State now contains updated parameter values AND updated network state
+                    if let Some(profile) = &mut timings.solve_profile {
+                        profile.record(timestep, scenario_index, &solve_timings);
+                    }
                     timings.solve += solve_timings;
 
                     // Now run the "after" method on all components
@@ -871,6 +962,7 @@ impl Network {
                         current_state,
                         p_internal_states,
                         ms_internal_states,
+                        transport_order,
                         Some(&mut timings.component_timings),
                     )?;
 
@@ -891,6 +983,7 @@ impl Network {
     pub fn step_par<S>(
         &self,
         timestep: &Timestep,
+        forecast_timesteps: &[Timestep],
         scenario_indices: &[ScenarioIndex],
         solvers: &mut [Box<S>],
         state: &mut NetworkState,
@@ -899,6 +992,8 @@ impl Network {
     where
         S: Solver,
     {
+        let transport_order = &state.transport_order;
+
         // Collect all the timings from each parallel solve
         let step_times: Vec<_> = scenario_indices
             .par_iter()
@@ -918,7 +1013,23 @@ impl Network {
                     let mut parameter_calculation = start_p_calc.elapsed();
 
                     // Solve determines the new network state
-                    let solve_timings = solver.solve(self, timestep, current_state).unwrap();
+                    let solve_timings = if solver.horizon() > 1 {
+                        let start_forecast = Instant::now();
+                        let forecasts = self
+                            .compute_forecasts(
+                                &forecast_timesteps[..forecast_timesteps.len().min(solver.horizon() - 1)],
+                                scenario_index,
+                                current_state,
+                                p_internal_state,
+                            )
+                            .unwrap();
+                        parameter_calculation += start_forecast.elapsed();
+                        solver
+                            .solve_with_forecasts(self, timestep, current_state, &forecasts)
+                            .unwrap()
+                    } else {
+                        solver.solve(self, timestep, current_state).unwrap()
+                    };
                     // State now contains updated parameter values AND updated network state
 
                     // Now run the "after" method on all components
@@ -929,20 +1040,24 @@ impl Network {
                         current_state,
                         p_internal_state,
                         ms_internal_state,
+                        transport_order,
                         None,
                     )
                     .unwrap();
 
                     parameter_calculation += start_p_after.elapsed();
 
-                    (parameter_calculation, solve_timings)
+                    (scenario_index, parameter_calculation, solve_timings)
                 },
             )
             .collect();
 
         // Add them all together
-        for (parameter_calculation, solve_timings) in step_times.into_iter() {
+        for (scenario_index, parameter_calculation, solve_timings) in step_times.into_iter() {
             timings.component_timings.total += parameter_calculation;
+            if let Some(profile) = &mut timings.solve_profile {
+                profile.record(timestep, scenario_index, &solve_timings);
+            }
             timings.solve += solve_timings;
         }
 
@@ -990,6 +1105,7 @@ impl Network {
         timings.solve += solve_timings;
 
         // Now run the "after" method on all components
+        let transport_order = &state.transport_order;
         let p_after_timings: Vec<_> = scenario_indices
             .par_iter()
             .zip(&mut state.states)
@@ -1004,6 +1120,7 @@ impl Network {
                         current_state,
                         p_internal_states,
                         ms_internal_states,
+                        transport_order,
                         None,
                     )
                     .unwrap();
@@ -1047,6 +1164,11 @@ impl Network {
             features.insert(SolverFeatures::MutualExclusivity);
         }
 
+        // Link nodes with semi-continuous flows require the SemiContinuous feature.
+        if self.nodes.iter().any(|n| n.semi_continuous_flow().is_some()) {
+            features.insert(SolverFeatures::SemiContinuous);
+        }
+
         // The presence of any virtual storage node requires the VirtualStorage feature.
         if !self.virtual_storage_nodes.is_empty() {
             features.insert(SolverFeatures::VirtualStorage);
@@ -1055,6 +1177,42 @@ impl Network {
         features
     }
 
+    /// Compute forecasts of the state for each of the given (future) time-steps.
+    ///
+    /// The forecasts start from a copy of the current `state` and of the parameters' current
+    /// `internal_states`, and the components are computed for each time-step in turn. Stateful
+    /// parameters (e.g. rolling or licence parameters) therefore continue from their current
+    /// state, but are not updated with the (unknown) flows of the forecast time-steps. The network
+    /// state (e.g. storage volumes) is not carried between the forecast time-steps; each forecast
+    /// uses the volumes at the start of the current time-step.
+    fn compute_forecasts(
+        &self,
+        timesteps: &[Timestep],
+        scenario_index: &ScenarioIndex,
+        state: &State,
+        internal_states: &ParameterStates,
+    ) -> Result<Vec<Forecast>, NetworkStepError> {
+        let mut internal_states = internal_states.try_clone()?;
+        let mut forecast_state = state.clone();
+
+        let mut forecasts = Vec::with_capacity(timesteps.len());
+        for timestep in timesteps {
+            self.compute_components(
+                timestep,
+                scenario_index,
+                &mut forecast_state,
+                &mut internal_states,
+                None,
+            )?;
+            forecasts.push(Forecast {
+                timestep: *timestep,
+                state: forecast_state.clone(),
+            });
+        }
+
+        Ok(forecasts)
+    }
+
     /// Undertake calculations for network components before solve.
     ///
     /// This method iterates through the network components (nodes, parameters, etc) to perform
@@ -1202,6 +1360,7 @@ impl Network {
     /// set initial volume). For parameters this involves computing the current value for the
     /// the timestep. The `state` object is progressively updated with these values during this
     /// method.
+    #[allow(clippy::too_many_arguments)]
     fn after(
         &self,
         timestep: &Timestep,
@@ -1209,8 +1368,22 @@ impl Network {
         state: &mut State,
         internal_states: &mut ParameterStates,
         metric_set_states: &mut [MetricSetState],
+        transport_order: &[NodeIndex],
         mut timings: Option<&mut ComponentTimings>,
     ) -> Result<(), NetworkStepError> {
+        // Constituents are transported first so that the new concentrations are available
+        // to the parameters and metric sets below.
+        if !self.constituents.is_empty() {
+            for constituent in self.constituents.iter() {
+                constituent
+                    .transport(self, transport_order, timestep, state)
+                    .map_err(|source| NetworkStepError::ConstituentTransportError {
+                        name: constituent.name().to_string(),
+                        source,
+                    })?;
+            }
+        }
+
         // TODO reset parameter state to zero
 
         self.parameters
@@ -1452,6 +1625,29 @@ impl Network {
                 source: Box::new(source),
             })
     }
+
+    pub fn set_node_semi_continuous_flow(
+        &mut self,
+        name: &str,
+        sub_name: Option<&str>,
+        value: Option<SemiContinuousFlow>,
+    ) -> Result<(), NetworkError> {
+        let node = self
+            .get_mut_node_by_name(name, sub_name)
+            .ok_or(NetworkError::NodeNotFound {
+                name: name.to_string(),
+                sub_name: sub_name.map(|s| s.to_string()),
+            })?;
+
+        node.set_semi_continuous_flow(value)
+            .map_err(|source| NetworkError::NodeSetAttributeError {
+                name: node.name().to_string(),
+                sub_name: node.sub_name().map(|s| s.to_string()),
+                attribute: "semi_continuous".to_string(),
+                source: Box::new(source),
+            })
+    }
+
     pub fn set_node_initial_volume(
         &mut self,
         name: &str,
@@ -1885,6 +2081,39 @@ impl Network {
         Ok(node_index)
     }
 
+    /// Get a [`Constituent`] from its index.
+    pub fn get_constituent(&self, index: &ConstituentIndex) -> Option<&Constituent> {
+        self.constituents.get(index)
+    }
+
+    /// Get a [`ConstituentIndex`] from a constituent's name.
+    pub fn get_constituent_index_by_name(&self, name: &str) -> Option<ConstituentIndex> {
+        self.constituents.iter().find(|c| c.name() == name).map(|c| c.index())
+    }
+
+    /// Add a new [`Constituent`] to the network.
+    pub fn add_constituent(&mut self, builder: ConstituentBuilder) -> Result<ConstituentIndex, NetworkError> {
+        self.constituents.push_new(builder)
+    }
+
+    /// Set the concentration of the flow leaving an input node for an existing [`Constituent`].
+    ///
+    /// This allows the concentration to be defined by a metric (e.g. a parameter) that is
+    /// added to the network after the constituent.
+    pub fn set_constituent_input_concentration(
+        &mut self,
+        index: ConstituentIndex,
+        node: NodeIndex,
+        concentration: MetricF64,
+    ) -> Result<(), NetworkError> {
+        let constituent = self
+            .constituents
+            .get_mut(&index)
+            .ok_or(NetworkError::ConstituentIndexNotFound(index))?;
+        constituent.set_input_concentration(node, concentration);
+        Ok(())
+    }
+
     /// Add a new `VirtualStorage` to the network.
     pub fn add_virtual_storage_node(
         &mut self,
@@ -2337,13 +2566,18 @@ impl Network {
 mod tests {
     use super::*;
     use crate::metric::MetricF64;
+    use crate::models::Model;
     use crate::network::Network;
-    use crate::parameters::{ActivationFunction, ControlCurveInterpolatedParameter, Parameter};
+    use crate::node::StorageInitialVolume;
+    use crate::parameters::{
+        ActivationFunction, Array1Parameter, ControlCurveInterpolatedParameter, IndexedArrayParameter,
+        InterpolationKind, Parameter, Predicate, ThresholdParameter,
+    };
     use crate::recorders::AssertionF64Recorder;
     use crate::solvers::{ClpSolver, ClpSolverSettings};
-    use crate::test_utils::{run_all_solvers, simple_model, simple_storage_model};
+    use crate::test_utils::{default_time_domain, run_all_solvers, simple_model, simple_storage_model};
     use float_cmp::assert_approx_eq;
-    use ndarray::{Array, Array2};
+    use ndarray::{Array, Array1, Array2};
     use std::default::Default;
     use std::ops::Deref;
 
@@ -2542,6 +2776,7 @@ mod tests {
             MetricF64::NodeProportionalVolume(idx),
             vec![],
             vec![100.0.into(), 0.0.into()],
+            InterpolationKind::Linear,
         );
         let p_idx = network.add_parameter(Box::new(cc)).unwrap();
         let expected = Array2::from_shape_fn((15, 10), |(i, _j)| (100.0 - 10.0 * i as f64).max(0.0));
@@ -2553,6 +2788,125 @@ mod tests {
         run_all_solvers(&model, &[], &[], &[]);
     }
 
+    /// Test that a rolling-horizon look-ahead pre-fills storage ahead of a demand peak.
+    ///
+    /// The input can supply at most 10 per day, and the demand peaks at 20 on day 5. Without
+    /// look-ahead there is no incentive to store water, and the peak is under-supplied. With a
+    /// horizon of 3 the solver sees the peak coming and stores the shortfall in advance.
+    #[test]
+    fn test_rolling_horizon() {
+        let build_model = |expected: Array2<f64>| {
+            let mut network = Network::default();
+            let input_node = network.add_input_node("input", None).unwrap();
+            let storage_node = network
+                .add_storage_node(
+                    "reservoir",
+                    None,
+                    StorageInitialVolume::Absolute(0.0),
+                    None,
+                    Some(100.0.into()),
+                )
+                .unwrap();
+            let output_node = network.add_output_node("output", None).unwrap();
+            network.connect_nodes(input_node, storage_node).unwrap();
+            network.connect_nodes(storage_node, output_node).unwrap();
+
+            let input = network.get_mut_node_by_name("input", None).unwrap();
+            input.set_max_flow_constraint(Some(10.0.into())).unwrap();
+            input.set_cost(Some(1.0.into()));
+
+            let demand = Array1::from_shape_fn(15, |i| if i == 5 { 20.0 } else { 5.0 });
+            let demand = Array1Parameter::new("demand".into(), demand, None);
+            let demand = network.add_simple_parameter(Box::new(demand)).unwrap();
+            let output = network.get_mut_node_by_name("output", None).unwrap();
+            output
+                .set_max_flow_constraint(Some(demand.into_metric_f64_before()))
+                .unwrap();
+            output.set_cost(Some((-10.0).into()));
+
+            let recorder =
+                AssertionF64Recorder::new("output-flow", MetricF64::NodeInFlow(output_node), expected, None, None);
+            network.add_recorder(Box::new(recorder)).unwrap();
+
+            Model::new(default_time_domain().into(), network)
+        };
+
+        // Without look-ahead the peak is limited by the input capacity.
+        let expected = Array2::from_shape_fn((15, 1), |(i, _)| if i == 5 { 10.0 } else { 5.0 });
+        let model = build_model(expected);
+        model.run::<ClpSolver>(&ClpSolverSettings::default()).unwrap();
+
+        // With look-ahead the peak is met in full.
+        let expected = Array2::from_shape_fn((15, 1), |(i, _)| if i == 5 { 20.0 } else { 5.0 });
+        let model = build_model(expected);
+        let settings = ClpSolverSettings::builder().horizon(3).build();
+        model.run::<ClpSolver>(&settings).unwrap();
+    }
+
+    /// Test that the forecasts of a rolling-horizon look-ahead continue from the current state of
+    /// stateful parameters.
+    ///
+    /// The demand of the `test_rolling_horizon` model only applies once a ratcheted threshold has
+    /// been triggered on the first day. If the forecasts reset the threshold the demand peak is
+    /// not seen in advance, and it is under-supplied.
+    #[test]
+    fn test_rolling_horizon_stateful_parameter() {
+        let mut network = Network::default();
+        let input_node = network.add_input_node("input", None).unwrap();
+        let storage_node = network
+            .add_storage_node(
+                "reservoir",
+                None,
+                StorageInitialVolume::Absolute(0.0),
+                None,
+                Some(100.0.into()),
+            )
+            .unwrap();
+        let output_node = network.add_output_node("output", None).unwrap();
+        network.connect_nodes(input_node, storage_node).unwrap();
+        network.connect_nodes(storage_node, output_node).unwrap();
+
+        let input = network.get_mut_node_by_name("input", None).unwrap();
+        input.set_max_flow_constraint(Some(10.0.into())).unwrap();
+        input.set_cost(Some(1.0.into()));
+
+        let trigger = Array1::from_shape_fn(15, |i| if i == 0 { 1.0 } else { 0.0 });
+        let trigger = Array1Parameter::new("trigger".into(), trigger, None);
+        let trigger = network.add_simple_parameter(Box::new(trigger)).unwrap();
+        let threshold = ThresholdParameter::new(
+            "threshold".into(),
+            trigger.into_metric_f64_before(),
+            0.5.into(),
+            Predicate::GreaterThan,
+            true,
+        );
+        let threshold = network.add_index_parameter(Box::new(threshold)).unwrap();
+
+        let demand = Array1::from_shape_fn(15, |i| if i == 5 { 20.0 } else { 5.0 });
+        let demand = Array1Parameter::new("demand".into(), demand, None);
+        let demand = network.add_simple_parameter(Box::new(demand)).unwrap();
+        let demand = IndexedArrayParameter::new(
+            "triggered-demand".into(),
+            threshold.into_metric_u64_before(),
+            &[0.0.into(), demand.into_metric_f64_before()],
+        );
+        let demand = network.add_parameter(Box::new(demand)).unwrap();
+        let output = network.get_mut_node_by_name("output", None).unwrap();
+        output
+            .set_max_flow_constraint(Some(demand.into_metric_f64_before()))
+            .unwrap();
+        output.set_cost(Some((-10.0).into()));
+
+        let expected = Array2::from_shape_fn((15, 1), |(i, _)| if i == 5 { 20.0 } else { 5.0 });
+        let recorder =
+            AssertionF64Recorder::new("output-flow", MetricF64::NodeInFlow(output_node), expected, None, None);
+        network.add_recorder(Box::new(recorder)).unwrap();
+
+        let model = Model::new(default_time_domain().into(), network);
+        let settings = ClpSolverSettings::builder().horizon(3).build();
+        model.run::<ClpSolver>(&settings).unwrap();
+    }
+
     #[test]
     /// Test the variable API
     fn test_variable_api() {
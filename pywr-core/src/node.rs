@@ -55,6 +55,8 @@ pub enum NodeError {
     VirtualStorageIndexNotFound(VirtualStorageIndex),
     #[error("Node index not found: {0}")]
     NodeIndexNotFound(NodeIndex),
+    #[error("Semi-continuous flow is only defined for link nodes")]
+    SemiContinuousFlowUndefined,
 }
 
 #[derive(Debug, PartialEq)]
@@ -436,6 +438,24 @@ impl Node {
         }
     }
 
+    pub fn set_semi_continuous_flow(&mut self, value: Option<SemiContinuousFlow>) -> Result<(), NodeError> {
+        match self {
+            Self::Link(n) => {
+                n.semi_continuous = value;
+                Ok(())
+            }
+            _ => Err(NodeError::SemiContinuousFlowUndefined),
+        }
+    }
+
+    /// Return the node's semi-continuous flow constraint, if any.
+    pub fn semi_continuous_flow(&self) -> Option<&SemiContinuousFlow> {
+        match self {
+            Self::Link(n) => n.semi_continuous.as_ref(),
+            _ => None,
+        }
+    }
+
     pub fn set_initial_volume(&mut self, initial_volume: StorageInitialVolume) -> Result<(), NodeError> {
         match self {
             Self::Input(_) => Err(NodeError::StorageConstraintsUndefined),
@@ -597,6 +617,32 @@ impl Node {
             Self::Storage(n) => Ok(n.get_cost(network, state)?),
         }
     }
+
+    /// Return the cost of the node's outgoing edges if it is constant.
+    ///
+    /// See [`Node::get_outgoing_cost`].
+    pub fn get_const_outgoing_cost(&self, values: &ConstParameterValues) -> Result<Option<f64>, NodeError> {
+        let cost = match self {
+            Self::Input(n) => n.cost.get_const_cost(values)?,
+            Self::Link(n) => n.cost.get_const_cost(values)?.map(|c| c / 2.0),
+            Self::Output(n) => n.cost.get_const_cost(values)?,
+            Self::Storage(n) => n.get_const_cost(values)?.map(|c| -c),
+        };
+        Ok(cost)
+    }
+
+    /// Return the cost of the node's incoming edges if it is constant.
+    ///
+    /// See [`Node::get_incoming_cost`].
+    pub fn get_const_incoming_cost(&self, values: &ConstParameterValues) -> Result<Option<f64>, NodeError> {
+        let cost = match self {
+            Self::Input(n) => n.cost.get_const_cost(values)?,
+            Self::Link(n) => n.cost.get_const_cost(values)?.map(|c| c / 2.0),
+            Self::Output(n) => n.cost.get_const_cost(values)?,
+            Self::Storage(n) => n.get_const_cost(values)?,
+        };
+        Ok(cost)
+    }
 }
 
 /// Meta data common to all nodes.
@@ -684,6 +730,59 @@ impl FlowConstraints {
     pub fn is_max_flow_unconstrained(&self) -> bool {
         self.max_flow.is_none()
     }
+
+    pub fn is_min_flow_unconstrained(&self) -> bool {
+        self.min_flow.is_none()
+    }
+}
+
+/// A semi-continuous flow constraint.
+///
+/// The node is either "off", with no flow, or "on" with a flow of at least `min_flow_if_on`
+/// (and no more than the node's maximum flow). An optional `start_up_cost` is incurred in the
+/// objective function when the node is switched on after having no flow in the previous
+/// time-step; the node is considered off before the first time-step. Solvers represent the
+/// on/off state with a binary variable, and therefore require
+/// [`crate::solvers::SolverFeatures::SemiContinuous`]. The coefficients of the binary variable are
+/// updated each time-step if the node's maximum flow or `min_flow_if_on` is not constant. CBC can
+/// not update them; it uses a fixed coefficient for a non-constant maximum flow, and rejects a
+/// non-constant `min_flow_if_on` when it is set up.
+#[derive(Debug, PartialEq)]
+pub struct SemiContinuousFlow {
+    pub min_flow_if_on: MetricF64,
+    pub start_up_cost: Option<MetricF64>,
+}
+
+impl SemiContinuousFlow {
+    pub fn new(min_flow_if_on: MetricF64, start_up_cost: Option<MetricF64>) -> Self {
+        Self {
+            min_flow_if_on,
+            start_up_cost,
+        }
+    }
+
+    /// Return the current minimum flow when the node is on.
+    pub fn get_min_flow_if_on(&self, network: &Network, state: &State) -> Result<f64, MetricF64Error> {
+        self.min_flow_if_on.get_value(network, state)
+    }
+
+    /// Return the constant minimum flow when the node is on, if it exists.
+    pub fn get_const_min_flow_if_on(
+        &self,
+        values: &ConstParameterValues,
+    ) -> Result<Option<f64>, ConstantMetricF64Error> {
+        self.min_flow_if_on.try_get_constant_value(values)
+    }
+
+    /// Return the current start-up cost.
+    ///
+    /// Defaults to zero if no start-up cost is defined.
+    pub fn get_start_up_cost(&self, network: &Network, state: &State) -> Result<f64, MetricF64Error> {
+        match &self.start_up_cost {
+            None => Ok(0.0),
+            Some(m) => m.get_value(network, state),
+        }
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -761,6 +860,20 @@ impl NodeCost {
 
         Ok(cost)
     }
+
+    /// Return the cost if it is constant.
+    ///
+    /// A cost that is aggregated with the cost of any virtual storage nodes is never constant.
+    fn get_const_cost(&self, values: &ConstParameterValues) -> Result<Option<f64>, ConstantMetricF64Error> {
+        if self.agg_func.is_some() && !self.virtual_storage_nodes.is_empty() {
+            return Ok(None);
+        }
+
+        match &self.local {
+            None => Ok(Some(0.0)),
+            Some(m) => m.try_get_constant_value(values),
+        }
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -872,6 +985,7 @@ pub struct LinkNode {
     pub meta: NodeMeta<NodeIndex>,
     cost: NodeCost,
     pub flow_constraints: FlowConstraints,
+    pub semi_continuous: Option<SemiContinuousFlow>,
     pub incoming_edges: Vec<EdgeIndex>,
     pub outgoing_edges: Vec<EdgeIndex>,
 }
@@ -882,6 +996,7 @@ impl LinkNode {
             meta: NodeMeta::new(index, name, sub_name),
             cost: NodeCost::default(),
             flow_constraints: FlowConstraints::default(),
+            semi_continuous: None,
             incoming_edges: Vec::new(),
             outgoing_edges: Vec::new(),
         }
@@ -1037,6 +1152,12 @@ impl StorageNode {
             Some(m) => m.get_value(network, state),
         }
     }
+    fn get_const_cost(&self, values: &ConstParameterValues) -> Result<Option<f64>, ConstantMetricF64Error> {
+        match &self.cost {
+            None => Ok(Some(0.0)),
+            Some(m) => m.try_get_constant_value(values),
+        }
+    }
     fn set_initial_volume(&mut self, initial_volume: StorageInitialVolume) {
         self.initial_volume = initial_volume;
     }
@@ -1063,3 +1184,140 @@ impl StorageNode {
         self.outgoing_edges.push(edge);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::metric::MetricF64;
+    use crate::models::Model;
+    use crate::network::Network;
+    use crate::node::SemiContinuousFlow;
+    use crate::parameters::MonthlyProfileParameter;
+    use crate::recorders::AssertionF64Recorder;
+    use crate::state::ParameterReturnValue;
+    use crate::test_utils::{default_time_domain, run_all_solvers};
+    #[cfg(feature = "cbc")]
+    use crate::{
+        models::ModelSetupError,
+        network::NetworkSolverSetupError,
+        solvers::{CbcSolver, CbcSolverSettings, SolverSetupError},
+    };
+    use ndarray::Array2;
+
+    /// Create a network with an input, link and output node where the link has a
+    /// semi-continuous flow with a minimum flow of 10.0 when on.
+    fn semi_continuous_network(demand: f64, start_up_cost: Option<f64>) -> Network {
+        let mut network = Network::default();
+
+        let input_node = network.add_input_node("input", None).unwrap();
+        let link_node = network.add_link_node("link", None).unwrap();
+        let output_node = network.add_output_node("output", None).unwrap();
+
+        network.connect_nodes(input_node, link_node).unwrap();
+        network.connect_nodes(link_node, output_node).unwrap();
+
+        let semi_continuous = SemiContinuousFlow::new(10.0.into(), start_up_cost.map(|c| c.into()));
+        network
+            .set_node_semi_continuous_flow("link", None, Some(semi_continuous))
+            .unwrap();
+        network.set_node_max_flow("output", None, Some(demand.into())).unwrap();
+        network.set_node_cost("output", None, Some((-10.0).into())).unwrap();
+
+        network
+    }
+
+    fn add_link_flow_assertion(network: &mut Network, value: f64) {
+        let idx = network.get_node_by_name("link", None).unwrap().index();
+        let expected = Array2::from_elem((366, 10), value);
+        let recorder = AssertionF64Recorder::new("link-flow", MetricF64::NodeOutFlow(idx), expected, None, None);
+        network.add_recorder(Box::new(recorder)).unwrap();
+    }
+
+    /// Test a semi-continuous flow that can not meet its minimum flow, and is therefore off.
+    #[test]
+    fn test_semi_continuous_off() {
+        let mut network = semi_continuous_network(5.0, None);
+        add_link_flow_assertion(&mut network, 0.0);
+
+        let model = Model::new(default_time_domain().into(), network);
+        run_all_solvers(&model, &["clp", "microlp", "ipm-simd", "ipm-ocl"], &[], &[]);
+    }
+
+    /// Test a semi-continuous flow that meets its minimum flow, and is therefore on.
+    #[test]
+    fn test_semi_continuous_on() {
+        let mut network = semi_continuous_network(15.0, None);
+        add_link_flow_assertion(&mut network, 15.0);
+
+        let model = Model::new(default_time_domain().into(), network);
+        run_all_solvers(&model, &["clp", "microlp", "ipm-simd", "ipm-ocl"], &[], &[]);
+    }
+
+    /// Test the start-up cost of a semi-continuous flow.
+    ///
+    /// The benefit of the flow in a single time-step (15.0 * 10.0) is greater than the start-up
+    /// cost, so the node starts in the first time-step and then remains on.
+    #[test]
+    fn test_semi_continuous_start_up_cost() {
+        let mut network = semi_continuous_network(15.0, Some(100.0));
+        add_link_flow_assertion(&mut network, 15.0);
+
+        let model = Model::new(default_time_domain().into(), network);
+        run_all_solvers(&model, &["clp", "microlp", "ipm-simd", "ipm-ocl"], &[], &[]);
+    }
+
+    /// Test a start-up cost that is greater than the benefit of the flow, so the node is never started.
+    #[test]
+    fn test_semi_continuous_prohibitive_start_up_cost() {
+        let mut network = semi_continuous_network(15.0, Some(1000.0));
+        add_link_flow_assertion(&mut network, 0.0);
+
+        let model = Model::new(default_time_domain().into(), network);
+        run_all_solvers(&model, &["clp", "microlp", "ipm-simd", "ipm-ocl"], &[], &[]);
+    }
+
+    /// Test a semi-continuous flow with a maximum flow that is not constant, and is therefore
+    /// updated each time-step (or, with CBC, bounded by a fixed coefficient).
+    #[test]
+    fn test_semi_continuous_non_constant_max_flow() {
+        let mut network = semi_continuous_network(15.0, None);
+
+        let max_flow = MonthlyProfileParameter::new("max-flow".into(), [12.0; 12], None);
+        let max_flow_idx = network.add_simple_parameter(Box::new(max_flow)).unwrap();
+        network
+            .set_node_max_flow(
+                "link",
+                None,
+                Some(max_flow_idx.into_metric_f64(ParameterReturnValue::Before)),
+            )
+            .unwrap();
+        add_link_flow_assertion(&mut network, 12.0);
+
+        let model = Model::new(default_time_domain().into(), network);
+        run_all_solvers(&model, &["clp", "microlp", "ipm-simd", "ipm-ocl"], &[], &[]);
+    }
+
+    /// Test that CBC, which can not update the coefficient of the binary variable, rejects a
+    /// minimum flow if on that is not constant when it is set up.
+    #[test]
+    #[cfg(feature = "cbc")]
+    fn test_semi_continuous_non_constant_min_flow_if_on_cbc() {
+        let mut network = semi_continuous_network(15.0, None);
+
+        let min_flow = MonthlyProfileParameter::new("min-flow".into(), [10.0; 12], None);
+        let min_flow_idx = network.add_simple_parameter(Box::new(min_flow)).unwrap();
+        let semi_continuous = SemiContinuousFlow::new(min_flow_idx.into_metric_f64(ParameterReturnValue::Before), None);
+        network
+            .set_node_semi_continuous_flow("link", None, Some(semi_continuous))
+            .unwrap();
+
+        let model = Model::new(default_time_domain().into(), network);
+        let result = model.setup::<CbcSolver>(&CbcSolverSettings::default());
+        assert!(matches!(
+            result,
+            Err(ModelSetupError::SolverSetupError(ref e)) if matches!(
+                **e,
+                NetworkSolverSetupError::SolverSetupError(SolverSetupError::NonConstantMinFlowIfOn { .. })
+            )
+        ));
+    }
+}
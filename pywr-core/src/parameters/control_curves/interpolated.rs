@@ -1,28 +1,59 @@
 use crate::metric::MetricF64;
 use crate::network::Network;
 use crate::parameters::errors::ParameterCalculationError;
-use crate::parameters::interpolate::interpolate;
+use crate::parameters::interpolate::{Extrapolation, InterpolationKind, interpolate, interpolate_points};
 use crate::parameters::{GeneralParameter, Parameter, ParameterMeta, ParameterName, ParameterState};
 use crate::scenario::ScenarioIndex;
 use crate::state::State;
 use crate::timestep::Timestep;
 
+/// Interpolates `values` between the control curves using the current value of `metric`.
+///
+/// The first value applies at 1.0, the following values at each control curve and the last
+/// value at 0.0. Non-linear `kind`s are applied across all of these points, which must then be
+/// strictly decreasing.
 pub struct ControlCurveInterpolatedParameter {
     meta: ParameterMeta,
     metric: MetricF64,
     control_curves: Vec<MetricF64>,
     values: Vec<MetricF64>,
+    kind: InterpolationKind,
 }
 
 impl ControlCurveInterpolatedParameter {
-    pub fn new(name: ParameterName, metric: MetricF64, control_curves: Vec<MetricF64>, values: Vec<MetricF64>) -> Self {
+    pub fn new(
+        name: ParameterName,
+        metric: MetricF64,
+        control_curves: Vec<MetricF64>,
+        values: Vec<MetricF64>,
+        kind: InterpolationKind,
+    ) -> Self {
         Self {
             meta: ParameterMeta::new(name),
             metric,
             control_curves,
             values,
+            kind,
         }
     }
+
+    /// Interpolate with a non-linear kind across all of the control curves.
+    fn interpolate_points(&self, x: f64, network: &Network, state: &State) -> Result<f64, ParameterCalculationError> {
+        let n = self.values.len();
+
+        // Points in increasing order from 0.0 to 1.0
+        let mut points = Vec::with_capacity(n);
+        points.push((0.0, self.values[n - 1].get_value(network, state)?));
+        for (idx, control_curve) in self.control_curves.iter().enumerate().rev() {
+            points.push((
+                control_curve.get_value(network, state)?,
+                self.values[idx + 1].get_value(network, state)?,
+            ));
+        }
+        points.push((1.0, self.values[0].get_value(network, state)?));
+
+        Ok(interpolate_points(x, &points, self.kind, Extrapolation::Constant)?)
+    }
 }
 
 impl Parameter for ControlCurveInterpolatedParameter {
@@ -43,6 +74,10 @@ impl GeneralParameter<f64> for ControlCurveInterpolatedParameter {
         // Current value
         let x = self.metric.get_value(model, state)?;
 
+        if self.kind != InterpolationKind::Linear {
+            return Ok(Some(self.interpolate_points(x, model, state)?));
+        }
+
         let mut cc_prev = 1.0;
         for (idx, control_curve) in self.control_curves.iter().enumerate() {
             let cc_value = control_curve.get_value(model, state)?;
@@ -73,3 +108,39 @@ impl GeneralParameter<f64> for ControlCurveInterpolatedParameter {
         self
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::parameters::{Array1Parameter, ControlCurveInterpolatedParameter, InterpolationKind};
+    use crate::test_utils::{run_and_assert_parameter, simple_model};
+    use ndarray::{Array1, Array2, Axis};
+
+    /// Test a step interpolation between the control curves.
+    #[test]
+    fn test_previous_kind() {
+        let mut model = simple_model(1, None);
+
+        let volume = Array1Parameter::new("test-x".into(), Array1::linspace(1.0, 0.0, 17), None);
+        let volume_idx = model.network_mut().add_simple_parameter(Box::new(volume)).unwrap();
+
+        let parameter = ControlCurveInterpolatedParameter::new(
+            "test-parameter".into(),
+            volume_idx.into_metric_f64_before(),
+            vec![0.5.into()],
+            vec![10.0.into(), 5.0.into(), 0.0.into()],
+            InterpolationKind::Previous,
+        );
+
+        // Values step down at the control curve (0.5) and 0.0
+        let expected_values: Array1<f64> = (0..17)
+            .map(|i| match i {
+                0 => 10.0,
+                1..=8 => 5.0,
+                _ => 0.0,
+            })
+            .collect();
+        let expected_values: Array2<f64> = expected_values.insert_axis(Axis(1));
+
+        run_and_assert_parameter(&mut model, Box::new(parameter), expected_values, None, Some(1e-12));
+    }
+}
@@ -1,12 +1,16 @@
 use crate::metric::MetricF64;
 use crate::network::Network;
 use crate::parameters::errors::ParameterCalculationError;
-use crate::parameters::interpolate::interpolate;
+use crate::parameters::interpolate::{Extrapolation, InterpolationKind, interpolate, interpolate_points};
 use crate::parameters::{GeneralParameter, Parameter, ParameterMeta, ParameterName, ParameterState};
 use crate::scenario::ScenarioIndex;
 use crate::state::State;
 use crate::timestep::Timestep;
 
+/// Interpolates a separate pair of `values` within each band between the control curves.
+///
+/// Each band is interpolated independently using `kind`. As each band only has two points the
+/// [`InterpolationKind::Pchip`] kind is the same as linear interpolation.
 pub struct PiecewiseInterpolatedParameter {
     meta: ParameterMeta,
     metric: MetricF64,
@@ -14,6 +18,7 @@ pub struct PiecewiseInterpolatedParameter {
     values: Vec<[f64; 2]>,
     maximum: f64,
     minimum: f64,
+    kind: InterpolationKind,
 }
 
 impl PiecewiseInterpolatedParameter {
@@ -24,6 +29,7 @@ impl PiecewiseInterpolatedParameter {
         values: Vec<[f64; 2]>,
         maximum: f64,
         minimum: f64,
+        kind: InterpolationKind,
     ) -> Self {
         Self {
             meta: ParameterMeta::new(name),
@@ -32,6 +38,20 @@ impl PiecewiseInterpolatedParameter {
             values,
             maximum,
             minimum,
+            kind,
+        }
+    }
+
+    /// Interpolate within a band from `lower` to `upper` with the band's pair of values.
+    fn interpolate(&self, x: f64, lower: f64, upper: f64, v: &[f64; 2]) -> Result<f64, ParameterCalculationError> {
+        match self.kind {
+            InterpolationKind::Linear => Ok(interpolate(x, lower, upper, v[1], v[0])),
+            kind => Ok(interpolate_points(
+                x,
+                &[(lower, v[1]), (upper, v[0])],
+                kind,
+                Extrapolation::Constant,
+            )?),
         }
     }
 }
@@ -64,7 +84,7 @@ impl GeneralParameter<f64> for PiecewiseInterpolatedParameter {
                         index: idx,
                         length: self.values.len(),
                     })?;
-                return Ok(Some(interpolate(x, cc_value, cc_previous_value, v[1], v[0])));
+                return Ok(Some(self.interpolate(x, cc_value, cc_previous_value, v)?));
             }
             cc_previous_value = cc_value;
         }
@@ -76,7 +96,7 @@ impl GeneralParameter<f64> for PiecewiseInterpolatedParameter {
                 index: 0,
                 length: self.values.len(),
             })?;
-        Ok(Some(interpolate(x, self.minimum, cc_previous_value, v[1], v[0])))
+        Ok(Some(self.interpolate(x, self.minimum, cc_previous_value, v)?))
     }
 
     fn as_parameter(&self) -> &dyn Parameter
@@ -89,7 +109,7 @@ impl GeneralParameter<f64> for PiecewiseInterpolatedParameter {
 
 #[cfg(test)]
 mod test {
-    use crate::parameters::{Array1Parameter, PiecewiseInterpolatedParameter};
+    use crate::parameters::{Array1Parameter, InterpolationKind, PiecewiseInterpolatedParameter};
     use crate::test_utils::{run_and_assert_parameter, simple_model};
     use ndarray::{Array1, Array2, Axis};
 
@@ -110,6 +130,7 @@ mod test {
             vec![[10.0, 1.0], [0.0, 0.0], [-1.0, -10.0]],
             1.0,
             0.0,
+            InterpolationKind::Linear,
         );
 
         let expected_values: Array1<f64> = [
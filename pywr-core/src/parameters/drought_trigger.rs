@@ -0,0 +1,214 @@
+use crate::metric::MetricF64;
+use crate::network::Network;
+use crate::parameters::errors::{ParameterCalculationError, ParameterSetupError};
+use crate::parameters::{
+    GeneralParameter, Parameter, ParameterMeta, ParameterName, ParameterState, downcast_internal_state_mut,
+};
+use crate::scenario::ScenarioIndex;
+use crate::state::State;
+use crate::timestep::Timestep;
+
+/// The current drought level and the number of time-steps it has applied for.
+#[derive(Clone)]
+struct DroughtTriggerState {
+    level: u64,
+    duration: u64,
+}
+
+/// A parameter that returns a drought restriction level with hysteresis.
+///
+/// The target level is found by comparing `metric` against the `triggers` in the same way as a
+/// [`crate::parameters::ControlCurveIndexParameter`]: zero if the metric is greater than or equal
+/// to the first trigger, one if it is only greater than or equal to the second, and so on. The
+/// triggers should therefore be ordered from the least to the most severe level.
+///
+/// The level returned by the parameter follows the target level with the following rules:
+///
+/// - The level increases to the target level as soon as the target level is higher. Levels may be
+///   skipped when moving to a more severe level.
+/// - The level only decreases once it has applied for at least `min_duration` time-steps, and then
+///   only by one level at a time. Each decrease restarts the minimum duration.
+/// - If `evaluation_days` are given the level only changes on those days of the year. Days are
+///   numbered from 1 to 366 as if every year is a leap year.
+///
+/// The level at the start of the simulation is `initial_level`.
+pub struct DroughtTriggerParameter {
+    meta: ParameterMeta,
+    metric: MetricF64,
+    triggers: Vec<MetricF64>,
+    min_duration: u64,
+    evaluation_days: Option<Vec<u32>>,
+    initial_level: u64,
+}
+
+impl DroughtTriggerParameter {
+    pub fn new(
+        name: ParameterName,
+        metric: MetricF64,
+        triggers: Vec<MetricF64>,
+        min_duration: u64,
+        evaluation_days: Option<Vec<u32>>,
+        initial_level: u64,
+    ) -> Self {
+        Self {
+            meta: ParameterMeta::new(name),
+            metric,
+            triggers,
+            min_duration,
+            evaluation_days,
+            initial_level,
+        }
+    }
+
+    fn target_level(&self, network: &Network, state: &State) -> Result<u64, ParameterCalculationError> {
+        let x = self.metric.get_value(network, state)?;
+
+        for (idx, trigger) in self.triggers.iter().enumerate() {
+            if x >= trigger.get_value(network, state)? {
+                return Ok(idx as u64);
+            }
+        }
+        Ok(self.triggers.len() as u64)
+    }
+
+    fn is_evaluation_day(&self, timestep: &Timestep) -> bool {
+        match &self.evaluation_days {
+            Some(days) => days.contains(&(timestep.day_of_year_index() as u32 + 1)),
+            None => true,
+        }
+    }
+}
+
+impl Parameter for DroughtTriggerParameter {
+    fn meta(&self) -> &ParameterMeta {
+        &self.meta
+    }
+
+    fn setup(
+        &self,
+        _timesteps: &[Timestep],
+        _scenario_index: &ScenarioIndex,
+    ) -> Result<Option<Box<dyn ParameterState>>, ParameterSetupError> {
+        let state = DroughtTriggerState {
+            level: self.initial_level,
+            duration: 0,
+        };
+        Ok(Some(Box::new(state)))
+    }
+}
+
+impl GeneralParameter<u64> for DroughtTriggerParameter {
+    fn before(
+        &self,
+        timestep: &Timestep,
+        _scenario_index: &ScenarioIndex,
+        network: &Network,
+        state: &State,
+        internal_state: &mut Option<Box<dyn ParameterState>>,
+    ) -> Result<Option<u64>, ParameterCalculationError> {
+        let target = self.target_level(network, state)?;
+
+        // Downcast the internal state to the correct type
+        let current = downcast_internal_state_mut::<DroughtTriggerState>(internal_state);
+
+        if self.is_evaluation_day(timestep) {
+            if target > current.level {
+                current.level = target;
+                current.duration = 0;
+            } else if target < current.level && current.duration >= self.min_duration {
+                current.level -= 1;
+                current.duration = 0;
+            }
+        }
+
+        current.duration += 1;
+
+        Ok(Some(current.level))
+    }
+
+    fn as_parameter(&self) -> &dyn Parameter
+    where
+        Self: Sized,
+    {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DroughtTriggerParameter;
+    use crate::metric::MetricF64;
+    use crate::parameters::{Array1Parameter, ParameterName};
+    use crate::recorders::AssertionU64Recorder;
+    use crate::test_utils::{run_all_solvers, simple_model};
+    use ndarray::{Array1, Array2};
+
+    /// The level escalates immediately and relaxes one level at a time after the minimum duration.
+    #[test]
+    fn test_drought_trigger() {
+        let mut model = simple_model(1, None);
+        let network = model.network_mut();
+
+        let storage = Array1::from_vec(vec![
+            0.9, 0.2, 0.9, 0.9, 0.9, 0.9, 0.9, 0.9, 0.9, 0.9, 0.4, 0.4, 0.4, 0.9, 0.9,
+        ]);
+        let storage = Array1Parameter::new("storage".into(), storage, None);
+        let storage: MetricF64 = network
+            .add_simple_parameter(Box::new(storage))
+            .unwrap()
+            .into_metric_f64_before();
+
+        let p = DroughtTriggerParameter::new(
+            ParameterName::new("drought", None),
+            storage,
+            vec![0.5.into(), 0.3.into()],
+            3,
+            None,
+            0,
+        );
+        let idx = network.add_index_parameter(Box::new(p)).unwrap();
+
+        // Level 2 is entered on the second day and held for three days, then relaxes to level 1
+        // and is held for a further three days before returning to level 0. The second drought
+        // at level 1 is also held for three days.
+        let expected = Array2::from_shape_vec((15, 1), vec![0, 2, 2, 2, 1, 1, 1, 0, 0, 0, 1, 1, 1, 0, 0]).unwrap();
+        let recorder = AssertionU64Recorder::new("drought", idx.into_metric_u64_before(), expected);
+        network.add_recorder(Box::new(recorder)).unwrap();
+
+        run_all_solvers(&model, &[], &[], &[]);
+    }
+
+    /// The level only changes on the evaluation days.
+    #[test]
+    fn test_drought_trigger_evaluation_days() {
+        let mut model = simple_model(1, None);
+        let network = model.network_mut();
+
+        let storage = Array1Parameter::new("storage".into(), Array1::linspace(0.7, 0.0, 15), None);
+        let storage: MetricF64 = network
+            .add_simple_parameter(Box::new(storage))
+            .unwrap()
+            .into_metric_f64_before();
+
+        let p = DroughtTriggerParameter::new(
+            ParameterName::new("drought", None),
+            storage,
+            vec![0.5.into(), 0.3.into()],
+            0,
+            Some(vec![1, 8, 15]),
+            0,
+        );
+        let idx = network.add_index_parameter(Box::new(p)).unwrap();
+
+        // Storage is 0.7 - 0.05 * i; it is below 0.5 from day 6 and below 0.3 from day 10.
+        let expected = Array2::from_shape_fn((15, 1), |(i, _)| match i {
+            0..=6 => 0,
+            7..=13 => 1,
+            _ => 2,
+        });
+        let recorder = AssertionU64Recorder::new("drought", idx.into_metric_u64_before(), expected);
+        network.add_recorder(Box::new(recorder)).unwrap();
+
+        run_all_solvers(&model, &[], &[], &[]);
+    }
+}
@@ -3,12 +3,19 @@ use crate::metric::{
     ConstantMetricF64Error, ConstantMetricU64Error, MetricF64Error, MetricU64Error, SimpleMetricF64Error,
     SimpleMetricU64Error,
 };
-use crate::parameters::InterpolationError;
+use crate::parameters::{InterpolationError, RbfProfileError};
+use chrono::NaiveDateTime;
 use thiserror::Error;
 
 /// Errors returned during parameter setup.
 #[derive(Error, Debug)]
 pub enum ParameterSetupError {
+    #[error("Error with RBF profile parameter `{name}`: {source}")]
+    RbfProfileError {
+        name: String,
+        #[source]
+        source: RbfProfileError,
+    },
     #[cfg(feature = "pyo3")]
     #[error("Error with Python parameter `{name}` (`{object}`): {py_error}")]
     PythonError {
@@ -19,6 +26,17 @@ pub enum ParameterSetupError {
     },
 }
 
+/// Errors returned when copying a parameter's internal state.
+#[derive(Error, Debug)]
+pub enum ParameterStateCloneError {
+    #[cfg(feature = "pyo3")]
+    #[error("Error copying the state of a Python parameter: {py_error}")]
+    PythonError {
+        #[source]
+        py_error: Box<pyo3::PyErr>,
+    },
+}
+
 /// Errors returned by parameter calculations.
 #[derive(Error, Debug)]
 pub enum ParameterCalculationError {
@@ -58,6 +76,8 @@ pub enum SimpleCalculationError {
     Internal { message: String },
     #[error("Aggregation error: {0}")]
     AggFuncError(#[from] AggFuncError),
+    #[error("No forecast was issued at or before {date}")]
+    NoForecastIssued { date: NaiveDateTime },
 }
 
 #[allow(clippy::enum_variant_names)]
@@ -0,0 +1,843 @@
+use super::{ConstParameter, Parameter, ParameterName, ParameterState, SimpleParameter};
+use crate::metric::{ConstantMetricF64, MetricF64, SimpleMetricF64};
+use crate::network::Network;
+use crate::parameters::errors::{ConstCalculationError, ParameterCalculationError, SimpleCalculationError};
+use crate::parameters::{GeneralParameter, ParameterMeta};
+use crate::scenario::ScenarioIndex;
+use crate::state::{ConstParameterValues, SimpleParameterValues, State};
+use crate::timestep::Timestep;
+use chrono::{Datelike, Timelike};
+use thiserror::Error;
+
+/// Errors from compiling an expression.
+///
+/// Positions are the byte offset in the expression string.
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ExpressionError {
+    #[error("Unexpected character `{character}` at position {position}")]
+    UnexpectedCharacter { character: char, position: usize },
+    #[error("Invalid number `{value}` at position {position}")]
+    InvalidNumber { value: String, position: usize },
+    #[error("Unexpected `{token}` at position {position}")]
+    UnexpectedToken { token: String, position: usize },
+    #[error("Unexpected end of the expression")]
+    UnexpectedEnd,
+    #[error("Unknown variable `{name}` at position {position}")]
+    UnknownVariable { name: String, position: usize },
+    #[error("Unknown function `{name}` at position {position}")]
+    UnknownFunction { name: String, position: usize },
+    #[error("Function `{name}` expects {expected} arguments but {found} were given")]
+    IncorrectNumberOfArguments {
+        name: String,
+        expected: String,
+        found: usize,
+    },
+    #[error("The expression is nested more than {max_depth} levels deep at position {position}")]
+    TooDeeplyNested { max_depth: usize, position: usize },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Identifier(String),
+    Operator(&'static str),
+    LeftParen,
+    RightParen,
+    Comma,
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::Number(v) => v.to_string(),
+            Token::Identifier(name) => name.clone(),
+            Token::Operator(op) => op.to_string(),
+            Token::LeftParen => "(".to_string(),
+            Token::RightParen => ")".to_string(),
+            Token::Comma => ",".to_string(),
+        }
+    }
+}
+
+/// Operators ordered so that the longer operators are matched first.
+const OPERATORS: [&str; 16] = [
+    "==", "!=", "<=", ">=", "&&", "||", "+", "-", "*", "/", "%", "^", "<", ">", "!", "=",
+];
+
+/// Split the expression into tokens and their positions.
+fn tokenize(expression: &str) -> Result<Vec<(Token, usize)>, ExpressionError> {
+    let mut tokens = Vec::new();
+    let bytes = expression.as_bytes();
+    let mut position = 0;
+
+    while position < expression.len() {
+        let rest = &expression[position..];
+        let c = rest.chars().next().unwrap();
+
+        if c.is_whitespace() {
+            position += c.len_utf8();
+        } else if c.is_ascii_digit() || (c == '.' && rest[1..].starts_with(|n: char| n.is_ascii_digit())) {
+            let start = position;
+            while position < bytes.len() && (bytes[position].is_ascii_digit() || bytes[position] == b'.') {
+                position += 1;
+            }
+            // Exponent, e.g. `1.5e-3`
+            if position < bytes.len() && (bytes[position] == b'e' || bytes[position] == b'E') {
+                let mut end = position + 1;
+                if end < bytes.len() && (bytes[end] == b'+' || bytes[end] == b'-') {
+                    end += 1;
+                }
+                if end < bytes.len() && bytes[end].is_ascii_digit() {
+                    position = end;
+                    while position < bytes.len() && bytes[position].is_ascii_digit() {
+                        position += 1;
+                    }
+                }
+            }
+
+            let value = &expression[start..position];
+            let number = value.parse::<f64>().map_err(|_| ExpressionError::InvalidNumber {
+                value: value.to_string(),
+                position: start,
+            })?;
+            tokens.push((Token::Number(number), start));
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let start = position;
+            while position < bytes.len() && (bytes[position].is_ascii_alphanumeric() || bytes[position] == b'_') {
+                position += 1;
+            }
+            tokens.push((Token::Identifier(expression[start..position].to_string()), start));
+        } else if c == '(' {
+            tokens.push((Token::LeftParen, position));
+            position += 1;
+        } else if c == ')' {
+            tokens.push((Token::RightParen, position));
+            position += 1;
+        } else if c == ',' {
+            tokens.push((Token::Comma, position));
+            position += 1;
+        } else {
+            // A single `=` is not a valid operator; it is only matched to give a helpful error.
+            match OPERATORS.iter().find(|op| rest.starts_with(**op)) {
+                Some(&op) if op != "=" => {
+                    tokens.push((Token::Operator(op), position));
+                    position += op.len();
+                }
+                _ => return Err(ExpressionError::UnexpectedCharacter { character: c, position }),
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum BinaryOp {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+    Power,
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    And,
+    Or,
+}
+
+impl BinaryOp {
+    fn apply(&self, a: f64, b: f64) -> f64 {
+        match self {
+            BinaryOp::Add => a + b,
+            BinaryOp::Subtract => a - b,
+            BinaryOp::Multiply => a * b,
+            BinaryOp::Divide => a / b,
+            BinaryOp::Remainder => a % b,
+            BinaryOp::Power => a.powf(b),
+            BinaryOp::Equal => bool_to_f64(a == b),
+            BinaryOp::NotEqual => bool_to_f64(a != b),
+            BinaryOp::Less => bool_to_f64(a < b),
+            BinaryOp::LessOrEqual => bool_to_f64(a <= b),
+            BinaryOp::Greater => bool_to_f64(a > b),
+            BinaryOp::GreaterOrEqual => bool_to_f64(a >= b),
+            // These are short-circuited when evaluating.
+            BinaryOp::And => bool_to_f64(a != 0.0 && b != 0.0),
+            BinaryOp::Or => bool_to_f64(a != 0.0 || b != 0.0),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Function {
+    Min,
+    Max,
+    Abs,
+    Clamp,
+    If,
+    Year,
+    Month,
+    Day,
+    DayOfYear,
+    DayOfWeek,
+    Hour,
+}
+
+impl Function {
+    fn from_name(name: &str) -> Option<Self> {
+        let f = match name {
+            "min" => Self::Min,
+            "max" => Self::Max,
+            "abs" => Self::Abs,
+            "clamp" => Self::Clamp,
+            "if" => Self::If,
+            "year" => Self::Year,
+            "month" => Self::Month,
+            "day" => Self::Day,
+            "day_of_year" => Self::DayOfYear,
+            "day_of_week" => Self::DayOfWeek,
+            "hour" => Self::Hour,
+            _ => return None,
+        };
+        Some(f)
+    }
+
+    /// Check the number of arguments given to the function.
+    fn check_arguments(&self, name: &str, found: usize) -> Result<(), ExpressionError> {
+        let (valid, expected) = match self {
+            Self::Min | Self::Max => (found >= 2, "at least 2"),
+            Self::Abs => (found == 1, "1"),
+            Self::Clamp | Self::If => (found == 3, "3"),
+            Self::Year | Self::Month | Self::Day | Self::DayOfYear | Self::DayOfWeek | Self::Hour => (found == 0, "0"),
+        };
+
+        if valid {
+            Ok(())
+        } else {
+            Err(ExpressionError::IncorrectNumberOfArguments {
+                name: name.to_string(),
+                expected: expected.to_string(),
+                found,
+            })
+        }
+    }
+
+    /// Whether the function uses the date of the time-step.
+    fn uses_timestep(&self) -> bool {
+        matches!(
+            self,
+            Self::Year | Self::Month | Self::Day | Self::DayOfYear | Self::DayOfWeek | Self::Hour
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Number(f64),
+    Variable(usize),
+    Negate(Box<Node>),
+    Not(Box<Node>),
+    Binary(BinaryOp, Box<Node>, Box<Node>),
+    Call(Function, Vec<Node>),
+}
+
+fn bool_to_f64(value: bool) -> f64 {
+    if value { 1.0 } else { 0.0 }
+}
+
+impl Node {
+    fn evaluate(&self, values: &[f64], timestep: Option<&Timestep>) -> f64 {
+        match self {
+            Node::Number(v) => *v,
+            Node::Variable(i) => values[*i],
+            Node::Negate(n) => -n.evaluate(values, timestep),
+            Node::Not(n) => bool_to_f64(n.evaluate(values, timestep) == 0.0),
+            Node::Binary(BinaryOp::And, a, b) => {
+                bool_to_f64(a.evaluate(values, timestep) != 0.0 && b.evaluate(values, timestep) != 0.0)
+            }
+            Node::Binary(BinaryOp::Or, a, b) => {
+                bool_to_f64(a.evaluate(values, timestep) != 0.0 || b.evaluate(values, timestep) != 0.0)
+            }
+            Node::Binary(op, a, b) => op.apply(a.evaluate(values, timestep), b.evaluate(values, timestep)),
+            Node::Call(Function::If, args) => {
+                if args[0].evaluate(values, timestep) != 0.0 {
+                    args[1].evaluate(values, timestep)
+                } else {
+                    args[2].evaluate(values, timestep)
+                }
+            }
+            Node::Call(f, args) => {
+                let mut args = args.iter().map(|a| a.evaluate(values, timestep));
+                match f {
+                    Function::Min => args.fold(f64::INFINITY, f64::min),
+                    Function::Max => args.fold(f64::NEG_INFINITY, f64::max),
+                    Function::Abs => args.next().unwrap().abs(),
+                    Function::Clamp => {
+                        let (x, lower, upper) = (args.next().unwrap(), args.next().unwrap(), args.next().unwrap());
+                        x.max(lower).min(upper)
+                    }
+                    Function::If => unreachable!("If is evaluated lazily."),
+                    _ => {
+                        let date = timestep
+                            .expect("Date functions are not used in constant expressions.")
+                            .date;
+                        match f {
+                            Function::Year => date.year() as f64,
+                            Function::Month => date.month() as f64,
+                            Function::Day => date.day() as f64,
+                            Function::DayOfYear => date.ordinal() as f64,
+                            Function::DayOfWeek => date.weekday().number_from_monday() as f64,
+                            Function::Hour => date.hour() as f64,
+                            _ => unreachable!(),
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn uses_timestep(&self) -> bool {
+        match self {
+            Node::Number(_) | Node::Variable(_) => false,
+            Node::Negate(n) | Node::Not(n) => n.uses_timestep(),
+            Node::Binary(_, a, b) => a.uses_timestep() || b.uses_timestep(),
+            Node::Call(f, args) => f.uses_timestep() || args.iter().any(|a| a.uses_timestep()),
+        }
+    }
+}
+
+/// A recursive descent parser of the tokens.
+///
+/// The grammar from the lowest to highest precedence is:
+/// ```text
+/// or         := and ("||" and)*
+/// and        := comparison ("&&" comparison)*
+/// comparison := additive (("==" | "!=" | "<" | "<=" | ">" | ">=") additive)?
+/// additive   := term (("+" | "-") term)*
+/// term       := unary (("*" | "/" | "%") unary)*
+/// unary      := ("-" | "!") unary | power
+/// power      := primary ("^" unary)?
+/// primary    := number | variable | function "(" arguments ")" | "(" or ")"
+/// ```
+///
+/// Every level of nesting (parentheses, function arguments, unary operators and powers) recurses
+/// through `unary`, which limits the depth to [`MAX_NESTING_DEPTH`] so that a malformed
+/// expression returns an error rather than overflowing the stack.
+struct Parser<'a> {
+    tokens: Vec<(Token, usize)>,
+    position: usize,
+    variables: &'a [&'a str],
+    depth: usize,
+}
+
+/// The maximum nesting depth of an expression.
+const MAX_NESTING_DEPTH: usize = 64;
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(t, _)| t)
+    }
+
+    fn next(&mut self) -> Result<(Token, usize), ExpressionError> {
+        let token = self
+            .tokens
+            .get(self.position)
+            .cloned()
+            .ok_or(ExpressionError::UnexpectedEnd)?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), ExpressionError> {
+        let (token, position) = self.next()?;
+        if token == expected {
+            Ok(())
+        } else {
+            Err(ExpressionError::UnexpectedToken {
+                token: token.describe(),
+                position,
+            })
+        }
+    }
+
+    /// Consume the next token if it is one of the `operators`.
+    fn next_operator(&mut self, operators: &[&'static str]) -> Option<&'static str> {
+        match self.peek() {
+            Some(Token::Operator(op)) if operators.contains(op) => {
+                let op = *op;
+                self.position += 1;
+                Some(op)
+            }
+            _ => None,
+        }
+    }
+
+    fn parse(mut self) -> Result<Node, ExpressionError> {
+        let node = self.or()?;
+        match self.tokens.get(self.position) {
+            None => Ok(node),
+            Some((token, position)) => Err(ExpressionError::UnexpectedToken {
+                token: token.describe(),
+                position: *position,
+            }),
+        }
+    }
+
+    fn or(&mut self) -> Result<Node, ExpressionError> {
+        let mut node = self.and()?;
+        while self.next_operator(&["||"]).is_some() {
+            node = Node::Binary(BinaryOp::Or, Box::new(node), Box::new(self.and()?));
+        }
+        Ok(node)
+    }
+
+    fn and(&mut self) -> Result<Node, ExpressionError> {
+        let mut node = self.comparison()?;
+        while self.next_operator(&["&&"]).is_some() {
+            node = Node::Binary(BinaryOp::And, Box::new(node), Box::new(self.comparison()?));
+        }
+        Ok(node)
+    }
+
+    fn comparison(&mut self) -> Result<Node, ExpressionError> {
+        let node = self.additive()?;
+        let op = match self.next_operator(&["==", "!=", "<", "<=", ">", ">="]) {
+            Some("==") => BinaryOp::Equal,
+            Some("!=") => BinaryOp::NotEqual,
+            Some("<") => BinaryOp::Less,
+            Some("<=") => BinaryOp::LessOrEqual,
+            Some(">") => BinaryOp::Greater,
+            Some(">=") => BinaryOp::GreaterOrEqual,
+            _ => return Ok(node),
+        };
+        Ok(Node::Binary(op, Box::new(node), Box::new(self.additive()?)))
+    }
+
+    fn additive(&mut self) -> Result<Node, ExpressionError> {
+        let mut node = self.term()?;
+        while let Some(op) = self.next_operator(&["+", "-"]) {
+            let op = if op == "+" { BinaryOp::Add } else { BinaryOp::Subtract };
+            node = Node::Binary(op, Box::new(node), Box::new(self.term()?));
+        }
+        Ok(node)
+    }
+
+    fn term(&mut self) -> Result<Node, ExpressionError> {
+        let mut node = self.unary()?;
+        while let Some(op) = self.next_operator(&["*", "/", "%"]) {
+            let op = match op {
+                "*" => BinaryOp::Multiply,
+                "/" => BinaryOp::Divide,
+                _ => BinaryOp::Remainder,
+            };
+            node = Node::Binary(op, Box::new(node), Box::new(self.unary()?));
+        }
+        Ok(node)
+    }
+
+    fn unary(&mut self) -> Result<Node, ExpressionError> {
+        if self.depth == MAX_NESTING_DEPTH {
+            return Err(ExpressionError::TooDeeplyNested {
+                max_depth: MAX_NESTING_DEPTH,
+                position: self.tokens.get(self.position).map_or(0, |(_, p)| *p),
+            });
+        }
+        self.depth += 1;
+        let node = match self.next_operator(&["-", "!"]) {
+            Some("-") => self.unary().map(|n| Node::Negate(Box::new(n))),
+            Some(_) => self.unary().map(|n| Node::Not(Box::new(n))),
+            None => self.power(),
+        };
+        self.depth -= 1;
+        node
+    }
+
+    fn power(&mut self) -> Result<Node, ExpressionError> {
+        let node = self.primary()?;
+        if self.next_operator(&["^"]).is_some() {
+            // Right associative, and binds tighter than a unary minus on the left: `-2^2 == -4`
+            return Ok(Node::Binary(BinaryOp::Power, Box::new(node), Box::new(self.unary()?)));
+        }
+        Ok(node)
+    }
+
+    fn primary(&mut self) -> Result<Node, ExpressionError> {
+        let (token, position) = self.next()?;
+        match token {
+            Token::Number(v) => Ok(Node::Number(v)),
+            Token::LeftParen => {
+                let node = self.or()?;
+                self.expect(Token::RightParen)?;
+                Ok(node)
+            }
+            Token::Identifier(name) if self.peek() == Some(&Token::LeftParen) => {
+                let function = Function::from_name(&name).ok_or_else(|| ExpressionError::UnknownFunction {
+                    name: name.clone(),
+                    position,
+                })?;
+                self.position += 1;
+
+                let mut args = Vec::new();
+                if self.peek() == Some(&Token::RightParen) {
+                    self.position += 1;
+                } else {
+                    loop {
+                        args.push(self.or()?);
+                        let (token, position) = self.next()?;
+                        match token {
+                            Token::Comma => continue,
+                            Token::RightParen => break,
+                            _ => {
+                                return Err(ExpressionError::UnexpectedToken {
+                                    token: token.describe(),
+                                    position,
+                                });
+                            }
+                        }
+                    }
+                }
+
+                function.check_arguments(&name, args.len())?;
+                Ok(Node::Call(function, args))
+            }
+            Token::Identifier(name) => match self.variables.iter().position(|v| *v == name) {
+                Some(i) => Ok(Node::Variable(i)),
+                None => Err(ExpressionError::UnknownVariable { name, position }),
+            },
+            _ => Err(ExpressionError::UnexpectedToken {
+                token: token.describe(),
+                position,
+            }),
+        }
+    }
+}
+
+/// A parameter that evaluates an arithmetic expression of other metrics.
+///
+/// The expression is compiled once when the parameter is created. Named variables in the
+/// expression are bound to metrics. The expression supports:
+///
+/// - arithmetic: `+`, `-`, `*`, `/`, `%` and `^` (power);
+/// - comparisons: `==`, `!=`, `<`, `<=`, `>` and `>=`, which return 1.0 if true and 0.0 otherwise;
+/// - logic: `&&`, `||` and `!`, where any non-zero value is true;
+/// - functions: `min(a, b, ...)`, `max(a, b, ...)`, `abs(x)`, `clamp(x, lower, upper)` and
+///   `if(condition, a, b)`;
+/// - date functions of the time-step: `year()`, `month()`, `day()`, `day_of_year()`,
+///   `day_of_week()` (Monday is 1) and `hour()`.
+///
+/// The parameter is simple or constant if all of its metrics are; a constant parameter must
+/// also not use the date functions.
+pub struct ExpressionParameter<M> {
+    meta: ParameterMeta,
+    expression: Node,
+    metrics: Vec<M>,
+}
+
+impl<M> ExpressionParameter<M>
+where
+    M: Send + Sync + Clone,
+{
+    /// Compile `expression` with each of the named `variables` bound to a metric.
+    pub fn new(name: ParameterName, expression: &str, variables: Vec<(String, M)>) -> Result<Self, ExpressionError> {
+        let (names, metrics): (Vec<_>, Vec<_>) = variables.into_iter().unzip();
+        let names: Vec<&str> = names.iter().map(|n| n.as_str()).collect();
+
+        let parser = Parser {
+            tokens: tokenize(expression)?,
+            position: 0,
+            variables: &names,
+            depth: 0,
+        };
+
+        Ok(Self {
+            meta: ParameterMeta::new(name),
+            expression: parser.parse()?,
+            metrics,
+        })
+    }
+}
+
+impl<M> Parameter for ExpressionParameter<M>
+where
+    M: Send + Sync,
+{
+    fn meta(&self) -> &ParameterMeta {
+        &self.meta
+    }
+}
+
+impl GeneralParameter<f64> for ExpressionParameter<MetricF64> {
+    fn before(
+        &self,
+        timestep: &Timestep,
+        _scenario_index: &ScenarioIndex,
+        model: &Network,
+        state: &State,
+        _internal_state: &mut Option<Box<dyn ParameterState>>,
+    ) -> Result<Option<f64>, ParameterCalculationError> {
+        let values = self
+            .metrics
+            .iter()
+            .map(|m| m.get_value(model, state))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Some(self.expression.evaluate(&values, Some(timestep))))
+    }
+
+    fn as_parameter(&self) -> &dyn Parameter
+    where
+        Self: Sized,
+    {
+        self
+    }
+
+    fn try_into_simple(&self) -> Option<Box<dyn SimpleParameter<f64>>> {
+        // We can make a simple version if all metrics can be simplified
+        let metrics: Vec<SimpleMetricF64> = self
+            .metrics
+            .clone()
+            .into_iter()
+            .map(|m| m.try_into().ok())
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(Box::new(ExpressionParameter::<SimpleMetricF64> {
+            meta: self.meta.clone(),
+            expression: self.expression.clone(),
+            metrics,
+        }))
+    }
+}
+
+impl SimpleParameter<f64> for ExpressionParameter<SimpleMetricF64> {
+    fn before(
+        &self,
+        timestep: &Timestep,
+        _scenario_index: &ScenarioIndex,
+        values: &SimpleParameterValues,
+        _internal_state: &mut Option<Box<dyn ParameterState>>,
+    ) -> Result<Option<f64>, SimpleCalculationError> {
+        let values = self
+            .metrics
+            .iter()
+            .map(|m| m.get_value(values))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Some(self.expression.evaluate(&values, Some(timestep))))
+    }
+
+    fn as_parameter(&self) -> &dyn Parameter
+    where
+        Self: Sized,
+    {
+        self
+    }
+
+    fn try_into_const(&self) -> Option<Box<dyn ConstParameter<f64>>> {
+        // The value of a constant parameter can not depend on the time-step
+        if self.expression.uses_timestep() {
+            return None;
+        }
+
+        let metrics: Vec<ConstantMetricF64> = self
+            .metrics
+            .clone()
+            .into_iter()
+            .map(|m| m.try_into().ok())
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(Box::new(ExpressionParameter::<ConstantMetricF64> {
+            meta: self.meta.clone(),
+            expression: self.expression.clone(),
+            metrics,
+        }))
+    }
+}
+
+impl ConstParameter<f64> for ExpressionParameter<ConstantMetricF64> {
+    fn compute(
+        &self,
+        _scenario_index: &ScenarioIndex,
+        values: &ConstParameterValues,
+        _internal_state: &mut Option<Box<dyn ParameterState>>,
+    ) -> Result<f64, ConstCalculationError> {
+        let values = self
+            .metrics
+            .iter()
+            .map(|m| m.get_value(values))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(self.expression.evaluate(&values, None))
+    }
+
+    fn as_parameter(&self) -> &dyn Parameter
+    where
+        Self: Sized,
+    {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ExpressionError, ExpressionParameter};
+    use crate::metric::{ConstantMetricF64, MetricF64};
+    use crate::parameters::ParameterIndex;
+    use crate::recorders::AssertionF64Recorder;
+    use crate::test_utils::{run_all_solvers, simple_model};
+    use crate::timestep::{PywrDuration, Timestep};
+    use chrono::NaiveDate;
+    use float_cmp::assert_approx_eq;
+    use ndarray::Array2;
+
+    /// Evaluate an expression with the given variables at 2020-03-04 (a Wednesday).
+    fn evaluate(expression: &str, variables: &[(&str, f64)]) -> Result<f64, ExpressionError> {
+        let variables = variables
+            .iter()
+            .map(|(n, v)| (n.to_string(), ConstantMetricF64::Constant(*v)))
+            .collect();
+        let p = ExpressionParameter::new("test".into(), expression, variables)?;
+        let values: Vec<f64> = p
+            .metrics
+            .iter()
+            .map(|m| match m {
+                ConstantMetricF64::Constant(v) => *v,
+                _ => unreachable!(),
+            })
+            .collect();
+
+        let date = NaiveDate::from_ymd_opt(2020, 3, 4)
+            .unwrap()
+            .and_hms_opt(6, 0, 0)
+            .unwrap();
+        let timestep = Timestep::new(date, 0, PywrDuration::from_days(1));
+        Ok(p.expression.evaluate(&values, Some(&timestep)))
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let vars = [("demand", 10.0), ("gauge_flow", 4.0)];
+        assert_approx_eq!(
+            f64,
+            evaluate("0.9 * max(demand - gauge_flow, 0) + 2", &vars).unwrap(),
+            7.4
+        );
+        assert_approx_eq!(f64, evaluate("1 + 2 * 3", &[]).unwrap(), 7.0);
+        assert_approx_eq!(f64, evaluate("(1 + 2) * 3", &[]).unwrap(), 9.0);
+        assert_approx_eq!(f64, evaluate("10 - 4 - 3", &[]).unwrap(), 3.0);
+        assert_approx_eq!(f64, evaluate("2 ^ 3 ^ 2", &[]).unwrap(), 512.0);
+        assert_approx_eq!(f64, evaluate("-2^2", &[]).unwrap(), -4.0);
+        assert_approx_eq!(f64, evaluate("2^-1", &[]).unwrap(), 0.5);
+        assert_approx_eq!(f64, evaluate("7 % 4", &[]).unwrap(), 3.0);
+        assert_approx_eq!(f64, evaluate("1.5e2 + .5", &[]).unwrap(), 150.5);
+    }
+
+    #[test]
+    fn test_functions_and_logic() {
+        let vars = [("x", 5.0)];
+        assert_approx_eq!(f64, evaluate("min(x, 3, 4)", &vars).unwrap(), 3.0);
+        assert_approx_eq!(f64, evaluate("abs(-x)", &vars).unwrap(), 5.0);
+        assert_approx_eq!(f64, evaluate("clamp(x, 0, 2)", &vars).unwrap(), 2.0);
+        assert_approx_eq!(f64, evaluate("if(x > 3 && x <= 5, 1, 2)", &vars).unwrap(), 1.0);
+        assert_approx_eq!(f64, evaluate("if(x == 3 || !(x != 5), 1, 2)", &vars).unwrap(), 1.0);
+        assert_approx_eq!(f64, evaluate("x >= 6", &vars).unwrap(), 0.0);
+        assert_approx_eq!(
+            f64,
+            evaluate("year() + month() + day() + day_of_year() + day_of_week() + hour()", &[]).unwrap(),
+            2020.0 + 3.0 + 4.0 + 64.0 + 3.0 + 6.0
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            evaluate("x + 1", &[]),
+            Err(ExpressionError::UnknownVariable {
+                name: "x".to_string(),
+                position: 0
+            })
+        );
+        assert_eq!(
+            evaluate("1 + foo(2)", &[]),
+            Err(ExpressionError::UnknownFunction {
+                name: "foo".to_string(),
+                position: 4
+            })
+        );
+        assert!(matches!(
+            evaluate("clamp(1, 2)", &[]),
+            Err(ExpressionError::IncorrectNumberOfArguments { .. })
+        ));
+        assert!(matches!(
+            evaluate("1 = 2", &[]),
+            Err(ExpressionError::UnexpectedCharacter { character: '=', .. })
+        ));
+        assert_eq!(evaluate("(1 + 2", &[]), Err(ExpressionError::UnexpectedEnd));
+        assert!(matches!(
+            evaluate("1 2", &[]),
+            Err(ExpressionError::UnexpectedToken { position: 2, .. })
+        ));
+
+        // Deep nesting is an error rather than a stack overflow
+        let nested = format!("{}1{}", "(".repeat(100_000), ")".repeat(100_000));
+        assert!(matches!(
+            evaluate(&nested, &[]),
+            Err(ExpressionError::TooDeeplyNested { position: 64, .. })
+        ));
+        assert!(matches!(
+            evaluate(&"-".repeat(100_000), &[]),
+            Err(ExpressionError::TooDeeplyNested { .. })
+        ));
+        let nested = format!("{}1{}", "abs(".repeat(100), ")".repeat(100));
+        assert!(matches!(
+            evaluate(&nested, &[]),
+            Err(ExpressionError::TooDeeplyNested { .. })
+        ));
+        let nested = format!("{}1{}", "(".repeat(63), ")".repeat(63));
+        assert_approx_eq!(f64, evaluate(&nested, &[]).unwrap(), 1.0);
+    }
+
+    /// Test that the parameter is added as a constant or simple parameter where possible.
+    #[test]
+    fn test_classification() {
+        let mut model = simple_model(1, None);
+        let network = model.network_mut();
+
+        let p = ExpressionParameter::new(
+            "constant".into(),
+            "2 * x",
+            vec![("x".to_string(), MetricF64::from(3.0))],
+        )
+        .unwrap();
+        let constant_idx = network.add_parameter(Box::new(p)).unwrap();
+        assert!(matches!(constant_idx, ParameterIndex::Const(_)));
+
+        let p = ExpressionParameter::new(
+            "simple".into(),
+            "x * month()",
+            vec![("x".to_string(), constant_idx.into_metric_f64_before())],
+        )
+        .unwrap();
+        let simple_idx = network.add_parameter(Box::new(p)).unwrap();
+        assert!(matches!(simple_idx, ParameterIndex::Simple(_)));
+
+        let node_idx = network.get_node_index_by_name("input", None).unwrap();
+        let p = ExpressionParameter::new(
+            "general".into(),
+            "if(flow > 1, flow, 0)",
+            vec![("flow".to_string(), MetricF64::NodeOutFlow(node_idx))],
+        )
+        .unwrap();
+        let general_idx = network.add_parameter(Box::new(p)).unwrap();
+        assert!(matches!(general_idx, ParameterIndex::General(_)));
+
+        // The default time-stepper is in January
+        let expected = Array2::from_elem((15, 1), 6.0);
+        let recorder = AssertionF64Recorder::new("simple", simple_idx.into_metric_f64_before(), expected, None, None);
+        network.add_recorder(Box::new(recorder)).unwrap();
+
+        run_all_solvers(&model, &[], &[], &[]);
+    }
+}
@@ -0,0 +1,98 @@
+use crate::metric::MetricF64;
+use crate::network::Network;
+use crate::parameters::{
+    GeneralParameter, Parameter, ParameterCalculationError, ParameterMeta, ParameterName, ParameterState,
+};
+use crate::scenario::ScenarioIndex;
+use crate::state::State;
+use crate::timestep::Timestep;
+
+/// A parameter representing the volume passed by a flow metric during the time-step.
+///
+/// The volume is the flow multiplied by the length of the time-step in days. It is only
+/// calculated after the solve when the flow is known.
+pub struct FlowVolumeParameter {
+    meta: ParameterMeta,
+    flow: MetricF64,
+}
+
+impl FlowVolumeParameter {
+    pub fn new(name: ParameterName, flow: MetricF64) -> Self {
+        Self {
+            meta: ParameterMeta::new(name),
+            flow,
+        }
+    }
+}
+
+impl Parameter for FlowVolumeParameter {
+    fn meta(&self) -> &ParameterMeta {
+        &self.meta
+    }
+}
+
+impl GeneralParameter<f64> for FlowVolumeParameter {
+    fn after(
+        &self,
+        timestep: &Timestep,
+        _scenario_index: &ScenarioIndex,
+        model: &Network,
+        state: &State,
+        _internal_state: &mut Option<Box<dyn ParameterState>>,
+    ) -> Result<Option<f64>, ParameterCalculationError> {
+        let flow = self.flow.get_value(model, state)?;
+        Ok(Some(flow * timestep.days()))
+    }
+
+    fn as_parameter(&self) -> &dyn Parameter
+    where
+        Self: Sized,
+    {
+        self
+    }
+}
+
+/// A parameter representing the flow that passes a volume metric during the time-step.
+///
+/// The flow is the volume divided by the length of the time-step in days. It is calculated before
+/// the solve, and can be used to limit a flow to the volume available at the start of the time-step.
+pub struct VolumeFlowParameter {
+    meta: ParameterMeta,
+    volume: MetricF64,
+}
+
+impl VolumeFlowParameter {
+    pub fn new(name: ParameterName, volume: MetricF64) -> Self {
+        Self {
+            meta: ParameterMeta::new(name),
+            volume,
+        }
+    }
+}
+
+impl Parameter for VolumeFlowParameter {
+    fn meta(&self) -> &ParameterMeta {
+        &self.meta
+    }
+}
+
+impl GeneralParameter<f64> for VolumeFlowParameter {
+    fn before(
+        &self,
+        timestep: &Timestep,
+        _scenario_index: &ScenarioIndex,
+        model: &Network,
+        state: &State,
+        _internal_state: &mut Option<Box<dyn ParameterState>>,
+    ) -> Result<Option<f64>, ParameterCalculationError> {
+        let volume = self.volume.get_value(model, state)?;
+        Ok(Some(volume / timestep.days()))
+    }
+
+    fn as_parameter(&self) -> &dyn Parameter
+    where
+        Self: Sized,
+    {
+        self
+    }
+}
@@ -0,0 +1,350 @@
+use crate::agg_funcs::AggFuncF64;
+use crate::parameters::errors::SimpleCalculationError;
+use crate::parameters::{Parameter, ParameterMeta, ParameterName, ParameterState, SimpleParameter};
+use crate::scenario::ScenarioIndex;
+use crate::state::SimpleParameterValues;
+use crate::timestep::Timestep;
+use chrono::NaiveDateTime;
+use ndarray::{Array3, Axis, s};
+use thiserror::Error;
+
+/// Errors from defining a [`Forecast`].
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ForecastError {
+    #[error("The forecast has {issues} issue times but values for {values} issues")]
+    IssueLength { issues: usize, values: usize },
+    #[error("The forecast issue times must be unique and in increasing order")]
+    UnsortedIssueTimes,
+    #[error("The forecast has no values")]
+    Empty,
+    #[error("The forecast issued at {issue_time} has no value for member {member} at lead {lead}")]
+    MissingValue {
+        issue_time: NaiveDateTime,
+        member: usize,
+        lead: usize,
+    },
+}
+
+/// An ensemble forecast issued at a series of times.
+///
+/// Each issue of the forecast has a value for each ensemble member and lead time. Lead times are
+/// measured in time-steps from the issue time, so lead zero is the time-step in which the forecast
+/// is issued.
+#[derive(Debug, Clone)]
+pub struct Forecast {
+    issue_times: Vec<NaiveDateTime>,
+    /// The values with shape (issues, members, leads).
+    values: Array3<f64>,
+}
+
+impl Forecast {
+    pub fn new(issue_times: Vec<NaiveDateTime>, values: Array3<f64>) -> Result<Self, ForecastError> {
+        if issue_times.len() != values.len_of(Axis(0)) {
+            return Err(ForecastError::IssueLength {
+                issues: issue_times.len(),
+                values: values.len_of(Axis(0)),
+            });
+        }
+
+        if issue_times.windows(2).any(|w| w[0] >= w[1]) {
+            return Err(ForecastError::UnsortedIssueTimes);
+        }
+
+        Ok(Self { issue_times, values })
+    }
+
+    /// Create a forecast from records of the issue time, member index, lead time and value.
+    ///
+    /// The records may be in any order, but there must be a record for every combination of issue
+    /// time, member and lead time.
+    pub fn from_long<I>(records: I) -> Result<Self, ForecastError>
+    where
+        I: IntoIterator<Item = (NaiveDateTime, usize, usize, f64)>,
+    {
+        let records: Vec<_> = records.into_iter().collect();
+        if records.is_empty() {
+            return Err(ForecastError::Empty);
+        }
+
+        let mut issue_times: Vec<NaiveDateTime> = records.iter().map(|r| r.0).collect();
+        issue_times.sort();
+        issue_times.dedup();
+
+        let num_members = records.iter().map(|r| r.1).max().unwrap_or_default() + 1;
+        let num_leads = records.iter().map(|r| r.2).max().unwrap_or_default() + 1;
+
+        let mut values = Array3::from_elem((issue_times.len(), num_members, num_leads), None);
+        for (issue_time, member, lead, value) in records {
+            // The issue times are sorted and contain every record's issue time.
+            let issue = issue_times.binary_search(&issue_time).expect("Issue time not found");
+            values[[issue, member, lead]] = Some(value);
+        }
+
+        if let Some(((issue, member, lead), _)) = values.indexed_iter().find(|(_, v)| v.is_none()) {
+            return Err(ForecastError::MissingValue {
+                issue_time: issue_times[issue],
+                member,
+                lead,
+            });
+        }
+        let values = values.mapv(|v| v.expect("All values are present"));
+
+        Self::new(issue_times, values)
+    }
+
+    pub fn num_members(&self) -> usize {
+        self.values.len_of(Axis(1))
+    }
+
+    pub fn num_leads(&self) -> usize {
+        self.values.len_of(Axis(2))
+    }
+
+    /// The index of the latest issue at or before `date`, if there is one.
+    fn latest_issue(&self, date: NaiveDateTime) -> Option<usize> {
+        self.issue_times.partition_point(|t| *t <= date).checked_sub(1)
+    }
+}
+
+/// The ensemble member used by a [`ForecastParameter`].
+#[derive(Debug, Clone, Copy)]
+pub enum ForecastMember {
+    /// The same member in every scenario.
+    Fixed(usize),
+    /// The member is the index of the scenario in the scenario group with the given index. This is
+    /// the index in the full group, even if only a subset of the group is simulated.
+    ScenarioGroup(usize),
+}
+
+/// The value returned by a [`ForecastParameter`].
+#[derive(Debug, Clone)]
+pub enum ForecastValue {
+    /// The value at the given number of time-steps ahead of the current time-step.
+    Lead(usize),
+    /// An aggregate of the values from the current time-step over a horizon of the given number
+    /// of time-steps, or over the rest of the forecast if no horizon is given.
+    Aggregate {
+        agg_func: AggFuncF64,
+        horizon: Option<usize>,
+    },
+}
+
+/// A parameter that returns a value from the latest issue of an ensemble [`Forecast`].
+///
+/// At each time-step the latest forecast issued at or before the start of the time-step is used.
+/// The lead times of the forecast are offset by the number of time-steps since it was issued, so
+/// that a [`ForecastValue::Lead`] of zero is always the forecast for the current time-step. An
+/// error is returned if no forecast has been issued, or if the requested lead time is beyond the
+/// end of the forecast.
+pub struct ForecastParameter {
+    meta: ParameterMeta,
+    forecast: Forecast,
+    member: ForecastMember,
+    value: ForecastValue,
+}
+
+impl ForecastParameter {
+    pub fn new(name: ParameterName, forecast: Forecast, member: ForecastMember, value: ForecastValue) -> Self {
+        Self {
+            meta: ParameterMeta::new(name),
+            forecast,
+            member,
+            value,
+        }
+    }
+
+    fn member_index(&self, scenario_index: &ScenarioIndex) -> Result<usize, SimpleCalculationError> {
+        let member = match self.member {
+            ForecastMember::Fixed(member) => member,
+            ForecastMember::ScenarioGroup(group_index) => scenario_index.schema_index_for_group(group_index),
+        };
+
+        if member >= self.forecast.num_members() {
+            return Err(SimpleCalculationError::OutOfBoundsError {
+                index: member,
+                length: self.forecast.num_members(),
+                axis: 1,
+            });
+        }
+
+        Ok(member)
+    }
+}
+
+/// Check that the lead index `end` (exclusive) is within the forecast.
+fn check_lead(end: usize, num_leads: usize) -> Result<(), SimpleCalculationError> {
+    if end > num_leads {
+        return Err(SimpleCalculationError::OutOfBoundsError {
+            index: end - 1,
+            length: num_leads,
+            axis: 2,
+        });
+    }
+    Ok(())
+}
+
+impl Parameter for ForecastParameter {
+    fn meta(&self) -> &ParameterMeta {
+        &self.meta
+    }
+}
+
+impl SimpleParameter<f64> for ForecastParameter {
+    fn before(
+        &self,
+        timestep: &Timestep,
+        scenario_index: &ScenarioIndex,
+        _values: &SimpleParameterValues,
+        _internal_state: &mut Option<Box<dyn ParameterState>>,
+    ) -> Result<Option<f64>, SimpleCalculationError> {
+        let issue = self
+            .forecast
+            .latest_issue(timestep.date)
+            .ok_or(SimpleCalculationError::NoForecastIssued { date: timestep.date })?;
+        let member = self.member_index(scenario_index)?;
+
+        // The number of whole time-steps since the forecast was issued
+        let elapsed = timestep.date - self.forecast.issue_times[issue];
+        let offset = (elapsed.num_milliseconds() / timestep.duration.milliseconds()) as usize;
+
+        let num_leads = self.forecast.num_leads();
+        let value = match &self.value {
+            ForecastValue::Lead(lead) => {
+                let lead = offset + lead;
+                check_lead(lead + 1, num_leads)?;
+                self.forecast.values[[issue, member, lead]]
+            }
+            ForecastValue::Aggregate { agg_func, horizon } => {
+                let end = match horizon {
+                    Some(horizon) => offset + horizon,
+                    None => num_leads.max(offset + 1),
+                };
+                check_lead(end, num_leads)?;
+                let values = self.forecast.values.slice(s![issue, member, offset..end]);
+                agg_func.calc_iter_f64(values)?
+            }
+        };
+
+        Ok(Some(value))
+    }
+
+    fn as_parameter(&self) -> &dyn Parameter
+    where
+        Self: Sized,
+    {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Forecast, ForecastError, ForecastMember, ForecastParameter, ForecastValue};
+    use crate::agg_funcs::AggFuncF64;
+    use crate::recorders::AssertionF64Recorder;
+    use crate::test_utils::{run_all_solvers, simple_model};
+    use chrono::{NaiveDate, NaiveDateTime};
+    use ndarray::{Array2, Array3};
+
+    fn date(day: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2020, 1, day)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+    }
+
+    /// A forecast issued every 5 days with 3 members and 10 lead times. The value is
+    /// `100 * issue + 10 * member + lead`.
+    fn forecast() -> Forecast {
+        let records = (0..3).flat_map(|issue| {
+            (0..3).flat_map(move |member| {
+                (0..10).map(move |lead| {
+                    let value = (100 * issue + 10 * member + lead) as f64;
+                    (date(1 + 5 * issue as u32), member, lead, value)
+                })
+            })
+        });
+        Forecast::from_long(records).unwrap()
+    }
+
+    #[test]
+    fn test_from_long() {
+        let records = vec![
+            (date(3), 1, 1, 5.0),
+            (date(1), 0, 0, 1.0),
+            (date(1), 0, 1, 2.0),
+            (date(1), 1, 0, 3.0),
+            (date(1), 1, 1, 4.0),
+            (date(3), 0, 0, 6.0),
+            (date(3), 0, 1, 7.0),
+            (date(3), 1, 0, 8.0),
+        ];
+        let forecast = Forecast::from_long(records.clone()).unwrap();
+        assert_eq!(forecast.issue_times, vec![date(1), date(3)]);
+        assert_eq!(forecast.values.shape(), &[2, 2, 2]);
+        assert_eq!(forecast.values[[0, 0, 0]], 1.0);
+        assert_eq!(forecast.values[[1, 1, 1]], 5.0);
+
+        assert_eq!(forecast.latest_issue(date(2)), Some(0));
+        assert_eq!(forecast.latest_issue(date(3)), Some(1));
+
+        // A missing combination of issue time, member and lead is an error
+        assert_eq!(
+            Forecast::from_long(records[1..].to_vec()).unwrap_err(),
+            ForecastError::MissingValue {
+                issue_time: date(3),
+                member: 1,
+                lead: 1
+            }
+        );
+
+        assert_eq!(
+            Forecast::new(vec![date(3), date(1)], Array3::zeros((2, 1, 1))).unwrap_err(),
+            ForecastError::UnsortedIssueTimes
+        );
+    }
+
+    /// The lead is offset by the time since the latest issue and the member follows the scenario.
+    #[test]
+    fn test_forecast_lead() {
+        let mut model = simple_model(3, None);
+        let network = model.network_mut();
+
+        let p = ForecastParameter::new(
+            "forecast".into(),
+            forecast(),
+            ForecastMember::ScenarioGroup(0),
+            ForecastValue::Lead(1),
+        );
+        let idx = network.add_simple_parameter(Box::new(p)).unwrap();
+
+        let expected = Array2::from_shape_fn((15, 3), |(t, s)| (100 * (t / 5) + 10 * s + t % 5 + 1) as f64);
+        let recorder = AssertionF64Recorder::new("forecast", idx.into_metric_f64_before(), expected, None, None);
+        network.add_recorder(Box::new(recorder)).unwrap();
+
+        run_all_solvers(&model, &[], &[], &[]);
+    }
+
+    /// The mean of a fixed member over a three time-step horizon.
+    #[test]
+    fn test_forecast_aggregate() {
+        let mut model = simple_model(1, None);
+        let network = model.network_mut();
+
+        let p = ForecastParameter::new(
+            "forecast".into(),
+            forecast(),
+            ForecastMember::Fixed(2),
+            ForecastValue::Aggregate {
+                agg_func: AggFuncF64::Mean,
+                horizon: Some(3),
+            },
+        );
+        let idx = network.add_simple_parameter(Box::new(p)).unwrap();
+
+        let expected = Array2::from_shape_fn((15, 1), |(t, _)| (100 * (t / 5) + 20 + t % 5 + 1) as f64);
+        let recorder = AssertionF64Recorder::new("forecast", idx.into_metric_f64_before(), expected, None, None);
+        network.add_recorder(Box::new(recorder)).unwrap();
+
+        run_all_solvers(&model, &[], &[], &[]);
+    }
+}
@@ -0,0 +1,255 @@
+use crate::metric::MetricF64;
+use crate::network::Network;
+use crate::parameters::errors::ParameterCalculationError;
+use crate::parameters::{GeneralParameter, Parameter, ParameterMeta, ParameterName, ParameterState};
+use crate::scenario::ScenarioIndex;
+use crate::state::State;
+use crate::timestep::Timestep;
+
+/// A rule that gives the release from a reservoir for the available water and the demand.
+pub enum HedgingRule {
+    /// The standard operating policy: release the demand, or all of the available water if it is
+    /// less than the demand.
+    StandardOperatingPolicy,
+    /// Release a fixed proportion (`slope`) of the available water, up to the demand. A slope of
+    /// one is the standard operating policy.
+    Linear { slope: MetricF64 },
+    /// Release all of the available water up to the `start` of hedging. Between the `start` and
+    /// the `end` of hedging the release increases linearly from the `start` to the demand, and
+    /// above the `end` the demand is released in full.
+    ///
+    /// The release is never more than the available water or the demand. If the `end` is not
+    /// greater than the `start` there is no hedging zone.
+    TwoPoint { start: MetricF64, end: MetricF64 },
+    /// Release a proportion of the demand that depends on the zone of the available water.
+    ///
+    /// The zones are defined by `thresholds` in descending order. The first of the `factors` is
+    /// used when the available water is greater than or equal to the first threshold, the second
+    /// when it is only greater than or equal to the second threshold, and so on. There should
+    /// therefore be one more factor than there are thresholds. The release is never more than the
+    /// available water.
+    Zones {
+        thresholds: Vec<MetricF64>,
+        factors: Vec<MetricF64>,
+    },
+}
+
+impl HedgingRule {
+    /// The release for the available water and demand.
+    fn release(
+        &self,
+        available: f64,
+        demand: f64,
+        network: &Network,
+        state: &State,
+    ) -> Result<f64, ParameterCalculationError> {
+        let release = match self {
+            Self::StandardOperatingPolicy => demand.min(available),
+            Self::Linear { slope } => demand.min(slope.get_value(network, state)? * available),
+            Self::TwoPoint { start, end } => {
+                let start = start.get_value(network, state)?;
+                let end = end.get_value(network, state)?;
+
+                if available >= end {
+                    demand
+                } else if available <= start {
+                    available
+                } else {
+                    start + (demand - start) * (available - start) / (end - start)
+                }
+                .min(demand)
+                .min(available)
+            }
+            Self::Zones { thresholds, factors } => {
+                let mut zone = thresholds.len();
+                for (idx, threshold) in thresholds.iter().enumerate() {
+                    if available >= threshold.get_value(network, state)? {
+                        zone = idx;
+                        break;
+                    }
+                }
+
+                let factor = factors
+                    .get(zone)
+                    .ok_or(ParameterCalculationError::OutOfBoundsError {
+                        index: zone,
+                        length: factors.len(),
+                        axis: 0,
+                    })?
+                    .get_value(network, state)?;
+
+                (factor * demand).min(available)
+            }
+        };
+
+        Ok(release.max(0.0))
+    }
+}
+
+/// The value returned by a [`HedgingParameter`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HedgingOutput {
+    /// The release target.
+    Release,
+    /// The release as a proportion of the demand. This is one if the demand is zero.
+    Factor,
+}
+
+/// A parameter that returns the release target of a reservoir from a [`HedgingRule`].
+///
+/// The available water is the sum of the `storage` and the `inflow` (for example a forecast of
+/// the inflow over the time-step or a longer period). The parameter returns either the release
+/// target given by the `rule` for the available water and the `demand`, or the release as a
+/// proportion of the demand, which can be used to scale demands as a demand-saving factor.
+pub struct HedgingParameter {
+    meta: ParameterMeta,
+    storage: MetricF64,
+    inflow: Option<MetricF64>,
+    demand: MetricF64,
+    rule: HedgingRule,
+    output: HedgingOutput,
+}
+
+impl HedgingParameter {
+    pub fn new(
+        name: ParameterName,
+        storage: MetricF64,
+        inflow: Option<MetricF64>,
+        demand: MetricF64,
+        rule: HedgingRule,
+        output: HedgingOutput,
+    ) -> Self {
+        Self {
+            meta: ParameterMeta::new(name),
+            storage,
+            inflow,
+            demand,
+            rule,
+            output,
+        }
+    }
+}
+
+impl Parameter for HedgingParameter {
+    fn meta(&self) -> &ParameterMeta {
+        &self.meta
+    }
+}
+
+impl GeneralParameter<f64> for HedgingParameter {
+    fn before(
+        &self,
+        _timestep: &Timestep,
+        _scenario_index: &ScenarioIndex,
+        network: &Network,
+        state: &State,
+        _internal_state: &mut Option<Box<dyn ParameterState>>,
+    ) -> Result<Option<f64>, ParameterCalculationError> {
+        let mut available = self.storage.get_value(network, state)?;
+        if let Some(inflow) = &self.inflow {
+            available += inflow.get_value(network, state)?;
+        }
+        let demand = self.demand.get_value(network, state)?;
+
+        let release = self.rule.release(available, demand, network, state)?;
+
+        let value = match self.output {
+            HedgingOutput::Release => release,
+            HedgingOutput::Factor if demand > 0.0 => release / demand,
+            HedgingOutput::Factor => 1.0,
+        };
+
+        Ok(Some(value))
+    }
+
+    fn as_parameter(&self) -> &dyn Parameter
+    where
+        Self: Sized,
+    {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HedgingOutput, HedgingParameter, HedgingRule};
+    use crate::metric::MetricF64;
+    use crate::parameters::Array1Parameter;
+    use crate::recorders::AssertionF64Recorder;
+    use crate::test_utils::{run_all_solvers, simple_model};
+    use ndarray::{Array1, Array2};
+
+    /// Run a hedging rule with a demand of 10 and available water from 0 to 28 in steps of 2.
+    fn run_hedging(rule: HedgingRule, output: HedgingOutput, expected: Vec<f64>) {
+        let mut model = simple_model(1, None);
+        let network = model.network_mut();
+
+        let storage = Array1Parameter::new("storage".into(), Array1::linspace(0.0, 28.0, 15), None);
+        let storage: MetricF64 = network
+            .add_simple_parameter(Box::new(storage))
+            .unwrap()
+            .into_metric_f64_before();
+
+        let p = HedgingParameter::new("hedging".into(), storage, None, 10.0.into(), rule, output);
+        let idx = network.add_parameter(Box::new(p)).unwrap();
+
+        let expected = Array2::from_shape_vec((15, 1), expected).unwrap();
+        let recorder = AssertionF64Recorder::new("hedging", idx.into_metric_f64_before(), expected, None, None);
+        network.add_recorder(Box::new(recorder)).unwrap();
+
+        run_all_solvers(&model, &[], &[], &[]);
+    }
+
+    #[test]
+    fn test_standard_operating_policy() {
+        let expected = (0..15).map(|i| (2.0 * i as f64).min(10.0)).collect();
+        run_hedging(HedgingRule::StandardOperatingPolicy, HedgingOutput::Release, expected);
+    }
+
+    #[test]
+    fn test_linear() {
+        let expected = (0..15).map(|i| (i as f64).min(10.0)).collect();
+        run_hedging(
+            HedgingRule::Linear { slope: 0.5.into() },
+            HedgingOutput::Release,
+            expected,
+        );
+    }
+
+    /// Hedging starts at 6 and ends at 16, so the release increases from 6 to 10 between them.
+    #[test]
+    fn test_two_point() {
+        let expected = vec![
+            0.0, 2.0, 4.0, 6.0, 6.8, 7.6, 8.4, 9.2, 10.0, 10.0, 10.0, 10.0, 10.0, 10.0, 10.0,
+        ];
+        run_hedging(
+            HedgingRule::TwoPoint {
+                start: 6.0.into(),
+                end: 16.0.into(),
+            },
+            HedgingOutput::Release,
+            expected,
+        );
+    }
+
+    #[test]
+    fn test_zones() {
+        let expected = (0..15)
+            .map(|i| match 2 * i {
+                20.. => 1.0,
+                10..20 => 0.8,
+                // The release is limited to the available water
+                0 => 0.0,
+                a => (0.5_f64).min(a as f64 / 10.0),
+            })
+            .collect();
+        run_hedging(
+            HedgingRule::Zones {
+                thresholds: vec![20.0.into(), 10.0.into()],
+                factors: vec![1.0.into(), 0.8.into(), 0.5.into()],
+            },
+            HedgingOutput::Factor,
+            expected,
+        );
+    }
+}
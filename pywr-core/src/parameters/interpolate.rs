@@ -25,49 +25,204 @@ pub enum InterpolationError {
     AboveUpperBounds,
     #[error("Points are not strictly monotonic")]
     NotStrictlyMonotonic,
+    #[error("Log-linear interpolation requires all values to be greater than zero")]
+    NonPositiveValue,
+    #[error("Value to interpolate is not finite: {0}")]
+    NonFiniteValue(String),
 }
 
+/// The method used to interpolate between data points.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum InterpolationKind {
+    /// Straight lines between the points.
+    #[default]
+    Linear,
+    /// Monotone piecewise cubic Hermite interpolation (PCHIP).
+    ///
+    /// The interpolant does not overshoot the data and preserves its monotonicity, which makes it
+    /// suitable for rating and yield curves.
+    Pchip,
+    /// A step function that uses the value of the previous point.
+    Previous,
+    /// A step function that uses the value of the next point.
+    Next,
+    /// Straight lines between the logarithm of the values (i.e. exponential between the points).
+    ///
+    /// All of the values must be greater than zero.
+    LogLinear,
+}
+
+/// How values outside the range of the data points are handled.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Extrapolation {
+    /// Return an error.
+    #[default]
+    Error,
+    /// Return the value of the nearest end point.
+    Constant,
+    /// Extend the interpolant from the nearest end point using its gradient at that point.
+    ///
+    /// The step kinds have a zero gradient, and log-linear extrapolation uses the gradient in log space.
+    Linear,
+}
+
+/// Piecewise linear interpolation of `points`.
+///
+/// If `error_on_bounds` is false values outside the range of the points are clamped to the end
+/// values. See [`interpolate_points`] for other kinds of interpolation.
 pub fn linear_interpolation(
     value: f64,
     points: &[(f64, f64)],
     error_on_bounds: bool,
+) -> Result<f64, InterpolationError> {
+    let extrapolation = if error_on_bounds {
+        Extrapolation::Error
+    } else {
+        Extrapolation::Constant
+    };
+    interpolate_points(value, points, InterpolationKind::Linear, extrapolation)
+}
+
+/// Interpolate `value` using `points` sorted by strictly increasing x values.
+pub fn interpolate_points(
+    value: f64,
+    points: &[(f64, f64)],
+    kind: InterpolationKind,
+    extrapolation: Extrapolation,
 ) -> Result<f64, InterpolationError> {
     if points.len() < 2 {
         return Err(InterpolationError::InsufficientPoints);
     }
 
-    // Handle lower bounds checking
-    if value < points[0].0 {
-        return if error_on_bounds {
-            Err(InterpolationError::BelowLowerBounds)
+    if points.windows(2).any(|pts| pts[0].0 >= pts[1].0) {
+        return Err(InterpolationError::NotStrictlyMonotonic);
+    }
+
+    if kind == InterpolationKind::LogLinear && points.iter().any(|(_, f)| *f <= 0.0) {
+        return Err(InterpolationError::NonPositiveValue);
+    }
+
+    if !value.is_finite() {
+        return Err(InterpolationError::NonFiniteValue(value.to_string()));
+    }
+
+    let last = points.len() - 1;
+
+    // Handle the bounds
+    if value < points[0].0 || value > points[last].0 {
+        let (end, error) = if value < points[0].0 {
+            (0, InterpolationError::BelowLowerBounds)
         } else {
-            Ok(points[0].1)
+            (last, InterpolationError::AboveUpperBounds)
+        };
+
+        return match extrapolation {
+            Extrapolation::Error => Err(error),
+            Extrapolation::Constant => Ok(points[end].1),
+            Extrapolation::Linear => Ok(extrapolate(value, points, end, kind)),
         };
     }
 
-    for pts in points.windows(2) {
-        let lp = pts[0];
-        let up = pts[1];
+    // The first segment whose upper bound is at or above the value
+    let i = points[1..]
+        .iter()
+        .position(|(x, _)| value <= *x)
+        .expect("Value has been checked to be within the bounds of the points.");
+    let (lp, up) = (points[i], points[i + 1]);
 
-        if lp.0 >= up.0 {
-            return Err(InterpolationError::NotStrictlyMonotonic);
-        }
+    let f = match kind {
+        InterpolationKind::Linear => interpolate(value, lp.0, up.0, lp.1, up.1),
+        InterpolationKind::Pchip => {
+            let h = up.0 - lp.0;
+            let t = (value - lp.0) / h;
+            let t2 = t * t;
+            let t3 = t2 * t;
 
-        if value <= up.0 {
-            return Ok(interpolate(value, lp.0, up.0, lp.1, up.1));
+            (2.0 * t3 - 3.0 * t2 + 1.0) * lp.1
+                + (t3 - 2.0 * t2 + t) * h * pchip_derivative(points, i)
+                + (-2.0 * t3 + 3.0 * t2) * up.1
+                + (t3 - t2) * h * pchip_derivative(points, i + 1)
         }
-    }
+        InterpolationKind::Previous => {
+            if value >= up.0 {
+                up.1
+            } else {
+                lp.1
+            }
+        }
+        InterpolationKind::Next => {
+            if value <= lp.0 {
+                lp.1
+            } else {
+                up.1
+            }
+        }
+        InterpolationKind::LogLinear => interpolate(value, lp.0, up.0, lp.1.ln(), up.1.ln()).exp(),
+    };
+
+    Ok(f)
+}
 
-    if error_on_bounds {
-        Err(InterpolationError::AboveUpperBounds)
+/// Extend the interpolant beyond the end point at index `end` using its gradient at that point.
+fn extrapolate(value: f64, points: &[(f64, f64)], end: usize, kind: InterpolationKind) -> f64 {
+    // The segment at the end of the points
+    let (lp, up) = if end == 0 {
+        (points[0], points[1])
     } else {
-        Ok(points
-            .last()
-            .expect("This should be impossible because fp has been checked for a length of at least 2")
-            .1)
+        (points[end - 1], points[end])
+    };
+    let (x, f) = points[end];
+
+    match kind {
+        InterpolationKind::Linear => f + (value - x) * (up.1 - lp.1) / (up.0 - lp.0),
+        InterpolationKind::Pchip => f + (value - x) * pchip_derivative(points, end),
+        InterpolationKind::Previous | InterpolationKind::Next => f,
+        InterpolationKind::LogLinear => f * ((value - x) * (up.1.ln() - lp.1.ln()) / (up.0 - lp.0)).exp(),
     }
 }
 
+/// The derivative of the PCHIP interpolant at the point with index `k`.
+///
+/// This uses the weighted harmonic mean of the neighbouring secants (Fritsch & Butland) at the
+/// interior points, and a shape preserving three-point formula at the end points. These are
+/// the same as SciPy's `PchipInterpolator`.
+fn pchip_derivative(points: &[(f64, f64)], k: usize) -> f64 {
+    let n = points.len();
+    let h = |i: usize| points[i + 1].0 - points[i].0;
+    let delta = |i: usize| (points[i + 1].1 - points[i].1) / h(i);
+
+    if n == 2 {
+        return delta(0);
+    }
+
+    if k == 0 || k == n - 1 {
+        // The end segment and its neighbour
+        let (h0, h1, d0, d1) = if k == 0 {
+            (h(0), h(1), delta(0), delta(1))
+        } else {
+            (h(n - 2), h(n - 3), delta(n - 2), delta(n - 3))
+        };
+
+        let d = ((2.0 * h0 + h1) * d0 - h0 * d1) / (h0 + h1);
+        return if d.signum() != d0.signum() || d0 == 0.0 {
+            0.0
+        } else if d0.signum() != d1.signum() && d.abs() > 3.0 * d0.abs() {
+            3.0 * d0
+        } else {
+            d
+        };
+    }
+
+    let (d0, d1) = (delta(k - 1), delta(k));
+    if d0 * d1 <= 0.0 {
+        return 0.0;
+    }
+
+    let w1 = 2.0 * h(k) + h(k - 1);
+    let w2 = h(k) + 2.0 * h(k - 1);
+    (w1 + w2) / (w1 / d0 + w2 / d1)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -107,4 +262,127 @@ mod tests {
         let non_monotonic_points = vec![(1.0, 3.0), (2.0, 4.5), (2.0, 6.0), (4.0, 7.5), (5.0, 9.0)];
         assert!(linear_interpolation(3.0, &non_monotonic_points, true).is_err());
     }
+
+    #[test]
+    fn test_step_interpolation() {
+        let points = vec![(1.0, 3.0), (2.0, 4.5), (3.0, 6.0)];
+        let previous = |x| interpolate_points(x, &points, InterpolationKind::Previous, Extrapolation::Error).unwrap();
+        let next = |x| interpolate_points(x, &points, InterpolationKind::Next, Extrapolation::Error).unwrap();
+
+        assert_approx_eq!(f64, previous(1.0), 3.0);
+        assert_approx_eq!(f64, previous(1.5), 3.0);
+        assert_approx_eq!(f64, previous(2.0), 4.5);
+        assert_approx_eq!(f64, previous(3.0), 6.0);
+
+        assert_approx_eq!(f64, next(1.0), 3.0);
+        assert_approx_eq!(f64, next(1.5), 4.5);
+        assert_approx_eq!(f64, next(2.0), 4.5);
+        assert_approx_eq!(f64, next(2.5), 6.0);
+    }
+
+    #[test]
+    fn test_log_linear_interpolation() {
+        let points = vec![(0.0, 1.0), (1.0, 10.0), (2.0, 1000.0)];
+        let f = |x, e| interpolate_points(x, &points, InterpolationKind::LogLinear, e);
+
+        assert_approx_eq!(f64, f(0.5, Extrapolation::Error).unwrap(), 10f64.sqrt());
+        assert_approx_eq!(f64, f(1.5, Extrapolation::Error).unwrap(), 100.0);
+        assert_approx_eq!(f64, f(3.0, Extrapolation::Linear).unwrap(), 100_000.0, epsilon = 1e-9);
+        assert_approx_eq!(f64, f(-1.0, Extrapolation::Linear).unwrap(), 0.1);
+
+        let non_positive = vec![(0.0, 0.0), (1.0, 10.0)];
+        assert_eq!(
+            interpolate_points(0.5, &non_positive, InterpolationKind::LogLinear, Extrapolation::Error),
+            Err(InterpolationError::NonPositiveValue)
+        );
+    }
+
+    #[test]
+    fn test_pchip_interpolation() {
+        // Derivatives at the points are 1.25, 2/3, 0.1 and 0.0
+        let points = vec![(0.0, 0.0), (1.0, 1.0), (2.0, 1.5), (4.0, 1.6)];
+        let f = |x| interpolate_points(x, &points, InterpolationKind::Pchip, Extrapolation::Error).unwrap();
+
+        for (x, f_x) in points.iter() {
+            assert_approx_eq!(f64, f(*x), *f_x);
+        }
+        assert_approx_eq!(f64, f(0.5), 0.5729166666666666, epsilon = 1e-12);
+        assert_approx_eq!(f64, f(1.5), 1.3208333333333335, epsilon = 1e-12);
+        assert_approx_eq!(f64, f(3.0), 1.575, epsilon = 1e-12);
+
+        // The interpolant is monotonic and does not overshoot
+        let mut previous = f(0.0);
+        for i in 1..=400 {
+            let value = f(i as f64 / 100.0);
+            assert!(value >= previous);
+            assert!(value <= 1.6);
+            previous = value;
+        }
+
+        // Two points are a straight line
+        let two_points = vec![(0.0, 1.0), (2.0, 3.0)];
+        assert_approx_eq!(
+            f64,
+            interpolate_points(0.5, &two_points, InterpolationKind::Pchip, Extrapolation::Error).unwrap(),
+            1.5
+        );
+    }
+
+    #[test]
+    fn test_extrapolation() {
+        let points = vec![(1.0, 3.0), (2.0, 4.5), (3.0, 6.0)];
+        let f = |x, k, e| interpolate_points(x, &points, k, e);
+
+        assert_eq!(
+            f(0.0, InterpolationKind::Linear, Extrapolation::Error),
+            Err(InterpolationError::BelowLowerBounds)
+        );
+        assert_eq!(
+            f(4.0, InterpolationKind::Pchip, Extrapolation::Error),
+            Err(InterpolationError::AboveUpperBounds)
+        );
+        assert_approx_eq!(
+            f64,
+            f(0.0, InterpolationKind::Linear, Extrapolation::Constant).unwrap(),
+            3.0
+        );
+        assert_approx_eq!(
+            f64,
+            f(0.0, InterpolationKind::Linear, Extrapolation::Linear).unwrap(),
+            1.5
+        );
+        assert_approx_eq!(
+            f64,
+            f(4.0, InterpolationKind::Linear, Extrapolation::Linear).unwrap(),
+            7.5
+        );
+        assert_approx_eq!(
+            f64,
+            f(4.0, InterpolationKind::Pchip, Extrapolation::Linear).unwrap(),
+            7.5
+        );
+        assert_approx_eq!(
+            f64,
+            f(4.0, InterpolationKind::Previous, Extrapolation::Linear).unwrap(),
+            6.0
+        );
+    }
+
+    #[test]
+    fn test_non_finite_value() {
+        let points = vec![(1.0, 3.0), (2.0, 4.5), (3.0, 6.0)];
+
+        for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            for kind in [
+                InterpolationKind::Linear,
+                InterpolationKind::Pchip,
+                InterpolationKind::Next,
+            ] {
+                assert_eq!(
+                    interpolate_points(value, &points, kind, Extrapolation::Constant),
+                    Err(InterpolationError::NonFiniteValue(value.to_string()))
+                );
+            }
+        }
+    }
 }
@@ -1,27 +1,38 @@
 use crate::metric::MetricF64;
 use crate::network::Network;
 use crate::parameters::errors::ParameterCalculationError;
-use crate::parameters::interpolate::linear_interpolation;
+use crate::parameters::interpolate::{Extrapolation, InterpolationKind, interpolate_points};
 use crate::parameters::{GeneralParameter, Parameter, ParameterMeta, ParameterName, ParameterState};
 use crate::scenario::ScenarioIndex;
 use crate::state::State;
 use crate::timestep::Timestep;
 
 /// A parameter that interpolates a value to a function with given discrete data points.
+///
+/// The `kind` of interpolation is used between the points, and `extrapolation` determines the
+/// value outside the range of the points.
 pub struct InterpolatedParameter {
     meta: ParameterMeta,
     x: MetricF64,
     points: Vec<(MetricF64, MetricF64)>,
-    error_on_bounds: bool,
+    kind: InterpolationKind,
+    extrapolation: Extrapolation,
 }
 
 impl InterpolatedParameter {
-    pub fn new(name: ParameterName, x: MetricF64, points: Vec<(MetricF64, MetricF64)>, error_on_bounds: bool) -> Self {
+    pub fn new(
+        name: ParameterName,
+        x: MetricF64,
+        points: Vec<(MetricF64, MetricF64)>,
+        kind: InterpolationKind,
+        extrapolation: Extrapolation,
+    ) -> Self {
         Self {
             meta: ParameterMeta::new(name),
             x,
             points,
-            error_on_bounds,
+            kind,
+            extrapolation,
         }
     }
 }
@@ -53,7 +64,7 @@ impl GeneralParameter<f64> for InterpolatedParameter {
             })
             .collect::<Result<Vec<_>, _>>()?;
 
-        let f = linear_interpolation(x, &points, self.error_on_bounds)?;
+        let f = interpolate_points(x, &points, self.kind, self.extrapolation)?;
 
         Ok(Some(f))
     }
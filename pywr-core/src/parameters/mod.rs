@@ -11,7 +11,12 @@ mod delay;
 mod difference;
 mod discount_factor;
 mod division;
+mod drought_trigger;
 mod errors;
+mod expression;
+mod flow_volume;
+mod forecast;
+mod hedging;
 mod hydropower;
 mod indexed_array;
 mod interpolate;
@@ -26,11 +31,17 @@ mod negativemin;
 mod offset;
 mod polynomial;
 mod profiles;
+mod pumping;
 #[cfg(feature = "pyo3")]
 mod py;
 mod rolling;
+mod rule_set;
+mod sampled;
+mod surface_area;
+mod tariff;
 mod threshold;
 mod vector;
+mod weather_demand;
 
 use std::any::Any;
 // Re-imports
@@ -57,11 +68,18 @@ pub use delay::DelayParameter;
 pub use difference::DifferenceParameter;
 pub use discount_factor::DiscountFactorParameter;
 pub use division::DivisionParameter;
+pub use drought_trigger::DroughtTriggerParameter;
 use errors::{ConstCalculationError, SimpleCalculationError};
-pub use errors::{ParameterCalculationError, ParameterSetupError};
+pub use errors::{ParameterCalculationError, ParameterSetupError, ParameterStateCloneError};
+pub use expression::{ExpressionError, ExpressionParameter};
+pub use flow_volume::{FlowVolumeParameter, VolumeFlowParameter};
+pub use forecast::{Forecast, ForecastError, ForecastMember, ForecastParameter, ForecastValue};
+pub use hedging::{HedgingOutput, HedgingParameter, HedgingRule};
 pub use hydropower::{HydropowerTargetData, HydropowerTargetParameter};
 pub use indexed_array::IndexedArrayParameter;
-pub use interpolate::{InterpolationError, interpolate, linear_interpolation};
+pub use interpolate::{
+    Extrapolation, InterpolationError, InterpolationKind, interpolate, interpolate_points, linear_interpolation,
+};
 pub use interpolated::InterpolatedParameter;
 pub use max::MaxParameter;
 pub use min::MinParameter;
@@ -73,21 +91,28 @@ pub use negativemin::NegativeMinParameter;
 pub use offset::OffsetParameter;
 pub use polynomial::Polynomial1DParameter;
 pub use profiles::{
-    DailyProfileParameter, DiurnalProfileParameter, MonthlyInterpDay, MonthlyProfileParameter, RadialBasisFunction,
-    RbfProfileParameter, RbfProfileVariableConfig, UniformDrawdownProfileParameter, WeeklyInterpDay,
+    BreakpointInterpolation, BreakpointProfileError, BreakpointProfileParameter, DailyProfileParameter,
+    DiurnalProfileParameter, LeapDayHandling, MonthlyInterpDay, MonthlyProfileParameter, RadialBasisFunction, RbfNorm,
+    RbfProfileError, RbfProfileParameter, RbfProfileVariableConfig, UniformDrawdownProfileParameter, WeeklyInterpDay,
     WeeklyProfileError, WeeklyProfileParameter, WeeklyProfileValues,
 };
+pub use pumping::{PumpingData, PumpingEnergyParameter};
 #[cfg(feature = "pyo3")]
 pub use py::{ParameterInfo, PyClassParameter, PyFuncParameter};
 pub use rolling::RollingParameter;
+pub use rule_set::{Rule, RuleCondition, RuleSetParameter};
+pub use sampled::{SampleDistribution, SampleDistributionError, SampledAnnualParameter, SampledConstantParameter};
 use std::fmt;
 use std::fmt::{Display, Formatter};
 use std::hash::{Hash, Hasher};
 use std::marker::PhantomData;
 use std::ops::Deref;
+pub use surface_area::{SurfaceAreaCurve, SurfaceAreaParameter};
+pub use tariff::{TariffBand, TariffDays, TariffParameter};
 use thiserror::Error;
 pub use threshold::{Predicate, ThresholdParameter};
 pub use vector::VectorParameter;
+pub use weather_demand::{DemandResponse, DemandSaving, DemandSensitivity, PopulationGrowth, WeatherDemandParameter};
 
 /// Simple parameter index.
 ///
@@ -467,11 +492,13 @@ impl ParameterMeta {
 pub trait ParameterState: Any + Send {
     fn as_any(&self) -> &dyn Any;
     fn as_any_mut(&mut self) -> &mut dyn Any;
+    /// Copy the state so that it can be advanced independently (e.g. for a forecast).
+    fn try_clone(&self) -> Result<Box<dyn ParameterState>, ParameterStateCloneError>;
 }
 
 impl<T> ParameterState for T
 where
-    T: Any + Send,
+    T: Any + Send + Clone,
 {
     fn as_any(&self) -> &dyn Any {
         self
@@ -479,6 +506,9 @@ where
     fn as_any_mut(&mut self) -> &mut dyn Any {
         self
     }
+    fn try_clone(&self) -> Result<Box<dyn ParameterState>, ParameterStateCloneError> {
+        Ok(Box::new(self.clone()))
+    }
 }
 
 struct ParameterStatesByType {
@@ -487,6 +517,23 @@ struct ParameterStatesByType {
     multi: Vec<Option<Box<dyn ParameterState>>>,
 }
 
+impl ParameterStatesByType {
+    fn try_clone(&self) -> Result<Self, ParameterStateCloneError> {
+        let clone_states = |states: &[Option<Box<dyn ParameterState>>]| {
+            states
+                .iter()
+                .map(|state| state.as_ref().map(|s| s.try_clone()).transpose())
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        Ok(Self {
+            f64: clone_states(&self.f64)?,
+            u64: clone_states(&self.u64)?,
+            multi: clone_states(&self.multi)?,
+        })
+    }
+}
+
 pub struct ParameterStates {
     constant: ParameterStatesByType,
     simple: ParameterStatesByType,
@@ -511,6 +558,15 @@ impl ParameterStates {
         })
     }
 
+    /// Copy the current states so that they can be advanced independently (e.g. for a forecast).
+    pub fn try_clone(&self) -> Result<Self, ParameterStateCloneError> {
+        Ok(Self {
+            constant: self.constant.try_clone()?,
+            simple: self.simple.try_clone()?,
+            general: self.general.try_clone()?,
+        })
+    }
+
     pub fn get_f64_state(&self, index: ParameterIndex<f64>) -> Option<&Option<Box<dyn ParameterState>>> {
         match index {
             ParameterIndex::Const(idx) => self.constant.f64.get(*idx.deref()),
@@ -876,6 +932,8 @@ impl From<ParameterIndex<MultiValue>> for ParameterType {
 pub enum VariableParameterError {
     #[error("Incorrect number of values provided for parameter. Expected {expected}, received {received}")]
     IncorrectNumberOfValues { expected: usize, received: usize },
+    #[error("RBF profile error: {0}")]
+    RbfProfileError(#[from] RbfProfileError),
 }
 
 /// A parameter that can be optimised.
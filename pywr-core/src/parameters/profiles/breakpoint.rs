@@ -0,0 +1,318 @@
+use crate::parameters::errors::SimpleCalculationError;
+use crate::parameters::{Parameter, ParameterMeta, ParameterName, ParameterState, SimpleParameter};
+use crate::scenario::ScenarioIndex;
+use crate::state::SimpleParameterValues;
+use crate::timestep::Timestep;
+use chrono::{Datelike, NaiveDate, NaiveDateTime, Timelike};
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum BreakpointProfileError {
+    #[error("At least one breakpoint is required")]
+    NoBreakpoints,
+    #[error("The number of dates ({dates}) does not match the number of values ({values})")]
+    LengthMismatch { dates: usize, values: usize },
+    #[error("Invalid breakpoint date: month {month}, day {day}")]
+    InvalidDate { month: u32, day: u32 },
+    #[error("A breakpoint on the 29th February requires the 366-day calendar")]
+    LeapDay,
+    #[error("The breakpoint dates must be in increasing order within the year")]
+    NotIncreasing,
+}
+
+/// How the value of a [`BreakpointProfileParameter`] changes between breakpoints.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BreakpointInterpolation {
+    /// The value of the latest breakpoint applies until the next breakpoint.
+    Step,
+    /// The value is linearly interpolated between breakpoints.
+    Linear,
+}
+
+/// The calendar used to position breakpoints and time-steps within a year.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LeapDayHandling {
+    /// Use the actual calendar of each year. The 29th February is included in leap years, and the
+    /// interval between breakpoints either side of it is one day longer.
+    Calendar,
+    /// Use a 365-day year. The 29th February has the same value as the 28th February.
+    NoLeap,
+    /// Use a 366-day year in which non-leap years skip the 29th February. This is the same
+    /// calendar as a [`crate::parameters::DailyProfileParameter`], and is required to define a
+    /// breakpoint on the 29th February.
+    Calendar366,
+}
+
+fn is_leap_year(year: i32) -> bool {
+    NaiveDate::from_ymd_opt(year, 1, 1).is_some_and(|d| d.leap_year())
+}
+
+impl LeapDayHandling {
+    /// The number of days in the year.
+    fn year_length(&self, year: i32) -> f64 {
+        match self {
+            Self::Calendar if is_leap_year(year) => 366.0,
+            Self::Calendar | Self::NoLeap => 365.0,
+            Self::Calendar366 => 366.0,
+        }
+    }
+
+    /// The zero-based day of the year of a breakpoint in `year`.
+    fn breakpoint_position(&self, month: u32, day: u32, year: i32) -> f64 {
+        let year = match self {
+            Self::Calendar => year,
+            Self::NoLeap => 2021,
+            Self::Calendar366 => 2020,
+        };
+        // Breakpoints are validated on construction, and are never on the 29th February in a
+        // non-leap year.
+        let date = NaiveDate::from_ymd_opt(year, month, day).expect("Invalid breakpoint date");
+        date.ordinal0() as f64
+    }
+
+    /// The zero-based day of the year of a date, including the fraction of the day.
+    fn position(&self, date: &NaiveDateTime) -> f64 {
+        let mut day = date.ordinal0();
+        let leap_year = date.date().leap_year();
+        match self {
+            Self::Calendar => {}
+            Self::NoLeap if leap_year && day >= 59 => day -= 1,
+            Self::NoLeap => {}
+            Self::Calendar366 if !leap_year && day >= 59 => day += 1,
+            Self::Calendar366 => {}
+        }
+        day as f64 + date.num_seconds_from_midnight() as f64 / 86400.0
+    }
+}
+
+/// A parameter that defines an annual profile by values at breakpoint dates.
+///
+/// The breakpoints are given as a month and day in increasing order within the year, and the
+/// profile repeats each year. Between breakpoints the value is either the value of the latest
+/// breakpoint or linearly interpolated between the breakpoints, and before the first breakpoint
+/// of the year the profile continues from the last breakpoint of the previous year. The position
+/// of a time-step in the year includes the time of day, so the profile can be used with sub-daily
+/// time-steps.
+pub struct BreakpointProfileParameter {
+    meta: ParameterMeta,
+    values: Vec<f64>,
+    /// The position of each breakpoint in a non-leap and a leap year.
+    positions: [Vec<f64>; 2],
+    interpolation: BreakpointInterpolation,
+    leap_day: LeapDayHandling,
+}
+
+impl BreakpointProfileParameter {
+    /// Create a new profile from the `(month, day)` of each breakpoint and its value.
+    pub fn new(
+        name: ParameterName,
+        dates: Vec<(u32, u32)>,
+        values: Vec<f64>,
+        interpolation: BreakpointInterpolation,
+        leap_day: LeapDayHandling,
+    ) -> Result<Self, BreakpointProfileError> {
+        if dates.is_empty() {
+            return Err(BreakpointProfileError::NoBreakpoints);
+        }
+
+        if dates.len() != values.len() {
+            return Err(BreakpointProfileError::LengthMismatch {
+                dates: dates.len(),
+                values: values.len(),
+            });
+        }
+
+        for &(month, day) in &dates {
+            if NaiveDate::from_ymd_opt(2020, month, day).is_none() {
+                return Err(BreakpointProfileError::InvalidDate { month, day });
+            }
+            if (month, day) == (2, 29) && leap_day != LeapDayHandling::Calendar366 {
+                return Err(BreakpointProfileError::LeapDay);
+            }
+        }
+
+        if dates.windows(2).any(|w| w[0] >= w[1]) {
+            return Err(BreakpointProfileError::NotIncreasing);
+        }
+
+        let positions = [2021, 2020].map(|year| {
+            dates
+                .iter()
+                .map(|&(month, day)| leap_day.breakpoint_position(month, day, year))
+                .collect()
+        });
+
+        Ok(Self {
+            meta: ParameterMeta::new(name),
+            values,
+            positions,
+            interpolation,
+            leap_day,
+        })
+    }
+
+    /// The positions of the breakpoints in the given year.
+    fn positions(&self, year: i32) -> &[f64] {
+        &self.positions[usize::from(is_leap_year(year))]
+    }
+
+    fn value(&self, date: &NaiveDateTime) -> f64 {
+        let year = date.year();
+        let position = self.leap_day.position(date);
+        let positions = self.positions(year);
+
+        let last = self.values.len() - 1;
+        // The number of breakpoints at or before the date
+        let i = positions.partition_point(|p| *p <= position);
+
+        match self.interpolation {
+            BreakpointInterpolation::Step => self.values[if i == 0 { last } else { i - 1 }],
+            BreakpointInterpolation::Linear => {
+                let (p0, v0, p1, v1) = if i == 0 {
+                    // Interpolate from the last breakpoint of the previous year
+                    let p0 = self.positions(year - 1)[last] - self.leap_day.year_length(year - 1);
+                    (p0, self.values[last], positions[0], self.values[0])
+                } else if i > last {
+                    // Interpolate to the first breakpoint of the next year
+                    let p1 = self.positions(year + 1)[0] + self.leap_day.year_length(year);
+                    (positions[last], self.values[last], p1, self.values[0])
+                } else {
+                    (positions[i - 1], self.values[i - 1], positions[i], self.values[i])
+                };
+
+                v0 + (v1 - v0) * (position - p0) / (p1 - p0)
+            }
+        }
+    }
+}
+
+impl Parameter for BreakpointProfileParameter {
+    fn meta(&self) -> &ParameterMeta {
+        &self.meta
+    }
+}
+
+impl SimpleParameter<f64> for BreakpointProfileParameter {
+    fn before(
+        &self,
+        timestep: &Timestep,
+        _scenario_index: &ScenarioIndex,
+        _values: &SimpleParameterValues,
+        _internal_state: &mut Option<Box<dyn ParameterState>>,
+    ) -> Result<Option<f64>, SimpleCalculationError> {
+        Ok(Some(self.value(&timestep.date)))
+    }
+
+    fn as_parameter(&self) -> &dyn Parameter
+    where
+        Self: Sized,
+    {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BreakpointInterpolation, BreakpointProfileError, BreakpointProfileParameter, LeapDayHandling};
+    use chrono::{NaiveDate, NaiveDateTime};
+    use float_cmp::assert_approx_eq;
+
+    fn date(year: i32, month: u32, day: u32, hour: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(year, month, day)
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap()
+    }
+
+    /// A profile of 2.0 from 15th March and 1.0 from 1st July.
+    fn profile(interpolation: BreakpointInterpolation, leap_day: LeapDayHandling) -> BreakpointProfileParameter {
+        BreakpointProfileParameter::new(
+            "profile".into(),
+            vec![(3, 15), (7, 1)],
+            vec![2.0, 1.0],
+            interpolation,
+            leap_day,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_step() {
+        let p = profile(BreakpointInterpolation::Step, LeapDayHandling::Calendar);
+
+        assert_eq!(p.value(&date(2021, 1, 1, 0)), 1.0);
+        assert_eq!(p.value(&date(2021, 3, 14, 23)), 1.0);
+        assert_eq!(p.value(&date(2021, 3, 15, 0)), 2.0);
+        assert_eq!(p.value(&date(2021, 6, 30, 12)), 2.0);
+        assert_eq!(p.value(&date(2021, 7, 1, 0)), 1.0);
+        assert_eq!(p.value(&date(2021, 12, 31, 0)), 1.0);
+    }
+
+    #[test]
+    fn test_linear() {
+        let p = profile(BreakpointInterpolation::Linear, LeapDayHandling::Calendar);
+
+        // There are 108 days from 15th March to 1st July
+        assert_eq!(p.value(&date(2021, 3, 15, 0)), 2.0);
+        assert_approx_eq!(f64, p.value(&date(2021, 4, 15, 0)), 2.0 - 31.0 / 108.0);
+        // Sub-daily time-steps are interpolated within the day
+        assert_approx_eq!(f64, p.value(&date(2021, 4, 15, 12)), 2.0 - 31.5 / 108.0);
+        assert_eq!(p.value(&date(2021, 7, 1, 0)), 1.0);
+
+        // There are 257 days from 1st July to 15th March, with 184 of them before 1st January
+        assert_approx_eq!(f64, p.value(&date(2021, 1, 1, 0)), 1.0 + 184.0 / 257.0);
+        assert_approx_eq!(f64, p.value(&date(2021, 12, 31, 0)), 1.0 + 183.0 / 257.0);
+    }
+
+    #[test]
+    fn test_leap_day_handling() {
+        // In a leap year there are 109 days from 15th March to 1st July
+        let p = profile(BreakpointInterpolation::Linear, LeapDayHandling::Calendar);
+        assert_approx_eq!(f64, p.value(&date(2020, 1, 1, 0)), 1.0 + 184.0 / 258.0);
+        assert_approx_eq!(f64, p.value(&date(2020, 3, 1, 0)), 1.0 + 244.0 / 258.0);
+
+        // The 29th February is the same as the 28th February
+        let p = profile(BreakpointInterpolation::Linear, LeapDayHandling::NoLeap);
+        assert_eq!(p.value(&date(2020, 2, 29, 0)), p.value(&date(2020, 2, 28, 0)));
+        assert_eq!(p.value(&date(2020, 3, 1, 0)), p.value(&date(2021, 3, 1, 0)));
+
+        // A breakpoint on the 29th February is skipped in non-leap years
+        let p = BreakpointProfileParameter::new(
+            "profile".into(),
+            vec![(2, 29), (3, 1)],
+            vec![5.0, 1.0],
+            BreakpointInterpolation::Step,
+            LeapDayHandling::Calendar366,
+        )
+        .unwrap();
+        assert_eq!(p.value(&date(2020, 2, 29, 0)), 5.0);
+        assert_eq!(p.value(&date(2021, 2, 28, 0)), 1.0);
+        assert_eq!(p.value(&date(2021, 3, 1, 0)), 1.0);
+    }
+
+    #[test]
+    fn test_invalid_breakpoints() {
+        let new = |dates: Vec<(u32, u32)>, leap_day| {
+            let values = vec![1.0; dates.len()];
+            BreakpointProfileParameter::new("profile".into(), dates, values, BreakpointInterpolation::Step, leap_day)
+                .err()
+        };
+
+        assert_eq!(
+            new(vec![], LeapDayHandling::Calendar),
+            Some(BreakpointProfileError::NoBreakpoints)
+        );
+        assert_eq!(
+            new(vec![(4, 31)], LeapDayHandling::Calendar),
+            Some(BreakpointProfileError::InvalidDate { month: 4, day: 31 })
+        );
+        assert_eq!(
+            new(vec![(2, 29)], LeapDayHandling::NoLeap),
+            Some(BreakpointProfileError::LeapDay)
+        );
+        assert_eq!(
+            new(vec![(7, 1), (3, 15)], LeapDayHandling::Calendar),
+            Some(BreakpointProfileError::NotIncreasing)
+        );
+    }
+}
@@ -1,3 +1,4 @@
+mod breakpoint;
 mod daily;
 mod diurnal;
 mod monthly;
@@ -5,9 +6,10 @@ mod rbf;
 mod uniform_drawdown;
 mod weekly;
 
+pub use breakpoint::{BreakpointInterpolation, BreakpointProfileError, BreakpointProfileParameter, LeapDayHandling};
 pub use daily::DailyProfileParameter;
 pub use diurnal::DiurnalProfileParameter;
 pub use monthly::{MonthlyInterpDay, MonthlyProfileParameter};
-pub use rbf::{RadialBasisFunction, RbfProfileParameter, RbfProfileVariableConfig};
+pub use rbf::{RadialBasisFunction, RbfNorm, RbfProfileError, RbfProfileParameter, RbfProfileVariableConfig};
 pub use uniform_drawdown::UniformDrawdownProfileParameter;
 pub use weekly::{WeeklyInterpDay, WeeklyProfileError, WeeklyProfileParameter, WeeklyProfileValues};
@@ -7,6 +7,15 @@ use crate::scenario::ScenarioIndex;
 use crate::state::SimpleParameterValues;
 use crate::timestep::Timestep;
 use nalgebra::DMatrix;
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum RbfProfileError {
+    #[error(
+        "The radial basis function interpolation matrix is singular; try a different function, norm or smoothing, or different points"
+    )]
+    SingularMatrix,
+}
 
 pub struct RbfProfileVariableConfig {
     days_of_year_range: Option<u32>,
@@ -38,10 +47,16 @@ impl RbfProfileVariableConfig {
 
 /// A parameter that interpolates between a set of points using a radial basis function to
 /// create a daily profile.
+///
+/// The interpolation follows SciPy's `Rbf` class. A non-zero `smooth` value relaxes the
+/// interpolation so that the profile does not have to pass exactly through the points, and the
+/// `norm` is the measure of the distance between days of the year.
 pub struct RbfProfileParameter {
     meta: ParameterMeta,
     points: Vec<(u32, f64)>,
     function: RadialBasisFunction,
+    smooth: f64,
+    norm: RbfNorm,
 }
 
 /// The internal state of the RbfProfileParameter.
@@ -58,14 +73,15 @@ struct RbfProfileInternalState {
 }
 
 impl RbfProfileInternalState {
-    fn new(points: &[(u32, f64)], function: &RadialBasisFunction) -> Self {
-        let profile = interpolate_rbf_profile(points, function);
+    fn new(parameter: &RbfProfileParameter) -> Result<Self, RbfProfileError> {
+        let profile =
+            interpolate_rbf_profile(&parameter.points, &parameter.function, parameter.smooth, parameter.norm)?;
 
-        Self {
+        Ok(Self {
             profile,
             points_x: None,
             points_y: None,
-        }
+        })
     }
 
     /// Update the x values of the points.
@@ -82,9 +98,10 @@ impl RbfProfileInternalState {
         self.points_y = Some(y);
     }
 
-    /// Update the profile with the given points used as default. Any locally stored x and y values are
+    /// Update the profile with the parameter's points used as default. Any locally stored x and y values are
     /// used in preference to the default points when interpolating the profile.
-    fn update_profile(&mut self, points: &[(u32, f64)], function: &RadialBasisFunction) {
+    fn update_profile(&mut self, parameter: &RbfProfileParameter) -> Result<(), RbfProfileError> {
+        let points = &parameter.points;
         let points: Vec<_> = match (&self.points_x, &self.points_y) {
             (Some(x), Some(y)) => x.iter().zip(y.iter()).map(|(x, y)| (*x, *y)).collect(),
             (Some(x), None) => x
@@ -96,16 +113,25 @@ impl RbfProfileInternalState {
             (None, None) => points.to_vec(),
         };
 
-        self.profile = interpolate_rbf_profile(&points, function);
+        self.profile = interpolate_rbf_profile(&points, &parameter.function, parameter.smooth, parameter.norm)?;
+        Ok(())
     }
 }
 
 impl RbfProfileParameter {
-    pub fn new(name: ParameterName, points: Vec<(u32, f64)>, function: RadialBasisFunction) -> Self {
+    pub fn new(
+        name: ParameterName,
+        points: Vec<(u32, f64)>,
+        function: RadialBasisFunction,
+        smooth: f64,
+        norm: RbfNorm,
+    ) -> Self {
         Self {
             meta: ParameterMeta::new(name),
             points,
             function,
+            smooth,
+            norm,
         }
     }
 }
@@ -120,7 +146,11 @@ impl Parameter for RbfProfileParameter {
         _timesteps: &[Timestep],
         _scenario_index: &ScenarioIndex,
     ) -> Result<Option<Box<dyn ParameterState>>, ParameterSetupError> {
-        let internal_state = RbfProfileInternalState::new(&self.points, &self.function);
+        let internal_state =
+            RbfProfileInternalState::new(self).map_err(|source| ParameterSetupError::RbfProfileError {
+                name: self.meta.name.to_string(),
+                source,
+            })?;
         Ok(Some(Box::new(internal_state)))
     }
     fn as_f64_variable(&self) -> Option<&dyn VariableParameter<f64>> {
@@ -192,7 +222,7 @@ impl VariableParameter<f64> for RbfProfileParameter {
             let value = downcast_internal_state_mut::<RbfProfileInternalState>(internal_state);
 
             value.update_y(values.to_vec());
-            value.update_profile(&self.points, &self.function);
+            value.update_profile(self)?;
 
             Ok(())
         } else {
@@ -246,7 +276,7 @@ impl VariableParameter<u32> for RbfProfileParameter {
             let value = downcast_internal_state_mut::<RbfProfileInternalState>(internal_state);
 
             value.update_x(values.to_vec());
-            value.update_profile(&self.points, &self.function);
+            value.update_profile(self)?;
 
             Ok(())
         } else {
@@ -315,6 +345,8 @@ impl RadialBasisFunction {
             RadialBasisFunction::Linear => r,
             RadialBasisFunction::Cubic => r.powi(3),
             RadialBasisFunction::Quintic => r.powi(5),
+            // The limit of r^2 ln(r) as r tends to zero is zero
+            RadialBasisFunction::ThinPlateSpline if r == 0.0 => 0.0,
             RadialBasisFunction::ThinPlateSpline => r.powi(2) * r.ln(),
             RadialBasisFunction::Gaussian { epsilon } => (-(epsilon * r).powi(2)).exp(),
             RadialBasisFunction::MultiQuadric { epsilon } => (1.0 + (epsilon * r).powi(2)).sqrt(),
@@ -323,15 +355,47 @@ impl RadialBasisFunction {
     }
 }
 
+/// The measure of distance used for radial basis function interpolation.
+///
+/// These are the distance measures of SciPy's `Rbf` class that are distinct for one-dimensional
+/// points. The other norms supported by SciPy (`cityblock`, `chebyshev` and `minkowski`) are
+/// equal to the Euclidean distance in one dimension.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum RbfNorm {
+    /// The absolute difference between two points.
+    #[default]
+    Euclidean,
+    /// The square of the difference between two points.
+    SquaredEuclidean,
+}
+
+impl RbfNorm {
+    fn distance(&self, a: f64, b: f64) -> f64 {
+        match self {
+            RbfNorm::Euclidean => (a - b).abs(),
+            RbfNorm::SquaredEuclidean => (a - b).powi(2),
+        }
+    }
+}
+
 /// Perform radial-basis function interpolation from the given points.
 ///
-/// The provided points are a tuple of observed (x, y) values.
-fn interpolate_rbf<const N: usize>(points: &[(f64, f64)], function: &RadialBasisFunction, x: &[f64; N]) -> [f64; N] {
+/// The provided points are a tuple of observed (x, y) values. As in SciPy's `Rbf` class, the
+/// `smooth` value is subtracted from the diagonal of the interpolation matrix; zero gives an
+/// exact interpolation through the points. An error is returned if the matrix is singular.
+fn interpolate_rbf<const N: usize>(
+    points: &[(f64, f64)],
+    function: &RadialBasisFunction,
+    smooth: f64,
+    norm: RbfNorm,
+    x: &[f64; N],
+) -> Result<[f64; N], RbfProfileError> {
     let n = points.len();
 
     let matrix = DMatrix::from_fn(n, n, |r, c| {
-        let r = (points[c].0 - points[r].0).abs();
-        function.compute(r)
+        let distance = norm.distance(points[c].0, points[r].0);
+        let value = function.compute(distance);
+        if r == c { value - smooth } else { value }
     });
 
     let b = DMatrix::from_fn(n, 1, |r, _| points[r].1);
@@ -339,7 +403,8 @@ fn interpolate_rbf<const N: usize>(points: &[(f64, f64)], function: &RadialBasis
     let weights = matrix
         .lu()
         .solve(&b)
-        .expect("Failed to solve RBF system for interpolation weights.");
+        .filter(|w| w.iter().all(|v| v.is_finite()))
+        .ok_or(RbfProfileError::SingularMatrix)?;
 
     let mut profile = [f64::default(); N];
 
@@ -348,14 +413,13 @@ fn interpolate_rbf<const N: usize>(points: &[(f64, f64)], function: &RadialBasis
             .iter()
             .enumerate()
             .map(|(i, p)| {
-                let r = (doy - p.0).abs();
-                let distance = function.compute(r);
-                distance * weights[(i, 0)]
+                let distance = norm.distance(doy, p.0);
+                function.compute(distance) * weights[(i, 0)]
             })
             .sum();
     }
 
-    profile
+    Ok(profile)
 }
 
 /// Calculate the interpolation weights for the given points.
@@ -363,7 +427,12 @@ fn interpolate_rbf<const N: usize>(points: &[(f64, f64)], function: &RadialBasis
 /// This method repeats the point 365 days before and after the user provided points. This
 /// helps create a cyclic interpolation suitable for a annual profile. It then repeats the
 /// value for the 58th day to create a daily profile 366 days long.
-fn interpolate_rbf_profile(points: &[(u32, f64)], function: &RadialBasisFunction) -> [f64; 366] {
+fn interpolate_rbf_profile(
+    points: &[(u32, f64)],
+    function: &RadialBasisFunction,
+    smooth: f64,
+    norm: RbfNorm,
+) -> Result<[f64; 366], RbfProfileError> {
     // Replicate the points in the year before and after.
     let year_before = points.iter().map(|p| (p.0 as f64 - 365.0, p.1));
     let year_after = points.iter().map(|p| (p.0 as f64 + 365.0, p.1));
@@ -376,18 +445,20 @@ fn interpolate_rbf_profile(points: &[(u32, f64)], function: &RadialBasisFunction
     for (i, v) in x_out.iter_mut().enumerate() {
         *v = i as f64;
     }
-    let short_profile = interpolate_rbf(&points, function, &x_out);
+    let short_profile = interpolate_rbf(&points, function, smooth, norm, &x_out)?;
 
     let (start, end) = short_profile.split_at(58);
 
     let profile = [start, &[end[0]], end].concat();
 
-    profile.try_into().unwrap()
+    Ok(profile.try_into().unwrap())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::parameters::profiles::rbf::{RadialBasisFunction, interpolate_rbf, interpolate_rbf_profile};
+    use crate::parameters::profiles::rbf::{
+        RadialBasisFunction, RbfNorm, RbfProfileError, interpolate_rbf, interpolate_rbf_profile,
+    };
     use float_cmp::{F64Margin, assert_approx_eq};
     use std::f64::consts::PI;
 
@@ -424,7 +495,7 @@ mod tests {
         }
 
         let rbf = RadialBasisFunction::Gaussian { epsilon: 3.0 };
-        let f_interp = interpolate_rbf(&points, &rbf, &x_out);
+        let f_interp = interpolate_rbf(&points, &rbf, 0.0, RbfNorm::Euclidean, &x_out).unwrap();
 
         // Values computed from the Scipy RBF interpolation function for the same problem.
         let f_expected = [
@@ -476,7 +547,7 @@ mod tests {
         let points: Vec<(u32, f64)> = vec![(90, 0.5), (180, 0.3), (270, 0.7)];
 
         let rbf = RadialBasisFunction::MultiQuadric { epsilon: 1.0 / 50.0 };
-        let f_interp = interpolate_rbf_profile(&points, &rbf);
+        let f_interp = interpolate_rbf_profile(&points, &rbf, 0.0, RbfNorm::Euclidean).unwrap();
 
         let f_expected = [
             0.69464463, 0.69308183, 0.69150736, 0.68992139, 0.68832406, 0.68671551, 0.68509589, 0.68346531, 0.68182389,
@@ -526,4 +597,83 @@ mod tests {
             assert_approx_eq!(f64, *i, e, F64Margin { ulps: 2, epsilon: 1e-6 });
         }
     }
+
+    /// Test smoothing against the solution of a two point system.
+    ///
+    /// With a linear function and a smoothing of 0.5 the weights solve
+    /// `[[-0.5, 1.0], [1.0, -0.5]] w = [0.0, 1.0]`, which gives `w = [4/3, 2/3]`.
+    #[test]
+    fn test_rbf_smoothing() {
+        let points = [(0.0, 0.0), (1.0, 1.0)];
+        let x_out = [0.0, 0.5, 1.0];
+
+        let f_interp = interpolate_rbf(&points, &RadialBasisFunction::Linear, 0.5, RbfNorm::Euclidean, &x_out).unwrap();
+        let f_expected = [2.0 / 3.0, 1.0, 4.0 / 3.0];
+
+        for (i, e) in f_interp.iter().zip(f_expected) {
+            assert_approx_eq!(f64, *i, e, F64Margin { ulps: 2, epsilon: 1e-9 });
+        }
+    }
+
+    /// The polynomial and thin-plate functions, and the squared Euclidean norm, interpolate
+    /// through the points without smoothing.
+    ///
+    /// The linear function is not used with the squared Euclidean norm because the squared
+    /// distances of four or more points give a singular interpolation matrix.
+    #[test]
+    fn test_rbf_functions_and_norms() {
+        let points: Vec<(f64, f64)> = vec![(1.0, 0.5), (3.0, 0.3), (4.0, 0.9), (7.0, 0.6)];
+        let x_out = [1.0, 3.0, 4.0, 7.0];
+
+        let cases = [
+            (RadialBasisFunction::Linear, vec![RbfNorm::Euclidean]),
+            (
+                RadialBasisFunction::Cubic,
+                vec![RbfNorm::Euclidean, RbfNorm::SquaredEuclidean],
+            ),
+            (
+                RadialBasisFunction::Quintic,
+                vec![RbfNorm::Euclidean, RbfNorm::SquaredEuclidean],
+            ),
+            (
+                RadialBasisFunction::ThinPlateSpline,
+                vec![RbfNorm::Euclidean, RbfNorm::SquaredEuclidean],
+            ),
+        ];
+
+        for (function, norms) in cases {
+            for norm in norms {
+                let f_interp = interpolate_rbf(&points, &function, 0.0, norm, &x_out).unwrap();
+
+                for (i, (_, e)) in f_interp.iter().zip(&points) {
+                    assert_approx_eq!(f64, *i, *e, F64Margin { ulps: 2, epsilon: 1e-6 });
+                }
+            }
+        }
+    }
+
+    /// A singular interpolation matrix is an error rather than a panic.
+    #[test]
+    fn test_rbf_singular_matrix() {
+        // The squared distances of four or more points with the linear function are singular
+        let points: Vec<(f64, f64)> = vec![(1.0, 0.5), (3.0, 0.3), (4.0, 0.9), (7.0, 0.6)];
+        let x_out = [1.0, 3.0, 4.0, 7.0];
+        assert_eq!(
+            interpolate_rbf(
+                &points,
+                &RadialBasisFunction::Linear,
+                0.0,
+                RbfNorm::SquaredEuclidean,
+                &x_out
+            ),
+            Err(RbfProfileError::SingularMatrix)
+        );
+
+        // Repeated points are singular
+        let points: Vec<(f64, f64)> = vec![(1.0, 0.5), (1.0, 0.5)];
+        assert_eq!(
+            interpolate_rbf(&points, &RadialBasisFunction::Cubic, 0.0, RbfNorm::Euclidean, &x_out),
+            Err(RbfProfileError::SingularMatrix)
+        );
+    }
 }
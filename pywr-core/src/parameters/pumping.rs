@@ -0,0 +1,213 @@
+use crate::metric::MetricF64;
+use crate::network::Network;
+use crate::parameters::errors::ParameterCalculationError;
+use crate::parameters::{GeneralParameter, Parameter, ParameterMeta, ParameterName, ParameterState};
+use crate::scenario::ScenarioIndex;
+use crate::state::{MultiValue, State};
+use crate::timestep::Timestep;
+use crate::utils::pumping_energy_calculation;
+use std::collections::HashMap;
+
+pub struct PumpingData {
+    /// The flow being pumped.
+    pub flow: MetricF64,
+    /// The head the flow is pumped against.
+    pub head: MetricF64,
+    pub efficiency: Option<f64>,
+    /// The cost per unit of energy.
+    pub tariff: Option<MetricF64>,
+    /// The carbon emissions per unit of energy.
+    pub carbon_intensity: Option<MetricF64>,
+    pub water_density: Option<f64>,
+    pub flow_unit_conversion: Option<f64>,
+    pub energy_unit_conversion: Option<f64>,
+}
+
+/// A parameter that computes the energy, cost and carbon emissions of pumping.
+///
+/// The energy required per unit of flow is calculated from the head and efficiency of the pump
+/// (see [`pumping_energy_calculation`]). This is converted to a cost and carbon emissions using
+/// the optional `tariff` and `carbon_intensity` metrics respectively.
+///
+/// # Values
+///
+/// Before the solve, when the flow is not yet known, the parameter returns the intensities per
+/// unit of flow over the time-step. These can be used as a cost in the linear program.
+/// - `energy_intensity`: The energy required to pump a unit of flow.
+/// - `cost_intensity`: The cost of pumping a unit of flow.
+/// - `carbon_intensity`: The carbon emitted pumping a unit of flow.
+///
+/// After the solve, the parameter additionally returns the totals for the time-step using
+/// the `flow` metric.
+/// - `energy`: The energy used.
+/// - `cost`: The cost of the energy used.
+/// - `carbon`: The carbon emitted.
+///
+pub struct PumpingEnergyParameter {
+    meta: ParameterMeta,
+    flow: MetricF64,
+    head: MetricF64,
+    efficiency: f64,
+    tariff: Option<MetricF64>,
+    carbon_intensity: Option<MetricF64>,
+    water_density: f64,
+    flow_unit_conversion: f64,
+    energy_unit_conversion: f64,
+}
+
+impl PumpingEnergyParameter {
+    pub fn new(name: ParameterName, pumping_data: PumpingData) -> Self {
+        Self {
+            meta: ParameterMeta::new(name),
+            flow: pumping_data.flow,
+            head: pumping_data.head,
+            efficiency: pumping_data.efficiency.unwrap_or(1.0),
+            tariff: pumping_data.tariff,
+            carbon_intensity: pumping_data.carbon_intensity,
+            water_density: pumping_data.water_density.unwrap_or(1000.0),
+            flow_unit_conversion: pumping_data.flow_unit_conversion.unwrap_or(1.0),
+            energy_unit_conversion: pumping_data.energy_unit_conversion.unwrap_or(1e-6),
+        }
+    }
+
+    /// Compute the energy, cost and carbon intensities for pumping a unit of flow.
+    fn intensities(
+        &self,
+        timestep: &Timestep,
+        model: &Network,
+        state: &State,
+    ) -> Result<HashMap<String, f64>, ParameterCalculationError> {
+        if self.efficiency <= 0.0 {
+            return Err(ParameterCalculationError::Internal {
+                message: "The pumping efficiency must be greater than zero".into(),
+            });
+        }
+
+        let head = self.head.get_value(model, state)?.max(0.0);
+        let energy = pumping_energy_calculation(
+            1.0,
+            head,
+            self.efficiency,
+            self.flow_unit_conversion,
+            self.energy_unit_conversion,
+            self.water_density,
+        ) * timestep.days();
+
+        let tariff = match &self.tariff {
+            Some(tariff) => tariff.get_value(model, state)?,
+            None => 0.0,
+        };
+        let carbon_intensity = match &self.carbon_intensity {
+            Some(carbon_intensity) => carbon_intensity.get_value(model, state)?,
+            None => 0.0,
+        };
+
+        let mut values = HashMap::new();
+        values.insert("energy_intensity".to_string(), energy);
+        values.insert("cost_intensity".to_string(), energy * tariff);
+        values.insert("carbon_intensity".to_string(), energy * carbon_intensity);
+        Ok(values)
+    }
+}
+
+impl Parameter for PumpingEnergyParameter {
+    fn meta(&self) -> &ParameterMeta {
+        &self.meta
+    }
+}
+
+impl GeneralParameter<MultiValue> for PumpingEnergyParameter {
+    fn before(
+        &self,
+        timestep: &Timestep,
+        _scenario_index: &ScenarioIndex,
+        model: &Network,
+        state: &State,
+        _internal_state: &mut Option<Box<dyn ParameterState>>,
+    ) -> Result<Option<MultiValue>, ParameterCalculationError> {
+        let values = self.intensities(timestep, model, state)?;
+        Ok(Some(MultiValue::new(values, HashMap::new())))
+    }
+
+    fn after(
+        &self,
+        timestep: &Timestep,
+        _scenario_index: &ScenarioIndex,
+        model: &Network,
+        state: &State,
+        _internal_state: &mut Option<Box<dyn ParameterState>>,
+    ) -> Result<Option<MultiValue>, ParameterCalculationError> {
+        let flow = self.flow.get_value(model, state)?;
+        let mut values = self.intensities(timestep, model, state)?;
+
+        for (total, intensity) in [
+            ("energy", "energy_intensity"),
+            ("cost", "cost_intensity"),
+            ("carbon", "carbon_intensity"),
+        ] {
+            let value = flow * values[intensity];
+            values.insert(total.to_string(), value);
+        }
+
+        Ok(Some(MultiValue::new(values, HashMap::new())))
+    }
+
+    fn as_parameter(&self) -> &dyn Parameter
+    where
+        Self: Sized,
+    {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PumpingData, PumpingEnergyParameter};
+    use crate::metric::MetricF64;
+    use crate::parameters::{ParameterName, ParameterReturnValue};
+    use crate::recorders::AssertionF64Recorder;
+    use crate::test_utils::{run_all_solvers, simple_model};
+    use ndarray::Array2;
+
+    /// Pumping the link's flow against a 50 m head with an efficiency of 0.8.
+    #[test]
+    fn test_pumping_energy() {
+        let mut model = simple_model(1, None);
+        let network = model.network_mut();
+
+        let link_idx = network.get_node_index_by_name("link", None).unwrap();
+
+        let data = PumpingData {
+            flow: MetricF64::NodeInFlow(link_idx),
+            head: 50.0.into(),
+            efficiency: Some(0.8),
+            tariff: Some(0.2.into()),
+            carbon_intensity: Some(0.5.into()),
+            water_density: None,
+            flow_unit_conversion: Some(1e3),
+            energy_unit_conversion: Some(1e-6),
+        };
+        let p = PumpingEnergyParameter::new(ParameterName::new("pumping", None), data);
+        let idx = network.add_multi_value_parameter(Box::new(p)).unwrap();
+
+        // The flow through the link is the inflow (1.0 + i) limited by the demand of 12.0.
+        // Energy (MJ) = flow * 1e3 * 1000 * 9.81 * 50 * 1e-6 / 0.8
+        let num_timesteps = model.domain().time().timesteps().len();
+        let energy = Array2::from_shape_fn((num_timesteps, 1), |(i, _)| {
+            (1.0 + i as f64).min(12.0) * 1e3 * 1000.0 * 9.81 * 50.0 * 1e-6 / 0.8
+        });
+
+        for (key, factor) in [("energy", 1.0), ("cost", 0.2), ("carbon", 0.5)] {
+            let recorder = AssertionF64Recorder::new(
+                key,
+                idx.clone().into_metric_f64(key, ParameterReturnValue::After),
+                energy.mapv(|e| e * factor),
+                None,
+                Some(1e-3),
+            );
+            model.network_mut().add_recorder(Box::new(recorder)).unwrap();
+        }
+
+        run_all_solvers(&model, &[], &[], &[]);
+    }
+}
@@ -2,13 +2,14 @@ use super::{GeneralParameter, Parameter, ParameterMeta, ParameterName, Parameter
 use crate::metric::{MetricF64, MetricU64};
 use crate::network::Network;
 use crate::parameters::downcast_internal_state_mut;
-use crate::parameters::errors::{ParameterCalculationError, ParameterSetupError};
+use crate::parameters::errors::{ParameterCalculationError, ParameterSetupError, ParameterStateCloneError};
 use crate::scenario::ScenarioIndex;
 use crate::state::{MultiValue, State};
 use ahash::RandomState;
 use pyo3::exceptions::PyKeyError;
 use pyo3::prelude::*;
 use pyo3::types::{PyDict, PyTuple};
+use std::any::Any;
 use std::collections::HashMap;
 
 /// Provides data for a custom Pywr parameter.
@@ -137,6 +138,32 @@ impl InternalObj {
     }
 }
 
+impl ParameterState for InternalObj {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+    /// The user's object is deep copied so that the copy can be advanced independently. The
+    /// `ParameterInfo` is recreated when the copy is first used.
+    fn try_clone(&self) -> Result<Box<dyn ParameterState>, ParameterStateCloneError> {
+        let user_obj = Python::attach(|py| -> PyResult<_> {
+            let copy = py.import("copy")?;
+            Ok(copy.call_method1("deepcopy", (self.user_obj.bind(py),))?.unbind())
+        })
+        .map_err(|py_error| ParameterStateCloneError::PythonError {
+            py_error: Box::new(py_error),
+        })?;
+
+        Ok(Self {
+            user_obj,
+            info_obj: None,
+        }
+        .into_boxed_any())
+    }
+}
+
 /// Ensure that `info_obj` is populated with a `ParameterInfo`.
 fn ensure_parameter_info(
     info_obj: &mut Option<Py<ParameterInfo>>,
@@ -395,6 +422,13 @@ struct InternalInfo {
     info_obj: Option<Py<ParameterInfo>>,
 }
 
+/// The `ParameterInfo` is recreated when the copy is first used.
+impl Clone for InternalInfo {
+    fn clone(&self) -> Self {
+        Self { info_obj: None }
+    }
+}
+
 impl InternalInfo {
     fn into_boxed_any(self) -> Box<dyn ParameterState> {
         Box::new(self)
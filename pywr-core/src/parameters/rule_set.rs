@@ -0,0 +1,277 @@
+use crate::metric::{MetricF64, MetricU64};
+use crate::network::Network;
+use crate::parameters::errors::ParameterCalculationError;
+use crate::parameters::{GeneralParameter, Parameter, ParameterMeta, ParameterName, ParameterState, Predicate};
+use crate::scenario::ScenarioIndex;
+use crate::state::{MultiValue, State};
+use crate::timestep::Timestep;
+use chrono::Datelike;
+use std::collections::HashMap;
+
+/// A condition of a [`Rule`].
+pub enum RuleCondition {
+    /// Compare the value of a metric to a threshold.
+    Metric {
+        metric: MetricF64,
+        predicate: Predicate,
+        threshold: MetricF64,
+    },
+    /// Compare the value of an index metric to a threshold.
+    Index {
+        metric: MetricU64,
+        predicate: Predicate,
+        threshold: MetricU64,
+    },
+    /// The month of the time-step is one of the given months (1 to 12).
+    Months(Vec<u32>),
+    /// The day of the year of the time-step is between `start` and `end` (inclusive).
+    ///
+    /// Days are numbered from 1 to 366 as if every year is a leap year, so that a day number
+    /// refers to the same date in every year. If `start` is greater than `end` the range wraps
+    /// past the end of the year.
+    DayOfYear { start: u32, end: u32 },
+}
+
+impl RuleCondition {
+    fn is_met(&self, timestep: &Timestep, network: &Network, state: &State) -> Result<bool, ParameterCalculationError> {
+        let met = match self {
+            Self::Metric {
+                metric,
+                predicate,
+                threshold,
+            } => predicate.apply(metric.get_value(network, state)?, threshold.get_value(network, state)?),
+            Self::Index {
+                metric,
+                predicate,
+                threshold,
+            } => predicate.apply(
+                metric.get_value(network, state)? as f64,
+                threshold.get_value(network, state)? as f64,
+            ),
+            Self::Months(months) => months.contains(&timestep.date.month()),
+            Self::DayOfYear { start, end } => {
+                let day = timestep.day_of_year_index() as u32 + 1;
+                if start <= end {
+                    day >= *start && day <= *end
+                } else {
+                    day >= *start || day <= *end
+                }
+            }
+        };
+
+        Ok(met)
+    }
+}
+
+/// A named rule that applies its `value` when all of its conditions are met.
+pub struct Rule<M> {
+    pub name: String,
+    pub conditions: Vec<RuleCondition>,
+    pub value: M,
+}
+
+/// A parameter that returns the value of the first of an ordered set of rules whose conditions
+/// are all met.
+///
+/// This is a multi-valued parameter with two outputs:
+///
+/// - `value`: the value of the rule that applies, or the `default` value if no rule applies.
+///   This is a value when the rules return [`MetricF64`], and an index when they return
+///   [`MetricU64`].
+/// - `rule`: the index of the rule that applies, or the number of rules if no rule applies.
+///
+/// The `rule` output can be recorded with a [`crate::recorders::RuleTraceRecorder`] to audit
+/// which rule applied in each time-step and scenario.
+pub struct RuleSetParameter<M> {
+    meta: ParameterMeta,
+    rules: Vec<Rule<M>>,
+    default: M,
+}
+
+impl<M> RuleSetParameter<M> {
+    pub fn new(name: ParameterName, rules: Vec<Rule<M>>, default: M) -> Self {
+        Self {
+            meta: ParameterMeta::new(name),
+            rules,
+            default,
+        }
+    }
+
+    /// The index and value of the first rule whose conditions are all met.
+    fn matching_rule(
+        &self,
+        timestep: &Timestep,
+        network: &Network,
+        state: &State,
+    ) -> Result<(usize, &M), ParameterCalculationError> {
+        'rules: for (idx, rule) in self.rules.iter().enumerate() {
+            for condition in rule.conditions.iter() {
+                if !condition.is_met(timestep, network, state)? {
+                    continue 'rules;
+                }
+            }
+            return Ok((idx, &rule.value));
+        }
+
+        Ok((self.rules.len(), &self.default))
+    }
+}
+
+impl<M> Parameter for RuleSetParameter<M>
+where
+    M: Send + Sync,
+{
+    fn meta(&self) -> &ParameterMeta {
+        &self.meta
+    }
+}
+
+impl GeneralParameter<MultiValue> for RuleSetParameter<MetricF64> {
+    fn before(
+        &self,
+        timestep: &Timestep,
+        _scenario_index: &ScenarioIndex,
+        network: &Network,
+        state: &State,
+        _internal_state: &mut Option<Box<dyn ParameterState>>,
+    ) -> Result<Option<MultiValue>, ParameterCalculationError> {
+        let (rule, value) = self.matching_rule(timestep, network, state)?;
+
+        let values = HashMap::from([("value".to_string(), value.get_value(network, state)?)]);
+        let indices = HashMap::from([("rule".to_string(), rule as u64)]);
+        Ok(Some(MultiValue::new(values, indices)))
+    }
+
+    fn as_parameter(&self) -> &dyn Parameter
+    where
+        Self: Sized,
+    {
+        self
+    }
+}
+
+impl GeneralParameter<MultiValue> for RuleSetParameter<MetricU64> {
+    fn before(
+        &self,
+        timestep: &Timestep,
+        _scenario_index: &ScenarioIndex,
+        network: &Network,
+        state: &State,
+        _internal_state: &mut Option<Box<dyn ParameterState>>,
+    ) -> Result<Option<MultiValue>, ParameterCalculationError> {
+        let (rule, value) = self.matching_rule(timestep, network, state)?;
+
+        let indices = HashMap::from([
+            ("value".to_string(), value.get_value(network, state)?),
+            ("rule".to_string(), rule as u64),
+        ]);
+        Ok(Some(MultiValue::new(HashMap::new(), indices)))
+    }
+
+    fn as_parameter(&self) -> &dyn Parameter
+    where
+        Self: Sized,
+    {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Rule, RuleCondition, RuleSetParameter};
+    use crate::metric::{MetricF64, MetricU64};
+    use crate::parameters::{Array1Parameter, ParameterName, Predicate};
+    use crate::recorders::{AssertionF64Recorder, AssertionU64Recorder};
+    use crate::test_utils::{run_all_solvers, simple_model};
+    use ndarray::{Array1, Array2};
+
+    /// Rules with metric and calendar conditions over the first 15 days of January.
+    #[test]
+    fn test_rule_set() {
+        let mut model = simple_model(1, None);
+        let network = model.network_mut();
+
+        let storage = Array1Parameter::new("storage".into(), Array1::linspace(0.7, 0.0, 15), None);
+        let storage: MetricF64 = network
+            .add_simple_parameter(Box::new(storage))
+            .unwrap()
+            .into_metric_f64_before();
+
+        let rules = vec![
+            Rule {
+                name: "summer".to_string(),
+                conditions: vec![RuleCondition::Months(vec![6, 7, 8, 9])],
+                value: MetricF64::from(0.0),
+            },
+            Rule {
+                name: "low-storage".to_string(),
+                conditions: vec![
+                    RuleCondition::Metric {
+                        metric: storage.clone(),
+                        predicate: Predicate::LessThan,
+                        threshold: 0.52.into(),
+                    },
+                    RuleCondition::DayOfYear { start: 300, end: 10 },
+                ],
+                value: MetricF64::from(2.0),
+            },
+            Rule {
+                name: "low-storage-late".to_string(),
+                conditions: vec![RuleCondition::Metric {
+                    metric: storage,
+                    predicate: Predicate::LessThan,
+                    threshold: 0.52.into(),
+                }],
+                value: MetricF64::from(3.0),
+            },
+        ];
+
+        let p = RuleSetParameter::new(ParameterName::new("rules", None), rules, MetricF64::from(1.0));
+        let idx = network.add_multi_value_parameter(Box::new(p)).unwrap();
+
+        // Storage is 0.7 - 0.05 * i, which is below the threshold from i = 4. The second rule
+        // applies until day 10 (i = 9), and then the third rule.
+        let expected_rules = Array2::from_shape_fn((15, 1), |(i, _)| match i {
+            0..=3 => 3,
+            4..=9 => 1,
+            _ => 2,
+        });
+        let expected_values = expected_rules.mapv(|r| [0.0, 2.0, 3.0, 1.0][r as usize]);
+
+        let recorder = AssertionF64Recorder::new(
+            "value",
+            idx.clone().into_metric_f64_before("value"),
+            expected_values,
+            None,
+            None,
+        );
+        network.add_recorder(Box::new(recorder)).unwrap();
+
+        let recorder = AssertionU64Recorder::new("rule", idx.into_metric_u64_before("rule"), expected_rules);
+        network.add_recorder(Box::new(recorder)).unwrap();
+
+        run_all_solvers(&model, &[], &[], &[]);
+    }
+
+    /// Rules that return an index.
+    #[test]
+    fn test_index_rule_set() {
+        let mut model = simple_model(1, None);
+        let network = model.network_mut();
+
+        let rules = vec![Rule {
+            name: "first-week".to_string(),
+            conditions: vec![RuleCondition::DayOfYear { start: 1, end: 7 }],
+            value: MetricU64::from(4),
+        }];
+
+        let p = RuleSetParameter::new(ParameterName::new("rules", None), rules, MetricU64::from(5));
+        let idx = network.add_multi_value_parameter(Box::new(p)).unwrap();
+
+        let expected = Array2::from_shape_fn((15, 1), |(i, _)| if i < 7 { 4 } else { 5 });
+        let recorder = AssertionU64Recorder::new("value", idx.into_metric_u64_before("value"), expected);
+        network.add_recorder(Box::new(recorder)).unwrap();
+
+        run_all_solvers(&model, &[], &[], &[]);
+    }
+}
@@ -0,0 +1,282 @@
+use crate::parameters::errors::{ConstCalculationError, SimpleCalculationError};
+use crate::parameters::{ConstParameter, Parameter, ParameterMeta, ParameterName, ParameterState, SimpleParameter};
+use crate::scenario::ScenarioIndex;
+use crate::state::{ConstParameterValues, SimpleParameterValues};
+use crate::timestep::Timestep;
+use chrono::Datelike;
+use rand::SeedableRng;
+use rand::distr::weighted::{Error as WeightedError, WeightedIndex};
+use rand_chacha::ChaCha8Rng;
+use rand_distr::uniform::Error as UniformError;
+use rand_distr::{Distribution, LogNormal, Normal, NormalError, Triangular, TriangularError, Uniform};
+use thiserror::Error;
+
+/// Errors from defining a [`SampleDistribution`].
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum SampleDistributionError {
+    #[error("Invalid normal distribution: {0}")]
+    Normal(NormalError),
+    #[error("Invalid uniform distribution: {0}")]
+    Uniform(UniformError),
+    #[error("Invalid triangular distribution: {0}")]
+    Triangular(TriangularError),
+    #[error("Invalid empirical distribution: {0}")]
+    Empirical(WeightedError),
+    #[error("The empirical distribution has {values} values but {weights} weights")]
+    EmpiricalWeightsLength { values: usize, weights: usize },
+}
+
+/// A probability distribution from which values are sampled.
+#[derive(Debug, Clone)]
+pub enum SampleDistribution {
+    Normal(Normal<f64>),
+    LogNormal(LogNormal<f64>),
+    Uniform(Uniform<f64>),
+    Triangular(Triangular<f64>),
+    /// One of a set of values, chosen with the probability of its weight.
+    Empirical {
+        values: Vec<f64>,
+        index: WeightedIndex<f64>,
+    },
+}
+
+impl SampleDistribution {
+    pub fn normal(mean: f64, std_dev: f64) -> Result<Self, SampleDistributionError> {
+        Ok(Self::Normal(
+            Normal::new(mean, std_dev).map_err(SampleDistributionError::Normal)?,
+        ))
+    }
+
+    /// A distribution whose logarithm is normally distributed with mean `mu` and standard
+    /// deviation `sigma`.
+    pub fn log_normal(mu: f64, sigma: f64) -> Result<Self, SampleDistributionError> {
+        Ok(Self::LogNormal(
+            LogNormal::new(mu, sigma).map_err(SampleDistributionError::Normal)?,
+        ))
+    }
+
+    /// A uniform distribution between `low` and `high` (inclusive).
+    pub fn uniform(low: f64, high: f64) -> Result<Self, SampleDistributionError> {
+        Ok(Self::Uniform(
+            Uniform::new_inclusive(low, high).map_err(SampleDistributionError::Uniform)?,
+        ))
+    }
+
+    pub fn triangular(min: f64, max: f64, mode: f64) -> Result<Self, SampleDistributionError> {
+        Ok(Self::Triangular(
+            Triangular::new(min, max, mode).map_err(SampleDistributionError::Triangular)?,
+        ))
+    }
+
+    /// One of `values`, each with the probability of the corresponding weight or with equal
+    /// probability if no weights are given.
+    pub fn empirical(values: Vec<f64>, weights: Option<Vec<f64>>) -> Result<Self, SampleDistributionError> {
+        let weights = match weights {
+            Some(weights) => {
+                if weights.len() != values.len() {
+                    return Err(SampleDistributionError::EmpiricalWeightsLength {
+                        values: values.len(),
+                        weights: weights.len(),
+                    });
+                }
+                weights
+            }
+            None => vec![1.0; values.len()],
+        };
+
+        let index = WeightedIndex::new(weights).map_err(SampleDistributionError::Empirical)?;
+        Ok(Self::Empirical { values, index })
+    }
+
+    fn sample(&self, rng: &mut ChaCha8Rng) -> f64 {
+        match self {
+            Self::Normal(d) => d.sample(rng),
+            Self::LogNormal(d) => d.sample(rng),
+            Self::Uniform(d) => d.sample(rng),
+            Self::Triangular(d) => d.sample(rng),
+            Self::Empirical { values, index } => values[index.sample(rng)],
+        }
+    }
+}
+
+/// Create the random number generator for a scenario, and optionally a year.
+///
+/// Each scenario uses a separate stream of the generator based on its global simulation id, so
+/// the sample does not depend on which other scenarios are simulated or on the number of threads.
+/// Each year starts from a separate position in that stream.
+fn scenario_rng(seed: u64, scenario_index: &ScenarioIndex, year: Option<i32>) -> ChaCha8Rng {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    rng.set_stream(scenario_index.global_simulation_id() as u64);
+    if let Some(year) = year {
+        // Allow 2^32 words for the samples of each year.
+        let offset = (year as i64 - i32::MIN as i64) as u128;
+        rng.set_word_pos(offset << 32);
+    }
+    rng
+}
+
+/// A parameter that returns a value sampled once for each scenario.
+///
+/// The value is sampled from the `distribution` using a random number generator seeded with
+/// `seed` and the global simulation id of the scenario. The sampled values are therefore
+/// reproducible regardless of the number of threads or which scenarios are simulated.
+pub struct SampledConstantParameter {
+    meta: ParameterMeta,
+    distribution: SampleDistribution,
+    seed: u64,
+}
+
+impl SampledConstantParameter {
+    pub fn new(name: ParameterName, distribution: SampleDistribution, seed: u64) -> Self {
+        Self {
+            meta: ParameterMeta::new(name),
+            distribution,
+            seed,
+        }
+    }
+}
+
+impl Parameter for SampledConstantParameter {
+    fn meta(&self) -> &ParameterMeta {
+        &self.meta
+    }
+}
+
+impl ConstParameter<f64> for SampledConstantParameter {
+    fn compute(
+        &self,
+        scenario_index: &ScenarioIndex,
+        _values: &ConstParameterValues,
+        _internal_state: &mut Option<Box<dyn ParameterState>>,
+    ) -> Result<f64, ConstCalculationError> {
+        let mut rng = scenario_rng(self.seed, scenario_index, None);
+        Ok(self.distribution.sample(&mut rng))
+    }
+
+    fn as_parameter(&self) -> &dyn Parameter
+    where
+        Self: Sized,
+    {
+        self
+    }
+}
+
+/// A parameter that returns a value sampled once for each calendar year of each scenario.
+///
+/// This is the same as a [`SampledConstantParameter`] except that a new value is sampled for
+/// each year. The value for a year depends only on the `seed`, the scenario and the year, so it
+/// is the same regardless of the start date of the simulation.
+pub struct SampledAnnualParameter {
+    meta: ParameterMeta,
+    distribution: SampleDistribution,
+    seed: u64,
+}
+
+impl SampledAnnualParameter {
+    pub fn new(name: ParameterName, distribution: SampleDistribution, seed: u64) -> Self {
+        Self {
+            meta: ParameterMeta::new(name),
+            distribution,
+            seed,
+        }
+    }
+}
+
+impl Parameter for SampledAnnualParameter {
+    fn meta(&self) -> &ParameterMeta {
+        &self.meta
+    }
+}
+
+impl SimpleParameter<f64> for SampledAnnualParameter {
+    fn before(
+        &self,
+        timestep: &Timestep,
+        scenario_index: &ScenarioIndex,
+        _values: &SimpleParameterValues,
+        _internal_state: &mut Option<Box<dyn ParameterState>>,
+    ) -> Result<Option<f64>, SimpleCalculationError> {
+        let mut rng = scenario_rng(self.seed, scenario_index, Some(timestep.date.year()));
+        Ok(Some(self.distribution.sample(&mut rng)))
+    }
+
+    fn as_parameter(&self) -> &dyn Parameter
+    where
+        Self: Sized,
+    {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SampleDistribution, SampleDistributionError, SampledConstantParameter, scenario_rng};
+    use crate::parameters::{ConstParameter, Parameter};
+    use crate::scenario::{ScenarioDomainBuilder, ScenarioGroupBuilder};
+    use crate::state::ConstParameterValues;
+    use crate::test_utils::default_domain;
+
+    /// Samples are reproducible and differ between scenarios.
+    #[test]
+    fn test_sampled_constant() {
+        let mut builder = ScenarioDomainBuilder::default();
+        let group = ScenarioGroupBuilder::new("test", 100).build().unwrap();
+        builder = builder.with_group(group).unwrap();
+        let scenarios = builder.build().unwrap();
+
+        let distribution = SampleDistribution::triangular(1.0, 3.0, 2.0).unwrap();
+        let p = SampledConstantParameter::new("sample".into(), distribution, 42);
+
+        let values = ConstParameterValues::default();
+        let samples = scenarios
+            .indices()
+            .iter()
+            .map(|si| {
+                let mut state = p.setup(&[], si).unwrap();
+                p.compute(si, &values, &mut state).unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        assert!(samples.iter().all(|s| (1.0..=3.0).contains(s)));
+        assert!(samples.windows(2).any(|w| w[0] != w[1]));
+
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        assert!((mean - 2.0).abs() < 0.1);
+
+        // The same scenario gives the same sample
+        let si = &scenarios.indices()[7];
+        let mut state = p.setup(&[], si).unwrap();
+        assert_eq!(p.compute(si, &values, &mut state).unwrap(), samples[7]);
+    }
+
+    /// Annual samples differ between years, and are the same for the same year.
+    #[test]
+    fn test_annual_samples() {
+        let domain = default_domain();
+        let si = &domain.scenarios().indices()[0];
+
+        let distribution = SampleDistribution::uniform(0.0, 1.0).unwrap();
+        let samples = (2000..2010)
+            .map(|year| distribution.sample(&mut scenario_rng(1, si, Some(year))))
+            .collect::<Vec<_>>();
+
+        assert!(samples.windows(2).all(|w| w[0] != w[1]));
+        assert_eq!(distribution.sample(&mut scenario_rng(1, si, Some(2003))), samples[3]);
+    }
+
+    #[test]
+    fn test_invalid_distributions() {
+        assert!(matches!(
+            SampleDistribution::normal(0.0, f64::NAN),
+            Err(SampleDistributionError::Normal(_))
+        ));
+        assert!(matches!(
+            SampleDistribution::triangular(1.0, 0.0, 0.5),
+            Err(SampleDistributionError::Triangular(_))
+        ));
+        assert!(matches!(
+            SampleDistribution::empirical(vec![1.0, 2.0], Some(vec![1.0])),
+            Err(SampleDistributionError::EmpiricalWeightsLength { values: 2, weights: 1 })
+        ));
+    }
+}
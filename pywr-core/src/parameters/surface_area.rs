@@ -0,0 +1,154 @@
+use crate::metric::MetricF64;
+use crate::network::Network;
+use crate::parameters::errors::ParameterCalculationError;
+use crate::parameters::interpolate::linear_interpolation;
+use crate::parameters::{GeneralParameter, Parameter, ParameterMeta, ParameterName, ParameterState};
+use crate::scenario::ScenarioIndex;
+use crate::state::{MultiValue, State};
+use crate::timestep::Timestep;
+use std::collections::HashMap;
+
+/// The relationship between the storage and surface area of a reservoir.
+pub enum SurfaceAreaCurve {
+    /// Piecewise linear interpolation of `(storage, area)` points.
+    Interpolated(Vec<(f64, f64)>),
+    /// A polynomial of the storage with the given coefficients.
+    Polynomial(Vec<f64>),
+}
+
+impl SurfaceAreaCurve {
+    /// Returns the area and the gradient of the area with respect to the storage.
+    fn area_and_gradient(&self, storage: f64) -> Result<(f64, f64), ParameterCalculationError> {
+        match self {
+            Self::Interpolated(points) => {
+                let area = linear_interpolation(storage, points, true)?;
+                // Use the gradient of the segment the storage is interpolated within
+                let gradient = points
+                    .windows(2)
+                    .find(|pts| storage <= pts[1].0)
+                    .map_or(0.0, |pts| (pts[1].1 - pts[0].1) / (pts[1].0 - pts[0].0));
+                Ok((area, gradient))
+            }
+            Self::Polynomial(coefficients) => {
+                let area = coefficients
+                    .iter()
+                    .enumerate()
+                    .fold(0.0, |y, (i, c)| y + c * storage.powi(i as i32));
+                let gradient = coefficients
+                    .iter()
+                    .enumerate()
+                    .skip(1)
+                    .fold(0.0, |y, (i, c)| y + c * i as f64 * storage.powi(i as i32 - 1));
+                Ok((area, gradient))
+            }
+        }
+    }
+}
+
+/// A parameter that calculates the surface area of a reservoir and its rate of change with
+/// respect to the reservoir's volume.
+///
+/// The gradient allows the surface area to be linearised about the current volume. This can be
+/// used to express a flow that depends on the end of time-step surface area (e.g. evaporation)
+/// as a linear function of the net inflow to the reservoir.
+///
+/// # Values
+///
+/// - `area`: The surface area at the current volume.
+/// - `gradient`: The change in surface area per unit of (absolute) volume.
+/// - `flow_gradient`: The change in surface area per unit of net inflow sustained over the
+///   time-step. This is the `gradient` multiplied by the length of the time-step in days.
+///
+pub struct SurfaceAreaParameter {
+    meta: ParameterMeta,
+    storage: MetricF64,
+    max_volume: Option<MetricF64>,
+    curve: SurfaceAreaCurve,
+}
+
+impl SurfaceAreaParameter {
+    /// Create a new parameter. If `max_volume` is given the `storage` metric and the curve are
+    /// assumed to be proportional (0-1) and the gradient is scaled to absolute volume.
+    pub fn new(
+        name: ParameterName,
+        storage: MetricF64,
+        max_volume: Option<MetricF64>,
+        curve: SurfaceAreaCurve,
+    ) -> Self {
+        Self {
+            meta: ParameterMeta::new(name),
+            storage,
+            max_volume,
+            curve,
+        }
+    }
+}
+
+impl Parameter for SurfaceAreaParameter {
+    fn meta(&self) -> &ParameterMeta {
+        &self.meta
+    }
+}
+
+impl GeneralParameter<MultiValue> for SurfaceAreaParameter {
+    fn before(
+        &self,
+        timestep: &Timestep,
+        _scenario_index: &ScenarioIndex,
+        network: &Network,
+        state: &State,
+        _internal_state: &mut Option<Box<dyn ParameterState>>,
+    ) -> Result<Option<MultiValue>, ParameterCalculationError> {
+        let storage = self.storage.get_value(network, state)?;
+        let (area, mut gradient) = self.curve.area_and_gradient(storage)?;
+
+        if let Some(max_volume) = &self.max_volume {
+            let max_volume = max_volume.get_value(network, state)?;
+            gradient = if max_volume > 0.0 { gradient / max_volume } else { 0.0 };
+        }
+
+        let mut values = HashMap::new();
+        values.insert("area".to_string(), area);
+        values.insert("gradient".to_string(), gradient);
+        values.insert("flow_gradient".to_string(), gradient * timestep.days());
+
+        Ok(Some(MultiValue::new(values, HashMap::new())))
+    }
+
+    fn as_parameter(&self) -> &dyn Parameter
+    where
+        Self: Sized,
+    {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SurfaceAreaCurve;
+    use float_cmp::assert_approx_eq;
+
+    #[test]
+    fn test_interpolated_gradient() {
+        let curve = SurfaceAreaCurve::Interpolated(vec![(0.0, 10.0), (100.0, 20.0), (200.0, 40.0)]);
+
+        let (area, gradient) = curve.area_and_gradient(50.0).unwrap();
+        assert_approx_eq!(f64, area, 15.0);
+        assert_approx_eq!(f64, gradient, 0.1);
+
+        let (area, gradient) = curve.area_and_gradient(150.0).unwrap();
+        assert_approx_eq!(f64, area, 30.0);
+        assert_approx_eq!(f64, gradient, 0.2);
+
+        assert!(curve.area_and_gradient(250.0).is_err());
+    }
+
+    #[test]
+    fn test_polynomial_gradient() {
+        let curve = SurfaceAreaCurve::Polynomial(vec![1.0, 2.0, 3.0]);
+
+        let (area, gradient) = curve.area_and_gradient(2.0).unwrap();
+        assert_approx_eq!(f64, area, 1.0 + 4.0 + 12.0);
+        assert_approx_eq!(f64, gradient, 2.0 + 12.0);
+    }
+}
@@ -0,0 +1,223 @@
+use crate::parameters::errors::SimpleCalculationError;
+use crate::parameters::{Parameter, ParameterMeta, ParameterName, ParameterState, SimpleParameter};
+use crate::scenario::ScenarioIndex;
+use crate::state::SimpleParameterValues;
+use crate::timestep::Timestep;
+use chrono::{Datelike, NaiveDateTime, TimeDelta, Timelike};
+
+const MILLISECS_IN_HOUR: f64 = 3_600_000.0;
+
+/// The days of the week a [`TariffBand`] applies to.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum TariffDays {
+    #[default]
+    All,
+    /// Monday to Friday.
+    Weekdays,
+    /// Saturday and Sunday.
+    Weekends,
+}
+
+impl TariffDays {
+    fn applies(&self, dt: NaiveDateTime) -> bool {
+        let weekday = dt.weekday().num_days_from_monday() < 5;
+        match self {
+            TariffDays::All => true,
+            TariffDays::Weekdays => weekday,
+            TariffDays::Weekends => !weekday,
+        }
+    }
+}
+
+/// A period of the day during which a tariff applies a given rate.
+///
+/// The band covers the hours from `start_hour` (inclusive) to `end_hour` (exclusive). If
+/// `end_hour` is less than `start_hour` the band wraps past midnight (e.g. a night band from
+/// 22:00 to 06:00). If the two are equal the band covers the whole day.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TariffBand {
+    pub start_hour: u32,
+    pub end_hour: u32,
+    pub days: TariffDays,
+    pub rate: f64,
+}
+
+impl TariffBand {
+    /// Returns true if the band applies at the given date and time.
+    pub fn applies(&self, dt: NaiveDateTime) -> bool {
+        if !self.days.applies(dt) {
+            return false;
+        }
+
+        let hour = dt.hour();
+        match self.start_hour.cmp(&self.end_hour) {
+            std::cmp::Ordering::Less => hour >= self.start_hour && hour < self.end_hour,
+            std::cmp::Ordering::Greater => hour >= self.start_hour || hour < self.end_hour,
+            std::cmp::Ordering::Equal => true,
+        }
+    }
+}
+
+/// A time-of-use tariff that returns the unit cost applicable to the current time-step.
+///
+/// The bands are checked in order and the rate of the first band that applies is used. If no
+/// band applies the `default_rate` is used. For time-steps longer than one hour (e.g. daily
+/// time-steps) the rate is the time-weighted average of the hourly rates over the time-step.
+/// Sub-hourly time-steps use the rate of the hour in which they start.
+pub struct TariffParameter {
+    meta: ParameterMeta,
+    bands: Vec<TariffBand>,
+    default_rate: f64,
+}
+
+impl TariffParameter {
+    pub fn new(name: ParameterName, bands: Vec<TariffBand>, default_rate: f64) -> Self {
+        Self {
+            meta: ParameterMeta::new(name),
+            bands,
+            default_rate,
+        }
+    }
+
+    /// The rate that applies at the given date and time.
+    fn rate(&self, dt: NaiveDateTime) -> f64 {
+        self.bands
+            .iter()
+            .find(|b| b.applies(dt))
+            .map_or(self.default_rate, |b| b.rate)
+    }
+
+    /// The time-weighted average rate over the time-step.
+    fn average_rate(&self, timestep: &Timestep) -> f64 {
+        let hours = timestep.duration.milliseconds() as f64 / MILLISECS_IN_HOUR;
+        if hours <= 1.0 {
+            return self.rate(timestep.date);
+        }
+
+        let mut total = 0.0;
+        let mut weight = 0.0;
+        for i in 0..hours.ceil() as i64 {
+            let w = (hours - i as f64).min(1.0);
+            total += w * self.rate(timestep.date + TimeDelta::hours(i));
+            weight += w;
+        }
+
+        total / weight
+    }
+}
+
+impl Parameter for TariffParameter {
+    fn meta(&self) -> &ParameterMeta {
+        &self.meta
+    }
+}
+
+impl SimpleParameter<f64> for TariffParameter {
+    fn before(
+        &self,
+        timestep: &Timestep,
+        _scenario_index: &ScenarioIndex,
+        _values: &SimpleParameterValues,
+        _internal_state: &mut Option<Box<dyn ParameterState>>,
+    ) -> Result<Option<f64>, SimpleCalculationError> {
+        Ok(Some(self.average_rate(timestep)))
+    }
+
+    fn as_parameter(&self) -> &dyn Parameter
+    where
+        Self: Sized,
+    {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TariffBand, TariffDays, TariffParameter};
+    use crate::recorders::AssertionF64Recorder;
+    use crate::test_utils::{run_all_solvers, simple_model};
+    use crate::timestep::{PywrDuration, Timestep, TimestepDuration, Timestepper};
+    use chrono::NaiveDate;
+    use float_cmp::assert_approx_eq;
+    use ndarray::Array2;
+    use std::num::NonZeroU64;
+
+    fn test_tariff() -> TariffParameter {
+        let bands = vec![
+            // Night rate every day
+            TariffBand {
+                start_hour: 22,
+                end_hour: 6,
+                days: TariffDays::All,
+                rate: 1.0,
+            },
+            // Weekday peak
+            TariffBand {
+                start_hour: 16,
+                end_hour: 19,
+                days: TariffDays::Weekdays,
+                rate: 10.0,
+            },
+        ];
+        TariffParameter::new("tariff".into(), bands, 4.0)
+    }
+
+    /// Test the tariff with hourly time-steps.
+    #[test]
+    fn test_hourly() {
+        // 2020-01-03 is a Friday and 2020-01-04 a Saturday.
+        let start = NaiveDate::from_ymd_opt(2020, 1, 3)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        let end = NaiveDate::from_ymd_opt(2020, 1, 4)
+            .unwrap()
+            .and_hms_opt(23, 0, 0)
+            .unwrap();
+        let timestepper = Timestepper::new(start, end, TimestepDuration::Hours(NonZeroU64::new(1).unwrap()));
+        let mut model = simple_model(1, Some(timestepper));
+
+        let idx = model
+            .network_mut()
+            .add_simple_parameter(Box::new(test_tariff()))
+            .unwrap();
+
+        let expected = Array2::from_shape_fn((48, 1), |(i, _)| {
+            let hour = i % 24;
+            if !(6..22).contains(&hour) {
+                1.0
+            } else if i < 24 && (16..19).contains(&hour) {
+                10.0
+            } else {
+                4.0
+            }
+        });
+
+        let recorder = AssertionF64Recorder::new("tariff", idx.into_metric_f64_before(), expected, None, None);
+        model.network_mut().add_recorder(Box::new(recorder)).unwrap();
+
+        run_all_solvers(&model, &[], &[], &[]);
+    }
+
+    /// Test the averaging of the hourly rates over daily time-steps.
+    #[test]
+    fn test_daily_average() {
+        let tariff = test_tariff();
+
+        // A weekday: 8 night hours, 3 peak hours and 13 hours at the default rate.
+        let date = NaiveDate::from_ymd_opt(2020, 1, 3)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        let timestep = Timestep::new(date, 0, PywrDuration::from_days(1));
+        assert_approx_eq!(f64, tariff.average_rate(&timestep), (8.0 + 30.0 + 52.0) / 24.0);
+
+        // A weekend day has no peak band.
+        let timestep = Timestep::new(date + chrono::TimeDelta::days(1), 1, PywrDuration::from_days(1));
+        assert_approx_eq!(f64, tariff.average_rate(&timestep), (8.0 + 64.0) / 24.0);
+
+        // A six-hour time-step spanning the start of the peak band.
+        let timestep = Timestep::new(date + chrono::TimeDelta::hours(14), 0, PywrDuration::from_hours(6));
+        assert_approx_eq!(f64, tariff.average_rate(&timestep), (4.0 * 3.0 + 10.0 * 3.0) / 6.0);
+    }
+}
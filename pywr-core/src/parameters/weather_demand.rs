@@ -0,0 +1,278 @@
+use crate::metric::{MetricF64, MetricU64};
+use crate::network::Network;
+use crate::parameters::errors::ParameterCalculationError;
+use crate::parameters::{GeneralParameter, Parameter, ParameterMeta, ParameterName, ParameterState};
+use crate::scenario::ScenarioIndex;
+use crate::state::State;
+use crate::timestep::Timestep;
+use chrono::Datelike;
+
+/// How a weather metric changes the demand of a [`WeatherDemandParameter`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DemandResponse {
+    /// The demand responds to the amount by which the metric exceeds the reference, such as the
+    /// temperature above a threshold.
+    Excess,
+    /// The demand responds to the amount by which the metric is below the reference, such as the
+    /// shortfall of rainfall below the normal rainfall.
+    Deficit,
+    /// The demand responds to the difference between the metric and the reference.
+    Linear,
+}
+
+/// The sensitivity of the demand of a [`WeatherDemandParameter`] to a weather metric.
+///
+/// The change in demand is the `coefficient` multiplied by the [`DemandResponse`] of the
+/// `metric` to the `reference`, as a proportion of the base demand. For example, a coefficient of
+/// 0.02 with an `Excess` response to a reference temperature of 20 increases the demand by 2% for
+/// each degree above 20.
+pub struct DemandSensitivity {
+    pub metric: MetricF64,
+    pub reference: f64,
+    pub coefficient: f64,
+    pub response: DemandResponse,
+}
+
+impl DemandSensitivity {
+    /// The proportional change in demand for the current value of the metric.
+    fn change(&self, network: &Network, state: &State) -> Result<f64, ParameterCalculationError> {
+        let value = self.metric.get_value(network, state)?;
+
+        let difference = match self.response {
+            DemandResponse::Excess => (value - self.reference).max(0.0),
+            DemandResponse::Deficit => (self.reference - value).max(0.0),
+            DemandResponse::Linear => value - self.reference,
+        };
+
+        Ok(self.coefficient * difference)
+    }
+}
+
+/// Annual population growth of a [`WeatherDemandParameter`].
+///
+/// The growth factor is `(1 + rate)^n`, where `n` is the number of whole years since the
+/// `base_year`. The factor is less than one for years before the base year.
+pub struct PopulationGrowth {
+    pub rate: MetricF64,
+    pub base_year: i32,
+}
+
+impl PopulationGrowth {
+    fn factor(&self, timestep: &Timestep, network: &Network, state: &State) -> Result<f64, ParameterCalculationError> {
+        let rate = self.rate.get_value(network, state)?;
+        let years = timestep.date.year() - self.base_year;
+        Ok((1.0 + rate).powi(years))
+    }
+}
+
+/// Demand-saving factors of a [`WeatherDemandParameter`] for the levels of a drought index.
+///
+/// The demand is multiplied by the factor in the position of the current `level`, so the first
+/// factor is normally one.
+pub struct DemandSaving {
+    pub level: MetricU64,
+    pub factors: Vec<f64>,
+}
+
+impl DemandSaving {
+    fn factor(&self, network: &Network, state: &State) -> Result<f64, ParameterCalculationError> {
+        let level = self.level.get_value(network, state)? as usize;
+
+        self.factors
+            .get(level)
+            .copied()
+            .ok_or(ParameterCalculationError::OutOfBoundsError {
+                index: level,
+                length: self.factors.len(),
+                axis: 0,
+            })
+    }
+}
+
+/// A parameter that calculates a demand from a base demand and its sensitivity to the weather.
+///
+/// The demand is calculated as:
+///
+/// ```text
+/// demand = base * growth * (1 + sum of the changes from the sensitivities) * saving
+/// ```
+///
+/// where `base` is the base demand (for example an annual profile), `growth` is the optional
+/// [`PopulationGrowth`] factor and `saving` is the optional [`DemandSaving`] factor. Each
+/// [`DemandSensitivity`] gives a proportional change in demand for a weather metric, such as the
+/// temperature or the rainfall over a rolling window. Because the weather metrics and growth rate
+/// can vary by scenario, climate and population scenarios are applied consistently to the demand.
+/// The demand is never less than zero.
+pub struct WeatherDemandParameter {
+    meta: ParameterMeta,
+    base: MetricF64,
+    sensitivities: Vec<DemandSensitivity>,
+    growth: Option<PopulationGrowth>,
+    saving: Option<DemandSaving>,
+}
+
+impl WeatherDemandParameter {
+    pub fn new(
+        name: ParameterName,
+        base: MetricF64,
+        sensitivities: Vec<DemandSensitivity>,
+        growth: Option<PopulationGrowth>,
+        saving: Option<DemandSaving>,
+    ) -> Self {
+        Self {
+            meta: ParameterMeta::new(name),
+            base,
+            sensitivities,
+            growth,
+            saving,
+        }
+    }
+}
+
+impl Parameter for WeatherDemandParameter {
+    fn meta(&self) -> &ParameterMeta {
+        &self.meta
+    }
+}
+
+impl GeneralParameter<f64> for WeatherDemandParameter {
+    fn before(
+        &self,
+        timestep: &Timestep,
+        _scenario_index: &ScenarioIndex,
+        network: &Network,
+        state: &State,
+        _internal_state: &mut Option<Box<dyn ParameterState>>,
+    ) -> Result<Option<f64>, ParameterCalculationError> {
+        let mut demand = self.base.get_value(network, state)?;
+
+        if let Some(growth) = &self.growth {
+            demand *= growth.factor(timestep, network, state)?;
+        }
+
+        let mut weather_factor = 1.0;
+        for sensitivity in &self.sensitivities {
+            weather_factor += sensitivity.change(network, state)?;
+        }
+        demand *= weather_factor;
+
+        if let Some(saving) = &self.saving {
+            demand *= saving.factor(network, state)?;
+        }
+
+        Ok(Some(demand.max(0.0)))
+    }
+
+    fn as_parameter(&self) -> &dyn Parameter
+    where
+        Self: Sized,
+    {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DemandResponse, DemandSaving, DemandSensitivity, PopulationGrowth, WeatherDemandParameter};
+    use crate::agg_funcs::AggFuncF64;
+    use crate::metric::MetricF64;
+    use crate::parameters::{Array1Parameter, RollingParameter};
+    use crate::recorders::AssertionF64Recorder;
+    use crate::test_utils::{run_all_solvers, simple_model};
+    use ndarray::{Array1, Array2};
+
+    /// The demand responds to the temperature above 20 and to a rolling sum of the rainfall.
+    #[test]
+    fn test_weather_sensitivity() {
+        let mut model = simple_model(1, None);
+        let network = model.network_mut();
+
+        let temperature = Array1Parameter::new("temperature".into(), Array1::linspace(16.0, 30.0, 15), None);
+        let temperature: MetricF64 = network
+            .add_simple_parameter(Box::new(temperature))
+            .unwrap()
+            .into_metric_f64_before();
+
+        // 2 units of rainfall on every fifth day
+        let rainfall: Array1<f64> = (0..15).map(|i| if i % 5 == 0 { 2.0 } else { 0.0 }).collect();
+        let rainfall = Array1Parameter::new("rainfall".into(), rainfall, None);
+        let rainfall: MetricF64 = network
+            .add_simple_parameter(Box::new(rainfall))
+            .unwrap()
+            .into_metric_f64_before();
+
+        // The rainfall over the previous three days, or the normal rainfall until then
+        let rolling = RollingParameter::new("rolling-rainfall".into(), rainfall, 3, 1.0, 3, AggFuncF64::Sum);
+        let rolling = network
+            .add_parameter(Box::new(rolling))
+            .unwrap()
+            .into_metric_f64_before();
+
+        let sensitivities = vec![
+            DemandSensitivity {
+                metric: temperature,
+                reference: 20.0,
+                coefficient: 0.02,
+                response: DemandResponse::Excess,
+            },
+            DemandSensitivity {
+                metric: rolling,
+                reference: 1.0,
+                coefficient: 0.1,
+                response: DemandResponse::Deficit,
+            },
+        ];
+
+        let p = WeatherDemandParameter::new("demand".into(), 10.0.into(), sensitivities, None, None);
+        let idx = network.add_parameter(Box::new(p)).unwrap();
+
+        let rolling_rainfall: [f64; 15] = [
+            1.0, 1.0, 1.0, 2.0, 0.0, 0.0, 2.0, 2.0, 2.0, 0.0, 0.0, 2.0, 2.0, 2.0, 0.0,
+        ];
+        let expected: Vec<f64> = (0..15)
+            .map(|i| {
+                let temperature = 16.0 + i as f64;
+                let deficit = (1.0 - rolling_rainfall[i]).max(0.0);
+                10.0 * (1.0 + 0.02 * (temperature - 20.0).max(0.0) + 0.1 * deficit)
+            })
+            .collect();
+        let expected = Array2::from_shape_vec((15, 1), expected).unwrap();
+        let recorder = AssertionF64Recorder::new("demand", idx.into_metric_f64_before(), expected, None, None);
+        network.add_recorder(Box::new(recorder)).unwrap();
+
+        run_all_solvers(&model, &[], &[], &[]);
+    }
+
+    /// The demand grows by 10% per year from 2018 and is reduced by the demand-saving level.
+    #[test]
+    fn test_growth_and_saving() {
+        let mut model = simple_model(1, None);
+        let network = model.network_mut();
+
+        let levels: Array1<u64> = (0..15).map(|i| i / 5).collect();
+        let levels = Array1Parameter::new("levels".into(), levels, None);
+        let levels = network
+            .add_simple_index_parameter(Box::new(levels))
+            .unwrap()
+            .into_metric_u64_before();
+
+        let growth = PopulationGrowth {
+            rate: 0.1.into(),
+            base_year: 2018,
+        };
+        let saving = DemandSaving {
+            level: levels,
+            factors: vec![1.0, 0.9, 0.8],
+        };
+
+        let p = WeatherDemandParameter::new("demand".into(), 10.0.into(), vec![], Some(growth), Some(saving));
+        let idx = network.add_parameter(Box::new(p)).unwrap();
+
+        let expected: Vec<f64> = (0..15).map(|i| 10.0 * 1.21 * [1.0, 0.9, 0.8][i / 5]).collect();
+        let expected = Array2::from_shape_vec((15, 1), expected).unwrap();
+        let recorder = AssertionF64Recorder::new("demand", idx.into_metric_f64_before(), expected, None, None);
+        network.add_recorder(Box::new(recorder)).unwrap();
+
+        run_all_solvers(&model, &[], &[], &[]);
+    }
+}
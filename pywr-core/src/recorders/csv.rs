@@ -9,10 +9,11 @@ use crate::scenario::ScenarioIndex;
 use crate::state::State;
 use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 use std::fs::File;
 use std::num::NonZeroU32;
 use std::ops::Deref;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
 /// Errors returned by recorder saving.
@@ -26,6 +27,20 @@ pub enum CsvError {
         #[source]
         source: ::csv::Error,
     },
+    #[error("No files to merge")]
+    NoFilesToMerge,
+    #[error("Invalid header in CSV file at `{path}`")]
+    InvalidHeader { path: PathBuf },
+    #[error("CSV file at `{path}` has {found} rows; expected {expected} rows")]
+    RowCountMismatch {
+        path: PathBuf,
+        found: usize,
+        expected: usize,
+    },
+    #[error("Simulation id {simulation_id} in CSV file at `{path}` is also in another file")]
+    DuplicateSimulationId { path: PathBuf, simulation_id: usize },
+    #[error("Simulation id {simulation_id} is missing from the merged files")]
+    MissingSimulationId { simulation_id: usize },
 }
 
 /// Output the values from a [`crate::recorders::MetricSet`] to a CSV file.
@@ -131,7 +146,7 @@ impl Recorder for CsvWideFmtOutput {
             // Repeat the names, sub-names and attributes for every scenario
             header_name.extend(names.clone());
             header_attribute.extend(attributes.clone());
-            header_scenario.extend(vec![format!("{}", scenario_index.simulation_id()); names.len()]);
+            header_scenario.extend(vec![format!("{}", scenario_index.global_simulation_id()); names.len()]);
             header_label.extend(vec![format!("{}", scenario_index.label()); names.len()]);
         }
 
@@ -264,7 +279,7 @@ impl CsvLongFmtOutput {
                         let record = CsvLongFmtRecord {
                             time_start: value.start,
                             time_end: value.end(),
-                            simulation_id: scenario_index.simulation_id(),
+                            simulation_id: scenario_index.global_simulation_id(),
                             label: scenario_index.label(),
                             metric_set: metric_set.name().to_string(),
                             name,
@@ -332,3 +347,181 @@ impl Recorder for CsvLongFmtOutput {
         Ok(None)
     }
 }
+
+/// The number of header rows in the wide format CSV file.
+const WIDE_FMT_HEADER_ROWS: usize = 4;
+/// The position of the simulation id header row in the wide format CSV file.
+const WIDE_FMT_SIMULATION_ID_ROW: usize = 2;
+
+/// Check that the simulation ids of each file are unique and together form a contiguous range
+/// starting from zero.
+fn check_merged_simulation_ids(ids: &[(&Path, BTreeSet<usize>)]) -> Result<(), CsvError> {
+    let mut all_ids = BTreeSet::new();
+
+    for (path, file_ids) in ids {
+        for simulation_id in file_ids {
+            if !all_ids.insert(*simulation_id) {
+                return Err(CsvError::DuplicateSimulationId {
+                    path: path.to_path_buf(),
+                    simulation_id: *simulation_id,
+                });
+            }
+        }
+    }
+
+    if let Some(simulation_id) = all_ids.iter().enumerate().find_map(|(i, id)| (i != *id).then_some(i)) {
+        return Err(CsvError::MissingSimulationId { simulation_id });
+    }
+
+    Ok(())
+}
+
+/// Merge CSV files written by [`CsvWideFmtOutput`] for separate shards of a simulation into a
+/// single file.
+///
+/// The columns of each scenario are ordered by their simulation id, and the result is the same
+/// as the file written by a single simulation of all the scenarios.
+pub fn merge_csv_wide_outputs<P: AsRef<Path>>(inputs: &[P], output: &Path) -> Result<(), CsvError> {
+    let mut files = Vec::with_capacity(inputs.len());
+
+    for input in inputs {
+        let path = input.as_ref();
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .from_path(path)
+            .map_err(|source| CsvError::CSVError {
+                path: path.to_path_buf(),
+                source,
+            })?;
+
+        let rows = reader
+            .records()
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|source| CsvError::CSVError {
+                path: path.to_path_buf(),
+                source,
+            })?;
+
+        if rows.len() < WIDE_FMT_HEADER_ROWS {
+            return Err(CsvError::InvalidHeader {
+                path: path.to_path_buf(),
+            });
+        }
+
+        files.push((path, rows));
+    }
+
+    let (_, first_rows) = files.first().ok_or(CsvError::NoFilesToMerge)?;
+    let num_rows = first_rows.len();
+
+    // Collect the simulation id for every column of every file
+    let mut columns = Vec::new();
+    let mut ids = Vec::with_capacity(files.len());
+    for (file_idx, (path, rows)) in files.iter().enumerate() {
+        if rows.len() != num_rows {
+            return Err(CsvError::RowCountMismatch {
+                path: path.to_path_buf(),
+                found: rows.len(),
+                expected: num_rows,
+            });
+        }
+
+        let mut file_ids = BTreeSet::new();
+        for (col_idx, id) in rows[WIDE_FMT_SIMULATION_ID_ROW].iter().enumerate().skip(1) {
+            let simulation_id: usize = id.parse().map_err(|_| CsvError::InvalidHeader {
+                path: path.to_path_buf(),
+            })?;
+            file_ids.insert(simulation_id);
+            columns.push((simulation_id, file_idx, col_idx));
+        }
+        ids.push((*path, file_ids));
+    }
+
+    check_merged_simulation_ids(&ids)?;
+
+    // A stable sort retains the order of the metrics for each scenario
+    columns.sort_by_key(|(simulation_id, _, _)| *simulation_id);
+
+    let mut writer = csv::Writer::from_path(output).map_err(|source| CsvError::CSVError {
+        path: output.to_path_buf(),
+        source,
+    })?;
+
+    for (row_idx, first_row) in first_rows.iter().enumerate() {
+        let mut row = vec![first_row.get(0).unwrap_or_default()];
+        row.extend(
+            columns
+                .iter()
+                .map(|(_, file_idx, col_idx)| files[*file_idx].1[row_idx].get(*col_idx).unwrap_or_default()),
+        );
+
+        writer.write_record(row).map_err(|source| CsvError::CSVError {
+            path: output.to_path_buf(),
+            source,
+        })?;
+    }
+
+    writer.flush().map_err(|source| CsvError::CSVError {
+        path: output.to_path_buf(),
+        source: source.into(),
+    })?;
+
+    Ok(())
+}
+
+/// Merge CSV files written by [`CsvLongFmtOutput`] for separate shards of a simulation into a
+/// single file.
+///
+/// The records are ordered by the end of their period and then by simulation id, retaining the
+/// order of the records within each file otherwise.
+pub fn merge_csv_long_outputs<P: AsRef<Path>>(inputs: &[P], output: &Path) -> Result<(), CsvError> {
+    if inputs.is_empty() {
+        return Err(CsvError::NoFilesToMerge);
+    }
+
+    let mut records = Vec::new();
+    let mut ids = Vec::with_capacity(inputs.len());
+
+    for input in inputs {
+        let path = input.as_ref();
+        let mut reader = csv::Reader::from_path(path).map_err(|source| CsvError::CSVError {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        let mut file_ids = BTreeSet::new();
+        for record in reader.deserialize() {
+            let record: CsvLongFmtRecord = record.map_err(|source| CsvError::CSVError {
+                path: path.to_path_buf(),
+                source,
+            })?;
+            file_ids.insert(record.simulation_id);
+            records.push(record);
+        }
+        ids.push((path, file_ids));
+    }
+
+    check_merged_simulation_ids(&ids)?;
+
+    // A stable sort retains the order of the metrics for each scenario
+    records.sort_by_key(|r| (r.time_end, r.simulation_id));
+
+    let mut writer = csv::Writer::from_path(output).map_err(|source| CsvError::CSVError {
+        path: output.to_path_buf(),
+        source,
+    })?;
+
+    for record in records {
+        writer.serialize(record).map_err(|source| CsvError::CSVError {
+            path: output.to_path_buf(),
+            source,
+        })?;
+    }
+
+    writer.flush().map_err(|source| CsvError::CSVError {
+        path: output.to_path_buf(),
+        source: source.into(),
+    })?;
+
+    Ok(())
+}
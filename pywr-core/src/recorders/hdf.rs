@@ -11,9 +11,9 @@ use crate::state::State;
 use chrono::{Datelike, Timelike};
 use hdf5_metno::types::StringError;
 use hdf5_metno::{Extents, Group};
-use ndarray::{Array1, s};
+use ndarray::{Array1, Array2, s};
 use std::ops::Deref;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use thiserror::Error;
 
@@ -41,6 +41,12 @@ pub enum Hdf5Error {
         #[source]
         source: StringError,
     },
+    #[error("No files to merge")]
+    NoFilesToMerge,
+    #[error("Simulation id {simulation_id} in HDF5 file at `{path}` is also in another file")]
+    DuplicateSimulationId { path: PathBuf, simulation_id: usize },
+    #[error("Simulation id {simulation_id} is missing from the merged files")]
+    MissingSimulationId { simulation_id: usize },
 }
 
 /// A recorder that saves model outputs to an HDF5 file.
@@ -370,7 +376,7 @@ fn write_scenarios_metadata(file: &hdf5_metno::File, domain: &ScenarioDomain) ->
             })?;
 
             Ok(H5ScenarioIndex {
-                index: s.simulation_id(),
+                index: s.global_simulation_id(),
                 indices,
                 label,
             })
@@ -387,3 +393,165 @@ fn write_scenarios_metadata(file: &hdf5_metno::File, domain: &ScenarioDomain) ->
 
     Ok(())
 }
+
+/// Merge HDF5 files written by [`HDF5Recorder`] for separate shards of a simulation into a
+/// single file.
+///
+/// The scenarios of each metric's dataset are ordered by their simulation id, and the result is
+/// the same as the file written by a single simulation of all the scenarios.
+pub fn merge_hdf5_outputs<P: AsRef<Path>>(inputs: &[P], output: &Path) -> Result<(), Hdf5Error> {
+    let files = inputs
+        .iter()
+        .map(|path| {
+            hdf5_metno::File::open(path.as_ref()).map_err(|source| Hdf5Error::HDF5Error {
+                path: path.as_ref().to_path_buf(),
+                source,
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let first = files.first().ok_or(Hdf5Error::NoFilesToMerge)?;
+
+    // Collect the scenarios of every file, with the file and column they are saved in
+    let mut scenarios = Vec::new();
+    for (file_idx, file) in files.iter().enumerate() {
+        let indices = file
+            .dataset("scenarios/indices")
+            .and_then(|ds| ds.read_1d::<H5ScenarioIndex>())
+            .map_err(|source| Hdf5Error::HDF5Error {
+                path: file.filename().into(),
+                source,
+            })?;
+
+        for (col_idx, scenario) in indices.into_iter().enumerate() {
+            scenarios.push((scenario, file_idx, col_idx));
+        }
+    }
+
+    scenarios.sort_by_key(|(scenario, _, _)| scenario.index);
+
+    for (simulation_id, (scenario, file_idx, _)) in scenarios.iter().enumerate() {
+        match scenario.index.cmp(&simulation_id) {
+            std::cmp::Ordering::Equal => {}
+            std::cmp::Ordering::Less => {
+                return Err(Hdf5Error::DuplicateSimulationId {
+                    path: files[*file_idx].filename().into(),
+                    simulation_id: scenario.index,
+                });
+            }
+            std::cmp::Ordering::Greater => return Err(Hdf5Error::MissingSimulationId { simulation_id }),
+        }
+    }
+
+    let file = hdf5_metno::File::create(output).map_err(|source| Hdf5Error::HDF5Error {
+        path: output.to_path_buf(),
+        source,
+    })?;
+
+    write_pywr_metadata(&file)?;
+
+    // The time table and scenario groups are the same in every file
+    let scenario_grp = require_group(file.deref(), "scenarios")?;
+    first
+        .dataset("time")
+        .and_then(|ds| ds.copy_to(&file, "time"))
+        .and_then(|_| first.dataset("scenarios/groups"))
+        .and_then(|ds| ds.copy_to(&scenario_grp, "groups"))
+        .map_err(|source| Hdf5Error::HDF5Error {
+            path: output.to_path_buf(),
+            source,
+        })?;
+
+    let indices: Array1<H5ScenarioIndex> = scenarios.iter().map(|(scenario, _, _)| scenario.clone()).collect();
+    scenario_grp
+        .new_dataset_builder()
+        .with_data(&indices)
+        .create("indices")
+        .map_err(|source| Hdf5Error::HDF5Error {
+            path: output.to_path_buf(),
+            source,
+        })?;
+
+    // Merge the dataset of each metric; these are stored as `/name/attribute`
+    let metric_names = first.member_names().map_err(|source| Hdf5Error::HDF5Error {
+        path: first.filename().into(),
+        source,
+    })?;
+
+    for name in metric_names.iter().filter(|n| *n != "time" && *n != "scenarios") {
+        let first_grp = first.group(name).map_err(|source| Hdf5Error::HDF5Error {
+            path: first.filename().into(),
+            source,
+        })?;
+        let attributes = first_grp.member_names().map_err(|source| Hdf5Error::HDF5Error {
+            path: first.filename().into(),
+            source,
+        })?;
+
+        let grp = require_group(file.deref(), name)?;
+
+        for attribute in attributes {
+            let path = format!("{name}/{attribute}");
+
+            let values = files
+                .iter()
+                .map(|f| {
+                    f.dataset(&path)
+                        .and_then(|ds| ds.read_2d::<f64>())
+                        .map_err(|source| Hdf5Error::HDF5MetricError {
+                            path: f.filename().into(),
+                            metric: path.clone(),
+                            source,
+                        })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let mut merged = Array2::<f64>::zeros((values[0].nrows(), scenarios.len()));
+            for (col, (_, file_idx, col_idx)) in scenarios.iter().enumerate() {
+                merged.column_mut(col).assign(&values[*file_idx].column(*col_idx));
+            }
+
+            let ds = grp
+                .new_dataset_builder()
+                .with_data(&merged)
+                .create(attribute.as_str())
+                .map_err(|source| Hdf5Error::HDF5MetricError {
+                    path: output.to_path_buf(),
+                    metric: path.clone(),
+                    source,
+                })?;
+
+            // Copy the type attributes of the metric
+            let first_ds = first_grp.dataset(&attribute).map_err(|source| Hdf5Error::HDF5Error {
+                path: first.filename().into(),
+                source,
+            })?;
+            for attr_name in first_ds.attr_names().map_err(|source| Hdf5Error::HDF5Error {
+                path: first.filename().into(),
+                source,
+            })? {
+                first_ds
+                    .attr(&attr_name)
+                    .and_then(|attr| attr.read_scalar::<hdf5_metno::types::VarLenUnicode>())
+                    .and_then(|value| {
+                        ds.new_attr::<hdf5_metno::types::VarLenUnicode>()
+                            .shape(())
+                            .create(attr_name.as_str())?
+                            .as_writer()
+                            .write_scalar(&value)
+                    })
+                    .map_err(|source| Hdf5Error::HDF5Error {
+                        path: output.to_path_buf(),
+                        source,
+                    })?;
+            }
+        }
+    }
+
+    file.close().map_err(|source| Hdf5Error::HDF5Error {
+        path: output.to_path_buf(),
+        source,
+    })?;
+
+    Ok(())
+}
@@ -0,0 +1,288 @@
+use super::{
+    CsvError, MetricSetState, Recorder, RecorderAggregationError, RecorderDataFrameError, RecorderFinalResult,
+    RecorderFinaliseError, RecorderInternalState, RecorderMeta, RecorderSaveError, RecorderSetupError,
+    downcast_internal_state, downcast_internal_state_mut,
+};
+use crate::metric::MetricU64;
+use crate::models::ModelDomain;
+use crate::network::Network;
+use crate::scenario::ScenarioIndex;
+use crate::state::State;
+use crate::timestep::Timestep;
+use chrono::NaiveDateTime;
+use polars::df;
+use polars::frame::DataFrame;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// A continuous period in which a level of service was not met.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LevelOfServiceEvent {
+    pub simulation_id: usize,
+    pub label: String,
+    /// The start of the first time-step of the event.
+    pub time_start: NaiveDateTime,
+    /// The end of the last time-step of the event.
+    pub time_end: NaiveDateTime,
+    /// The number of time-steps in the event.
+    pub duration: u64,
+    /// The most severe level reached during the event.
+    pub max_level: u64,
+}
+
+/// Records level of service events from an index metric, such as the level of a
+/// [`crate::parameters::DroughtTriggerParameter`].
+///
+/// An event starts when the metric is greater than or equal to `level` and lasts until it falls
+/// below `level` again. Events still in progress at the end of the simulation are ended at the end
+/// of the last time-step. The result gives the frequency, duration and start date of the events in
+/// each scenario, and its aggregated value is the mean number of events per scenario. If a
+/// `filename` is given the events are also written to a CSV file with a row for each event.
+pub struct LevelOfServiceRecorder {
+    meta: RecorderMeta,
+    metric: MetricU64,
+    level: u64,
+    filename: Option<PathBuf>,
+}
+
+struct Internal {
+    /// The event in progress in each scenario.
+    current: Vec<Option<LevelOfServiceEvent>>,
+    /// The completed events in each scenario.
+    events: Vec<Vec<LevelOfServiceEvent>>,
+}
+
+impl LevelOfServiceRecorder {
+    pub fn new(name: &str, metric: MetricU64, level: u64, filename: Option<PathBuf>) -> Self {
+        Self {
+            meta: RecorderMeta::new(name),
+            metric,
+            level,
+            filename,
+        }
+    }
+
+    fn write_csv(&self, filename: &Path, events: &[LevelOfServiceEvent]) -> Result<(), CsvError> {
+        let mut writer = csv::Writer::from_path(filename).map_err(|source| CsvError::CSVError {
+            path: filename.to_path_buf(),
+            source,
+        })?;
+
+        for event in events {
+            writer.serialize(event).map_err(|source| CsvError::CSVError {
+                path: filename.to_path_buf(),
+                source,
+            })?;
+        }
+
+        writer.flush().map_err(|source| CsvError::CSVError {
+            path: filename.to_path_buf(),
+            source: source.into(),
+        })?;
+
+        Ok(())
+    }
+}
+
+impl Recorder for LevelOfServiceRecorder {
+    fn meta(&self) -> &RecorderMeta {
+        &self.meta
+    }
+
+    fn setup(
+        &self,
+        domain: &ModelDomain,
+        _network: &Network,
+    ) -> Result<Option<Box<dyn RecorderInternalState>>, RecorderSetupError> {
+        let num_scenarios = domain.scenarios().len();
+        let internal = Internal {
+            current: vec![None; num_scenarios],
+            events: vec![Vec::new(); num_scenarios],
+        };
+
+        Ok(Some(Box::new(internal)))
+    }
+
+    fn save(
+        &self,
+        timestep: &Timestep,
+        scenario_indices: &[ScenarioIndex],
+        network: &Network,
+        state: &[State],
+        _metric_set_states: &[Vec<MetricSetState>],
+        internal_state: &mut Option<Box<dyn RecorderInternalState>>,
+    ) -> Result<(), RecorderSaveError> {
+        let internal = downcast_internal_state_mut::<Internal>(internal_state);
+
+        for scenario_index in scenario_indices {
+            let sim_id = scenario_index.simulation_id();
+            let level = self.metric.get_value(network, &state[sim_id])?;
+            let time_end = timestep.duration + timestep.date;
+
+            if level >= self.level {
+                match internal.current[sim_id].as_mut() {
+                    Some(event) => {
+                        event.time_end = time_end;
+                        event.duration += 1;
+                        event.max_level = event.max_level.max(level);
+                    }
+                    None => {
+                        internal.current[sim_id] = Some(LevelOfServiceEvent {
+                            simulation_id: scenario_index.global_simulation_id(),
+                            label: scenario_index.label(),
+                            time_start: timestep.date,
+                            time_end,
+                            duration: 1,
+                            max_level: level,
+                        });
+                    }
+                }
+            } else if let Some(event) = internal.current[sim_id].take() {
+                internal.events[sim_id].push(event);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn finalise(
+        &self,
+        _network: &Network,
+        _scenario_indices: &[ScenarioIndex],
+        _metric_set_states: &[Vec<MetricSetState>],
+        internal_state: Option<Box<dyn RecorderInternalState>>,
+    ) -> Result<Option<Box<dyn RecorderFinalResult>>, RecorderFinaliseError> {
+        let mut internal = downcast_internal_state::<Internal>(internal_state);
+
+        // End any events that are still in progress
+        for (current, events) in internal.current.iter_mut().zip(internal.events.iter_mut()) {
+            if let Some(event) = current.take() {
+                events.push(event);
+            }
+        }
+
+        if let Some(filename) = &self.filename {
+            let events = internal.events.iter().flatten().cloned().collect::<Vec<_>>();
+            self.write_csv(filename, &events)?;
+        }
+
+        let result = LevelOfServiceResult {
+            meta: self.meta.clone(),
+            events: internal.events,
+        };
+
+        Ok(Some(Box::new(result)))
+    }
+}
+
+/// The events recorded by a [`LevelOfServiceRecorder`].
+pub struct LevelOfServiceResult {
+    meta: RecorderMeta,
+    events: Vec<Vec<LevelOfServiceEvent>>,
+}
+
+impl LevelOfServiceResult {
+    /// The events in a scenario in order of time.
+    pub fn events(&self, simulation_id: usize) -> Option<&[LevelOfServiceEvent]> {
+        self.events.get(simulation_id).map(|e| e.as_slice())
+    }
+
+    /// The number of events in each scenario.
+    pub fn frequency(&self) -> Vec<usize> {
+        self.events.iter().map(|e| e.len()).collect()
+    }
+
+    /// The total number of time-steps in events in each scenario.
+    pub fn total_duration(&self) -> Vec<u64> {
+        self.events.iter().map(|e| e.iter().map(|e| e.duration).sum()).collect()
+    }
+
+    /// The start of the first event in each scenario, if there is one.
+    pub fn first_entry(&self) -> Vec<Option<NaiveDateTime>> {
+        self.events.iter().map(|e| e.first().map(|e| e.time_start)).collect()
+    }
+}
+
+impl RecorderFinalResult for LevelOfServiceResult {
+    fn aggregated_value(&self) -> Result<f64, RecorderAggregationError> {
+        if self.events.is_empty() {
+            return Ok(0.0);
+        }
+
+        let total: usize = self.events.iter().map(|e| e.len()).sum();
+        Ok(total as f64 / self.events.len() as f64)
+    }
+
+    fn to_dataframe(&self) -> Result<DataFrame, RecorderDataFrameError> {
+        let events = self.events.iter().flatten().collect::<Vec<_>>();
+
+        df!(
+            "simulation_id" => events.iter().map(|e| e.simulation_id as u32).collect::<Vec<_>>(),
+            "label" => events.iter().map(|e| e.label.as_str()).collect::<Vec<_>>(),
+            "time_start" => events.iter().map(|e| e.time_start).collect::<Vec<_>>(),
+            "time_end" => events.iter().map(|e| e.time_end).collect::<Vec<_>>(),
+            "duration" => events.iter().map(|e| e.duration).collect::<Vec<_>>(),
+            "max_level" => events.iter().map(|e| e.max_level).collect::<Vec<_>>(),
+        )
+        .map_err(|source| RecorderDataFrameError::PolarsError {
+            name: self.meta.name.clone(),
+            source,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LevelOfServiceRecorder, LevelOfServiceResult};
+    use crate::parameters::Array1Parameter;
+    use crate::solvers::{ClpSolver, ClpSolverSettings};
+    use crate::test_utils::simple_model;
+    use chrono::NaiveDate;
+    use ndarray::Array1;
+    use std::any::Any;
+
+    /// Test the events of an index series with two events, one of which is still in progress.
+    #[test]
+    fn test_level_of_service() {
+        let mut model = simple_model(1, None);
+        let network = model.network_mut();
+
+        let levels = Array1::from_vec(vec![0, 1, 2, 1, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1]);
+        let levels = Array1Parameter::new("levels".into(), levels, None);
+        let levels = network.add_simple_index_parameter(Box::new(levels)).unwrap();
+
+        let recorder = LevelOfServiceRecorder::new("los", levels.into_metric_u64_before(), 1, None);
+        network.add_recorder(Box::new(recorder)).unwrap();
+
+        let result = model
+            .run::<ClpSolver>(&ClpSolverSettings::default())
+            .expect("Failed to solve");
+
+        let los = result.network_result.get("los").unwrap();
+        let los = (los as &dyn Any).downcast_ref::<LevelOfServiceResult>().unwrap();
+
+        assert_eq!(los.frequency(), vec![2]);
+        assert_eq!(los.total_duration(), vec![6]);
+
+        let events = los.events(0).unwrap();
+        assert_eq!(events[0].duration, 3);
+        assert_eq!(events[0].max_level, 2);
+        assert_eq!(
+            events[0].time_start,
+            NaiveDate::from_ymd_opt(2020, 1, 2)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+        );
+        assert_eq!(events[1].duration, 3);
+        assert_eq!(
+            events[1].time_end,
+            NaiveDate::from_ymd_opt(2020, 1, 16)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+        );
+
+        assert_eq!(result.network_result.get_aggregated_value("los"), Some(2.0));
+    }
+}
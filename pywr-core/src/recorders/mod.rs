@@ -3,29 +3,32 @@ mod csv;
 
 #[cfg(feature = "hdf5")]
 mod hdf;
+mod level_of_service;
 mod memory;
 mod metric_set;
 mod py;
+mod rule_trace;
 
 use crate::metric::{MetricF64, MetricF64Error, MetricU64, MetricU64Error};
 use crate::models::ModelDomain;
 use crate::network::Network;
-use crate::recorders::csv::CsvError;
-#[cfg(feature = "hdf5")]
-use crate::recorders::hdf::Hdf5Error;
 use crate::scenario::ScenarioIndex;
 use crate::state::State;
 use crate::timestep::Timestep;
 pub use aggregator::{AggregationFrequency, Aggregator, PeriodValue};
-pub use csv::{CsvLongFmtOutput, CsvLongFmtRecord, CsvWideFmtOutput};
+pub use csv::{
+    CsvError, CsvLongFmtOutput, CsvLongFmtRecord, CsvWideFmtOutput, merge_csv_long_outputs, merge_csv_wide_outputs,
+};
 use float_cmp::{ApproxEq, F64Margin, approx_eq};
 #[cfg(feature = "hdf5")]
-pub use hdf::HDF5Recorder;
+pub use hdf::{HDF5Recorder, Hdf5Error, merge_hdf5_outputs};
+pub use level_of_service::{LevelOfServiceEvent, LevelOfServiceRecorder, LevelOfServiceResult};
 pub use memory::{Aggregation, AggregationError, AggregationOrder, MemoryRecorder};
 pub use metric_set::{MetricSet, MetricSetIndex, MetricSetSaveError, MetricSetState, OutputMetric};
 use ndarray::Array2;
 use ndarray::prelude::*;
 use polars::prelude::PolarsError;
+pub use rule_trace::{RuleTraceRecord, RuleTraceRecorder, RuleTraceResult};
 use std::any::Any;
 use std::fmt;
 use std::fmt::{Display, Formatter};
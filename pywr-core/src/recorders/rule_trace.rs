@@ -0,0 +1,242 @@
+use super::{
+    CsvError, MetricSetState, Recorder, RecorderDataFrameError, RecorderFinalResult, RecorderFinaliseError,
+    RecorderInternalState, RecorderMeta, RecorderSaveError, RecorderSetupError, downcast_internal_state,
+    downcast_internal_state_mut,
+};
+use crate::metric::MetricU64;
+use crate::models::ModelDomain;
+use crate::network::Network;
+use crate::scenario::ScenarioIndex;
+use crate::state::State;
+use crate::timestep::Timestep;
+use chrono::NaiveDateTime;
+use polars::df;
+use polars::frame::DataFrame;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::path::PathBuf;
+
+/// The rule that applied in a time-step and scenario.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleTraceRecord {
+    pub time_start: NaiveDateTime,
+    pub time_end: NaiveDateTime,
+    pub simulation_id: usize,
+    pub label: String,
+    pub rule_index: u64,
+    pub rule: String,
+}
+
+/// Records which rule of a rule set applied in each time-step and scenario.
+///
+/// The rule is read from an index `metric`, typically the `rule` output of a
+/// [`crate::parameters::RuleSetParameter`], and is named using `rule_names`. An index beyond the
+/// given names is recorded with the `default_name`. If a `filename` is given the trace is also
+/// written to a CSV file with a row for each time-step and scenario.
+pub struct RuleTraceRecorder {
+    meta: RecorderMeta,
+    metric: MetricU64,
+    rule_names: Vec<String>,
+    default_name: String,
+    filename: Option<PathBuf>,
+}
+
+struct Internal {
+    writer: Option<csv::Writer<File>>,
+    records: Vec<RuleTraceRecord>,
+}
+
+impl RuleTraceRecorder {
+    pub fn new(
+        name: &str,
+        metric: MetricU64,
+        rule_names: Vec<String>,
+        default_name: &str,
+        filename: Option<PathBuf>,
+    ) -> Self {
+        Self {
+            meta: RecorderMeta::new(name),
+            metric,
+            rule_names,
+            default_name: default_name.to_string(),
+            filename,
+        }
+    }
+
+    fn rule_name(&self, index: u64) -> &str {
+        self.rule_names
+            .get(index as usize)
+            .map(|n| n.as_str())
+            .unwrap_or(self.default_name.as_str())
+    }
+}
+
+impl Recorder for RuleTraceRecorder {
+    fn meta(&self) -> &RecorderMeta {
+        &self.meta
+    }
+
+    fn setup(
+        &self,
+        _domain: &ModelDomain,
+        _network: &Network,
+    ) -> Result<Option<Box<dyn RecorderInternalState>>, RecorderSetupError> {
+        let writer = match &self.filename {
+            Some(filename) => Some(csv::Writer::from_path(filename).map_err(|source| CsvError::CSVError {
+                path: filename.clone(),
+                source,
+            })?),
+            None => None,
+        };
+
+        let internal = Internal {
+            writer,
+            records: Vec::new(),
+        };
+
+        Ok(Some(Box::new(internal)))
+    }
+
+    fn save(
+        &self,
+        timestep: &Timestep,
+        scenario_indices: &[ScenarioIndex],
+        network: &Network,
+        state: &[State],
+        _metric_set_states: &[Vec<MetricSetState>],
+        internal_state: &mut Option<Box<dyn RecorderInternalState>>,
+    ) -> Result<(), RecorderSaveError> {
+        let internal = downcast_internal_state_mut::<Internal>(internal_state);
+
+        for scenario_index in scenario_indices {
+            let rule_index = self.metric.get_value(network, &state[scenario_index.simulation_id()])?;
+
+            let record = RuleTraceRecord {
+                time_start: timestep.date,
+                time_end: timestep.duration + timestep.date,
+                simulation_id: scenario_index.global_simulation_id(),
+                label: scenario_index.label(),
+                rule_index,
+                rule: self.rule_name(rule_index).to_string(),
+            };
+
+            if let (Some(writer), Some(filename)) = (internal.writer.as_mut(), self.filename.as_ref()) {
+                writer.serialize(&record).map_err(|source| CsvError::CSVError {
+                    path: filename.clone(),
+                    source,
+                })?;
+            }
+
+            internal.records.push(record);
+        }
+
+        Ok(())
+    }
+
+    fn finalise(
+        &self,
+        _network: &Network,
+        _scenario_indices: &[ScenarioIndex],
+        _metric_set_states: &[Vec<MetricSetState>],
+        internal_state: Option<Box<dyn RecorderInternalState>>,
+    ) -> Result<Option<Box<dyn RecorderFinalResult>>, RecorderFinaliseError> {
+        let internal = downcast_internal_state::<Internal>(internal_state);
+
+        if let (Some(mut writer), Some(filename)) = (internal.writer, self.filename.as_ref()) {
+            writer.flush().map_err(|source| CsvError::CSVError {
+                path: filename.clone(),
+                source: source.into(),
+            })?;
+        }
+
+        let result = RuleTraceResult {
+            meta: self.meta.clone(),
+            records: internal.records,
+        };
+
+        Ok(Some(Box::new(result)))
+    }
+}
+
+/// The rules recorded by a [`RuleTraceRecorder`].
+pub struct RuleTraceResult {
+    meta: RecorderMeta,
+    records: Vec<RuleTraceRecord>,
+}
+
+impl RuleTraceResult {
+    /// The recorded rules in order of time-step and then scenario.
+    pub fn records(&self) -> &[RuleTraceRecord] {
+        &self.records
+    }
+}
+
+impl RecorderFinalResult for RuleTraceResult {
+    fn to_dataframe(&self) -> Result<DataFrame, RecorderDataFrameError> {
+        df!(
+            "time_start" => self.records.iter().map(|r| r.time_start).collect::<Vec<_>>(),
+            "time_end" => self.records.iter().map(|r| r.time_end).collect::<Vec<_>>(),
+            "simulation_id" => self.records.iter().map(|r| r.simulation_id as u32).collect::<Vec<_>>(),
+            "label" => self.records.iter().map(|r| r.label.as_str()).collect::<Vec<_>>(),
+            "rule_index" => self.records.iter().map(|r| r.rule_index).collect::<Vec<_>>(),
+            "rule" => self.records.iter().map(|r| r.rule.as_str()).collect::<Vec<_>>(),
+        )
+        .map_err(|source| RecorderDataFrameError::PolarsError {
+            name: self.meta.name.clone(),
+            source,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RuleTraceRecorder, RuleTraceResult};
+    use crate::metric::MetricF64;
+    use crate::parameters::{Rule, RuleCondition, RuleSetParameter};
+    use crate::recorders::RecorderFinalResult;
+    use crate::solvers::{ClpSolver, ClpSolverSettings};
+    use crate::test_utils::simple_model;
+    use std::any::Any;
+
+    /// Test the trace of a rule that applies in the first week.
+    #[test]
+    fn test_rule_trace() {
+        let mut model = simple_model(2, None);
+        let network = model.network_mut();
+
+        let rules = vec![Rule {
+            name: "first-week".to_string(),
+            conditions: vec![RuleCondition::DayOfYear { start: 1, end: 7 }],
+            value: MetricF64::from(2.0),
+        }];
+        let p = RuleSetParameter::new("rules".into(), rules, MetricF64::from(1.0));
+        let idx = network.add_multi_value_parameter(Box::new(p)).unwrap();
+
+        let recorder = RuleTraceRecorder::new(
+            "trace",
+            idx.into_metric_u64_before("rule"),
+            vec!["first-week".to_string()],
+            "default",
+            None,
+        );
+        network.add_recorder(Box::new(recorder)).unwrap();
+
+        let result = model
+            .run::<ClpSolver>(&ClpSolverSettings::default())
+            .expect("Failed to solve");
+
+        let trace = result.network_result.get("trace").unwrap();
+        let trace = (trace as &dyn Any).downcast_ref::<RuleTraceResult>().unwrap();
+
+        // 15 time-steps for each of the two scenarios
+        assert_eq!(trace.records().len(), 30);
+        for (i, record) in trace.records().iter().enumerate() {
+            let expected = if i / 2 < 7 { "first-week" } else { "default" };
+            assert_eq!(record.rule, expected);
+            assert_eq!(record.simulation_id, i % 2);
+        }
+
+        let df = trace.to_dataframe().unwrap();
+        assert_eq!(df.shape(), (30, 6));
+    }
+}
@@ -2,6 +2,9 @@
 #[cfg(feature = "pyo3")]
 use pyo3::{pyclass, pymethods};
 use std::collections::BTreeSet;
+use std::num::NonZeroUsize;
+use std::ops::Range;
+use std::str::FromStr;
 use thiserror::Error;
 
 /// Errors that can occur when building a [`ScenarioDomain`].
@@ -30,6 +33,10 @@ pub enum ScenarioError {
         start: usize,
         end: usize,
     },
+    #[error("Invalid shard `{0}`; expected `INDEX/COUNT` with INDEX less than COUNT")]
+    InvalidShard(String),
+    #[error("Shard {index}/{count} contains no scenarios; the domain only has {size} scenarios")]
+    EmptyShard { index: usize, count: usize, size: usize },
 }
 
 /// A subset of a scenario group to run, either defined as a slice or as specific indices.
@@ -261,11 +268,76 @@ impl From<usize> for ScenarioLabelOrIndex {
     }
 }
 
+/// A deterministic part of a [`ScenarioDomain`] to simulate.
+///
+/// The scenarios of the domain are split into `count` contiguous shards of (nearly) equal
+/// size, and only the shard at (zero based) `index` is simulated. This allows the scenarios
+/// of a model to be run across several processes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScenarioShard {
+    index: usize,
+    count: NonZeroUsize,
+}
+
+impl ScenarioShard {
+    /// Create a new shard.
+    ///
+    /// # Errors
+    ///
+    /// - [`ScenarioError::InvalidShard`] if `index` is not less than `count`.
+    pub fn new(index: usize, count: usize) -> Result<Self, ScenarioError> {
+        match NonZeroUsize::new(count) {
+            Some(count) if index < count.get() => Ok(Self { index, count }),
+            _ => Err(ScenarioError::InvalidShard(format!("{index}/{count}"))),
+        }
+    }
+
+    #[must_use]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    #[must_use]
+    pub fn count(&self) -> usize {
+        self.count.get()
+    }
+
+    /// The range of scenarios in this shard from a domain of `len` scenarios.
+    #[must_use]
+    pub fn range(&self, len: usize) -> Range<usize> {
+        let count = self.count.get();
+        (len * self.index / count)..(len * (self.index + 1) / count)
+    }
+}
+
+impl FromStr for ScenarioShard {
+    type Err = ScenarioError;
+
+    /// Parse a shard from a string of the form `INDEX/COUNT`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (index, count) = s
+            .split_once('/')
+            .ok_or_else(|| ScenarioError::InvalidShard(s.to_string()))?;
+
+        let index = index
+            .trim()
+            .parse()
+            .map_err(|_| ScenarioError::InvalidShard(s.to_string()))?;
+        let count = count
+            .trim()
+            .parse()
+            .map_err(|_| ScenarioError::InvalidShard(s.to_string()))?;
+
+        Self::new(index, count)
+    }
+}
+
 /// A builder for creating a [`ScenarioDomain`].
 #[derive(Clone, Debug, Default)]
 pub struct ScenarioDomainBuilder {
     groups: Vec<ScenarioGroup>,
     combinations: Option<Vec<Vec<ScenarioLabelOrIndex>>>,
+    shard: Option<ScenarioShard>,
 }
 
 impl ScenarioDomainBuilder {
@@ -301,6 +373,16 @@ impl ScenarioDomainBuilder {
         self
     }
 
+    /// Only simulate a shard of the scenarios.
+    ///
+    /// The shard is taken from the scenarios remaining after any subsets or combinations
+    /// have been applied.
+    #[must_use]
+    pub fn with_shard(mut self, shard: ScenarioShard) -> Self {
+        self.shard = Some(shard);
+        self
+    }
+
     /// Build a map of simulation indices to schema indices for each group
     fn build_scenario_map_from_subsets(&self) -> Vec<Option<Vec<usize>>> {
         let mut scenario_map: Vec<Option<Vec<usize>>> = vec![None; self.groups.len()];
@@ -518,10 +600,38 @@ impl ScenarioDomainBuilder {
             (scenario_indices, self.groups, scenario_map)
         };
 
+        let global_len = indices.len();
+        let indices = match self.shard {
+            Some(shard) => {
+                let range = shard.range(global_len);
+                if range.is_empty() {
+                    return Err(ScenarioError::EmptyShard {
+                        index: shard.index(),
+                        count: shard.count(),
+                        size: global_len,
+                    });
+                }
+
+                indices
+                    .into_iter()
+                    .skip(range.start)
+                    .take(range.len())
+                    .enumerate()
+                    .map(|(simulation_id, mut scenario_index)| {
+                        scenario_index.global_id = Some(scenario_index.core.index);
+                        scenario_index.core.index = simulation_id;
+                        scenario_index
+                    })
+                    .collect()
+            }
+            None => indices,
+        };
+
         Ok(ScenarioDomain {
             indices,
             groups,
             scenario_map,
+            global_len,
         })
     }
 }
@@ -582,6 +692,7 @@ impl ScenarioIndexBuilder {
             core: self.core,
             schema: self.schema,
             labels: self.labels,
+            global_id: None,
         }
     }
 }
@@ -597,6 +708,9 @@ pub struct ScenarioIndex {
     schema: Option<ScenarioIndices>,
     /// Labels to use for the scenario; one for each group
     labels: Vec<String>,
+    /// The global index of the scenario when only a shard of the domain is simulated.
+    /// Otherwise, it will be `None`.
+    global_id: Option<usize>,
 }
 
 impl Default for ScenarioIndex {
@@ -605,6 +719,7 @@ impl Default for ScenarioIndex {
             core: ScenarioIndices::default(),
             schema: None,
             labels: vec!["0".to_string()],
+            global_id: None,
         }
     }
 }
@@ -626,6 +741,13 @@ impl ScenarioIndex {
     pub fn get_simulation_indices(&self) -> &[usize] {
         &self.core.indices
     }
+
+    /// The global index of the scenario across all shards of the simulation.
+    #[getter]
+    #[must_use]
+    pub fn get_global_simulation_id(&self) -> usize {
+        self.global_simulation_id()
+    }
 }
 
 impl ScenarioIndex {
@@ -641,6 +763,15 @@ impl ScenarioIndex {
         &self.core.indices
     }
 
+    /// The global index of the scenario across all shards of the simulation. This is the
+    /// same as [`ScenarioIndex::simulation_id`] unless only a shard of the domain is simulated.
+    ///
+    /// Outputs should use this index so that the results of each shard can be merged.
+    #[must_use]
+    pub fn global_simulation_id(&self) -> usize {
+        self.global_id.unwrap_or(self.core.index)
+    }
+
     #[must_use]
     pub fn simulation_index_for_group(&self, group_index: usize) -> usize {
         self.core.indices[group_index]
@@ -674,6 +805,7 @@ pub struct ScenarioDomain {
     indices: Vec<ScenarioIndex>,
     groups: Vec<ScenarioGroup>,
     scenario_map: Vec<Option<Vec<usize>>>,
+    global_len: usize,
 }
 
 impl ScenarioDomain {
@@ -683,6 +815,14 @@ impl ScenarioDomain {
         self.indices.len()
     }
 
+    /// The total number of scenario combinations across all shards of the domain.
+    ///
+    /// This is the same as [`ScenarioDomain::len`] unless only a shard of the domain is simulated.
+    #[must_use]
+    pub fn global_len(&self) -> usize {
+        self.global_len
+    }
+
     /// Whether the domain contains any indices.
     #[must_use]
     pub fn is_empty(&self) -> bool {
@@ -736,7 +876,9 @@ impl ScenarioDomain {
 
 #[cfg(test)]
 mod tests {
-    use super::{ScenarioDomain, ScenarioDomainBuilder, ScenarioError, ScenarioGroupBuilder};
+    use super::{
+        ScenarioDomain, ScenarioDomainBuilder, ScenarioError, ScenarioGroupBuilder, ScenarioIndex, ScenarioShard,
+    };
 
     #[test]
     fn test_group_builder() {
@@ -969,4 +1111,51 @@ mod tests {
         assert_eq!(si.simulation_id(), 3);
         assert_eq!(si.simulation_indices(), &[1, 1, 2]);
     }
+
+    #[test]
+    /// Test [`ScenarioDomain`] with shards
+    fn test_scenario_shards() {
+        let build_shard = |shard: &str| {
+            let group_a = ScenarioGroupBuilder::new("A", 5).build().unwrap();
+            let group_b = ScenarioGroupBuilder::new("B", 2).build().unwrap();
+
+            ScenarioDomainBuilder::default()
+                .with_group(group_a)
+                .unwrap()
+                .with_group(group_b)
+                .unwrap()
+                .with_shard(shard.parse().unwrap())
+                .build()
+        };
+
+        let domain = build_shard("1/3").unwrap();
+        assert_eq!(domain.len(), 3);
+        assert_eq!(domain.global_len(), 10);
+
+        let si = &domain.indices()[0];
+        assert_eq!(si.simulation_id(), 0);
+        assert_eq!(si.global_simulation_id(), 3);
+        assert_eq!(si.simulation_indices(), &[1, 1]);
+
+        // All the shards together cover the whole domain once
+        let global_ids: Vec<usize> = (0..3)
+            .flat_map(|i| {
+                let domain = build_shard(&format!("{i}/3")).unwrap();
+                domain
+                    .indices()
+                    .iter()
+                    .map(ScenarioIndex::global_simulation_id)
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        assert_eq!(global_ids, (0..10).collect::<Vec<_>>());
+
+        assert!(matches!(
+            build_shard("0/12").err().unwrap(),
+            ScenarioError::EmptyShard { .. }
+        ));
+        assert!("3/3".parse::<ScenarioShard>().is_err());
+        assert!("1-3".parse::<ScenarioShard>().is_err());
+        assert!("0/0".parse::<ScenarioShard>().is_err());
+    }
 }
@@ -1,9 +1,9 @@
-use crate::aggregated_node::AggregatedNodeIndex;
+use crate::aggregated_node::{AggregatedNodeIndex, RhsConstraint};
 use crate::edge::EdgeIndex;
 use crate::network::Network;
 use crate::node::{Node, NodeBounds, NodeIndex, NodeType};
 use crate::solvers::col_edge_map::{ColumnEdgeMap, ColumnEdgeMapBuilder};
-use crate::solvers::{SolverSetupError, SolverSolveError, SolverTimings};
+use crate::solvers::{Forecast, SolverSetupError, SolverSolveError, SolverTimings};
 use crate::state::{ConstParameterValues, State};
 use crate::timestep::Timestep;
 use std::collections::{BTreeMap, HashMap, HashSet};
@@ -45,6 +45,8 @@ struct Lp<I> {
     row_starts: Vec<I>,
     columns: Vec<I>,
     elements: Vec<f64>,
+    /// The number of variable rows; these are the first rows of the LP.
+    num_variable_rows: usize,
 
     coefficients_to_update: Vec<(I, I, f64)>,
 }
@@ -53,9 +55,11 @@ impl<I> Lp<I>
 where
     I: num::PrimInt,
 {
-    /// Zero all objective coefficients.
-    fn zero_obj_coefficients(&mut self) {
-        self.col_obj_coef.fill(0.0);
+    /// Zero the objective coefficients of the given columns.
+    fn zero_obj_coefficients(&mut self, cols: &[I]) {
+        for col in cols {
+            self.col_obj_coef[col.to_usize().unwrap()] = 0.0;
+        }
     }
 
     /// Increment the given column's objective coefficient.
@@ -90,34 +94,59 @@ where
         self.row_upper[row] = self.row_upper[row].min(ub);
     }
 
-    fn update_row_coefficients(&mut self, row: I, node: &Node, factor: f64, col_edge_map: &ColumnEdgeMap<I>) {
+    /// Add a coefficient to update. If the column has already been updated in the same row the
+    /// values are summed. This occurs when the node sets in a row share an edge (e.g. a storage
+    /// node and one of its outflows) and matches how [`RowBuilder::add_element`] creates the row.
+    fn push_coefficient_update(&mut self, row: I, column: I, value: f64) {
+        match self
+            .coefficients_to_update
+            .iter_mut()
+            .rev()
+            .take_while(|(r, _, _)| *r == row)
+            .find(|(_, c, _)| *c == column)
+        {
+            Some(entry) => entry.2 += value,
+            None => self.coefficients_to_update.push((row, column, value)),
+        }
+    }
+
+    /// Update the coefficients of a node's columns in a row. The `col_offset` is added to each
+    /// column to select the period of a multi-period LP.
+    fn update_row_coefficients(
+        &mut self,
+        row: I,
+        node: &Node,
+        factor: f64,
+        col_edge_map: &ColumnEdgeMap<I>,
+        col_offset: I,
+    ) {
         match node.node_type() {
             NodeType::Link => {
                 for edge in node.get_outgoing_edges().unwrap() {
-                    let column = col_edge_map.col_for_edge(edge);
-                    self.coefficients_to_update.push((row, column, factor))
+                    let column = col_edge_map.col_for_edge(edge) + col_offset;
+                    self.push_coefficient_update(row, column, factor)
                 }
             }
             NodeType::Input => {
                 for edge in node.get_outgoing_edges().unwrap() {
-                    let column = col_edge_map.col_for_edge(edge);
-                    self.coefficients_to_update.push((row, column, factor))
+                    let column = col_edge_map.col_for_edge(edge) + col_offset;
+                    self.push_coefficient_update(row, column, factor)
                 }
             }
             NodeType::Output => {
                 for edge in node.get_incoming_edges().unwrap() {
-                    let column = col_edge_map.col_for_edge(edge);
-                    self.coefficients_to_update.push((row, column, factor))
+                    let column = col_edge_map.col_for_edge(edge) + col_offset;
+                    self.push_coefficient_update(row, column, factor)
                 }
             }
             NodeType::Storage => {
                 for edge in node.get_incoming_edges().unwrap() {
-                    let column = col_edge_map.col_for_edge(edge);
-                    self.coefficients_to_update.push((row, column, factor))
+                    let column = col_edge_map.col_for_edge(edge) + col_offset;
+                    self.push_coefficient_update(row, column, factor)
                 }
                 for edge in node.get_outgoing_edges().unwrap() {
-                    let column = col_edge_map.col_for_edge(edge);
-                    self.coefficients_to_update.push((row, column, factor))
+                    let column = col_edge_map.col_for_edge(edge) + col_offset;
+                    self.push_coefficient_update(row, column, -factor)
                 }
             }
         }
@@ -173,6 +202,11 @@ where
         I::from(self.col_lower.len() - 1).unwrap()
     }
 
+    /// Increment the given column's objective coefficient.
+    fn add_obj_coefficient(&mut self, col: usize, obj_coef: f64) {
+        self.col_obj_coef[col] += obj_coef;
+    }
+
     /// Add a fixed row to the LP.
     ///
     /// This row is always added to the end of the LP, and does not return its row number
@@ -202,6 +236,7 @@ where
     /// Build the LP into a final sparse form
     fn build(self) -> Lp<I> {
         let nrows = self.rows.len();
+        let num_variable_rows = nrows;
         let mut row_lower = Vec::with_capacity(nrows);
         let mut row_upper = Vec::with_capacity(nrows);
         let mut row_mask = Vec::with_capacity(nrows);
@@ -241,6 +276,7 @@ where
             row_starts,
             columns,
             elements,
+            num_variable_rows,
             coefficients_to_update: Vec::new(),
         }
     }
@@ -297,6 +333,8 @@ struct NodeRowId<I> {
     row_id: I,
     node_idx: NodeIndex,
     row_type: NodeRowType<I>,
+    /// The period of the look-ahead horizon the row applies to.
+    period: usize,
 }
 
 /// The row id types associated with a node's constraints.
@@ -310,8 +348,20 @@ enum NodeRowType<I> {
     BinaryLowerBound { bin_col_id: I },
 }
 
+/// The binary column and rows associated with a node's semi-continuous flow.
+struct SemiContinuousCols<I> {
+    node_idx: NodeIndex,
+    period: usize,
+    bin_col_id: I,
+    /// Row enforcing the maximum flow. If `None` the row is fixed and does not need updating.
+    upper_bound_row_id: Option<I>,
+    /// Row enforcing the minimum flow if on. If `None` the row is fixed and does not need updating.
+    lower_bound_row_id: Option<I>,
+}
+
 struct AggNodeFactorRow<I> {
     agg_node_idx: AggregatedNodeIndex,
+    period: usize,
     // Row index for each node-pair. If `None` the row is fixed and does not need updating.
     row_indices: Vec<Option<I>>,
 }
@@ -319,10 +369,20 @@ struct AggNodeFactorRow<I> {
 pub struct BuiltSolver<I> {
     builder: Lp<I>,
     col_edge_map: ColumnEdgeMap<I>,
+    horizon: usize,
+    num_edge_cols: usize,
     node_constraints_row_ids: Vec<NodeRowId<I>>,
-    agg_node_constraint_row_ids: Vec<usize>,
+    /// Row ids of the aggregated node constraints for each period. If `None` the row is fixed
+    /// and does not need updating.
+    agg_node_constraint_row_ids: Vec<Vec<Option<usize>>>,
     agg_node_factor_constraint_row_ids: Vec<AggNodeFactorRow<I>>,
-    virtual_storage_constraint_row_ids: Vec<usize>,
+    /// Row ids of the virtual storage constraints for each period.
+    virtual_storage_constraint_row_ids: Vec<Vec<usize>>,
+    semi_continuous_cols: Vec<SemiContinuousCols<I>>,
+    /// Edges whose columns do not have a constant objective coefficient.
+    dynamic_cost_edges: Vec<EdgeIndex>,
+    /// Columns whose objective coefficient is updated each time-step.
+    dynamic_obj_cols: Vec<I>,
 }
 
 impl<I> BuiltSolver<I>
@@ -374,6 +434,20 @@ where
         &self.builder.row_mask
     }
 
+    /// The number of variable rows. These are the first rows of the LP, and the only rows whose
+    /// bounds are changed by [`BuiltSolver::update`].
+    #[allow(dead_code)]
+    pub fn num_variable_rows(&self) -> usize {
+        self.builder.num_variable_rows
+    }
+
+    /// The columns whose objective coefficients are changed by [`BuiltSolver::update`]. The
+    /// objective coefficients of all other columns are constant.
+    #[allow(dead_code)]
+    pub fn dynamic_obj_cols(&self) -> &[I] {
+        &self.dynamic_obj_cols
+    }
+
     pub fn row_starts(&self) -> &[I] {
         &self.builder.row_starts
     }
@@ -386,10 +460,21 @@ where
         &self.builder.elements
     }
 
+    /// The column of an edge in the first period; i.e. the current time-step.
     pub fn col_for_edge(&self, edge_index: &EdgeIndex) -> I {
         self.col_edge_map.col_for_edge(edge_index)
     }
 
+    /// The number of time-steps in the look-ahead horizon, including the current time-step.
+    pub fn horizon(&self) -> usize {
+        self.horizon
+    }
+
+    /// The offset of the edge columns for the given period.
+    fn col_offset(&self, period: usize) -> I {
+        I::from(period * self.num_edge_cols).unwrap()
+    }
+
     #[allow(dead_code)]
     pub fn coefficients_to_update(&self) -> &[(I, I, f64)] {
         &self.builder.coefficients_to_update
@@ -416,15 +501,26 @@ where
         }
     }
 
+    /// Update the LP for the current time-step.
+    ///
+    /// If the LP has a look-ahead horizon the `forecasts` are used for the following periods. If
+    /// fewer forecasts than periods are given the last forecast (or the current time-step if there
+    /// are none) is repeated for the remaining periods.
     pub fn update(
         &mut self,
         network: &Network,
         timestep: &Timestep,
         state: &State,
+        forecasts: &[Forecast],
         timings: &mut SolverTimings,
     ) -> Result<(), SolverSolveError> {
         let start_objective_update = Instant::now();
-        self.update_edge_objectives(network, state)?;
+        self.builder.zero_obj_coefficients(&self.dynamic_obj_cols);
+        for period in 0..self.horizon {
+            let (_, period_state) = Self::period(period, timestep, state, forecasts);
+            self.update_edge_objectives(network, period_state, period)?;
+        }
+        self.update_semi_continuous_objectives(network, state)?;
         timings.update_objective += start_objective_update.elapsed();
 
         let start_constraint_update = Instant::now();
@@ -432,19 +528,56 @@ where
         self.builder.reset_row_bounds();
         self.builder.reset_coefficients_to_update();
         // Then these methods will add their bounds
-        self.update_node_constraint_bounds(network, timestep, state)?;
-        self.update_aggregated_node_factor_constraints(network, state)?;
-        self.update_aggregated_node_constraint_bounds(network, state)?;
-        self.update_virtual_storage_node_constraint_bounds(network, timestep, state)?;
+        self.update_node_constraint_bounds(network, timestep, state, forecasts)?;
+        self.update_aggregated_node_factor_constraints(network, timestep, state, forecasts)?;
+        for period in 0..self.horizon {
+            let (period_timestep, period_state) = Self::period(period, timestep, state, forecasts);
+            self.update_aggregated_node_constraint_bounds(network, period_state, period)?;
+            // Storage continuity is expressed in terms of the current time-step's duration.
+            self.update_virtual_storage_node_constraint_bounds(
+                network,
+                period_timestep,
+                period_state,
+                timestep.days(),
+                period,
+            )?;
+        }
+        self.update_semi_continuous_constraints(network, timestep, state, forecasts)?;
         timings.update_constraints += start_constraint_update.elapsed();
 
         Ok(())
     }
 
-    /// Update edge objective coefficients
-    fn update_edge_objectives(&mut self, network: &Network, state: &State) -> Result<(), SolverSolveError> {
-        self.builder.zero_obj_coefficients();
-        for edge in network.edges().deref() {
+    /// Return the time-step and state for the given period of the look-ahead horizon.
+    fn period<'a>(
+        period: usize,
+        timestep: &'a Timestep,
+        state: &'a State,
+        forecasts: &'a [Forecast],
+    ) -> (&'a Timestep, &'a State) {
+        if period == 0 {
+            return (timestep, state);
+        }
+
+        match forecasts.get(period - 1).or(forecasts.last()) {
+            Some(forecast) => (&forecast.timestep, &forecast.state),
+            None => (timestep, state),
+        }
+    }
+
+    /// Update the objective coefficients of the edges without a constant cost for a period
+    fn update_edge_objectives(
+        &mut self,
+        network: &Network,
+        state: &State,
+        period: usize,
+    ) -> Result<(), SolverSolveError> {
+        let col_offset = self.col_offset(period);
+        for edge_index in self.dynamic_cost_edges.iter() {
+            let edge = network
+                .edges()
+                .get(edge_index)
+                .expect("Edge index not found in the network!");
             let obj_coef: f64 = edge.cost(network.nodes(), network, state).map_err(|source| {
                 let from_node = match network.get_node(&edge.from_node_index()) {
                     Some(n) => n,
@@ -464,23 +597,119 @@ where
                     source,
                 }
             })?;
-            let col = self.col_for_edge(&edge.index());
+            let col = self.col_edge_map.col_for_edge(edge_index) + col_offset;
 
             self.builder.add_obj_coefficient(col.to_usize().unwrap(), obj_coef);
         }
         Ok(())
     }
 
+    /// Update the objective coefficients of the semi-continuous binary columns.
+    ///
+    /// The start-up cost is applied to the binary column if the node had no flow in the previous
+    /// time-step. This must be called after the dynamic objective coefficients have been zeroed.
+    /// Start-up costs are not applied in the later periods of a look-ahead horizon.
+    fn update_semi_continuous_objectives(&mut self, network: &Network, state: &State) -> Result<(), SolverSolveError> {
+        for sc in self.semi_continuous_cols.iter().filter(|sc| sc.period == 0) {
+            let node = network
+                .get_node(&sc.node_idx)
+                .ok_or(SolverSolveError::NodeIndexNotFound(sc.node_idx))?;
+
+            let Some(semi_continuous) = node.semi_continuous_flow() else {
+                continue;
+            };
+
+            // The network state still contains the flows from the previous time-step.
+            let was_on = state.get_network_state().get_node_in_flow(&sc.node_idx)? > 0.0;
+            if !was_on {
+                let cost = semi_continuous.get_start_up_cost(network, state).map_err(|source| {
+                    SolverSolveError::NodeError {
+                        name: node.name().to_string(),
+                        sub_name: node.sub_name().map(|s| s.to_string()),
+                        source: source.into(),
+                    }
+                })?;
+                self.builder
+                    .add_obj_coefficient(sc.bin_col_id.to_usize().unwrap(), cost);
+            }
+        }
+        Ok(())
+    }
+
+    /// Update the semi-continuous constraints that do not have a constant maximum flow or
+    /// minimum flow if on.
+    fn update_semi_continuous_constraints(
+        &mut self,
+        network: &Network,
+        timestep: &Timestep,
+        state: &State,
+        forecasts: &[Forecast],
+    ) -> Result<(), SolverSolveError> {
+        for sc in self.semi_continuous_cols.iter() {
+            if sc.upper_bound_row_id.is_none() && sc.lower_bound_row_id.is_none() {
+                continue;
+            }
+            let (_, state) = Self::period(sc.period, timestep, state, forecasts);
+
+            let node = network
+                .get_node(&sc.node_idx)
+                .ok_or(SolverSolveError::NodeIndexNotFound(sc.node_idx))?;
+
+            if let Some(row_id) = sc.upper_bound_row_id {
+                let max_flow = node
+                    .get_max_flow(network, state)
+                    .map_err(|source| SolverSolveError::NodeError {
+                        name: node.name().to_string(),
+                        sub_name: node.sub_name().map(|s| s.to_string()),
+                        source,
+                    })?;
+
+                // `binary_variable * max_flow - flow >= 0`
+                self.builder
+                    .coefficients_to_update
+                    .push((row_id, sc.bin_col_id, max_flow.min(1e6)));
+                self.builder
+                    .apply_row_bounds(row_id.to_usize().unwrap(), 0.0, self.builder.f64_max);
+            }
+
+            if let Some(row_id) = sc.lower_bound_row_id {
+                let min_flow_if_on = match node.semi_continuous_flow() {
+                    Some(semi_continuous) => semi_continuous.get_min_flow_if_on(network, state).map_err(|source| {
+                        SolverSolveError::NodeError {
+                            name: node.name().to_string(),
+                            sub_name: node.sub_name().map(|s| s.to_string()),
+                            source: source.into(),
+                        }
+                    })?,
+                    None => 0.0,
+                };
+
+                // `flow - binary_variable * min_flow_if_on >= 0`
+                self.builder
+                    .coefficients_to_update
+                    .push((row_id, sc.bin_col_id, -min_flow_if_on));
+                self.builder
+                    .apply_row_bounds(row_id.to_usize().unwrap(), 0.0, self.builder.f64_max);
+            }
+        }
+        Ok(())
+    }
+
     /// Update node constraints
+    ///
+    /// Storage rows in later periods of a look-ahead horizon constrain the cumulative change in
+    /// volume; their bounds use the current time-step's duration.
     fn update_node_constraint_bounds(
         &mut self,
         network: &Network,
         timestep: &Timestep,
         state: &State,
+        forecasts: &[Forecast],
     ) -> Result<(), SolverSolveError> {
         let dt = timestep.days();
 
         for row in self.node_constraints_row_ids.iter() {
+            let (_, state) = Self::period(row.period, timestep, state, forecasts);
             let node = network
                 .get_node(&row.node_idx)
                 .ok_or(SolverSolveError::NodeIndexNotFound(row.node_idx))?;
@@ -521,10 +750,14 @@ where
     fn update_aggregated_node_factor_constraints(
         &mut self,
         network: &Network,
+        timestep: &Timestep,
         state: &State,
+        forecasts: &[Forecast],
     ) -> Result<(), SolverSolveError> {
         // Update the aggregated node factor constraints which are *not* constant
         for agg_node_row in self.agg_node_factor_constraint_row_ids.iter() {
+            let (_, state) = Self::period(agg_node_row.period, timestep, state, forecasts);
+            let col_offset = I::from(agg_node_row.period * self.num_edge_cols).unwrap();
             let agg_node = network
                 .get_aggregated_node(&agg_node_row.agg_node_idx)
                 .ok_or(SolverSolveError::AggregatedNodeIndexNotFound(agg_node_row.agg_node_idx))?;
@@ -550,6 +783,7 @@ where
                                 node0,
                                 node_pair.node0_factor(),
                                 &self.col_edge_map,
+                                col_offset,
                             );
                         }
 
@@ -560,12 +794,17 @@ where
                                 node1,
                                 node_pair.node1_factor(),
                                 &self.col_edge_map,
+                                col_offset,
                             );
                         }
 
                         // Apply the bounds to the row
+                        let lb = match node_pair.rhs_constraint() {
+                            RhsConstraint::Equal => node_pair.rhs(),
+                            RhsConstraint::UpperBound => self.builder.f64_neg_max,
+                        };
                         self.builder
-                            .apply_row_bounds(row_idx.to_usize().unwrap(), node_pair.rhs(), node_pair.rhs());
+                            .apply_row_bounds(row_idx.to_usize().unwrap(), lb, node_pair.rhs());
                     }
                 }
             } else {
@@ -576,17 +815,22 @@ where
         Ok(())
     }
 
-    /// Update aggregated node constraints
+    /// Update aggregated node constraints for a period
     fn update_aggregated_node_constraint_bounds(
         &mut self,
         network: &Network,
         state: &State,
+        period: usize,
     ) -> Result<(), SolverSolveError> {
-        for (row_id, agg_node) in self
-            .agg_node_constraint_row_ids
+        for (row_id, agg_node) in self.agg_node_constraint_row_ids[period]
             .iter()
             .zip(network.aggregated_nodes().deref())
         {
+            // Rows with constant bounds are fixed, and unconstrained nodes have no row
+            let Some(row_id) = row_id else {
+                continue;
+            };
+
             let (lb, ub): (f64, f64) =
                 agg_node
                     .get_flow_bounds(network, state)
@@ -601,16 +845,19 @@ where
         Ok(())
     }
 
+    /// Update virtual storage constraints for a period
+    ///
+    /// The bounds are divided by `dt`, the duration of the current time-step, which is also used
+    /// for the cumulative rows of later periods.
     fn update_virtual_storage_node_constraint_bounds(
         &mut self,
         network: &Network,
         timestep: &Timestep,
         state: &State,
+        dt: f64,
+        period: usize,
     ) -> Result<(), SolverSolveError> {
-        let dt = timestep.days();
-
-        for (row_id, node) in self
-            .virtual_storage_constraint_row_ids
+        for (row_id, node) in self.virtual_storage_constraint_row_ids[period]
             .iter()
             .zip(network.virtual_storage_nodes().deref())
         {
@@ -632,8 +879,16 @@ where
 pub struct SolverBuilder<I> {
     builder: LpBuilder<I>,
     col_edge_map: ColumnEdgeMapBuilder<I>,
-    node_bin_col_map: HashMap<NodeIndex, Vec<I>>,
-    node_set_bin_col_map: HashMap<Vec<NodeIndex>, I>,
+    horizon: usize,
+    num_edge_cols: usize,
+    /// Binary columns associated with each node for each period.
+    node_bin_col_map: Vec<HashMap<NodeIndex, Vec<I>>>,
+    /// Binary columns associated with each set of nodes for each period.
+    node_set_bin_col_map: Vec<HashMap<Vec<NodeIndex>, I>>,
+    /// Binary columns of semi-continuous nodes with their period.
+    node_semi_continuous_cols: Vec<(usize, NodeIndex, I)>,
+    /// Whether the coefficients of the LP are fixed after it is built.
+    fixed_coefficients: bool,
 }
 
 impl<I> SolverBuilder<I>
@@ -644,13 +899,39 @@ where
         Self {
             builder: LpBuilder::new(f64_max, f64_neg_max),
             col_edge_map: ColumnEdgeMapBuilder::default(),
-            node_bin_col_map: HashMap::new(),
-            node_set_bin_col_map: HashMap::new(),
+            horizon: 1,
+            num_edge_cols: 0,
+            node_bin_col_map: Vec::new(),
+            node_set_bin_col_map: Vec::new(),
+            node_semi_continuous_cols: Vec::new(),
+            fixed_coefficients: false,
         }
     }
 
-    pub fn col_for_edge(&self, edge_index: &EdgeIndex) -> I {
-        self.col_edge_map.col_for_edge(edge_index)
+    /// Build an LP whose coefficients are not updated after it is built, for solvers that can not
+    /// modify them.
+    ///
+    /// The upper bound rows of semi-continuous nodes with a non-constant `max_flow` then use a
+    /// fixed coefficient of 1e6, and the flow is still limited by the node's own constraint. A
+    /// non-constant `min_flow_if_on` can not be represented, and is an error.
+    pub fn with_fixed_coefficients(mut self) -> Self {
+        self.fixed_coefficients = true;
+        self
+    }
+
+    /// Build a multi-period LP spanning `horizon` time-steps, including the current one.
+    ///
+    /// Each period has its own copy of the columns and constraints. Storage (and virtual storage)
+    /// constraints in each period bound the cumulative change in volume since the start of the
+    /// current time-step, which links the periods together.
+    pub fn with_horizon(mut self, horizon: usize) -> Self {
+        self.horizon = horizon.max(1);
+        self
+    }
+
+    /// The column of an edge in the given period.
+    fn col_for_edge(&self, edge_index: &EdgeIndex, period: usize) -> I {
+        self.col_edge_map.col_for_edge(edge_index) + I::from(period * self.num_edge_cols).unwrap()
     }
 
     pub fn create(
@@ -660,27 +941,52 @@ where
     ) -> Result<BuiltSolver<I>, SolverSetupError> {
         // Create the columns
         self.create_columns(network)?;
+        // Set the constant objective coefficients
+        let dynamic_cost_edges = self.create_objective_coefficients(network, values)?;
 
         // Create edge mass balance constraints
         self.create_mass_balance_constraints(network);
         // Create the nodal constraints
         let node_constraints_row_ids = self.create_node_constraints(network, values)?;
         // Create the aggregated node constraints
-        let agg_node_constraint_row_ids = self.create_aggregated_node_constraints(network);
+        let agg_node_constraint_row_ids = self.create_aggregated_node_constraints(network, values)?;
         // Create the aggregated node factor constraints
         let agg_node_factor_constraint_row_ids = self.create_aggregated_node_factor_constraints(network, values);
         // Create virtual storage constraints
         let virtual_storage_constraint_row_ids = self.create_virtual_storage_constraints(network);
         // Create mutual exclusivity constraints
         self.create_mutual_exclusivity_constraints(network);
+        // Create semi-continuous flow constraints
+        let semi_continuous_cols = self.create_semi_continuous_constraints(network, values)?;
+
+        let mut dynamic_obj_cols: Vec<I> = (0..self.horizon)
+            .flat_map(|period| dynamic_cost_edges.iter().map(move |edge_index| (edge_index, period)))
+            .map(|(edge_index, period)| self.col_for_edge(edge_index, period))
+            .collect();
+        // Start-up costs depend on the previous time-step's flow
+        for sc in semi_continuous_cols.iter().filter(|sc| sc.period == 0) {
+            let node = network
+                .get_node(&sc.node_idx)
+                .ok_or(SolverSetupError::NodeIndexNotFound(sc.node_idx))?;
+            if node.semi_continuous_flow().is_some_and(|s| s.start_up_cost.is_some()) {
+                dynamic_obj_cols.push(sc.bin_col_id);
+            }
+        }
+        dynamic_obj_cols.sort();
+        dynamic_obj_cols.dedup();
 
         Ok(BuiltSolver {
             builder: self.builder.build(),
             col_edge_map: self.col_edge_map.build(),
+            horizon: self.horizon,
+            num_edge_cols: self.num_edge_cols,
             node_constraints_row_ids,
             agg_node_factor_constraint_row_ids,
             agg_node_constraint_row_ids,
             virtual_storage_constraint_row_ids,
+            semi_continuous_cols,
+            dynamic_cost_edges,
+            dynamic_obj_cols,
         })
     }
 
@@ -723,8 +1029,9 @@ where
             }
         }
 
-        // Add columns set the columns as x >= 0.0 (i.e. no upper bounds)
-        for _ in 0..self.col_edge_map.ncols() {
+        // Add columns set the columns as x >= 0.0 (i.e. no upper bounds); one set per period
+        self.num_edge_cols = self.col_edge_map.ncols();
+        for _ in 0..self.num_edge_cols * self.horizon {
             self.builder.add_column(0.0, Bounds::Lower(0.0), ColType::Continuous);
         }
 
@@ -742,87 +1049,143 @@ where
         }
 
         // Add any binary columns associated with each set of nodes
-        for node_set in node_sets_in_a_mutual_exclusivity.into_iter() {
-            let col_id = self.builder.add_column(0.0, Bounds::Double(0.0, 1.0), ColType::Integer);
-            for node_idx in node_set.iter() {
-                self.node_bin_col_map.entry(*node_idx).or_default().push(col_id);
+        for _ in 0..self.horizon {
+            let mut node_bin_col_map: HashMap<NodeIndex, Vec<I>> = HashMap::new();
+            let mut node_set_bin_col_map = HashMap::new();
+
+            for node_set in node_sets_in_a_mutual_exclusivity.iter() {
+                let col_id = self.builder.add_column(0.0, Bounds::Double(0.0, 1.0), ColType::Integer);
+                for node_idx in node_set.iter() {
+                    node_bin_col_map.entry(*node_idx).or_default().push(col_id);
+                }
+
+                node_set_bin_col_map.insert(node_set.to_vec(), col_id);
             }
 
-            self.node_set_bin_col_map.insert(node_set.to_vec(), col_id);
+            self.node_bin_col_map.push(node_bin_col_map);
+            self.node_set_bin_col_map.push(node_set_bin_col_map);
+        }
+
+        // Add a binary column for each node with a semi-continuous flow
+        for period in 0..self.horizon {
+            for node in network.nodes().deref() {
+                if node.semi_continuous_flow().is_some() {
+                    let col_id = self.builder.add_column(0.0, Bounds::Double(0.0, 1.0), ColType::Integer);
+                    self.node_semi_continuous_cols.push((period, node.index(), col_id));
+                }
+            }
         }
 
         Ok(())
     }
 
+    /// Set the objective coefficients of the edge columns with a constant cost.
+    ///
+    /// A column's coefficient is constant if the costs of all of its edges are constant. Returns
+    /// the edges of the remaining columns; their coefficients are recomputed every time-step.
+    fn create_objective_coefficients(
+        &mut self,
+        network: &Network,
+        values: &ConstParameterValues,
+    ) -> Result<Vec<EdgeIndex>, SolverSetupError> {
+        let mut const_costs = Vec::with_capacity(network.edges().len());
+        let mut is_dynamic_col = vec![false; self.num_edge_cols];
+
+        for edge in network.edges().iter() {
+            let cost = edge.const_cost(network.nodes(), values)?;
+            if cost.is_none() {
+                is_dynamic_col[self.col_for_edge(&edge.index(), 0).to_usize().unwrap()] = true;
+            }
+            const_costs.push((edge.index(), cost));
+        }
+
+        let mut dynamic_cost_edges = Vec::new();
+        for (edge_index, cost) in const_costs {
+            match cost {
+                Some(cost) if !is_dynamic_col[self.col_for_edge(&edge_index, 0).to_usize().unwrap()] => {
+                    for period in 0..self.horizon {
+                        let col = self.col_for_edge(&edge_index, period);
+                        self.builder.add_obj_coefficient(col.to_usize().unwrap(), cost);
+                    }
+                }
+                _ => dynamic_cost_edges.push(edge_index),
+            }
+        }
+
+        Ok(dynamic_cost_edges)
+    }
+
     /// Create mass balance constraints for each edge
     fn create_mass_balance_constraints(&mut self, network: &Network) {
-        for node in network.nodes().deref() {
-            // Only link nodes create mass-balance constraints
+        for period in 0..self.horizon {
+            for node in network.nodes().deref() {
+                // Only link nodes create mass-balance constraints
 
-            if let NodeType::Link = node.node_type() {
-                let mut row: RowBuilder<I> = RowBuilder::default();
+                if let NodeType::Link = node.node_type() {
+                    let mut row: RowBuilder<I> = RowBuilder::default();
 
-                let incoming_edges = node.get_incoming_edges().unwrap();
-                let outgoing_edges = node.get_outgoing_edges().unwrap();
+                    let incoming_edges = node.get_incoming_edges().unwrap();
+                    let outgoing_edges = node.get_outgoing_edges().unwrap();
 
-                // TODO use Display for the error message
-                if incoming_edges.is_empty() {
-                    panic!("Node {:?} contains no incoming edges 💥", node.full_name())
-                }
-                if outgoing_edges.is_empty() {
-                    panic!("Node {:?} contains no outgoing edges 💥", node.full_name())
-                }
+                    // TODO use Display for the error message
+                    if incoming_edges.is_empty() {
+                        panic!("Node {:?} contains no incoming edges 💥", node.full_name())
+                    }
+                    if outgoing_edges.is_empty() {
+                        panic!("Node {:?} contains no outgoing edges 💥", node.full_name())
+                    }
 
-                for edge in incoming_edges {
-                    let column = self.col_for_edge(edge);
-                    row.add_element(column, 1.0);
-                }
-                for edge in outgoing_edges {
-                    let column = self.col_for_edge(edge);
-                    row.add_element(column, -1.0);
-                }
+                    for edge in incoming_edges {
+                        let column = self.col_for_edge(edge, period);
+                        row.add_element(column, 1.0);
+                    }
+                    for edge in outgoing_edges {
+                        let column = self.col_for_edge(edge, period);
+                        row.add_element(column, -1.0);
+                    }
 
-                if row.columns.is_empty() {
-                    panic!("Row contains no columns!")
-                } else if row.columns.len() == 1 {
-                    // Skip this row because the edges must be mapped to the same column
-                } else {
-                    row.set_upper(0.0);
-                    row.set_lower(0.0);
+                    if row.columns.is_empty() {
+                        panic!("Row contains no columns!")
+                    } else if row.columns.len() == 1 {
+                        // Skip this row because the edges must be mapped to the same column
+                    } else {
+                        row.set_upper(0.0);
+                        row.set_lower(0.0);
 
-                    self.builder.add_fixed_row(row);
+                        self.builder.add_fixed_row(row);
+                    }
                 }
             }
         }
     }
 
-    fn add_node(&self, node: &Node, factor: f64, row: &mut RowBuilder<I>) {
+    fn add_node(&self, node: &Node, factor: f64, period: usize, row: &mut RowBuilder<I>) {
         match node.node_type() {
             NodeType::Link => {
                 for edge in node.get_outgoing_edges().unwrap() {
-                    let column = self.col_for_edge(edge);
+                    let column = self.col_for_edge(edge, period);
                     row.add_element(column, factor);
                 }
             }
             NodeType::Input => {
                 for edge in node.get_outgoing_edges().unwrap() {
-                    let column = self.col_for_edge(edge);
+                    let column = self.col_for_edge(edge, period);
                     row.add_element(column, factor);
                 }
             }
             NodeType::Output => {
                 for edge in node.get_incoming_edges().unwrap() {
-                    let column = self.col_for_edge(edge);
+                    let column = self.col_for_edge(edge, period);
                     row.add_element(column, factor);
                 }
             }
             NodeType::Storage => {
                 for edge in node.get_incoming_edges().unwrap() {
-                    let column = self.col_for_edge(edge);
+                    let column = self.col_for_edge(edge, period);
                     row.add_element(column, factor);
                 }
                 for edge in node.get_outgoing_edges().unwrap() {
-                    let column = self.col_for_edge(edge);
+                    let column = self.col_for_edge(edge, period);
                     row.add_element(column, -factor);
                 }
             }
@@ -842,98 +1205,113 @@ where
     /// - `binary_variable * max_flow >= flow`
     /// - `binary_variable * min_flow <= flow`
     ///
+    /// With a look-ahead horizon the constraints are repeated for each period. A storage node's
+    /// constraint in a period includes its flows in all the preceding periods, and therefore
+    /// bounds the cumulative change in volume.
     fn create_node_constraints(
         &mut self,
         network: &Network,
         values: &ConstParameterValues,
     ) -> Result<Vec<NodeRowId<I>>, SolverSetupError> {
-        let mut row_ids = Vec::with_capacity(network.nodes().len());
-
-        for node in network.nodes().deref() {
-            // Get the node's flow bounds if they are constants
-            // Storage nodes cannot have constant bounds
-            let bounds = match node.get_const_bounds(values)? {
-                Some(NodeBounds::Flow(bounds)) => Some(bounds),
-                _ => None,
-            };
+        let mut row_ids = Vec::with_capacity(network.nodes().len() * self.horizon);
+
+        for period in 0..self.horizon {
+            for node in network.nodes().deref() {
+                // Get the node's flow bounds if they are constants
+                // Storage nodes cannot have constant bounds
+                let bounds = match node.get_const_bounds(values)? {
+                    Some(NodeBounds::Flow(bounds)) => Some(bounds),
+                    _ => None,
+                };
 
-            // If there are binary variables associated with this node, then we need to add a row
-            // that enforces each binary variable's constraints
-            if let Some(cols) = self.node_bin_col_map.get(&node.index()) {
-                for col in cols {
-                    // Create separate rows for upper and lower bound constraints.
-                    let mut row_ub: RowBuilder<I> = RowBuilder::default();
-                    let mut row_lb: RowBuilder<I> = RowBuilder::default();
-
-                    self.add_node(node, -1.0, &mut row_ub);
-                    self.add_node(node, 1.0, &mut row_lb);
-
-                    match bounds {
-                        Some(bounds) => {
-                            // If the bounds are constant then the binary variable is used to control the upper bound
-                            row_ub.add_element(*col, bounds.max_flow.min(1e6));
-                            row_ub.set_lower(0.0);
-                            row_ub.set_upper(self.builder.f64_max);
-                            self.builder.add_fixed_row(row_ub);
-
-                            if bounds.min_flow != 0.0 {
-                                row_lb.add_element(*col, -bounds.min_flow.max(1e-6));
-                                row_lb.set_lower(0.0);
-                                row_lb.set_upper(self.builder.f64_max);
-
-                                self.builder.add_fixed_row(row_lb);
+                // If there are binary variables associated with this node, then we need to add a row
+                // that enforces each binary variable's constraints
+                if let Some(cols) = self.node_bin_col_map[period].get(&node.index()) {
+                    for col in cols {
+                        // Create separate rows for upper and lower bound constraints.
+                        let mut row_ub: RowBuilder<I> = RowBuilder::default();
+                        let mut row_lb: RowBuilder<I> = RowBuilder::default();
+
+                        self.add_node(node, -1.0, period, &mut row_ub);
+                        self.add_node(node, 1.0, period, &mut row_lb);
+
+                        match bounds {
+                            Some(bounds) => {
+                                // If the bounds are constant then the binary variable is used to control the upper bound
+                                row_ub.add_element(*col, bounds.max_flow.min(1e6));
+                                row_ub.set_lower(0.0);
+                                row_ub.set_upper(self.builder.f64_max);
+                                self.builder.add_fixed_row(row_ub);
+
+                                if bounds.min_flow != 0.0 {
+                                    row_lb.add_element(*col, -bounds.min_flow.max(1e-6));
+                                    row_lb.set_lower(0.0);
+                                    row_lb.set_upper(self.builder.f64_max);
+
+                                    self.builder.add_fixed_row(row_lb);
+                                }
+                            }
+                            None => {
+                                // If the bounds are not constant then the binary variable coefficient is updated later
+                                // Use a placeholder of 1.0 and -1.0 for now
+                                row_ub.add_element(*col, 1.0);
+                                row_lb.add_element(*col, -1.0);
+
+                                let row_id = self.builder.add_variable_row(row_ub);
+                                let row_type = NodeRowType::BinaryUpperBound { bin_col_id: *col };
+
+                                row_ids.push(NodeRowId {
+                                    row_id,
+                                    node_idx: node.index(),
+                                    row_type,
+                                    period,
+                                });
+
+                                // We do not know the bounds yet, so we have to assume there is a possibility
+                                // of a non-zero lower bound.
+                                let row_id = self.builder.add_variable_row(row_lb);
+                                let row_type = NodeRowType::BinaryLowerBound { bin_col_id: *col };
+
+                                row_ids.push(NodeRowId {
+                                    row_id,
+                                    node_idx: node.index(),
+                                    row_type,
+                                    period,
+                                });
                             }
                         }
-                        None => {
-                            // If the bounds are not constant then the binary variable coefficient is updated later
-                            // Use a placeholder of 1.0 and -1.0 for now
-                            row_ub.add_element(*col, 1.0);
-                            row_lb.add_element(*col, -1.0);
-
-                            let row_id = self.builder.add_variable_row(row_ub);
-                            let row_type = NodeRowType::BinaryUpperBound { bin_col_id: *col };
-
-                            row_ids.push(NodeRowId {
-                                row_id,
-                                node_idx: node.index(),
-                                row_type,
-                            });
-
-                            // We do not know the bounds yet, so we have to assume there is a possibility
-                            // of a non-zero lower bound.
-                            let row_id = self.builder.add_variable_row(row_lb);
-                            let row_type = NodeRowType::BinaryLowerBound { bin_col_id: *col };
-
-                            row_ids.push(NodeRowId {
-                                row_id,
-                                node_idx: node.index(),
-                                row_type,
-                            });
+                    }
+                } else {
+                    let mut row: RowBuilder<I> = RowBuilder::default();
+                    if let NodeType::Storage = node.node_type() {
+                        // Storage continuity; the change in volume up to and including this period
+                        for p in 0..=period {
+                            self.add_node(node, 1.0, p, &mut row);
                         }
+                    } else {
+                        self.add_node(node, 1.0, period, &mut row);
                     }
-                }
-            } else {
-                let mut row: RowBuilder<I> = RowBuilder::default();
-                self.add_node(node, 1.0, &mut row);
-                let mut is_fixed = false;
-
-                // Apply the bounds if they are constant; otherwise the bounds are updated later
-                if let Some(bounds) = bounds {
-                    row.set_lower(bounds.min_flow);
-                    row.set_upper(bounds.max_flow);
-                    is_fixed = true;
-                }
+                    let mut is_fixed = false;
 
-                if is_fixed {
-                    self.builder.add_fixed_row(row);
-                } else {
-                    let row_id = self.builder.add_variable_row(row);
+                    // Apply the bounds if they are constant; otherwise the bounds are updated later
+                    if let Some(bounds) = bounds {
+                        row.set_lower(bounds.min_flow);
+                        row.set_upper(bounds.max_flow);
+                        is_fixed = true;
+                    }
 
-                    row_ids.push(NodeRowId {
-                        row_id,
-                        node_idx: node.index(),
-                        row_type: NodeRowType::Continuous,
-                    });
+                    if is_fixed {
+                        self.builder.add_fixed_row(row);
+                    } else {
+                        let row_id = self.builder.add_variable_row(row);
+
+                        row_ids.push(NodeRowId {
+                            row_id,
+                            node_idx: node.index(),
+                            row_type: NodeRowType::Continuous,
+                            period,
+                        });
+                    }
                 }
             }
         }
@@ -942,7 +1320,7 @@ where
 
     /// Create aggregated node factor constraints
     ///
-    /// One constraint is created per node to enforce any factor constraints.
+    /// One constraint is created per node (and period) to enforce any factor constraints.
     fn create_aggregated_node_factor_constraints(
         &mut self,
         network: &Network,
@@ -950,54 +1328,60 @@ where
     ) -> Vec<AggNodeFactorRow<I>> {
         let mut row_ids = Vec::new();
 
-        for agg_node in network.aggregated_nodes().deref() {
-            // Only create row for nodes that have factors
-            if let Some(Ok(node_pairs)) = agg_node.get_const_norm_factor_pairs(values) {
-                let mut row_indices_for_agg_node = Vec::with_capacity(node_pairs.len());
+        for period in 0..self.horizon {
+            for agg_node in network.aggregated_nodes().deref() {
+                // Only create row for nodes that have factors
+                if let Some(Ok(node_pairs)) = agg_node.get_const_norm_factor_pairs(values) {
+                    let mut row_indices_for_agg_node = Vec::with_capacity(node_pairs.len());
 
-                for node_pair in node_pairs {
-                    // Create rows for each node in the aggregated node pair with the first one.
-
-                    let mut row = RowBuilder::default();
+                    for node_pair in node_pairs {
+                        // Create rows for each node in the aggregated node pair with the first one.
 
-                    // TODO error handling?
-                    let nodes = network.nodes();
+                        let mut row = RowBuilder::default();
 
-                    let f0 = node_pair.node0_factor();
+                        // TODO error handling?
+                        let nodes = network.nodes();
 
-                    for node0_idx in node_pair.node0_indices() {
-                        let node0 = nodes.get(node0_idx).expect("Node index not found!");
-                        self.add_node(node0, f0.unwrap_or(1.0), &mut row);
-                    }
+                        let f0 = node_pair.node0_factor();
 
-                    let f1 = node_pair.node1_factor();
+                        for node0_idx in node_pair.node0_indices() {
+                            let node0 = nodes.get(node0_idx).expect("Node index not found!");
+                            self.add_node(node0, f0.unwrap_or(1.0), period, &mut row);
+                        }
 
-                    for node1_idx in node_pair.node1_indices() {
-                        let node1 = nodes.get(node1_idx).expect("Node index not found!");
-                        self.add_node(node1, f1.unwrap_or(1.0), &mut row);
-                    }
+                        let f1 = node_pair.node1_factor();
 
-                    // Make the row fixed at RHS
-                    let rhs = node_pair.rhs();
+                        for node1_idx in node_pair.node1_indices() {
+                            let node1 = nodes.get(node1_idx).expect("Node index not found!");
+                            self.add_node(node1, f1.unwrap_or(1.0), period, &mut row);
+                        }
 
-                    row.set_lower(rhs);
-                    row.set_upper(rhs);
+                        // Make the row fixed at, or limited to, the RHS
+                        let rhs = node_pair.rhs();
 
-                    // Row is fixed if we can compute the ratio now
-                    if f0.is_some() && f1.is_some() {
-                        self.builder.add_fixed_row(row);
-                        row_indices_for_agg_node.push(None)
-                    } else {
-                        // These rows will be updated with the correct ratio later
-                        let row_idx = self.builder.add_variable_row(row);
-                        row_indices_for_agg_node.push(Some(row_idx));
+                        match node_pair.rhs_constraint() {
+                            RhsConstraint::Equal => row.set_lower(rhs),
+                            RhsConstraint::UpperBound => row.set_lower(self.builder.f64_neg_max),
+                        }
+                        row.set_upper(rhs);
+
+                        // Row is fixed if we can compute the ratio now
+                        if f0.is_some() && f1.is_some() {
+                            self.builder.add_fixed_row(row);
+                            row_indices_for_agg_node.push(None)
+                        } else {
+                            // These rows will be updated with the correct ratio later
+                            let row_idx = self.builder.add_variable_row(row);
+                            row_indices_for_agg_node.push(Some(row_idx));
+                        }
                     }
-                }
 
-                row_ids.push(AggNodeFactorRow {
-                    agg_node_idx: agg_node.index(),
-                    row_indices: row_indices_for_agg_node,
-                })
+                    row_ids.push(AggNodeFactorRow {
+                        agg_node_idx: agg_node.index(),
+                        period,
+                        row_indices: row_indices_for_agg_node,
+                    })
+                }
             }
         }
 
@@ -1007,71 +1391,210 @@ where
     /// Create aggregated node constraints
     ///
     /// One constraint is created per node to enforce any constraints (flow or storage)
-    /// that it may define. Returns the row ids associated with each aggregated node constraint.
-    /// Panics if the model contains aggregated nodes with broken references to nodes.
-    fn create_aggregated_node_constraints(&mut self, network: &Network) -> Vec<usize> {
-        let mut row_ids = Vec::with_capacity(network.aggregated_nodes().len());
+    /// that it may define. Returns the row ids associated with each aggregated node constraint
+    /// for each period; rows with constant bounds are fixed and have no row id. No row is created
+    /// for aggregated nodes without flow constraints. Panics if the model contains aggregated nodes
+    /// with broken references to nodes.
+    fn create_aggregated_node_constraints(
+        &mut self,
+        network: &Network,
+        values: &ConstParameterValues,
+    ) -> Result<Vec<Vec<Option<usize>>>, SolverSetupError> {
+        let mut row_ids = Vec::with_capacity(self.horizon);
+
+        for period in 0..self.horizon {
+            let mut period_row_ids = Vec::with_capacity(network.aggregated_nodes().len());
+
+            for agg_node in network.aggregated_nodes().deref() {
+                // The default lower bound of zero is not meaningful for the sum of the flows of
+                // nodes that include a storage node; only constrain the flow if requested.
+                if agg_node.is_flow_unconstrained() {
+                    period_row_ids.push(None);
+                    continue;
+                }
 
-        for agg_node in network.aggregated_nodes().deref() {
-            // Create empty arrays to store the matrix data
-            let mut row: RowBuilder<I> = RowBuilder::default();
-
-            for node_indices in agg_node.iter_nodes() {
-                // TODO error handling?
-                for node_idx in node_indices {
-                    let node = network.nodes().get(node_idx).expect("Node index not found!");
-                    self.add_node(node, 1.0, &mut row);
+                // Create empty arrays to store the matrix data
+                let mut row: RowBuilder<I> = RowBuilder::default();
+
+                for node_indices in agg_node.iter_nodes() {
+                    // TODO error handling?
+                    for node_idx in node_indices {
+                        let node = network.nodes().get(node_idx).expect("Node index not found!");
+                        self.add_node(node, 1.0, period, &mut row);
+                    }
+                }
+
+                let bounds = agg_node.get_const_flow_bounds(values).map_err(|source| {
+                    SolverSetupError::AggregatedNodeBoundsError {
+                        name: agg_node.name().to_string(),
+                        sub_name: agg_node.sub_name().map(|s| s.to_string()),
+                        source,
+                    }
+                })?;
+
+                match bounds {
+                    Some((lb, ub)) => {
+                        row.set_lower(lb);
+                        row.set_upper(ub);
+                        self.builder.add_fixed_row(row);
+                        period_row_ids.push(None);
+                    }
+                    None => {
+                        let row_id = self.builder.add_variable_row(row);
+                        period_row_ids.push(Some(row_id.to_usize().unwrap()))
+                    }
                 }
             }
 
-            let row_id = self.builder.add_variable_row(row);
-            row_ids.push(row_id.to_usize().unwrap())
+            row_ids.push(period_row_ids);
         }
-        row_ids
+        Ok(row_ids)
     }
 
     /// Create virtual storage node constraints
     ///
-    fn create_virtual_storage_constraints(&mut self, network: &Network) -> Vec<usize> {
-        let mut row_ids = Vec::with_capacity(network.virtual_storage_nodes().len());
-
-        for virtual_storage in network.virtual_storage_nodes().deref() {
-            // Create empty arrays to store the matrix data
-
-            let mut row: RowBuilder<I> = RowBuilder::default();
-            for (node_index, factor) in virtual_storage.iter_nodes_with_factors() {
-                if !factor.is_finite() {
-                    panic!(
-                        "Virtual storage node {:?} contains a non-finite factor.",
-                        virtual_storage.full_name()
-                    );
+    /// As with storage nodes, the constraint in each period includes the flows of all the
+    /// preceding periods.
+    fn create_virtual_storage_constraints(&mut self, network: &Network) -> Vec<Vec<usize>> {
+        let mut row_ids = Vec::with_capacity(self.horizon);
+
+        for period in 0..self.horizon {
+            let mut period_row_ids = Vec::with_capacity(network.virtual_storage_nodes().len());
+
+            for virtual_storage in network.virtual_storage_nodes().deref() {
+                // Create empty arrays to store the matrix data
+
+                let mut row: RowBuilder<I> = RowBuilder::default();
+                for (node_index, factor) in virtual_storage.iter_nodes_with_factors() {
+                    if !factor.is_finite() {
+                        panic!(
+                            "Virtual storage node {:?} contains a non-finite factor.",
+                            virtual_storage.full_name()
+                        );
+                    }
+                    let node = network.nodes().get(node_index).expect("Node index not found!");
+                    for p in 0..=period {
+                        self.add_node(node, -factor, p, &mut row);
+                    }
                 }
-                let node = network.nodes().get(node_index).expect("Node index not found!");
-                self.add_node(node, -factor, &mut row);
+                let row_id = self.builder.add_variable_row(row);
+                period_row_ids.push(row_id.to_usize().unwrap());
             }
-            let row_id = self.builder.add_variable_row(row);
-            row_ids.push(row_id.to_usize().unwrap());
+
+            row_ids.push(period_row_ids);
         }
         row_ids
     }
 
+    /// Create semi-continuous flow constraints
+    ///
+    /// Two rows are created for each node with a semi-continuous flow. These enforce the
+    /// following inequalities:
+    /// - `binary_variable * max_flow >= flow`
+    /// - `binary_variable * min_flow_if_on <= flow`
+    ///
+    /// The `max_flow` in the first row is limited to 1e6. Each row is updated each time-step if
+    /// the node's `max_flow` or `min_flow_if_on`, respectively, is not constant. With fixed
+    /// coefficients a non-constant `max_flow` uses 1e6 instead, and a non-constant
+    /// `min_flow_if_on` is an error.
+    fn create_semi_continuous_constraints(
+        &mut self,
+        network: &Network,
+        values: &ConstParameterValues,
+    ) -> Result<Vec<SemiContinuousCols<I>>, SolverSetupError> {
+        let mut cols = Vec::with_capacity(self.node_semi_continuous_cols.len());
+
+        for (period, node_idx, bin_col_id) in self.node_semi_continuous_cols.iter() {
+            let node = network
+                .get_node(node_idx)
+                .ok_or(SolverSetupError::NodeIndexNotFound(*node_idx))?;
+            let semi_continuous = node
+                .semi_continuous_flow()
+                .expect("Semi-continuous flow not found for node with a semi-continuous binary column!");
+
+            let mut row_ub: RowBuilder<I> = RowBuilder::default();
+            self.add_node(node, -1.0, *period, &mut row_ub);
+            row_ub.set_lower(0.0);
+            row_ub.set_upper(self.builder.f64_max);
+
+            let upper_bound_row_id = match node.get_const_max_flow(values)? {
+                Some(max_flow) => {
+                    row_ub.add_element(*bin_col_id, max_flow.min(1e6));
+                    self.builder.add_fixed_row(row_ub);
+                    None
+                }
+                None if self.fixed_coefficients => {
+                    row_ub.add_element(*bin_col_id, 1e6);
+                    self.builder.add_fixed_row(row_ub);
+                    None
+                }
+                None => {
+                    // Use a placeholder of 1.0; the coefficient is updated later
+                    row_ub.add_element(*bin_col_id, 1.0);
+                    Some(self.builder.add_variable_row(row_ub))
+                }
+            };
+
+            let mut row_lb: RowBuilder<I> = RowBuilder::default();
+            self.add_node(node, 1.0, *period, &mut row_lb);
+            row_lb.set_lower(0.0);
+            row_lb.set_upper(self.builder.f64_max);
+
+            let min_flow_if_on = semi_continuous
+                .get_const_min_flow_if_on(values)
+                .map_err(crate::node::NodeError::from)?;
+
+            let lower_bound_row_id = match min_flow_if_on {
+                Some(min_flow_if_on) => {
+                    if min_flow_if_on != 0.0 {
+                        row_lb.add_element(*bin_col_id, -min_flow_if_on);
+                        self.builder.add_fixed_row(row_lb);
+                    }
+                    None
+                }
+                None if self.fixed_coefficients => {
+                    return Err(SolverSetupError::NonConstantMinFlowIfOn {
+                        name: node.name().to_string(),
+                        sub_name: node.sub_name().map(|s| s.to_string()),
+                    });
+                }
+                None => {
+                    // Use a placeholder of -1.0; the coefficient is updated later
+                    row_lb.add_element(*bin_col_id, -1.0);
+                    Some(self.builder.add_variable_row(row_lb))
+                }
+            };
+
+            cols.push(SemiContinuousCols {
+                node_idx: *node_idx,
+                period: *period,
+                bin_col_id: *bin_col_id,
+                upper_bound_row_id,
+                lower_bound_row_id,
+            });
+        }
+
+        Ok(cols)
+    }
+
     /// Create mutual exclusivity constraints
     fn create_mutual_exclusivity_constraints(&mut self, network: &Network) {
-        for agg_node in network.aggregated_nodes().iter() {
-            if let Some(exclusivity) = agg_node.get_exclusivity() {
-                let mut row = RowBuilder::default();
-                for node_index in agg_node.iter_nodes() {
-                    let bin_col = self
-                        .node_set_bin_col_map
-                        .get(node_index)
-                        .expect("Binary column not found for Node in mutual exclusivity constraint!");
-
-                    row.add_element(*bin_col, 1.0);
-                }
-                row.set_upper(exclusivity.max_active() as f64);
-                row.set_lower(exclusivity.min_active() as f64);
+        for node_set_bin_col_map in self.node_set_bin_col_map.iter() {
+            for agg_node in network.aggregated_nodes().iter() {
+                if let Some(exclusivity) = agg_node.get_exclusivity() {
+                    let mut row = RowBuilder::default();
+                    for node_index in agg_node.iter_nodes() {
+                        let bin_col = node_set_bin_col_map
+                            .get(node_index)
+                            .expect("Binary column not found for Node in mutual exclusivity constraint!");
 
-                self.builder.add_fixed_row(row);
+                        row.add_element(*bin_col, 1.0);
+                    }
+                    row.set_upper(exclusivity.max_active() as f64);
+                    row.set_lower(exclusivity.min_active() as f64);
+
+                    self.builder.add_fixed_row(row);
+                }
             }
         }
     }
@@ -1080,6 +1603,8 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parameters::Array1Parameter;
+    use ndarray::Array1;
 
     #[test]
     fn model_builder_new() {
@@ -1134,4 +1659,39 @@ mod tests {
         assert_eq!(lp.columns, vec![0, 1, 2, 0, 1, 2]);
         assert_eq!(lp.elements, vec![3.0, 2.0, 1.0, 2.0, 5.0, 3.0]);
     }
+
+    #[test]
+    fn builder_dynamic_obj_cols() {
+        let mut network = Network::default();
+        let input_node = network.add_input_node("input", None).unwrap();
+        let link_node = network.add_link_node("link", None).unwrap();
+        let output_node = network.add_output_node("output", None).unwrap();
+        network.connect_nodes(input_node, link_node).unwrap();
+        network.connect_nodes(link_node, output_node).unwrap();
+
+        let input = network.get_mut_node_by_name("input", None).unwrap();
+        input.set_cost(Some(1.0.into()));
+        let output = network.get_mut_node_by_name("output", None).unwrap();
+        output.set_cost(Some((-10.0).into()));
+
+        // All the costs are constant; the two edges share a single column.
+        let values = ConstParameterValues::default();
+        let built: BuiltSolver<i32> = SolverBuilder::new(f64::MAX, f64::MIN)
+            .create(&network, &values)
+            .unwrap();
+        assert!(built.dynamic_obj_cols().is_empty());
+        assert_eq!(built.col_obj_coef(), &[-9.0]);
+
+        // A link cost from a parameter makes the column's objective coefficient dynamic.
+        let cost = Array1Parameter::new("link-cost".into(), Array1::from_elem(10, 2.0), None);
+        let cost = network.add_simple_parameter(Box::new(cost)).unwrap();
+        let link = network.get_mut_node_by_name("link", None).unwrap();
+        link.set_cost(Some(cost.into_metric_f64_before()));
+
+        let built: BuiltSolver<i32> = SolverBuilder::new(f64::MAX, f64::MIN)
+            .create(&network, &values)
+            .unwrap();
+        assert_eq!(built.dynamic_obj_cols(), &[0]);
+        assert_eq!(built.col_obj_coef(), &[0.0]);
+    }
 }
@@ -3,7 +3,10 @@ mod settings;
 use super::builder::{ColType, SolverBuilder};
 use crate::network::Network;
 use crate::solvers::builder::BuiltSolver;
-use crate::solvers::{Solver, SolverFeatures, SolverSetupError, SolverSolveError, SolverTimings};
+use crate::solvers::{
+    Forecast, SimplexStrategy, Solver, SolverFeatures, SolverOptionValue, SolverOptions, SolverSetupError,
+    SolverSolveError, SolverTimings,
+};
 use crate::state::{ConstParameterValues, State};
 use crate::timestep::Timestep;
 use coin_or_sys::cbc::*;
@@ -76,11 +79,10 @@ impl Cbc {
         }
     }
 
-    pub fn change_objective_coefficients(&mut self, obj_coefficients: &[c_double]) {
-        for (i, val) in obj_coefficients.iter().enumerate() {
-            unsafe {
-                Cbc_setObjCoeff(self.ptr, i as c_int, *val);
-            }
+    /// Change the objective coefficient of a single column.
+    pub fn change_objective_coefficient(&mut self, col: c_int, obj_coefficient: c_double) {
+        unsafe {
+            Cbc_setObjCoeff(self.ptr, col, obj_coefficient);
         }
     }
 
@@ -155,6 +157,68 @@ impl Cbc {
         }
     }
 
+    /// Set a parameter as if it was given as `-name value` on the Cbc command line.
+    fn set_parameter(&mut self, name: &str, value: &str) -> Result<(), SolverSetupError> {
+        let invalid = || SolverSetupError::InvalidSolverOptionValue {
+            name: name.to_string(),
+            value: value.to_string(),
+        };
+        let c_name = CString::new(name).map_err(|_| invalid())?;
+        let c_value = CString::new(value).map_err(|_| invalid())?;
+        unsafe {
+            Cbc_setParameter(self.ptr, c_name.as_ptr(), c_value.as_ptr());
+        }
+        Ok(())
+    }
+
+    fn set_maximum_seconds(&mut self, value: c_double) {
+        unsafe {
+            Cbc_setMaximumSeconds(self.ptr, value);
+        }
+    }
+
+    /// Apply the tuning options to the model.
+    ///
+    /// Named solver options are passed to Cbc as command line parameters. Boolean values are
+    /// given as `on` or `off`.
+    fn apply_options(&mut self, options: &SolverOptions) -> Result<(), SolverSetupError> {
+        if let Some(tolerance) = options.primal_tolerance {
+            self.set_parameter("primalTolerance", &tolerance.to_string())?;
+        }
+        if let Some(tolerance) = options.dual_tolerance {
+            self.set_parameter("dualTolerance", &tolerance.to_string())?;
+        }
+        if let Some(presolve) = options.presolve {
+            self.set_parameter("presolve", if presolve { "on" } else { "off" })?;
+        }
+        match options.simplex_strategy {
+            None | Some(SimplexStrategy::Auto) => {}
+            // Cbc only exposes the choice of simplex algorithm as command line actions
+            Some(_) => {
+                return Err(SolverSetupError::UnsupportedSolverOption {
+                    name: "simplex_strategy".to_string(),
+                });
+            }
+        }
+        if let Some(seconds) = options.time_limit {
+            self.set_maximum_seconds(seconds);
+        }
+        if let Some(iterations) = options.iteration_limit {
+            self.set_parameter("maxIterations", &iterations.to_string())?;
+        }
+
+        for (name, value) in options.solver_options.iter() {
+            let value = match value {
+                SolverOptionValue::Bool(true) => "on".to_string(),
+                SolverOptionValue::Bool(false) => "off".to_string(),
+                _ => value.to_string(),
+            };
+            self.set_parameter(name, &value)?;
+        }
+
+        Ok(())
+    }
+
     fn solve(&mut self) {
         unsafe {
             let ret = Cbc_solve(self.ptr);
@@ -164,6 +228,11 @@ impl Cbc {
         }
     }
 
+    /// The number of simplex iterations of the last solve.
+    fn iteration_count(&self) -> usize {
+        unsafe { Cbc_getIterationCount(self.ptr) as usize }
+    }
+
     fn primal_column_solution(&mut self, number: usize) -> Vec<c_double> {
         let solution: Vec<c_double>;
         unsafe {
@@ -205,8 +274,9 @@ pub struct CbcSolver {
 }
 
 impl CbcSolver {
-    fn from_builder(builder: BuiltSolver<c_int>) -> Self {
+    fn from_builder(builder: BuiltSolver<c_int>, options: &SolverOptions) -> Result<Self, SolverSetupError> {
         let mut cbc = Cbc::default();
+        cbc.apply_options(options)?;
 
         cbc.add_cols(
             builder.col_lower(),
@@ -223,7 +293,7 @@ impl CbcSolver {
             builder.elements(),
         );
 
-        CbcSolver { builder, cbc }
+        Ok(CbcSolver { builder, cbc })
     }
 
     fn solve(&mut self) -> Vec<c_double> {
@@ -248,18 +318,22 @@ impl Solver for CbcSolver {
             SolverFeatures::VirtualStorage,
             SolverFeatures::AggregatedNodeFactors,
             SolverFeatures::MutualExclusivity,
+            SolverFeatures::SemiContinuous,
         ]
     }
 
     fn setup(
         model: &Network,
         values: &ConstParameterValues,
-        _settings: &Self::Settings,
+        settings: &Self::Settings,
     ) -> Result<Box<Self>, SolverSetupError> {
-        let builder = SolverBuilder::new(f64::MAX, -f64::MAX);
+        // CBC can not modify the coefficients of the LP after it is built
+        let builder = SolverBuilder::new(f64::MAX, -f64::MAX)
+            .with_horizon(settings.horizon())
+            .with_fixed_coefficients();
         let built = builder.create(model, values)?;
 
-        let solver = CbcSolver::from_builder(built);
+        let solver = CbcSolver::from_builder(built, settings.options())?;
         Ok(Box::new(solver))
     }
 
@@ -268,17 +342,38 @@ impl Solver for CbcSolver {
         model: &Network,
         timestep: &Timestep,
         state: &mut State,
+    ) -> Result<SolverTimings, SolverSolveError> {
+        self.solve_with_forecasts(model, timestep, state, &[])
+    }
+
+    fn horizon(&self) -> usize {
+        self.builder.horizon()
+    }
+
+    fn solve_with_forecasts(
+        &mut self,
+        model: &Network,
+        timestep: &Timestep,
+        state: &mut State,
+        forecasts: &[Forecast],
     ) -> Result<SolverTimings, SolverSolveError> {
         let mut timings = SolverTimings::default();
-        self.builder.update(model, timestep, state, &mut timings)?;
+        self.builder.update(model, timestep, state, forecasts, &mut timings)?;
 
+        // Only the dynamic objective coefficients and variable rows (which are first) are updated
         let now = Instant::now();
-        self.cbc.change_objective_coefficients(self.builder.col_obj_coef());
+        let col_obj_coef = self.builder.col_obj_coef();
+        for col in self.builder.dynamic_obj_cols() {
+            self.cbc.change_objective_coefficient(*col, col_obj_coef[*col as usize]);
+        }
         timings.update_objective += now.elapsed();
 
         let now = Instant::now();
-        self.cbc.change_row_lower(self.builder.row_lower());
-        self.cbc.change_row_upper(self.builder.row_upper());
+        let num_variable_rows = self.builder.num_variable_rows();
+        self.cbc
+            .change_row_lower(&self.builder.row_lower()[..num_variable_rows]);
+        self.cbc
+            .change_row_upper(&self.builder.row_upper()[..num_variable_rows]);
 
         if !self.builder.coefficients_to_update().is_empty() {
             return Err(SolverSolveError::MissingSolverFeatures);
@@ -291,6 +386,7 @@ impl Solver for CbcSolver {
         let now = Instant::now();
         let solution = self.solve();
         timings.solve = now.elapsed();
+        timings.iterations = self.cbc.iteration_count();
 
         // Create the updated network state from the results
         let network_state = state.get_mut_network_state();
@@ -3,7 +3,9 @@ mod settings;
 use super::builder::SolverBuilder;
 use crate::network::Network;
 use crate::solvers::builder::BuiltSolver;
-use crate::solvers::{Solver, SolverFeatures, SolverSetupError, SolverSolveError, SolverTimings};
+use crate::solvers::{
+    Forecast, SimplexStrategy, Solver, SolverFeatures, SolverOptions, SolverSetupError, SolverSolveError, SolverTimings,
+};
 use crate::state::{ConstParameterValues, State};
 use crate::timestep::Timestep;
 use coin_or_sys::clp::*;
@@ -244,6 +246,7 @@ impl ClpSimplex {
         }
     }
 
+    #[allow(dead_code)]
     fn initial_dual_solve(&mut self) {
         unsafe {
             Clp_initialDualSolve(self.ptr);
@@ -257,6 +260,109 @@ impl ClpSimplex {
         }
     }
 
+    /// Initial solve with the given presolve and simplex algorithm choices.
+    fn initial_solve_with_options(&mut self, presolve: bool, strategy: SimplexStrategy) {
+        unsafe {
+            let options = ClpSolve_new();
+            // Presolve types: 0 - on, 1 - off
+            ClpSolve_setPresolveType(options, if presolve { 0 } else { 1 }, -1);
+            // Solve types: 0 - dual, 1 - primal, 5 - automatic
+            let method = match strategy {
+                SimplexStrategy::Dual => 0,
+                SimplexStrategy::Primal => 1,
+                SimplexStrategy::Auto => 5,
+            };
+            ClpSolve_setSolveType(options, method, -1);
+            Clp_initialSolveWithOptions(self.ptr, options);
+            ClpSolve_delete(options);
+        }
+    }
+
+    fn set_primal_tolerance(&mut self, value: c_double) {
+        unsafe {
+            Clp_setPrimalTolerance(self.ptr, value);
+        }
+    }
+
+    fn set_dual_tolerance(&mut self, value: c_double) {
+        unsafe {
+            Clp_setDualTolerance(self.ptr, value);
+        }
+    }
+
+    fn set_maximum_iterations(&mut self, value: c_int) {
+        unsafe {
+            Clp_setMaximumIterations(self.ptr, value);
+        }
+    }
+
+    fn set_maximum_seconds(&mut self, value: c_double) {
+        unsafe {
+            Clp_setMaximumSeconds(self.ptr, value);
+        }
+    }
+
+    fn set_log_level(&mut self, value: c_int) {
+        unsafe {
+            Clp_setLogLevel(self.ptr, value);
+        }
+    }
+
+    fn set_scaling(&mut self, value: c_int) {
+        unsafe {
+            Clp_scaling(self.ptr, value);
+        }
+    }
+
+    fn set_perturbation(&mut self, value: c_int) {
+        unsafe {
+            Clp_setPerturbation(self.ptr, value);
+        }
+    }
+
+    fn set_dual_bound(&mut self, value: c_double) {
+        unsafe {
+            Clp_setDualBound(self.ptr, value);
+        }
+    }
+
+    /// Apply the tuning options to the model.
+    ///
+    /// Named solver options are mapped to the corresponding Clp setter; the supported names
+    /// are `log_level`, `scaling`, `perturbation` and `dual_bound`.
+    fn apply_options(&mut self, options: &SolverOptions) -> Result<(), SolverSetupError> {
+        if let Some(tolerance) = options.primal_tolerance {
+            self.set_primal_tolerance(tolerance);
+        }
+        if let Some(tolerance) = options.dual_tolerance {
+            self.set_dual_tolerance(tolerance);
+        }
+        if let Some(iterations) = options.iteration_limit {
+            self.set_maximum_iterations(iterations.try_into().unwrap_or(c_int::MAX));
+        }
+        if let Some(seconds) = options.time_limit {
+            self.set_maximum_seconds(seconds);
+        }
+
+        for (name, value) in options.solver_options.iter() {
+            let invalid = || SolverSetupError::InvalidSolverOptionValue {
+                name: name.clone(),
+                value: value.to_string(),
+            };
+            let int_value = || value.as_i64().and_then(|v| c_int::try_from(v).ok()).ok_or_else(invalid);
+
+            match name.as_str() {
+                "log_level" => self.set_log_level(int_value()?),
+                "scaling" => self.set_scaling(int_value()?),
+                "perturbation" => self.set_perturbation(int_value()?),
+                "dual_bound" => self.set_dual_bound(value.as_f64().ok_or_else(invalid)?),
+                _ => return Err(SolverSetupError::UnsupportedSolverOption { name: name.clone() }),
+            }
+        }
+
+        Ok(())
+    }
+
     fn dual_solve(&mut self) -> Result<(), ClpSolveStatusError> {
         unsafe {
             let _ret = Clp_dual(self.ptr, 0);
@@ -266,7 +372,6 @@ impl ClpSimplex {
         }
     }
 
-    #[allow(dead_code)]
     fn primal_solve(&mut self) -> Result<(), ClpSolveStatusError> {
         unsafe {
             let _ret = Clp_primal(self.ptr, 0);
@@ -310,6 +415,11 @@ impl ClpSimplex {
         unsafe { Clp_objectiveValue(self.ptr) }
     }
 
+    /// The number of iterations of the last solve.
+    fn number_iterations(&self) -> usize {
+        unsafe { Clp_numberIterations(self.ptr) as usize }
+    }
+
     #[allow(dead_code)]
     fn write_mps(&mut self, filename: &str) {
         let c_filename = CString::new(filename).expect("CString::new failed");
@@ -322,11 +432,13 @@ impl ClpSimplex {
 pub struct ClpSolver {
     builder: BuiltSolver<c_int>,
     clp_simplex: ClpSimplex,
+    simplex_strategy: SimplexStrategy,
 }
 
 impl ClpSolver {
-    fn from_builder(builder: BuiltSolver<c_int>) -> Self {
+    fn from_builder(builder: BuiltSolver<c_int>, options: &SolverOptions) -> Result<Self, SolverSetupError> {
         let mut clp_simplex = ClpSimplex::default();
+        clp_simplex.apply_options(options)?;
 
         let num_cols = builder.num_cols();
 
@@ -344,13 +456,23 @@ impl ClpSolver {
             builder.elements(),
         );
 
-        clp_simplex.initial_dual_solve();
+        // The dual simplex is used by default because it is efficient when re-solving after
+        // the bounds change between time-steps.
+        let simplex_strategy = options.simplex_strategy.unwrap_or(SimplexStrategy::Dual);
+        clp_simplex.initial_solve_with_options(options.presolve.unwrap_or(false), simplex_strategy);
 
-        ClpSolver { builder, clp_simplex }
+        Ok(ClpSolver {
+            builder,
+            clp_simplex,
+            simplex_strategy,
+        })
     }
 
     fn solve(&mut self) -> Result<Vec<c_double>, ClpSolveStatusError> {
-        self.clp_simplex.dual_solve()?;
+        match self.simplex_strategy {
+            SimplexStrategy::Primal => self.clp_simplex.primal_solve()?,
+            SimplexStrategy::Dual | SimplexStrategy::Auto => self.clp_simplex.dual_solve()?,
+        }
 
         let num_cols = self.builder.num_cols() as usize;
 
@@ -377,12 +499,12 @@ impl Solver for ClpSolver {
     fn setup(
         model: &Network,
         values: &ConstParameterValues,
-        _settings: &Self::Settings,
+        settings: &Self::Settings,
     ) -> Result<Box<Self>, SolverSetupError> {
-        let builder = SolverBuilder::new(f64::MAX, -f64::MAX);
+        let builder = SolverBuilder::new(f64::MAX, -f64::MAX).with_horizon(settings.horizon());
         let built = builder.create(model, values)?;
 
-        let solver = ClpSolver::from_builder(built);
+        let solver = ClpSolver::from_builder(built, settings.options())?;
         Ok(Box::new(solver))
     }
 
@@ -391,13 +513,30 @@ impl Solver for ClpSolver {
         model: &Network,
         timestep: &Timestep,
         state: &mut State,
+    ) -> Result<SolverTimings, SolverSolveError> {
+        self.solve_with_forecasts(model, timestep, state, &[])
+    }
+
+    fn horizon(&self) -> usize {
+        self.builder.horizon()
+    }
+
+    fn solve_with_forecasts(
+        &mut self,
+        model: &Network,
+        timestep: &Timestep,
+        state: &mut State,
+        forecasts: &[Forecast],
     ) -> Result<SolverTimings, SolverSolveError> {
         let mut timings = SolverTimings::default();
-        self.builder.update(model, timestep, state, &mut timings)?;
+        self.builder.update(model, timestep, state, forecasts, &mut timings)?;
 
         let now = Instant::now();
-        self.clp_simplex
-            .change_objective_coefficients(self.builder.col_obj_coef());
+        // The constant objective coefficients were set when the model was created
+        if !self.builder.dynamic_obj_cols().is_empty() {
+            self.clp_simplex
+                .change_objective_coefficients(self.builder.col_obj_coef());
+        }
         timings.update_objective += now.elapsed();
 
         let now = Instant::now();
@@ -416,6 +555,7 @@ impl Solver for ClpSolver {
 
         let solution = self.solve()?;
         timings.solve = now.elapsed();
+        timings.iterations = self.clp_simplex.number_iterations();
 
         // Create the updated network state from the results
         let network_state = state.get_mut_network_state();
@@ -437,6 +577,7 @@ impl Solver for ClpSolver {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::solvers::SolverOptionValue;
     use float_cmp::approx_eq;
 
     #[test]
@@ -483,6 +624,46 @@ mod tests {
         assert_eq!(lp.primal_column_solution(3), vec![0.0, 0.0, 5.0]);
     }
 
+    #[test]
+    fn simple_solve_with_options() {
+        let row_upper = vec![10.0, 15.0];
+        let row_lower = vec![0.0, 0.0];
+        let col_lower = vec![0.0, 0.0, 0.0];
+        let col_upper = vec![f64::MAX, f64::MAX, f64::MAX];
+        let col_obj_coef = vec![-2.0, -3.0, -4.0];
+        let row_starts = vec![0, 3, 6];
+        let columns = vec![0, 1, 2, 0, 1, 2];
+        let elements = vec![3.0, 2.0, 1.0, 2.0, 5.0, 3.0];
+
+        let mut lp = ClpSimplex::default();
+        lp.resize(0, col_upper.len() as c_int);
+
+        lp.change_column_lower(&col_lower);
+        lp.change_column_upper(&col_upper);
+        lp.change_objective_coefficients(&col_obj_coef);
+
+        lp.add_rows(&row_lower, &row_upper, &row_starts, &columns, &elements);
+
+        let options = SolverOptions {
+            primal_tolerance: Some(1e-9),
+            dual_tolerance: Some(1e-9),
+            iteration_limit: Some(100),
+            solver_options: [("scaling".to_string(), SolverOptionValue::Int(0))].into(),
+            ..Default::default()
+        };
+        lp.apply_options(&options).unwrap();
+        lp.initial_solve_with_options(false, SimplexStrategy::Primal);
+
+        assert!(approx_eq!(f64, lp.objective_value(), -20.0));
+        assert_eq!(lp.primal_column_solution(3), vec![0.0, 0.0, 5.0]);
+
+        let options = SolverOptions {
+            solver_options: [("unknown".to_string(), SolverOptionValue::Int(0))].into(),
+            ..Default::default()
+        };
+        assert!(lp.apply_options(&options).is_err());
+    }
+
     #[test]
     fn solve_with_inf_row_bound() {
         let row_upper = vec![10.0, f64::MAX];
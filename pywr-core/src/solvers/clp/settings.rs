@@ -1,15 +1,17 @@
-use crate::solvers::SolverSettings;
+use crate::solvers::{SimplexStrategy, SolverOptionValue, SolverOptions, SolverSettings};
 #[cfg(feature = "pyo3")]
 use pyo3::{Bound, PyResult, exceptions::PyRuntimeError, prelude::PyAnyMethods, types::PyDict};
 
 /// Settings for the OpenCL IPM solvers.
 ///
 /// Create new settings using [`ClpSolverSettingsBuilder`] or use the default implementation;
-#[derive(PartialEq, Debug, Copy, Clone)]
+#[derive(PartialEq, Debug, Clone)]
 pub struct ClpSolverSettings {
     parallel: bool,
     threads: usize,
     ignore_feature_requirements: bool,
+    options: SolverOptions,
+    horizon: usize,
 }
 
 // Default implementation is a convenience that defers to the builder.
@@ -38,6 +40,16 @@ impl ClpSolverSettings {
     pub fn builder() -> ClpSolverSettingsBuilder {
         ClpSolverSettingsBuilder::default()
     }
+
+    /// The tuning options to apply to the solver.
+    pub fn options(&self) -> &SolverOptions {
+        &self.options
+    }
+
+    /// The number of time-steps, including the current time-step, in the look-ahead horizon.
+    pub fn horizon(&self) -> usize {
+        self.horizon
+    }
 }
 
 /// Builder for [`ClpSolverSettings`].
@@ -61,6 +73,8 @@ pub struct ClpSolverSettingsBuilder {
     parallel: bool,
     threads: usize,
     ignore_feature_requirements: bool,
+    options: SolverOptions,
+    horizon: usize,
 }
 
 impl ClpSolverSettingsBuilder {
@@ -79,12 +93,68 @@ impl ClpSolverSettingsBuilder {
         self
     }
 
+    /// Set all of the tuning options, replacing any set previously.
+    pub fn options(mut self, options: SolverOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn primal_tolerance(mut self, tolerance: f64) -> Self {
+        self.options.primal_tolerance = Some(tolerance);
+        self
+    }
+
+    pub fn dual_tolerance(mut self, tolerance: f64) -> Self {
+        self.options.dual_tolerance = Some(tolerance);
+        self
+    }
+
+    pub fn presolve(mut self, presolve: bool) -> Self {
+        self.options.presolve = Some(presolve);
+        self
+    }
+
+    pub fn simplex_strategy(mut self, strategy: SimplexStrategy) -> Self {
+        self.options.simplex_strategy = Some(strategy);
+        self
+    }
+
+    /// Set the time limit, in seconds, for each solve.
+    pub fn time_limit(mut self, seconds: f64) -> Self {
+        self.options.time_limit = Some(seconds);
+        self
+    }
+
+    pub fn iteration_limit(mut self, iterations: usize) -> Self {
+        self.options.iteration_limit = Some(iterations);
+        self
+    }
+
+    /// Optimise over a rolling horizon of `horizon` time-steps, including the current time-step.
+    ///
+    /// Forecasts of the network's parameters are used for the future time-steps, and only the
+    /// flows of the current time-step are applied. The forecasts continue from the current state
+    /// of any stateful parameters, but use the storage volumes at the start of the current
+    /// time-step. A horizon of one (the default) solves each time-step independently.
+    pub fn horizon(mut self, horizon: usize) -> Self {
+        self.horizon = horizon;
+        self
+    }
+
+    /// Set a named option that is passed directly to the solver.
+    pub fn solver_option(mut self, name: &str, value: SolverOptionValue) -> Self {
+        self.options.solver_options.insert(name.to_string(), value);
+        self
+    }
+
     /// Construct a [`ClpSolverSettings`] from the builder.
     pub fn build(self) -> ClpSolverSettings {
         ClpSolverSettings {
             parallel: self.parallel,
             threads: self.threads,
             ignore_feature_requirements: self.ignore_feature_requirements,
+            options: self.options,
+            horizon: self.horizon.max(1),
         }
     }
 }
@@ -107,6 +177,13 @@ pub fn build_clp_settings_py(kwargs: Option<&Bound<'_, PyDict>>) -> PyResult<Clp
             kwargs.del_item("parallel")?;
         }
 
+        if let Ok(horizon) = kwargs.get_item("horizon") {
+            builder = builder.horizon(horizon.extract::<usize>()?);
+            kwargs.del_item("horizon")?;
+        }
+
+        builder = builder.options(SolverOptions::extract_py_kwargs(kwargs)?);
+
         if !kwargs.is_empty()? {
             return Err(PyRuntimeError::new_err(format!(
                 "Unknown keyword arguments: {kwargs:?}",
@@ -119,7 +196,7 @@ pub fn build_clp_settings_py(kwargs: Option<&Bound<'_, PyDict>>) -> PyResult<Clp
 
 #[cfg(test)]
 mod tests {
-    use super::{ClpSolverSettings, ClpSolverSettingsBuilder};
+    use super::{ClpSolverSettings, ClpSolverSettingsBuilder, SolverOptions};
 
     #[test]
     fn builder_test() {
@@ -127,6 +204,8 @@ mod tests {
             parallel: true,
             threads: 0,
             ignore_feature_requirements: false,
+            options: SolverOptions::default(),
+            horizon: 1,
         };
         let settings_from_builder = ClpSolverSettingsBuilder::default().parallel().build();
 
@@ -0,0 +1,411 @@
+use crate::network::Network;
+use crate::solvers::{
+    Forecast, Solver, SolverFeatures, SolverSettings, SolverSetupError, SolverSolveError, SolverTimings,
+};
+use crate::state::{ConstParameterValues, State};
+use crate::timestep::Timestep;
+use std::time::Instant;
+use tracing::warn;
+
+/// Settings for a [`FallbackSolver`].
+///
+/// The common settings (parallel, threads and feature requirements) are taken from the
+/// primary solver's settings.
+#[derive(PartialEq, Debug, Clone)]
+pub struct FallbackSolverSettings<P, F> {
+    primary: P,
+    fallback: F,
+}
+
+impl<P, F> FallbackSolverSettings<P, F> {
+    pub fn new(primary: P, fallback: F) -> Self {
+        Self { primary, fallback }
+    }
+
+    pub fn primary(&self) -> &P {
+        &self.primary
+    }
+
+    pub fn fallback(&self) -> &F {
+        &self.fallback
+    }
+}
+
+impl<P, F> SolverSettings for FallbackSolverSettings<P, F>
+where
+    P: SolverSettings,
+{
+    fn parallel(&self) -> bool {
+        self.primary.parallel()
+    }
+
+    fn threads(&self) -> usize {
+        self.primary.threads()
+    }
+
+    fn ignore_feature_requirements(&self) -> bool {
+        self.primary.ignore_feature_requirements()
+    }
+}
+
+/// A solver that retries a failed solve with a second solver.
+///
+/// Both solvers are setup for each scenario, and the fallback solver is only used for the
+/// time-steps on which the primary solver returns an error. Fallback solvers can be nested
+/// to create a chain; for example, CLP, then HiGHS, and finally CLP again with presolve and
+/// scaling disabled:
+///
+/// ```ignore
+/// type Chain = FallbackSolver<ClpSolver, FallbackSolver<HighsSolver, ClpSolver>>;
+/// ```
+///
+/// Use a [`FallbackChain`] as the fallback solver to choose the chain at runtime instead.
+///
+/// The number of solves that used a fallback solver is counted in [`SolverTimings::fallback_solves`].
+pub struct FallbackSolver<P, F> {
+    primary: Box<P>,
+    fallback: Box<F>,
+}
+
+impl<P, F> Solver for FallbackSolver<P, F>
+where
+    P: Solver,
+    F: Solver,
+    P::Settings: SolverSettings,
+{
+    type Settings = FallbackSolverSettings<P::Settings, F::Settings>;
+
+    fn name() -> &'static str {
+        "fallback"
+    }
+
+    /// The features of the primary solver.
+    ///
+    /// The fallback solver's features are checked against the network during setup.
+    fn features() -> &'static [SolverFeatures] {
+        P::features()
+    }
+
+    fn setup(
+        model: &Network,
+        values: &ConstParameterValues,
+        settings: &Self::Settings,
+    ) -> Result<Box<Self>, SolverSetupError> {
+        if !settings.ignore_feature_requirements() && !model.check_solver_features::<F>() {
+            return Err(SolverSetupError::MissingFallbackSolverFeatures {
+                fallback: F::name().to_string(),
+            });
+        }
+
+        let primary = P::setup(model, values, &settings.primary)?;
+        let fallback = F::setup(model, values, &settings.fallback)?;
+
+        Ok(Box::new(Self { primary, fallback }))
+    }
+
+    fn solve(
+        &mut self,
+        model: &Network,
+        timestep: &Timestep,
+        state: &mut State,
+    ) -> Result<SolverTimings, SolverSolveError> {
+        self.solve_with_forecasts(model, timestep, state, &[])
+    }
+
+    /// The horizon of the primary solver.
+    fn horizon(&self) -> usize {
+        self.primary.horizon()
+    }
+
+    fn solve_with_forecasts(
+        &mut self,
+        model: &Network,
+        timestep: &Timestep,
+        state: &mut State,
+        forecasts: &[Forecast],
+    ) -> Result<SolverTimings, SolverSolveError> {
+        let now = Instant::now();
+        match self.primary.solve_with_forecasts(model, timestep, state, forecasts) {
+            Ok(timings) => Ok(timings),
+            Err(error) => {
+                let failed_duration = now.elapsed();
+                warn!(
+                    "Solver `{}` failed at time-step {} ({}); retrying with `{}`.",
+                    P::name(),
+                    timestep.index,
+                    error,
+                    F::name()
+                );
+
+                let mut timings = self.fallback.solve_with_forecasts(model, timestep, state, forecasts)?;
+                timings.solve += failed_duration;
+                // Nested fallback solvers count this as a single solve that fell back.
+                timings.fallback_solves = 1;
+                Ok(timings)
+            }
+        }
+    }
+}
+
+/// A solver that has been setup as one of the steps of a [`FallbackChain`].
+trait ChainSolver: Send {
+    fn solve_step(
+        &mut self,
+        model: &Network,
+        timestep: &Timestep,
+        state: &mut State,
+        forecasts: &[Forecast],
+    ) -> Result<SolverTimings, SolverSolveError>;
+}
+
+impl<S: Solver> ChainSolver for S {
+    fn solve_step(
+        &mut self,
+        model: &Network,
+        timestep: &Timestep,
+        state: &mut State,
+        forecasts: &[Forecast],
+    ) -> Result<SolverTimings, SolverSolveError> {
+        self.solve_with_forecasts(model, timestep, state, forecasts)
+    }
+}
+
+type ChainSolverSetup =
+    dyn Fn(&Network, &ConstParameterValues) -> Result<Box<dyn ChainSolver>, SolverSetupError> + Send + Sync;
+
+/// A solver and its settings in a [`FallbackChain`].
+pub struct FallbackChainStep {
+    name: &'static str,
+    features: &'static [SolverFeatures],
+    setup: Box<ChainSolverSetup>,
+}
+
+impl FallbackChainStep {
+    /// A step that solves with solver `S` and the given settings.
+    pub fn new<S>(settings: S::Settings) -> Self
+    where
+        S: Solver + 'static,
+        S::Settings: Send + Sync + 'static,
+    {
+        Self {
+            name: S::name(),
+            features: S::features(),
+            setup: Box::new(move |model, values| {
+                let solver: Box<dyn ChainSolver> = S::setup(model, values, &settings)?;
+                Ok(solver)
+            }),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+/// Settings for a [`FallbackChain`].
+pub struct FallbackChainSettings {
+    steps: Vec<FallbackChainStep>,
+    ignore_feature_requirements: bool,
+}
+
+impl FallbackChainSettings {
+    /// Try each of the `steps` in order.
+    pub fn new(steps: Vec<FallbackChainStep>) -> Self {
+        Self {
+            steps,
+            ignore_feature_requirements: false,
+        }
+    }
+
+    /// Do not check that the solver of each step has the features required by the network.
+    pub fn ignore_feature_requirements(mut self) -> Self {
+        self.ignore_feature_requirements = true;
+        self
+    }
+
+    pub fn steps(&self) -> &[FallbackChainStep] {
+        &self.steps
+    }
+}
+
+/// A solver that tries an ordered list of solvers, chosen at runtime, until one succeeds.
+///
+/// This is intended as the fallback solver of a [`FallbackSolver`], which checks the features of
+/// the primary solver before setup. The features of each step of the chain are checked when the
+/// chain is setup, so [`FallbackChain::features`] returns every feature.
+pub struct FallbackChain {
+    steps: Vec<(&'static str, Box<dyn ChainSolver>)>,
+}
+
+impl Solver for FallbackChain {
+    type Settings = FallbackChainSettings;
+
+    fn name() -> &'static str {
+        "fallback-chain"
+    }
+
+    fn features() -> &'static [SolverFeatures] {
+        &[
+            SolverFeatures::AggregatedNode,
+            SolverFeatures::AggregatedNodeFactors,
+            SolverFeatures::AggregatedNodeDynamicFactors,
+            SolverFeatures::VirtualStorage,
+            SolverFeatures::MutualExclusivity,
+            SolverFeatures::SemiContinuous,
+        ]
+    }
+
+    fn setup(
+        model: &Network,
+        values: &ConstParameterValues,
+        settings: &Self::Settings,
+    ) -> Result<Box<Self>, SolverSetupError> {
+        let required_features = model.required_features();
+
+        let steps = settings
+            .steps
+            .iter()
+            .map(|step| {
+                if !settings.ignore_feature_requirements && !required_features.iter().all(|f| step.features.contains(f))
+                {
+                    return Err(SolverSetupError::MissingFallbackSolverFeatures {
+                        fallback: step.name.to_string(),
+                    });
+                }
+                Ok((step.name, (step.setup)(model, values)?))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Box::new(Self { steps }))
+    }
+
+    fn solve(
+        &mut self,
+        model: &Network,
+        timestep: &Timestep,
+        state: &mut State,
+    ) -> Result<SolverTimings, SolverSolveError> {
+        self.solve_with_forecasts(model, timestep, state, &[])
+    }
+
+    fn solve_with_forecasts(
+        &mut self,
+        model: &Network,
+        timestep: &Timestep,
+        state: &mut State,
+        forecasts: &[Forecast],
+    ) -> Result<SolverTimings, SolverSolveError> {
+        let now = Instant::now();
+        let mut steps = self.steps.iter_mut().peekable();
+        while let Some((name, solver)) = steps.next() {
+            match solver.solve_step(model, timestep, state, forecasts) {
+                Ok(mut timings) => {
+                    timings.solve = now.elapsed();
+                    return Ok(timings);
+                }
+                Err(error) => match steps.peek() {
+                    Some((next, _)) => warn!(
+                        "Solver `{name}` failed at time-step {} ({error}); retrying with `{next}`.",
+                        timestep.index
+                    ),
+                    None => return Err(error),
+                },
+            }
+        }
+        // An empty chain has no solver to try.
+        Err(SolverSolveError::MissingSolverFeatures)
+    }
+}
+
+#[cfg(all(test, feature = "clp"))]
+mod tests {
+    use super::{FallbackChain, FallbackChainSettings, FallbackChainStep, FallbackSolver, FallbackSolverSettings};
+    use crate::network::Network;
+    use crate::solvers::{
+        ClpSolver, ClpSolverSettings, Solver, SolverFeatures, SolverSetupError, SolverSolveError, SolverTimings,
+    };
+    use crate::state::{ConstParameterValues, State};
+    use crate::test_utils::simple_model;
+    use crate::timestep::Timestep;
+
+    /// A solver that fails on every other time-step.
+    struct UnreliableSolver {
+        inner: Box<ClpSolver>,
+    }
+
+    impl Solver for UnreliableSolver {
+        type Settings = ClpSolverSettings;
+
+        fn name() -> &'static str {
+            "unreliable"
+        }
+
+        fn features() -> &'static [SolverFeatures] {
+            ClpSolver::features()
+        }
+
+        fn setup(
+            model: &Network,
+            values: &ConstParameterValues,
+            settings: &Self::Settings,
+        ) -> Result<Box<Self>, SolverSetupError> {
+            let inner = ClpSolver::setup(model, values, settings)?;
+            Ok(Box::new(Self { inner }))
+        }
+
+        fn solve(
+            &mut self,
+            model: &Network,
+            timestep: &Timestep,
+            state: &mut State,
+        ) -> Result<SolverTimings, SolverSolveError> {
+            if timestep.index % 2 == 1 {
+                return Err(SolverSolveError::MissingSolverFeatures);
+            }
+            self.inner.solve(model, timestep, state)
+        }
+    }
+
+    #[test]
+    fn test_fallback_solves() {
+        let model = simple_model(2, None);
+
+        let settings = FallbackSolverSettings::new(ClpSolverSettings::default(), ClpSolverSettings::default());
+        let result = model
+            .run::<FallbackSolver<UnreliableSolver, ClpSolver>>(&settings)
+            .unwrap();
+
+        // 7 of the 15 time-steps fail in each of the 2 scenarios.
+        assert_eq!(result.timings.fallback_solves(), 14);
+
+        // Without a fallback the run fails.
+        assert!(model.run::<UnreliableSolver>(&ClpSolverSettings::default()).is_err());
+    }
+
+    /// The steps of a chain are tried in order until one succeeds.
+    #[test]
+    fn test_fallback_chain() {
+        let model = simple_model(2, None);
+
+        let chain = FallbackChainSettings::new(vec![
+            FallbackChainStep::new::<UnreliableSolver>(ClpSolverSettings::default()),
+            FallbackChainStep::new::<ClpSolver>(ClpSolverSettings::default()),
+        ]);
+        let settings = FallbackSolverSettings::new(ClpSolverSettings::default(), chain);
+        let result = model
+            .run::<FallbackSolver<UnreliableSolver, FallbackChain>>(&settings)
+            .unwrap();
+        assert_eq!(result.timings.fallback_solves(), 14);
+
+        // A chain of only unreliable solvers fails.
+        let chain = FallbackChainSettings::new(vec![FallbackChainStep::new::<UnreliableSolver>(
+            ClpSolverSettings::default(),
+        )]);
+        let settings = FallbackSolverSettings::new(ClpSolverSettings::default(), chain);
+        assert!(
+            model
+                .run::<FallbackSolver<UnreliableSolver, FallbackChain>>(&settings)
+                .is_err()
+        );
+    }
+}
@@ -2,21 +2,25 @@ mod settings;
 
 use crate::network::Network;
 use crate::solvers::builder::{BuiltSolver, ColType, SolverBuilder};
-use crate::solvers::{Solver, SolverFeatures, SolverSetupError, SolverSolveError, SolverTimings};
+use crate::solvers::{
+    Forecast, SimplexStrategy, Solver, SolverFeatures, SolverOptionValue, SolverOptions, SolverSetupError,
+    SolverSolveError, SolverTimings,
+};
 use crate::state::{ConstParameterValues, State};
 use crate::timestep::Timestep;
 use highs_sys::{
     Highs_addCols, Highs_addRows, Highs_changeCoeff, Highs_changeColIntegrality, Highs_changeColsCostByRange,
-    Highs_changeObjectiveSense, Highs_changeRowsBoundsByMask, Highs_clearSolver, Highs_create,
-    Highs_getDoubleInfoValue, Highs_getModelStatus, Highs_getSolution, Highs_run, Highs_setBoolOptionValue,
-    Highs_setStringOptionValue, Highs_writeModel, HighsInt, OBJECTIVE_SENSE_MINIMIZE, STATUS_OK,
-    kHighsModelStatusInfeasible, kHighsModelStatusInterrupt, kHighsModelStatusIterationLimit,
-    kHighsModelStatusLoadError, kHighsModelStatusModelEmpty, kHighsModelStatusModelError, kHighsModelStatusNotset,
-    kHighsModelStatusObjectiveBound, kHighsModelStatusObjectiveTarget, kHighsModelStatusOptimal,
-    kHighsModelStatusPostsolveError, kHighsModelStatusPresolveError, kHighsModelStatusSolutionLimit,
-    kHighsModelStatusSolveError, kHighsModelStatusTimeLimit, kHighsModelStatusUnbounded,
-    kHighsModelStatusUnboundedOrInfeasible, kHighsModelStatusUnknown, kHighsStatusError, kHighsStatusOk,
-    kHighsStatusWarning, kHighsVarTypeContinuous, kHighsVarTypeInteger,
+    Highs_changeColsCostBySet, Highs_changeObjectiveSense, Highs_changeRowsBoundsByMask, Highs_clearSolver,
+    Highs_create, Highs_getDoubleInfoValue, Highs_getIntInfoValue, Highs_getModelStatus, Highs_getSolution, Highs_run,
+    Highs_setBoolOptionValue, Highs_setDoubleOptionValue, Highs_setIntOptionValue, Highs_setStringOptionValue,
+    Highs_writeModel, HighsInt, OBJECTIVE_SENSE_MINIMIZE, STATUS_OK, kHighsModelStatusInfeasible,
+    kHighsModelStatusInterrupt, kHighsModelStatusIterationLimit, kHighsModelStatusLoadError,
+    kHighsModelStatusModelEmpty, kHighsModelStatusModelError, kHighsModelStatusNotset, kHighsModelStatusObjectiveBound,
+    kHighsModelStatusObjectiveTarget, kHighsModelStatusOptimal, kHighsModelStatusPostsolveError,
+    kHighsModelStatusPresolveError, kHighsModelStatusSolutionLimit, kHighsModelStatusSolveError,
+    kHighsModelStatusTimeLimit, kHighsModelStatusUnbounded, kHighsModelStatusUnboundedOrInfeasible,
+    kHighsModelStatusUnknown, kHighsStatusError, kHighsStatusOk, kHighsStatusWarning, kHighsVarTypeContinuous,
+    kHighsVarTypeInteger,
 };
 use libc::c_void;
 #[cfg(feature = "pyo3")]
@@ -155,7 +159,6 @@ fn to_highs_model_result(status: i32) -> Result<(), HighsModelError> {
 }
 
 impl Highs {
-    #[allow(dead_code)]
     fn presolve(&mut self, value: &str) {
         let option_name = CString::new("presolve").unwrap();
         let option_value = CString::new(value).unwrap();
@@ -165,6 +168,73 @@ impl Highs {
         }
     }
 
+    /// Set a named option using the setter that corresponds to the type of `value`.
+    fn set_option(&mut self, name: &str, value: &SolverOptionValue) -> Result<(), SolverSetupError> {
+        let c_name =
+            CString::new(name).map_err(|_| SolverSetupError::UnsupportedSolverOption { name: name.to_string() })?;
+
+        let ret = unsafe {
+            match value {
+                SolverOptionValue::Bool(v) => Highs_setBoolOptionValue(self.ptr, c_name.as_ptr(), *v as HighsInt),
+                SolverOptionValue::Int(v) => {
+                    let v = HighsInt::try_from(*v).map_err(|_| SolverSetupError::InvalidSolverOptionValue {
+                        name: name.to_string(),
+                        value: value.to_string(),
+                    })?;
+                    Highs_setIntOptionValue(self.ptr, c_name.as_ptr(), v)
+                }
+                SolverOptionValue::Float(v) => Highs_setDoubleOptionValue(self.ptr, c_name.as_ptr(), *v),
+                SolverOptionValue::String(v) => {
+                    let c_value = CString::new(v.as_str()).map_err(|_| SolverSetupError::InvalidSolverOptionValue {
+                        name: name.to_string(),
+                        value: value.to_string(),
+                    })?;
+                    Highs_setStringOptionValue(self.ptr, c_name.as_ptr(), c_value.as_ptr())
+                }
+            }
+        };
+
+        Ok(to_highs_result(ret, &format!("Highs_setOptionValue({name})"))?)
+    }
+
+    /// Apply the tuning options to the model.
+    ///
+    /// Named solver options are passed to HiGHS with the setter matching the value's type.
+    fn apply_options(&mut self, options: &SolverOptions) -> Result<(), SolverSetupError> {
+        if let Some(tolerance) = options.primal_tolerance {
+            self.set_option("primal_feasibility_tolerance", &SolverOptionValue::Float(tolerance))?;
+        }
+        if let Some(tolerance) = options.dual_tolerance {
+            self.set_option("dual_feasibility_tolerance", &SolverOptionValue::Float(tolerance))?;
+        }
+
+        let presolve = if options.presolve.unwrap_or(true) { "on" } else { "off" };
+        self.presolve(presolve);
+
+        if let Some(strategy) = options.simplex_strategy {
+            // HiGHS simplex strategies: 0 - choose, 1 - dual (serial), 4 - primal
+            let strategy = match strategy {
+                SimplexStrategy::Auto => 0,
+                SimplexStrategy::Dual => 1,
+                SimplexStrategy::Primal => 4,
+            };
+            self.set_option("simplex_strategy", &SolverOptionValue::Int(strategy))?;
+        }
+        if let Some(seconds) = options.time_limit {
+            self.set_option("time_limit", &SolverOptionValue::Float(seconds))?;
+        }
+        if let Some(iterations) = options.iteration_limit {
+            let iterations = iterations.try_into().unwrap_or(i64::MAX);
+            self.set_option("simplex_iteration_limit", &SolverOptionValue::Int(iterations))?;
+        }
+
+        for (name, value) in options.solver_options.iter() {
+            self.set_option(name, value)?;
+        }
+
+        Ok(())
+    }
+
     pub fn add_cols(
         &mut self,
         col_lower: &[f64],
@@ -227,6 +297,7 @@ impl Highs {
         to_highs_result(ret, "addRows")
     }
 
+    #[allow(dead_code)]
     pub fn change_objective_coefficients(
         &mut self,
         obj_coefficients: &[f64],
@@ -236,6 +307,22 @@ impl Highs {
         to_highs_result(ret, "changeColsCostByRange")
     }
 
+    pub fn change_objective_coefficients_by_set(
+        &mut self,
+        cols: &[HighsInt],
+        obj_coefficients: &[f64],
+    ) -> Result<(), HighsStatusError> {
+        let ret = unsafe {
+            Highs_changeColsCostBySet(
+                self.ptr,
+                cols.len() as HighsInt,
+                cols.as_ptr(),
+                obj_coefficients.as_ptr(),
+            )
+        };
+        to_highs_result(ret, "changeColsCostBySet")
+    }
+
     pub fn change_row_bounds(
         &mut self,
         mask: &[HighsInt],
@@ -274,6 +361,16 @@ impl Highs {
         objective_function_value
     }
 
+    /// The number of simplex iterations of the last run.
+    pub fn simplex_iteration_count(&mut self) -> usize {
+        let mut iterations: HighsInt = 0;
+        unsafe {
+            let info_name = CString::new("simplex_iteration_count").unwrap();
+            Highs_getIntInfoValue(self.ptr, info_name.as_ptr(), (&mut iterations) as *mut HighsInt);
+        }
+        iterations.max(0) as usize
+    }
+
     pub fn primal_column_solution(&mut self, numcol: usize, numrow: usize) -> Result<Vec<f64>, HighsStatusError> {
         let colvalue: &mut [f64] = &mut vec![0.; numcol];
         let coldual: &mut [f64] = &mut vec![0.; numcol];
@@ -322,6 +419,7 @@ impl Solver for HighsSolver {
         &[
             SolverFeatures::VirtualStorage,
             SolverFeatures::MutualExclusivity,
+            SolverFeatures::SemiContinuous,
             SolverFeatures::AggregatedNode,
             SolverFeatures::AggregatedNodeFactors,
             SolverFeatures::AggregatedNodeDynamicFactors,
@@ -332,16 +430,16 @@ impl Solver for HighsSolver {
     fn setup(
         network: &Network,
         values: &ConstParameterValues,
-        _settings: &Self::Settings,
+        settings: &Self::Settings,
     ) -> Result<Box<Self>, SolverSetupError> {
-        let builder: SolverBuilder<HighsInt> = SolverBuilder::new(f64::MAX, -f64::MAX);
+        let builder: SolverBuilder<HighsInt> = SolverBuilder::new(f64::MAX, -f64::MAX).with_horizon(settings.horizon());
         let built = builder.create(network, values)?;
 
         let num_cols = built.num_cols();
         let num_nz = built.num_non_zero();
 
         let mut highs_lp = Highs::default();
-        highs_lp.presolve("on");
+        highs_lp.apply_options(settings.options())?;
 
         highs_lp.add_cols(
             built.col_lower(),
@@ -370,16 +468,36 @@ impl Solver for HighsSolver {
         network: &Network,
         timestep: &Timestep,
         state: &mut State,
+    ) -> Result<SolverTimings, SolverSolveError> {
+        self.solve_with_forecasts(network, timestep, state, &[])
+    }
+
+    fn horizon(&self) -> usize {
+        self.builder.horizon()
+    }
+
+    fn solve_with_forecasts(
+        &mut self,
+        network: &Network,
+        timestep: &Timestep,
+        state: &mut State,
+        forecasts: &[Forecast],
     ) -> Result<SolverTimings, SolverSolveError> {
         let mut timings = SolverTimings::default();
-        self.builder.update(network, timestep, state, &mut timings)?;
+        self.builder.update(network, timestep, state, forecasts, &mut timings)?;
 
         let num_cols = self.builder.num_cols();
         let num_rows = self.builder.num_rows();
 
+        // Only the dynamic objective coefficients are updated
         let now = Instant::now();
-        self.highs
-            .change_objective_coefficients(self.builder.col_obj_coef(), num_cols)?;
+        let dynamic_obj_cols = self.builder.dynamic_obj_cols();
+        if !dynamic_obj_cols.is_empty() {
+            let col_obj_coef = self.builder.col_obj_coef();
+            let obj_coefficients: Vec<f64> = dynamic_obj_cols.iter().map(|col| col_obj_coef[*col as usize]).collect();
+            self.highs
+                .change_objective_coefficients_by_set(dynamic_obj_cols, &obj_coefficients)?;
+        }
         timings.update_objective += now.elapsed();
 
         let now = Instant::now();
@@ -420,6 +538,7 @@ impl Solver for HighsSolver {
             .highs
             .primal_column_solution(num_cols as usize, num_rows as usize)?;
         timings.solve = now.elapsed();
+        timings.iterations = self.highs.simplex_iteration_count();
 
         // Reset the network state from the results
         let network_state = state.get_mut_network_state();
@@ -1,5 +1,6 @@
 mod settings;
 
+use crate::aggregated_node::{AggregatedNodeIndex, Factors, RhsConstraint};
 use crate::edge::EdgeIndex;
 use crate::network::Network;
 use crate::node::{Node, NodeBounds, NodeType};
@@ -7,18 +8,18 @@ use crate::solvers::col_edge_map::{ColumnEdgeMap, ColumnEdgeMapBuilder};
 use crate::solvers::{MultiStateSolver, SolverFeatures, SolverSetupError, SolverSolveError, SolverTimings};
 use crate::state::State;
 use crate::timestep::Timestep;
-use ipm_simd::{PathFollowingDirectSimdSolver, Tolerances};
+use ipm_simd::{PathFollowingDirectSimdSolver, SimdFloat, Tolerances};
 use rayon::iter::IndexedParallelIterator;
 use rayon::iter::ParallelIterator;
 use rayon::prelude::ParallelSliceMut;
 #[cfg(feature = "pyo3")]
 pub use settings::build_ipm_simd_settings_py;
 pub use settings::{SimdIpmSolverSettings, SimdIpmSolverSettingsBuilder};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::num::NonZeroUsize;
 use std::ops::Deref;
 use std::time::Instant;
-use wide::f64x4;
+use wide::{f32x4, f32x8, f32x16, f64x4};
 
 const B_MAX: f64 = 999999.0;
 
@@ -54,59 +55,48 @@ impl Matrix {
     }
 }
 
-struct Lp {
+struct Lp<T> {
     inequality: Matrix,
     equality: Matrix,
     num_cols: usize,
-    row_upper: Vec<f64x4>,
-    col_obj_coef: Vec<f64x4>,
+    row_upper: Vec<T>,
+    col_obj_coef: Vec<T>,
 }
 
-impl Lp {
+impl<T: SimdFloat> Lp<T> {
     /// Zero all objective coefficients.
     fn zero_obj_coefficients(&mut self) {
-        self.col_obj_coef.fill(f64x4::splat(0.0));
+        self.col_obj_coef.fill(T::splat(0.0));
     }
 
+    /// Add `obj_coef` to the objective coefficients of `col`. The last value is used to pad
+    /// any lanes without a scenario.
     pub fn add_obj_coefficient(&mut self, col: usize, obj_coef: &[f64]) {
-        let value = if obj_coef.is_empty() {
-            panic!("Row bound vector is empty!")
-        } else if obj_coef.len() > 4 {
-            panic!("Row bound vector is larger than the number of SIMD lanes.")
-        } else if obj_coef.len() == 4 {
-            f64x4::from(obj_coef)
-        } else {
-            // Pad the last entry to ensure it is the full width
-            let pad: Vec<_> = (0..4 - obj_coef.len()).map(|_| *obj_coef.last().unwrap()).collect();
-            let values = [obj_coef, &pad].concat();
-            f64x4::from(values.as_slice())
-        };
-
-        self.col_obj_coef[col] += value;
+        self.col_obj_coef[col] += T::from_f64_slice(obj_coef);
     }
 
     /// Reset the row bounds to `FMIN` and `FMAX` for all rows with a mask.
     fn reset_row_bounds(&mut self) {
         for ub in self.row_upper.iter_mut().take(self.inequality.nrows()) {
-            *ub = f64x4::splat(B_MAX)
+            *ub = T::splat(B_MAX)
         }
     }
 
     pub fn apply_row_bounds(&mut self, row: usize, ub: &[f64]) {
-        let value = if ub.is_empty() {
-            panic!("Row bound vector is empty!")
-        } else if ub.len() > 4 {
-            panic!("Row bound vector is larger than the number of SIMD lanes.")
-        } else if ub.len() == 4 {
-            f64x4::from(ub)
-        } else {
-            // Pad the last entry to ensure it is the full width
-            let pad: Vec<_> = (0..4 - ub.len()).map(|_| *ub.last().unwrap()).collect();
-            let values = [ub, &pad].concat();
-            f64x4::from(values.as_slice())
-        };
+        self.row_upper[row] = self.row_upper[row].min(T::from_f64_slice(ub));
+    }
 
-        self.row_upper[row] = self.row_upper[row].min(value);
+    /// Set the right-hand side of an equality row.
+    pub fn set_row_rhs(&mut self, row: usize, rhs: &[f64]) {
+        self.row_upper[row] = T::from_f64_slice(rhs);
+    }
+
+    /// The index of `row` in the full matrix; the equality rows follow the inequality rows.
+    fn row_index(&self, row: RowId) -> usize {
+        match row {
+            RowId::Inequality(row) => row,
+            RowId::Equality(row) => self.inequality.nrows() + row,
+        }
     }
 
     fn get_full_matrix(&self) -> Matrix {
@@ -170,24 +160,49 @@ impl LpBuilder {
                     }
                 }
             }
-            Bounds::Fixed => {
+            Bounds::Fixed(_) => {
                 self.equality.push(row);
                 None
             }
         }
     }
 
+    /// Add a row whose coefficients are updated at each time-step.
+    ///
+    /// Unlike [`LpBuilder::add_row`] the row is never merged with an existing inequality row.
+    fn add_variable_row(&mut self, row: RowBuilder) -> RowId {
+        match &row.upper {
+            Bounds::Upper => {
+                self.inequality.push(row);
+                RowId::Inequality(self.inequality.len() - 1)
+            }
+            Bounds::Fixed(_) => {
+                self.equality.push(row);
+                RowId::Equality(self.equality.len() - 1)
+            }
+        }
+    }
+
     /// Build the LP into a final sparse form
-    fn build(self) -> Lp {
+    fn build<T: SimdFloat>(self) -> Lp<T> {
         let num_rows = self.equality.len() + self.inequality.len();
 
         // By using chunks we make sure any scenarios that do not divide in to the number
         // of lanes are padded at the end.
         // let row_range: Vec<_> = (0..num_rows).collect();
-        let row_upper = (0..num_rows).map(|_| f64x4::splat(0.0)).collect();
+        // Equality constraints are fixed at their right-hand side; inequality bounds are
+        // set at each time-step.
+        let row_upper: Vec<_> = (0..self.inequality.len())
+            .map(|_| T::splat(0.0))
+            .chain(self.equality.iter().map(|row| match row.upper {
+                Bounds::Fixed(rhs) => T::splat(rhs),
+                Bounds::Upper => unreachable!("Equality rows must have fixed bounds."),
+            }))
+            .collect();
+        debug_assert_eq!(num_rows, row_upper.len());
 
         // let col_range: Vec<_> = (0..self.num_cols).collect();
-        let col_obj_coef = (0..self.num_cols).map(|_| f64x4::splat(0.0)).collect();
+        let col_obj_coef = vec![T::splat(0.0); self.num_cols];
 
         // println!("Number of columns: {}", self.num_cols);
         // println!("Number of rows: {num_rows}");
@@ -228,10 +243,18 @@ impl LpBuilder {
     }
 }
 
+/// The index of a row in either the inequality or the equality matrix.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum RowId {
+    Inequality(usize),
+    Equality(usize),
+}
+
 #[derive(Debug, Copy, Clone, PartialEq)]
 enum Bounds {
     Upper,
-    Fixed,
+    /// An equality row fixed at the given right-hand side.
+    Fixed(f64),
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -241,9 +264,9 @@ struct RowBuilder {
 }
 
 impl RowBuilder {
-    fn fixed() -> Self {
+    fn fixed(rhs: f64) -> Self {
         Self {
-            upper: Bounds::Fixed,
+            upper: Bounds::Fixed(rhs),
             columns: BTreeMap::new(),
         }
     }
@@ -273,18 +296,38 @@ impl RowBuilder {
     }
 }
 
-struct BuiltSolver {
-    lp: Lp,
+/// The row of an aggregated node factor constraint for one pair of nodes.
+///
+/// The coefficient of each column is `f0 * node0_weight + f1 * node1_weight`, where `f0` and `f1`
+/// are the factors of the pair. This allows the coefficients to be updated for each scenario
+/// without rebuilding the row.
+struct AggNodeFactorRow {
+    row: RowId,
+    columns: Vec<usize>,
+    node0_weights: Vec<f64>,
+    node1_weights: Vec<f64>,
+}
+
+struct AggNodeFactorRows {
+    agg_node_idx: AggregatedNodeIndex,
+    rows: Vec<AggNodeFactorRow>,
+}
+
+struct BuiltSolver<T> {
+    lp: Lp<T>,
     col_edge_map: ColumnEdgeMap<usize>,
     node_constraints_row_ids: Vec<usize>,
+    agg_node_constraint_row_ids: Vec<(usize, Option<usize>)>,
+    agg_node_factor_constraint_rows: Vec<AggNodeFactorRows>,
+    virtual_storage_constraint_row_ids: Vec<(usize, usize)>,
 }
 
-impl BuiltSolver {
-    pub fn col_obj_coef(&self) -> &[f64x4] {
+impl<T: SimdFloat> BuiltSolver<T> {
+    pub fn col_obj_coef(&self) -> &[T] {
         &self.lp.col_obj_coef
     }
 
-    pub fn row_upper(&self) -> &[f64x4] {
+    pub fn row_upper(&self) -> &[T] {
         &self.lp.row_upper
     }
 
@@ -297,6 +340,7 @@ impl BuiltSolver {
         network: &Network,
         timestep: &Timestep,
         states: &[State],
+        ipm: &mut PathFollowingDirectSimdSolver<T>,
         timings: &mut SolverTimings,
     ) -> Result<(), SolverSolveError> {
         let start_objective_update = Instant::now();
@@ -307,7 +351,9 @@ impl BuiltSolver {
 
         self.lp.reset_row_bounds();
         self.update_node_constraint_bounds(network, timestep, states)?;
-        // self.update_aggregated_node_constraint_bounds(network, state)?;
+        self.update_aggregated_node_constraint_bounds(network, states)?;
+        self.update_aggregated_node_factor_constraints(network, states, ipm)?;
+        self.update_virtual_storage_node_constraint_bounds(network, timestep, states)?;
         timings.update_constraints += start_constraint_update.elapsed();
 
         Ok(())
@@ -403,6 +449,145 @@ impl BuiltSolver {
 
         Ok(())
     }
+
+    /// Update aggregated node constraints
+    ///
+    /// Unlike the other nodes, the lower bound on the total flow is also respected if one is
+    /// defined.
+    fn update_aggregated_node_constraint_bounds(
+        &mut self,
+        network: &Network,
+        states: &[State],
+    ) -> Result<(), SolverSolveError> {
+        for ((max_row_id, min_row_id), agg_node) in self
+            .agg_node_constraint_row_ids
+            .iter()
+            .zip(network.aggregated_nodes().deref())
+        {
+            let (lb, ub): (Vec<_>, Vec<_>) = states
+                .iter()
+                .map(|state| {
+                    agg_node
+                        .get_flow_bounds(network, state)
+                        .map(|(lb, ub)| (-lb, ub.min(B_MAX)))
+                        .map_err(|e| SolverSolveError::AggregatedNodeError {
+                            name: agg_node.name().to_string(),
+                            sub_name: agg_node.sub_name().map(|s| s.to_string()),
+                            source: e,
+                        })
+                })
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .unzip();
+
+            self.lp.apply_row_bounds(*max_row_id, ub.as_slice());
+            if let Some(min_row_id) = min_row_id {
+                self.lp.apply_row_bounds(*min_row_id, lb.as_slice());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Update aggregated node factor constraints
+    ///
+    /// The factors may differ between the scenarios, so the coefficients are set in `ipm` for
+    /// each lane. The coefficients of constant factors are also set at every time-step.
+    fn update_aggregated_node_factor_constraints(
+        &mut self,
+        network: &Network,
+        states: &[State],
+        ipm: &mut PathFollowingDirectSimdSolver<T>,
+    ) -> Result<(), SolverSolveError> {
+        for agg_node_rows in self.agg_node_factor_constraint_rows.iter() {
+            let agg_node = network.get_aggregated_node(&agg_node_rows.agg_node_idx).ok_or(
+                SolverSolveError::AggregatedNodeIndexNotFound(agg_node_rows.agg_node_idx),
+            )?;
+
+            let node_pairs = states
+                .iter()
+                .map(|state| {
+                    agg_node
+                        .get_norm_factor_pairs(network, state)
+                        .expect("No factor pairs found for an aggregated node that was setup with factors?!")
+                        .map_err(|source| SolverSolveError::AggregatedNodeFactorError {
+                            name: agg_node.name().to_string(),
+                            sub_name: agg_node.sub_name().map(|s| s.to_string()),
+                            source,
+                        })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            for (i, factor_row) in agg_node_rows.rows.iter().enumerate() {
+                let row = self.lp.row_index(factor_row.row);
+                let pairs: Vec<_> = node_pairs
+                    .iter()
+                    .map(|pairs| pairs.get(i).expect("Row indices and node pairs do not match!"))
+                    .collect();
+
+                for ((column, w0), w1) in factor_row
+                    .columns
+                    .iter()
+                    .zip(&factor_row.node0_weights)
+                    .zip(&factor_row.node1_weights)
+                {
+                    let coefficients: Vec<f64> = pairs
+                        .iter()
+                        .map(|pair| pair.node0_factor() * w0 + pair.node1_factor() * w1)
+                        .collect();
+                    if coefficients.iter().any(|c| !c.is_finite()) {
+                        panic!("Row factor is non-finite.");
+                    }
+                    ipm.set_coefficient(row, *column, T::from_f64_slice(&coefficients));
+                }
+
+                let rhs: Vec<f64> = pairs.iter().map(|pair| pair.rhs()).collect();
+                match factor_row.row {
+                    RowId::Inequality(_) => self.lp.apply_row_bounds(row, &rhs),
+                    RowId::Equality(_) => self.lp.set_row_rhs(row, &rhs),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Update virtual storage node constraints
+    ///
+    /// Inactive virtual storage nodes are left unbounded.
+    fn update_virtual_storage_node_constraint_bounds(
+        &mut self,
+        network: &Network,
+        timestep: &Timestep,
+        states: &[State],
+    ) -> Result<(), SolverSolveError> {
+        let dt = timestep.days();
+
+        for ((missing_row_id, avail_row_id), node) in self
+            .virtual_storage_constraint_row_ids
+            .iter()
+            .zip(network.virtual_storage_nodes().deref())
+        {
+            if !node.is_active(timestep) {
+                continue;
+            }
+
+            let (avail, missing): (Vec<_>, Vec<_>) = states
+                .iter()
+                .map(|state| {
+                    node.get_available_volume_bounds(state)
+                        .map(|(avail, missing)| (avail / dt, missing / dt))
+                })
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .unzip();
+
+            self.lp.apply_row_bounds(*missing_row_id, missing.as_slice());
+            self.lp.apply_row_bounds(*avail_row_id, avail.as_slice());
+        }
+
+        Ok(())
+    }
 }
 
 struct SolverBuilder {
@@ -426,7 +611,7 @@ impl SolverBuilder {
         self.col_edge_map.col_for_edge(edge_index)
     }
 
-    fn create(mut self, network: &Network) -> Result<BuiltSolver, SolverSetupError> {
+    fn create<T: SimdFloat>(mut self, network: &Network) -> Result<BuiltSolver<T>, SolverSetupError> {
         // Create the columns
         self.create_columns(network)?;
 
@@ -434,17 +619,20 @@ impl SolverBuilder {
         self.create_mass_balance_constraints(network);
         // Create the nodal constraints
         let node_constraints_row_ids = self.create_node_constraints(network);
-        // // Create the aggregated node constraints
-        // builder.create_aggregated_node_constraints(network);
-        // // Create the aggregated node factor constraints
-        // builder.create_aggregated_node_factor_constraints(network);
-        // // Create virtual storage constraints
-        // builder.create_virtual_storage_constraints(network);
+        // Create the aggregated node constraints
+        let agg_node_constraint_row_ids = self.create_aggregated_node_constraints(network);
+        // Create virtual storage constraints
+        let virtual_storage_constraint_row_ids = self.create_virtual_storage_constraints(network);
+        // Create the aggregated node factor constraints last so that no other row is merged with them
+        let agg_node_factor_constraint_rows = self.create_aggregated_node_factor_constraints(network);
 
         Ok(BuiltSolver {
             lp: self.builder.build(),
             col_edge_map: self.col_edge_map.build(),
             node_constraints_row_ids,
+            agg_node_constraint_row_ids,
+            agg_node_factor_constraint_rows,
+            virtual_storage_constraint_row_ids,
         })
     }
 
@@ -501,7 +689,7 @@ impl SolverBuilder {
             // Only link nodes create mass-balance constraints
 
             if let NodeType::Link = node.node_type() {
-                let mut row = RowBuilder::fixed();
+                let mut row = RowBuilder::fixed(0.0);
                 let incoming_edges = node.get_incoming_edges().unwrap();
                 let outgoing_edges = node.get_outgoing_edges().unwrap();
 
@@ -596,37 +784,168 @@ impl SolverBuilder {
 
         row_ids
     }
-}
 
-pub struct SimdIpmF64Solver {
-    built: Vec<BuiltSolver>,
-    ipm: Vec<PathFollowingDirectSimdSolver>,
-    tolerances: Tolerances,
-    max_iterations: NonZeroUsize,
-}
+    /// Create aggregated node constraints
+    ///
+    /// One constraint is created per aggregated node to enforce its maximum flow. A second
+    /// constraint is created to enforce its minimum flow if one is defined. Returns the row id
+    /// of the maximum flow constraint, and of the minimum flow constraint if there is one.
+    fn create_aggregated_node_constraints(&mut self, network: &Network) -> Vec<(usize, Option<usize>)> {
+        let mut row_ids = Vec::with_capacity(network.aggregated_nodes().len());
+
+        for agg_node in network.aggregated_nodes().deref() {
+            let mut row = RowBuilder::upper();
+
+            for node_indices in agg_node.iter_nodes() {
+                for node_idx in node_indices {
+                    let node = network.nodes().get(node_idx).expect("Node index not found!");
+                    self.add_node(node, 1.0, &mut row);
+                }
+            }
 
-impl MultiStateSolver for SimdIpmF64Solver {
-    type Settings = SimdIpmSolverSettings;
+            let min_row_id = if agg_node.is_min_flow_unconstrained() {
+                None
+            } else {
+                Some(self.builder.add_row(row.clone_negative()).unwrap())
+            };
+            let max_row_id = self.builder.add_row(row).unwrap();
+            row_ids.push((max_row_id, min_row_id));
+        }
+        row_ids
+    }
 
-    fn name() -> &'static str {
-        "ipm-simd"
+    /// Create aggregated node factor constraints
+    ///
+    /// One constraint is created per node pair of each aggregated node with factors. The rows are
+    /// fixed at their right-hand side, unless the coefficients are limited to an upper bound. The
+    /// coefficients and right-hand sides are set at each time-step.
+    fn create_aggregated_node_factor_constraints(&mut self, network: &Network) -> Vec<AggNodeFactorRows> {
+        let mut factor_rows = Vec::new();
+
+        for agg_node in network.aggregated_nodes().deref() {
+            let (Some(factors), Some(node_pairs)) = (agg_node.get_factors(), agg_node.get_factor_node_pairs()) else {
+                continue;
+            };
+
+            let is_upper_bound = matches!(
+                factors,
+                Factors::Coefficients {
+                    rhs_constraint: RhsConstraint::UpperBound,
+                    ..
+                }
+            );
+
+            let rows = node_pairs
+                .into_iter()
+                .map(|(node0_indices, node1_indices)| {
+                    let nodes = network.nodes();
+                    let mut node0_row = RowBuilder::upper();
+                    for node0_idx in node0_indices {
+                        let node0 = nodes.get(node0_idx).expect("Node index not found!");
+                        self.add_node(node0, 1.0, &mut node0_row);
+                    }
+                    let mut node1_row = RowBuilder::upper();
+                    for node1_idx in node1_indices {
+                        let node1 = nodes.get(node1_idx).expect("Node index not found!");
+                        self.add_node(node1, 1.0, &mut node1_row);
+                    }
+
+                    let columns: Vec<usize> = node0_row
+                        .columns
+                        .keys()
+                        .chain(node1_row.columns.keys())
+                        .copied()
+                        .collect::<BTreeSet<_>>()
+                        .into_iter()
+                        .collect();
+                    let node0_weights: Vec<f64> = columns
+                        .iter()
+                        .map(|c| node0_row.columns.get(c).copied().unwrap_or(0.0))
+                        .collect();
+                    let node1_weights: Vec<f64> = columns
+                        .iter()
+                        .map(|c| node1_row.columns.get(c).copied().unwrap_or(0.0))
+                        .collect();
+
+                    // The coefficients are placeholders until the factors are known
+                    let mut row = if is_upper_bound {
+                        RowBuilder::upper()
+                    } else {
+                        RowBuilder::fixed(0.0)
+                    };
+                    for column in columns.iter() {
+                        row.add_element(*column, 1.0);
+                    }
+
+                    AggNodeFactorRow {
+                        row: self.builder.add_variable_row(row),
+                        columns,
+                        node0_weights,
+                        node1_weights,
+                    }
+                })
+                .collect();
+
+            factor_rows.push(AggNodeFactorRows {
+                agg_node_idx: agg_node.index(),
+                rows,
+            });
+        }
+
+        factor_rows
     }
 
-    fn features() -> &'static [SolverFeatures] {
-        &[]
+    /// Create virtual storage node constraints
+    ///
+    /// Similar to storage nodes, two constraints are created per virtual storage node. The
+    /// first bounds the recovery of volume and the second bounds the use of volume. Returns the
+    /// row ids of both constraints for each virtual storage node.
+    fn create_virtual_storage_constraints(&mut self, network: &Network) -> Vec<(usize, usize)> {
+        let mut row_ids = Vec::with_capacity(network.virtual_storage_nodes().len());
+
+        for virtual_storage in network.virtual_storage_nodes().deref() {
+            let mut row = RowBuilder::upper();
+            for (node_index, factor) in virtual_storage.iter_nodes_with_factors() {
+                if !factor.is_finite() {
+                    panic!(
+                        "Virtual storage node {:?} contains a non-finite factor.",
+                        virtual_storage.full_name()
+                    );
+                }
+                let node = network.nodes().get(node_index).expect("Node index not found!");
+                self.add_node(node, -factor, &mut row);
+            }
+            let missing_row_id = self.builder.add_row(row.clone()).unwrap();
+            let avail_row_id = self.builder.add_row(row.clone_negative()).unwrap();
+            row_ids.push((missing_row_id, avail_row_id));
+        }
+        row_ids
     }
+}
 
+/// The LPs and IPM solvers for all of the scenarios, with one scenario solved in each lane of `T`.
+struct SimdIpm<T> {
+    built: Vec<BuiltSolver<T>>,
+    ipm: Vec<PathFollowingDirectSimdSolver<T>>,
+    tolerances: Tolerances,
+    max_iterations: NonZeroUsize,
+    refinement_steps: usize,
+}
+
+impl<T: SimdFloat> SimdIpm<T> {
     fn setup(
         network: &Network,
         num_scenarios: usize,
-        settings: &Self::Settings,
-    ) -> Result<Box<Self>, SolverSetupError> {
+        settings: &SimdIpmSolverSettings,
+        tolerances: Tolerances,
+        refinement_steps: usize,
+    ) -> Result<Self, SolverSetupError> {
         let mut built_solvers = Vec::new();
         let mut ipms = Vec::new();
 
-        for _ in (0..num_scenarios).collect::<Vec<_>>().chunks(4) {
+        for _ in 0..num_scenarios.div_ceil(T::LANES) {
             let builder = SolverBuilder::new();
-            let built = builder.create(network)?;
+            let built: BuiltSolver<T> = builder.create(network)?;
 
             let matrix = built.lp.get_full_matrix();
             let num_rows = matrix.row_starts.len() - 1;
@@ -645,12 +964,13 @@ impl MultiStateSolver for SimdIpmF64Solver {
             ipms.push(ipm)
         }
 
-        Ok(Box::new(Self {
+        Ok(Self {
             built: built_solvers,
             ipm: ipms,
-            tolerances: settings.tolerances(),
+            tolerances,
             max_iterations: settings.max_iterations(),
-        }))
+            refinement_steps: settings.refinement_steps().unwrap_or(refinement_steps),
+        })
     }
 
     fn solve(
@@ -662,15 +982,16 @@ impl MultiStateSolver for SimdIpmF64Solver {
         // TODO complete the timings
         let timings = SolverTimings::default();
 
-        // TODO this will miss off anything that doesn't divide in to 4
         states
-            .par_chunks_mut(4)
+            .par_chunks_mut(T::LANES)
             .zip(&mut self.built)
             .zip(&mut self.ipm)
             .for_each(|((chunk_states, built), ipm)| {
                 let mut timings = SolverTimings::default();
 
-                built.update(network, timestep, chunk_states, &mut timings).unwrap();
+                built
+                    .update(network, timestep, chunk_states, ipm, &mut timings)
+                    .unwrap();
 
                 let now = Instant::now();
 
@@ -679,6 +1000,7 @@ impl MultiStateSolver for SimdIpmF64Solver {
                     built.col_obj_coef(),
                     &self.tolerances,
                     self.max_iterations,
+                    self.refinement_steps,
                 );
 
                 timings.solve = now.elapsed();
@@ -694,17 +1016,189 @@ impl MultiStateSolver for SimdIpmF64Solver {
                     let col = built.col_for_edge(&edge.index());
                     let flows = solution[col];
 
-                    for (state, flow) in chunk_states.iter_mut().zip(flows.as_array()) {
+                    for (lane, state) in chunk_states.iter_mut().enumerate() {
+                        let flow = flows.extract(lane);
                         if !flow.is_finite() {
                             panic!("Non-finite flow encountered from solver. Edge: {edge:#?}, value: {flow}")
                         }
-                        state.get_mut_network_state().add_flow(edge, timestep, *flow).unwrap();
+                        state.get_mut_network_state().add_flow(edge, timestep, flow).unwrap();
                     }
                 }
 
+                // Update derived states (e.g. virtual storage volumes) now all the flows are known.
+                // The volumes are not finalised because the IPM solution is only approximate.
+                for state in chunk_states.iter_mut() {
+                    state
+                        .get_mut_network_state()
+                        .update_derived_states(network, timestep)
+                        .unwrap();
+                }
+
                 timings.save_solution += start_save_solution.elapsed();
             });
 
         Ok(timings)
     }
 }
+
+const SIMD_IPM_FEATURES: &[SolverFeatures] = &[
+    SolverFeatures::AggregatedNode,
+    SolverFeatures::AggregatedNodeFactors,
+    SolverFeatures::AggregatedNodeDynamicFactors,
+    SolverFeatures::VirtualStorage,
+];
+
+/// A SIMD IPM solver that solves four scenarios at a time in double precision.
+pub struct SimdIpmF64Solver {
+    ipm: SimdIpm<f64x4>,
+}
+
+impl MultiStateSolver for SimdIpmF64Solver {
+    type Settings = SimdIpmSolverSettings;
+
+    fn name() -> &'static str {
+        "ipm-simd"
+    }
+
+    fn features() -> &'static [SolverFeatures] {
+        SIMD_IPM_FEATURES
+    }
+
+    fn setup(
+        network: &Network,
+        num_scenarios: usize,
+        settings: &Self::Settings,
+    ) -> Result<Box<Self>, SolverSetupError> {
+        if let Some(lanes) = settings.lanes().filter(|&l| l != f64x4::LANES) {
+            return Err(SolverSetupError::UnsupportedSimdLanes { lanes });
+        }
+
+        // Double precision is accurate enough without refinement.
+        let ipm = SimdIpm::setup(network, num_scenarios, settings, settings.tolerances(), 0)?;
+        Ok(Box::new(Self { ipm }))
+    }
+
+    fn solve(
+        &mut self,
+        network: &Network,
+        timestep: &Timestep,
+        states: &mut [State],
+    ) -> Result<SolverTimings, SolverSolveError> {
+        self.ipm.solve(network, timestep, states)
+    }
+}
+
+/// The default number of refinement steps of the single precision solver.
+const F32_REFINEMENT_STEPS: usize = 2;
+/// The tightest tolerance the single precision solver can reliably converge to.
+const F32_MIN_TOLERANCE: f64 = 1e-3;
+
+/// The default number of `f32` lanes.
+///
+/// The width of the vectors is chosen at runtime from the features of the CPU (16 lanes with
+/// AVX-512, 8 with AVX and 4 otherwise). The instructions used for the vectors are fixed when
+/// compiling, so the wider vectors are only faster if the crate is built with the matching target
+/// features (e.g. with `RUSTFLAGS="-C target-cpu=native"`).
+fn default_f32_lanes() -> usize {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if is_x86_feature_detected!("avx512f") {
+            return f32x16::LANES;
+        } else if is_x86_feature_detected!("avx") {
+            return f32x8::LANES;
+        }
+    }
+    f32x4::LANES
+}
+
+/// Loosen `tolerance` to [`F32_MIN_TOLERANCE`], warning if it is tighter.
+fn f32_tolerance(name: &str, tolerance: f64) -> f64 {
+    if tolerance < F32_MIN_TOLERANCE {
+        tracing::warn!(
+            "The {name} tolerance of {tolerance:e} is too tight for the single precision IPM solver; \
+            using {F32_MIN_TOLERANCE:e} instead."
+        );
+        F32_MIN_TOLERANCE
+    } else {
+        tolerance
+    }
+}
+
+enum SimdIpmF32 {
+    X4(SimdIpm<f32x4>),
+    X8(SimdIpm<f32x8>),
+    X16(SimdIpm<f32x16>),
+}
+
+/// A SIMD IPM solver that solves scenarios in single precision.
+///
+/// Single precision doubles the number of scenarios solved together for a given vector width.
+/// The number of lanes (4, 8 or 16) is chosen at runtime from the features of the CPU (AVX-512,
+/// AVX or neither), unless given in the settings. Iterative refinement of the normal
+/// equations is used to recover the accuracy lost in the factorisation. Tolerances tighter than
+/// `1e-3` cannot be reliably reached in single precision, and are loosened to this value with a
+/// warning.
+pub struct SimdIpmF32Solver {
+    ipm: SimdIpmF32,
+}
+
+impl SimdIpmF32Solver {
+    /// The number of scenarios solved together.
+    pub fn lanes(&self) -> usize {
+        match &self.ipm {
+            SimdIpmF32::X4(_) => f32x4::LANES,
+            SimdIpmF32::X8(_) => f32x8::LANES,
+            SimdIpmF32::X16(_) => f32x16::LANES,
+        }
+    }
+}
+
+impl MultiStateSolver for SimdIpmF32Solver {
+    type Settings = SimdIpmSolverSettings;
+
+    fn name() -> &'static str {
+        "ipm-simd-f32"
+    }
+
+    fn features() -> &'static [SolverFeatures] {
+        SIMD_IPM_FEATURES
+    }
+
+    fn setup(
+        network: &Network,
+        num_scenarios: usize,
+        settings: &Self::Settings,
+    ) -> Result<Box<Self>, SolverSetupError> {
+        let lanes = settings.lanes().unwrap_or(default_f32_lanes());
+
+        let tolerances = settings.tolerances();
+        let tolerances = Tolerances {
+            primal_feasibility: f32_tolerance("primal feasibility", tolerances.primal_feasibility),
+            dual_feasibility: f32_tolerance("dual feasibility", tolerances.dual_feasibility),
+            optimality: f32_tolerance("optimality", tolerances.optimality),
+        };
+        let steps = F32_REFINEMENT_STEPS;
+
+        let ipm = match lanes {
+            4 => SimdIpmF32::X4(SimdIpm::setup(network, num_scenarios, settings, tolerances, steps)?),
+            8 => SimdIpmF32::X8(SimdIpm::setup(network, num_scenarios, settings, tolerances, steps)?),
+            16 => SimdIpmF32::X16(SimdIpm::setup(network, num_scenarios, settings, tolerances, steps)?),
+            _ => return Err(SolverSetupError::UnsupportedSimdLanes { lanes }),
+        };
+
+        Ok(Box::new(Self { ipm }))
+    }
+
+    fn solve(
+        &mut self,
+        network: &Network,
+        timestep: &Timestep,
+        states: &mut [State],
+    ) -> Result<SolverTimings, SolverSolveError> {
+        match &mut self.ipm {
+            SimdIpmF32::X4(ipm) => ipm.solve(network, timestep, states),
+            SimdIpmF32::X8(ipm) => ipm.solve(network, timestep, states),
+            SimdIpmF32::X16(ipm) => ipm.solve(network, timestep, states),
+        }
+    }
+}
@@ -3,9 +3,8 @@ use ipm_simd::Tolerances;
 #[cfg(feature = "pyo3")]
 use pyo3::{Bound, PyResult, exceptions::PyRuntimeError, prelude::PyAnyMethods, types::PyDict};
 use std::num::NonZeroUsize;
-use wide::f64x4;
 
-/// Settings for the OpenCL IPM solvers.
+/// Settings for the SIMD IPM solvers.
 ///
 /// Create new settings using [`SimdIpmSolverSettingsBuilder`] or use the default implementation;
 #[derive(PartialEq, Debug)]
@@ -14,6 +13,8 @@ pub struct SimdIpmSolverSettings {
     threads: usize,
     tolerances: Tolerances,
     max_iterations: NonZeroUsize,
+    refinement_steps: Option<usize>,
+    lanes: Option<usize>,
     ignore_feature_requirements: bool,
 }
 
@@ -51,6 +52,16 @@ impl SimdIpmSolverSettings {
     pub fn max_iterations(&self) -> NonZeroUsize {
         self.max_iterations
     }
+
+    /// The number of iterative refinement steps, or `None` to use the solver's default.
+    pub fn refinement_steps(&self) -> Option<usize> {
+        self.refinement_steps
+    }
+
+    /// The number of SIMD lanes, or `None` to choose based on the features of the CPU.
+    pub fn lanes(&self) -> Option<usize> {
+        self.lanes
+    }
 }
 
 /// Builder for [`SimdIpmSolverSettings`].
@@ -78,6 +89,8 @@ pub struct SimdIpmSolverSettingsBuilder {
     threads: usize,
     tolerances: Tolerances,
     max_iterations: NonZeroUsize,
+    refinement_steps: Option<usize>,
+    lanes: Option<usize>,
     ignore_feature_requirements: bool,
 }
 
@@ -89,6 +102,8 @@ impl Default for SimdIpmSolverSettingsBuilder {
             tolerances: Tolerances::default(),
             // Unwrap is safe as the value is non-zero!
             max_iterations: NonZeroUsize::new(200).unwrap(),
+            refinement_steps: None,
+            lanes: None,
             ignore_feature_requirements: false,
         }
     }
@@ -106,17 +121,17 @@ impl SimdIpmSolverSettingsBuilder {
     }
 
     pub fn primal_feasibility(mut self, tolerance: f64) -> Self {
-        self.tolerances.primal_feasibility = f64x4::splat(tolerance);
+        self.tolerances.primal_feasibility = tolerance;
         self
     }
 
     pub fn dual_feasibility(mut self, tolerance: f64) -> Self {
-        self.tolerances.dual_feasibility = f64x4::splat(tolerance);
+        self.tolerances.dual_feasibility = tolerance;
         self
     }
 
     pub fn optimality(mut self, tolerance: f64) -> Self {
-        self.tolerances.optimality = f64x4::splat(tolerance);
+        self.tolerances.optimality = tolerance;
         self
     }
 
@@ -125,6 +140,20 @@ impl SimdIpmSolverSettingsBuilder {
         self
     }
 
+    /// Set the number of steps of iterative refinement used for each solution of the normal
+    /// equations.
+    pub fn refinement_steps(mut self, steps: usize) -> Self {
+        self.refinement_steps = Some(steps);
+        self
+    }
+
+    /// Set the number of SIMD lanes (i.e. scenarios solved together). This must be supported
+    /// by the solver; the single precision solver supports 4, 8 or 16 lanes.
+    pub fn lanes(mut self, lanes: usize) -> Self {
+        self.lanes = Some(lanes);
+        self
+    }
+
     pub fn ignore_feature_requirements(mut self) -> Self {
         self.ignore_feature_requirements = true;
         self
@@ -137,6 +166,8 @@ impl SimdIpmSolverSettingsBuilder {
             threads: self.threads,
             tolerances: self.tolerances,
             max_iterations: self.max_iterations,
+            refinement_steps: self.refinement_steps,
+            lanes: self.lanes,
             ignore_feature_requirements: self.ignore_feature_requirements,
         }
     }
@@ -161,6 +192,18 @@ pub fn build_ipm_simd_settings_py(kwargs: Option<&Bound<'_, PyDict>>) -> PyResul
             kwargs.del_item("parallel")?;
         }
 
+        if let Ok(steps) = kwargs.get_item("refinement_steps") {
+            builder = builder.refinement_steps(steps.extract::<usize>()?);
+
+            kwargs.del_item("refinement_steps")?;
+        }
+
+        if let Ok(lanes) = kwargs.get_item("lanes") {
+            builder = builder.lanes(lanes.extract::<usize>()?);
+
+            kwargs.del_item("lanes")?;
+        }
+
         if let Ok(ignore) = kwargs.get_item("ignore_feature_requirements") {
             if ignore.extract::<bool>()? {
                 builder = builder.ignore_feature_requirements();
@@ -192,6 +235,8 @@ mod tests {
             threads: 0,
             tolerances: Tolerances::default(),
             max_iterations: NonZeroUsize::new(200).unwrap(),
+            refinement_steps: None,
+            lanes: None,
             ignore_feature_requirements: false,
         };
         let settings_from_builder = SimdIpmSolverSettingsBuilder::default().parallel().build();
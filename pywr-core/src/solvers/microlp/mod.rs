@@ -115,7 +115,7 @@ impl Solver for MicroLpSolver {
         state: &mut State,
     ) -> Result<SolverTimings, SolverSolveError> {
         let mut timings = SolverTimings::default();
-        self.builder.update(model, timestep, state, &mut timings)?;
+        self.builder.update(model, timestep, state, &[], &mut timings)?;
 
         self.builder.apply_updated_coefficients();
 
@@ -5,7 +5,13 @@ use std::ops::{Add, AddAssign};
 use std::time::Duration;
 use thiserror::Error;
 
-#[cfg(any(feature = "cbc", feature = "clp", feature = "highs", feature = "microlp"))]
+#[cfg(any(
+    feature = "cbc",
+    feature = "clp",
+    feature = "highs",
+    feature = "microlp",
+    feature = "simplex"
+))]
 mod builder;
 
 #[cfg(feature = "cbc")]
@@ -13,6 +19,7 @@ mod cbc;
 
 #[cfg(feature = "clp")]
 mod clp;
+mod fallback;
 
 #[cfg(any(
     feature = "cbc",
@@ -20,7 +27,8 @@ mod clp;
     feature = "highs",
     feature = "ipm-ocl",
     feature = "ipm-simd",
-    feature = "microlp"
+    feature = "microlp",
+    feature = "simplex"
 ))]
 mod col_edge_map;
 #[cfg(feature = "highs")]
@@ -31,13 +39,17 @@ mod ipm_ocl;
 mod ipm_simd;
 #[cfg(feature = "microlp")]
 mod microlp;
+mod options;
+mod profile;
+#[cfg(feature = "simplex")]
+mod simplex;
 
 #[cfg(feature = "ipm-ocl")]
 pub use self::ipm_ocl::{ClIpmF32Solver, ClIpmF64Solver, ClIpmSolverSettings, ClIpmSolverSettingsBuilder};
 #[cfg(all(feature = "ipm-simd", feature = "pyo3"))]
 pub use self::ipm_simd::build_ipm_simd_settings_py;
 #[cfg(feature = "ipm-simd")]
-pub use self::ipm_simd::{SimdIpmF64Solver, SimdIpmSolverSettings, SimdIpmSolverSettingsBuilder};
+pub use self::ipm_simd::{SimdIpmF32Solver, SimdIpmF64Solver, SimdIpmSolverSettings, SimdIpmSolverSettingsBuilder};
 use crate::aggregated_node::AggregatedNodeIndex;
 use crate::node::NodeIndex;
 #[cfg(all(feature = "cbc", feature = "pyo3"))]
@@ -48,12 +60,28 @@ pub use cbc::{CbcError, CbcSolver, CbcSolverSettings, CbcSolverSettingsBuilder};
 pub use clp::build_clp_settings_py;
 #[cfg(feature = "clp")]
 pub use clp::{ClpSolveStatusError, ClpSolver, ClpSolverSettings, ClpSolverSettingsBuilder};
+pub use fallback::{FallbackChain, FallbackChainSettings, FallbackChainStep, FallbackSolver, FallbackSolverSettings};
 #[cfg(all(feature = "highs", feature = "pyo3"))]
 pub use highs::build_highs_settings_py;
 #[cfg(feature = "highs")]
 pub use highs::{HighsSolver, HighsSolverSettings, HighsSolverSettingsBuilder};
 #[cfg(feature = "microlp")]
 pub use microlp::{MicroLpError, MicroLpSolver, MicroLpSolverSettings, MicroLpSolverSettingsBuilder};
+pub use options::{ParseSimplexStrategyError, SimplexStrategy, SolverOptionValue, SolverOptions};
+pub use profile::{SolveProfile, SolveProfileError, SolveRecord};
+#[cfg(all(feature = "simplex", feature = "pyo3"))]
+pub use simplex::build_simplex_settings_py;
+#[cfg(feature = "simplex")]
+pub use simplex::{SimplexSolver, SimplexSolverSettings, SimplexSolverSettingsBuilder};
+
+/// A forecast of the state at a future time-step.
+///
+/// Forecasts are passed to solvers with a look-ahead horizon; see [`Solver::horizon`].
+#[derive(Debug, Clone)]
+pub struct Forecast {
+    pub timestep: Timestep,
+    pub state: State,
+}
 
 #[derive(Default, Debug, Clone)]
 pub struct SolverTimings {
@@ -61,6 +89,10 @@ pub struct SolverTimings {
     pub update_constraints: Duration,
     pub solve: Duration,
     pub save_solution: Duration,
+    /// The number of solves that were completed by a fallback solver.
+    pub fallback_solves: usize,
+    /// The number of iterations of the solver's algorithm, if the solver reports them.
+    pub iterations: usize,
 }
 
 impl SolverTimings {
@@ -78,6 +110,8 @@ impl Add<SolverTimings> for SolverTimings {
             update_constraints: self.update_constraints + rhs.update_constraints,
             solve: self.solve + rhs.solve,
             save_solution: self.save_solution + rhs.save_solution,
+            fallback_solves: self.fallback_solves + rhs.fallback_solves,
+            iterations: self.iterations + rhs.iterations,
         }
     }
 }
@@ -88,6 +122,8 @@ impl AddAssign for SolverTimings {
         self.update_constraints += rhs.update_constraints;
         self.solve += rhs.solve;
         self.save_solution += rhs.save_solution;
+        self.fallback_solves += rhs.fallback_solves;
+        self.iterations += rhs.iterations;
     }
 }
 
@@ -102,6 +138,7 @@ pub enum SolverFeatures {
     AggregatedNodeDynamicFactors,
     VirtualStorage,
     MutualExclusivity,
+    SemiContinuous,
 }
 
 /// Solver settings that are common to all solvers.
@@ -120,9 +157,32 @@ pub enum SolverSetupError {
     NoEdgesDefined,
     #[error("Node index not found: {0}")]
     NodeIndexNotFound(NodeIndex),
+    #[error("Aggregated node `{name}` and sub-name `{}` flow bounds error: {source}", .sub_name.as_deref().unwrap_or("None"))]
+    AggregatedNodeBoundsError {
+        name: String,
+        sub_name: Option<String>,
+        #[source]
+        source: crate::metric::ConstantMetricF64Error,
+    },
+    #[error("Edge cost error: {0}")]
+    EdgeError(#[from] crate::edge::EdgeError),
+    #[error("Node `{name}` and sub-name `{}` must have a constant semi-continuous `min_flow_if_on` with this solver", .sub_name.as_deref().unwrap_or("None"))]
+    NonConstantMinFlowIfOn { name: String, sub_name: Option<String> },
+    #[error("The fallback solver `{fallback}` does not have the features required by the network")]
+    MissingFallbackSolverFeatures { fallback: String },
+    #[error("Solver option `{name}` is not supported by this solver")]
+    UnsupportedSolverOption { name: String },
+    #[error("Invalid value `{value}` for solver option `{name}`")]
+    InvalidSolverOptionValue { name: String, value: String },
+    #[cfg(feature = "ipm-simd")]
+    #[error("{lanes} SIMD lanes are not supported by this solver")]
+    UnsupportedSimdLanes { lanes: usize },
     #[cfg(feature = "highs")]
     #[error("Highs error: {0}")]
     HighsError(#[from] highs::HighsStatusError),
+    #[cfg(feature = "simplex")]
+    #[error("Simplex error: {0}")]
+    SimplexError(#[from] sparse_simplex::SimplexError),
 }
 
 /// Errors that can occur during solver solve.
@@ -151,6 +211,13 @@ pub enum SolverSolveError {
         #[source]
         source: crate::aggregated_node::AggregatedNodeError,
     },
+    #[error("Aggregated node `{name}` and sub-name `{}` factor error: {source}", .sub_name.as_deref().unwrap_or("None"))]
+    AggregatedNodeFactorError {
+        name: String,
+        sub_name: Option<String>,
+        #[source]
+        source: crate::aggregated_node::FactorError,
+    },
     #[error("Virtual storage error: {0}")]
     VirtualStorageError(#[from] crate::virtual_storage::VirtualStorageError),
     #[error("Node index not found: {0}")]
@@ -175,6 +242,9 @@ pub enum SolverSolveError {
     #[cfg(feature = "microlp")]
     #[error("MicroLP error: {0}")]
     MicroLpError(#[from] MicroLpError),
+    #[cfg(feature = "simplex")]
+    #[error("Simplex error: {0}")]
+    SimplexError(#[from] sparse_simplex::SimplexError),
 }
 
 pub trait Solver: Send {
@@ -194,6 +264,28 @@ pub trait Solver: Send {
         timestep: &Timestep,
         state: &mut State,
     ) -> Result<SolverTimings, SolverSolveError>;
+
+    /// The number of time-steps, including the current time-step, that the solver optimises over.
+    ///
+    /// If this is greater than one the network computes forecasts of the state for the following
+    /// time-steps and solves with [`Solver::solve_with_forecasts`].
+    fn horizon(&self) -> usize {
+        1
+    }
+
+    /// Solve the current time-step using forecasts of the state for the following time-steps.
+    ///
+    /// Only the solution of the current time-step is applied to `state`. The default
+    /// implementation ignores the forecasts.
+    fn solve_with_forecasts(
+        &mut self,
+        model: &Network,
+        timestep: &Timestep,
+        state: &mut State,
+        _forecasts: &[Forecast],
+    ) -> Result<SolverTimings, SolverSolveError> {
+        self.solve(model, timestep, state)
+    }
 }
 
 pub trait MultiStateSolver: Send {
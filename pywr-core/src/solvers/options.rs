@@ -0,0 +1,250 @@
+#[cfg(feature = "pyo3")]
+use pyo3::{
+    Bound, PyResult,
+    exceptions::PyValueError,
+    prelude::PyAnyMethods,
+    types::{PyDict, PyDictMethods},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use thiserror::Error;
+
+/// The simplex algorithm a solver should use.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SimplexStrategy {
+    /// Let the solver choose the algorithm.
+    #[default]
+    Auto,
+    Primal,
+    Dual,
+}
+
+#[derive(Debug, Error)]
+#[error("Unknown simplex strategy `{0}`. Expected one of: auto, primal, dual.")]
+pub struct ParseSimplexStrategyError(String);
+
+impl FromStr for SimplexStrategy {
+    type Err = ParseSimplexStrategyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "primal" => Ok(Self::Primal),
+            "dual" => Ok(Self::Dual),
+            _ => Err(ParseSimplexStrategyError(s.to_string())),
+        }
+    }
+}
+
+/// The value of a solver specific option.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum SolverOptionValue {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+}
+
+impl Display for SolverOptionValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Bool(v) => write!(f, "{v}"),
+            Self::Int(v) => write!(f, "{v}"),
+            Self::Float(v) => write!(f, "{v}"),
+            Self::String(v) => write!(f, "{v}"),
+        }
+    }
+}
+
+impl FromStr for SolverOptionValue {
+    type Err = std::convert::Infallible;
+
+    /// Parse a value from a string, trying a boolean, integer and float before falling
+    /// back to a string.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(v) = s.parse::<bool>() {
+            Ok(Self::Bool(v))
+        } else if let Ok(v) = s.parse::<i64>() {
+            Ok(Self::Int(v))
+        } else if let Ok(v) = s.parse::<f64>() {
+            Ok(Self::Float(v))
+        } else {
+            Ok(Self::String(s.to_string()))
+        }
+    }
+}
+
+impl SolverOptionValue {
+    /// Return the value as a float if it is numeric.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Int(v) => Some(*v as f64),
+            Self::Float(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// Return the value as an integer if it is an integer.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Self::Int(v) => Some(*v),
+            _ => None,
+        }
+    }
+}
+
+/// Tuning options that are common to the LP solvers.
+///
+/// Any option that is `None` uses the solver's default. Options that are specific to a
+/// solver can be given by name in `solver_options`; these are passed to the solver after
+/// the common options and therefore take precedence.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct SolverOptions {
+    /// Primal feasibility tolerance.
+    pub primal_tolerance: Option<f64>,
+    /// Dual feasibility tolerance.
+    pub dual_tolerance: Option<f64>,
+    /// Whether to presolve the LP.
+    pub presolve: Option<bool>,
+    /// The simplex algorithm to use.
+    pub simplex_strategy: Option<SimplexStrategy>,
+    /// Time limit, in seconds, for each solve.
+    pub time_limit: Option<f64>,
+    /// Iteration limit for each solve.
+    pub iteration_limit: Option<usize>,
+    /// Named options passed directly to the solver.
+    #[serde(default)]
+    pub solver_options: BTreeMap<String, SolverOptionValue>,
+}
+
+impl SolverOptions {
+    /// Returns `true` if no options are set.
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Update these options with any options that are set in `other`.
+    pub fn merge(&mut self, other: SolverOptions) {
+        self.primal_tolerance = other.primal_tolerance.or(self.primal_tolerance);
+        self.dual_tolerance = other.dual_tolerance.or(self.dual_tolerance);
+        self.presolve = other.presolve.or(self.presolve);
+        self.simplex_strategy = other.simplex_strategy.or(self.simplex_strategy);
+        self.time_limit = other.time_limit.or(self.time_limit);
+        self.iteration_limit = other.iteration_limit.or(self.iteration_limit);
+        self.solver_options.extend(other.solver_options);
+    }
+
+    /// Extract the options from Python keyword arguments.
+    ///
+    /// The options are removed from `kwargs` so that any remaining keyword arguments can be
+    /// reported as unknown.
+    #[cfg(feature = "pyo3")]
+    pub(crate) fn extract_py_kwargs(kwargs: &Bound<'_, PyDict>) -> PyResult<Self> {
+        let mut options = Self::default();
+
+        if let Some(value) = kwargs.get_item("primal_tolerance")? {
+            options.primal_tolerance = Some(value.extract::<f64>()?);
+            kwargs.del_item("primal_tolerance")?;
+        }
+
+        if let Some(value) = kwargs.get_item("dual_tolerance")? {
+            options.dual_tolerance = Some(value.extract::<f64>()?);
+            kwargs.del_item("dual_tolerance")?;
+        }
+
+        if let Some(value) = kwargs.get_item("presolve")? {
+            options.presolve = Some(value.extract::<bool>()?);
+            kwargs.del_item("presolve")?;
+        }
+
+        if let Some(value) = kwargs.get_item("simplex_strategy")? {
+            let strategy = value
+                .extract::<String>()?
+                .parse::<SimplexStrategy>()
+                .map_err(|e| PyValueError::new_err(e.to_string()))?;
+            options.simplex_strategy = Some(strategy);
+            kwargs.del_item("simplex_strategy")?;
+        }
+
+        if let Some(value) = kwargs.get_item("time_limit")? {
+            options.time_limit = Some(value.extract::<f64>()?);
+            kwargs.del_item("time_limit")?;
+        }
+
+        if let Some(value) = kwargs.get_item("iteration_limit")? {
+            options.iteration_limit = Some(value.extract::<usize>()?);
+            kwargs.del_item("iteration_limit")?;
+        }
+
+        if let Some(value) = kwargs.get_item("solver_options")? {
+            for (name, value) in value.cast::<PyDict>()?.iter() {
+                let value = if let Ok(v) = value.extract::<bool>() {
+                    SolverOptionValue::Bool(v)
+                } else if let Ok(v) = value.extract::<i64>() {
+                    SolverOptionValue::Int(v)
+                } else if let Ok(v) = value.extract::<f64>() {
+                    SolverOptionValue::Float(v)
+                } else {
+                    SolverOptionValue::String(value.extract::<String>()?)
+                };
+                options.solver_options.insert(name.extract::<String>()?, value);
+            }
+            kwargs.del_item("solver_options")?;
+        }
+
+        Ok(options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SimplexStrategy, SolverOptionValue, SolverOptions};
+
+    #[test]
+    fn test_option_value_from_str() {
+        assert_eq!("true".parse::<SolverOptionValue>(), Ok(SolverOptionValue::Bool(true)));
+        assert_eq!("10".parse::<SolverOptionValue>(), Ok(SolverOptionValue::Int(10)));
+        assert_eq!("1e-7".parse::<SolverOptionValue>(), Ok(SolverOptionValue::Float(1e-7)));
+        assert_eq!(
+            "ipm".parse::<SolverOptionValue>(),
+            Ok(SolverOptionValue::String("ipm".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut options = SolverOptions {
+            primal_tolerance: Some(1e-7),
+            presolve: Some(true),
+            solver_options: [
+                ("log_level".to_string(), SolverOptionValue::Int(1)),
+                ("mode".to_string(), SolverOptionValue::String("a".to_string())),
+            ]
+            .into(),
+            ..Default::default()
+        };
+
+        let other = SolverOptions {
+            presolve: Some(false),
+            simplex_strategy: Some(SimplexStrategy::Dual),
+            solver_options: [("mode".to_string(), SolverOptionValue::String("b".to_string()))].into(),
+            ..Default::default()
+        };
+
+        options.merge(other);
+
+        assert_eq!(options.primal_tolerance, Some(1e-7));
+        assert_eq!(options.presolve, Some(false));
+        assert_eq!(options.simplex_strategy, Some(SimplexStrategy::Dual));
+        assert_eq!(options.solver_options["log_level"], SolverOptionValue::Int(1));
+        assert_eq!(
+            options.solver_options["mode"],
+            SolverOptionValue::String("b".to_string())
+        );
+    }
+}
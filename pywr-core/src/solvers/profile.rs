@@ -0,0 +1,232 @@
+use crate::scenario::ScenarioIndex;
+use crate::solvers::SolverTimings;
+use crate::timestep::Timestep;
+use chrono::NaiveDateTime;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use tracing::info;
+
+#[derive(Error, Debug)]
+pub enum SolveProfileError {
+    #[error("CSV error with file at `{path}`: {source}")]
+    CSVError {
+        path: PathBuf,
+        #[source]
+        source: ::csv::Error,
+    },
+}
+
+/// The timings of a single solve.
+#[derive(Debug, Clone)]
+pub struct SolveRecord {
+    pub timestep: Timestep,
+    /// The global simulation id of the scenario; see [`ScenarioIndex::global_simulation_id`].
+    pub simulation_id: usize,
+    pub timings: SolverTimings,
+}
+
+/// A row of the CSV profile. Durations are in seconds.
+#[derive(Serialize)]
+struct SolveProfileRow<'a> {
+    time_step: usize,
+    time: NaiveDateTime,
+    simulation_id: usize,
+    label: &'a str,
+    update_objective: f64,
+    update_constraints: f64,
+    solve: f64,
+    save_solution: f64,
+    total: f64,
+    iterations: usize,
+    fallback: bool,
+}
+
+/// A record of the timings of every solve in a model run.
+///
+/// Profiling is opt-in because it stores a record for each time-step of each scenario; see
+/// [`crate::models::ModelTimings::with_solve_profile`]. Only solvers that solve each scenario
+/// separately are profiled; the multi-state (IPM) solvers are not.
+#[derive(Debug, Clone)]
+pub struct SolveProfile {
+    records: Vec<SolveRecord>,
+    /// The label of each scenario by global simulation id.
+    labels: BTreeMap<usize, String>,
+    /// The number of the slowest solves to print at the end of the run.
+    num_slowest_solves: usize,
+}
+
+impl Default for SolveProfile {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_NUM_SLOWEST_SOLVES)
+    }
+}
+
+impl SolveProfile {
+    /// The default number of the slowest solves to print at the end of the run.
+    pub const DEFAULT_NUM_SLOWEST_SOLVES: usize = 10;
+
+    /// Create an empty profile that prints the `num_slowest_solves` slowest solves at the end of
+    /// the run.
+    pub fn new(num_slowest_solves: usize) -> Self {
+        Self {
+            records: Vec::new(),
+            labels: BTreeMap::new(),
+            num_slowest_solves,
+        }
+    }
+
+    /// Add the timings of the solve of `scenario_index` at `timestep`.
+    pub fn record(&mut self, timestep: &Timestep, scenario_index: &ScenarioIndex, timings: &SolverTimings) {
+        let simulation_id = scenario_index.global_simulation_id();
+        self.labels
+            .entry(simulation_id)
+            .or_insert_with(|| scenario_index.label());
+
+        self.records.push(SolveRecord {
+            timestep: *timestep,
+            simulation_id,
+            timings: timings.clone(),
+        });
+    }
+
+    pub fn records(&self) -> &[SolveRecord] {
+        &self.records
+    }
+
+    /// The label of the scenario with the given global simulation id.
+    pub fn label(&self, simulation_id: usize) -> Option<&str> {
+        self.labels.get(&simulation_id).map(|l| l.as_str())
+    }
+
+    /// Returns the `n` slowest solves, slowest first.
+    pub fn slowest_solves(&self, n: usize) -> Vec<&SolveRecord> {
+        let mut records: Vec<_> = self.records.iter().collect();
+        if n < records.len() {
+            records.select_nth_unstable_by_key(n, |r| std::cmp::Reverse(r.timings.solve));
+            records.truncate(n);
+        }
+        records.sort_by_key(|r| std::cmp::Reverse(r.timings.solve));
+        records
+    }
+
+    /// Write the profile to a CSV file with one row per solve.
+    pub fn write_csv(&self, path: &Path) -> Result<(), SolveProfileError> {
+        let map_err = |source| SolveProfileError::CSVError {
+            path: path.to_path_buf(),
+            source,
+        };
+
+        let mut writer = csv::Writer::from_path(path).map_err(map_err)?;
+        for record in &self.records {
+            let row = SolveProfileRow {
+                time_step: record.timestep.index,
+                time: record.timestep.date,
+                simulation_id: record.simulation_id,
+                label: self.label(record.simulation_id).unwrap_or_default(),
+                update_objective: record.timings.update_objective.as_secs_f64(),
+                update_constraints: record.timings.update_constraints.as_secs_f64(),
+                solve: record.timings.solve.as_secs_f64(),
+                save_solution: record.timings.save_solution.as_secs_f64(),
+                total: record.timings.total().as_secs_f64(),
+                iterations: record.timings.iterations,
+                fallback: record.timings.fallback_solves > 0,
+            };
+            writer.serialize(row).map_err(map_err)?;
+        }
+        writer.flush().map_err(|source| map_err(source.into()))?;
+
+        Ok(())
+    }
+
+    /// Print the slowest solves to the log.
+    pub fn print_slowest_solves(&self) {
+        let slowest = self.slowest_solves(self.num_slowest_solves);
+        if slowest.is_empty() {
+            return;
+        }
+
+        info!("Slowest solves:");
+        info!(
+            "  {: <19} | {: <9} | {: <24} | {: <10}  | {: <10}",
+            "Time", "Time-step", "Scenario", "solve", "iterations"
+        );
+        for record in slowest {
+            info!(
+                "  {: <19} | {: <9} | {: <24} | {: <10.5}s | {: <10}",
+                record.timestep.date.format("%Y-%m-%d %H:%M:%S").to_string(),
+                record.timestep.index,
+                self.label(record.simulation_id).unwrap_or_default(),
+                record.timings.solve.as_secs_f64(),
+                record.timings.iterations,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SolveProfile;
+    use crate::scenario::ScenarioIndexBuilder;
+    use crate::solvers::SolverTimings;
+    use crate::timestep::{PywrDuration, Timestep};
+    use chrono::{NaiveDate, TimeDelta};
+    use std::time::Duration;
+
+    #[test]
+    fn test_slowest_solves() {
+        let start = NaiveDate::from_ymd_opt(2020, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        let scenarios: Vec<_> = (0..2)
+            .map(|i| ScenarioIndexBuilder::new(i, vec![i], vec![format!("s{i}")]).build())
+            .collect();
+
+        let mut profile = SolveProfile::default();
+        for t in 0..5 {
+            let timestep = Timestep::new(start, t, PywrDuration::from(TimeDelta::days(1)));
+            for scenario_index in &scenarios {
+                let timings = SolverTimings {
+                    solve: Duration::from_millis((t * 10 + scenario_index.simulation_id()) as u64),
+                    iterations: t,
+                    ..Default::default()
+                };
+                profile.record(&timestep, scenario_index, &timings);
+            }
+        }
+
+        assert_eq!(profile.records().len(), 10);
+        let slowest = profile.slowest_solves(3);
+        let slowest: Vec<_> = slowest
+            .iter()
+            .map(|r| (r.timestep.index, profile.label(r.simulation_id).unwrap()))
+            .collect();
+        assert_eq!(slowest, vec![(4, "s1"), (4, "s0"), (3, "s1")]);
+        assert_eq!(profile.slowest_solves(20).len(), 10);
+    }
+
+    #[test]
+    #[cfg(feature = "clp")]
+    fn test_model_solve_profile() {
+        use crate::models::ModelTimings;
+        use crate::solvers::{ClpSolver, ClpSolverSettings};
+        use crate::test_utils::simple_model;
+
+        let model = simple_model(2, None);
+        let timings =
+            ModelTimings::new_with_component_timings(model.network()).with_solve_profile(SolveProfile::default());
+        let result = model
+            .run_with_timings::<ClpSolver>(&ClpSolverSettings::default(), timings)
+            .unwrap();
+
+        // One solve for each of the 15 time-steps in each of the 2 scenarios.
+        let profile = result.timings.solve_profile().unwrap();
+        assert_eq!(profile.records().len(), 30);
+        assert!(profile.label(1).is_some());
+
+        let result = model.run::<ClpSolver>(&ClpSolverSettings::default()).unwrap();
+        assert!(result.timings.solve_profile().is_none());
+    }
+}
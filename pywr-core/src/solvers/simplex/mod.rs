@@ -0,0 +1,225 @@
+mod settings;
+
+use super::builder::{ColType, SolverBuilder};
+use crate::network::Network;
+use crate::solvers::builder::BuiltSolver;
+use crate::solvers::{
+    Forecast, SimplexStrategy, Solver, SolverFeatures, SolverOptions, SolverSetupError, SolverSolveError, SolverTimings,
+};
+use crate::state::{ConstParameterValues, State};
+use crate::timestep::Timestep;
+#[cfg(feature = "pyo3")]
+pub use settings::build_simplex_settings_py;
+pub use settings::{SimplexSolverSettings, SimplexSolverSettingsBuilder};
+use sparse_simplex::{Problem, Settings, Strategy};
+use std::time::Instant;
+
+/// A solver using the pure Rust simplex implementation in the `sparse-simplex` crate.
+///
+/// The basis is kept between time-steps so that each solve is warm started. Mutual exclusivity
+/// and semi-continuous flows are solved with branch and bound.
+pub struct SimplexSolver {
+    builder: BuiltSolver<usize>,
+    problem: Problem,
+}
+
+/// Convert the tuning options to the settings of the simplex solver.
+///
+/// Named solver options are mapped to the corresponding setting; the supported names are
+/// `refactor_frequency` and `node_limit`. There is no presolve, so that option is ignored.
+fn simplex_settings(options: &SolverOptions) -> Result<Settings, SolverSetupError> {
+    let mut settings = Settings::default();
+
+    if let Some(tolerance) = options.primal_tolerance {
+        settings.primal_tolerance = tolerance;
+    }
+    if let Some(tolerance) = options.dual_tolerance {
+        settings.dual_tolerance = tolerance;
+    }
+    settings.iteration_limit = options.iteration_limit;
+    settings.time_limit = options.time_limit;
+    if let Some(strategy) = options.simplex_strategy {
+        settings.strategy = match strategy {
+            SimplexStrategy::Primal => Strategy::Primal,
+            SimplexStrategy::Dual | SimplexStrategy::Auto => Strategy::Dual,
+        };
+    }
+
+    for (name, value) in options.solver_options.iter() {
+        let int_value = || {
+            value
+                .as_i64()
+                .and_then(|v| usize::try_from(v).ok())
+                .filter(|v| *v > 0)
+                .ok_or_else(|| SolverSetupError::InvalidSolverOptionValue {
+                    name: name.clone(),
+                    value: value.to_string(),
+                })
+        };
+
+        match name.as_str() {
+            "refactor_frequency" => settings.refactor_frequency = int_value()?,
+            "node_limit" => settings.node_limit = int_value()?,
+            _ => return Err(SolverSetupError::UnsupportedSolverOption { name: name.clone() }),
+        }
+    }
+
+    Ok(settings)
+}
+
+impl SimplexSolver {
+    fn from_builder(builder: BuiltSolver<usize>, options: &SolverOptions) -> Result<Self, SolverSetupError> {
+        let mut problem = Problem::new(builder.num_cols());
+        problem.set_settings(simplex_settings(options)?);
+
+        problem.set_col_bounds(builder.col_lower(), builder.col_upper());
+        problem.set_objective(builder.col_obj_coef());
+        for (col, col_type) in builder.col_type().iter().enumerate() {
+            if let ColType::Integer = col_type {
+                problem.set_integer(col, true);
+            }
+        }
+
+        problem.add_rows(
+            builder.row_lower(),
+            builder.row_upper(),
+            builder.row_starts(),
+            builder.columns(),
+            builder.elements(),
+        )?;
+
+        Ok(SimplexSolver { builder, problem })
+    }
+}
+
+impl Solver for SimplexSolver {
+    type Settings = SimplexSolverSettings;
+
+    fn name() -> &'static str {
+        "simplex"
+    }
+
+    fn features() -> &'static [SolverFeatures] {
+        &[
+            SolverFeatures::AggregatedNode,
+            SolverFeatures::AggregatedNodeFactors,
+            SolverFeatures::AggregatedNodeDynamicFactors,
+            SolverFeatures::VirtualStorage,
+            SolverFeatures::MutualExclusivity,
+            SolverFeatures::SemiContinuous,
+        ]
+    }
+
+    fn setup(
+        model: &Network,
+        values: &ConstParameterValues,
+        settings: &Self::Settings,
+    ) -> Result<Box<Self>, SolverSetupError> {
+        let builder = SolverBuilder::new(f64::INFINITY, f64::NEG_INFINITY).with_horizon(settings.horizon());
+        let built = builder.create(model, values)?;
+
+        let solver = SimplexSolver::from_builder(built, settings.options())?;
+        Ok(Box::new(solver))
+    }
+
+    fn solve(
+        &mut self,
+        model: &Network,
+        timestep: &Timestep,
+        state: &mut State,
+    ) -> Result<SolverTimings, SolverSolveError> {
+        self.solve_with_forecasts(model, timestep, state, &[])
+    }
+
+    fn horizon(&self) -> usize {
+        self.builder.horizon()
+    }
+
+    fn solve_with_forecasts(
+        &mut self,
+        model: &Network,
+        timestep: &Timestep,
+        state: &mut State,
+        forecasts: &[Forecast],
+    ) -> Result<SolverTimings, SolverSolveError> {
+        let mut timings = SolverTimings::default();
+        self.builder.update(model, timestep, state, forecasts, &mut timings)?;
+
+        let now = Instant::now();
+        // The constant objective coefficients were set when the model was created
+        for &col in self.builder.dynamic_obj_cols() {
+            self.problem
+                .set_objective_coefficient(col, self.builder.col_obj_coef()[col]);
+        }
+        timings.update_objective += now.elapsed();
+
+        let now = Instant::now();
+        let num_variable_rows = self.builder.num_variable_rows();
+        self.problem.set_row_bounds(
+            &self.builder.row_lower()[..num_variable_rows],
+            &self.builder.row_upper()[..num_variable_rows],
+        );
+
+        for &(row, column, coefficient) in self.builder.coefficients_to_update() {
+            self.problem.set_coefficient(row, column, coefficient)?;
+        }
+        timings.update_constraints += now.elapsed();
+
+        let now = Instant::now();
+        let iterations = self.problem.iterations();
+        self.problem.solve()?;
+        let solution = self.problem.solution();
+        timings.solve = now.elapsed();
+        timings.iterations = self.problem.iterations() - iterations;
+
+        // Create the updated network state from the results
+        let network_state = state.get_mut_network_state();
+        network_state.reset();
+
+        let start_save_solution = Instant::now();
+        for edge in model.edges().iter() {
+            let col = self.builder.col_for_edge(&edge.index());
+            let flow = solution[col];
+            network_state.add_flow(edge, timestep, flow)?;
+        }
+        state.complete(model, timestep)?;
+        timings.save_solution += start_save_solution.elapsed();
+
+        Ok(timings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::simplex_settings;
+    use crate::solvers::{SimplexStrategy, SolverOptionValue, SolverOptions};
+    use sparse_simplex::Strategy;
+
+    #[test]
+    fn test_simplex_settings() {
+        let options = SolverOptions {
+            primal_tolerance: Some(1e-9),
+            iteration_limit: Some(100),
+            simplex_strategy: Some(SimplexStrategy::Primal),
+            solver_options: [("refactor_frequency".to_string(), SolverOptionValue::Int(50))].into(),
+            ..Default::default()
+        };
+        let settings = simplex_settings(&options).unwrap();
+        assert_eq!(settings.primal_tolerance, 1e-9);
+        assert_eq!(settings.iteration_limit, Some(100));
+        assert_eq!(settings.strategy, Strategy::Primal);
+        assert_eq!(settings.refactor_frequency, 50);
+
+        let options = SolverOptions {
+            solver_options: [("refactor_frequency".to_string(), SolverOptionValue::Int(0))].into(),
+            ..Default::default()
+        };
+        assert!(simplex_settings(&options).is_err());
+
+        let options = SolverOptions {
+            solver_options: [("unknown".to_string(), SolverOptionValue::Int(0))].into(),
+            ..Default::default()
+        };
+        assert!(simplex_settings(&options).is_err());
+    }
+}
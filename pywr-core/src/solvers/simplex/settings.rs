@@ -0,0 +1,214 @@
+use crate::solvers::{SimplexStrategy, SolverOptionValue, SolverOptions, SolverSettings};
+#[cfg(feature = "pyo3")]
+use pyo3::{Bound, PyResult, exceptions::PyRuntimeError, prelude::PyAnyMethods, types::PyDict};
+
+/// Settings for the pure Rust simplex solver.
+///
+/// Create new settings using [`SimplexSolverSettingsBuilder`] or use the default implementation;
+#[derive(PartialEq, Debug, Clone)]
+pub struct SimplexSolverSettings {
+    parallel: bool,
+    threads: usize,
+    ignore_feature_requirements: bool,
+    options: SolverOptions,
+    horizon: usize,
+}
+
+// Default implementation is a convenience that defers to the builder.
+impl Default for SimplexSolverSettings {
+    fn default() -> Self {
+        SimplexSolverSettingsBuilder::default().build()
+    }
+}
+
+impl SolverSettings for SimplexSolverSettings {
+    fn parallel(&self) -> bool {
+        self.parallel
+    }
+
+    fn threads(&self) -> usize {
+        self.threads
+    }
+
+    fn ignore_feature_requirements(&self) -> bool {
+        self.ignore_feature_requirements
+    }
+}
+
+impl SimplexSolverSettings {
+    /// Create a new builder for the settings
+    pub fn builder() -> SimplexSolverSettingsBuilder {
+        SimplexSolverSettingsBuilder::default()
+    }
+
+    /// The tuning options to apply to the solver.
+    pub fn options(&self) -> &SolverOptions {
+        &self.options
+    }
+
+    /// The number of time-steps, including the current time-step, in the look-ahead horizon.
+    pub fn horizon(&self) -> usize {
+        self.horizon
+    }
+}
+
+/// Builder for [`SimplexSolverSettings`].
+///
+/// # Examples
+///
+/// ```
+/// use std::num::NonZeroUsize;
+/// use pywr_core::solvers::SimplexSolverSettingsBuilder;
+/// // Settings with parallel enabled and 4 threads.
+/// let settings = SimplexSolverSettingsBuilder::default().parallel().threads(4).build();
+///
+/// let mut builder = SimplexSolverSettingsBuilder::default();
+///
+/// builder = builder.parallel();
+/// let settings = builder.build();
+///
+/// ```
+#[derive(Default)]
+pub struct SimplexSolverSettingsBuilder {
+    parallel: bool,
+    threads: usize,
+    ignore_feature_requirements: bool,
+    options: SolverOptions,
+    horizon: usize,
+}
+
+impl SimplexSolverSettingsBuilder {
+    pub fn parallel(mut self) -> Self {
+        self.parallel = true;
+        self
+    }
+
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    pub fn ignore_feature_requirements(mut self) -> Self {
+        self.ignore_feature_requirements = true;
+        self
+    }
+
+    /// Set all of the tuning options, replacing any set previously.
+    pub fn options(mut self, options: SolverOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn primal_tolerance(mut self, tolerance: f64) -> Self {
+        self.options.primal_tolerance = Some(tolerance);
+        self
+    }
+
+    pub fn dual_tolerance(mut self, tolerance: f64) -> Self {
+        self.options.dual_tolerance = Some(tolerance);
+        self
+    }
+
+    pub fn presolve(mut self, presolve: bool) -> Self {
+        self.options.presolve = Some(presolve);
+        self
+    }
+
+    pub fn simplex_strategy(mut self, strategy: SimplexStrategy) -> Self {
+        self.options.simplex_strategy = Some(strategy);
+        self
+    }
+
+    /// Set the time limit, in seconds, for each solve.
+    pub fn time_limit(mut self, seconds: f64) -> Self {
+        self.options.time_limit = Some(seconds);
+        self
+    }
+
+    pub fn iteration_limit(mut self, iterations: usize) -> Self {
+        self.options.iteration_limit = Some(iterations);
+        self
+    }
+
+    /// Optimise over a rolling horizon of `horizon` time-steps, including the current time-step.
+    ///
+    /// Forecasts of the network's parameters are used for the future time-steps, and only the
+    /// flows of the current time-step are applied. The forecasts continue from the current state
+    /// of any stateful parameters, but use the storage volumes at the start of the current
+    /// time-step. A horizon of one (the default) solves each time-step independently.
+    pub fn horizon(mut self, horizon: usize) -> Self {
+        self.horizon = horizon;
+        self
+    }
+
+    /// Set a named option that is passed directly to the solver.
+    pub fn solver_option(mut self, name: &str, value: SolverOptionValue) -> Self {
+        self.options.solver_options.insert(name.to_string(), value);
+        self
+    }
+
+    /// Construct a [`SimplexSolverSettings`] from the builder.
+    pub fn build(self) -> SimplexSolverSettings {
+        SimplexSolverSettings {
+            parallel: self.parallel,
+            threads: self.threads,
+            ignore_feature_requirements: self.ignore_feature_requirements,
+            options: self.options,
+            horizon: self.horizon.max(1),
+        }
+    }
+}
+
+#[cfg(feature = "pyo3")]
+/// Build simplex solver settings from Python kwargs.
+pub fn build_simplex_settings_py(kwargs: Option<&Bound<'_, PyDict>>) -> PyResult<SimplexSolverSettings> {
+    let mut builder = SimplexSolverSettingsBuilder::default();
+
+    if let Some(kwargs) = kwargs {
+        if let Ok(threads) = kwargs.get_item("threads") {
+            builder = builder.threads(threads.extract::<usize>()?);
+            kwargs.del_item("threads")?;
+        }
+
+        if let Ok(parallel) = kwargs.get_item("parallel") {
+            if parallel.extract::<bool>()? {
+                builder = builder.parallel();
+            }
+            kwargs.del_item("parallel")?;
+        }
+
+        if let Ok(horizon) = kwargs.get_item("horizon") {
+            builder = builder.horizon(horizon.extract::<usize>()?);
+            kwargs.del_item("horizon")?;
+        }
+
+        builder = builder.options(SolverOptions::extract_py_kwargs(kwargs)?);
+
+        if !kwargs.is_empty()? {
+            return Err(PyRuntimeError::new_err(format!(
+                "Unknown keyword arguments: {kwargs:?}",
+            )));
+        }
+    }
+
+    Ok(builder.build())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SimplexSolverSettings, SimplexSolverSettingsBuilder, SolverOptions};
+
+    #[test]
+    fn builder_test() {
+        let _settings = SimplexSolverSettings {
+            parallel: true,
+            threads: 0,
+            ignore_feature_requirements: false,
+            options: SolverOptions::default(),
+            horizon: 1,
+        };
+        let settings_from_builder = SimplexSolverSettingsBuilder::default().parallel().build();
+
+        assert_eq!(settings_from_builder, settings_from_builder);
+    }
+}
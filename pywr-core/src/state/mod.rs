@@ -1,6 +1,7 @@
 mod flow;
 mod storage;
 
+use crate::constituent::ConstituentIndex;
 use crate::edge::{Edge, EdgeIndex};
 use crate::metric::SimpleMetricF64Error;
 use crate::models::MultiNetworkTransferIndex;
@@ -188,6 +189,22 @@ impl EdgeState {
     }
 }
 
+/// The concentration of a constituent at each node in the network.
+#[derive(Clone, Debug, Default)]
+pub struct ConstituentState {
+    concentrations: Vec<f64>,
+}
+
+impl ConstituentState {
+    pub fn new(concentrations: Vec<f64>) -> Self {
+        Self { concentrations }
+    }
+
+    pub fn concentrations(&self) -> &[f64] {
+        &self.concentrations
+    }
+}
+
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct MultiValue {
     values: HashMap<String, f64>,
@@ -397,6 +414,8 @@ pub enum NetworkStateError {
     VirtualStorageIndexNotFound(VirtualStorageIndex),
     #[error("Node has no volume: {0}")]
     NodeHasNoVolume(NodeIndex),
+    #[error("Constituent index not found: {0}")]
+    ConstituentIndexNotFound(ConstituentIndex),
 }
 
 // State of the nodes and edges
@@ -405,6 +424,7 @@ pub struct NetworkState {
     node_states: Vec<NodeState>,
     edge_states: Vec<EdgeState>,
     virtual_storage_states: Vec<VirtualStorageState>,
+    constituent_states: Vec<ConstituentState>,
 }
 
 impl NetworkState {
@@ -412,11 +432,13 @@ impl NetworkState {
         initial_node_states: Vec<NodeState>,
         num_edges: usize,
         initial_virtual_storage_states: Vec<VirtualStorageState>,
+        initial_constituent_states: Vec<ConstituentState>,
     ) -> Self {
         Self {
             node_states: initial_node_states,
             edge_states: (0..num_edges).map(|_| EdgeState::default()).collect(),
             virtual_storage_states: initial_virtual_storage_states,
+            constituent_states: initial_constituent_states,
         }
     }
 
@@ -465,7 +487,11 @@ impl NetworkState {
     ///
     /// This final step ensures that derived states (e.g. virtual storage volume) are updated
     /// once all the flows have been updated.
-    fn update_derived_states(&mut self, model: &Network, timestep: &Timestep) -> Result<(), NetworkStateError> {
+    pub(crate) fn update_derived_states(
+        &mut self,
+        model: &Network,
+        timestep: &Timestep,
+    ) -> Result<(), NetworkStateError> {
         // Update virtual storage node states
         for (state, node) in self
             .virtual_storage_states
@@ -592,6 +618,43 @@ impl NetworkState {
         }
     }
 
+    pub fn get_constituent_state(&self, idx: &ConstituentIndex) -> Option<&ConstituentState> {
+        self.constituent_states.get(*idx.deref())
+    }
+
+    /// Get the concentration of a constituent at a node.
+    pub fn get_node_concentration(
+        &self,
+        node_index: &NodeIndex,
+        idx: &ConstituentIndex,
+    ) -> Result<f64, NetworkStateError> {
+        let state = self
+            .constituent_states
+            .get(*idx.deref())
+            .ok_or(NetworkStateError::ConstituentIndexNotFound(*idx))?;
+
+        state
+            .concentrations
+            .get(*node_index.deref())
+            .copied()
+            .ok_or(NetworkStateError::NodeIndexNotFound(*node_index))
+    }
+
+    /// Replace the concentrations of a constituent at every node.
+    pub fn set_constituent_concentrations(
+        &mut self,
+        idx: &ConstituentIndex,
+        concentrations: Vec<f64>,
+    ) -> Result<(), NetworkStateError> {
+        match self.constituent_states.get_mut(*idx.deref()) {
+            Some(s) => {
+                s.concentrations = concentrations;
+                Ok(())
+            }
+            None => Err(NetworkStateError::ConstituentIndexNotFound(*idx)),
+        }
+    }
+
     pub fn set_volume(
         &mut self,
         node_index: &NodeIndex,
@@ -1316,11 +1379,13 @@ impl State {
 ///
 /// This builder is used to create a new state with the desired initial values. The builder
 /// allows for the creation of a state with a specific number of nodes and edges, and optionally
-/// with initial virtual storage, parameter, derived metric, and inter-network transfer states.
+/// with initial virtual storage, constituent, parameter, derived metric, and inter-network
+/// transfer states.
 pub struct StateBuilder {
     initial_node_states: Vec<NodeState>,
     num_edges: usize,
     initial_virtual_storage_states: Option<Vec<VirtualStorageState>>,
+    initial_constituent_states: Option<Vec<ConstituentState>>,
     num_parameters: Option<ParameterCollectionSize>,
     num_derived_metrics: Option<usize>,
     num_inter_network_values: Option<usize>,
@@ -1338,6 +1403,7 @@ impl StateBuilder {
             initial_node_states,
             num_edges,
             initial_virtual_storage_states: None,
+            initial_constituent_states: None,
             num_parameters: None,
             num_derived_metrics: None,
             num_inter_network_values: None,
@@ -1350,6 +1416,12 @@ impl StateBuilder {
         self
     }
 
+    /// Add initial constituent states to the builder.
+    pub fn with_constituent_states(mut self, initial_constituent_states: Vec<ConstituentState>) -> Self {
+        self.initial_constituent_states = Some(initial_constituent_states);
+        self
+    }
+
     /// Add the number of value parameters to the builder.
     pub fn with_parameters(mut self, collection: &ParameterCollection) -> Self {
         self.num_parameters = Some(collection.size());
@@ -1394,6 +1466,7 @@ impl StateBuilder {
                 self.initial_node_states,
                 self.num_edges,
                 self.initial_virtual_storage_states.unwrap_or_default(),
+                self.initial_constituent_states.unwrap_or_default(),
             ),
             parameters_constant: constant,
             parameters_before: parameters.clone(),
@@ -5,7 +5,9 @@ use crate::models::{Model, ModelDomain};
 /// TODO move this to its own local crate ("test-utilities") as part of a workspace.
 use crate::network::{Network, NetworkError};
 use crate::node::StorageInitialVolume;
-use crate::parameters::{AggregatedParameter, Array2Parameter, ConstantParameter, GeneralParameter};
+use crate::parameters::{
+    AggregatedParameter, Array2Parameter, ConstantParameter, GeneralParameter, MonthlyProfileParameter,
+};
 use crate::recorders::{AssertionF64Recorder, AssertionU64Recorder};
 use crate::scenario::{ScenarioDomainBuilder, ScenarioGroupBuilder};
 #[cfg(feature = "cbc")]
@@ -20,7 +22,13 @@ use crate::solvers::HighsSolver;
 use crate::solvers::MultiStateSolver;
 #[cfg(feature = "ipm-simd")]
 use crate::solvers::SimdIpmF64Solver;
-#[cfg(any(feature = "cbc", feature = "clp", feature = "highs", feature = "microlp"))]
+#[cfg(any(
+    feature = "cbc",
+    feature = "clp",
+    feature = "highs",
+    feature = "microlp",
+    feature = "simplex"
+))]
 use crate::solvers::Solver;
 #[cfg(any(
     feature = "cbc",
@@ -28,7 +36,8 @@ use crate::solvers::Solver;
     feature = "highs",
     feature = "ipm-ocl",
     feature = "ipm-simd",
-    feature = "microlp"
+    feature = "microlp",
+    feature = "simplex"
 ))]
 use crate::solvers::SolverSettings;
 use crate::timestep::{TimeDomain, TimestepDuration, Timestepper};
@@ -393,7 +402,8 @@ impl VerifyExpected for ExpectedOutputsWide {
     feature = "highs",
     feature = "ipm-ocl",
     feature = "ipm-simd",
-    feature = "microlp"
+    feature = "microlp",
+    feature = "simplex"
 ))]
 pub fn run_all_solvers(
     model: &Model,
@@ -437,6 +447,17 @@ pub fn run_all_solvers(
         }
     }
 
+    #[cfg(feature = "simplex")]
+    {
+        if !solvers_to_skip.contains(&"simplex") {
+            check_features_and_run::<crate::solvers::SimplexSolver>(
+                model,
+                !solvers_without_features.contains(&"simplex"),
+                expected_outputs,
+            );
+        }
+    }
+
     #[cfg(feature = "ipm-simd")]
     {
         if !solvers_to_skip.contains(&"ipm-simd") {
@@ -466,7 +487,8 @@ pub fn run_all_solvers(
     feature = "highs",
     feature = "ipm-ocl",
     feature = "ipm-simd",
-    feature = "microlp"
+    feature = "microlp",
+    feature = "simplex"
 )))]
 pub fn run_all_solvers(
     _model: &Model,
@@ -478,7 +500,13 @@ pub fn run_all_solvers(
 }
 
 /// Check features and
-#[cfg(any(feature = "cbc", feature = "clp", feature = "highs", feature = "microlp"))]
+#[cfg(any(
+    feature = "cbc",
+    feature = "clp",
+    feature = "highs",
+    feature = "microlp",
+    feature = "simplex"
+))]
 fn check_features_and_run<S>(model: &Model, expect_features: bool, expected_outputs: &[Box<dyn VerifyExpected>])
 where
     S: Solver,
@@ -536,6 +564,21 @@ where
     }
 }
 
+/// Create a metric for a constant `value`.
+///
+/// If `dynamic` is true the value is returned by a (non-constant) profile parameter so that the
+/// solvers update it every time-step.
+fn make_value_metric(network: &mut Network, name: &str, value: f64, dynamic: bool) -> Result<MetricF64, NetworkError> {
+    if dynamic {
+        let profile = MonthlyProfileParameter::new(name.into(), [value; 12], None);
+        Ok(network
+            .add_simple_parameter(Box::new(profile))?
+            .into_metric_f64_before())
+    } else {
+        Ok(value.into())
+    }
+}
+
 /// Make a simple system with random inputs.
 fn make_simple_system<R: Rng + ?Sized>(
     network: &mut Network,
@@ -543,6 +586,7 @@ fn make_simple_system<R: Rng + ?Sized>(
     num_timesteps: usize,
     num_inflow_scenarios: usize,
     inflow_scenario_group_index: usize,
+    dynamic: bool,
     rng: &mut R,
 ) -> Result<(), NetworkError> {
     let input_idx = network.add_input_node("input", Some(suffix))?;
@@ -570,15 +614,18 @@ fn make_simple_system<R: Rng + ?Sized>(
     network.set_node_max_flow("input", Some(suffix), Some(idx.into_metric_f64_before()))?;
 
     let input_cost = rng.random_range(-20.0..-5.00);
-    network.set_node_cost("input", Some(suffix), Some(input_cost.into()))?;
+    let input_cost = make_value_metric(network, &format!("input-cost-{suffix}"), input_cost, dynamic)?;
+    network.set_node_cost("input", Some(suffix), Some(input_cost))?;
 
     let outflow_distr = Normal::new(8.0, 3.0).unwrap();
     let mut outflow: f64 = outflow_distr.sample(rng);
     outflow = outflow.max(0.0);
 
-    network.set_node_max_flow("output", Some(suffix), Some(outflow.into()))?;
+    let outflow = make_value_metric(network, &format!("outflow-{suffix}"), outflow, dynamic)?;
+    network.set_node_max_flow("output", Some(suffix), Some(outflow))?;
 
-    network.set_node_cost("output", Some(suffix), Some((-500.0).into()))?;
+    let output_cost = make_value_metric(network, &format!("output-cost-{suffix}"), -500.0, dynamic)?;
+    network.set_node_cost("output", Some(suffix), Some(output_cost))?;
 
     Ok(())
 }
@@ -590,6 +637,7 @@ fn make_simple_connections<R: Rng>(
     model: &mut Network,
     num_systems: usize,
     density: usize,
+    dynamic: bool,
     rng: &mut R,
 ) -> Result<(), NetworkError> {
     let num_connections = (num_systems.pow(2) * density / 100 / 2).max(1);
@@ -608,7 +656,8 @@ fn make_simple_connections<R: Rng>(
 
         if let Ok(idx) = model.add_link_node("transfer", Some(&name)) {
             let transfer_cost = rng.random_range(0.0..1.0);
-            model.set_node_cost("transfer", Some(&name), Some(transfer_cost.into()))?;
+            let transfer_cost = make_value_metric(model, &format!("transfer-cost-{name}"), transfer_cost, dynamic)?;
+            model.set_node_cost("transfer", Some(&name), Some(transfer_cost))?;
 
             let from_suffix = format!("sys-{i:04}");
             let from_idx = model
@@ -634,6 +683,28 @@ pub fn make_random_model<R: Rng>(
     density: usize,
     num_scenarios: usize,
     rng: &mut R,
+) -> Result<Model, NetworkError> {
+    build_random_model(num_systems, density, num_scenarios, false, rng)
+}
+
+/// Make the same model as [`make_random_model`], but with the constant costs and maximum flows
+/// given by non-constant parameters. The solvers must therefore update every edge cost each
+/// time-step, which provides a baseline for the incremental updates of constant values.
+pub fn make_random_model_with_dynamic_values<R: Rng>(
+    num_systems: usize,
+    density: usize,
+    num_scenarios: usize,
+    rng: &mut R,
+) -> Result<Model, NetworkError> {
+    build_random_model(num_systems, density, num_scenarios, true, rng)
+}
+
+fn build_random_model<R: Rng>(
+    num_systems: usize,
+    density: usize,
+    num_scenarios: usize,
+    dynamic: bool,
+    rng: &mut R,
 ) -> Result<Model, NetworkError> {
     let start = NaiveDate::from_ymd_opt(2020, 1, 1)
         .unwrap()
@@ -672,11 +743,12 @@ pub fn make_random_model<R: Rng>(
             num_timesteps,
             num_inflow_scenarios,
             inflow_scenario_group_index,
+            dynamic,
             rng,
         )?;
     }
 
-    make_simple_connections(&mut network, num_systems, density, rng)?;
+    make_simple_connections(&mut network, num_systems, density, dynamic, rng)?;
 
     let model = Model::new(domain, network);
 
@@ -686,7 +758,7 @@ pub fn make_random_model<R: Rng>(
 #[cfg(all(test, feature = "ipm-simd"))]
 mod tests {
     use super::make_random_model;
-    use crate::solvers::{SimdIpmF64Solver, SimdIpmSolverSettings};
+    use crate::solvers::{SimdIpmF32Solver, SimdIpmF64Solver, SimdIpmSolverSettings};
     use rand::SeedableRng;
     use rand_chacha::ChaCha8Rng;
 
@@ -706,6 +778,24 @@ mod tests {
             .run_multi_scenario::<SimdIpmF64Solver>(&settings)
             .expect("Failed to run model!");
     }
+
+    #[test]
+    fn test_random_model_f32() {
+        // The number of scenarios does not divide in to 8 or 16 lanes
+        let n_sc = 12;
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let model = make_random_model(50, 5, n_sc, &mut rng).unwrap();
+
+        for lanes in [4, 8, 16] {
+            let settings = SimdIpmSolverSettings::builder().lanes(lanes).build();
+            model
+                .run_multi_scenario::<SimdIpmF32Solver>(&settings)
+                .expect("Failed to run model!");
+        }
+
+        let settings = SimdIpmSolverSettings::builder().lanes(5).build();
+        assert!(model.run_multi_scenario::<SimdIpmF32Solver>(&settings).is_err());
+    }
 }
 
 /// Compare two arrays of f64
@@ -21,3 +21,18 @@ pub fn hydropower_calculation(
 ) -> f64 {
     flow * (energy_unit_conversion * density * 9.81 * head * efficiency * flow_unit_conversion)
 }
+
+/// Calculate the energy required to pump a flow against a head.
+///
+/// This is the inverse of [`hydropower_calculation`] in that the efficiency increases, rather
+/// than decreases, the energy required for a given flow.
+pub fn pumping_energy_calculation(
+    flow: f64,
+    head: f64,
+    efficiency: f64,
+    flow_unit_conversion: f64,
+    energy_unit_conversion: f64,
+    density: f64,
+) -> f64 {
+    flow * (energy_unit_conversion * density * 9.81 * head * flow_unit_conversion) / efficiency
+}
@@ -385,7 +385,7 @@ mod tests {
     use crate::models::Model;
     use crate::network::Network;
     use crate::node::{CostAggFunc, StorageInitialVolume};
-    use crate::parameters::ControlCurveInterpolatedParameter;
+    use crate::parameters::{ControlCurveInterpolatedParameter, InterpolationKind};
     use crate::recorders::{AssertionF64Recorder, AssertionFnRecorder};
     use crate::scenario::ScenarioIndex;
     use crate::test_utils::{default_timestepper, run_all_solvers, simple_model};
@@ -441,9 +441,9 @@ mod tests {
         assert_eq!(months_since_last_reset(&current, &last_reset), 0);
     }
 
-    /// Test the virtual storage constraints
-    #[test]
-    fn test_basic_virtual_storage() {
+    /// A model with a virtual storage that limits the flow through two links. The volume and
+    /// flows are asserted to within `epsilon`.
+    fn basic_virtual_storage_model(epsilon: Option<f64>) -> Model {
         let mut network = Network::default();
 
         let input_node = network.add_input_node("input", None).unwrap();
@@ -485,7 +485,7 @@ mod tests {
             MetricF64::VirtualStorageVolume(vs_idx),
             expected_vol,
             None,
-            None,
+            epsilon,
         );
         network.add_recorder(Box::new(recorder)).unwrap();
         // Set-up assertion for "link" node
@@ -493,7 +493,7 @@ mod tests {
         let expected = |ts: &Timestep, _si: &ScenarioIndex| {
             if ts.index < 3 { 10.0 } else { 0.0 }
         };
-        let recorder = AssertionFnRecorder::new("link-0-flow", MetricF64::NodeOutFlow(idx), expected, None, None);
+        let recorder = AssertionFnRecorder::new("link-0-flow", MetricF64::NodeOutFlow(idx), expected, None, epsilon);
         network.add_recorder(Box::new(recorder)).unwrap();
 
         // Set-up assertion for "input" node
@@ -501,13 +501,29 @@ mod tests {
         let expected = |ts: &Timestep, _si: &ScenarioIndex| {
             if ts.index < 4 { 10.0 } else { 0.0 }
         };
-        let recorder = AssertionFnRecorder::new("link-1-flow", MetricF64::NodeOutFlow(idx), expected, None, None);
+        let recorder = AssertionFnRecorder::new("link-1-flow", MetricF64::NodeOutFlow(idx), expected, None, epsilon);
         network.add_recorder(Box::new(recorder)).unwrap();
 
         let domain = default_timestepper().try_into().unwrap();
-        let model = Model::new(domain, network);
-        // Test all solvers
-        run_all_solvers(&model, &["ipm-ocl", "ipm-simd"], &[], &[]);
+        Model::new(domain, network)
+    }
+
+    /// Test the virtual storage constraints
+    #[test]
+    fn test_basic_virtual_storage() {
+        let model = basic_virtual_storage_model(None);
+        // Test all solvers; the IPM's solution is only accurate to its tolerance and is tested below.
+        run_all_solvers(&model, &["ipm-ocl"], &["ipm-simd"], &[]);
+    }
+
+    /// Test the virtual storage constraints with the SIMD IPM to within its tolerance.
+    #[cfg(feature = "ipm-simd")]
+    #[test]
+    fn test_basic_virtual_storage_ipm_simd() {
+        let model = basic_virtual_storage_model(Some(1e-6));
+        model
+            .run_multi_scenario::<crate::solvers::SimdIpmF64Solver>(&Default::default())
+            .unwrap();
     }
 
     #[test]
@@ -540,7 +556,7 @@ mod tests {
         network.add_recorder(Box::new(recorder)).unwrap();
 
         // Test all solvers
-        run_all_solvers(&model, &["ipm-ocl", "ipm-simd"], &[], &[]);
+        run_all_solvers(&model, &["ipm-ocl"], &[], &[]);
     }
 
     #[test]
@@ -579,6 +595,7 @@ mod tests {
             MetricF64::VirtualStorageProportionalVolume(vs_idx),
             vec![],
             vec![0.0.into(), 20.0.into()],
+            InterpolationKind::Linear,
         );
 
         let cost_param = network.add_parameter(Box::new(cost_param)).unwrap();
@@ -611,8 +628,8 @@ mod tests {
         let recorder = AssertionFnRecorder::new("output-flow", MetricF64::NodeInFlow(idx), expected, None, None);
         network.add_recorder(Box::new(recorder)).unwrap();
 
-        // Test all solvers
-        run_all_solvers(&model, &["ipm-ocl", "ipm-simd"], &[], &[]);
+        // Test all solvers; the IPM's solution is only accurate to its tolerance.
+        run_all_solvers(&model, &["ipm-ocl"], &["ipm-simd"], &[]);
     }
 
     #[test]
@@ -651,8 +668,8 @@ mod tests {
         let recorder = AssertionFnRecorder::new("output-flow", MetricF64::NodeInFlow(idx), expected, None, None);
         network.add_recorder(Box::new(recorder)).unwrap();
 
-        // Test all solvers
-        run_all_solvers(&model, &["ipm-ocl", "ipm-simd"], &[], &[]);
+        // Test all solvers; the IPM's solution is only accurate to its tolerance.
+        run_all_solvers(&model, &["ipm-ocl"], &["ipm-simd"], &[]);
     }
 
     #[test]
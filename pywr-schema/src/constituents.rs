@@ -0,0 +1,190 @@
+//! Conservative water quality constituents transported with the flows of a network.
+//!
+//! See [`pywr_core::constituent`] for details of the transport calculation.
+#[cfg(feature = "core")]
+use crate::error::SchemaError;
+use crate::metric::Metric;
+#[cfg(feature = "core")]
+use crate::network::LoadArgs;
+#[cfg(feature = "core")]
+use pywr_core::{constituent::ConstituentIndex, metric::MetricF64, node::NodeIndex};
+use pywr_schema_macros::{PywrVisitAll, skip_serializing_none};
+use schemars::JsonSchema;
+use strum_macros::{Display, EnumIter};
+
+/// The concentration of the flow leaving an input node (e.g. a catchment).
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, JsonSchema, PywrVisitAll, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct InputConcentration {
+    /// The name of the node.
+    pub node: String,
+    pub concentration: Metric,
+}
+
+/// The concentration of the initial volume of a storage node.
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, JsonSchema, PywrVisitAll, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct InitialConcentration {
+    /// The name of the node.
+    pub node: String,
+    pub concentration: f64,
+}
+
+/// A conservative water quality constituent (e.g. salinity or nitrate).
+///
+/// The constituent is carried with the flows of the network after each time-step. Flow from an
+/// input node has the concentration given in `input_concentrations`, or zero if the node is not
+/// listed. Flows blend at nodes with multiple inflows, and fully mix with the stored volume in
+/// storage nodes, which start with the concentration given in `initial_concentrations` or zero.
+/// The concentrations can be used elsewhere in the model using a [`Metric::Constituent`].
+///
+/// # JSON Example
+///
+/// ```json
+#[doc = include_str!("doc_examples/constituent.json")]
+/// ```
+#[skip_serializing_none]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, JsonSchema, PywrVisitAll, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Constituent {
+    pub name: String,
+    pub input_concentrations: Option<Vec<InputConcentration>>,
+    pub initial_concentrations: Option<Vec<InitialConcentration>>,
+}
+
+#[cfg(feature = "core")]
+impl Constituent {
+    /// Add the constituent and its initial concentrations to the network.
+    ///
+    /// The input concentrations may refer to parameters, and are therefore set separately by
+    /// [`Self::set_input_concentrations`] once the parameters have been added.
+    pub fn add_to_model(
+        &self,
+        network: &mut pywr_core::network::Network,
+        args: &LoadArgs,
+    ) -> Result<ConstituentIndex, SchemaError> {
+        let mut builder = pywr_core::constituent::ConstituentBuilder::new(&self.name);
+
+        for initial in self.initial_concentrations.iter().flatten() {
+            let node = core_node_index(network, args, &initial.node)?;
+            builder = builder.initial_concentration(node, initial.concentration);
+        }
+
+        Ok(network.add_constituent(builder)?)
+    }
+
+    /// Set the input concentrations of a constituent that has already been added to the network.
+    pub fn set_input_concentrations(
+        &self,
+        network: &mut pywr_core::network::Network,
+        args: &LoadArgs,
+    ) -> Result<(), SchemaError> {
+        let index = network
+            .get_constituent_index_by_name(&self.name)
+            .ok_or_else(|| SchemaError::ConstituentNotFound(self.name.clone()))?;
+
+        for input in self.input_concentrations.iter().flatten() {
+            let node = core_node_index(network, args, &input.node)?;
+            let concentration = input.concentration.load(network, args, None)?;
+            network.set_constituent_input_concentration(index, node, concentration)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The attribute of a constituent at a node.
+#[derive(
+    serde::Deserialize, serde::Serialize, Debug, Clone, Copy, Default, Display, JsonSchema, PartialEq, EnumIter,
+)]
+pub enum ConstituentAttribute {
+    /// The concentration of the constituent.
+    #[default]
+    Concentration,
+    /// The mass of the constituent in the volume of a storage node, or the mass flux through any
+    /// other node.
+    Mass,
+}
+
+/// A reference to the concentration or mass of a constituent at a node.
+#[skip_serializing_none]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, JsonSchema, PartialEq)]
+#[serde(deny_unknown_fields)]
+#[cfg_attr(feature = "pyo3", pyo3::pyclass)]
+pub struct ConstituentReference {
+    /// The name of the constituent.
+    pub constituent: String,
+    /// The name of the node.
+    pub node: String,
+    /// The attribute of the constituent. If this is `None` then the concentration is used.
+    pub attribute: Option<ConstituentAttribute>,
+}
+
+#[cfg(feature = "core")]
+impl ConstituentReference {
+    pub fn load(&self, network: &mut pywr_core::network::Network, args: &LoadArgs) -> Result<MetricF64, SchemaError> {
+        let constituent = network
+            .get_constituent_index_by_name(&self.constituent)
+            .ok_or_else(|| SchemaError::ConstituentNotFound(self.constituent.clone()))?;
+        let node = core_node_index(network, args, &self.node)?;
+
+        let metric = match self.attribute.unwrap_or_default() {
+            ConstituentAttribute::Concentration => MetricF64::NodeConcentration { node, constituent },
+            ConstituentAttribute::Mass => MetricF64::NodeMass { node, constituent },
+        };
+
+        Ok(metric)
+    }
+
+    /// The name of the attribute, including the name of the constituent.
+    pub fn attribute(&self) -> String {
+        format!("{}:{}", self.constituent, self.attribute.unwrap_or_default())
+    }
+}
+
+/// Find the core node that carries the outflow of the schema node with the given name.
+///
+/// Only nodes with a single output connector are supported, as the concentration of nodes with
+/// several internal nodes would otherwise be ambiguous.
+#[cfg(feature = "core")]
+fn core_node_index(
+    network: &pywr_core::network::Network,
+    args: &LoadArgs,
+    name: &str,
+) -> Result<NodeIndex, SchemaError> {
+    let node = args
+        .schema
+        .get_node_by_name(name)
+        .ok_or_else(|| SchemaError::NodeNotFound { name: name.to_string() })?;
+
+    let connectors = node.output_connectors(None)?;
+    let [(core_name, sub_name)] = connectors.as_slice() else {
+        return Err(SchemaError::ConstituentNodeNotSupported { name: name.to_string() });
+    };
+
+    network
+        .get_node_index_by_name(core_name, sub_name.as_deref())
+        .ok_or_else(|| SchemaError::CoreNodeNotFound {
+            name: core_name.to_string(),
+            sub_name: sub_name.clone(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Constituent;
+    use std::fs::read_to_string;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_doc_example() {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("src/doc_examples/constituent.json");
+
+        let data = read_to_string(&path).unwrap_or_else(|e| panic!("Failed to read file: {path:?}: {e}"));
+        let constituent: Constituent =
+            serde_json::from_str(&data).unwrap_or_else(|e| panic!("Failed to deserialize {path:?}: {e}"));
+
+        assert_eq!(constituent.input_concentrations.as_ref().map(|c| c.len()), Some(2));
+    }
+}
@@ -85,6 +85,34 @@ pub enum SchemaError {
     InterNetworkTransferNotFound(String),
     #[error("Invalid rolling window definition on parameter {name}. Must convert to a positive integer.")]
     InvalidRollingWindow { name: String },
+    #[error("Invalid tariff band hours ({start_hour} to {end_hour}) on parameter {name}. Must be 0 to 24.")]
+    InvalidTariffBand {
+        name: String,
+        start_hour: u32,
+        end_hour: u32,
+    },
+    #[error("Invalid expression on parameter {name}: {source}")]
+    #[cfg(feature = "core")]
+    InvalidExpression {
+        name: String,
+        #[source]
+        source: pywr_core::parameters::ExpressionError,
+    },
+    #[error("Invalid rule condition on parameter {name}: {msg}")]
+    InvalidRuleCondition { name: String, msg: String },
+    #[error("Invalid hedging rule on parameter {name}: {msg}")]
+    InvalidHedgingRule { name: String, msg: String },
+    #[error("Invalid evaluation day {day} on parameter {name}. Must be 1 to 366.")]
+    InvalidEvaluationDay { name: String, day: u32 },
+    #[error("Parameter `{0}` is not a rule set parameter.")]
+    RuleSetParameterExpected(String),
+    #[error("Invalid sample distribution on parameter {name}: {source}")]
+    #[cfg(feature = "core")]
+    InvalidSampleDistribution {
+        name: String,
+        #[source]
+        source: pywr_core::parameters::SampleDistributionError,
+    },
     #[error("Failed to load parameter {name}: {error}")]
     LoadParameter { name: String, error: String },
     #[error("Timeseries error: {0}")]
@@ -130,6 +158,21 @@ pub enum SchemaError {
         scenarios: usize,
         group: String,
     },
+    #[error("Constituent `{0}` not found in the network.")]
+    ConstituentNotFound(String),
+    #[error("Node `{name}` has more than one output connector and cannot be used with a constituent.")]
+    ConstituentNodeNotSupported { name: String },
+    #[error("Member {member} of the forecast for parameter '{name}' was not found.")]
+    ForecastMemberNotFound { name: String, member: i64 },
+    #[error(
+        "Number of forecast members ({members}) for parameter '{name}' is less than the size ({scenarios}) of the specified scenario group '{group}'."
+    )]
+    ForecastMembersMismatch {
+        members: usize,
+        name: String,
+        scenarios: usize,
+        group: String,
+    },
 }
 
 #[cfg(all(feature = "core", feature = "pyo3"))]
@@ -1,4 +1,5 @@
 use crate::ConversionError;
+use crate::constituents::ConstituentReference;
 use crate::data_tables::TableDataRef;
 use crate::edge::Edge;
 use crate::error::ComponentConversionError;
@@ -66,6 +67,8 @@ pub enum Metric {
     LocalParameter(ParameterReference),
     /// A reference to an inter-network transfer by name.
     InterNetworkTransfer { name: String },
+    /// The concentration or mass of a constituent at a node.
+    Constituent(ConstituentReference),
 }
 
 impl Default for Metric {
@@ -148,6 +151,7 @@ impl Metric {
                 }
             }
             Self::Edge(edge_ref) => edge_ref.load(network, args),
+            Self::Constituent(constituent_ref) => constituent_ref.load(network, args),
         }
     }
 
@@ -162,6 +166,7 @@ impl Metric {
             Self::Timeseries(ts_ref) => Ok(ts_ref.name.clone()),
             Self::InterNetworkTransfer { name } => Ok(name.clone()),
             Self::Edge(edge_ref) => Ok(edge_ref.edge.to_string()),
+            Self::Constituent(constituent_ref) => Ok(constituent_ref.node.clone()),
         }
     }
 
@@ -179,6 +184,7 @@ impl Metric {
                 .unwrap_or_else(|| "value".to_string()),
             Self::InterNetworkTransfer { .. } => "value".to_string(),
             Self::Edge { .. } => "Flow".to_string(),
+            Self::Constituent(constituent_ref) => constituent_ref.attribute(),
         };
 
         Ok(attribute)
@@ -198,6 +204,7 @@ impl Metric {
             Self::Timeseries(_) => None,
             Self::InterNetworkTransfer { .. } => None,
             Self::Edge { .. } => None,
+            Self::Constituent(_) => None,
         };
 
         Ok(sub_type)
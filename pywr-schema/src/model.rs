@@ -306,6 +306,18 @@ impl From<ScenarioLabelOrIndex> for pywr_core::scenario::ScenarioLabelOrIndex {
     }
 }
 
+/// A shard of the scenarios to simulate.
+///
+/// The scenarios of the domain are split into `count` contiguous shards of (nearly) equal size,
+/// and only the shard at (zero based) `index` is simulated. This allows a model's scenarios to be
+/// run across several processes, with the outputs of each shard merged afterwards.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ScenarioShard {
+    pub index: usize,
+    pub count: usize,
+}
+
 /// A scenario domain is a collection of scenario groups that define the possible scenarios that
 /// can be run in a model.
 ///
@@ -322,6 +334,9 @@ impl From<ScenarioLabelOrIndex> for pywr_core::scenario::ScenarioLabelOrIndex {
 ///
 /// It is an error if both a `slice`(s) and `combinations` are defined.
 ///
+/// Finally, a `shard` can be defined to simulate only part of the scenarios that remain after
+/// any subsets or combinations are applied.
+///
 /// # JSON Examples
 ///
 /// The examples below show how a scenario group can be defined in JSON.
@@ -349,6 +364,9 @@ pub struct ScenarioDomain {
     pub groups: Vec<ScenarioGroup>,
     /// Optional combinations of the groups that allow simulation of specific scenarios.
     pub combinations: Option<Vec<Vec<ScenarioLabelOrIndex>>>,
+    /// Optional shard of the scenarios to simulate.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shard: Option<ScenarioShard>,
 }
 
 impl TryFrom<Vec<pywr_v1_schema::model::Scenario>> for ScenarioDomain {
@@ -359,6 +377,7 @@ impl TryFrom<Vec<pywr_v1_schema::model::Scenario>> for ScenarioDomain {
         Ok(Self {
             groups,
             combinations: None,
+            shard: None,
         })
     }
 }
@@ -378,6 +397,10 @@ impl TryInto<pywr_core::scenario::ScenarioDomainBuilder> for ScenarioDomain {
             builder = builder.with_combinations(combinations.into_iter().collect());
         }
 
+        if let Some(shard) = self.shard {
+            builder = builder.with_shard(pywr_core::scenario::ScenarioShard::new(shard.index, shard.count)?);
+        }
+
         Ok(builder)
     }
 }
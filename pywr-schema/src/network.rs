@@ -2,6 +2,7 @@ use super::edge::Edge;
 use super::nodes::{Node, NodeOrVirtualNode, VirtualNode};
 use super::parameters::{Parameter, ParameterOrTimeseriesRef};
 use crate::ConversionError;
+use crate::constituents::Constituent;
 use crate::data_tables::DataTable;
 #[cfg(feature = "core")]
 use crate::data_tables::{LoadedTableCollection, TableCollectionLoadError};
@@ -93,6 +94,12 @@ pub enum NetworkSchemaBuildError {
         #[source]
         source: Box<SchemaError>,
     },
+    #[error("Failed to add constituent `{name}` to the model: {source}")]
+    AddConstituentError {
+        name: String,
+        #[source]
+        source: Box<SchemaError>,
+    },
     #[error("Failed to add metric set with name `{name}` to the model: {source}")]
     AddMetricSetError {
         name: String,
@@ -121,6 +128,7 @@ impl TryFrom<NetworkSchemaBuildError> for PyErr {
             NetworkSchemaBuildError::AddEdgeError { source, .. } => (*source).try_into(),
             NetworkSchemaBuildError::AddParameterError { source, .. } => (*source).try_into(),
             NetworkSchemaBuildError::AddLocalParameterError { source, .. } => (*source).try_into(),
+            NetworkSchemaBuildError::AddConstituentError { source, .. } => (*source).try_into(),
             NetworkSchemaBuildError::AddMetricSetError { source, .. } => (*source).try_into(),
             NetworkSchemaBuildError::AddOutputError { source, .. } => (*source).try_into(),
             NetworkSchemaBuildError::LoadTimeseriesError(e) => e.try_into(),
@@ -151,6 +159,7 @@ pub struct NetworkSchema {
     pub parameters: Option<Vec<Parameter>>,
     pub tables: Option<Vec<DataTable>>,
     pub timeseries: Option<Vec<Timeseries>>,
+    pub constituents: Option<Vec<Constituent>>,
     pub metric_sets: Option<Vec<MetricSet>>,
     pub outputs: Option<Vec<Output>>,
 }
@@ -210,6 +219,8 @@ impl VisitMetrics for NetworkSchema {
             parameter.visit_metrics(visitor);
         }
 
+        self.constituents.visit_metrics(visitor);
+
         if let Some(metric_sets) = &self.metric_sets {
             for metric_set in metric_sets {
                 if let Some(metrics) = &metric_set.metrics {
@@ -230,6 +241,8 @@ impl VisitMetrics for NetworkSchema {
             parameter.visit_metrics_mut(visitor);
         }
 
+        self.constituents.visit_metrics_mut(visitor);
+
         if let Some(metric_sets) = &mut self.metric_sets {
             for metric_set in metric_sets {
                 if let Some(metrics) = &mut metric_set.metrics {
@@ -392,6 +405,7 @@ impl NetworkSchema {
                 parameters,
                 tables,
                 timeseries,
+                constituents: None,
                 metric_sets,
                 outputs,
             },
@@ -532,6 +546,16 @@ impl NetworkSchema {
                 })?;
         }
 
+        // Create the constituents; their input concentrations are set once the parameters exist
+        for constituent in self.constituents.iter().flatten() {
+            constituent.add_to_model(&mut network, &args).map_err(|source| {
+                NetworkSchemaBuildError::AddConstituentError {
+                    name: constituent.name.clone(),
+                    source: Box::new(source),
+                }
+            })?;
+        }
+
         // Gather all the parameters from the nodes
         let mut remaining_parameters: Vec<(Option<&str>, Parameter)> = Vec::new();
         for node in &self.nodes {
@@ -584,6 +608,15 @@ impl NetworkSchema {
             remaining_parameters = failed_parameters;
         }
 
+        for constituent in self.constituents.iter().flatten() {
+            constituent
+                .set_input_concentrations(&mut network, &args)
+                .map_err(|source| NetworkSchemaBuildError::AddConstituentError {
+                    name: constituent.name.clone(),
+                    source: Box::new(source),
+                })?;
+        }
+
         // Apply the constraints to the nodes
         for node in &self.nodes {
             node.set_constraints(&mut network, &args).map_err(|source| {
@@ -622,7 +655,7 @@ impl NetworkSchema {
         if let Some(outputs) = &self.outputs {
             for output in outputs {
                 output
-                    .add_to_model(&mut network, data_path, output_path)
+                    .add_to_model(&mut network, &args, output_path)
                     .map_err(|source| NetworkSchemaBuildError::AddOutputError {
                         name: output.name().to_string(),
                         source: Box::new(source),
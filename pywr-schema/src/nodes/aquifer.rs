@@ -0,0 +1,727 @@
+use crate::error::SchemaError;
+use crate::metric::Metric;
+#[cfg(feature = "core")]
+use crate::network::LoadArgs;
+#[cfg(feature = "core")]
+use crate::nodes::AnnualReset;
+#[cfg(feature = "core")]
+use crate::nodes::{NodeAttribute, NodeComponent};
+use crate::nodes::{NodeMeta, NodeSlot, StorageInitialVolume, VirtualStorageReset};
+use crate::parameters::Parameter;
+use crate::{mermaid, node_attribute_subset_enum, node_component_subset_enum};
+#[cfg(feature = "core")]
+use pywr_core::{
+    agg_funcs::AggFuncF64,
+    metric::MetricF64,
+    node::NodeIndex,
+    parameters::{AggregatedParameter, DifferenceParameter, ParameterName, Polynomial1DParameter, VolumeFlowParameter},
+    virtual_storage::{VirtualStorageBuilder, VirtualStorageResetVolume},
+};
+use pywr_schema_macros::{PywrVisitAll, skip_serializing_none};
+use schemars::JsonSchema;
+
+// This macro generates a subset enum for the `AquiferNode` attributes.
+// It allows for easy conversion between the enum and the `NodeAttribute` type.
+node_attribute_subset_enum! {
+    pub enum AquiferNodeAttribute {
+        /// The total volume of all the cells.
+        Volume,
+        /// The head in the first cell.
+        Head,
+        /// The abstracted flow.
+        Abstraction,
+        /// The baseflow to the river when the `baseflow` field is provided.
+        Baseflow,
+    }
+}
+
+node_component_subset_enum! {
+    pub enum AquiferNodeComponent {
+        Abstraction,
+        Baseflow,
+    }
+}
+
+pub enum AquiferOutputNodeSlot {
+    Abstraction,
+    River,
+}
+
+impl From<AquiferOutputNodeSlot> for NodeSlot {
+    fn from(slot: AquiferOutputNodeSlot) -> Self {
+        match slot {
+            AquiferOutputNodeSlot::Abstraction => NodeSlot::Abstraction,
+            AquiferOutputNodeSlot::River => NodeSlot::River,
+        }
+    }
+}
+
+impl TryFrom<NodeSlot> for AquiferOutputNodeSlot {
+    type Error = SchemaError;
+
+    fn try_from(slot: NodeSlot) -> Result<Self, Self::Error> {
+        match slot {
+            NodeSlot::Abstraction => Ok(AquiferOutputNodeSlot::Abstraction),
+            NodeSlot::River => Ok(AquiferOutputNodeSlot::River),
+            _ => Err(SchemaError::OutputNodeSlotNotSupported { slot }),
+        }
+    }
+}
+
+/// A lumped cell of an [`AquiferNode`].
+#[skip_serializing_none]
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, JsonSchema, PywrVisitAll)]
+#[serde(deny_unknown_fields)]
+pub struct AquiferCell {
+    /// The plan area of the cell.
+    pub area: f64,
+    /// The storage coefficient (or specific yield) of the cell.
+    pub storage_coefficient: f64,
+    /// The head in the cell when its volume is zero.
+    pub base_elevation: f64,
+    /// The maximum volume of the cell.
+    pub max_volume: Option<Metric>,
+    pub initial_volume: StorageInitialVolume,
+    /// The recharge into the cell. Use `None` not to add a recharge node.
+    pub recharge: Option<Metric>,
+}
+
+/// The head-dependent exchange between the last cell of an [`AquiferNode`] and a river.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, JsonSchema, PywrVisitAll)]
+#[serde(deny_unknown_fields)]
+pub struct AquiferBaseflow {
+    /// The level of the river that the aquifer discharges to.
+    pub river_level: Metric,
+    /// The baseflow per unit of head above the `river_level`.
+    pub conductance: f64,
+}
+
+/// A volumetric licence on the abstraction from an [`AquiferNode`].
+#[skip_serializing_none]
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, JsonSchema, PywrVisitAll)]
+#[serde(deny_unknown_fields)]
+pub struct AquiferLicence {
+    /// The volume that can be abstracted between resets.
+    pub volume: Metric,
+    /// When the licence is reset to `volume`. Defaults to annually on the 1st January.
+    pub reset: Option<VirtualStorageReset>,
+}
+
+/// This node represents a groundwater aquifer.
+///
+/// The aquifer is made up of one (i.e. a single lumped cell) or more cells in series. Each cell is
+/// a storage node with an optional recharge input. The head in each cell is calculated from its
+/// volume ($V$) using the cell's `base_elevation` ($z$), `area` ($A$) and `storage_coefficient`
+/// ($S$):
+///
+/// $$
+/// h = z + \frac{V}{S A}
+/// $$
+///
+/// Water moves from cell `i` to cell `i + 1` through the link `[name].transfer-{i:02}` at a
+/// rate equal to `transfer_conductance` multiplied by the (non-negative) head difference between
+/// the two cells. If `baseflow` is provided, the last cell discharges to the river through the
+/// link `[name].baseflow` at a rate equal to the baseflow `conductance` multiplied by the head above
+/// the `river_level`. These exchanges are calculated from the heads at the start of each time-step.
+/// An exchange is limited to the volume of the cell it drains and, if the receiving cell has a
+/// `max_volume`, to the space available in the receiving cell; below this limit it is exact, and
+/// above it the exchange may be reduced so that the cells do not empty or over-fill. The
+/// conductances should be small enough (i.e. $C \Delta t \leq S A$) that an exchange does not
+/// drain more than the volume of the cell above the receiving head.
+///
+/// Water is abstracted from the first cell through the link `[name].abstraction`. This can be
+/// constrained by `max_abstraction` and by a volumetric `licence`, which is implemented as a
+/// virtual storage node.
+///
+#[doc = mermaid!("doc_diagrams/aquifer.mmd")]
+///
+/// The first cell is named `[name]` and subsequent cells `[name].cell-{i:02}`. The node defines
+/// two output slots: the 'abstraction' slot (the default) and the 'river' slot for the baseflow.
+/// Edges into this node are connected to the first cell.
+///
+/// # Available attributes and components
+///
+/// The enums [`AquiferNodeAttribute`] and [`AquiferNodeComponent`] define the available
+/// attributes and components for this node.
+///
+#[skip_serializing_none]
+#[derive(serde::Deserialize, serde::Serialize, Clone, Default, Debug, JsonSchema, PywrVisitAll)]
+#[serde(deny_unknown_fields)]
+pub struct AquiferNode {
+    pub meta: NodeMeta,
+    /// Optional local parameters.
+    pub parameters: Option<Vec<Parameter>>,
+    /// The cells of the aquifer.
+    pub cells: Vec<AquiferCell>,
+    /// The flow between adjacent cells per unit of head difference. Required if there is
+    /// more than one cell.
+    pub transfer_conductance: Option<f64>,
+    /// The baseflow from the last cell to a river. Use `None` not to add the baseflow node.
+    pub baseflow: Option<AquiferBaseflow>,
+    /// The maximum abstraction flow.
+    pub max_abstraction: Option<Metric>,
+    /// The abstraction cost.
+    pub abstraction_cost: Option<Metric>,
+    /// The optional volumetric abstraction licence.
+    pub licence: Option<AquiferLicence>,
+}
+
+impl AquiferNode {
+    const DEFAULT_ATTRIBUTE: AquiferNodeAttribute = AquiferNodeAttribute::Volume;
+    const DEFAULT_COMPONENT: AquiferNodeComponent = AquiferNodeComponent::Abstraction;
+    const DEFAULT_OUTPUT_SLOT: AquiferOutputNodeSlot = AquiferOutputNodeSlot::Abstraction;
+
+    /// The sub-name of the storage node for cell `i`. The first cell uses the node itself.
+    fn cell_sub_name(i: usize) -> Option<String> {
+        if i == 0 { None } else { Some(format!("cell-{i:02}")) }
+    }
+
+    fn abstraction_sub_name() -> Option<&'static str> {
+        Some("abstraction")
+    }
+
+    fn baseflow_sub_name() -> Option<&'static str> {
+        Some("baseflow")
+    }
+
+    pub fn input_connectors(&self, slot: Option<&NodeSlot>) -> Result<Vec<(&str, Option<String>)>, SchemaError> {
+        if let Some(slot) = slot {
+            Err(SchemaError::InputNodeSlotNotSupported { slot: slot.clone() })
+        } else {
+            Ok(vec![(self.meta.name.as_str(), Self::cell_sub_name(0))])
+        }
+    }
+
+    pub fn output_connectors(&self, slot: Option<&NodeSlot>) -> Result<Vec<(&str, Option<String>)>, SchemaError> {
+        let slot = match slot {
+            Some(s) => s.clone().try_into()?,
+            None => Self::DEFAULT_OUTPUT_SLOT,
+        };
+
+        let indices = match slot {
+            AquiferOutputNodeSlot::Abstraction => vec![(
+                self.meta.name.as_str(),
+                Self::abstraction_sub_name().map(|s| s.to_string()),
+            )],
+            AquiferOutputNodeSlot::River => match self.baseflow {
+                Some(_) => vec![(
+                    self.meta.name.as_str(),
+                    Self::baseflow_sub_name().map(|s| s.to_string()),
+                )],
+                None => return Err(SchemaError::OutputNodeSlotNotSupported { slot: slot.into() }),
+            },
+        };
+
+        Ok(indices)
+    }
+
+    pub fn iter_output_slots(&self) -> impl Iterator<Item = NodeSlot> + '_ {
+        [
+            AquiferOutputNodeSlot::Abstraction.into(),
+            AquiferOutputNodeSlot::River.into(),
+        ]
+        .into_iter()
+    }
+
+    pub fn default_attribute(&self) -> AquiferNodeAttribute {
+        Self::DEFAULT_ATTRIBUTE
+    }
+
+    pub fn default_component(&self) -> AquiferNodeComponent {
+        Self::DEFAULT_COMPONENT
+    }
+}
+
+#[cfg(feature = "core")]
+impl AquiferNode {
+    fn recharge_sub_name(i: usize) -> Option<String> {
+        Some(format!("recharge-{i:02}"))
+    }
+
+    fn transfer_sub_name(i: usize) -> Option<String> {
+        Some(format!("transfer-{i:02}"))
+    }
+
+    fn licence_sub_name() -> &'static str {
+        "licence"
+    }
+
+    /// The name of the parameter that calculates the head in cell `i`.
+    fn head_parameter_name(&self, i: usize) -> ParameterName {
+        let name = if i == 0 {
+            "head".to_string()
+        } else {
+            format!("head-{i:02}")
+        };
+        ParameterName::new(&name, Some(self.meta.name.as_str()))
+    }
+
+    fn agg_sub_name() -> Option<&'static str> {
+        Some("aggregate")
+    }
+
+    fn get_node_index(
+        &self,
+        network: &pywr_core::network::Network,
+        sub_name: Option<&str>,
+    ) -> Result<NodeIndex, SchemaError> {
+        network
+            .get_node_index_by_name(self.meta.name.as_str(), sub_name)
+            .ok_or_else(|| SchemaError::CoreNodeNotFound {
+                name: self.meta.name.clone(),
+                sub_name: sub_name.map(String::from),
+            })
+    }
+
+    fn cell_node_indices(&self, network: &pywr_core::network::Network) -> Result<Vec<NodeIndex>, SchemaError> {
+        (0..self.cells.len())
+            .map(|i| self.get_node_index(network, Self::cell_sub_name(i).as_deref()))
+            .collect()
+    }
+
+    pub fn node_indices_for_flow_constraints(
+        &self,
+        network: &pywr_core::network::Network,
+        component: Option<NodeComponent>,
+    ) -> Result<Vec<NodeIndex>, SchemaError> {
+        // Use the default component if none is specified
+        let component = match component {
+            Some(c) => c.try_into()?,
+            None => Self::DEFAULT_COMPONENT,
+        };
+
+        let idx = match component {
+            AquiferNodeComponent::Abstraction => self.get_node_index(network, Self::abstraction_sub_name())?,
+            AquiferNodeComponent::Baseflow => {
+                if self.baseflow.is_none() {
+                    return Err(SchemaError::NodeComponentNotSupported {
+                        attr: NodeComponent::Baseflow,
+                    });
+                }
+                self.get_node_index(network, Self::baseflow_sub_name())?
+            }
+        };
+
+        Ok(vec![idx])
+    }
+
+    pub fn node_indices_for_storage_constraints(
+        &self,
+        network: &pywr_core::network::Network,
+    ) -> Result<Vec<NodeIndex>, SchemaError> {
+        self.cell_node_indices(network)
+    }
+
+    pub fn add_to_model(&self, network: &mut pywr_core::network::Network) -> Result<(), SchemaError> {
+        let name = self.meta.name.as_str();
+
+        if self.cells.is_empty() {
+            return Err(SchemaError::InvalidNodeAttributes {
+                msg: format!("Aquifer node '{name}' must define at least one cell."),
+            });
+        }
+        if self.cells.len() > 1 && self.transfer_conductance.is_none() {
+            return Err(SchemaError::InvalidNodeAttributes {
+                msg: format!("Aquifer node '{name}' must define `transfer_conductance` when it has multiple cells."),
+            });
+        }
+
+        if self.cells.iter().any(|c| c.area <= 0.0 || c.storage_coefficient <= 0.0) {
+            return Err(SchemaError::InvalidNodeAttributes {
+                msg: format!("Aquifer node '{name}' must have cells with a positive area and storage coefficient."),
+            });
+        }
+
+        // add the cells, their recharge and the transfers between them
+        let mut cells: Vec<NodeIndex> = Vec::with_capacity(self.cells.len());
+        for (i, cell) in self.cells.iter().enumerate() {
+            let storage = network.add_storage_node(
+                name,
+                Self::cell_sub_name(i).as_deref(),
+                cell.initial_volume.into(),
+                None,
+                None,
+            )?;
+
+            if cell.recharge.is_some() {
+                let recharge = network.add_input_node(name, Self::recharge_sub_name(i).as_deref())?;
+                network.connect_nodes(recharge, storage)?;
+            }
+
+            if let Some(upstream) = cells.last() {
+                let transfer = network.add_link_node(name, Self::transfer_sub_name(i - 1).as_deref())?;
+                network.connect_nodes(*upstream, transfer)?;
+                network.connect_nodes(transfer, storage)?;
+            }
+
+            cells.push(storage);
+        }
+
+        let abstraction = network.add_link_node(name, Self::abstraction_sub_name())?;
+        network.connect_nodes(cells[0], abstraction)?;
+
+        if self.baseflow.is_some() {
+            let baseflow = network.add_link_node(name, Self::baseflow_sub_name())?;
+            network.connect_nodes(cells[cells.len() - 1], baseflow)?;
+        }
+
+        if let Some(licence) = &self.licence {
+            let reset = licence
+                .reset
+                .clone()
+                .unwrap_or(VirtualStorageReset::Annual(AnnualReset { day: 1, month: 1 }))
+                .try_into()?;
+
+            let builder = VirtualStorageBuilder::new(name, &[abstraction])
+                .sub_name(Self::licence_sub_name())
+                .initial_volume(pywr_core::node::StorageInitialVolume::Proportional(1.0))
+                .reset(reset)
+                .reset_volume(VirtualStorageResetVolume::Max);
+            network.add_virtual_storage_node(builder)?;
+        }
+
+        // the total volume of a multi-cell aquifer
+        if cells.len() > 1 {
+            network.add_aggregated_storage_node(name, Self::agg_sub_name(), cells)?;
+        }
+
+        Ok(())
+    }
+
+    /// Add the parameters to calculate a head-dependent exchange flow. The flow is the
+    /// `conductance` multiplied by the head difference, which is limited to be non-negative.
+    fn add_exchange_parameter(
+        &self,
+        network: &mut pywr_core::network::Network,
+        name: &str,
+        head: MetricF64,
+        receiving_head: MetricF64,
+        conductance: f64,
+    ) -> Result<MetricF64, SchemaError> {
+        let difference = DifferenceParameter::new(
+            ParameterName::new(&format!("{name}-head-difference"), Some(self.meta.name.as_str())),
+            head,
+            receiving_head,
+            Some(0.0.into()),
+            None,
+        );
+        let difference = network.add_parameter(Box::new(difference))?.into_metric_f64_before();
+
+        let flow = Polynomial1DParameter::new(
+            ParameterName::new(name, Some(self.meta.name.as_str())),
+            difference,
+            vec![0.0, conductance],
+            1.0,
+            0.0,
+        );
+        Ok(network.add_parameter(Box::new(flow))?.into_metric_f64_before())
+    }
+
+    /// Add the parameters to limit an exchange flow to the volume available in the `source` cell
+    /// at the start of the time-step and, if it has a maximum volume, to the space available in the
+    /// `receiving` cell. The limited flow is used as the minimum flow of the exchange link, so the
+    /// exchange is as large as the head difference allows without emptying or over-filling a cell.
+    fn add_exchange_limit(
+        &self,
+        network: &mut pywr_core::network::Network,
+        name: &str,
+        flow: MetricF64,
+        source: NodeIndex,
+        receiving: Option<NodeIndex>,
+    ) -> Result<MetricF64, SchemaError> {
+        let parent = Some(self.meta.name.as_str());
+
+        let available = VolumeFlowParameter::new(
+            ParameterName::new(&format!("{name}-available"), parent),
+            MetricF64::NodeVolume(source),
+        );
+        let mut metrics = vec![
+            flow,
+            network.add_parameter(Box::new(available))?.into_metric_f64_before(),
+        ];
+
+        if let Some(receiving) = receiving {
+            let space = DifferenceParameter::new(
+                ParameterName::new(&format!("{name}-space-volume"), parent),
+                MetricF64::NodeMaxVolume(receiving),
+                MetricF64::NodeVolume(receiving),
+                Some(0.0.into()),
+                None,
+            );
+            let space = network.add_parameter(Box::new(space))?.into_metric_f64_before();
+            let space = VolumeFlowParameter::new(ParameterName::new(&format!("{name}-space"), parent), space);
+            metrics.push(network.add_parameter(Box::new(space))?.into_metric_f64_before());
+        }
+
+        let limit = AggregatedParameter::new(
+            ParameterName::new(&format!("{name}-limit"), parent),
+            &metrics,
+            AggFuncF64::Min,
+        );
+        Ok(network.add_parameter(Box::new(limit))?.into_metric_f64_before())
+    }
+
+    pub fn set_constraints(
+        &self,
+        network: &mut pywr_core::network::Network,
+        args: &LoadArgs,
+    ) -> Result<(), SchemaError> {
+        let name = self.meta.name.as_str();
+        let cells = self.cell_node_indices(network)?;
+
+        let mut heads = Vec::with_capacity(self.cells.len());
+        for (i, (cell, idx)) in self.cells.iter().zip(&cells).enumerate() {
+            let sub_name = Self::cell_sub_name(i);
+
+            if let Some(max_volume) = &cell.max_volume {
+                let value = max_volume.load(network, args, Some(name))?;
+                network.set_node_max_volume(name, sub_name.as_deref(), Some(value.try_into()?))?;
+            }
+
+            if let Some(recharge) = &cell.recharge {
+                let value = recharge.load(network, args, Some(name))?;
+                let recharge_sub_name = Self::recharge_sub_name(i);
+                network.set_node_min_flow(name, recharge_sub_name.as_deref(), Some(value.clone()))?;
+                network.set_node_max_flow(name, recharge_sub_name.as_deref(), Some(value))?;
+            }
+
+            // head is linear with the volume of the cell
+            let head = Polynomial1DParameter::new(
+                self.head_parameter_name(i),
+                MetricF64::NodeVolume(*idx),
+                vec![cell.base_elevation, 1.0 / (cell.storage_coefficient * cell.area)],
+                1.0,
+                0.0,
+            );
+            heads.push(network.add_parameter(Box::new(head))?.into_metric_f64_before());
+        }
+
+        if let Some(conductance) = self.transfer_conductance {
+            for (i, pair) in heads.windows(2).enumerate() {
+                let sub_name = Self::transfer_sub_name(i);
+                let flow = self.add_exchange_parameter(
+                    network,
+                    sub_name.as_deref().unwrap(),
+                    pair[0].clone(),
+                    pair[1].clone(),
+                    conductance,
+                )?;
+                let receiving = self.cells[i + 1].max_volume.as_ref().map(|_| cells[i + 1]);
+                let limit =
+                    self.add_exchange_limit(network, sub_name.as_deref().unwrap(), flow.clone(), cells[i], receiving)?;
+                network.set_node_min_flow(name, sub_name.as_deref(), Some(limit))?;
+                network.set_node_max_flow(name, sub_name.as_deref(), Some(flow))?;
+            }
+        }
+
+        if let Some(baseflow) = &self.baseflow {
+            let river_level = baseflow.river_level.load(network, args, Some(name))?;
+            let flow = self.add_exchange_parameter(
+                network,
+                "baseflow",
+                heads[heads.len() - 1].clone(),
+                river_level,
+                baseflow.conductance,
+            )?;
+            let limit = self.add_exchange_limit(network, "baseflow", flow.clone(), cells[cells.len() - 1], None)?;
+            network.set_node_min_flow(name, Self::baseflow_sub_name(), Some(limit))?;
+            network.set_node_max_flow(name, Self::baseflow_sub_name(), Some(flow))?;
+        }
+
+        if let Some(max_abstraction) = &self.max_abstraction {
+            let value = max_abstraction.load(network, args, Some(name))?;
+            network.set_node_max_flow(name, Self::abstraction_sub_name(), value.into())?;
+        }
+
+        if let Some(cost) = &self.abstraction_cost {
+            let value = cost.load(network, args, Some(name))?;
+            network.set_node_cost(name, Self::abstraction_sub_name(), value.into())?;
+        }
+
+        if let Some(licence) = &self.licence {
+            let value = licence.volume.load(network, args, Some(name))?;
+            network.set_virtual_storage_max_volume(name, Some(Self::licence_sub_name()), Some(value.try_into()?))?;
+        }
+
+        Ok(())
+    }
+
+    fn get_parameter_metric(
+        &self,
+        network: &pywr_core::network::Network,
+        name: ParameterName,
+    ) -> Result<MetricF64, SchemaError> {
+        let idx = network
+            .get_parameter_index_by_name(&name)
+            .ok_or_else(|| SchemaError::CoreParameterNotFound {
+                name: name.to_string(),
+                key: None,
+            })?;
+        Ok(idx.into_metric_f64_before())
+    }
+
+    pub fn create_metric(
+        &self,
+        network: &pywr_core::network::Network,
+        attribute: Option<NodeAttribute>,
+    ) -> Result<MetricF64, SchemaError> {
+        // Use the default attribute if none is specified
+        let attr = match attribute {
+            Some(attr) => attr.try_into()?,
+            None => Self::DEFAULT_ATTRIBUTE,
+        };
+
+        let metric = match attr {
+            AquiferNodeAttribute::Volume => {
+                if self.cells.len() > 1 {
+                    let idx = network
+                        .get_aggregated_storage_node_index_by_name(self.meta.name.as_str(), Self::agg_sub_name())
+                        .ok_or_else(|| SchemaError::CoreNodeNotFound {
+                            name: self.meta.name.clone(),
+                            sub_name: Self::agg_sub_name().map(String::from),
+                        })?;
+                    MetricF64::AggregatedNodeVolume(idx)
+                } else {
+                    MetricF64::NodeVolume(self.get_node_index(network, None)?)
+                }
+            }
+            AquiferNodeAttribute::Head => self.get_parameter_metric(network, self.head_parameter_name(0))?,
+            AquiferNodeAttribute::Abstraction => {
+                MetricF64::NodeInFlow(self.get_node_index(network, Self::abstraction_sub_name())?)
+            }
+            AquiferNodeAttribute::Baseflow => match self.baseflow {
+                Some(_) => MetricF64::NodeInFlow(self.get_node_index(network, Self::baseflow_sub_name())?),
+                None => return Err(SchemaError::NodeAttributeNotSupported { attr: attr.into() }),
+            },
+        };
+
+        Ok(metric)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "core")]
+mod tests {
+    use super::AquiferNode;
+    use crate::metric::Metric;
+    use crate::model::ModelSchema;
+    use crate::nodes::Node;
+    use float_cmp::assert_approx_eq;
+    use ndarray::Array2;
+    use pywr_core::network::NetworkTimings;
+    use pywr_core::parameters::ParameterName;
+    use pywr_core::recorders::AssertionF64Recorder;
+    use pywr_core::solvers::ClpSolver;
+
+    fn aquifer_str() -> &'static str {
+        include_str!("../../tests/aquifer1.json")
+    }
+
+    #[test]
+    fn test_model_nodes_and_edges() {
+        let data = aquifer_str();
+        let schema: ModelSchema = serde_json::from_str(data).unwrap();
+        let mut model: pywr_core::models::Model = schema.build_model(None, None).unwrap();
+
+        let network = model.network_mut();
+        // 2 cells, 1 recharge, 1 transfer, abstraction, baseflow, plus the demand and river nodes
+        assert_eq!(network.nodes().len(), 8);
+        assert_eq!(network.virtual_storage_nodes().len(), 1);
+    }
+
+    /// The head in the second cell follows its volume as water is exchanged between the cells.
+    #[test]
+    fn test_cell_head() {
+        let data = aquifer_str();
+        let mut schema: ModelSchema = serde_json::from_str(data).unwrap();
+        schema.network.outputs = None;
+        let mut model: pywr_core::models::Model = schema.build_model(None, None).unwrap();
+
+        let network = model.network_mut();
+        let head = network
+            .get_parameter_index_by_name(&ParameterName::new("head-01", Some("aquifer1")))
+            .unwrap();
+
+        // Both cells have a storage coefficient multiplied by area of 100
+        let mut volumes: [f64; 2] = [1000.0, 500.0];
+        let mut licence = 10.0;
+        let mut expected = Vec::new();
+        for _ in 0..5 {
+            let heads = [volumes[0] / 100.0, volumes[1] / 100.0];
+            expected.push(heads[1]);
+
+            let transfer = 0.5 * (heads[0] - heads[1]).max(0.0);
+            let baseflow = (heads[1] - 2.0).max(0.0);
+            let abstraction = f64::min(4.0, licence);
+            licence -= abstraction;
+
+            volumes[0] += 2.0 - transfer - abstraction;
+            volumes[1] += transfer - baseflow;
+        }
+        let expected = Array2::from_shape_vec((5, 1), expected).unwrap();
+
+        let recorder = AssertionF64Recorder::new("head-01", head.into_metric_f64_before(), expected, None, None);
+        network.add_recorder(Box::new(recorder)).unwrap();
+
+        model.run::<ClpSolver>(&Default::default()).unwrap();
+    }
+
+    /// The exchanges are limited when they would over-fill the second cell or empty it to the river.
+    #[test]
+    fn test_exchange_limited_by_volume() {
+        let data = aquifer_str();
+        let mut schema: ModelSchema = serde_json::from_str(data).unwrap();
+        schema.network.outputs = None;
+
+        let Node::Aquifer(aquifer) = &mut schema.network.nodes[0] else {
+            panic!("The first node should be the aquifer.");
+        };
+        aquifer.transfer_conductance = Some(1000.0);
+        aquifer.cells[1].max_volume = Some(Metric::Literal { value: 600.0 });
+        if let Some(baseflow) = &mut aquifer.baseflow {
+            baseflow.conductance = 1000.0;
+        }
+
+        let model: pywr_core::models::Model = schema.build_model(None, None).unwrap();
+        let network = model.network();
+        let node = |sub_name: Option<&str>| network.get_node_index_by_name("aquifer1", sub_name).unwrap();
+        let cells = [node(None), node(AquiferNode::cell_sub_name(1).as_deref())];
+        let transfer = node(AquiferNode::transfer_sub_name(0).as_deref());
+        let baseflow = node(AquiferNode::baseflow_sub_name());
+        let abstraction = node(AquiferNode::abstraction_sub_name());
+
+        let max_volumes = [10000.0, 600.0];
+        let recharge = 2.0;
+        let mut volumes: [f64; 2] = [1000.0, 500.0];
+        let (mut min_volume, mut max_volume) = (volumes[1], volumes[1]);
+
+        let mut timings = NetworkTimings::new_without_component_timings();
+        let scenario_index = &model.domain().scenarios().indices()[0];
+        let mut state = model.setup::<ClpSolver>(&Default::default()).unwrap();
+        for _ in 0..5 {
+            model.step(&mut state, None, &mut timings).unwrap();
+            let network_state = state.network_state().state(scenario_index).get_network_state();
+
+            let transfer_flow = network_state.get_node_in_flow(&transfer).unwrap();
+            let baseflow_flow = network_state.get_node_in_flow(&baseflow).unwrap();
+            let abstraction_flow = network_state.get_node_in_flow(&abstraction).unwrap();
+
+            // The transfer is limited by the water available in the first cell and the space
+            // available in the second, and the baseflow by the water available in the second
+            assert!(transfer_flow + abstraction_flow <= volumes[0] + recharge + 1e-6);
+            assert!(transfer_flow <= max_volumes[1] - volumes[1] + baseflow_flow + 1e-6);
+            assert!(baseflow_flow <= volumes[1] + transfer_flow + 1e-6);
+
+            for (i, cell) in cells.iter().enumerate() {
+                volumes[i] = network_state.get_node_volume(cell).unwrap();
+                assert!(volumes[i] >= -1e-6 && volumes[i] <= max_volumes[i] + 1e-6);
+            }
+            min_volume = min_volume.min(volumes[1]);
+            max_volume = max_volume.max(volumes[1]);
+        }
+
+        // The large conductances both fill the second cell and empty it to the river
+        assert_approx_eq!(f64, max_volume, max_volumes[1], epsilon = 1e-6);
+        assert_approx_eq!(f64, min_volume, 0.0, epsilon = 1e-6);
+    }
+}
@@ -25,6 +25,22 @@ pub enum NodeAttribute {
     Evaporation,
     /// The abstracted flow
     Abstraction,
+    /// The energy used (e.g. by pumping).
+    Energy,
+    /// The cost of the energy used.
+    EnergyCost,
+    /// The carbon emissions.
+    Carbon,
+    /// The groundwater head.
+    Head,
+    /// The baseflow from groundwater to a river.
+    Baseflow,
+    /// The rainfall volume during the time-step.
+    RainfallVolume,
+    /// The evaporation volume during the time-step.
+    EvaporationVolume,
+    /// The loss (e.g. leakage) volume during the time-step.
+    LossVolume,
 }
 
 /// Macro to generate a subset enum of `NodeAttribute` with conversion implementations.
@@ -23,6 +23,8 @@ pub enum NodeComponent {
     Evaporation,
     /// The abstracted flow.
     Abstraction,
+    /// The baseflow from groundwater to a river.
+    Baseflow,
 }
 
 /// Macro to generate a subset enum of `NodeComponent` with conversion implementations.
@@ -5,7 +5,7 @@ use crate::metric::Metric;
 use crate::network::LoadArgs;
 #[cfg(feature = "core")]
 use crate::nodes::{NodeAttribute, NodeComponent};
-use crate::nodes::{NodeMeta, NodeSlot};
+use crate::nodes::{NodeMeta, NodeSlot, Pumping};
 use crate::parameters::Parameter;
 use crate::v1::{ConversionData, TryFromV1, try_convert_initial_storage, try_convert_node_attr, try_convert_node_meta};
 use crate::{mermaid, node_attribute_subset_enum, node_component_subset_enum};
@@ -13,7 +13,7 @@ use crate::{mermaid, node_attribute_subset_enum, node_component_subset_enum};
 use pywr_core::{
     metric::MetricF64,
     node::StorageInitialVolume as CoreStorageInitialVolume,
-    parameters::{DeficitParameter, ParameterName},
+    parameters::{AggregatedParameter, DeficitParameter, ParameterName},
 };
 use pywr_schema_macros::PywrVisitAll;
 use pywr_schema_macros::skip_serializing_none;
@@ -205,12 +205,64 @@ pub struct SoftConstraint {
     pub flow: Option<Metric>,
 }
 
+/// A band of flow with its own cost for use in a [`LinkNode`]'s `tiered_cost`.
+#[skip_serializing_none]
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, JsonSchema, PywrVisitAll)]
+#[serde(deny_unknown_fields)]
+pub struct CostTier {
+    /// The size of the band of flow. If not given the band is unbounded.
+    pub flow: Option<Metric>,
+    /// The cost of flow in this band. This is added to the node's `cost`.
+    pub cost: Metric,
+}
+
+/// A semi-continuous flow for a link-like node.
+///
+/// The node is either off, with no flow, or on with a flow of at least `min_flow_if_on`. The
+/// optional `start_up_cost` is added to the objective when the node is switched on after having no
+/// flow in the previous time-step. This requires a solver that supports integer variables
+/// (e.g. CBC or HiGHS). CBC also requires `min_flow_if_on` to be constant.
+#[skip_serializing_none]
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, JsonSchema, PywrVisitAll)]
+#[serde(deny_unknown_fields)]
+pub struct SemiContinuousFlow {
+    /// The minimum flow through the node when it is on.
+    pub min_flow_if_on: Metric,
+    /// The optional cost of switching the node on.
+    pub start_up_cost: Option<Metric>,
+}
+
+#[cfg(feature = "core")]
+impl SemiContinuousFlow {
+    pub fn load(
+        &self,
+        network: &mut pywr_core::network::Network,
+        args: &LoadArgs,
+        parent: Option<&str>,
+    ) -> Result<pywr_core::node::SemiContinuousFlow, SchemaError> {
+        let min_flow_if_on = self.min_flow_if_on.load(network, args, parent)?;
+        let start_up_cost = self
+            .start_up_cost
+            .as_ref()
+            .map(|c| c.load(network, args, parent))
+            .transpose()?;
+
+        Ok(pywr_core::node::SemiContinuousFlow::new(min_flow_if_on, start_up_cost))
+    }
+}
+
 // This macro generates a subset enum for the `LinkNode` attributes.
 // It allows for easy conversion between the enum and the `NodeAttribute` type.
 node_attribute_subset_enum! {
     pub enum LinkNodeAttribute {
         Inflow,
         Outflow,
+        /// The energy used pumping. Requires `pumping` to be defined.
+        Energy,
+        /// The cost of the energy used pumping. Requires `pumping` to be defined.
+        EnergyCost,
+        /// The carbon emitted pumping. Requires `pumping` to be defined.
+        Carbon,
     }
 }
 
@@ -293,6 +345,32 @@ node_component_subset_enum! {
 ///   with a negative cost to allow the minimum flow requirement. However, when this cannot be met
 ///   (for example when the abstraction license or the source runs out), the minimum flow will not
 ///   be honoured and the solver will find a solution.
+///
+/// # Tiered costs
+///
+/// A piecewise-linear cost can be applied to the flow through the node using `tiered_cost`. This is
+/// expanded into a sequence of links, similar to a [`crate::nodes::PiecewiseLinkNode`], where each
+/// tier carries up to its `flow` at its own cost (plus `cost`). The first tier is applied to `[name]`
+/// and each subsequent tier `i` to a node named `[name].tier-{i:02}`. An aggregated node ensures the
+/// combined flow through the tiers is bound between `min_flow` and `max_flow`. Tiers must be given
+/// in order of strictly increasing cost, otherwise the solver may use a later tier before an
+/// earlier one is full. This is checked for literal costs when the model is built; tiers with
+/// costs from parameters or other dynamic values must be kept in order by the user. Tiered costs
+/// can not be combined with soft constraints.
+///
+/// # Pumping
+///
+/// If the link represents a pumped transfer, the optional [`Pumping`] component can be used to
+/// account for the energy, cost and carbon emissions of the flow through the node. The pumping
+/// cost can optionally be added to the costs of the node.
+///
+/// # Semi-continuous flow
+///
+/// If the link represents a works or transfer that must run at a minimum rate when it is used, the
+/// optional [`SemiContinuousFlow`] can be used. The flow through the node is then either zero or
+/// at least its `min_flow_if_on`, and an optional start-up cost is applied when the node is switched
+/// on. This requires a solver that supports integer variables, and can not be combined with soft
+/// constraints or tiered costs.
 #[skip_serializing_none]
 #[derive(serde::Deserialize, serde::Serialize, Clone, Default, Debug, JsonSchema, PywrVisitAll)]
 #[serde(deny_unknown_fields)]
@@ -310,6 +388,12 @@ pub struct LinkNode {
     pub soft_min: Option<SoftConstraint>,
     /// The maximum soft constraints.
     pub soft_max: Option<SoftConstraint>,
+    /// Optional energy accounting for pumping the flow through the node.
+    pub pumping: Option<Pumping>,
+    /// Optional piecewise-linear cost tiers.
+    pub tiered_cost: Option<Vec<CostTier>>,
+    /// Optional semi-continuous flow through the node.
+    pub semi_continuous: Option<SemiContinuousFlow>,
 }
 
 impl LinkNode {
@@ -324,6 +408,18 @@ impl LinkNode {
         Some("soft_max")
     }
 
+    /// The sub-name of the node for cost tier `i`. The first tier uses the node itself.
+    fn tier_node_sub_name(i: usize) -> Option<String> {
+        if i == 0 { None } else { Some(format!("tier-{i:02}")) }
+    }
+
+    /// The sub-names of the nodes added for cost tiers after the first.
+    fn additional_tier_sub_names(&self) -> impl Iterator<Item = Option<String>> + '_ {
+        self.tiered_cost
+            .iter()
+            .flat_map(|tiers| (1..tiers.len()).map(Self::tier_node_sub_name))
+    }
+
     pub fn input_connectors(&self, slot: Option<&NodeSlot>) -> Result<Vec<(&str, Option<String>)>, SchemaError> {
         if let Some(slot) = slot {
             return Err(SchemaError::InputNodeSlotNotSupported { slot: slot.clone() });
@@ -342,6 +438,9 @@ impl LinkNode {
                 Self::soft_max_node_sub_name().map(|s| s.to_string()),
             ));
         }
+        for sub_name in self.additional_tier_sub_names() {
+            connectors.push((self.meta.name.as_str(), sub_name));
+        }
         Ok(connectors)
     }
 
@@ -363,6 +462,9 @@ impl LinkNode {
                 Self::soft_max_node_sub_name().map(|s| s.to_string()),
             ));
         }
+        for sub_name in self.additional_tier_sub_names() {
+            connectors.push((self.meta.name.as_str(), sub_name));
+        }
         Ok(connectors)
     }
 
@@ -386,6 +488,23 @@ impl LinkNode {
         Some("aggregate_node_l_l_min")
     }
 
+    /// The indices of the nodes added for cost tiers after the first.
+    fn additional_tier_node_indices(
+        &self,
+        network: &pywr_core::network::Network,
+    ) -> Result<Vec<pywr_core::node::NodeIndex>, SchemaError> {
+        self.additional_tier_sub_names()
+            .map(|sub_name| {
+                network
+                    .get_node_index_by_name(self.meta.name.as_str(), sub_name.as_deref())
+                    .ok_or_else(|| SchemaError::CoreNodeNotFound {
+                        name: self.meta.name.clone(),
+                        sub_name,
+                    })
+            })
+            .collect()
+    }
+
     pub fn node_indices_for_flow_constraints(
         &self,
         network: &pywr_core::network::Network,
@@ -431,6 +550,8 @@ impl LinkNode {
                     indices.push(idx);
                 }
 
+                indices.extend(self.additional_tier_node_indices(network)?);
+
                 indices
             }
         };
@@ -439,6 +560,40 @@ impl LinkNode {
     }
     pub fn add_to_model(&self, network: &mut pywr_core::network::Network) -> Result<(), SchemaError> {
         let node_name = self.meta.name.as_str();
+
+        if let Some(tiers) = &self.tiered_cost {
+            if tiers.is_empty() {
+                return Err(SchemaError::InvalidNodeAttributes {
+                    msg: format!("Link node '{node_name}' must define at least one cost tier."),
+                });
+            }
+            if self.soft_min.is_some() || self.soft_max.is_some() {
+                return Err(SchemaError::InvalidNodeAttributes {
+                    msg: format!("Link node '{node_name}' can not define both tiered costs and soft constraints."),
+                });
+            }
+            // only literal costs can be checked before the model is run
+            let increasing = tiers.windows(2).all(|w| match (&w[0].cost, &w[1].cost) {
+                (Metric::Literal { value: a }, Metric::Literal { value: b }) => a < b,
+                _ => true,
+            });
+            if !increasing {
+                return Err(SchemaError::InvalidNodeAttributes {
+                    msg: format!("Link node '{node_name}' must define cost tiers in order of increasing cost."),
+                });
+            }
+        }
+
+        if self.semi_continuous.is_some()
+            && (self.tiered_cost.is_some() || self.soft_min.is_some() || self.soft_max.is_some())
+        {
+            return Err(SchemaError::InvalidNodeAttributes {
+                msg: format!(
+                    "Link node '{node_name}' can not define a semi-continuous flow with tiered costs or soft constraints."
+                ),
+            });
+        }
+
         let link = network.add_link_node(node_name, None)?;
         // add soft constrained nodes and aggregated node
         match (&self.soft_min, &self.soft_max) {
@@ -479,8 +634,68 @@ impl LinkNode {
                     None,
                 )?;
             }
-            (None, None) => {}
+            (None, None) => {
+                // add a node for each additional cost tier, and aggregated node for all the tiers
+                if self.tiered_cost.is_some() {
+                    let mut tier_nodes = vec![vec![link]];
+                    for sub_name in self.additional_tier_sub_names() {
+                        tier_nodes.push(vec![network.add_link_node(node_name, sub_name.as_deref())?]);
+                    }
+                    network.add_aggregated_node(node_name, Self::aggregated_node_sub_name(), &tier_nodes, None)?;
+                }
+            }
+        };
+        Ok(())
+    }
+
+    /// Create a metric for one of the pumping values; this errors if `pumping` is not defined.
+    fn pumping_metric(
+        &self,
+        network: &pywr_core::network::Network,
+        key: &str,
+        attr: LinkNodeAttribute,
+    ) -> Result<MetricF64, SchemaError> {
+        if self.pumping.is_none() {
+            return Err(SchemaError::NodeAttributeNotSupported { attr: attr.into() });
+        }
+        Pumping::create_metric(network, self.meta.name.as_str(), key)
+    }
+
+    /// Set the cost of one of the node's sub-nodes to the sum of the given `costs`, including the
+    /// pumping cost if provided.
+    fn set_cost(
+        &self,
+        network: &mut pywr_core::network::Network,
+        args: &LoadArgs,
+        sub_name: Option<&str>,
+        costs: &[Option<&Metric>],
+        pumping_cost: Option<&MetricF64>,
+    ) -> Result<(), SchemaError> {
+        let mut metrics = costs
+            .iter()
+            .flatten()
+            .map(|c| c.load(network, args, Some(&self.meta.name)))
+            .collect::<Result<Vec<_>, _>>()?;
+        metrics.extend(pumping_cost.cloned());
+
+        let value = match metrics.len() {
+            0 => return Ok(()),
+            1 => metrics.remove(0),
+            _ => {
+                let name = match sub_name {
+                    Some(sub_name) => format!("{sub_name}-total-cost"),
+                    None => "total-cost".to_string(),
+                };
+                let p = AggregatedParameter::new(
+                    ParameterName::new(&name, Some(&self.meta.name)),
+                    &metrics,
+                    pywr_core::agg_funcs::AggFuncF64::Sum,
+                );
+                network.add_parameter(Box::new(p))?.into_metric_f64_before()
+            }
         };
+
+        network.set_node_cost(self.meta.name.as_str(), sub_name, value.into())?;
         Ok(())
     }
 
@@ -490,13 +705,51 @@ impl LinkNode {
         args: &LoadArgs,
     ) -> Result<(), SchemaError> {
         let node_name = self.meta.name.as_str();
+
+        let pumping_cost = match &self.pumping {
+            Some(pumping) => {
+                let flow = MetricF64::MultiNodeInFlow {
+                    indices: self.node_indices_for_flow_constraints(network, None)?,
+                    name: self.meta.name.to_string(),
+                };
+                let idx = pumping.add_to_model(network, args, node_name, flow)?;
+                pumping.unit_cost(&idx)
+            }
+            None => None,
+        };
+
         match (&self.soft_min, &self.soft_max) {
+            (None, None) if self.tiered_cost.is_some() => {
+                // set the costs and flow of each tier
+                for (i, tier) in self.tiered_cost.iter().flatten().enumerate() {
+                    let sub_name = Self::tier_node_sub_name(i);
+                    self.set_cost(
+                        network,
+                        args,
+                        sub_name.as_deref(),
+                        &[self.cost.as_ref(), Some(&tier.cost)],
+                        pumping_cost.as_ref(),
+                    )?;
+
+                    if let Some(flow) = &tier.flow {
+                        let value = flow.load(network, args, Some(&self.meta.name))?;
+                        network.set_node_max_flow(node_name, sub_name.as_deref(), value.into())?;
+                    }
+                }
+
+                // add constraints on aggregated node
+                if let Some(max_flow) = &self.max_flow {
+                    let value = max_flow.load(network, args, Some(&self.meta.name))?;
+                    network.set_aggregated_node_max_flow(node_name, Self::aggregated_node_sub_name(), value.into())?;
+                }
+                if let Some(min_flow) = &self.min_flow {
+                    let value = min_flow.load(network, args, Some(&self.meta.name))?;
+                    network.set_aggregated_node_min_flow(node_name, Self::aggregated_node_sub_name(), value.into())?;
+                }
+            }
             (None, None) => {
                 // soft constraints not added. Set constraints for L only
-                if let Some(cost) = &self.cost {
-                    let value = cost.load(network, args, Some(&self.meta.name))?;
-                    network.set_node_cost(node_name, None, value.into())?;
-                }
+                self.set_cost(network, args, None, &[self.cost.as_ref()], pumping_cost.as_ref())?;
 
                 if let Some(max_flow) = &self.max_flow {
                     let value = max_flow.load(network, args, Some(&self.meta.name))?;
@@ -507,6 +760,11 @@ impl LinkNode {
                     let value = min_flow.load(network, args, Some(&self.meta.name))?;
                     network.set_node_min_flow(node_name, None, value.into())?;
                 }
+
+                if let Some(semi_continuous) = &self.semi_continuous {
+                    let value = semi_continuous.load(network, args, Some(&self.meta.name))?;
+                    network.set_node_semi_continuous_flow(node_name, None, Some(value))?;
+                }
             }
             (Some(soft_min), None) => {
                 // add L_min constraints
@@ -514,16 +772,16 @@ impl LinkNode {
                     let value = soft_min_flow.load(network, args, Some(&self.meta.name))?;
                     network.set_node_max_flow(node_name, Self::soft_min_node_sub_name(), value.into())?;
                 }
-                if let Some(soft_min_cost) = &soft_min.cost {
-                    let value = soft_min_cost.load(network, args, Some(&self.meta.name))?;
-                    network.set_node_cost(node_name, Self::soft_min_node_sub_name(), value.into())?;
-                }
+                self.set_cost(
+                    network,
+                    args,
+                    Self::soft_min_node_sub_name(),
+                    &[soft_min.cost.as_ref()],
+                    pumping_cost.as_ref(),
+                )?;
 
                 // add cost on L
-                if let Some(cost) = &self.cost {
-                    let value = cost.load(network, args, Some(&self.meta.name))?;
-                    network.set_node_cost(node_name, None, value.into())?;
-                }
+                self.set_cost(network, args, None, &[self.cost.as_ref()], pumping_cost.as_ref())?;
 
                 // add constraints on aggregated node
                 if let Some(max_flow) = &self.max_flow {
@@ -537,20 +795,20 @@ impl LinkNode {
             }
             (None, Some(soft_max)) => {
                 // add L_max constraints
-                if let Some(cost) = &self.cost {
-                    let value = cost.load(network, args, Some(&self.meta.name))?;
-                    network.set_node_cost(node_name, Self::soft_max_node_sub_name(), value.into())?;
-                }
+                self.set_cost(
+                    network,
+                    args,
+                    Self::soft_max_node_sub_name(),
+                    &[self.cost.as_ref()],
+                    pumping_cost.as_ref(),
+                )?;
                 if let Some(soft_max_flow) = &soft_max.flow {
                     let value = soft_max_flow.load(network, args, Some(&self.meta.name))?;
                     network.set_node_max_flow(node_name, Self::soft_max_node_sub_name(), value.into())?;
                 }
 
                 // add constraints on L
-                if let Some(soft_max_cost) = &soft_max.cost {
-                    let value = soft_max_cost.load(network, args, Some(&self.meta.name))?;
-                    network.set_node_cost(node_name, None, value.into())?;
-                }
+                self.set_cost(network, args, None, &[soft_max.cost.as_ref()], pumping_cost.as_ref())?;
 
                 // add constraints on aggregated node
                 if let Some(max_flow) = &self.max_flow {
@@ -564,24 +822,27 @@ impl LinkNode {
             }
             (Some(soft_min), Some(soft_max)) => {
                 // set L_max constraint
-                if let Some(soft_max_cost) = &soft_max.cost {
-                    let value = soft_max_cost.load(network, args, Some(&self.meta.name))?;
-                    network.set_node_cost(node_name, Self::soft_max_node_sub_name(), value.into())?;
-                }
+                self.set_cost(
+                    network,
+                    args,
+                    Self::soft_max_node_sub_name(),
+                    &[soft_max.cost.as_ref()],
+                    pumping_cost.as_ref(),
+                )?;
                 // set L constraint
-                if let Some(cost) = &self.cost {
-                    let value = cost.load(network, args, Some(&self.meta.name))?;
-                    network.set_node_cost(node_name, None, value.into())?;
-                }
+                self.set_cost(network, args, None, &[self.cost.as_ref()], pumping_cost.as_ref())?;
                 // set L_min constraints
                 if let Some(soft_min_flow) = &soft_min.flow {
                     let value = soft_min_flow.load(network, args, Some(&self.meta.name))?;
                     network.set_node_max_flow(node_name, Self::soft_min_node_sub_name(), value.into())?;
                 }
-                if let Some(soft_min_cost) = &soft_min.cost {
-                    let value = soft_min_cost.load(network, args, Some(&self.meta.name))?;
-                    network.set_node_cost(node_name, Self::soft_min_node_sub_name(), value.into())?;
-                }
+                self.set_cost(
+                    network,
+                    args,
+                    Self::soft_min_node_sub_name(),
+                    &[soft_min.cost.as_ref()],
+                    pumping_cost.as_ref(),
+                )?;
 
                 // add constraints on node aggregating all three nodes
                 if let Some(max_flow) = &self.max_flow {
@@ -629,7 +890,7 @@ impl LinkNode {
                 })?;
 
         // combine the flow through the nodes
-        let indices = match (&self.soft_min, &self.soft_max) {
+        let mut indices = match (&self.soft_min, &self.soft_max) {
             (Some(_), None) => {
                 let soft_min_node = network
                     .get_node_index_by_name(node_name, Self::soft_min_node_sub_name())
@@ -665,6 +926,7 @@ impl LinkNode {
             }
             (None, None) => vec![link_node],
         };
+        indices.extend(self.additional_tier_node_indices(network)?);
 
         let metric = match attr {
             LinkNodeAttribute::Outflow => MetricF64::MultiNodeInFlow {
@@ -675,6 +937,9 @@ impl LinkNode {
                 indices,
                 name: self.meta.name.to_string(),
             },
+            LinkNodeAttribute::Energy => self.pumping_metric(network, "energy", attr)?,
+            LinkNodeAttribute::EnergyCost => self.pumping_metric(network, "cost", attr)?,
+            LinkNodeAttribute::Carbon => self.pumping_metric(network, "carbon", attr)?,
         };
 
         Ok(metric)
@@ -706,6 +971,9 @@ impl TryFromV1<LinkNodeV1> for LinkNode {
             soft_min,
             soft_max,
             cost,
+            pumping: None,
+            tiered_cost: None,
+            semi_continuous: None,
         };
         Ok(n)
     }
@@ -1320,7 +1588,11 @@ impl TryFromV1<CatchmentNodeV1> for CatchmentNode {
 
 #[cfg(test)]
 mod tests {
+    #[cfg(feature = "core")]
+    use crate::error::SchemaError;
     use crate::nodes::InputNode;
+    #[cfg(feature = "core")]
+    use crate::nodes::LinkNode;
     use crate::nodes::StorageNode;
     use crate::nodes::core::StorageInitialVolume;
 
@@ -1391,4 +1663,28 @@ mod tests {
             StorageInitialVolume::Proportional { proportion: 0.5 }
         );
     }
+
+    #[test]
+    #[cfg(feature = "core")]
+    fn test_link_tiered_cost_not_increasing() {
+        let data = r#"
+            {
+                "meta": {
+                    "name": "link1"
+                },
+                "tiered_cost": [
+                    {"flow": {"type": "Literal", "value": 5.0}, "cost": {"type": "Literal", "value": 2.0}},
+                    {"cost": {"type": "Literal", "value": 1.0}}
+                ]
+            }
+            "#;
+
+        let link: LinkNode = serde_json::from_str(data).unwrap();
+        let mut network = pywr_core::network::Network::default();
+
+        assert!(matches!(
+            link.add_to_model(&mut network),
+            Err(SchemaError::InvalidNodeAttributes { .. })
+        ));
+    }
 }
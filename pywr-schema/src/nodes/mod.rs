@@ -37,6 +37,7 @@
 //! output slots for connecting either the spill, compensation or storage itself.
 //!
 mod abstraction;
+mod aquifer;
 mod attributes;
 mod components;
 mod core;
@@ -45,6 +46,7 @@ mod loss_link;
 mod piecewise_link;
 mod piecewise_storage;
 mod placeholder;
+mod pumping;
 mod reservoir;
 mod river;
 mod river_gauge;
@@ -65,12 +67,15 @@ use crate::parameters::Parameter;
 use crate::v1::{ConversionData, TryFromV1, TryIntoV2};
 use crate::visit::{VisitMetrics, VisitPaths};
 pub use abstraction::AbstractionNode;
+pub use aquifer::{
+    AquiferBaseflow, AquiferCell, AquiferLicence, AquiferNode, AquiferNodeAttribute, AquiferNodeComponent,
+};
 pub use attributes::NodeAttribute;
 pub use components::NodeComponent;
 pub use core::{
-    CatchmentNode, CatchmentNodeAttribute, CatchmentNodeComponent, InputNode, InputNodeAttribute, InputNodeComponent,
-    LinkNode, LinkNodeAttribute, LinkNodeComponent, OutputNode, OutputNodeAttribute, OutputNodeComponent,
-    SoftConstraint, StorageInitialVolume, StorageNode, StorageNodeAttribute,
+    CatchmentNode, CatchmentNodeAttribute, CatchmentNodeComponent, CostTier, InputNode, InputNodeAttribute,
+    InputNodeComponent, LinkNode, LinkNodeAttribute, LinkNodeComponent, OutputNode, OutputNodeAttribute,
+    OutputNodeComponent, SemiContinuousFlow, SoftConstraint, StorageInitialVolume, StorageNode, StorageNodeAttribute,
 };
 pub use delay::{DelayNode, DelayNodeAttribute, DelayNodeComponent};
 pub use loss_link::{LossFactor, LossLinkNode, LossLinkNodeAttribute, LossLinkNodeComponent};
@@ -79,6 +84,7 @@ pub use piecewise_link::{
 };
 pub use piecewise_storage::{PiecewiseStorageNode, PiecewiseStorageNodeAttribute, PiecewiseStore};
 pub use placeholder::PlaceholderNode;
+pub use pumping::Pumping;
 #[cfg(feature = "core")]
 use pywr_core::metric::MetricF64;
 use pywr_schema_macros::PywrVisitAll;
@@ -87,7 +93,7 @@ use pywr_v1_schema::nodes::{
 };
 pub use reservoir::{
     Bathymetry, BathymetryType, Evaporation, Leakage, Rainfall, ReservoirNode, ReservoirNodeAttribute,
-    ReservoirNodeComponent, SpillNodeType,
+    ReservoirNodeComponent, SpillNodeType, SurfaceAreaMethod,
 };
 pub use river::{MuskingumInitialCondition, RiverNode, RiverNodeAttribute, RiverNodeComponent, RoutingMethod};
 pub use river_gauge::{RiverGaugeNode, RiverGaugeNodeAttribute, RiverGaugeNodeComponent};
@@ -272,6 +278,10 @@ impl NodeBuilder {
                 meta,
                 ..Default::default()
             }),
+            NodeType::Aquifer => Node::Aquifer(AquiferNode {
+                meta,
+                ..Default::default()
+            }),
         }
     }
 }
@@ -302,6 +312,7 @@ pub enum Node {
     Reservoir(ReservoirNode),
     Placeholder(PlaceholderNode),
     Abstraction(AbstractionNode),
+    Aquifer(AquiferNode),
 }
 
 impl Node {
@@ -337,6 +348,7 @@ impl Node {
             Node::Reservoir(n) => n.meta(),
             Node::Placeholder(n) => &n.meta,
             Node::Abstraction(n) => &n.meta,
+            Node::Aquifer(n) => &n.meta,
         }
     }
 
@@ -369,6 +381,7 @@ impl Node {
             // Deliberately do not take a slot for Placeholder nodes so they can be used with any slot
             Node::Placeholder(n) => n.input_connectors(),
             Node::Abstraction(n) => n.input_connectors(slot),
+            Node::Aquifer(n) => n.input_connectors(slot),
         }
     }
 
@@ -392,6 +405,7 @@ impl Node {
             Node::Reservoir(_) => None,
             Node::Placeholder(_) => None,
             Node::Abstraction(_) => None,
+            Node::Aquifer(_) => None,
         }
     }
 
@@ -424,6 +438,7 @@ impl Node {
             // Deliberately do not take a slot for Placeholder nodes so they can be used with any slot
             Node::Placeholder(n) => n.output_connectors(),
             Node::Abstraction(n) => n.output_connectors(slot),
+            Node::Aquifer(n) => n.output_connectors(slot),
         }
     }
 
@@ -447,6 +462,7 @@ impl Node {
             Node::Reservoir(n) => Some(Box::new(n.iter_output_slots())),
             Node::Placeholder(_) => None,
             Node::Abstraction(n) => Some(Box::new(n.iter_output_slots())),
+            Node::Aquifer(n) => Some(Box::new(n.iter_output_slots())),
         }
     }
 
@@ -469,6 +485,7 @@ impl Node {
             Node::Reservoir(n) => n.default_attribute().into(),
             Node::Placeholder(n) => n.default_attribute(),
             Node::Abstraction(n) => n.default_attribute().into(),
+            Node::Aquifer(n) => n.default_attribute().into(),
         }
     }
 
@@ -492,6 +509,7 @@ impl Node {
             Node::Reservoir(n) => Some(n.default_component().into()),
             Node::Placeholder(_) => None,
             Node::Abstraction(n) => Some(n.default_component().into()),
+            Node::Aquifer(n) => Some(n.default_component().into()),
         }
     }
 
@@ -518,6 +536,7 @@ impl Node {
             Node::Reservoir(n) => n.storage.parameters.as_deref(),
             Node::Placeholder(_) => None,
             Node::Abstraction(n) => n.parameters.as_deref(),
+            Node::Aquifer(n) => n.parameters.as_deref(),
         }
     }
 }
@@ -543,6 +562,7 @@ impl Node {
             Node::Reservoir(n) => n.add_to_model(network),
             Node::Placeholder(n) => n.add_to_model(),
             Node::Abstraction(n) => n.add_to_model(network),
+            Node::Aquifer(n) => n.add_to_model(network),
         }
     }
 
@@ -578,6 +598,7 @@ impl Node {
             Node::Reservoir(n) => n.node_indices_for_flow_constraints(network, component),
             Node::Placeholder(n) => n.node_indices_for_flow_constraints(),
             Node::Abstraction(n) => n.node_indices_for_flow_constraints(network, component),
+            Node::Aquifer(n) => n.node_indices_for_flow_constraints(network, component),
         }
     }
 
@@ -609,6 +630,7 @@ impl Node {
             Node::Reservoir(n) => n.node_indices_for_storage_constraints(network),
             Node::Placeholder(n) => n.node_indices_for_storage_constraints(),
             Node::Abstraction(_) => Err(SchemaError::NodeNotAllowedInStorageConstraint),
+            Node::Aquifer(n) => n.node_indices_for_storage_constraints(network),
         }
     }
 
@@ -635,6 +657,7 @@ impl Node {
             Node::Reservoir(n) => n.set_constraints(network, args),
             Node::Placeholder(n) => n.set_constraints(),
             Node::Abstraction(n) => n.set_constraints(network, args),
+            Node::Aquifer(n) => n.set_constraints(network, args),
         }
     }
 
@@ -662,6 +685,7 @@ impl Node {
             Node::Reservoir(n) => n.create_metric(network, attribute),
             Node::Placeholder(n) => n.create_metric(),
             Node::Abstraction(n) => n.create_metric(network, attribute),
+            Node::Aquifer(n) => n.create_metric(network, attribute),
         }
     }
 }
@@ -784,6 +808,7 @@ impl VisitMetrics for Node {
             Node::Reservoir(n) => n.visit_metrics(visitor),
             Node::Placeholder(n) => n.visit_metrics(visitor),
             Node::Abstraction(n) => n.visit_metrics(visitor),
+            Node::Aquifer(n) => n.visit_metrics(visitor),
         }
     }
 
@@ -806,6 +831,7 @@ impl VisitMetrics for Node {
             Node::Reservoir(n) => n.visit_metrics_mut(visitor),
             Node::Placeholder(n) => n.visit_metrics_mut(visitor),
             Node::Abstraction(n) => n.visit_metrics_mut(visitor),
+            Node::Aquifer(n) => n.visit_metrics_mut(visitor),
         }
     }
 }
@@ -830,6 +856,7 @@ impl VisitPaths for Node {
             Node::Reservoir(n) => n.visit_paths(visitor),
             Node::Placeholder(n) => n.visit_paths(visitor),
             Node::Abstraction(n) => n.visit_paths(visitor),
+            Node::Aquifer(n) => n.visit_paths(visitor),
         }
     }
 
@@ -852,6 +879,7 @@ impl VisitPaths for Node {
             Node::Reservoir(n) => n.visit_paths_mut(visitor),
             Node::Placeholder(n) => n.visit_paths_mut(visitor),
             Node::Abstraction(n) => n.visit_paths_mut(visitor),
+            Node::Aquifer(n) => n.visit_paths_mut(visitor),
         }
     }
 }
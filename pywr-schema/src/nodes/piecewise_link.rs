@@ -5,7 +5,7 @@ use crate::metric::Metric;
 use crate::network::LoadArgs;
 #[cfg(feature = "core")]
 use crate::nodes::{NodeAttribute, NodeComponent};
-use crate::nodes::{NodeMeta, NodeSlot};
+use crate::nodes::{NodeMeta, NodeSlot, Pumping};
 use crate::parameters::Parameter;
 use crate::v1::{ConversionData, TryFromV1, try_convert_node_attr, try_convert_node_meta};
 use crate::{mermaid, node_attribute_subset_enum, node_component_subset_enum};
@@ -30,6 +30,12 @@ node_attribute_subset_enum! {
     pub enum PiecewiseLinkNodeAttribute {
         Inflow,
         Outflow,
+        /// The energy used pumping. Requires `pumping` to be defined.
+        Energy,
+        /// The cost of the energy used pumping. Requires `pumping` to be defined.
+        EnergyCost,
+        /// The carbon emitted pumping. Requires `pumping` to be defined.
+        Carbon,
     }
 }
 
@@ -47,6 +53,10 @@ node_component_subset_enum! {
 ///
 #[doc = mermaid!("doc_diagrams/piecewise.mmd")]
 ///
+/// If the link represents a pumped transfer, the optional [`Pumping`] component accounts for the
+/// energy, cost and carbon emissions of the total flow through the steps. If the pumping cost is
+/// included it is added to the cost of every step.
+///
 /// # Available attributes and components
 ///
 /// The enums [`PiecewiseLinkNodeAttribute`] and [`PiecewiseLinkNodeComponent`] define the available
@@ -60,6 +70,8 @@ pub struct PiecewiseLinkNode {
     /// Optional local parameters.
     pub parameters: Option<Vec<Parameter>>,
     pub steps: Vec<PiecewiseLinkStep>,
+    /// Optional energy accounting for pumping the flow through the node.
+    pub pumping: Option<Pumping>,
 }
 
 impl PiecewiseLinkNode {
@@ -147,12 +159,34 @@ impl PiecewiseLinkNode {
         network: &mut pywr_core::network::Network,
         args: &LoadArgs,
     ) -> Result<(), SchemaError> {
+        let pumping = match &self.pumping {
+            Some(pumping) => {
+                let flow = MetricF64::MultiNodeInFlow {
+                    indices: self.node_indices_for_flow_constraints(network, None)?,
+                    name: self.meta.name.to_string(),
+                };
+                let idx = pumping.add_to_model(network, args, self.meta.name.as_str(), flow)?;
+                Some((pumping, idx))
+            }
+            None => None,
+        };
+
         for (i, step) in self.steps.iter().enumerate() {
             let sub_name = Self::step_sub_name(i);
 
-            if let Some(cost) = &step.cost {
-                let value = cost.load(network, args, Some(&self.meta.name))?;
-                network.set_node_cost(self.meta.name.as_str(), sub_name.as_deref(), value.into())?;
+            let cost = step
+                .cost
+                .as_ref()
+                .map(|c| c.load(network, args, Some(&self.meta.name)))
+                .transpose()?;
+            let cost = match &pumping {
+                Some((pumping, idx)) => {
+                    pumping.total_cost(network, idx, self.meta.name.as_str(), sub_name.as_deref(), cost)?
+                }
+                None => cost,
+            };
+            if let Some(cost) = cost {
+                network.set_node_cost(self.meta.name.as_str(), sub_name.as_deref(), cost.into())?;
             }
 
             if let Some(max_flow) = &step.max_flow {
@@ -179,33 +213,35 @@ impl PiecewiseLinkNode {
             None => Self::DEFAULT_ATTRIBUTE,
         };
 
-        let indices = self
-            .steps
-            .iter()
-            .enumerate()
-            .map(|(i, _)| {
-                network
-                    .get_node_index_by_name(self.meta.name.as_str(), Self::step_sub_name(i).as_deref())
-                    .ok_or_else(|| SchemaError::CoreNodeNotFound {
-                        name: self.meta.name.clone(),
-                        sub_name: Self::step_sub_name(i),
-                    })
-            })
-            .collect::<Result<Vec<_>, _>>()?;
-
         let metric = match attr {
             PiecewiseLinkNodeAttribute::Inflow => MetricF64::MultiNodeInFlow {
-                indices,
+                indices: self.node_indices_for_flow_constraints(network, None)?,
                 name: self.meta.name.to_string(),
             },
             PiecewiseLinkNodeAttribute::Outflow => MetricF64::MultiNodeOutFlow {
-                indices,
+                indices: self.node_indices_for_flow_constraints(network, None)?,
                 name: self.meta.name.to_string(),
             },
+            PiecewiseLinkNodeAttribute::Energy => self.pumping_metric(network, "energy", attr)?,
+            PiecewiseLinkNodeAttribute::EnergyCost => self.pumping_metric(network, "cost", attr)?,
+            PiecewiseLinkNodeAttribute::Carbon => self.pumping_metric(network, "carbon", attr)?,
         };
 
         Ok(metric)
     }
+
+    /// Create a metric for one of the pumping values; this errors if `pumping` is not defined.
+    fn pumping_metric(
+        &self,
+        network: &pywr_core::network::Network,
+        key: &str,
+        attr: PiecewiseLinkNodeAttribute,
+    ) -> Result<MetricF64, SchemaError> {
+        if self.pumping.is_none() {
+            return Err(SchemaError::NodeAttributeNotSupported { attr: attr.into() });
+        }
+        Pumping::create_metric(network, self.meta.name.as_str(), key)
+    }
 }
 
 impl TryFromV1<PiecewiseLinkNodeV1> for PiecewiseLinkNode {
@@ -262,6 +298,7 @@ impl TryFromV1<PiecewiseLinkNodeV1> for PiecewiseLinkNode {
             meta,
             parameters: None,
             steps,
+            pumping: None,
         };
         Ok(n)
     }
@@ -0,0 +1,144 @@
+#[cfg(feature = "core")]
+use crate::SchemaError;
+use crate::metric::Metric;
+#[cfg(feature = "core")]
+use crate::network::LoadArgs;
+#[cfg(feature = "core")]
+use pywr_core::{
+    metric::MetricF64,
+    parameters::{AggregatedParameter, ParameterIndex, ParameterName, PumpingData, PumpingEnergyParameter},
+    state::MultiValue,
+};
+use pywr_schema_macros::{PywrVisitAll, skip_serializing_none};
+use schemars::JsonSchema;
+
+/// Energy and carbon accounting for flow that is pumped through a node.
+///
+/// The energy required to pump the flow is calculated from the `head` and `efficiency` of the
+/// pump. The energy used each time-step is converted to a cost using the optional `tariff` (e.g.
+/// a time-of-use tariff timeseries) and to carbon emissions using the optional `carbon_intensity`.
+/// These are available from the node as the `Energy`, `EnergyCost` and `Carbon` attributes.
+///
+/// If `include_cost` is `true` the cost of pumping a unit of flow is added to the node's cost
+/// in the linear program. This allows the model to prefer sources that are cheaper to pump.
+#[skip_serializing_none]
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, JsonSchema, PywrVisitAll)]
+#[serde(deny_unknown_fields)]
+pub struct Pumping {
+    /// The head the flow is pumped against.
+    pub head: Metric,
+    /// The efficiency of the pump. Default to `1.0`.
+    pub efficiency: Option<f64>,
+    /// The cost per unit of energy.
+    pub tariff: Option<Metric>,
+    /// The carbon emissions per unit of energy.
+    pub carbon_intensity: Option<Metric>,
+    /// Whether to add the cost of pumping to the node's cost. Default to `false`.
+    pub include_cost: Option<bool>,
+    /// The density of water. Default to `1000.0`.
+    pub water_density: Option<f64>,
+    /// A factor used to transform the units of flow to be compatible with the energy equation.
+    /// This should convert flow to units of m<sup>3</sup> day<sup>-1</sup>. Default to `1.0`.
+    pub flow_unit_conversion: Option<f64>,
+    /// A factor used to transform the units of total energy. Defaults to 1e<sup>-6</sup> to
+    /// return `MJ`.
+    pub energy_unit_conversion: Option<f64>,
+}
+
+#[cfg(feature = "core")]
+impl Pumping {
+    fn parameter_name(node_name: &str) -> ParameterName {
+        ParameterName::new("pumping", Some(node_name))
+    }
+
+    /// Add the pumping energy parameter for the node `node_name` to the network.
+    ///
+    /// The `flow` metric should be the total flow pumped by the node.
+    pub fn add_to_model(
+        &self,
+        network: &mut pywr_core::network::Network,
+        args: &LoadArgs,
+        node_name: &str,
+        flow: MetricF64,
+    ) -> Result<ParameterIndex<MultiValue>, SchemaError> {
+        let pumping_data = PumpingData {
+            flow,
+            head: self.head.load(network, args, Some(node_name))?,
+            efficiency: self.efficiency,
+            tariff: self
+                .tariff
+                .as_ref()
+                .map(|t| t.load(network, args, Some(node_name)))
+                .transpose()?,
+            carbon_intensity: self
+                .carbon_intensity
+                .as_ref()
+                .map(|c| c.load(network, args, Some(node_name)))
+                .transpose()?,
+            water_density: self.water_density,
+            flow_unit_conversion: self.flow_unit_conversion,
+            energy_unit_conversion: self.energy_unit_conversion,
+        };
+
+        let p = PumpingEnergyParameter::new(Self::parameter_name(node_name), pumping_data);
+        Ok(network.add_multi_value_parameter(Box::new(p))?)
+    }
+
+    /// The cost of pumping a unit of flow, if it should be included in the node's cost.
+    pub fn unit_cost(&self, idx: &ParameterIndex<MultiValue>) -> Option<MetricF64> {
+        if self.include_cost.unwrap_or(false) {
+            Some(idx.clone().into_metric_f64_before("cost_intensity"))
+        } else {
+            None
+        }
+    }
+
+    /// The total of the node's `cost` and the cost of pumping a unit of flow, if it should be
+    /// included in the node's cost.
+    ///
+    /// If both are defined a parameter is added to sum them, named `{sub_name}-total-cost` (or
+    /// `total-cost` without a `sub_name`).
+    pub fn total_cost(
+        &self,
+        network: &mut pywr_core::network::Network,
+        idx: &ParameterIndex<MultiValue>,
+        node_name: &str,
+        sub_name: Option<&str>,
+        cost: Option<MetricF64>,
+    ) -> Result<Option<MetricF64>, SchemaError> {
+        let total = match (cost, self.unit_cost(idx)) {
+            (Some(cost), Some(unit_cost)) => {
+                let name = match sub_name {
+                    Some(sub_name) => format!("{sub_name}-total-cost"),
+                    None => "total-cost".to_string(),
+                };
+                let p = AggregatedParameter::new(
+                    ParameterName::new(&name, Some(node_name)),
+                    &[cost, unit_cost],
+                    pywr_core::agg_funcs::AggFuncF64::Sum,
+                );
+                Some(network.add_parameter(Box::new(p))?.into_metric_f64_before())
+            }
+            (cost, unit_cost) => cost.or(unit_cost),
+        };
+
+        Ok(total)
+    }
+
+    /// Create a metric for one of the values (e.g. `energy`) of the node's pumping parameter.
+    pub fn create_metric(
+        network: &pywr_core::network::Network,
+        node_name: &str,
+        key: &str,
+    ) -> Result<MetricF64, SchemaError> {
+        let name = Self::parameter_name(node_name);
+        let idx = network.get_multi_valued_parameter_index_by_name(&name).ok_or_else(|| {
+            SchemaError::CoreParameterNotFound {
+                name: name.to_string(),
+                key: Some(key.to_string()),
+            }
+        })?;
+
+        Ok(idx.into_metric_f64(key, pywr_core::state::ParameterReturnValue::After))
+    }
+}
@@ -10,13 +10,17 @@ use crate::{node_attribute_subset_enum, node_component_subset_enum};
 #[cfg(feature = "core")]
 use pywr_core::agg_funcs::AggFuncF64;
 #[cfg(feature = "core")]
+use pywr_core::aggregated_node::Relationship;
+#[cfg(feature = "core")]
 use pywr_core::metric::ConstantMetricF64::Constant;
 #[cfg(feature = "core")]
 use pywr_core::metric::MetricF64;
 #[cfg(feature = "core")]
 use pywr_core::metric::SimpleMetricF64;
 #[cfg(feature = "core")]
-use pywr_core::parameters::ParameterName;
+use pywr_core::node::NodeIndex;
+#[cfg(feature = "core")]
+use pywr_core::parameters::{FlowVolumeParameter, ParameterName, SurfaceAreaCurve, SurfaceAreaParameter};
 use pywr_schema_macros::{PywrVisitAll, skip_serializing_none};
 use schemars::JsonSchema;
 
@@ -50,6 +54,18 @@ pub struct Bathymetry {
     pub is_storage_proportional: bool,
 }
 
+/// How the surface area used for the rainfall and evaporation calculations is determined.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Debug, Default, JsonSchema, PywrVisitAll)]
+pub enum SurfaceAreaMethod {
+    /// The area is calculated from the volume at the start of the time-step.
+    #[default]
+    StartOfTimestep,
+    /// The area is linearised about the volume at the start of the time-step and the rainfall
+    /// and evaporation are related to the net inflow in the linear program. The flows are then
+    /// consistent with the volume at the end of the time-step.
+    Linearised,
+}
+
 /// The evaporation data
 #[derive(serde::Deserialize, serde::Serialize, Clone, Debug, JsonSchema, PywrVisitAll)]
 pub struct Evaporation {
@@ -96,6 +112,14 @@ node_attribute_subset_enum! {
         Rainfall,
         /// The evaporation flow when the `evaporation` field is provided.
         Evaporation,
+        /// The leakage flow when the `leakage` field is provided.
+        Loss,
+        /// The rainfall volume during the time-step.
+        RainfallVolume,
+        /// The evaporation volume during the time-step.
+        EvaporationVolume,
+        /// The leakage volume during the time-step.
+        LossVolume,
     }
 }
 
@@ -195,6 +219,21 @@ impl TryFrom<NodeSlot> for ReservoirOutputNodeSlot {
 ///
 /// If `rainfall.use_max_area` is set to `true`, then the rainfall volume is calculated using the
 /// maximum surface area only.
+///
+/// By default the area is calculated from the volume at the start of the time-step. When
+/// `surface_area_method` is [`SurfaceAreaMethod::Linearised`] the area ($A$) is instead linearised
+/// about the start volume ($V_0$) and the rainfall or evaporation flow ($Q$) for a depth ($D$) is
+/// constrained in the linear program to be consistent with the end volume ($V_1$):
+///
+/// $$
+/// Q = D \left( A(V_0) + \frac{dA}{dV} (V_1 - V_0) \right)
+/// $$
+///
+/// This is exact for a linear storage-area relationship. As with the other method, the rainfall flow
+/// is fixed and the evaporation flow is only limited to this value, so a cost may be needed to
+/// incentivise evaporation. Flows that use the maximum area are not linearised. This method
+/// requires a solver that supports aggregated nodes with dynamic factors.
+///
 /// It is up to the user to ensure that the units for these calculations are consistent. The
 /// units for the rainfall and evaporation depth ($D$) must be consistent with those of the area ($A$)
 /// to produce a flow ($Q$) consistent with the model's inflows
@@ -238,6 +277,8 @@ pub struct ReservoirNode {
     /// The storage table with the relationship between storage and reservoir surface area. This must
     /// be provided for the calculations of the precipitation and evaporation volumes.
     pub surface_area: Option<Bathymetry>,
+    /// How the surface area is calculated. Defaults to [`SurfaceAreaMethod::StartOfTimestep`].
+    pub surface_area_method: Option<SurfaceAreaMethod>,
     /// The rainfall data. Use `None` not to add the rainfall node.
     pub rainfall: Option<Rainfall>,
     /// The evaporation data. Use `None` not to add the evaporation node.
@@ -339,6 +380,24 @@ impl ReservoirNode {
         Some("leakage")
     }
 
+    /// The sub-name of the aggregated node relating the rainfall to the net inflow.
+    fn rainfall_area_node_sub_name() -> Option<&'static str> {
+        Some("rainfall_area")
+    }
+
+    /// The sub-name of the aggregated node relating the evaporation to the net inflow.
+    fn evaporation_area_node_sub_name() -> Option<&'static str> {
+        Some("evaporation_area")
+    }
+
+    /// Whether the area used by a flow with the given `use_max_area` option is linearised.
+    fn is_area_linearised(&self, use_max_area: Option<bool>) -> bool {
+        matches!(
+            self.surface_area_method.unwrap_or_default(),
+            SurfaceAreaMethod::Linearised
+        ) && !use_max_area.unwrap_or(false)
+    }
+
     pub fn node_indices_for_flow_constraints(
         &self,
         network: &pywr_core::network::Network,
@@ -452,6 +511,15 @@ impl ReservoirNode {
 
             let rainfall = network.add_input_node(self.meta().name.as_str(), Self::rainfall_node_sub_name())?;
             network.connect_nodes(rainfall, storage)?;
+
+            if self.is_area_linearised(self.rainfall.as_ref().and_then(|r| r.use_max_area)) {
+                network.add_aggregated_node(
+                    self.meta().name.as_str(),
+                    Self::rainfall_area_node_sub_name(),
+                    &[vec![rainfall], vec![storage]],
+                    None,
+                )?;
+            }
         }
 
         // add evaporation node and edge
@@ -465,6 +533,15 @@ impl ReservoirNode {
 
             let evaporation = network.add_output_node(self.meta().name.as_str(), Self::evaporation_node_sub_name())?;
             network.connect_nodes(storage, evaporation)?;
+
+            if self.is_area_linearised(self.evaporation.as_ref().and_then(|e| e.use_max_area)) {
+                network.add_aggregated_node(
+                    self.meta().name.as_str(),
+                    Self::evaporation_area_node_sub_name(),
+                    &[vec![evaporation], vec![storage]],
+                    None,
+                )?;
+            }
         }
 
         // add leakage node and edge
@@ -507,52 +584,78 @@ impl ReservoirNode {
         if let Some(bathymetry) = &self.surface_area {
             // add the rainfall
             if let Some(rainfall) = &self.rainfall {
-                let use_max_area = rainfall.use_max_area.unwrap_or(false);
-                let rainfall_area_metric =
-                    self.get_area_metric(network, args, "rainfall_area", bathymetry, use_max_area)?;
                 let rainfall_metric = rainfall.data.load(network, args, Some(&self.meta().name))?;
 
-                let rainfall_flow_parameter = pywr_core::parameters::AggregatedParameter::new(
-                    ParameterName::new("rainfall", Some(self.meta().name.as_str())),
-                    &[rainfall_metric, rainfall_area_metric],
-                    AggFuncF64::Product,
-                );
-                let rainfall_idx = network.add_parameter(Box::new(rainfall_flow_parameter))?;
-                let rainfall_flow_metric: MetricF64 = rainfall_idx.into_metric_f64_before();
-
-                network.set_node_min_flow(
-                    self.meta().name.as_str(),
-                    Self::rainfall_node_sub_name(),
-                    Some(rainfall_flow_metric.clone()),
-                )?;
-                network.set_node_max_flow(
-                    self.meta().name.as_str(),
-                    Self::rainfall_node_sub_name(),
-                    Some(rainfall_flow_metric),
-                )?;
+                if self.is_area_linearised(rainfall.use_max_area) {
+                    self.set_linearised_flow(
+                        network,
+                        args,
+                        "rainfall",
+                        bathymetry,
+                        rainfall_metric,
+                        Self::rainfall_area_node_sub_name(),
+                        false,
+                    )?;
+                } else {
+                    let use_max_area = rainfall.use_max_area.unwrap_or(false);
+                    let rainfall_area_metric =
+                        self.get_area_metric(network, args, "rainfall_area", bathymetry, use_max_area)?;
+
+                    let rainfall_flow_parameter = pywr_core::parameters::AggregatedParameter::new(
+                        ParameterName::new("rainfall", Some(self.meta().name.as_str())),
+                        &[rainfall_metric, rainfall_area_metric],
+                        AggFuncF64::Product,
+                    );
+                    let rainfall_idx = network.add_parameter(Box::new(rainfall_flow_parameter))?;
+                    let rainfall_flow_metric: MetricF64 = rainfall_idx.into_metric_f64_before();
+
+                    network.set_node_min_flow(
+                        self.meta().name.as_str(),
+                        Self::rainfall_node_sub_name(),
+                        Some(rainfall_flow_metric.clone()),
+                    )?;
+                    network.set_node_max_flow(
+                        self.meta().name.as_str(),
+                        Self::rainfall_node_sub_name(),
+                        Some(rainfall_flow_metric),
+                    )?;
+                }
             }
 
             // add the evaporation
             if let Some(evaporation) = &self.evaporation {
-                let use_max_area = evaporation.use_max_area.unwrap_or(false);
-                let evaporation_area_metric =
-                    self.get_area_metric(network, args, "evaporation_area", bathymetry, use_max_area)?;
-
-                // add volume to output node
                 let evaporation_metric = evaporation.data.load(network, args, Some(&self.meta().name))?;
-                let evaporation_flow_parameter = pywr_core::parameters::AggregatedParameter::new(
-                    ParameterName::new("evaporation", Some(self.meta().name.as_str())),
-                    &[evaporation_metric, evaporation_area_metric],
-                    AggFuncF64::Product,
-                );
-                let evaporation_idx = network.add_parameter(Box::new(evaporation_flow_parameter))?;
-                let evaporation_flow_metric: MetricF64 = evaporation_idx.into_metric_f64_before();
 
-                network.set_node_max_flow(
-                    self.meta().name.as_str(),
-                    Self::evaporation_node_sub_name(),
-                    Some(evaporation_flow_metric),
-                )?;
+                if self.is_area_linearised(evaporation.use_max_area) {
+                    self.set_linearised_flow(
+                        network,
+                        args,
+                        "evaporation",
+                        bathymetry,
+                        evaporation_metric,
+                        Self::evaporation_area_node_sub_name(),
+                        true,
+                    )?;
+                } else {
+                    let use_max_area = evaporation.use_max_area.unwrap_or(false);
+                    let evaporation_area_metric =
+                        self.get_area_metric(network, args, "evaporation_area", bathymetry, use_max_area)?;
+
+                    // add volume to output node
+                    let evaporation_flow_parameter = pywr_core::parameters::AggregatedParameter::new(
+                        ParameterName::new("evaporation", Some(self.meta().name.as_str())),
+                        &[evaporation_metric, evaporation_area_metric],
+                        AggFuncF64::Product,
+                    );
+                    let evaporation_idx = network.add_parameter(Box::new(evaporation_flow_parameter))?;
+                    let evaporation_flow_metric: MetricF64 = evaporation_idx.into_metric_f64_before();
+
+                    network.set_node_max_flow(
+                        self.meta().name.as_str(),
+                        Self::evaporation_node_sub_name(),
+                        Some(evaporation_flow_metric),
+                    )?;
+                }
 
                 // set optional cost
                 if let Some(cost) = &evaporation.cost {
@@ -624,7 +727,8 @@ impl ReservoirNode {
                     ParameterName::new(name, Some(self.meta().name.as_str())),
                     current_storage,
                     points,
-                    true,
+                    pywr_core::parameters::InterpolationKind::Linear,
+                    pywr_core::parameters::Extrapolation::Error,
                 );
                 let area_idx = network.add_parameter(Box::new(interpolated_area_parameter))?;
                 let interpolated_area_metric: MetricF64 = area_idx.into_metric_f64_before();
@@ -647,6 +751,126 @@ impl ReservoirNode {
         Ok(area_metric)
     }
 
+    /// Constrain a rainfall or evaporation flow using the linearised surface area. See
+    /// [`SurfaceAreaMethod::Linearised`].
+    ///
+    /// The flow's node and the storage node are in an aggregated node, which is given the
+    /// relationship $Q - D \frac{dA}{dV} \Delta t N = D A(V_0)$ where $N$ is the net inflow to the
+    /// storage node and $\Delta t$ the length of the time-step.
+    ///
+    /// # Arguments
+    ///
+    /// * `network`: The network.
+    /// * `args`: The arguments.
+    /// * `name`: The name of the flow (e.g. "evaporation") used to name the created parameters.
+    /// * `bathymetry`: The bathymetric data.
+    /// * `depth`: The rainfall or evaporation depth.
+    /// * `agg_sub_name`: The sub-name of the aggregated node.
+    /// * `is_upper_bound`: Whether the linearised flow is a maximum rather than a fixed flow.
+    ///
+    /// returns: `Result<(), SchemaError>`
+    #[allow(clippy::too_many_arguments)]
+    fn set_linearised_flow(
+        &self,
+        network: &mut pywr_core::network::Network,
+        args: &LoadArgs,
+        name: &str,
+        bathymetry: &Bathymetry,
+        depth: MetricF64,
+        agg_sub_name: Option<&str>,
+        is_upper_bound: bool,
+    ) -> Result<(), SchemaError> {
+        let parent = Some(self.meta().name.as_str());
+        let storage_node = network
+            .get_node_index_by_name(self.meta().name.as_str(), None)
+            .ok_or_else(|| SchemaError::CoreNodeNotFound {
+                name: self.meta().name.clone(),
+                sub_name: None,
+            })?;
+
+        let (storage, max_volume) = if bathymetry.is_storage_proportional {
+            (
+                MetricF64::NodeProportionalVolume(storage_node),
+                Some(MetricF64::NodeMaxVolume(storage_node)),
+            )
+        } else {
+            (MetricF64::NodeVolume(storage_node), None)
+        };
+
+        let curve = match &bathymetry.data {
+            BathymetryType::Interpolated { storage, area } => {
+                let storage = storage.load(args.tables)?;
+                let area = area.load(args.tables)?;
+                SurfaceAreaCurve::Interpolated(storage.into_iter().zip(area).collect())
+            }
+            BathymetryType::Polynomial(coeffs) => SurfaceAreaCurve::Polynomial(coeffs.clone()),
+        };
+
+        let area_parameter = SurfaceAreaParameter::new(
+            ParameterName::new(&format!("{name}_area"), parent),
+            storage,
+            max_volume,
+            curve,
+        );
+        let area_idx = network.add_multi_value_parameter(Box::new(area_parameter))?;
+
+        // the flow using the area at the start of the time-step
+        let flow_parameter = pywr_core::parameters::AggregatedParameter::new(
+            ParameterName::new(name, parent),
+            &[depth.clone(), area_idx.clone().into_metric_f64_before("area")],
+            AggFuncF64::Product,
+        );
+        let flow_metric = network
+            .add_parameter(Box::new(flow_parameter))?
+            .into_metric_f64_before();
+
+        // the change in flow per unit of net inflow to the storage
+        let gradient_parameter = pywr_core::parameters::AggregatedParameter::new(
+            ParameterName::new(&format!("{name}_gradient"), parent),
+            &[depth, area_idx.into_metric_f64_before("flow_gradient"), (-1.0).into()],
+            AggFuncF64::Product,
+        );
+        let gradient_metric = network
+            .add_parameter(Box::new(gradient_parameter))?
+            .into_metric_f64_before();
+
+        // rainfall is fixed, whereas evaporation is limited like the non-linearised flow
+        let factors = [1.0.into(), gradient_metric];
+        let relationship = if is_upper_bound {
+            Relationship::new_coefficient_upper_bound_factors(&factors, Some(flow_metric))
+        } else {
+            Relationship::new_coefficient_factors(&factors, Some(flow_metric))
+        };
+        network.set_aggregated_node_relationship(self.meta().name.as_str(), agg_sub_name, Some(relationship))?;
+
+        Ok(())
+    }
+
+    /// Get the metric for the volume of a flow during the time-step. The parameter calculating the
+    /// volume is created the first time the metric is requested.
+    fn get_flow_volume_metric(
+        &self,
+        network: &mut pywr_core::network::Network,
+        name: &str,
+        sub_name: Option<&str>,
+        flow: fn(NodeIndex) -> MetricF64,
+    ) -> Result<MetricF64, SchemaError> {
+        let Some(idx) = network.get_node_index_by_name(self.meta().name.as_str(), sub_name) else {
+            return Ok(0.0.into());
+        };
+
+        let parameter_name = ParameterName::new(name, Some(self.meta().name.as_str()));
+        let parameter_idx = match network.get_parameter_index_by_name(&parameter_name) {
+            Some(p_idx) => p_idx,
+            None => {
+                let parameter = FlowVolumeParameter::new(parameter_name, flow(idx));
+                network.add_parameter(Box::new(parameter))?
+            }
+        };
+
+        Ok(parameter_idx.into_metric_f64_after())
+    }
+
     pub fn create_metric(
         &self,
         network: &mut pywr_core::network::Network,
@@ -670,15 +894,39 @@ impl ReservoirNode {
                     ReservoirNodeAttribute::Rainfall => match network
                         .get_node_index_by_name(self.meta().name.as_str(), Self::rainfall_node_sub_name())
                     {
-                        Some(idx) => MetricF64::NodeInFlow(idx),
+                        Some(idx) => MetricF64::NodeOutFlow(idx),
                         None => 0.0.into(),
                     },
                     ReservoirNodeAttribute::Evaporation => match network
-                        .get_node_index_by_name(self.meta().name.as_str(), Self::rainfall_node_sub_name())
+                        .get_node_index_by_name(self.meta().name.as_str(), Self::evaporation_node_sub_name())
                     {
                         Some(idx) => MetricF64::NodeInFlow(idx),
                         None => 0.0.into(),
                     },
+                    ReservoirNodeAttribute::Loss => {
+                        match network.get_node_index_by_name(self.meta().name.as_str(), Self::leakage_node_sub_name()) {
+                            Some(idx) => MetricF64::NodeInFlow(idx),
+                            None => 0.0.into(),
+                        }
+                    }
+                    ReservoirNodeAttribute::RainfallVolume => self.get_flow_volume_metric(
+                        network,
+                        "rainfall_volume",
+                        Self::rainfall_node_sub_name(),
+                        MetricF64::NodeOutFlow,
+                    )?,
+                    ReservoirNodeAttribute::EvaporationVolume => self.get_flow_volume_metric(
+                        network,
+                        "evaporation_volume",
+                        Self::evaporation_node_sub_name(),
+                        MetricF64::NodeInFlow,
+                    )?,
+                    ReservoirNodeAttribute::LossVolume => self.get_flow_volume_metric(
+                        network,
+                        "leakage_volume",
+                        Self::leakage_node_sub_name(),
+                        MetricF64::NodeInFlow,
+                    )?,
                     ReservoirNodeAttribute::Volume => {
                         let idx = network
                             .get_node_index_by_name(self.meta().name.as_str(), None)
@@ -718,12 +966,40 @@ impl ReservoirNode {
 #[cfg(test)]
 #[cfg(feature = "core")]
 mod tests {
+    use crate::metric::Metric;
     use crate::model::ModelSchema;
+    use crate::nodes::{Node, NodeAttribute, StorageInitialVolume};
+    use pywr_core::metric::MetricF64;
+    use pywr_core::solvers::ClpSolver;
 
     fn reservoir_with_spill_str() -> &'static str {
         include_str!("../../tests/reservoir_with_spill1.json")
     }
 
+    fn reservoir_evaporation_str() -> &'static str {
+        include_str!("../../tests/reservoir_evaporation1.json")
+    }
+
+    /// Create the metric for an attribute of the reservoir in `reservoir_evaporation1.json`,
+    /// returning it with the index of the core node with the given sub-name.
+    fn reservoir_evaporation_metric(
+        attribute: NodeAttribute,
+        sub_name: &str,
+    ) -> (MetricF64, pywr_core::node::NodeIndex) {
+        let mut schema: ModelSchema = serde_json::from_str(reservoir_evaporation_str()).unwrap();
+        schema.network.outputs = None;
+        let mut model: pywr_core::models::Model = schema.build_model(None, None).unwrap();
+
+        let Some(Node::Reservoir(reservoir)) = schema.network.get_node_by_name("Reservoir") else {
+            panic!("The reservoir node should exist.");
+        };
+
+        let network = model.network_mut();
+        let metric = reservoir.create_metric(network, Some(attribute)).unwrap();
+        let idx = network.get_node_index_by_name("Reservoir", Some(sub_name)).unwrap();
+        (metric, idx)
+    }
+
     #[test]
     fn test_model_nodes_and_edges() {
         let data = reservoir_with_spill_str();
@@ -734,4 +1010,41 @@ mod tests {
         assert_eq!(network.nodes().len(), 5);
         assert_eq!(network.edges().len(), 5);
     }
+
+    /// The evaporation attribute is the flow into the evaporation node.
+    #[test]
+    fn test_evaporation_attribute() {
+        let (metric, idx) = reservoir_evaporation_metric(NodeAttribute::Evaporation, "evaporation");
+        assert_eq!(metric, MetricF64::NodeInFlow(idx));
+    }
+
+    /// The rainfall attribute is the flow out of the rainfall input node. An input node has no
+    /// inflow, so its inflow is always zero.
+    #[test]
+    fn test_rainfall_attribute() {
+        let (metric, idx) = reservoir_evaporation_metric(NodeAttribute::Rainfall, "rainfall");
+        assert_eq!(metric, MetricF64::NodeOutFlow(idx));
+    }
+
+    /// The linearised evaporation is a maximum flow, so an empty reservoir without any inflow
+    /// does not evaporate and the model remains feasible.
+    #[test]
+    fn test_linearised_evaporation_when_empty() {
+        let mut schema: ModelSchema = serde_json::from_str(reservoir_evaporation_str()).unwrap();
+        schema.network.outputs = None;
+
+        for node in schema.network.nodes.iter_mut() {
+            match node {
+                Node::Catchment(catchment) => catchment.flow = Some(Metric::Literal { value: 0.0 }),
+                Node::Reservoir(reservoir) => {
+                    reservoir.storage.initial_volume = StorageInitialVolume::Absolute { volume: 0.0 };
+                    reservoir.rainfall = None;
+                }
+                _ => {}
+            }
+        }
+
+        let model: pywr_core::models::Model = schema.build_model(None, None).unwrap();
+        model.run::<ClpSolver>(&Default::default()).unwrap();
+    }
 }
@@ -5,7 +5,7 @@ use crate::network::LoadArgs;
 use crate::nodes::loss_link::LossFactor;
 #[cfg(feature = "core")]
 use crate::nodes::{NodeAttribute, NodeComponent};
-use crate::nodes::{NodeMeta, NodeSlot};
+use crate::nodes::{NodeMeta, NodeSlot, Pumping, SemiContinuousFlow};
 use crate::parameters::Parameter;
 use crate::{mermaid, node_attribute_subset_enum, node_component_subset_enum};
 #[cfg(feature = "core")]
@@ -20,6 +20,12 @@ node_attribute_subset_enum! {
         Inflow,
         Outflow,
         Loss,
+        /// The energy used pumping. Requires `pumping` to be defined.
+        Energy,
+        /// The cost of the energy used pumping. Requires `pumping` to be defined.
+        EnergyCost,
+        /// The carbon emitted pumping. Requires `pumping` to be defined.
+        Carbon,
     }
 }
 
@@ -41,6 +47,9 @@ node_component_subset_enum! {
 /// factor is provided [`pywr_core::node::OutputNode`] and [`pywr_core::aggregated_node::AggregatedNode`]
 /// nodes are created.
 ///
+/// If the works is supplied by pumping, the optional [`Pumping`] component accounts for the
+/// energy, cost and carbon emissions of the gross flow into the works. If the pumping cost is
+/// included it is added to the cost of the `net` node.
 ///
 #[doc = mermaid!("doc_diagrams/wtw.mmd")]
 ///
@@ -70,6 +79,10 @@ pub struct WaterTreatmentWorksNode {
     pub soft_min_flow_cost: Option<Metric>,
     /// The cost applied to the `net` flow node.
     pub cost: Option<Metric>,
+    /// Optional semi-continuous flow through the `net` flow node.
+    pub semi_continuous: Option<SemiContinuousFlow>,
+    /// Optional energy accounting for pumping the gross flow into the works.
+    pub pumping: Option<Pumping>,
 }
 
 impl WaterTreatmentWorksNode {
@@ -224,9 +237,27 @@ impl WaterTreatmentWorksNode {
         network: &mut pywr_core::network::Network,
         args: &LoadArgs,
     ) -> Result<(), SchemaError> {
-        if let Some(cost) = &self.cost {
-            let value = cost.load(network, args, Some(&self.meta.name))?;
-            network.set_node_cost(self.meta.name.as_str(), Self::net_sub_name(), value.into())?;
+        let cost = self
+            .cost
+            .as_ref()
+            .map(|c| c.load(network, args, Some(&self.meta.name)))
+            .transpose()?;
+        let cost = match &self.pumping {
+            Some(pumping) => {
+                let flow = MetricF64::MultiNodeInFlow {
+                    indices: self.node_indices_for_flow_constraints(
+                        network,
+                        Some(WaterTreatmentWorksNodeComponent::Inflow.into()),
+                    )?,
+                    name: self.meta.name.to_string(),
+                };
+                let idx = pumping.add_to_model(network, args, self.meta.name.as_str(), flow)?;
+                pumping.total_cost(network, &idx, self.meta.name.as_str(), Self::net_sub_name(), cost)?
+            }
+            None => cost,
+        };
+        if let Some(cost) = cost {
+            network.set_node_cost(self.meta.name.as_str(), Self::net_sub_name(), cost.into())?;
         }
 
         if let Some(max_flow) = &self.max_flow {
@@ -239,6 +270,11 @@ impl WaterTreatmentWorksNode {
             network.set_node_min_flow(self.meta.name.as_str(), Self::net_sub_name(), value.into())?;
         }
 
+        if let Some(semi_continuous) = &self.semi_continuous {
+            let value = semi_continuous.load(network, args, Some(&self.meta.name))?;
+            network.set_node_semi_continuous_flow(self.meta.name.as_str(), Self::net_sub_name(), Some(value))?;
+        }
+
         // soft min flow constraints; This typically applies a negative cost upto a maximum
         // defined by the `soft_min_flow`
         if let Some(cost) = &self.soft_min_flow_cost {
@@ -325,8 +361,24 @@ impl WaterTreatmentWorksNode {
                     None => 0.0.into(),
                 }
             }
+            WaterTreatmentWorksNodeAttribute::Energy => self.pumping_metric(network, "energy", attr)?,
+            WaterTreatmentWorksNodeAttribute::EnergyCost => self.pumping_metric(network, "cost", attr)?,
+            WaterTreatmentWorksNodeAttribute::Carbon => self.pumping_metric(network, "carbon", attr)?,
         };
 
         Ok(metric)
     }
+
+    /// Create a metric for one of the pumping values; this errors if `pumping` is not defined.
+    fn pumping_metric(
+        &self,
+        network: &pywr_core::network::Network,
+        key: &str,
+        attr: WaterTreatmentWorksNodeAttribute,
+    ) -> Result<MetricF64, SchemaError> {
+        if self.pumping.is_none() {
+            return Err(SchemaError::NodeAttributeNotSupported { attr: attr.into() });
+        }
+        Pumping::create_metric(network, self.meta.name.as_str(), key)
+    }
 }
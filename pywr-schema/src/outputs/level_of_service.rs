@@ -0,0 +1,51 @@
+#[cfg(feature = "core")]
+use crate::error::SchemaError;
+use crate::metric::IndexMetric;
+#[cfg(feature = "core")]
+use crate::network::LoadArgs;
+#[cfg(feature = "core")]
+use pywr_core::recorders::LevelOfServiceRecorder;
+use pywr_schema_macros::{PywrVisitPaths, skip_serializing_none};
+use schemars::JsonSchema;
+#[cfg(feature = "core")]
+use std::path::Path;
+use std::path::PathBuf;
+
+/// Record level of service events from an index metric.
+///
+/// An event starts when the `metric`, typically a `DroughtTrigger` parameter, is greater than or
+/// equal to `level` and lasts until it falls below `level` again. Each event records its scenario,
+/// start and end dates, duration in time-steps, and the most severe level reached.
+///
+/// If a `filename` is given the events are written to a CSV file with a row for each event.
+/// The aggregated value of the output is the mean number of events per scenario.
+#[skip_serializing_none]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, JsonSchema, PywrVisitPaths)]
+pub struct LevelOfServiceOutput {
+    pub name: String,
+    pub metric: IndexMetric,
+    pub level: u64,
+    pub filename: Option<PathBuf>,
+}
+
+#[cfg(feature = "core")]
+impl LevelOfServiceOutput {
+    pub fn add_to_model(
+        &self,
+        network: &mut pywr_core::network::Network,
+        args: &LoadArgs,
+        output_path: Option<&Path>,
+    ) -> Result<(), SchemaError> {
+        let metric = self.metric.load(network, args, None)?;
+
+        let filename = self.filename.as_ref().map(|f| match (output_path, f.is_relative()) {
+            (Some(odir), true) => odir.join(f),
+            _ => f.to_path_buf(),
+        });
+
+        let recorder = LevelOfServiceRecorder::new(&self.name, metric, self.level, filename);
+        network.add_recorder(Box::new(recorder))?;
+
+        Ok(())
+    }
+}
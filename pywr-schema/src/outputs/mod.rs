@@ -1,13 +1,19 @@
 mod csv;
 mod hdf;
+mod level_of_service;
 mod memory;
+mod rule_trace;
 
 pub use self::csv::CsvOutput;
 #[cfg(feature = "core")]
 use crate::error::SchemaError;
+#[cfg(feature = "core")]
+use crate::network::LoadArgs;
 pub use hdf::Hdf5Output;
+pub use level_of_service::LevelOfServiceOutput;
 pub use memory::MemoryOutput;
 use pywr_schema_macros::PywrVisitPaths;
+pub use rule_trace::RuleTraceOutput;
 use schemars::JsonSchema;
 #[cfg(feature = "core")]
 use std::path::Path;
@@ -23,6 +29,8 @@ pub enum Output {
     CSV(CsvOutput),
     HDF5(Hdf5Output),
     Memory(Box<MemoryOutput>),
+    RuleTrace(RuleTraceOutput),
+    LevelOfService(LevelOfServiceOutput),
 }
 
 #[cfg(feature = "core")]
@@ -30,13 +38,15 @@ impl Output {
     pub fn add_to_model(
         &self,
         network: &mut pywr_core::network::Network,
-        data_path: Option<&Path>,
+        args: &LoadArgs,
         output_path: Option<&Path>,
     ) -> Result<(), SchemaError> {
         match self {
             Self::CSV(o) => o.add_to_model(network, output_path),
             Self::HDF5(o) => o.add_to_model(network, output_path),
-            Self::Memory(o) => o.add_to_model(network, data_path),
+            Self::Memory(o) => o.add_to_model(network, args.data_path),
+            Self::RuleTrace(o) => o.add_to_model(network, args, output_path),
+            Self::LevelOfService(o) => o.add_to_model(network, args, output_path),
         }
     }
 
@@ -45,6 +55,8 @@ impl Output {
             Self::CSV(o) => &o.name,
             Self::HDF5(o) => &o.name,
             Self::Memory(o) => &o.name,
+            Self::RuleTrace(o) => &o.name,
+            Self::LevelOfService(o) => &o.name,
         }
     }
 }
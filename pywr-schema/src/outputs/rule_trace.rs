@@ -0,0 +1,77 @@
+#[cfg(feature = "core")]
+use crate::error::SchemaError;
+#[cfg(feature = "core")]
+use crate::network::LoadArgs;
+#[cfg(feature = "core")]
+use crate::parameters::Parameter;
+#[cfg(feature = "core")]
+use pywr_core::parameters::ParameterName;
+#[cfg(feature = "core")]
+use pywr_core::recorders::RuleTraceRecorder;
+use pywr_schema_macros::{PywrVisitPaths, skip_serializing_none};
+use schemars::JsonSchema;
+#[cfg(feature = "core")]
+use std::path::Path;
+use std::path::PathBuf;
+
+/// Record which rule of a rule set parameter applied in each time-step and scenario.
+///
+/// The `parameter` must be the name of a `RuleSet` or `IndexRuleSet` parameter. Each record
+/// contains the time-step, the scenario, and the index and name of the rule that applied. Time-steps
+/// where no rule applied are recorded with the `default_name`, which defaults to `"default"`.
+///
+/// If a `filename` is given the records are written to a CSV file with a row for each time-step
+/// and scenario. Otherwise the records are only available from the results of the model run.
+#[skip_serializing_none]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, JsonSchema, PywrVisitPaths)]
+pub struct RuleTraceOutput {
+    pub name: String,
+    pub parameter: String,
+    pub filename: Option<PathBuf>,
+    pub default_name: Option<String>,
+}
+
+#[cfg(feature = "core")]
+impl RuleTraceOutput {
+    pub fn add_to_model(
+        &self,
+        network: &mut pywr_core::network::Network,
+        args: &LoadArgs,
+        output_path: Option<&Path>,
+    ) -> Result<(), SchemaError> {
+        let rule_names = match args.schema.get_parameter_by_name(&self.parameter) {
+            Some(Parameter::RuleSet(p)) => p.rule_names(),
+            Some(Parameter::IndexRuleSet(p)) => p.rule_names(),
+            Some(_) => return Err(SchemaError::RuleSetParameterExpected(self.parameter.clone())),
+            None => {
+                return Err(SchemaError::ParameterNotFound {
+                    name: self.parameter.clone(),
+                    key: None,
+                });
+            }
+        };
+
+        let idx = network
+            .get_multi_valued_parameter_index_by_name(&ParameterName::new(&self.parameter, None))
+            .ok_or_else(|| SchemaError::CoreParameterNotFound {
+                name: self.parameter.clone(),
+                key: Some("rule".to_string()),
+            })?;
+
+        let filename = self.filename.as_ref().map(|f| match (output_path, f.is_relative()) {
+            (Some(odir), true) => odir.join(f),
+            _ => f.to_path_buf(),
+        });
+
+        let recorder = RuleTraceRecorder::new(
+            &self.name,
+            idx.into_metric_u64_before("rule"),
+            rule_names,
+            self.default_name.as_deref().unwrap_or("default"),
+            filename,
+        );
+        network.add_recorder(Box::new(recorder))?;
+
+        Ok(())
+    }
+}
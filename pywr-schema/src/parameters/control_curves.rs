@@ -5,7 +5,7 @@ use crate::metric::{Metric, NodeAttrReference, VirtualNodeAttrReference};
 #[cfg(feature = "core")]
 use crate::network::LoadArgs;
 use crate::nodes::NodeAttribute;
-use crate::parameters::{ConversionData, ParameterMeta};
+use crate::parameters::{ConversionData, InterpolationKind, ParameterMeta};
 use crate::v1::{TryFromV1, TryIntoV2, try_convert_control_curves, try_convert_parameter_attr};
 
 #[cfg(feature = "core")]
@@ -19,6 +19,7 @@ use pywr_v1_schema::parameters::{
 };
 use schemars::JsonSchema;
 
+#[skip_serializing_none]
 #[derive(serde::Deserialize, serde::Serialize, Debug, Clone, JsonSchema, PywrVisitAll)]
 #[serde(deny_unknown_fields)]
 pub struct ControlCurveInterpolatedParameter {
@@ -26,6 +27,9 @@ pub struct ControlCurveInterpolatedParameter {
     pub control_curves: Vec<Metric>,
     pub storage_metric: Metric,
     pub values: Vec<Metric>,
+    /// The method of interpolation between the control curves. Defaults to linear. Other kinds
+    /// are applied across all of the control curves, which must then be strictly decreasing.
+    pub kind: Option<InterpolationKind>,
 }
 
 #[cfg(feature = "core")]
@@ -55,6 +59,7 @@ impl ControlCurveInterpolatedParameter {
             metric,
             control_curves,
             values,
+            self.kind.unwrap_or_default().into(),
         );
         Ok(network.add_parameter(Box::new(p))?)
     }
@@ -125,6 +130,7 @@ impl TryFromV1<ControlCurveInterpolatedParameterV1> for ControlCurveInterpolated
             control_curves,
             storage_metric,
             values,
+            kind: None,
         };
         Ok(p)
     }
@@ -359,6 +365,8 @@ pub struct ControlCurvePiecewiseInterpolatedParameter {
     pub values: Option<Vec<[f64; 2]>>,
     pub minimum: Option<f64>,
     pub maximum: Option<f64>,
+    /// The method of interpolation within each band. Defaults to linear.
+    pub kind: Option<InterpolationKind>,
 }
 
 #[cfg(feature = "core")]
@@ -389,6 +397,7 @@ impl ControlCurvePiecewiseInterpolatedParameter {
             values,
             self.maximum.unwrap_or(1.0),
             self.minimum.unwrap_or(0.0),
+            self.kind.unwrap_or_default().into(),
         );
         Ok(network.add_parameter(Box::new(p))?)
     }
@@ -434,6 +443,7 @@ impl TryFromV1<ControlCurvePiecewiseInterpolatedParameterV1> for ControlCurvePie
             values: v1.values,
             minimum: v1.minimum,
             maximum: None,
+            kind: None,
         };
         Ok(p)
     }
@@ -0,0 +1,78 @@
+#[cfg(feature = "core")]
+use crate::error::SchemaError;
+use crate::metric::Metric;
+#[cfg(feature = "core")]
+use crate::network::LoadArgs;
+use crate::parameters::ParameterMeta;
+#[cfg(feature = "core")]
+use pywr_core::parameters::{ParameterIndex, ParameterName};
+use pywr_schema_macros::{PywrVisitAll, skip_serializing_none};
+use schemars::JsonSchema;
+
+/// A parameter that returns a drought restriction level with hysteresis.
+///
+/// The target level is found by comparing `metric` against the `triggers` in the same way as a
+/// `ControlCurveIndex` parameter: zero if the metric is greater than or equal to the first
+/// trigger, one if it is only greater than or equal to the second, and so on. The level returned
+/// by the parameter moves to a more severe target level immediately. It only moves to a less
+/// severe level once it has applied for at least `min_duration` time-steps, and then only by one
+/// level at a time. If `evaluation_days` are given the level only changes on those days of the
+/// year, numbered from 1 to 366 as if every year is a leap year.
+///
+/// The level is an index, and events where it reaches a given level can be recorded with a
+/// [`crate::outputs::LevelOfServiceOutput`].
+///
+/// # JSON Example
+///
+/// ```json
+#[doc = include_str!("doc_examples/drought_trigger.json")]
+/// ```
+#[skip_serializing_none]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, JsonSchema, PywrVisitAll)]
+#[serde(deny_unknown_fields)]
+pub struct DroughtTriggerParameter {
+    pub meta: ParameterMeta,
+    pub metric: Metric,
+    /// The triggers ordered from the least to the most severe level.
+    pub triggers: Vec<Metric>,
+    /// The minimum number of time-steps a level applies before it can be relaxed. Defaults to zero.
+    pub min_duration: Option<u64>,
+    /// The days of the year on which the level can change. Defaults to every day.
+    pub evaluation_days: Option<Vec<u32>>,
+    /// The level at the start of the simulation. Defaults to zero.
+    pub initial_level: Option<u64>,
+}
+
+#[cfg(feature = "core")]
+impl DroughtTriggerParameter {
+    pub fn add_to_model(
+        &self,
+        network: &mut pywr_core::network::Network,
+        args: &LoadArgs,
+        parent: Option<&str>,
+    ) -> Result<ParameterIndex<u64>, SchemaError> {
+        if let Some(day) = self.evaluation_days.iter().flatten().find(|d| !(1..=366).contains(*d)) {
+            return Err(SchemaError::InvalidEvaluationDay {
+                name: self.meta.name.clone(),
+                day: *day,
+            });
+        }
+
+        let metric = self.metric.load(network, args, parent)?;
+        let triggers = self
+            .triggers
+            .iter()
+            .map(|t| t.load(network, args, parent))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let p = pywr_core::parameters::DroughtTriggerParameter::new(
+            ParameterName::new(&self.meta.name, parent),
+            metric,
+            triggers,
+            self.min_duration.unwrap_or_default(),
+            self.evaluation_days.clone(),
+            self.initial_level.unwrap_or_default(),
+        );
+        Ok(network.add_index_parameter(Box::new(p))?)
+    }
+}
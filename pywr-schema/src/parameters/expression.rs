@@ -0,0 +1,67 @@
+#[cfg(feature = "core")]
+use crate::error::SchemaError;
+use crate::metric::Metric;
+#[cfg(feature = "core")]
+use crate::network::LoadArgs;
+use crate::parameters::ParameterMeta;
+#[cfg(feature = "core")]
+use pywr_core::parameters::{ParameterIndex, ParameterName};
+use pywr_schema_macros::{PywrVisitAll, skip_serializing_none};
+use schemars::JsonSchema;
+use std::collections::HashMap;
+
+/// A parameter that evaluates an arithmetic expression of other metrics.
+///
+/// Each of the named `variables` is bound to a metric and can be used in the `expression`.
+/// The expression supports the arithmetic operators `+`, `-`, `*`, `/`, `%` and `^`; the
+/// comparisons `==`, `!=`, `<`, `<=`, `>` and `>=`; the logical operators `&&`, `||` and `!`;
+/// and the functions `min`, `max`, `abs`, `clamp(x, lower, upper)` and `if(condition, a, b)`.
+/// Comparisons return one if true and zero otherwise, and any non-zero value is treated as true.
+/// The date of the time-step is available from the functions `year()`, `month()`, `day()`,
+/// `day_of_year()`, `day_of_week()` (Monday is one) and `hour()`.
+///
+/// The expression is compiled when the model is loaded, and an invalid expression or an unknown
+/// variable is reported as an error at that point.
+///
+/// # JSON Example
+///
+/// ```json
+#[doc = include_str!("doc_examples/expression.json")]
+/// ```
+#[skip_serializing_none]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, JsonSchema, PywrVisitAll)]
+#[serde(deny_unknown_fields)]
+pub struct ExpressionParameter {
+    pub meta: ParameterMeta,
+    pub expression: String,
+    pub variables: Option<HashMap<String, Metric>>,
+}
+
+#[cfg(feature = "core")]
+impl ExpressionParameter {
+    pub fn add_to_model(
+        &self,
+        network: &mut pywr_core::network::Network,
+        args: &LoadArgs,
+        parent: Option<&str>,
+    ) -> Result<ParameterIndex<f64>, SchemaError> {
+        let variables = match &self.variables {
+            Some(variables) => variables
+                .iter()
+                .map(|(k, v)| Ok((k.to_string(), v.load(network, args, None)?)))
+                .collect::<Result<Vec<_>, SchemaError>>()?,
+            None => Vec::new(),
+        };
+
+        let p = pywr_core::parameters::ExpressionParameter::new(
+            ParameterName::new(&self.meta.name, parent),
+            &self.expression,
+            variables,
+        )
+        .map_err(|source| SchemaError::InvalidExpression {
+            name: self.meta.name.clone(),
+            source,
+        })?;
+        Ok(network.add_parameter(Box::new(p))?)
+    }
+}
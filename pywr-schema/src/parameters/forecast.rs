@@ -0,0 +1,120 @@
+use crate::agg_funcs::AggFunc;
+#[cfg(feature = "core")]
+use crate::error::SchemaError;
+#[cfg(feature = "core")]
+use crate::network::LoadArgs;
+use crate::parameters::ParameterMeta;
+use crate::timeseries::ForecastDataset;
+#[cfg(feature = "core")]
+use pywr_core::parameters::{ParameterIndex, ParameterName};
+use pywr_schema_macros::{PywrVisitAll, skip_serializing_none};
+use schemars::JsonSchema;
+use strum_macros::{Display, EnumDiscriminants, EnumIter, EnumString, IntoStaticStr};
+
+/// The ensemble member used by a [`ForecastParameter`].
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, JsonSchema, PywrVisitAll, Display, EnumDiscriminants)]
+#[serde(tag = "type", deny_unknown_fields)]
+#[strum_discriminants(derive(Display, IntoStaticStr, EnumString, EnumIter))]
+#[strum_discriminants(name(ForecastMemberType))]
+pub enum ForecastMember {
+    /// The member with the given identifier in every scenario.
+    Fixed { member: i64 },
+    /// Each scenario of the scenario group uses the member in the same position, in ascending
+    /// order of the member identifiers. The forecast must have at least as many members as the
+    /// scenario group has scenarios.
+    ScenarioGroup { scenario_group: String },
+}
+
+/// The value returned by a [`ForecastParameter`].
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, JsonSchema, PywrVisitAll, Display, EnumDiscriminants)]
+#[serde(tag = "type", deny_unknown_fields)]
+#[strum_discriminants(derive(Display, IntoStaticStr, EnumString, EnumIter))]
+#[strum_discriminants(name(ForecastValueType))]
+pub enum ForecastValue {
+    /// The value the given number of time-steps ahead of the current time-step.
+    Lead { lead: u64 },
+    /// An aggregate of the values from the current time-step over a horizon of the given number
+    /// of time-steps, or over the rest of the forecast if no horizon is given.
+    Aggregate { agg_func: AggFunc, horizon: Option<u64> },
+}
+
+/// A parameter that returns a value from an ensemble forecast.
+///
+/// At each time-step the latest forecast issued at or before the start of the time-step is used.
+/// The lead times of the forecast are offset by the number of time-steps since it was issued, so
+/// a `Lead` of zero is always the forecast for the current time-step. This allows the forecast,
+/// or an aggregate of it over a horizon (for example, the total forecast inflow over the next
+/// week), to be used in release rules. The member of the ensemble can be fixed, or can vary with
+/// the scenarios of a scenario group.
+///
+/// An error is raised if a time-step is before the first issue of the forecast, or if the
+/// requested lead time is beyond the end of the latest forecast. See [`ForecastDataset`] for the
+/// format of the forecast data.
+///
+/// # JSON Example
+///
+/// ```json
+#[doc = include_str!("doc_examples/forecast.json")]
+/// ```
+#[skip_serializing_none]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, JsonSchema, PywrVisitAll)]
+#[serde(deny_unknown_fields)]
+pub struct ForecastParameter {
+    pub meta: ParameterMeta,
+    pub forecast: ForecastDataset,
+    pub member: ForecastMember,
+    pub value: ForecastValue,
+}
+
+#[cfg(feature = "core")]
+impl ForecastParameter {
+    pub fn add_to_model(
+        &self,
+        network: &mut pywr_core::network::Network,
+        args: &LoadArgs,
+        parent: Option<&str>,
+    ) -> Result<ParameterIndex<f64>, SchemaError> {
+        let (forecast, member_ids) = self.forecast.load(&self.meta.name, args.data_path)?;
+
+        let member = match &self.member {
+            ForecastMember::Fixed { member } => {
+                let Some(idx) = member_ids.iter().position(|m| m == member) else {
+                    return Err(SchemaError::ForecastMemberNotFound {
+                        name: self.meta.name.clone(),
+                        member: *member,
+                    });
+                };
+                pywr_core::parameters::ForecastMember::Fixed(idx)
+            }
+            ForecastMember::ScenarioGroup { scenario_group } => {
+                let group_index = args.domain.scenarios().group_index(scenario_group)?;
+                let group_size = args.domain.scenarios().group_size(scenario_group)?;
+                if group_size > member_ids.len() {
+                    return Err(SchemaError::ForecastMembersMismatch {
+                        name: self.meta.name.clone(),
+                        members: member_ids.len(),
+                        scenarios: group_size,
+                        group: scenario_group.clone(),
+                    });
+                }
+                pywr_core::parameters::ForecastMember::ScenarioGroup(group_index)
+            }
+        };
+
+        let value = match &self.value {
+            ForecastValue::Lead { lead } => pywr_core::parameters::ForecastValue::Lead(*lead as usize),
+            ForecastValue::Aggregate { agg_func, horizon } => pywr_core::parameters::ForecastValue::Aggregate {
+                agg_func: agg_func.load(args.data_path)?,
+                horizon: horizon.map(|h| h as usize),
+            },
+        };
+
+        let p = pywr_core::parameters::ForecastParameter::new(
+            ParameterName::new(&self.meta.name, parent),
+            forecast,
+            member,
+            value,
+        );
+        Ok(network.add_simple_parameter(Box::new(p))?)
+    }
+}
@@ -0,0 +1,180 @@
+#[cfg(feature = "core")]
+use crate::error::SchemaError;
+use crate::metric::Metric;
+#[cfg(feature = "core")]
+use crate::network::LoadArgs;
+use crate::parameters::ParameterMeta;
+#[cfg(feature = "core")]
+use pywr_core::parameters::{ParameterIndex, ParameterName};
+use pywr_schema_macros::{PywrVisitAll, skip_serializing_none};
+use schemars::JsonSchema;
+use strum_macros::{Display, EnumDiscriminants, EnumIter, EnumString, IntoStaticStr};
+
+/// A rule that gives the release from a reservoir for the available water and the demand.
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, JsonSchema, PywrVisitAll, Display, EnumDiscriminants)]
+#[serde(tag = "type", deny_unknown_fields)]
+#[strum_discriminants(derive(Display, IntoStaticStr, EnumString, EnumIter))]
+#[strum_discriminants(name(HedgingRuleType))]
+pub enum HedgingRule {
+    /// Release the demand, or all of the available water if it is less than the demand.
+    StandardOperatingPolicy,
+    /// Release a fixed proportion (`slope`) of the available water, up to the demand.
+    Linear { slope: Metric },
+    /// Release all of the available water up to the `start` of hedging. Between the `start` and
+    /// the `end` of hedging the release increases linearly from the `start` to the demand, and
+    /// above the `end` the demand is released in full.
+    TwoPoint { start: Metric, end: Metric },
+    /// Release a proportion of the demand that depends on the zone of the available water.
+    ///
+    /// The zones are defined by `thresholds` in descending order. The first of the `factors` is
+    /// used when the available water is greater than or equal to the first threshold, the second
+    /// when it is only greater than or equal to the second threshold, and so on. There must be
+    /// one more factor than there are thresholds.
+    Zones {
+        thresholds: Vec<Metric>,
+        factors: Vec<Metric>,
+    },
+}
+
+#[cfg(feature = "core")]
+impl HedgingRule {
+    fn load(
+        &self,
+        network: &mut pywr_core::network::Network,
+        args: &LoadArgs,
+        parent: Option<&str>,
+        name: &str,
+    ) -> Result<pywr_core::parameters::HedgingRule, SchemaError> {
+        let rule = match self {
+            Self::StandardOperatingPolicy => pywr_core::parameters::HedgingRule::StandardOperatingPolicy,
+            Self::Linear { slope } => pywr_core::parameters::HedgingRule::Linear {
+                slope: slope.load(network, args, parent)?,
+            },
+            Self::TwoPoint { start, end } => pywr_core::parameters::HedgingRule::TwoPoint {
+                start: start.load(network, args, parent)?,
+                end: end.load(network, args, parent)?,
+            },
+            Self::Zones { thresholds, factors } => {
+                if factors.len() != thresholds.len() + 1 {
+                    return Err(SchemaError::InvalidHedgingRule {
+                        name: name.to_string(),
+                        msg: format!(
+                            "{} factors were given for {} thresholds; there must be one more factor than thresholds",
+                            factors.len(),
+                            thresholds.len()
+                        ),
+                    });
+                }
+
+                pywr_core::parameters::HedgingRule::Zones {
+                    thresholds: thresholds
+                        .iter()
+                        .map(|t| t.load(network, args, parent))
+                        .collect::<Result<Vec<_>, _>>()?,
+                    factors: factors
+                        .iter()
+                        .map(|f| f.load(network, args, parent))
+                        .collect::<Result<Vec<_>, _>>()?,
+                }
+            }
+        };
+
+        Ok(rule)
+    }
+}
+
+/// The value returned by a [`HedgingParameter`].
+#[derive(
+    serde::Deserialize,
+    serde::Serialize,
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    JsonSchema,
+    PywrVisitAll,
+    Display,
+    EnumIter,
+)]
+pub enum HedgingOutput {
+    /// The release target.
+    #[default]
+    Release,
+    /// The release as a proportion of the demand, or one if the demand is zero.
+    Factor,
+}
+
+#[cfg(feature = "core")]
+impl From<HedgingOutput> for pywr_core::parameters::HedgingOutput {
+    fn from(value: HedgingOutput) -> Self {
+        match value {
+            HedgingOutput::Release => Self::Release,
+            HedgingOutput::Factor => Self::Factor,
+        }
+    }
+}
+
+/// A parameter that returns the release target of a reservoir from a hedging rule.
+///
+/// The available water is the sum of the `storage` and the optional `inflow`, such as a forecast
+/// of the inflow from a `Forecast` parameter. The `rule` gives the release for the available water
+/// and the `demand`:
+///
+/// - `StandardOperatingPolicy` releases the demand, or all of the available water if it is less.
+/// - `Linear` releases a proportion of the available water, up to the demand.
+/// - `TwoPoint` releases all of the available water below the start of hedging, the demand above
+///   the end of hedging, and a release that increases linearly from the start to the demand
+///   between them.
+/// - `Zones` releases a proportion of the demand depending on the zone of the available water.
+///
+/// The release is never more than the available water or less than zero. The parameter returns
+/// the release target, or if the `output` is `Factor` the release as a proportion of the demand.
+/// The latter can be used as a demand-saving factor by multiplying it with the demand.
+///
+/// # JSON Example
+///
+/// ```json
+#[doc = include_str!("doc_examples/hedging.json")]
+/// ```
+#[skip_serializing_none]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, JsonSchema, PywrVisitAll)]
+#[serde(deny_unknown_fields)]
+pub struct HedgingParameter {
+    pub meta: ParameterMeta,
+    pub storage: Metric,
+    pub inflow: Option<Metric>,
+    pub demand: Metric,
+    pub rule: HedgingRule,
+    /// Defaults to the release target.
+    pub output: Option<HedgingOutput>,
+}
+
+#[cfg(feature = "core")]
+impl HedgingParameter {
+    pub fn add_to_model(
+        &self,
+        network: &mut pywr_core::network::Network,
+        args: &LoadArgs,
+        parent: Option<&str>,
+    ) -> Result<ParameterIndex<f64>, SchemaError> {
+        let storage = self.storage.load(network, args, parent)?;
+        let inflow = self
+            .inflow
+            .as_ref()
+            .map(|i| i.load(network, args, parent))
+            .transpose()?;
+        let demand = self.demand.load(network, args, parent)?;
+        let rule = self.rule.load(network, args, parent, &self.meta.name)?;
+
+        let p = pywr_core::parameters::HedgingParameter::new(
+            ParameterName::new(&self.meta.name, parent),
+            storage,
+            inflow,
+            demand,
+            rule,
+            self.output.unwrap_or_default().into(),
+        );
+        Ok(network.add_parameter(Box::new(p))?)
+    }
+}
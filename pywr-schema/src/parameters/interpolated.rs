@@ -16,11 +16,68 @@ use pywr_v1_schema::parameters::{
     InterpolatedVolumeParameter as InterpolatedVolumeParameterV1,
 };
 use schemars::JsonSchema;
+use std::collections::HashMap;
+use strum_macros::{Display, EnumIter};
+
+/// The method used to interpolate between data points.
+#[derive(
+    serde::Deserialize, serde::Serialize, Debug, Copy, Clone, Default, Display, JsonSchema, PywrVisitAll, EnumIter,
+)]
+pub enum InterpolationKind {
+    /// Straight lines between the points.
+    #[default]
+    Linear,
+    /// Monotone piecewise cubic Hermite interpolation. This does not overshoot the data points.
+    Pchip,
+    /// A step function that uses the value of the previous point.
+    Previous,
+    /// A step function that uses the value of the next point.
+    Next,
+    /// Straight lines between the logarithm of the values. All values must be greater than zero.
+    LogLinear,
+}
+
+#[cfg(feature = "core")]
+impl From<InterpolationKind> for pywr_core::parameters::InterpolationKind {
+    fn from(value: InterpolationKind) -> Self {
+        match value {
+            InterpolationKind::Linear => Self::Linear,
+            InterpolationKind::Pchip => Self::Pchip,
+            InterpolationKind::Previous => Self::Previous,
+            InterpolationKind::Next => Self::Next,
+            InterpolationKind::LogLinear => Self::LogLinear,
+        }
+    }
+}
+
+/// How values outside the range of the data points are handled.
+#[derive(serde::Deserialize, serde::Serialize, Debug, Copy, Clone, Display, JsonSchema, PywrVisitAll, EnumIter)]
+pub enum Extrapolation {
+    /// Raise an error.
+    Error,
+    /// Use the value of the nearest data point.
+    Constant,
+    /// Extend the interpolation from the nearest data point using its gradient at that point.
+    Linear,
+}
+
+#[cfg(feature = "core")]
+impl From<Extrapolation> for pywr_core::parameters::Extrapolation {
+    fn from(value: Extrapolation) -> Self {
+        match value {
+            Extrapolation::Error => Self::Error,
+            Extrapolation::Constant => Self::Constant,
+            Extrapolation::Linear => Self::Linear,
+        }
+    }
+}
 
 /// A parameter that interpolates a value to a function with given discrete data points.
 ///
-/// Internally this is implemented as a piecewise linear interpolation via
-/// [`pywr_core::parameters::InterpolatedParameter`].
+/// The data points must be in order of strictly increasing `xp`. The interpolation is linear
+/// unless another `kind` is given.
+///
+/// Internally this is implemented via [`pywr_core::parameters::InterpolatedParameter`].
 #[skip_serializing_none]
 #[derive(serde::Deserialize, serde::Serialize, Debug, Clone, JsonSchema, PywrVisitAll)]
 #[serde(deny_unknown_fields)]
@@ -30,7 +87,13 @@ pub struct InterpolatedParameter {
     pub xp: Vec<Metric>,
     pub fp: Vec<Metric>,
     /// If not given or true, raise an error if the x value is outside the range of the data points.
+    /// Otherwise, the value of the nearest data point is used.
     pub error_on_bounds: Option<bool>,
+    /// The method of interpolation. Defaults to linear.
+    pub kind: Option<InterpolationKind>,
+    /// How values outside the range of the data points are handled. If given, this overrides
+    /// `error_on_bounds`.
+    pub extrapolation: Option<Extrapolation>,
 }
 
 #[cfg(feature = "core")]
@@ -64,11 +127,18 @@ impl InterpolatedParameter {
 
         let points = xp.into_iter().zip(fp).collect::<Vec<_>>();
 
+        let extrapolation = match self.extrapolation {
+            Some(extrapolation) => extrapolation,
+            None if self.error_on_bounds.unwrap_or(true) => Extrapolation::Error,
+            None => Extrapolation::Constant,
+        };
+
         let p = pywr_core::parameters::InterpolatedParameter::new(
             ParameterName::new(&self.meta.name, parent),
             x,
             points,
-            self.error_on_bounds.unwrap_or(true),
+            self.kind.unwrap_or_default().into(),
+            extrapolation.into(),
         );
         Ok(network.add_parameter(Box::new(p))?)
     }
@@ -104,38 +174,16 @@ impl TryFromV1<InterpolatedFlowParameterV1> for InterpolatedParameter {
             .map(|p| try_convert_parameter_attr(&meta.name, "values", p, parent_node, conversion_data))
             .collect::<Result<Vec<_>, _>>()?;
 
-        // Default values
-        let mut error_on_bounds = None;
-        if let Some(interp_kwargs) = v1.interp_kwargs {
-            if let Some(error_on_bounds_value) = interp_kwargs.get("bounds_error") {
-                // Try to get the value as a boolean;
-                if let Some(eob) = error_on_bounds_value.as_bool() {
-                    error_on_bounds = Some(eob);
-                }
-            }
-
-            // Check if non-linear interpolation is requested; this is not supported at the moment.
-            if let Some(kind) = interp_kwargs.get("kind") {
-                if let Some(kind_str) = kind.as_str() {
-                    if kind_str != "linear" {
-                        return Err(Box::new(ComponentConversionError::Parameter {
-                            name: meta.name.clone(),
-                            attr: "interp_kwargs".to_string(),
-                            error: ConversionError::UnsupportedFeature {
-                                feature: "Interpolation with `kind` other than `linear` is not supported.".to_string(),
-                            },
-                        }));
-                    }
-                }
-            }
-        }
+        let options = try_convert_interp_kwargs(&meta.name, v1.interp_kwargs)?;
 
         Ok(Self {
             meta,
             x,
             xp,
             fp,
-            error_on_bounds,
+            error_on_bounds: options.error_on_bounds,
+            kind: options.kind,
+            extrapolation: options.extrapolation,
         })
     }
 }
@@ -170,38 +218,127 @@ impl TryFromV1<InterpolatedVolumeParameterV1> for InterpolatedParameter {
             .map(|p| try_convert_parameter_attr(&meta.name, "values", p, parent_node, conversion_data))
             .collect::<Result<Vec<_>, _>>()?;
 
-        // Default values
-        let mut error_on_bounds = None;
-        if let Some(interp_kwargs) = v1.interp_kwargs {
-            if let Some(error_on_bounds_value) = interp_kwargs.get("bounds_error") {
-                // Try to get the value as a boolean;
-                if let Some(eob) = error_on_bounds_value.as_bool() {
-                    error_on_bounds = Some(eob);
-                }
-            }
-
-            // Check if non-linear interpolation is requested; this is not supported at the moment.
-            if let Some(kind) = interp_kwargs.get("kind") {
-                if let Some(kind_str) = kind.as_str() {
-                    if kind_str != "linear" {
-                        return Err(Box::new(ComponentConversionError::Parameter {
-                            name: meta.name.clone(),
-                            attr: "interp_kwargs".to_string(),
-                            error: ConversionError::UnsupportedFeature {
-                                feature: "Interpolation with `kind` other than `linear` is not supported.".to_string(),
-                            },
-                        }));
-                    }
-                }
-            }
-        }
+        let options = try_convert_interp_kwargs(&meta.name, v1.interp_kwargs)?;
 
         Ok(Self {
             meta,
             x,
             xp,
             fp,
-            error_on_bounds,
+            error_on_bounds: options.error_on_bounds,
+            kind: options.kind,
+            extrapolation: options.extrapolation,
         })
     }
 }
+
+/// The interpolation options converted from v1's `interp_kwargs`.
+struct InterpOptions {
+    error_on_bounds: Option<bool>,
+    kind: Option<InterpolationKind>,
+    extrapolation: Option<Extrapolation>,
+}
+
+/// Convert v1's `interp_kwargs`, which are passed to SciPy's `interp1d`.
+///
+/// The `linear`, `slinear`, `zero`, `previous` and `next` kinds are supported. A `fill_value` of
+/// `"extrapolate"` is converted to linear extrapolation; other fill values are not supported.
+fn try_convert_interp_kwargs(
+    name: &str,
+    interp_kwargs: Option<HashMap<String, serde_json::Value>>,
+) -> Result<InterpOptions, Box<ComponentConversionError>> {
+    let Some(interp_kwargs) = interp_kwargs else {
+        return Ok(InterpOptions {
+            error_on_bounds: None,
+            kind: None,
+            extrapolation: None,
+        });
+    };
+
+    // Try to get the value as a boolean;
+    let error_on_bounds = interp_kwargs.get("bounds_error").and_then(|v| v.as_bool());
+
+    let kind = match interp_kwargs.get("kind").and_then(|v| v.as_str()) {
+        // Linear is the default in both v1 and v2
+        None | Some("linear") | Some("slinear") => None,
+        // A zero order spline is equivalent to the previous value
+        Some("zero") | Some("previous") => Some(InterpolationKind::Previous),
+        Some("next") => Some(InterpolationKind::Next),
+        Some(kind) => {
+            return Err(Box::new(ComponentConversionError::Parameter {
+                name: name.to_string(),
+                attr: "interp_kwargs".to_string(),
+                error: ConversionError::UnsupportedFeature {
+                    feature: format!(
+                        "Interpolation with `kind` of `{kind}` is not supported. Supported kinds are `linear`, `slinear`, `zero`, `previous` and `next`."
+                    ),
+                },
+            }));
+        }
+    };
+
+    let extrapolation = match interp_kwargs.get("fill_value") {
+        None | Some(serde_json::Value::Null) => None,
+        Some(serde_json::Value::String(v)) if v == "extrapolate" => Some(Extrapolation::Linear),
+        // A fill value is used outside the points, which is not the same as the end values
+        Some(v) => {
+            return Err(Box::new(ComponentConversionError::Parameter {
+                name: name.to_string(),
+                attr: "interp_kwargs".to_string(),
+                error: ConversionError::UnsupportedFeature {
+                    feature: format!(
+                        "Interpolation with a `fill_value` of `{v}` is not supported. Only `extrapolate` is supported."
+                    ),
+                },
+            }));
+        }
+    };
+
+    Ok(InterpOptions {
+        error_on_bounds,
+        kind,
+        extrapolation,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Extrapolation, InterpolatedParameter, InterpolationKind};
+    use crate::parameters::ConversionData;
+    use crate::v1::TryFromV1;
+    use pywr_v1_schema::parameters::InterpolatedVolumeParameter as InterpolatedVolumeParameterV1;
+
+    fn convert(interp_kwargs: &str) -> Result<InterpolatedParameter, String> {
+        let data = format!(
+            r#"{{
+                "node": "reservoir",
+                "volumes": [0.0, 50.0, 100.0],
+                "values": [0.0, 10.0, 15.0],
+                "interp_kwargs": {interp_kwargs}
+            }}"#
+        );
+        let v1: InterpolatedVolumeParameterV1 = serde_json::from_str(&data).unwrap();
+        let mut conversion_data = ConversionData::default();
+        InterpolatedParameter::try_from_v1(v1, Some("reservoir"), &mut conversion_data).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn test_v1_interp_kwargs_conversion() {
+        let p = convert(r#"{"kind": "linear", "bounds_error": false}"#).unwrap();
+        assert!(p.kind.is_none());
+        assert_eq!(p.error_on_bounds, Some(false));
+
+        let p = convert(r#"{"kind": "zero"}"#).unwrap();
+        assert!(matches!(p.kind, Some(InterpolationKind::Previous)));
+
+        let p = convert(r#"{"kind": "next", "fill_value": "extrapolate"}"#).unwrap();
+        assert!(matches!(p.kind, Some(InterpolationKind::Next)));
+        assert!(matches!(p.extrapolation, Some(Extrapolation::Linear)));
+
+        let err = convert(r#"{"kind": "cubic"}"#).unwrap_err();
+        assert!(err.contains("`cubic` is not supported"));
+
+        let err = convert(r#"{"fill_value": 0.0}"#).unwrap_err();
+        assert!(err.contains("`fill_value` of `0.0` is not supported"));
+    }
+}
@@ -13,6 +13,10 @@ mod control_curves;
 mod core;
 mod delay;
 mod discount_factor;
+mod drought_trigger;
+mod expression;
+mod forecast;
+mod hedging;
 mod hydropower;
 mod indexed_array;
 mod interpolated;
@@ -23,8 +27,12 @@ mod polynomial;
 mod profiles;
 mod python;
 mod rolling;
+mod rule_set;
+mod sampled;
 mod tables;
+mod tariff;
 mod thresholds;
+mod weather_demand;
 
 #[cfg(feature = "core")]
 pub use super::data_tables::LoadedTableCollection;
@@ -50,14 +58,19 @@ pub use core::{
 };
 pub use delay::{DelayIndexParameter, DelayParameter};
 pub use discount_factor::DiscountFactorParameter;
+pub use drought_trigger::DroughtTriggerParameter;
+pub use expression::ExpressionParameter;
+pub use forecast::{ForecastMember, ForecastParameter, ForecastValue};
+pub use hedging::{HedgingOutput, HedgingParameter, HedgingRule};
 pub use hydropower::HydropowerTargetParameter;
 pub use indexed_array::IndexedArrayParameter;
-pub use interpolated::InterpolatedParameter;
+pub use interpolated::{Extrapolation, InterpolatedParameter, InterpolationKind};
 pub use offset::OffsetParameter;
 pub use placeholder::PlaceholderParameter;
 pub use polynomial::Polynomial1DParameter;
 pub use profiles::{
-    DailyProfileParameter, DirunalProfileParameter, MonthlyInterpDay, MonthlyProfileParameter, RadialBasisFunction,
+    BreakpointDate, BreakpointInterpolation, BreakpointProfileParameter, DailyProfileParameter,
+    DirunalProfileParameter, LeapDayHandling, MonthlyInterpDay, MonthlyProfileParameter, RadialBasisFunction, RbfNorm,
     RbfProfileParameter, RbfProfileVariableSettings, UniformDrawdownProfileParameter, WeeklyProfileParameter,
 };
 pub use python::{PythonObject, PythonParameter, PythonReturnType};
@@ -67,12 +80,18 @@ use pywr_v1_schema::parameters::{
     ParameterValue as ParameterValueV1, TableIndex as TableIndexV1, TableIndexEntry as TableIndexEntryV1,
 };
 pub use rolling::{RollingIndexParameter, RollingParameter};
+pub use rule_set::{IndexRule, IndexRuleSetParameter, Rule, RuleCondition, RuleSetParameter};
+pub use sampled::{SampleDistribution, SampleFrequency, SampledParameter};
 use schemars::JsonSchema;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use strum_macros::{Display, EnumDiscriminants, EnumIter, EnumString, IntoStaticStr};
 pub use tables::TablesArrayParameter;
+pub use tariff::{TariffBand, TariffDays, TariffParameter};
 pub use thresholds::{MultiThresholdParameter, Predicate, ThresholdParameter};
+pub use weather_demand::{
+    DemandResponse, DemandSaving, DemandSensitivity, PopulationGrowth, RollingWindow, WeatherDemandParameter,
+};
 
 #[skip_serializing_none]
 #[derive(serde::Deserialize, serde::Serialize, Debug, Clone, JsonSchema, PywrVisitAll)]
@@ -126,6 +145,16 @@ pub enum Parameter {
     RollingIndex(RollingIndexParameter),
     Placeholder(PlaceholderParameter),
     DiurnalProfile(DirunalProfileParameter),
+    Tariff(TariffParameter),
+    Expression(ExpressionParameter),
+    RuleSet(RuleSetParameter),
+    IndexRuleSet(IndexRuleSetParameter),
+    DroughtTrigger(DroughtTriggerParameter),
+    Sampled(SampledParameter),
+    Forecast(ForecastParameter),
+    Hedging(HedgingParameter),
+    BreakpointProfile(BreakpointProfileParameter),
+    WeatherDemand(WeatherDemandParameter),
 }
 
 impl Parameter {
@@ -171,6 +200,16 @@ impl Parameter {
             Self::RollingIndex(p) => &p.meta,
             Self::Placeholder(p) => &p.meta,
             Self::DiurnalProfile(p) => &p.meta,
+            Self::Tariff(p) => &p.meta,
+            Self::Expression(p) => &p.meta,
+            Self::RuleSet(p) => &p.meta,
+            Self::IndexRuleSet(p) => &p.meta,
+            Self::DroughtTrigger(p) => &p.meta,
+            Self::Sampled(p) => &p.meta,
+            Self::Forecast(p) => &p.meta,
+            Self::Hedging(p) => &p.meta,
+            Self::BreakpointProfile(p) => &p.meta,
+            Self::WeatherDemand(p) => &p.meta,
         }
     }
 
@@ -275,6 +314,28 @@ impl Parameter {
             Self::DiurnalProfile(p) => {
                 pywr_core::parameters::ParameterType::Parameter(p.add_to_model(network, args, parent)?)
             }
+            Self::Tariff(p) => pywr_core::parameters::ParameterType::Parameter(p.add_to_model(network, args, parent)?),
+            Self::Expression(p) => {
+                pywr_core::parameters::ParameterType::Parameter(p.add_to_model(network, args, parent)?)
+            }
+            Self::RuleSet(p) => pywr_core::parameters::ParameterType::Multi(p.add_to_model(network, args, parent)?),
+            Self::IndexRuleSet(p) => {
+                pywr_core::parameters::ParameterType::Multi(p.add_to_model(network, args, parent)?)
+            }
+            Self::DroughtTrigger(p) => {
+                pywr_core::parameters::ParameterType::Index(p.add_to_model(network, args, parent)?)
+            }
+            Self::Sampled(p) => pywr_core::parameters::ParameterType::Parameter(p.add_to_model(network, args, parent)?),
+            Self::Forecast(p) => {
+                pywr_core::parameters::ParameterType::Parameter(p.add_to_model(network, args, parent)?)
+            }
+            Self::Hedging(p) => pywr_core::parameters::ParameterType::Parameter(p.add_to_model(network, args, parent)?),
+            Self::BreakpointProfile(p) => {
+                pywr_core::parameters::ParameterType::Parameter(p.add_to_model(network, args, parent)?)
+            }
+            Self::WeatherDemand(p) => {
+                pywr_core::parameters::ParameterType::Parameter(p.add_to_model(network, args, parent)?)
+            }
         };
 
         Ok(ty)
@@ -320,6 +381,16 @@ impl VisitMetrics for Parameter {
             Self::RollingIndex(p) => p.visit_metrics(visitor),
             Self::Placeholder(p) => p.visit_metrics(visitor),
             Self::DiurnalProfile(p) => p.visit_metrics(visitor),
+            Self::Tariff(p) => p.visit_metrics(visitor),
+            Self::Expression(p) => p.visit_metrics(visitor),
+            Self::RuleSet(p) => p.visit_metrics(visitor),
+            Self::IndexRuleSet(p) => p.visit_metrics(visitor),
+            Self::DroughtTrigger(p) => p.visit_metrics(visitor),
+            Self::Sampled(p) => p.visit_metrics(visitor),
+            Self::Forecast(p) => p.visit_metrics(visitor),
+            Self::Hedging(p) => p.visit_metrics(visitor),
+            Self::BreakpointProfile(p) => p.visit_metrics(visitor),
+            Self::WeatherDemand(p) => p.visit_metrics(visitor),
         }
     }
 
@@ -361,6 +432,16 @@ impl VisitMetrics for Parameter {
             Self::RollingIndex(p) => p.visit_metrics_mut(visitor),
             Self::Placeholder(p) => p.visit_metrics_mut(visitor),
             Self::DiurnalProfile(p) => p.visit_metrics_mut(visitor),
+            Self::Tariff(p) => p.visit_metrics_mut(visitor),
+            Self::Expression(p) => p.visit_metrics_mut(visitor),
+            Self::RuleSet(p) => p.visit_metrics_mut(visitor),
+            Self::IndexRuleSet(p) => p.visit_metrics_mut(visitor),
+            Self::DroughtTrigger(p) => p.visit_metrics_mut(visitor),
+            Self::Sampled(p) => p.visit_metrics_mut(visitor),
+            Self::Forecast(p) => p.visit_metrics_mut(visitor),
+            Self::Hedging(p) => p.visit_metrics_mut(visitor),
+            Self::BreakpointProfile(p) => p.visit_metrics_mut(visitor),
+            Self::WeatherDemand(p) => p.visit_metrics_mut(visitor),
         }
     }
 }
@@ -404,6 +485,16 @@ impl VisitPaths for Parameter {
             Self::RollingIndex(p) => p.visit_paths(visitor),
             Self::Placeholder(p) => p.visit_paths(visitor),
             Self::DiurnalProfile(p) => p.visit_paths(visitor),
+            Self::Tariff(p) => p.visit_paths(visitor),
+            Self::Expression(p) => p.visit_paths(visitor),
+            Self::RuleSet(p) => p.visit_paths(visitor),
+            Self::IndexRuleSet(p) => p.visit_paths(visitor),
+            Self::DroughtTrigger(p) => p.visit_paths(visitor),
+            Self::Sampled(p) => p.visit_paths(visitor),
+            Self::Forecast(p) => p.visit_paths(visitor),
+            Self::Hedging(p) => p.visit_paths(visitor),
+            Self::BreakpointProfile(p) => p.visit_paths(visitor),
+            Self::WeatherDemand(p) => p.visit_paths(visitor),
         }
     }
 
@@ -445,6 +536,16 @@ impl VisitPaths for Parameter {
             Self::RollingIndex(p) => p.visit_paths_mut(visitor),
             Self::Placeholder(p) => p.visit_paths_mut(visitor),
             Self::DiurnalProfile(p) => p.visit_paths_mut(visitor),
+            Self::Tariff(p) => p.visit_paths_mut(visitor),
+            Self::Expression(p) => p.visit_paths_mut(visitor),
+            Self::RuleSet(p) => p.visit_paths_mut(visitor),
+            Self::IndexRuleSet(p) => p.visit_paths_mut(visitor),
+            Self::DroughtTrigger(p) => p.visit_paths_mut(visitor),
+            Self::Sampled(p) => p.visit_paths_mut(visitor),
+            Self::Forecast(p) => p.visit_paths_mut(visitor),
+            Self::Hedging(p) => p.visit_paths_mut(visitor),
+            Self::BreakpointProfile(p) => p.visit_paths_mut(visitor),
+            Self::WeatherDemand(p) => p.visit_paths_mut(visitor),
         }
     }
 }
@@ -6,7 +6,9 @@ use crate::network::LoadArgs;
 use crate::parameters::{ConstantFloatVec, ConstantValue, ConversionData, ParameterMeta};
 use crate::v1::{TryFromV1, TryIntoV2, try_convert_values};
 #[cfg(feature = "core")]
-use pywr_core::parameters::{ParameterIndex, ParameterName, WeeklyProfileError, WeeklyProfileValues};
+use pywr_core::parameters::{
+    BreakpointProfileError, ParameterIndex, ParameterName, WeeklyProfileError, WeeklyProfileValues,
+};
 use pywr_schema_macros::{PywrVisitAll, skip_serializing_none};
 use pywr_v1_schema::parameters::{
     DailyProfileParameter as DailyProfileParameterV1, MonthInterpDay as MonthInterpDayV1,
@@ -159,6 +161,144 @@ impl TryFromV1<MonthlyProfileParameterV1> for MonthlyProfileParameter {
     }
 }
 
+/// The day and month of a breakpoint in a [`BreakpointProfileParameter`].
+#[derive(serde::Deserialize, serde::Serialize, Debug, Copy, Clone, JsonSchema, PywrVisitAll)]
+#[serde(deny_unknown_fields)]
+pub struct BreakpointDate {
+    pub day: u8,
+    pub month: u8,
+}
+
+/// How the value of a [`BreakpointProfileParameter`] changes between breakpoints.
+#[derive(
+    serde::Deserialize,
+    serde::Serialize,
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    JsonSchema,
+    PywrVisitAll,
+    Display,
+    EnumIter,
+)]
+pub enum BreakpointInterpolation {
+    /// The value of the latest breakpoint applies until the next breakpoint.
+    #[default]
+    Step,
+    /// The value is linearly interpolated between breakpoints.
+    Linear,
+}
+
+#[cfg(feature = "core")]
+impl From<BreakpointInterpolation> for pywr_core::parameters::BreakpointInterpolation {
+    fn from(value: BreakpointInterpolation) -> Self {
+        match value {
+            BreakpointInterpolation::Step => Self::Step,
+            BreakpointInterpolation::Linear => Self::Linear,
+        }
+    }
+}
+
+/// The calendar used by a [`BreakpointProfileParameter`].
+#[derive(
+    serde::Deserialize,
+    serde::Serialize,
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    JsonSchema,
+    PywrVisitAll,
+    Display,
+    EnumIter,
+)]
+pub enum LeapDayHandling {
+    /// Use the actual calendar of each year, including the 29th February in leap years.
+    #[default]
+    Calendar,
+    /// Use a 365-day year in which the 29th February has the same value as the 28th February.
+    NoLeap,
+    /// Use a 366-day year in which non-leap years skip the 29th February, as for a
+    /// [`DailyProfileParameter`]. This is required for a breakpoint on the 29th February.
+    Calendar366,
+}
+
+#[cfg(feature = "core")]
+impl From<LeapDayHandling> for pywr_core::parameters::LeapDayHandling {
+    fn from(value: LeapDayHandling) -> Self {
+        match value {
+            LeapDayHandling::Calendar => Self::Calendar,
+            LeapDayHandling::NoLeap => Self::NoLeap,
+            LeapDayHandling::Calendar366 => Self::Calendar366,
+        }
+    }
+}
+
+/// A parameter that defines an annual profile by values at breakpoint dates.
+///
+/// The `dates` of the breakpoints must be in increasing order within the year, and there must be
+/// one value for each date. The profile repeats each year, so before the first breakpoint of the
+/// year the profile continues from the last breakpoint of the previous year. With `Step`
+/// interpolation (the default) the value of the latest breakpoint applies until the next
+/// breakpoint, and with `Linear` interpolation the value is interpolated between the breakpoints.
+/// The time of day is included in the interpolation, so the profile can be used with sub-daily
+/// time-steps.
+///
+/// The `leap_day` handling controls the calendar in which the breakpoints and time-steps are
+/// positioned. By default the actual calendar of each year is used, and a breakpoint on the 29th
+/// February is only allowed with the `Calendar366` handling.
+///
+/// No Pywr v1 parameter is converted to a breakpoint profile. Pywr v1 has no breakpoint profile,
+/// and each of its daily, weekly and monthly profiles (including those with values in a table)
+/// is converted exactly to the matching v2 profile. A breakpoint profile could only approximate a
+/// v1 monthly profile interpolated from the last day of each month, because the breakpoint for
+/// February would be on the 28th in leap years.
+///
+/// # JSON Example
+///
+/// ```json
+#[doc = include_str!("doc_examples/breakpoint_profile.json")]
+/// ```
+#[skip_serializing_none]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, JsonSchema, PywrVisitAll)]
+#[serde(deny_unknown_fields)]
+pub struct BreakpointProfileParameter {
+    pub meta: ParameterMeta,
+    pub dates: Vec<BreakpointDate>,
+    pub values: ConstantFloatVec,
+    pub interpolation: Option<BreakpointInterpolation>,
+    pub leap_day: Option<LeapDayHandling>,
+}
+
+#[cfg(feature = "core")]
+impl BreakpointProfileParameter {
+    pub fn add_to_model(
+        &self,
+        network: &mut pywr_core::network::Network,
+        args: &LoadArgs,
+        parent: Option<&str>,
+    ) -> Result<ParameterIndex<f64>, SchemaError> {
+        let values = self.values.load(args.tables)?;
+        let dates = self.dates.iter().map(|d| (d.month as u32, d.day as u32)).collect();
+
+        let p = pywr_core::parameters::BreakpointProfileParameter::new(
+            ParameterName::new(&self.meta.name, parent),
+            dates,
+            values,
+            self.interpolation.unwrap_or_default().into(),
+            self.leap_day.unwrap_or_default().into(),
+        )
+        .map_err(|err: BreakpointProfileError| SchemaError::LoadParameter {
+            name: self.meta.name.to_string(),
+            error: err.to_string(),
+        })?;
+        Ok(network.add_simple_parameter(Box::new(p))?)
+    }
+}
+
 #[skip_serializing_none]
 #[derive(serde::Deserialize, serde::Serialize, Debug, Clone, JsonSchema, PywrVisitAll)]
 #[serde(deny_unknown_fields)]
@@ -304,6 +444,38 @@ fn estimate_epsilon(points: &[(u32, f64)]) -> Option<f64> {
     Some((x_range as f64 * y_range).powf(1.0 / points.len() as f64))
 }
 
+/// The measure of distance between days of the year used by a [`RbfProfileParameter`].
+#[derive(
+    serde::Deserialize,
+    serde::Serialize,
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    JsonSchema,
+    PywrVisitAll,
+    Display,
+    EnumIter,
+)]
+pub enum RbfNorm {
+    /// The number of days between two days of the year.
+    #[default]
+    Euclidean,
+    /// The square of the number of days between two days of the year.
+    SquaredEuclidean,
+}
+
+#[cfg(feature = "core")]
+impl From<RbfNorm> for pywr_core::parameters::RbfNorm {
+    fn from(value: RbfNorm) -> Self {
+        match value {
+            RbfNorm::Euclidean => Self::Euclidean,
+            RbfNorm::SquaredEuclidean => Self::SquaredEuclidean,
+        }
+    }
+}
+
 /// Settings for a variable RBF profile.
 #[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy, JsonSchema, PywrVisitAll)]
 #[serde(deny_unknown_fields)]
@@ -352,6 +524,14 @@ impl From<RbfProfileVariableSettings> for pywr_core::parameters::RbfProfileVaria
 #[doc = include_str!("doc_examples/rbf_2.json")]
 /// ```
 ///
+/// The example below shows a [`RbfProfileParameter`] with smoothing. This relaxes the
+/// interpolation so that the profile does not pass exactly through the points.
+///
+/// ```json
+#[doc = include_str!("doc_examples/rbf_3.json")]
+/// ```
+///
+#[skip_serializing_none]
 #[derive(serde::Deserialize, serde::Serialize, Debug, Clone, JsonSchema, PywrVisitAll)]
 #[serde(deny_unknown_fields)]
 pub struct RbfProfileParameter {
@@ -361,6 +541,14 @@ pub struct RbfProfileParameter {
     pub points: Vec<(u32, f64)>,
     /// The distance function used for interpolation.
     pub function: RadialBasisFunction,
+    /// Optional smoothing of the interpolation. As in SciPy's `Rbf` class this is subtracted from
+    /// the diagonal of the interpolation matrix. The default of zero gives an interpolation that
+    /// passes exactly through the points.
+    pub smooth: Option<f64>,
+    /// Optional measure of the distance between days of the year. Defaults to the Euclidean
+    /// distance (the number of days). The `Linear` function with the squared Euclidean distance
+    /// gives a singular interpolation matrix unless the interpolation is smoothed.
+    pub norm: Option<RbfNorm>,
     /// Optional settings for configuring how the value of this parameter can be varied. This
     /// is used by, for example, external algorithms to optimise the value of the parameter.
     pub variable: Option<RbfProfileVariableSettings>,
@@ -379,6 +567,8 @@ impl RbfProfileParameter {
             ParameterName::new(&self.meta.name, parent),
             self.points.clone(),
             function,
+            self.smooth.unwrap_or(0.0),
+            self.norm.unwrap_or_default().into(),
         );
         Ok(network.add_simple_parameter(Box::new(p))?)
     }
@@ -396,25 +586,53 @@ impl TryFromV1<RbfProfileParameterV1> for RbfProfileParameter {
 
         let points = v1.days_of_year.into_iter().zip(v1.values).collect();
 
-        if v1.rbf_kwargs.contains_key("smooth") {
-            return Err(Box::new(ComponentConversionError::Parameter {
-                name: meta.name,
-                attr: "smooth".to_string(),
-                error: ConversionError::UnsupportedFeature {
-                    feature: "The RBF `smooth` keyword argument is not supported.".to_string(),
-                },
-            }));
-        }
+        // Parse any smoothing value; we expect a float here.
+        let smooth = if let Some(smooth_value) = v1.rbf_kwargs.get("smooth") {
+            if let Some(smooth_f64) = smooth_value.as_f64() {
+                Some(smooth_f64)
+            } else {
+                return Err(Box::new(ComponentConversionError::Parameter {
+                    name: meta.name,
+                    attr: "smooth".to_string(),
+                    error: ConversionError::UnexpectedType {
+                        expected: "float".to_string(),
+                        actual: format!("{smooth_value}"),
+                    },
+                }));
+            }
+        } else {
+            None
+        };
 
-        if v1.rbf_kwargs.contains_key("norm") {
-            return Err(Box::new(ComponentConversionError::Parameter {
-                name: meta.name,
-                attr: "norm".to_string(),
-                error: ConversionError::UnsupportedFeature {
-                    feature: "The RBF `norm` keyword argument is not supported.".to_string(),
-                },
-            }));
-        }
+        // Parse any norm; only the norms that are distinct for one-dimensional points are
+        // supported, and the other SciPy norms are equivalent to the Euclidean distance.
+        let norm = if let Some(norm_value) = v1.rbf_kwargs.get("norm") {
+            match norm_value.as_str() {
+                Some("euclidean" | "cityblock" | "chebyshev" | "minkowski") => Some(RbfNorm::Euclidean),
+                Some("sqeuclidean") => Some(RbfNorm::SquaredEuclidean),
+                Some(norm_str) => {
+                    return Err(Box::new(ComponentConversionError::Parameter {
+                        name: meta.name,
+                        attr: "norm".to_string(),
+                        error: ConversionError::UnsupportedFeature {
+                            feature: format!("RBF norm `{norm_str}` not supported."),
+                        },
+                    }));
+                }
+                None => {
+                    return Err(Box::new(ComponentConversionError::Parameter {
+                        name: meta.name,
+                        attr: "norm".to_string(),
+                        error: ConversionError::UnexpectedType {
+                            expected: "string".to_string(),
+                            actual: format!("{norm_value}"),
+                        },
+                    }));
+                }
+            }
+        } else {
+            None
+        };
 
         // Parse any epsilon value; we expect a float here.
         let epsilon = if let Some(epsilon_value) = v1.rbf_kwargs.get("epsilon") {
@@ -443,6 +661,7 @@ impl TryFromV1<RbfProfileParameterV1> for RbfProfileParameter {
                     "gaussian" => RadialBasisFunction::Gaussian { epsilon },
                     "linear" => RadialBasisFunction::Linear,
                     "cubic" => RadialBasisFunction::Cubic,
+                    "quintic" => RadialBasisFunction::Quintic,
                     "thin_plate" => RadialBasisFunction::ThinPlateSpline,
                     _ => {
                         return Err(Box::new(ComponentConversionError::Parameter {
@@ -469,10 +688,27 @@ impl TryFromV1<RbfProfileParameterV1> for RbfProfileParameter {
             RadialBasisFunction::MultiQuadric { epsilon }
         };
 
+        // The linear function of the squared distances is singular without smoothing.
+        if matches!(function, RadialBasisFunction::Linear)
+            && norm == Some(RbfNorm::SquaredEuclidean)
+            && smooth.unwrap_or_default() == 0.0
+        {
+            return Err(Box::new(ComponentConversionError::Parameter {
+                name: meta.name,
+                attr: "rbf_kwargs".to_string(),
+                error: ConversionError::UnsupportedFeature {
+                    feature: "The `linear` function with the `sqeuclidean` norm and no smoothing is singular."
+                        .to_string(),
+                },
+            }));
+        }
+
         let p = Self {
             meta,
             points,
             function,
+            smooth,
+            norm,
             variable: None, // TODO convert variable settings
         };
 
@@ -719,4 +955,66 @@ mod tests {
 
         assert!(result.is_err());
     }
+
+    #[test]
+    fn add_to_model_with_unordered_breakpoints_returns_error() {
+        let meta = ParameterMeta {
+            name: "test".to_string(),
+            comment: None,
+            tags: Default::default(),
+        };
+        let param = BreakpointProfileParameter {
+            meta,
+            dates: vec![
+                BreakpointDate { day: 1, month: 7 },
+                BreakpointDate { day: 15, month: 3 },
+            ],
+            values: ConstantFloatVec::Literal { values: vec![1.0, 2.0] },
+            interpolation: None,
+            leap_day: None,
+        };
+        let domain: ModelDomain = default_time_domain().into();
+        let network = NetworkSchema {
+            parameters: Some(vec![Parameter::BreakpointProfile(param)]),
+            ..Default::default()
+        };
+
+        let result = network.build_network(&domain, None, None, &[]);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn rbf_profile_from_v1_rbf_kwargs() {
+        let convert = |rbf_kwargs: &str| {
+            let data = format!(
+                r#"{{
+                    "name": "profile",
+                    "days_of_year": [1, 90, 180, 270],
+                    "values": [0.5, 0.4, 0.3, 0.7],
+                    "rbf_kwargs": {rbf_kwargs}
+                }}"#
+            );
+            let v1: RbfProfileParameterV1 = serde_json::from_str(&data).unwrap();
+            let mut conversion_data = ConversionData::default();
+            RbfProfileParameter::try_from_v1(v1, None, &mut conversion_data).map_err(|e| e.to_string())
+        };
+
+        let p = convert(r#"{"function": "quintic", "smooth": 0.5, "norm": "sqeuclidean"}"#).unwrap();
+        assert!(matches!(p.function, RadialBasisFunction::Quintic));
+        assert_eq!(p.smooth, Some(0.5));
+        assert_eq!(p.norm, Some(RbfNorm::SquaredEuclidean));
+
+        let p = convert(r#"{"function": "thin_plate", "norm": "cityblock"}"#).unwrap();
+        assert!(matches!(p.function, RadialBasisFunction::ThinPlateSpline));
+        assert_eq!(p.norm, Some(RbfNorm::Euclidean));
+
+        let err = convert(r#"{"norm": "cosine"}"#).unwrap_err();
+        assert!(err.contains("`cosine` not supported"));
+
+        let err = convert(r#"{"function": "linear", "norm": "sqeuclidean"}"#).unwrap_err();
+        assert!(err.contains("is singular"));
+        let p = convert(r#"{"function": "linear", "norm": "sqeuclidean", "smooth": 0.5}"#).unwrap();
+        assert!(matches!(p.function, RadialBasisFunction::Linear));
+    }
 }
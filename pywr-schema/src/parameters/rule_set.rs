@@ -0,0 +1,245 @@
+#[cfg(feature = "core")]
+use crate::error::SchemaError;
+use crate::metric::{IndexMetric, Metric};
+#[cfg(feature = "core")]
+use crate::network::LoadArgs;
+use crate::parameters::{ParameterMeta, Predicate};
+#[cfg(feature = "core")]
+use pywr_core::parameters::{ParameterIndex, ParameterName};
+#[cfg(feature = "core")]
+use pywr_core::state::MultiValue;
+use pywr_schema_macros::{PywrVisitAll, skip_serializing_none};
+use schemars::JsonSchema;
+use strum_macros::{Display, EnumDiscriminants, EnumIter, EnumString, IntoStaticStr};
+
+/// A condition of a rule in a [`RuleSetParameter`] or [`IndexRuleSetParameter`].
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, JsonSchema, PywrVisitAll, Display, EnumDiscriminants)]
+#[serde(tag = "type", deny_unknown_fields)]
+#[strum_discriminants(derive(Display, IntoStaticStr, EnumString, EnumIter))]
+#[strum_discriminants(name(RuleConditionType))]
+pub enum RuleCondition {
+    /// Compare the value of a metric to a threshold.
+    Metric {
+        metric: Metric,
+        predicate: Predicate,
+        threshold: Metric,
+    },
+    /// Compare the value of an index metric to a threshold.
+    Index {
+        metric: IndexMetric,
+        predicate: Predicate,
+        threshold: IndexMetric,
+    },
+    /// The month of the time-step is one of the given months (1 to 12).
+    Months { months: Vec<u32> },
+    /// The day of the year of the time-step is between `start` and `end` (inclusive).
+    ///
+    /// Days are numbered from 1 to 366 as if every year is a leap year. If `start` is greater
+    /// than `end` the range wraps past the end of the year.
+    DayOfYear { start: u32, end: u32 },
+}
+
+#[cfg(feature = "core")]
+impl RuleCondition {
+    fn load(
+        &self,
+        network: &mut pywr_core::network::Network,
+        args: &LoadArgs,
+        parent: Option<&str>,
+        name: &str,
+    ) -> Result<pywr_core::parameters::RuleCondition, SchemaError> {
+        let condition = match self {
+            Self::Metric {
+                metric,
+                predicate,
+                threshold,
+            } => pywr_core::parameters::RuleCondition::Metric {
+                metric: metric.load(network, args, parent)?,
+                predicate: (*predicate).into(),
+                threshold: threshold.load(network, args, parent)?,
+            },
+            Self::Index {
+                metric,
+                predicate,
+                threshold,
+            } => pywr_core::parameters::RuleCondition::Index {
+                metric: metric.load(network, args, parent)?,
+                predicate: (*predicate).into(),
+                threshold: threshold.load(network, args, parent)?,
+            },
+            Self::Months { months } => {
+                if let Some(month) = months.iter().find(|m| !(1..=12).contains(*m)) {
+                    return Err(SchemaError::InvalidRuleCondition {
+                        name: name.to_string(),
+                        msg: format!("month {month} must be 1 to 12"),
+                    });
+                }
+                pywr_core::parameters::RuleCondition::Months(months.clone())
+            }
+            Self::DayOfYear { start, end } => {
+                if !(1..=366).contains(start) || !(1..=366).contains(end) {
+                    return Err(SchemaError::InvalidRuleCondition {
+                        name: name.to_string(),
+                        msg: format!("days of the year ({start} to {end}) must be 1 to 366"),
+                    });
+                }
+                pywr_core::parameters::RuleCondition::DayOfYear {
+                    start: *start,
+                    end: *end,
+                }
+            }
+        };
+
+        Ok(condition)
+    }
+}
+
+/// A named rule of a [`RuleSetParameter`].
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, JsonSchema, PywrVisitAll)]
+#[serde(deny_unknown_fields)]
+pub struct Rule {
+    pub name: String,
+    /// The conditions that must all be met for the rule to apply.
+    pub conditions: Vec<RuleCondition>,
+    /// The value returned when the rule applies.
+    pub value: Metric,
+}
+
+/// A parameter that returns the value of the first of an ordered set of rules whose conditions
+/// are all met.
+///
+/// The rules are evaluated in order each time-step, and the first rule whose conditions are all
+/// met applies. If no rule applies the `default` value is returned. A rule with no conditions
+/// always applies.
+///
+/// This is a multi-valued parameter with two outputs, which are referenced using the `key` of
+/// a parameter reference:
+///
+/// - `value`: the value of the rule that applies.
+/// - `rule`: the index of the rule that applies, or the number of rules if no rule applies.
+///
+/// The rule that applied in each time-step and scenario can be written to a file using a
+/// [`crate::outputs::RuleTraceOutput`].
+///
+/// # JSON Example
+///
+/// ```json
+#[doc = include_str!("doc_examples/rule_set.json")]
+/// ```
+#[skip_serializing_none]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, JsonSchema, PywrVisitAll)]
+#[serde(deny_unknown_fields)]
+pub struct RuleSetParameter {
+    pub meta: ParameterMeta,
+    pub rules: Vec<Rule>,
+    /// The value returned when no rule applies.
+    pub default: Metric,
+}
+
+impl RuleSetParameter {
+    /// The names of the rules in order.
+    pub fn rule_names(&self) -> Vec<String> {
+        self.rules.iter().map(|r| r.name.clone()).collect()
+    }
+}
+
+#[cfg(feature = "core")]
+impl RuleSetParameter {
+    pub fn add_to_model(
+        &self,
+        network: &mut pywr_core::network::Network,
+        args: &LoadArgs,
+        parent: Option<&str>,
+    ) -> Result<ParameterIndex<MultiValue>, SchemaError> {
+        let rules = self
+            .rules
+            .iter()
+            .map(|r| {
+                let conditions = r
+                    .conditions
+                    .iter()
+                    .map(|c| c.load(network, args, parent, &self.meta.name))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                Ok(pywr_core::parameters::Rule {
+                    name: r.name.clone(),
+                    conditions,
+                    value: r.value.load(network, args, parent)?,
+                })
+            })
+            .collect::<Result<Vec<_>, SchemaError>>()?;
+
+        let default = self.default.load(network, args, parent)?;
+
+        let p =
+            pywr_core::parameters::RuleSetParameter::new(ParameterName::new(&self.meta.name, parent), rules, default);
+        Ok(network.add_multi_value_parameter(Box::new(p))?)
+    }
+}
+
+/// A named rule of an [`IndexRuleSetParameter`].
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, JsonSchema, PywrVisitAll)]
+#[serde(deny_unknown_fields)]
+pub struct IndexRule {
+    pub name: String,
+    /// The conditions that must all be met for the rule to apply.
+    pub conditions: Vec<RuleCondition>,
+    /// The index returned when the rule applies.
+    pub value: IndexMetric,
+}
+
+/// A parameter that returns the index of the first of an ordered set of rules whose conditions
+/// are all met.
+///
+/// This is the same as a [`RuleSetParameter`] except that each rule returns an index. The `value`
+/// output is therefore an index, and can be used wherever an index metric is required.
+#[skip_serializing_none]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, JsonSchema, PywrVisitAll)]
+#[serde(deny_unknown_fields)]
+pub struct IndexRuleSetParameter {
+    pub meta: ParameterMeta,
+    pub rules: Vec<IndexRule>,
+    /// The index returned when no rule applies.
+    pub default: IndexMetric,
+}
+
+impl IndexRuleSetParameter {
+    /// The names of the rules in order.
+    pub fn rule_names(&self) -> Vec<String> {
+        self.rules.iter().map(|r| r.name.clone()).collect()
+    }
+}
+
+#[cfg(feature = "core")]
+impl IndexRuleSetParameter {
+    pub fn add_to_model(
+        &self,
+        network: &mut pywr_core::network::Network,
+        args: &LoadArgs,
+        parent: Option<&str>,
+    ) -> Result<ParameterIndex<MultiValue>, SchemaError> {
+        let rules = self
+            .rules
+            .iter()
+            .map(|r| {
+                let conditions = r
+                    .conditions
+                    .iter()
+                    .map(|c| c.load(network, args, parent, &self.meta.name))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                Ok(pywr_core::parameters::Rule {
+                    name: r.name.clone(),
+                    conditions,
+                    value: r.value.load(network, args, parent)?,
+                })
+            })
+            .collect::<Result<Vec<_>, SchemaError>>()?;
+
+        let default = self.default.load(network, args, parent)?;
+
+        let p =
+            pywr_core::parameters::RuleSetParameter::new(ParameterName::new(&self.meta.name, parent), rules, default);
+        Ok(network.add_multi_value_parameter(Box::new(p))?)
+    }
+}
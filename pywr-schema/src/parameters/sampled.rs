@@ -0,0 +1,141 @@
+#[cfg(feature = "core")]
+use crate::error::SchemaError;
+#[cfg(feature = "core")]
+use crate::network::LoadArgs;
+use crate::parameters::ParameterMeta;
+#[cfg(feature = "core")]
+use pywr_core::parameters::{ParameterIndex, ParameterName};
+use pywr_schema_macros::{PywrVisitAll, skip_serializing_none};
+use schemars::JsonSchema;
+use strum_macros::{Display, EnumDiscriminants, EnumIter, EnumString, IntoStaticStr};
+
+/// A probability distribution from which a [`SampledParameter`] draws its values.
+#[derive(
+    serde::Deserialize, serde::Serialize, Debug, Clone, PartialEq, JsonSchema, PywrVisitAll, Display, EnumDiscriminants,
+)]
+#[serde(tag = "type", deny_unknown_fields)]
+#[strum_discriminants(derive(Display, IntoStaticStr, EnumString, EnumIter))]
+#[strum_discriminants(name(SampleDistributionType))]
+pub enum SampleDistribution {
+    Normal {
+        mean: f64,
+        std_dev: f64,
+    },
+    /// A distribution whose logarithm is normally distributed with mean `mu` and standard
+    /// deviation `sigma`.
+    LogNormal {
+        mu: f64,
+        sigma: f64,
+    },
+    /// A uniform distribution between `low` and `high` (inclusive).
+    Uniform {
+        low: f64,
+        high: f64,
+    },
+    Triangular {
+        min: f64,
+        max: f64,
+        mode: f64,
+    },
+    /// One of `values`, each with the probability of the corresponding weight or with equal
+    /// probability if no weights are given.
+    Empirical {
+        values: Vec<f64>,
+        weights: Option<Vec<f64>>,
+    },
+}
+
+#[cfg(feature = "core")]
+impl SampleDistribution {
+    fn load(
+        &self,
+    ) -> Result<pywr_core::parameters::SampleDistribution, pywr_core::parameters::SampleDistributionError> {
+        use pywr_core::parameters::SampleDistribution as CoreDistribution;
+
+        match self {
+            Self::Normal { mean, std_dev } => CoreDistribution::normal(*mean, *std_dev),
+            Self::LogNormal { mu, sigma } => CoreDistribution::log_normal(*mu, *sigma),
+            Self::Uniform { low, high } => CoreDistribution::uniform(*low, *high),
+            Self::Triangular { min, max, mode } => CoreDistribution::triangular(*min, *max, *mode),
+            Self::Empirical { values, weights } => CoreDistribution::empirical(values.clone(), weights.clone()),
+        }
+    }
+}
+
+/// How often a [`SampledParameter`] draws a new value.
+#[derive(
+    serde::Deserialize,
+    serde::Serialize,
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    JsonSchema,
+    PywrVisitAll,
+    Display,
+    EnumIter,
+)]
+pub enum SampleFrequency {
+    /// A value is drawn once for each scenario.
+    #[default]
+    Scenario,
+    /// A value is drawn for each calendar year of each scenario.
+    Year,
+}
+
+/// A parameter that returns a value drawn from a probability distribution.
+///
+/// The value is drawn once for each scenario, or once for each calendar year of each scenario,
+/// depending on the `frequency`. The random number generator is seeded with the `seed` (which
+/// defaults to zero) and the global index of the scenario. The drawn values are therefore
+/// reproducible, and do not depend on the number of threads or on which scenarios are simulated.
+/// The drawn values can be recorded by including the parameter in a metric set.
+///
+/// # JSON Example
+///
+/// ```json
+#[doc = include_str!("doc_examples/sampled.json")]
+/// ```
+#[skip_serializing_none]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, JsonSchema, PywrVisitAll)]
+#[serde(deny_unknown_fields)]
+pub struct SampledParameter {
+    pub meta: ParameterMeta,
+    pub distribution: SampleDistribution,
+    pub seed: Option<u64>,
+    pub frequency: Option<SampleFrequency>,
+}
+
+#[cfg(feature = "core")]
+impl SampledParameter {
+    pub fn add_to_model(
+        &self,
+        network: &mut pywr_core::network::Network,
+        _args: &LoadArgs,
+        parent: Option<&str>,
+    ) -> Result<ParameterIndex<f64>, SchemaError> {
+        let distribution = self
+            .distribution
+            .load()
+            .map_err(|source| SchemaError::InvalidSampleDistribution {
+                name: self.meta.name.clone(),
+                source,
+            })?;
+        let name = ParameterName::new(&self.meta.name, parent);
+        let seed = self.seed.unwrap_or_default();
+
+        let idx = match self.frequency.unwrap_or_default() {
+            SampleFrequency::Scenario => {
+                let p = pywr_core::parameters::SampledConstantParameter::new(name, distribution, seed);
+                network.add_const_parameter(Box::new(p))?
+            }
+            SampleFrequency::Year => {
+                let p = pywr_core::parameters::SampledAnnualParameter::new(name, distribution, seed);
+                network.add_simple_parameter(Box::new(p))?
+            }
+        };
+
+        Ok(idx)
+    }
+}
@@ -0,0 +1,116 @@
+#[cfg(feature = "core")]
+use crate::error::SchemaError;
+#[cfg(feature = "core")]
+use crate::network::LoadArgs;
+use crate::parameters::{ConstantValue, ParameterMeta};
+#[cfg(feature = "core")]
+use pywr_core::parameters::{ParameterIndex, ParameterName};
+use pywr_schema_macros::{PywrVisitAll, skip_serializing_none};
+use schemars::JsonSchema;
+use strum_macros::{Display, EnumIter};
+
+/// The days of the week a [`TariffBand`] applies to.
+#[derive(
+    serde::Deserialize, serde::Serialize, Debug, Copy, Clone, Default, Display, JsonSchema, PywrVisitAll, EnumIter,
+)]
+pub enum TariffDays {
+    #[default]
+    All,
+    /// Monday to Friday.
+    Weekdays,
+    /// Saturday and Sunday.
+    Weekends,
+}
+
+#[cfg(feature = "core")]
+impl From<TariffDays> for pywr_core::parameters::TariffDays {
+    fn from(value: TariffDays) -> Self {
+        match value {
+            TariffDays::All => Self::All,
+            TariffDays::Weekdays => Self::Weekdays,
+            TariffDays::Weekends => Self::Weekends,
+        }
+    }
+}
+
+/// A period of the day during which a tariff applies a given rate.
+#[skip_serializing_none]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, JsonSchema, PywrVisitAll)]
+#[serde(deny_unknown_fields)]
+pub struct TariffBand {
+    /// The hour of the day the band starts (inclusive).
+    pub start_hour: u32,
+    /// The hour of the day the band ends (exclusive). If this is less than `start_hour` the
+    /// band wraps past midnight. If it is equal to `start_hour` the band covers the whole day.
+    pub end_hour: u32,
+    /// The days of the week the band applies to. Defaults to all days.
+    pub days: Option<TariffDays>,
+    /// The unit cost during the band.
+    pub rate: ConstantValue<f64>,
+}
+
+/// A time-of-use tariff that returns the unit cost applicable to each time-step.
+///
+/// The `bands` are checked in order and the rate of the first band that applies is returned.
+/// If no band applies the `default_rate` is returned. Sub-daily time-steps are supported; for
+/// time-steps longer than one hour the time-weighted average of the hourly rates is returned.
+///
+/// The parameter is typically used as the `tariff` of a node's pumping energy calculation.
+///
+/// # JSON Example
+///
+/// ```json
+#[doc = include_str!("doc_examples/tariff.json")]
+/// ```
+#[skip_serializing_none]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, JsonSchema, PywrVisitAll)]
+#[serde(deny_unknown_fields)]
+pub struct TariffParameter {
+    pub meta: ParameterMeta,
+    pub bands: Vec<TariffBand>,
+    /// The rate used outside all of the bands. Defaults to zero.
+    pub default_rate: Option<ConstantValue<f64>>,
+}
+
+#[cfg(feature = "core")]
+impl TariffParameter {
+    pub fn add_to_model(
+        &self,
+        network: &mut pywr_core::network::Network,
+        args: &LoadArgs,
+        parent: Option<&str>,
+    ) -> Result<ParameterIndex<f64>, SchemaError> {
+        let bands = self
+            .bands
+            .iter()
+            .map(|b| {
+                if b.start_hour > 24 || b.end_hour > 24 {
+                    return Err(SchemaError::InvalidTariffBand {
+                        name: self.meta.name.clone(),
+                        start_hour: b.start_hour,
+                        end_hour: b.end_hour,
+                    });
+                }
+
+                Ok(pywr_core::parameters::TariffBand {
+                    start_hour: b.start_hour,
+                    end_hour: b.end_hour,
+                    days: b.days.unwrap_or_default().into(),
+                    rate: b.rate.load(args.tables)?,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let default_rate = match &self.default_rate {
+            Some(r) => r.load(args.tables)?,
+            None => 0.0,
+        };
+
+        let p = pywr_core::parameters::TariffParameter::new(
+            ParameterName::new(&self.meta.name, parent),
+            bands,
+            default_rate,
+        );
+        Ok(network.add_simple_parameter(Box::new(p))?)
+    }
+}
@@ -0,0 +1,199 @@
+use crate::agg_funcs::AggFunc;
+#[cfg(feature = "core")]
+use crate::error::SchemaError;
+use crate::metric::{IndexMetric, Metric};
+#[cfg(feature = "core")]
+use crate::network::LoadArgs;
+use crate::parameters::ParameterMeta;
+#[cfg(feature = "core")]
+use pywr_core::parameters::{ParameterIndex, ParameterName};
+use pywr_schema_macros::{PywrVisitAll, skip_serializing_none};
+use schemars::JsonSchema;
+use strum_macros::{Display, EnumIter};
+
+/// How a weather metric changes the demand of a [`WeatherDemandParameter`].
+#[derive(
+    serde::Deserialize,
+    serde::Serialize,
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    JsonSchema,
+    PywrVisitAll,
+    Display,
+    EnumIter,
+)]
+pub enum DemandResponse {
+    /// The demand responds to the amount by which the metric exceeds the reference, such as the
+    /// temperature above a threshold.
+    #[default]
+    Excess,
+    /// The demand responds to the amount by which the metric is below the reference, such as the
+    /// shortfall of rainfall below the normal rainfall.
+    Deficit,
+    /// The demand responds to the difference between the metric and the reference.
+    Linear,
+}
+
+#[cfg(feature = "core")]
+impl From<DemandResponse> for pywr_core::parameters::DemandResponse {
+    fn from(value: DemandResponse) -> Self {
+        match value {
+            DemandResponse::Excess => Self::Excess,
+            DemandResponse::Deficit => Self::Deficit,
+            DemandResponse::Linear => Self::Linear,
+        }
+    }
+}
+
+/// A rolling window over which the metric of a [`DemandSensitivity`] is aggregated.
+///
+/// The aggregate is of the values of the metric over the previous `window_size` time-steps (see
+/// [`crate::parameters::RollingParameter`]). Until the window is full the metric is taken to be
+/// equal to the reference, so it has no effect on the demand.
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, JsonSchema, PywrVisitAll)]
+#[serde(deny_unknown_fields)]
+pub struct RollingWindow {
+    pub window_size: u64,
+    pub agg_func: AggFunc,
+}
+
+/// The sensitivity of the demand of a [`WeatherDemandParameter`] to a weather metric.
+///
+/// The change in demand, as a proportion of the base demand, is the `coefficient` multiplied by
+/// the response of the `metric` to the `reference`. By default the response is the amount by
+/// which the metric exceeds the reference. If a `rolling` window is given the metric is first
+/// aggregated over the window, for example to give the total rainfall over the previous 30 days.
+#[skip_serializing_none]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, JsonSchema, PywrVisitAll)]
+#[serde(deny_unknown_fields)]
+pub struct DemandSensitivity {
+    pub metric: Metric,
+    pub reference: f64,
+    pub coefficient: f64,
+    pub response: Option<DemandResponse>,
+    pub rolling: Option<RollingWindow>,
+}
+
+/// Annual population growth of a [`WeatherDemandParameter`].
+///
+/// The demand is multiplied by `(1 + rate)^n`, where `n` is the number of whole years since the
+/// `base_year`. The `rate` can vary by scenario, for example using a `ConstantScenario` parameter.
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, JsonSchema, PywrVisitAll)]
+#[serde(deny_unknown_fields)]
+pub struct PopulationGrowth {
+    pub rate: Metric,
+    pub base_year: i32,
+}
+
+/// Demand-saving factors of a [`WeatherDemandParameter`] for the levels of a drought index.
+///
+/// The demand is multiplied by the factor in the position of the current `level`, such as the
+/// level of a `DroughtTrigger` parameter, so the first factor is normally one. An error is raised
+/// if the level is greater than the number of factors.
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, JsonSchema, PywrVisitAll)]
+#[serde(deny_unknown_fields)]
+pub struct DemandSaving {
+    pub level: IndexMetric,
+    pub factors: Vec<f64>,
+}
+
+/// A parameter that calculates a demand from a base demand and its sensitivity to the weather.
+///
+/// The demand is calculated as:
+///
+/// ```text
+/// demand = base * growth * (1 + sum of the changes from the sensitivities) * saving
+/// ```
+///
+/// where the `base` demand is typically an annual profile, and the `growth` and `saving` factors
+/// are optional. Each of the `sensitivities` gives a proportional change in demand from a weather
+/// metric, such as the temperature above a threshold or the shortfall of rainfall over a rolling
+/// window. When the weather metrics and growth rate vary by scenario, the climate and population
+/// scenarios are applied consistently to the demand. The demand is never less than zero.
+///
+/// # JSON Example
+///
+/// ```json
+#[doc = include_str!("doc_examples/weather_demand.json")]
+/// ```
+#[skip_serializing_none]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, JsonSchema, PywrVisitAll)]
+#[serde(deny_unknown_fields)]
+pub struct WeatherDemandParameter {
+    pub meta: ParameterMeta,
+    pub base: Metric,
+    pub sensitivities: Vec<DemandSensitivity>,
+    pub growth: Option<PopulationGrowth>,
+    pub saving: Option<DemandSaving>,
+}
+
+#[cfg(feature = "core")]
+impl WeatherDemandParameter {
+    pub fn add_to_model(
+        &self,
+        network: &mut pywr_core::network::Network,
+        args: &LoadArgs,
+        parent: Option<&str>,
+    ) -> Result<ParameterIndex<f64>, SchemaError> {
+        let base = self.base.load(network, args, parent)?;
+
+        let mut sensitivities = Vec::with_capacity(self.sensitivities.len());
+        for (i, sensitivity) in self.sensitivities.iter().enumerate() {
+            let mut metric = sensitivity.metric.load(network, args, parent)?;
+
+            if let Some(rolling) = &sensitivity.rolling {
+                // The rolling aggregate is added as a child parameter of this parameter
+                let p = pywr_core::parameters::RollingParameter::new(
+                    ParameterName::new(&format!("rolling-{i}"), Some(self.meta.name.as_str())),
+                    metric,
+                    rolling.window_size as usize,
+                    sensitivity.reference,
+                    rolling.window_size as usize,
+                    rolling.agg_func.load(args.data_path)?,
+                );
+                metric = network.add_parameter(Box::new(p))?.into_metric_f64_before();
+            }
+
+            sensitivities.push(pywr_core::parameters::DemandSensitivity {
+                metric,
+                reference: sensitivity.reference,
+                coefficient: sensitivity.coefficient,
+                response: sensitivity.response.unwrap_or_default().into(),
+            });
+        }
+
+        let growth = self
+            .growth
+            .as_ref()
+            .map(|g| -> Result<_, SchemaError> {
+                Ok(pywr_core::parameters::PopulationGrowth {
+                    rate: g.rate.load(network, args, parent)?,
+                    base_year: g.base_year,
+                })
+            })
+            .transpose()?;
+
+        let saving = self
+            .saving
+            .as_ref()
+            .map(|s| -> Result<_, SchemaError> {
+                Ok(pywr_core::parameters::DemandSaving {
+                    level: s.level.load(network, args, parent)?,
+                    factors: s.factors.clone(),
+                })
+            })
+            .transpose()?;
+
+        let p = pywr_core::parameters::WeatherDemandParameter::new(
+            ParameterName::new(&self.meta.name, parent),
+            base,
+            sensitivities,
+            growth,
+            saving,
+        );
+        Ok(network.add_parameter(Box::new(p))?)
+    }
+}
@@ -0,0 +1,257 @@
+use crate::digest::Checksum;
+use crate::visit::{VisitMetrics, VisitPaths};
+use pywr_schema_macros::skip_serializing_none;
+use schemars::JsonSchema;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// An ensemble forecast dataset in long format.
+///
+/// The dataset has a row for each issue time, ensemble member and lead time. Lead times are
+/// whole time-steps from the issue time, starting from zero for the time-step in which the
+/// forecast is issued. Members are integer identifiers, and are numbered in ascending order of
+/// their identifiers. The default column names are `issue_time`, `member`, `lead` and `value`.
+/// There must be a value for every combination of issue time, member and lead time.
+///
+/// CSV (`.csv`) and Parquet (`.parquet`) files are loaded directly. HDF5 (`.h5`) files are loaded
+/// using Pandas, which requires Python support; any `kwargs` are passed to the Pandas load
+/// function.
+#[skip_serializing_none]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ForecastDataset {
+    pub url: PathBuf,
+    pub issue_col: Option<String>,
+    pub member_col: Option<String>,
+    pub lead_col: Option<String>,
+    pub value_col: Option<String>,
+    /// Keyword arguments to pass to the Pandas load function of HDF5 files.
+    pub kwargs: Option<HashMap<String, serde_json::Value>>,
+    /// Optional checksum to verify the dataset.
+    pub checksum: Option<Checksum>,
+}
+
+impl VisitMetrics for ForecastDataset {}
+
+impl VisitPaths for ForecastDataset {
+    fn visit_paths<F: FnMut(&Path)>(&self, visitor: &mut F) {
+        visitor(&self.url);
+    }
+
+    fn visit_paths_mut<F: FnMut(&mut PathBuf)>(&mut self, visitor: &mut F) {
+        visitor(&mut self.url);
+    }
+}
+
+#[cfg(feature = "core")]
+mod core {
+    use super::ForecastDataset;
+    use crate::timeseries::TimeseriesError;
+    use crate::timeseries::pandas::load_pandas;
+    use chrono::DateTime;
+    use polars::prelude::*;
+    use pywr_core::parameters::{Forecast, ForecastError};
+    use std::fs::File;
+    use std::path::Path;
+
+    impl ForecastDataset {
+        /// Load the forecast, and return it with the identifiers of its members in order.
+        pub fn load(&self, name: &str, data_path: Option<&Path>) -> Result<(Forecast, Vec<i64>), TimeseriesError> {
+            let fp = if self.url.is_absolute() {
+                self.url.clone()
+            } else if let Some(data_path) = data_path {
+                data_path.join(self.url.as_path())
+            } else {
+                self.url.clone()
+            };
+
+            // Validate the checksum if provided
+            if let Some(checksum) = &self.checksum {
+                checksum.check(&fp)?;
+            }
+
+            let ext = fp
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .map(|ext| ext.to_lowercase());
+            let df = match ext.as_deref() {
+                Some("csv") => {
+                    let parse_options = CsvParseOptions::default().with_try_parse_dates(true);
+                    CsvReadOptions::default()
+                        .with_has_header(true)
+                        .with_parse_options(parse_options)
+                        .try_into_reader_with_file_path(Some(fp))?
+                        .finish()?
+                }
+                Some("parquet") => ParquetReader::new(File::open(&fp).map_err(PolarsError::from)?).finish()?,
+                Some("h5") => load_pandas(&fp, None, self.kwargs.as_ref())?,
+                Some(other_ext) => {
+                    return Err(TimeseriesError::TimeseriesUnsupportedFileFormat {
+                        provider: "forecast".to_string(),
+                        fmt: other_ext.to_string(),
+                    });
+                }
+                None => {
+                    return Err(TimeseriesError::TimeseriesUnparsableFileFormat {
+                        provider: "forecast".to_string(),
+                        path: self.url.to_string_lossy().to_string(),
+                    });
+                }
+            };
+
+            let column = |col: &Option<String>, default: &str, dtype: DataType| {
+                let col = col.as_deref().unwrap_or(default);
+                df.column(col)
+                    .map_err(|_| TimeseriesError::ColumnNotFound {
+                        col: col.to_string(),
+                        name: name.to_string(),
+                    })?
+                    .strict_cast(&dtype)
+                    .map_err(TimeseriesError::from)
+            };
+
+            let issue_times = column(
+                &self.issue_col,
+                "issue_time",
+                DataType::Datetime(TimeUnit::Milliseconds, None),
+            )?;
+            let members = column(&self.member_col, "member", DataType::Int64)?;
+            let leads = column(&self.lead_col, "lead", DataType::UInt64)?;
+            let values = column(&self.value_col, "value", DataType::Float64)?;
+
+            let mut member_ids: Vec<i64> = members.i64()?.iter().flatten().collect();
+            member_ids.sort();
+            member_ids.dedup();
+
+            let records = issue_times
+                .datetime()?
+                .physical()
+                .iter()
+                .zip(members.i64()?.iter())
+                .zip(leads.u64()?.iter())
+                .zip(values.f64()?.iter())
+                .map(|(((issue_time, member), lead), value)| {
+                    let (Some(issue_time), Some(member), Some(lead)) = (issue_time, member, lead) else {
+                        return Err(TimeseriesError::ForecastMissingValues(name.to_string()));
+                    };
+                    let issue_time = DateTime::from_timestamp_millis(issue_time)
+                        .ok_or_else(|| TimeseriesError::ForecastMissingValues(name.to_string()))?
+                        .naive_utc();
+                    let Some(value) = value.filter(|v| !v.is_nan()) else {
+                        return Err(TimeseriesError::ForecastMissingValue {
+                            name: name.to_string(),
+                            issue_time,
+                            member,
+                            lead: lead as usize,
+                        });
+                    };
+                    let member = member_ids.binary_search(&member).expect("Member not found");
+
+                    Ok((issue_time, member, lead as usize, value))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let forecast = Forecast::from_long(records).map_err(|source| match source {
+                ForecastError::MissingValue {
+                    issue_time,
+                    member,
+                    lead,
+                } => TimeseriesError::ForecastMissingValue {
+                    name: name.to_string(),
+                    issue_time,
+                    member: member_ids[member],
+                    lead,
+                },
+                source => TimeseriesError::InvalidForecast {
+                    name: name.to_string(),
+                    source,
+                },
+            })?;
+
+            Ok((forecast, member_ids))
+        }
+    }
+    #[cfg(test)]
+    mod tests {
+        use super::ForecastDataset;
+        use crate::timeseries::TimeseriesError;
+        use polars::prelude::*;
+        use std::fs::File;
+        use std::path::{Path, PathBuf};
+        use tempfile::tempdir;
+
+        fn dataset(url: PathBuf) -> ForecastDataset {
+            ForecastDataset {
+                url,
+                issue_col: None,
+                member_col: None,
+                lead_col: None,
+                value_col: None,
+                kwargs: None,
+                checksum: None,
+            }
+        }
+
+        fn test_data_path() -> PathBuf {
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("tests")
+        }
+
+        /// Test that a Parquet file is loaded without Python.
+        #[test]
+        fn test_load_parquet() {
+            let csv = dataset("forecast1-inflow.csv".into());
+            let (forecast, members) = csv.load("forecast", Some(&test_data_path())).unwrap();
+
+            let dir = tempdir().unwrap();
+            let mut df = CsvReadOptions::default()
+                .with_has_header(true)
+                .with_parse_options(CsvParseOptions::default().with_try_parse_dates(true))
+                .try_into_reader_with_file_path(Some(test_data_path().join("forecast1-inflow.csv")))
+                .unwrap()
+                .finish()
+                .unwrap();
+            let file = File::create(dir.path().join("forecast.parquet")).unwrap();
+            ParquetWriter::new(file).finish(&mut df).unwrap();
+
+            let parquet = dataset("forecast.parquet".into());
+            let (parquet_forecast, parquet_members) = parquet.load("forecast", Some(dir.path())).unwrap();
+            assert_eq!(parquet_members, members);
+            assert_eq!(parquet_forecast.num_members(), forecast.num_members());
+            assert_eq!(parquet_forecast.num_leads(), forecast.num_leads());
+        }
+
+        /// Test that missing values are an error naming the issue time, member and lead.
+        #[test]
+        fn test_missing_values() {
+            let dir = tempdir().unwrap();
+            let data = "issue_time,member,lead,value
+2021-01-01,1,0,10.0
+2021-01-01,1,1,12.0
+2021-01-01,2,0,11.0
+2021-01-01,2,1,
+2021-01-02,1,0,10.0
+2021-01-02,1,1,12.0
+2021-01-02,2,0,11.0
+";
+            std::fs::write(dir.path().join("missing-value.csv"), data).unwrap();
+            let missing_value = dataset("missing-value.csv".into());
+            let err = missing_value.load("forecast", Some(dir.path())).unwrap_err();
+            assert!(
+                matches!(err, TimeseriesError::ForecastMissingValue { member: 2, lead: 1, .. }),
+                "{err}"
+            );
+            assert!(err.to_string().contains("2021-01-01"), "{err}");
+
+            // Remove the row with the missing value so the issue has no record for it
+            let data = data.replace("2021-01-01,2,1,\n", "");
+            std::fs::write(dir.path().join("missing-record.csv"), data).unwrap();
+            let missing_record = dataset("missing-record.csv".into());
+            let err = missing_record.load("forecast", Some(dir.path())).unwrap_err();
+            assert!(
+                matches!(err, TimeseriesError::ForecastMissingValue { member: 2, lead: 1, .. }),
+                "{err}"
+            );
+            assert!(err.to_string().contains("2021-01-01"), "{err}");
+        }
+    }
+}
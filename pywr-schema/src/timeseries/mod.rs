@@ -1,5 +1,6 @@
 #[cfg(feature = "core")]
 mod align_and_resample;
+mod forecast;
 mod pandas;
 mod polars_dataset;
 
@@ -9,6 +10,7 @@ use crate::error::ComponentConversionError;
 use crate::parameters::ParameterMeta;
 use crate::v1::{ConversionData, TryFromV1, TryIntoV2};
 use crate::visit::VisitPaths;
+pub use forecast::ForecastDataset;
 #[cfg(feature = "core")]
 use ndarray::{Array2, ShapeError, s};
 pub use pandas::PandasTimeseries;
@@ -76,6 +78,24 @@ pub enum TimeseriesError {
     #[cfg(feature = "core")]
     #[error("Shape error: {0}")]
     NdarrayShape(#[from] ShapeError),
+    #[error("The forecast dataset '{0}' has missing or invalid issue times, members or lead times")]
+    ForecastMissingValues(String),
+    #[error(
+        "The forecast dataset '{name}' has no value for member {member} at lead {lead} of the issue at {issue_time}"
+    )]
+    ForecastMissingValue {
+        name: String,
+        issue_time: chrono::NaiveDateTime,
+        member: i64,
+        lead: usize,
+    },
+    #[cfg(feature = "core")]
+    #[error("Invalid forecast dataset '{name}': {source}")]
+    InvalidForecast {
+        name: String,
+        #[source]
+        source: pywr_core::parameters::ForecastError,
+    },
     #[error("Pywr core network error: {0}")]
     #[cfg(feature = "core")]
     CoreNetworkError(#[from] pywr_core::NetworkError),
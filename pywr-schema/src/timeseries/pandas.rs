@@ -34,14 +34,26 @@ impl VisitPaths for PandasTimeseries {
     }
 }
 
+#[cfg(feature = "core")]
+pub(super) use self::core::load_pandas;
+
 #[cfg(all(feature = "core", not(feature = "pyo3")))]
 mod core {
     use super::PandasTimeseries;
     use crate::timeseries::TimeseriesError;
     use polars::frame::DataFrame;
     use pywr_core::models::ModelDomain;
+    use std::collections::HashMap;
     use std::path::Path;
 
+    pub fn load_pandas(
+        _fp: &Path,
+        _time_col: Option<&str>,
+        _kwargs: Option<&HashMap<String, serde_json::Value>>,
+    ) -> Result<DataFrame, TimeseriesError> {
+        Err(TimeseriesError::PythonNotEnabled)
+    }
+
     impl PandasTimeseries {
         pub fn load(&self, _data_path: Option<&Path>, _domain: &ModelDomain) -> Result<DataFrame, TimeseriesError> {
             Err(TimeseriesError::PythonNotEnabled)
@@ -64,14 +76,61 @@ mod core {
     use pyo3::{IntoPyObject, IntoPyObjectExt, Py, PyAny, PyErr, PyResult, Python};
     use pyo3_polars::PyDataFrame;
     use pywr_core::models::ModelDomain;
+    use std::collections::HashMap;
     use std::ffi::CStr;
     use std::path::Path;
 
+    /// Load a file using Pandas, and convert it to a Polars DataFrame.
+    ///
+    /// The `time_col` (or the first column if not given) is used as the index when reading CSV
+    /// files. Any index of the Pandas DataFrame is included as columns of the returned DataFrame.
+    pub fn load_pandas(
+        fp: &Path,
+        time_col: Option<&str>,
+        kwargs: Option<&HashMap<String, serde_json::Value>>,
+    ) -> Result<DataFrame, TimeseriesError> {
+        // Prepare the Python interpreter if not already
+        Python::initialize();
+
+        let df: PyDataFrame = Python::attach(|py| -> PyResult<PyDataFrame> {
+            let pandas_load =
+                PyModule::from_code(py, PANDAS_LOAD_SCRIPT, c_str!("pandas_load.py"), c_str!("pandas_load"))?;
+
+            let kwargs = kwargs
+                .map(|kwargs| {
+                    let kwargs: Vec<(Py<PyString>, Option<Py<PyAny>>)> = kwargs
+                        .iter()
+                        .map(|(k, v)| {
+                            let key = k.into_pyobject(py)?.unbind();
+                            let value = try_json_value_into_py(py, v)?;
+                            Ok((key, value))
+                        })
+                        .collect::<Result<Vec<_>, PyErr>>()?;
+
+                    let seq = PyTuple::new(py, kwargs)?;
+
+                    PyDict::from_sequence(seq.as_any())
+                })
+                .transpose()?;
+
+            // Time column used as the index, and then Pandas will parse the dates.
+            let index_col = time_col
+                .map(|col| col.into_bound_py_any(py))
+                .unwrap_or_else(|| 0.into_bound_py_any(py))?;
+
+            let df: PyDataFrame = pandas_load
+                .getattr("load_pandas")?
+                .call((fp, index_col), kwargs.as_ref())?
+                .extract()?;
+
+            Ok(df)
+        })?;
+
+        Ok(df.0)
+    }
+
     impl PandasTimeseries {
         pub fn load(&self, data_path: Option<&Path>, domain: &ModelDomain) -> Result<DataFrame, TimeseriesError> {
-            // Prepare the Python interpreter if not already
-            Python::initialize();
-
             let fp = if self.url.is_absolute() {
                 self.url.clone()
             } else if let Some(data_path) = data_path {
@@ -85,45 +144,7 @@ mod core {
                 checksum.check(&fp)?;
             }
 
-            let df: PyDataFrame = Python::attach(|py| -> PyResult<PyDataFrame> {
-                let pandas_load =
-                    PyModule::from_code(py, PANDAS_LOAD_SCRIPT, c_str!("pandas_load.py"), c_str!("pandas_load"))?;
-
-                let kwargs = self
-                    .kwargs
-                    .as_ref()
-                    .map(|kwargs| {
-                        let kwargs: Vec<(Py<PyString>, Option<Py<PyAny>>)> = kwargs
-                            .iter()
-                            .map(|(k, v)| {
-                                let key = k.into_pyobject(py)?.unbind();
-                                let value = try_json_value_into_py(py, v)?;
-                                Ok((key, value))
-                            })
-                            .collect::<Result<Vec<_>, PyErr>>()?;
-
-                        let seq = PyTuple::new(py, kwargs)?;
-
-                        PyDict::from_sequence(seq.as_any())
-                    })
-                    .transpose()?;
-
-                // Time column used as the index, and then Pandas will parse the dates.
-                let index_col = self
-                    .time_col
-                    .as_ref()
-                    .map(|col| col.into_bound_py_any(py))
-                    .unwrap_or_else(|| 0.into_bound_py_any(py))?;
-
-                let df: PyDataFrame = pandas_load
-                    .getattr("load_pandas")?
-                    .call((fp, index_col), kwargs.as_ref())?
-                    .extract()?;
-
-                Ok(df)
-            })?;
-
-            let mut df = df.0;
+            let mut df = load_pandas(&fp, self.time_col.as_deref(), self.kwargs.as_ref())?;
 
             df = match self.time_col {
                 Some(ref col) => align_and_resample(&self.meta.name, df, col, domain.time(), true)?,
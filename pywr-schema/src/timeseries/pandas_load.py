@@ -17,6 +17,8 @@ def load_pandas(path: str, index_col: Union[str, int], **kwargs) -> pl.DataFrame
             df = pd.read_excel(path, index_col=index_col, **kwargs)
         case ".h5":
             df = pd.read_hdf(path, **kwargs)
+        case ".parquet":
+            df = pd.read_parquet(path, **kwargs)
         case _:
             raise ValueError(f"Unsupported file format: {suffix}")
     return pl.from_pandas(df, include_index=True)
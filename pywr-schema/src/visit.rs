@@ -107,6 +107,7 @@ impl VisitMetrics for u8 {}
 impl VisitMetrics for u16 {}
 impl VisitMetrics for u32 {}
 impl VisitMetrics for i32 {}
+impl VisitMetrics for i64 {}
 impl VisitMetrics for chrono::Month {}
 impl VisitMetrics for f32 {}
 impl VisitMetrics for f64 {}
@@ -212,6 +213,7 @@ impl VisitPaths for u8 {}
 impl VisitPaths for u16 {}
 impl VisitPaths for u32 {}
 impl VisitPaths for i32 {}
+impl VisitPaths for i64 {}
 impl VisitPaths for chrono::Month {}
 impl VisitPaths for f32 {}
 impl VisitPaths for f64 {}
@@ -62,12 +62,13 @@ model_tests! {
     test_mutual_exclusivity2: ("mutual-exclusivity2.json", vec![("mutual-exclusivity2.csv", ResultsShape::Long)], vec!["clp", "ipm-simd", "ipm-ocl"], vec![]),
     test_mutual_exclusivity3: ("mutual-exclusivity3.json", vec![("mutual-exclusivity3.csv", ResultsShape::Long)], vec!["clp", "ipm-simd", "ipm-ocl"], vec![]),
     test_mutual_exclusivity4: ("mutual-exclusivity4.json", vec![("mutual-exclusivity4.csv", ResultsShape::Long)], vec!["clp", "ipm-simd", "ipm-ocl"], vec![]),
-    test_link_with_soft_min: ("link_with_soft_min.json", vec![], vec!["ipm-simd", "ipm-ocl"], vec![]),
-    test_link_with_soft_max: ("link_with_soft_max.json", vec![], vec!["ipm-simd", "ipm-ocl"], vec![]),
+    test_semi_continuous1: ("semi-continuous1.json", vec![("semi-continuous1.csv", ResultsShape::Long)], vec!["clp", "microlp", "ipm-simd", "ipm-ocl"], vec![]),
+    test_link_with_soft_min: ("link_with_soft_min.json", vec![], vec!["ipm-ocl"], vec![]),
+    test_link_with_soft_max: ("link_with_soft_max.json", vec![], vec!["ipm-ocl"], vec![]),
     test_delay1: ("delay1.json", vec![("delay1-expected.csv", ResultsShape::Long)], vec![], vec![]),
-    test_loss_link1: ("loss_link1.json", vec![("loss_link1-expected.csv", ResultsShape::Long)], vec!["ipm-simd", "ipm-ocl"], vec![]),
-    test_loss_link2: ("loss_link2.json", vec![("loss_link2-expected.csv", ResultsShape::Long)], vec!["ipm-simd", "ipm-ocl"], vec![]),
-    test_muskingum: ("muskingum1.json", vec![("muskingum1-expected.csv", ResultsShape::Long)], vec!["cbc", "ipm-simd", "ipm-ocl"], vec![]),
+    test_loss_link1: ("loss_link1.json", vec![("loss_link1-expected.csv", ResultsShape::Long)], vec!["ipm-ocl"], vec![]),
+    test_loss_link2: ("loss_link2.json", vec![("loss_link2-expected.csv", ResultsShape::Long)], vec!["ipm-ocl"], vec![]),
+    test_muskingum: ("muskingum1.json", vec![("muskingum1-expected.csv", ResultsShape::Long)], vec!["cbc", "ipm-ocl"], vec![]),
     // TODO this asserted internal flows in the previous test
     test_piecewise_link1: ("piecewise_link1.json", vec![("piecewise-link1-nodes.csv", ResultsShape::Long), ("piecewise-link1-edges.csv", ResultsShape::Long)], vec![], vec![]),
     // TODO not sure why this is failing in IPM solvers (https://github.com/pywr/pywr-next/issues/293)
@@ -79,31 +80,48 @@ model_tests! {
     test_reservoir_with_river1: ("reservoir_with_river1.json", vec![("reservoir_with_river1-expected.csv", ResultsShape::Long)], vec![], vec![]),
     test_reservoir_with_river2: ("reservoir_with_river2.json", vec![("reservoir_with_river2-expected.csv", ResultsShape::Long)], vec![], vec![]),
     test_reservoir_with_spill1: ("reservoir_with_spill1.json", vec![("reservoir_with_spill1-expected.csv", ResultsShape::Long)], vec![], vec![]),
-    test_river_loss1: ("river_loss1.json", vec![("river_loss1-expected.csv", ResultsShape::Long)], vec!["ipm-simd", "ipm-ocl"], vec![]),
+    test_reservoir_evaporation1: ("reservoir_evaporation1.json", vec![("reservoir_evaporation1-expected.csv", ResultsShape::Long)], vec!["cbc", "ipm-ocl"], vec![]),
+    test_river_loss1: ("river_loss1.json", vec![("river_loss1-expected.csv", ResultsShape::Long)], vec!["ipm-ocl"], vec![]),
     test_river_gauge1: ("river_gauge1.json", vec![("river_gauge1-expected.csv", ResultsShape::Long)], vec![], vec![]),
-    test_river_split_with_gauge1: ("river_split_with_gauge1.json", vec![("river_split_with_gauge1-expected.csv", ResultsShape::Long)], vec!["ipm-simd", "ipm-ocl"], vec![]),
-    test_river_split_with_gauge2: ("river_split_with_gauge2.json", vec![("river_split_with_gauge2-expected.csv", ResultsShape::Long)], vec!["ipm-simd", "ipm-ocl"], vec![]),
-    test_seasonal_vs1: ("seasonal-vs1.json", vec![("seasonal-vs1-expected.csv", ResultsShape::Long)], vec!["ipm-simd", "ipm-ocl"], vec![]),
-    test_seasonal_vs2: ("seasonal-vs2.json", vec![("seasonal-vs2-expected.csv", ResultsShape::Long)], vec!["ipm-simd", "ipm-ocl"], vec![]),
-    test_thirty_day_licence: ("30-day-licence.json", vec![], vec!["ipm-simd", "ipm-ocl"], vec![]),
-    test_wtw1: ("wtw1.json", vec![("wtw1-expected.csv", ResultsShape::Long)], vec!["ipm-simd", "ipm-ocl"], vec![]),
-    test_wtw2: ("wtw2.json", vec![("wtw2-expected.csv", ResultsShape::Long)], vec!["ipm-simd", "ipm-ocl"], vec![]),
+    test_river_split_with_gauge1: ("river_split_with_gauge1.json", vec![("river_split_with_gauge1-expected.csv", ResultsShape::Long)], vec!["ipm-ocl"], vec![]),
+    test_river_split_with_gauge2: ("river_split_with_gauge2.json", vec![("river_split_with_gauge2-expected.csv", ResultsShape::Long)], vec!["ipm-ocl"], vec![]),
+    test_seasonal_vs1: ("seasonal-vs1.json", vec![("seasonal-vs1-expected.csv", ResultsShape::Long)], vec!["ipm-ocl"], vec![]),
+    test_seasonal_vs2: ("seasonal-vs2.json", vec![("seasonal-vs2-expected.csv", ResultsShape::Long)], vec!["ipm-ocl"], vec![]),
+    test_thirty_day_licence: ("30-day-licence.json", vec![], vec!["ipm-ocl"], vec![]),
+    test_wtw1: ("wtw1.json", vec![("wtw1-expected.csv", ResultsShape::Long)], vec!["ipm-ocl"], vec![]),
+    test_wtw2: ("wtw2.json", vec![("wtw2-expected.csv", ResultsShape::Long)], vec!["ipm-ocl"], vec![]),
     test_local_parameter1: ("local-parameter1.json", vec![("local-parameter1-expected.csv", ResultsShape::Long)], vec![], vec![]),
     test_python_agg_func1: ("python-agg-func1.json", vec![("python-agg-func1-expected.csv", ResultsShape::Long)], vec![], vec![]),
     test_python_parameter2: ("python-parameter2.json", vec![("python-parameter2-expected.csv", ResultsShape::Long)], vec![], vec![]),
     test_python_parameter1: ("python-parameter1.json", vec![("python-parameter1-expected.csv", ResultsShape::Long)], vec![], vec![]),
     test_local_parameter2: ("local-parameter2.json", vec![("local-parameter2-expected.csv", ResultsShape::Long)], vec![], vec![]),
     test_tbl_formats1: ("tbl-formats1.json", vec![("tbl-formats1-expected.csv", ResultsShape::Long)], vec![], vec![]),
-    // IPM solvers currently do not support virtual storage nodes
-    test_vs_with_piecewise_link: ("vs-with-piecewise-link.json", vec![("vs-with-piecewise-link-expected.csv", ResultsShape::Long)], vec!["ipm-simd", "ipm-ocl"], vec![]),
-    test_vs_wtw1: ("vs-with-wtw1.json", vec![("vs-with-wtw1-expected.csv", ResultsShape::Long)], vec!["ipm-simd", "ipm-ocl"], vec![]),
-    test_vs_wtw2: ("vs-with-wtw2.json", vec![("vs-with-wtw2-expected.csv", ResultsShape::Long)], vec!["ipm-simd", "ipm-ocl"], vec![]),
-    test_vs_wtw3: ("vs-with-wtw3.json", vec![("vs-with-wtw3-expected.csv", ResultsShape::Long)], vec!["ipm-simd", "ipm-ocl"], vec![]),
-    test_abstraction1: ("abstraction1.json", vec![("abstraction1-expected.csv", ResultsShape::Long)], vec!["ipm-simd", "ipm-ocl"], vec![]),
-    test_abstraction2: ("abstraction2.json", vec![("abstraction2-expected.csv", ResultsShape::Long)], vec!["ipm-simd", "ipm-ocl"], vec![]),
+    // The OpenCL IPM solver does not support virtual storage nodes or aggregated node factors (used by
+    // the water treatment works)
+    test_vs_with_piecewise_link: ("vs-with-piecewise-link.json", vec![("vs-with-piecewise-link-expected.csv", ResultsShape::Long)], vec!["ipm-ocl"], vec![]),
+    test_vs_wtw1: ("vs-with-wtw1.json", vec![("vs-with-wtw1-expected.csv", ResultsShape::Long)], vec!["ipm-ocl"], vec![]),
+    test_vs_wtw2: ("vs-with-wtw2.json", vec![("vs-with-wtw2-expected.csv", ResultsShape::Long)], vec!["ipm-ocl"], vec![]),
+    test_vs_wtw3: ("vs-with-wtw3.json", vec![("vs-with-wtw3-expected.csv", ResultsShape::Long)], vec!["ipm-ocl"], vec![]),
+    test_abstraction1: ("abstraction1.json", vec![("abstraction1-expected.csv", ResultsShape::Long)], vec!["ipm-ocl"], vec![]),
+    test_abstraction2: ("abstraction2.json", vec![("abstraction2-expected.csv", ResultsShape::Long)], vec!["ipm-ocl"], vec![]),
     test_reservoir_failure_levels1: ("reservoir-failure-levels1.json", vec![("reservoir-failure-levels1-expected.csv", ResultsShape::Long)], vec![], vec![]),
     test_daily_profile1: ("daily-profile1.json", vec![("daily-profile1-expected.csv", ResultsShape::Long)], vec![], vec![]),
     test_turbine1: ("turbine1.json", vec![("turbine1-expected.csv", ResultsShape::Long)], vec![], vec![]),
+    test_constituent1: ("constituent1.json", vec![("constituent1-expected.csv", ResultsShape::Long)], vec![], vec![]),
+    test_pumping1: ("pumping1.json", vec![("pumping1-expected.csv", ResultsShape::Long)], vec![], vec![]),
+    // The OpenCL IPM solver does not support the aggregated node factors used by the water treatment works
+    test_pumping2: ("pumping2.json", vec![("pumping2-expected.csv", ResultsShape::Long)], vec!["ipm-ocl"], vec![]),
+    // The OpenCL IPM solver does not support the aggregated node that limits the total flow of the tiers
+    test_tiered_cost1: ("tiered-cost1.json", vec![("tiered-cost1-expected.csv", ResultsShape::Long)], vec!["ipm-ocl"], vec![]),
+    test_aquifer1: ("aquifer1.json", vec![("aquifer1-expected.csv", ResultsShape::Long)], vec!["ipm-ocl"], vec![]),
+    test_expression1: ("expression1.json", vec![("expression1-expected.csv", ResultsShape::Long)], vec![], vec![]),
+    test_rule_set1: ("rule_set1.json", vec![("rule_set1-expected.csv", ResultsShape::Long)], vec![], vec![]),
+    test_drought_trigger1: ("drought_trigger1.json", vec![("drought_trigger1-expected.csv", ResultsShape::Long)], vec![], vec![]),
+    test_sampled1: ("sampled1.json", vec![("sampled1-expected.csv", ResultsShape::Long)], vec![], vec![]),
+    test_forecast1: ("forecast1.json", vec![("forecast1-expected.csv", ResultsShape::Long)], vec![], vec![]),
+    test_hedging1: ("hedging1.json", vec![("hedging1-expected.csv", ResultsShape::Long)], vec![], vec![]),
+    test_breakpoint_profile1: ("breakpoint-profile1.json", vec![("breakpoint-profile1-expected.csv", ResultsShape::Long)], vec![], vec![]),
+    test_weather_demand1: ("weather-demand1.json", vec![("weather-demand1-expected.csv", ResultsShape::Long)], vec![], vec![]),
 }
 
 /// Test Pandas backend for reading timeseries data.
@@ -125,6 +143,45 @@ fn test_timeseries_pandas() {
     run_test_model(&_schema, &_expected_paths, &[], &[]);
 }
 
+/// Test that the merged outputs of a sharded run are the same as a single run.
+#[test]
+#[cfg(all(feature = "core", feature = "clp"))]
+fn test_timeseries2_shards() {
+    use pywr_core::recorders::merge_csv_long_outputs;
+    use pywr_core::solvers::{ClpSolver, ClpSolverSettings};
+    use pywr_schema::model::ScenarioShard;
+
+    let input_pth = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("timeseries2.json");
+    let expected_pth = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("timeseries2-expected.csv");
+    let data_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests");
+    let temp_dir = TempDir::new().unwrap();
+
+    let shard_outputs: Vec<PathBuf> = (0..3)
+        .map(|index| {
+            let mut schema = deserialise_test_model(&input_pth);
+            schema.scenarios.as_mut().unwrap().shard = Some(ScenarioShard { index, count: 3 });
+
+            let output_dir = temp_dir.path().join(format!("shard-{index}"));
+            fs::create_dir(&output_dir).unwrap();
+
+            let model = schema.build_model(Some(&data_dir), Some(&output_dir)).unwrap();
+            assert_eq!(model.domain().scenarios().global_len(), 10);
+            model.run::<ClpSolver>(&ClpSolverSettings::default()).unwrap();
+
+            output_dir.join("timeseries2-expected.csv")
+        })
+        .collect();
+
+    let merged = temp_dir.path().join("merged.csv");
+    merge_csv_long_outputs(&shard_outputs, &merged).unwrap();
+
+    ExpectedOutputsLong::new(merged, fs::read_to_string(expected_pth).unwrap()).verify();
+}
+
 fn deserialise_test_model(model_path: &Path) -> ModelSchema {
     let data = fs::read_to_string(model_path).expect("Unable to read file");
     serde_json::from_str(&data).expect("Failed to deserialize model")
@@ -0,0 +1,422 @@
+//! A sparse simplex solver for linear and mixed-integer programmes written in pure Rust.
+//!
+//! The solver implements the bounded primal and dual simplex methods with a sparse LU
+//! factorisation of the basis. The basis is kept between solves so that re-solving after
+//! changes to the bounds (dual simplex) or objective (primal simplex) is warm started; this
+//! is the typical pattern when solving the same problem for each time-step of a simulation.
+//! Problems with integer columns are solved by branch and bound.
+//!
+//! # Example
+//!
+//! ```
+//! use sparse_simplex::Problem;
+//!
+//! // max 2x + 3y s.t. x + y <= 4, x + 3y <= 6
+//! let mut problem = Problem::new(2);
+//! problem.set_objective(&[-2.0, -3.0]);
+//! problem
+//!     .add_rows(&[f64::NEG_INFINITY; 2], &[4.0, 6.0], &[0, 2, 4], &[0, 1, 0, 1], &[1.0, 1.0, 1.0, 3.0])
+//!     .unwrap();
+//! problem.solve().unwrap();
+//! assert!((problem.objective_value() + 9.0).abs() < 1e-9);
+//! ```
+mod lu;
+mod mip;
+mod simplex;
+
+use crate::simplex::Simplex;
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum SimplexError {
+    #[error("The problem is primal infeasible")]
+    Infeasible,
+    #[error("The problem is unbounded")]
+    Unbounded,
+    #[error("The iteration limit of {0} was reached")]
+    IterationLimit(usize),
+    #[error("The time limit of {0} seconds was reached")]
+    TimeLimit(f64),
+    #[error("The node limit of {0} was reached without finding an integer solution")]
+    NodeLimit(usize),
+    #[error("The solve was abandoned due to numerical difficulties")]
+    NumericalDifficulties,
+    #[error("There is no coefficient in row {row} and column {col} of the constraint matrix")]
+    MissingCoefficient { row: usize, col: usize },
+    #[error("Invalid problem: {0}")]
+    InvalidProblem(String),
+}
+
+/// The simplex method used to solve the problem.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum Strategy {
+    /// Use the dual simplex if the basis is dual feasible, and the primal simplex otherwise.
+    #[default]
+    Dual,
+    /// Always use the primal simplex.
+    Primal,
+}
+
+/// Settings for the solver.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Settings {
+    /// The tolerance for the bounds of the variables and rows.
+    pub primal_tolerance: f64,
+    /// The tolerance for the optimality of the reduced costs.
+    pub dual_tolerance: f64,
+    /// The maximum number of iterations of each solve.
+    pub iteration_limit: Option<usize>,
+    /// The maximum time, in seconds, of each solve.
+    pub time_limit: Option<f64>,
+    /// The number of basis updates before the basis is refactorised.
+    pub refactor_frequency: usize,
+    pub strategy: Strategy,
+    /// The maximum number of branch and bound nodes.
+    pub node_limit: usize,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            primal_tolerance: 1e-7,
+            dual_tolerance: 1e-7,
+            iteration_limit: None,
+            time_limit: None,
+            refactor_frequency: 100,
+            strategy: Strategy::Dual,
+            node_limit: 100_000,
+        }
+    }
+}
+
+/// A linear or mixed-integer programme that is minimised with the simplex method.
+///
+/// Columns have bounds `[0, inf)` and zero cost until changed. Bounds of +/-1e30 or larger are
+/// treated as infinite.
+pub struct Problem {
+    simplex: Simplex,
+    integer: Vec<bool>,
+    settings: Settings,
+    solution: Vec<f64>,
+}
+
+impl Problem {
+    /// Create a new problem with `num_cols` columns and no rows.
+    pub fn new(num_cols: usize) -> Self {
+        Self {
+            simplex: Simplex::new(num_cols),
+            integer: vec![false; num_cols],
+            settings: Settings::default(),
+            solution: vec![0.0; num_cols],
+        }
+    }
+
+    pub fn num_cols(&self) -> usize {
+        self.simplex.num_cols()
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.simplex.num_rows()
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    pub fn set_settings(&mut self, settings: Settings) {
+        self.settings = settings;
+    }
+
+    /// Append rows with the given bounds and elements in compressed sparse row format.
+    ///
+    /// This resets the basis, and should be used to create the problem before it is solved.
+    pub fn add_rows(
+        &mut self,
+        row_lower: &[f64],
+        row_upper: &[f64],
+        row_starts: &[usize],
+        columns: &[usize],
+        elements: &[f64],
+    ) -> Result<(), SimplexError> {
+        self.simplex
+            .add_rows(row_lower, row_upper, row_starts, columns, elements)
+    }
+
+    /// Set the lower and upper bounds of all of the columns.
+    pub fn set_col_bounds(&mut self, lower: &[f64], upper: &[f64]) {
+        for (col, (&l, &u)) in lower.iter().zip(upper).enumerate() {
+            self.simplex.set_col_bounds(col, l, u);
+        }
+    }
+
+    /// Set the lower and upper bounds of all of the rows.
+    pub fn set_row_bounds(&mut self, lower: &[f64], upper: &[f64]) {
+        for (row, (&l, &u)) in lower.iter().zip(upper).enumerate() {
+            self.simplex.set_row_bounds(row, l, u);
+        }
+    }
+
+    /// Set the objective coefficients of all of the columns.
+    pub fn set_objective(&mut self, cost: &[f64]) {
+        for (col, &c) in cost.iter().enumerate() {
+            self.simplex.set_cost(col, c);
+        }
+    }
+
+    pub fn set_objective_coefficient(&mut self, col: usize, cost: f64) {
+        self.simplex.set_cost(col, cost);
+    }
+
+    /// Change an existing element of the constraint matrix.
+    pub fn set_coefficient(&mut self, row: usize, col: usize, value: f64) -> Result<(), SimplexError> {
+        self.simplex.set_coefficient(row, col, value)
+    }
+
+    /// Restrict a column to integer values.
+    pub fn set_integer(&mut self, col: usize, integer: bool) {
+        self.integer[col] = integer;
+    }
+
+    /// Solve the problem, starting from the basis of the previous solve.
+    pub fn solve(&mut self) -> Result<(), SimplexError> {
+        let integer_cols: Vec<usize> = (0..self.num_cols()).filter(|&col| self.integer[col]).collect();
+
+        if integer_cols.is_empty() {
+            self.simplex.solve(&self.settings)?;
+            self.solution.copy_from_slice(self.simplex.primal_solution());
+        } else {
+            self.solution = mip::branch_and_bound(&mut self.simplex, &integer_cols, &self.settings)?;
+        }
+        Ok(())
+    }
+
+    /// The values of the columns from the last successful solve.
+    pub fn solution(&self) -> &[f64] {
+        &self.solution
+    }
+
+    /// The objective value of the last successful solve.
+    pub fn objective_value(&self) -> f64 {
+        (0..self.num_cols())
+            .map(|col| self.simplex.cost(col) * self.solution[col])
+            .sum()
+    }
+
+    /// The total number of simplex iterations performed by all solves.
+    pub fn iterations(&self) -> usize {
+        self.simplex.iterations()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Problem, Settings, SimplexError, Strategy};
+    use float_cmp::assert_approx_eq;
+
+    /// Build the problem from a dense matrix.
+    fn problem(cost: &[f64], rows: &[(&[f64], f64, f64)]) -> Problem {
+        let mut problem = Problem::new(cost.len());
+        problem.set_objective(cost);
+
+        let mut starts = vec![0];
+        let mut columns = Vec::new();
+        let mut elements = Vec::new();
+        for (row, _, _) in rows {
+            for (col, &value) in row.iter().enumerate() {
+                if value != 0.0 {
+                    columns.push(col);
+                    elements.push(value);
+                }
+            }
+            starts.push(columns.len());
+        }
+        let lower: Vec<f64> = rows.iter().map(|r| r.1).collect();
+        let upper: Vec<f64> = rows.iter().map(|r| r.2).collect();
+        problem.add_rows(&lower, &upper, &starts, &columns, &elements).unwrap();
+        problem
+    }
+
+    fn assert_solution(problem: &Problem, expected: &[f64]) {
+        for (a, b) in problem.solution().iter().zip(expected) {
+            assert_approx_eq!(f64, *a, *b, epsilon = 1e-7);
+        }
+    }
+
+    fn strategies() -> [Settings; 2] {
+        [
+            Settings::default(),
+            Settings {
+                strategy: Strategy::Primal,
+                ..Default::default()
+            },
+        ]
+    }
+
+    #[test]
+    fn test_simple_solve() {
+        for settings in strategies() {
+            let mut lp = problem(
+                &[-2.0, -3.0, -4.0],
+                &[(&[3.0, 2.0, 1.0], 0.0, 10.0), (&[2.0, 5.0, 3.0], 0.0, 15.0)],
+            );
+            lp.set_settings(settings);
+            lp.solve().unwrap();
+            assert_approx_eq!(f64, lp.objective_value(), -20.0, epsilon = 1e-9);
+            assert_solution(&lp, &[0.0, 0.0, 5.0]);
+        }
+    }
+
+    #[test]
+    fn test_equality_and_bounds() {
+        // min x - z s.t. x + y + z = 10, x - y >= 2, 0 <= z <= 4, y free
+        for settings in strategies() {
+            let mut lp = problem(
+                &[1.0, 0.0, -1.0],
+                &[(&[1.0, 1.0, 1.0], 10.0, 10.0), (&[1.0, -1.0, 0.0], 2.0, f64::INFINITY)],
+            );
+            lp.set_col_bounds(&[0.0, f64::NEG_INFINITY, 0.0], &[f64::INFINITY, f64::INFINITY, 4.0]);
+            lp.set_settings(settings);
+            lp.solve().unwrap();
+            // x = 4, y = 2, z = 4
+            assert_solution(&lp, &[4.0, 2.0, 4.0]);
+            assert_approx_eq!(f64, lp.objective_value(), 0.0, epsilon = 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_infeasible() {
+        for settings in strategies() {
+            let mut lp = problem(&[1.0, 1.0], &[(&[1.0, 1.0], 5.0, f64::INFINITY)]);
+            lp.set_col_bounds(&[0.0, 0.0], &[2.0, 2.0]);
+            lp.set_settings(settings);
+            assert_eq!(lp.solve(), Err(SimplexError::Infeasible));
+        }
+    }
+
+    #[test]
+    fn test_unbounded() {
+        let mut lp = problem(&[-1.0, 0.0], &[(&[1.0, -1.0], f64::NEG_INFINITY, 1.0)]);
+        assert_eq!(lp.solve(), Err(SimplexError::Unbounded));
+    }
+
+    #[test]
+    fn test_warm_start() {
+        // A transport problem whose supplies and costs change between solves
+        let mut lp = problem(
+            &[1.0, 2.0, 3.0, 1.0],
+            &[
+                (&[1.0, 1.0, 0.0, 0.0], f64::NEG_INFINITY, 10.0),
+                (&[0.0, 0.0, 1.0, 1.0], f64::NEG_INFINITY, 10.0),
+                (&[1.0, 0.0, 1.0, 0.0], 6.0, 6.0),
+                (&[0.0, 1.0, 0.0, 1.0], 8.0, 8.0),
+            ],
+        );
+        lp.solve().unwrap();
+        assert_solution(&lp, &[6.0, 0.0, 0.0, 8.0]);
+
+        // Reduce the second supply; the dual simplex re-solves from the previous basis
+        lp.set_row_bounds(
+            &[f64::NEG_INFINITY, f64::NEG_INFINITY, 6.0, 8.0],
+            &[10.0, 5.0, 6.0, 8.0],
+        );
+        lp.solve().unwrap();
+        assert_solution(&lp, &[6.0, 3.0, 0.0, 5.0]);
+
+        // Make the first route expensive; the primal simplex re-solves from the previous basis
+        lp.set_objective_coefficient(0, 10.0);
+        lp.solve().unwrap();
+        assert_solution(&lp, &[1.0, 8.0, 5.0, 0.0]);
+
+        // Changing a coefficient of the matrix
+        lp.set_coefficient(3, 3, 2.0).unwrap();
+        lp.solve().unwrap();
+        assert_approx_eq!(f64, lp.solution()[1] + 2.0 * lp.solution()[3], 8.0, epsilon = 1e-9);
+        assert!(lp.set_coefficient(0, 3, 1.0).is_err());
+    }
+
+    #[test]
+    fn test_integer() {
+        // A knapsack problem whose relaxation is fractional
+        let mut lp = problem(&[-5.0, -4.0, -3.0], &[(&[2.0, 3.0, 1.0], 0.0, 5.0)]);
+        lp.set_col_bounds(&[0.0; 3], &[1.0; 3]);
+        for col in 0..3 {
+            lp.set_integer(col, true);
+        }
+        lp.solve().unwrap();
+        assert_solution(&lp, &[1.0, 1.0, 0.0]);
+        assert_approx_eq!(f64, lp.objective_value(), -9.0, epsilon = 1e-9);
+
+        // The bounds of the integer columns are restored after branching
+        lp.set_row_bounds(&[0.0], &[6.0]);
+        lp.solve().unwrap();
+        assert_solution(&lp, &[1.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn test_random_problems() {
+        // Random feasible problems solved with both strategies and then re-solved after
+        // perturbing the bounds.
+        let mut seed = 12345u64;
+        let mut random = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            (seed % 10_000) as f64 / 10_000.0
+        };
+
+        for _ in 0..20 {
+            let num_cols = 30;
+            let num_rows = 20;
+            let cost: Vec<f64> = (0..num_cols).map(|_| random() * 2.0 - 1.0).collect();
+            let mut starts = vec![0];
+            let mut columns = Vec::new();
+            let mut elements = Vec::new();
+            for _ in 0..num_rows {
+                for col in 0..num_cols {
+                    if random() < 0.2 {
+                        columns.push(col);
+                        elements.push((random() * 4.0 - 2.0).round());
+                    }
+                }
+                starts.push(columns.len());
+            }
+            let row_lower: Vec<f64> = (0..num_rows).map(|_| -random() * 10.0).collect();
+            let row_upper: Vec<f64> = (0..num_rows).map(|_| random() * 10.0).collect();
+            let col_upper: Vec<f64> = (0..num_cols).map(|_| random() * 5.0).collect();
+
+            let mut objectives = Vec::new();
+            for settings in strategies() {
+                let mut lp = Problem::new(num_cols);
+                lp.set_settings(settings);
+                lp.set_objective(&cost);
+                lp.set_col_bounds(&vec![0.0; num_cols], &col_upper);
+                lp.add_rows(&row_lower, &row_upper, &starts, &columns, &elements)
+                    .unwrap();
+                lp.solve().unwrap();
+                objectives.push(lp.objective_value());
+
+                // Check the rows are feasible
+                for row in 0..num_rows {
+                    let activity: f64 = (starts[row]..starts[row + 1])
+                        .map(|k| elements[k] * lp.solution()[columns[k]])
+                        .sum();
+                    assert!(activity >= row_lower[row] - 1e-6 && activity <= row_upper[row] + 1e-6);
+                }
+
+                // Re-solve from the previous basis and compare with a cold start
+                let row_upper2: Vec<f64> = row_upper.iter().map(|u| u * 0.5).collect();
+                lp.set_row_bounds(&row_lower, &row_upper2);
+                lp.solve().unwrap();
+
+                let mut cold = Problem::new(num_cols);
+                cold.set_objective(&cost);
+                cold.set_col_bounds(&vec![0.0; num_cols], &col_upper);
+                cold.add_rows(&row_lower, &row_upper2, &starts, &columns, &elements)
+                    .unwrap();
+                cold.solve().unwrap();
+                assert_approx_eq!(f64, lp.objective_value(), cold.objective_value(), epsilon = 1e-6);
+            }
+            assert_approx_eq!(f64, objectives[0], objectives[1], epsilon = 1e-6);
+        }
+    }
+}
@@ -0,0 +1,518 @@
+//! Sparse LU factorisation of the simplex basis with product-form updates.
+//!
+//! The basis is factorised with a left-looking (Gilbert-Peierls) LU using threshold partial
+//! pivoting. Columns are processed in order of increasing number of non-zeros, and amongst the
+//! acceptable pivots the row with the fewest non-zeros is preferred. For the network-like
+//! problems solved by Pywr this keeps the factors almost triangular with very little fill-in.
+//!
+//! Changes of basis are applied as eta matrices (the product-form of the inverse) until the
+//! factorisation is recomputed.
+
+/// Pivots smaller than this are treated as zero when factorising.
+const SINGULAR_TOLERANCE: f64 = 1e-11;
+/// A pivot is acceptable if it is at least this fraction of the largest candidate.
+const PIVOT_THRESHOLD: f64 = 0.1;
+/// Values smaller than this are dropped from the eta matrices.
+const DROP_TOLERANCE: f64 = 1e-14;
+
+const NOT_PIVOTED: usize = usize::MAX;
+
+/// The columns of a basis matrix in compressed sparse column format.
+#[derive(Default)]
+pub(crate) struct BasisColumns {
+    pub start: Vec<usize>,
+    pub index: Vec<usize>,
+    pub value: Vec<f64>,
+}
+
+impl BasisColumns {
+    pub fn clear(&mut self) {
+        self.start.clear();
+        self.start.push(0);
+        self.index.clear();
+        self.value.clear();
+    }
+
+    pub fn push(&mut self, row: usize, value: f64) {
+        self.index.push(row);
+        self.value.push(value);
+    }
+
+    pub fn finish_column(&mut self) {
+        self.start.push(self.index.len());
+    }
+
+    fn column(&self, position: usize) -> (&[usize], &[f64]) {
+        let (start, end) = (self.start[position], self.start[position + 1]);
+        (&self.index[start..end], &self.value[start..end])
+    }
+}
+
+/// An eta matrix recording a single change of basis.
+struct Eta {
+    position: usize,
+    pivot: f64,
+    index: Vec<usize>,
+    value: Vec<f64>,
+}
+
+/// A sparse triangular factor stored by column in pivot order.
+#[derive(Default)]
+struct Factor {
+    start: Vec<usize>,
+    index: Vec<usize>,
+    value: Vec<f64>,
+}
+
+impl Factor {
+    fn clear(&mut self) {
+        self.start.clear();
+        self.start.push(0);
+        self.index.clear();
+        self.value.clear();
+    }
+
+    fn finish_column(&mut self) {
+        self.start.push(self.index.len());
+    }
+
+    fn column(&self, k: usize) -> (&[usize], &[f64]) {
+        let (start, end) = (self.start[k], self.start[k + 1]);
+        (&self.index[start..end], &self.value[start..end])
+    }
+}
+
+/// The LU factorisation of a basis matrix, `B`, with any subsequent eta updates.
+///
+/// Vectors indexed by row of the constraint matrix are "row space" and vectors indexed by
+/// position in the basis are "position space".
+pub(crate) struct BasisFactor {
+    num_rows: usize,
+    /// The original row of the `k`-th pivot.
+    pivot_row: Vec<usize>,
+    /// The basis position of the `k`-th pivot.
+    pivot_position: Vec<usize>,
+    lower: Factor,
+    upper: Factor,
+    diagonal: Vec<f64>,
+    etas: Vec<Eta>,
+    work: Vec<f64>,
+}
+
+impl BasisFactor {
+    pub fn new(num_rows: usize) -> Self {
+        Self {
+            num_rows,
+            pivot_row: Vec::with_capacity(num_rows),
+            pivot_position: Vec::with_capacity(num_rows),
+            lower: Factor::default(),
+            upper: Factor::default(),
+            diagonal: Vec::with_capacity(num_rows),
+            etas: Vec::new(),
+            work: vec![0.0; num_rows],
+        }
+    }
+
+    /// The number of eta updates since the last factorisation.
+    pub fn num_updates(&self) -> usize {
+        self.etas.len()
+    }
+
+    /// Factorise the basis with the given columns.
+    ///
+    /// Columns that are (numerically) linearly dependent on the others cannot be pivoted. These
+    /// are returned with the rows that were left without a pivot; the factorisation is of the
+    /// basis with each of these positions replaced by the slack (`-e_row`) of the paired row.
+    pub fn factorise(&mut self, columns: &BasisColumns) -> Vec<(usize, usize)> {
+        let m = self.num_rows;
+
+        self.pivot_row.clear();
+        self.pivot_position.clear();
+        self.lower.clear();
+        self.upper.clear();
+        self.diagonal.clear();
+        self.etas.clear();
+
+        let mut row_count = vec![0usize; m];
+        for &row in &columns.index {
+            row_count[row] += 1;
+        }
+
+        let mut order: Vec<usize> = (0..m).collect();
+        order.sort_by_key(|&p| columns.start[p + 1] - columns.start[p]);
+
+        // Map from original row to its pivot index
+        let mut pinv = vec![NOT_PIVOTED; m];
+        let mut x = vec![0.0; m];
+        let mut reach = Reach::new(m);
+        let mut singular = Vec::new();
+
+        for position in order {
+            let (rows, values) = columns.column(position);
+            let topo = reach.compute(rows, &pinv, &self.lower);
+
+            for (&row, &value) in rows.iter().zip(values) {
+                x[row] = value;
+            }
+
+            // Solve `L x = b` for the current column.
+            for &i in topo {
+                let k = pinv[i];
+                if k == NOT_PIVOTED || x[i] == 0.0 {
+                    continue;
+                }
+                let xi = x[i];
+                let (l_rows, l_values) = self.lower.column(k);
+                for (&r, &v) in l_rows.iter().zip(l_values) {
+                    x[r] -= v * xi;
+                }
+            }
+
+            let max_abs = topo
+                .iter()
+                .filter(|&&i| pinv[i] == NOT_PIVOTED)
+                .fold(0.0f64, |acc, &i| acc.max(x[i].abs()));
+
+            if max_abs <= SINGULAR_TOLERANCE {
+                singular.push(position);
+                for &i in topo {
+                    x[i] = 0.0;
+                }
+                continue;
+            }
+
+            // Choose the sparsest row amongst the acceptable pivots.
+            let mut pivot_row = NOT_PIVOTED;
+            for &i in topo {
+                if pinv[i] != NOT_PIVOTED || x[i].abs() < PIVOT_THRESHOLD * max_abs {
+                    continue;
+                }
+                if pivot_row == NOT_PIVOTED
+                    || row_count[i] < row_count[pivot_row]
+                    || (row_count[i] == row_count[pivot_row] && x[i].abs() > x[pivot_row].abs())
+                {
+                    pivot_row = i;
+                }
+            }
+
+            let pivot = x[pivot_row];
+            let k = self.pivot_row.len();
+
+            for &i in topo {
+                let xi = x[i];
+                x[i] = 0.0;
+                if xi == 0.0 || i == pivot_row {
+                    continue;
+                }
+                match pinv[i] {
+                    NOT_PIVOTED => {
+                        self.lower.index.push(i);
+                        self.lower.value.push(xi / pivot);
+                    }
+                    j => {
+                        self.upper.index.push(j);
+                        self.upper.value.push(xi);
+                    }
+                }
+            }
+            self.lower.finish_column();
+            self.upper.finish_column();
+            self.diagonal.push(pivot);
+
+            pinv[pivot_row] = k;
+            self.pivot_row.push(pivot_row);
+            self.pivot_position.push(position);
+        }
+
+        // Replace any singular columns with the slacks of the rows without a pivot.
+        let unpivoted: Vec<usize> = (0..m).filter(|&i| pinv[i] == NOT_PIVOTED).collect();
+        let replacements: Vec<(usize, usize)> = singular.into_iter().zip(unpivoted).collect();
+        for &(position, row) in &replacements {
+            pinv[row] = self.pivot_row.len();
+            self.pivot_row.push(row);
+            self.pivot_position.push(position);
+            self.lower.finish_column();
+            self.upper.finish_column();
+            self.diagonal.push(-1.0);
+        }
+
+        // Store the lower factor in pivot order.
+        for index in self.lower.index.iter_mut() {
+            *index = pinv[*index];
+        }
+
+        replacements
+    }
+
+    /// Record a change of basis at `position`, where `alpha` is the entering column in position
+    /// space (i.e. the result of [`BasisFactor::ftran`]).
+    pub fn update(&mut self, position: usize, alpha: &[f64]) {
+        let mut eta = Eta {
+            position,
+            pivot: alpha[position],
+            index: Vec::new(),
+            value: Vec::new(),
+        };
+        for (i, &v) in alpha.iter().enumerate() {
+            if i != position && v.abs() > DROP_TOLERANCE {
+                eta.index.push(i);
+                eta.value.push(v);
+            }
+        }
+        self.etas.push(eta);
+    }
+
+    /// Solve `B x = rhs` in place; `rhs` is given in row space and `x` is returned in position
+    /// space.
+    pub fn ftran(&mut self, rhs: &mut [f64]) {
+        let z = &mut self.work;
+        for (k, &row) in self.pivot_row.iter().enumerate() {
+            z[k] = rhs[row];
+        }
+
+        for k in 0..self.num_rows {
+            let zk = z[k];
+            if zk == 0.0 {
+                continue;
+            }
+            let (rows, values) = self.lower.column(k);
+            for (&i, &v) in rows.iter().zip(values) {
+                z[i] -= v * zk;
+            }
+        }
+
+        for k in (0..self.num_rows).rev() {
+            if z[k] == 0.0 {
+                continue;
+            }
+            z[k] /= self.diagonal[k];
+            let zk = z[k];
+            let (rows, values) = self.upper.column(k);
+            for (&i, &v) in rows.iter().zip(values) {
+                z[i] -= v * zk;
+            }
+        }
+
+        for (k, &position) in self.pivot_position.iter().enumerate() {
+            rhs[position] = z[k];
+        }
+
+        for eta in &self.etas {
+            let xr = rhs[eta.position] / eta.pivot;
+            rhs[eta.position] = xr;
+            if xr == 0.0 {
+                continue;
+            }
+            for (&i, &v) in eta.index.iter().zip(&eta.value) {
+                rhs[i] -= v * xr;
+            }
+        }
+    }
+
+    /// Solve `B^T y = rhs` in place; `rhs` is given in position space and `y` is returned in row
+    /// space.
+    pub fn btran(&mut self, rhs: &mut [f64]) {
+        for eta in self.etas.iter().rev() {
+            let mut sum = rhs[eta.position];
+            for (&i, &v) in eta.index.iter().zip(&eta.value) {
+                sum -= v * rhs[i];
+            }
+            rhs[eta.position] = sum / eta.pivot;
+        }
+
+        let w = &mut self.work;
+        for (k, &position) in self.pivot_position.iter().enumerate() {
+            w[k] = rhs[position];
+        }
+
+        for k in 0..self.num_rows {
+            let (rows, values) = self.upper.column(k);
+            let mut sum = w[k];
+            for (&i, &v) in rows.iter().zip(values) {
+                sum -= v * w[i];
+            }
+            w[k] = sum / self.diagonal[k];
+        }
+
+        for k in (0..self.num_rows).rev() {
+            let (rows, values) = self.lower.column(k);
+            let mut sum = w[k];
+            for (&i, &v) in rows.iter().zip(values) {
+                sum -= v * w[i];
+            }
+            w[k] = sum;
+        }
+
+        for (k, &row) in self.pivot_row.iter().enumerate() {
+            rhs[row] = w[k];
+        }
+    }
+}
+
+/// Work space for computing the rows reached when solving with the partial lower factor.
+struct Reach {
+    visited: Vec<usize>,
+    stamp: usize,
+    stack: Vec<(usize, usize)>,
+    postorder: Vec<usize>,
+}
+
+impl Reach {
+    fn new(num_rows: usize) -> Self {
+        Self {
+            visited: vec![0; num_rows],
+            stamp: 0,
+            stack: Vec::new(),
+            postorder: Vec::new(),
+        }
+    }
+
+    /// The rows reachable from `start` in the graph of the lower factor, in topological order.
+    fn compute(&mut self, start: &[usize], pinv: &[usize], lower: &Factor) -> &[usize] {
+        self.stamp += 1;
+        self.postorder.clear();
+
+        for &root in start {
+            if self.visited[root] == self.stamp {
+                continue;
+            }
+            self.visited[root] = self.stamp;
+            self.stack.push((root, 0));
+
+            while let Some(&(node, mut child)) = self.stack.last() {
+                let children: &[usize] = match pinv[node] {
+                    NOT_PIVOTED => &[],
+                    k => lower.column(k).0,
+                };
+
+                let mut next = None;
+                while child < children.len() {
+                    let c = children[child];
+                    child += 1;
+                    if self.visited[c] != self.stamp {
+                        next = Some(c);
+                        break;
+                    }
+                }
+
+                match next {
+                    Some(next) => {
+                        if let Some(top) = self.stack.last_mut() {
+                            top.1 = child;
+                        }
+                        self.visited[next] = self.stamp;
+                        self.stack.push((next, 0));
+                    }
+                    None => {
+                        self.postorder.push(node);
+                        self.stack.pop();
+                    }
+                }
+            }
+        }
+
+        self.postorder.reverse();
+        &self.postorder
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BasisColumns, BasisFactor};
+    use float_cmp::assert_approx_eq;
+
+    fn columns(dense: &[&[f64]]) -> BasisColumns {
+        let mut columns = BasisColumns::default();
+        columns.clear();
+        for col in dense {
+            for (row, &value) in col.iter().enumerate() {
+                if value != 0.0 {
+                    columns.push(row, value);
+                }
+            }
+            columns.finish_column();
+        }
+        columns
+    }
+
+    fn multiply(dense: &[&[f64]], x: &[f64]) -> Vec<f64> {
+        let mut b = vec![0.0; dense.len()];
+        for (col, &xj) in dense.iter().zip(x) {
+            for (row, &value) in col.iter().enumerate() {
+                b[row] += value * xj;
+            }
+        }
+        b
+    }
+
+    #[test]
+    fn test_ftran_btran() {
+        // Columns of the basis
+        let dense: &[&[f64]] = &[
+            &[2.0, 0.0, 1.0, 0.0],
+            &[0.0, -1.0, 0.0, 0.0],
+            &[1.0, 3.0, 0.0, 4.0],
+            &[0.0, 0.0, 5.0, 1.0],
+        ];
+        let mut factor = BasisFactor::new(4);
+        assert!(factor.factorise(&columns(dense)).is_empty());
+
+        let expected = [1.0, -2.0, 3.0, 0.5];
+        let mut x = multiply(dense, &expected);
+        factor.ftran(&mut x);
+        for (a, b) in x.iter().zip(expected) {
+            assert_approx_eq!(f64, *a, b, epsilon = 1e-12);
+        }
+
+        // B^T y = c
+        let y_expected = [0.5, 1.0, -1.0, 2.0];
+        let mut c: Vec<f64> = dense
+            .iter()
+            .map(|col| col.iter().zip(y_expected).map(|(a, y)| a * y).sum())
+            .collect();
+        factor.btran(&mut c);
+        for (a, b) in c.iter().zip(y_expected) {
+            assert_approx_eq!(f64, *a, b, epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_update() {
+        let dense: &[&[f64]] = &[&[1.0, 0.0, 0.0], &[0.0, 1.0, 0.0], &[0.0, 0.0, 1.0]];
+        let mut factor = BasisFactor::new(3);
+        factor.factorise(&columns(dense));
+
+        // Replace the second column with a new one
+        let entering = [1.0, 2.0, 3.0];
+        let mut alpha = entering.to_vec();
+        factor.ftran(&mut alpha);
+        factor.update(1, &alpha);
+
+        let updated: &[&[f64]] = &[&[1.0, 0.0, 0.0], &entering, &[0.0, 0.0, 1.0]];
+        let expected = [1.0, -1.0, 2.0];
+        let mut x = multiply(updated, &expected);
+        factor.ftran(&mut x);
+        for (a, b) in x.iter().zip(expected) {
+            assert_approx_eq!(f64, *a, b, epsilon = 1e-12);
+        }
+
+        let y_expected = [3.0, -2.0, 1.0];
+        let mut c: Vec<f64> = updated
+            .iter()
+            .map(|col| col.iter().zip(y_expected).map(|(a, y)| a * y).sum())
+            .collect();
+        factor.btran(&mut c);
+        for (a, b) in c.iter().zip(y_expected) {
+            assert_approx_eq!(f64, *a, b, epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_singular() {
+        // The third column is the sum of the first two.
+        let dense: &[&[f64]] = &[&[1.0, 1.0, 0.0], &[0.0, 1.0, 1.0], &[1.0, 2.0, 1.0]];
+        let mut factor = BasisFactor::new(3);
+        let replacements = factor.factorise(&columns(dense));
+        assert_eq!(replacements.len(), 1);
+    }
+}
@@ -0,0 +1,150 @@
+//! Depth-first branch and bound for problems with integer columns.
+//!
+//! Each node only tightens the bounds of the integer columns, so its parent's optimal basis
+//! remains dual feasible and the node is re-solved with a few iterations of the dual simplex.
+use crate::simplex::{BasisSnapshot, Simplex};
+use crate::{Settings, SimplexError};
+use std::rc::Rc;
+
+/// Values within this distance of an integer are considered integral.
+const INTEGER_TOLERANCE: f64 = 1e-6;
+
+struct Node {
+    /// The bounds of each integer column.
+    bounds: Vec<(f64, f64)>,
+    /// The optimal basis of the parent node.
+    basis: Rc<BasisSnapshot>,
+    /// The objective value of the parent node; a lower bound on the objective of this node.
+    bound: f64,
+}
+
+/// Solve the problem with the given columns restricted to integer values.
+///
+/// The bounds of the integer columns are restored afterwards, and the optimal basis of the
+/// relaxation is kept to warm start subsequent solves.
+pub(crate) fn branch_and_bound(
+    simplex: &mut Simplex,
+    integer_cols: &[usize],
+    settings: &Settings,
+) -> Result<Vec<f64>, SimplexError> {
+    let original: Vec<(f64, f64)> = integer_cols.iter().map(|&col| simplex.col_bounds(col)).collect();
+
+    let result = search(simplex, integer_cols, &original, settings);
+
+    for (&col, &(lower, upper)) in integer_cols.iter().zip(&original) {
+        simplex.set_col_bounds(col, lower, upper);
+    }
+
+    let (solution, root) = result?;
+    simplex.restore(&root);
+    Ok(solution)
+}
+
+fn search(
+    simplex: &mut Simplex,
+    integer_cols: &[usize],
+    original: &[(f64, f64)],
+    settings: &Settings,
+) -> Result<(Vec<f64>, BasisSnapshot), SimplexError> {
+    simplex.solve(settings)?;
+    let root = simplex.snapshot();
+
+    let mut incumbent: Option<(f64, Vec<f64>)> = None;
+    let mut stack: Vec<Node> = Vec::new();
+    let mut num_nodes = 0;
+
+    let mut bounds = original.to_vec();
+    loop {
+        let objective = simplex.objective_value();
+        let pruned = incumbent
+            .as_ref()
+            .is_some_and(|(best, _)| objective >= best - 1e-9 * best.abs().max(1.0));
+
+        if !pruned {
+            match most_fractional(simplex.primal_solution(), integer_cols) {
+                None => {
+                    let mut solution = simplex.primal_solution().to_vec();
+                    for &col in integer_cols {
+                        solution[col] = solution[col].round();
+                    }
+                    incumbent = Some((objective, solution));
+                }
+                Some((i, value)) => {
+                    let basis = Rc::new(simplex.snapshot());
+                    let mut down = bounds.clone();
+                    down[i].1 = value.floor();
+                    let mut up = bounds.clone();
+                    up[i].0 = value.ceil();
+
+                    // Explore the nearest branch first
+                    let (first, second) = if value - value.floor() < 0.5 {
+                        (down, up)
+                    } else {
+                        (up, down)
+                    };
+                    for bounds in [second, first] {
+                        stack.push(Node {
+                            bounds,
+                            basis: basis.clone(),
+                            bound: objective,
+                        });
+                    }
+                }
+            }
+        }
+
+        // Solve the next node that may improve on the incumbent
+        let mut solved = false;
+        while let Some(node) = stack.pop() {
+            if let Some((best, _)) = &incumbent
+                && node.bound >= best - 1e-9 * best.abs().max(1.0)
+            {
+                continue;
+            }
+
+            num_nodes += 1;
+            if num_nodes > settings.node_limit {
+                return match incumbent {
+                    Some((_, solution)) => Ok((solution, root)),
+                    None => Err(SimplexError::NodeLimit(settings.node_limit)),
+                };
+            }
+
+            for (&col, &(lower, upper)) in integer_cols.iter().zip(&node.bounds) {
+                simplex.set_col_bounds(col, lower, upper);
+            }
+            simplex.restore(&node.basis);
+            match simplex.solve(settings) {
+                Ok(()) => {
+                    bounds = node.bounds;
+                    solved = true;
+                    break;
+                }
+                Err(SimplexError::Infeasible) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+
+        if !solved {
+            return match incumbent {
+                Some((_, solution)) => Ok((solution, root)),
+                None => Err(SimplexError::Infeasible),
+            };
+        }
+    }
+}
+
+/// The integer column whose value is furthest from an integer, if any.
+fn most_fractional(solution: &[f64], integer_cols: &[usize]) -> Option<(usize, f64)> {
+    let mut best = None;
+    let mut best_distance = INTEGER_TOLERANCE;
+    for (i, &col) in integer_cols.iter().enumerate() {
+        let value = solution[col];
+        let distance = (value - value.round()).abs();
+        if distance > best_distance {
+            best_distance = distance;
+            best = Some((i, value));
+        }
+    }
+    best
+}
@@ -0,0 +1,999 @@
+//! The bounded primal and dual simplex methods.
+//!
+//! The problem is stored in the computational form
+//!
+//! ```text
+//!     min c^T x
+//!     s.t. A x - s = 0
+//!          l <= x <= u
+//!          r_l <= s <= r_u
+//! ```
+//!
+//! where `s` are the slack variables of the rows. The variables are indexed with the structural
+//! columns first followed by the slack of each row; the initial basis is the slacks.
+use crate::lu::{BasisColumns, BasisFactor};
+use crate::{Settings, SimplexError, Strategy};
+use std::time::Instant;
+
+/// Entries of the pivot column or row smaller than this are not considered as pivots.
+const PIVOT_TOLERANCE: f64 = 1e-7;
+/// The number of consecutive degenerate iterations before switching to Bland's rule.
+const DEGENERATE_ITERATIONS: usize = 50;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) enum Status {
+    Basic,
+    AtLower,
+    AtUpper,
+    /// A non-basic free variable at zero.
+    Free,
+}
+
+/// A basis that can be restored with [`Simplex::restore`].
+#[derive(Clone)]
+pub(crate) struct BasisSnapshot {
+    basis: Vec<usize>,
+    status: Vec<Status>,
+}
+
+/// The outcome of the dual simplex.
+enum DualOutcome {
+    Optimal,
+    /// The basis is primal feasible but no longer dual feasible.
+    DualInfeasible,
+}
+
+/// The result of the primal ratio test.
+enum PrimalStep {
+    /// The entering variable moves to its other bound.
+    Flip(f64),
+    /// The basic variable at `position` leaves the basis at one of its bounds.
+    Pivot {
+        position: usize,
+        theta: f64,
+        to_upper: bool,
+    },
+    Unbounded,
+}
+
+/// The iteration and time limits of a single solve.
+struct Limits {
+    start: Instant,
+    iterations: usize,
+    iteration_limit: Option<usize>,
+    time_limit: Option<f64>,
+}
+
+impl Limits {
+    fn new(settings: &Settings) -> Self {
+        Self {
+            start: Instant::now(),
+            iterations: 0,
+            iteration_limit: settings.iteration_limit,
+            time_limit: settings.time_limit,
+        }
+    }
+
+    fn iterate(&mut self) -> Result<(), SimplexError> {
+        self.iterations += 1;
+        if let Some(limit) = self.iteration_limit
+            && self.iterations > limit
+        {
+            return Err(SimplexError::IterationLimit(limit));
+        }
+        if let Some(limit) = self.time_limit
+            && self.start.elapsed().as_secs_f64() > limit
+        {
+            return Err(SimplexError::TimeLimit(limit));
+        }
+        Ok(())
+    }
+}
+
+pub(crate) struct Simplex {
+    num_cols: usize,
+    num_rows: usize,
+    // The structural columns of the constraint matrix in compressed sparse column format
+    col_start: Vec<usize>,
+    col_index: Vec<usize>,
+    col_value: Vec<f64>,
+    // The same matrix in compressed sparse row format; `row_to_col` maps each entry to its
+    // position in the column format.
+    row_start: Vec<usize>,
+    row_index: Vec<usize>,
+    row_value: Vec<f64>,
+    row_to_col: Vec<usize>,
+    // Bounds and costs of the structural and slack variables
+    lower: Vec<f64>,
+    upper: Vec<f64>,
+    cost: Vec<f64>,
+    // The current basis and values
+    basis: Vec<usize>,
+    status: Vec<Status>,
+    x: Vec<f64>,
+    d: Vec<f64>,
+    factor: BasisFactor,
+    factor_valid: bool,
+    // Work space
+    columns: BasisColumns,
+    work: Vec<f64>,
+    alpha: Vec<f64>,
+    alpha_row: Vec<f64>,
+    touched: Vec<usize>,
+    is_touched: Vec<bool>,
+    /// The total number of iterations performed.
+    iterations: usize,
+}
+
+impl Simplex {
+    pub fn new(num_cols: usize) -> Self {
+        let mut simplex = Self {
+            num_cols,
+            num_rows: 0,
+            col_start: vec![0; num_cols + 1],
+            col_index: Vec::new(),
+            col_value: Vec::new(),
+            row_start: vec![0],
+            row_index: Vec::new(),
+            row_value: Vec::new(),
+            row_to_col: Vec::new(),
+            lower: vec![0.0; num_cols],
+            upper: vec![f64::INFINITY; num_cols],
+            cost: vec![0.0; num_cols],
+            basis: Vec::new(),
+            status: Vec::new(),
+            x: Vec::new(),
+            d: Vec::new(),
+            factor: BasisFactor::new(0),
+            factor_valid: false,
+            columns: BasisColumns::default(),
+            work: Vec::new(),
+            alpha: Vec::new(),
+            alpha_row: Vec::new(),
+            touched: Vec::new(),
+            is_touched: Vec::new(),
+            iterations: 0,
+        };
+        simplex.reset_basis();
+        simplex
+    }
+
+    pub fn num_cols(&self) -> usize {
+        self.num_cols
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    /// Append rows given in compressed sparse row format. The basis is reset to the slacks.
+    pub fn add_rows(
+        &mut self,
+        row_lower: &[f64],
+        row_upper: &[f64],
+        row_starts: &[usize],
+        columns: &[usize],
+        elements: &[f64],
+    ) -> Result<(), SimplexError> {
+        let num_new = row_lower.len();
+        if row_upper.len() != num_new || row_starts.len() != num_new + 1 {
+            return Err(SimplexError::InvalidProblem("inconsistent number of rows".to_string()));
+        }
+        if columns.len() != elements.len() || row_starts[num_new] != columns.len() {
+            return Err(SimplexError::InvalidProblem(
+                "inconsistent number of matrix elements".to_string(),
+            ));
+        }
+        if let Some(&col) = columns.iter().find(|&&c| c >= self.num_cols) {
+            return Err(SimplexError::InvalidProblem(format!("column {col} is out of range")));
+        }
+
+        for row in 0..num_new {
+            for k in row_starts[row]..row_starts[row + 1] {
+                self.row_index.push(columns[k]);
+                self.row_value.push(elements[k]);
+            }
+            self.row_start.push(self.row_index.len());
+        }
+
+        self.lower.extend(row_lower.iter().map(|&v| normalise_bound(v)));
+        self.upper.extend(row_upper.iter().map(|&v| normalise_bound(v)));
+        self.cost.extend(std::iter::repeat_n(0.0, num_new));
+        self.num_rows += num_new;
+
+        self.build_columns();
+        self.reset_basis();
+        Ok(())
+    }
+
+    /// Rebuild the column format of the matrix from the row format.
+    fn build_columns(&mut self) {
+        let n = self.num_cols;
+        let mut counts = vec![0usize; n + 1];
+        for &col in &self.row_index {
+            counts[col + 1] += 1;
+        }
+        for j in 0..n {
+            counts[j + 1] += counts[j];
+        }
+        self.col_start = counts.clone();
+        self.col_index = vec![0; self.row_index.len()];
+        self.col_value = vec![0.0; self.row_index.len()];
+        self.row_to_col = vec![0; self.row_index.len()];
+
+        let mut next = counts;
+        for row in 0..self.num_rows {
+            for k in self.row_start[row]..self.row_start[row + 1] {
+                let col = self.row_index[k];
+                let pos = next[col];
+                next[col] += 1;
+                self.col_index[pos] = row;
+                self.col_value[pos] = self.row_value[k];
+                self.row_to_col[k] = pos;
+            }
+        }
+    }
+
+    /// Reset to the slack basis.
+    fn reset_basis(&mut self) {
+        let n = self.num_cols;
+        let m = self.num_rows;
+        self.basis = (n..n + m).collect();
+        self.status = vec![Status::AtLower; n + m];
+        for j in n..n + m {
+            self.status[j] = Status::Basic;
+        }
+        self.x = vec![0.0; n + m];
+        self.d = vec![0.0; n + m];
+        self.factor = BasisFactor::new(m);
+        self.factor_valid = false;
+        self.work = vec![0.0; m];
+        self.alpha = vec![0.0; m];
+        self.alpha_row = vec![0.0; n + m];
+        self.is_touched = vec![false; n + m];
+        self.touched.clear();
+    }
+
+    pub fn set_col_bounds(&mut self, col: usize, lower: f64, upper: f64) {
+        self.lower[col] = normalise_bound(lower);
+        self.upper[col] = normalise_bound(upper);
+    }
+
+    pub fn col_bounds(&self, col: usize) -> (f64, f64) {
+        (self.lower[col], self.upper[col])
+    }
+
+    pub fn set_row_bounds(&mut self, row: usize, lower: f64, upper: f64) {
+        let j = self.num_cols + row;
+        self.lower[j] = normalise_bound(lower);
+        self.upper[j] = normalise_bound(upper);
+    }
+
+    pub fn set_cost(&mut self, col: usize, cost: f64) {
+        self.cost[col] = cost;
+    }
+
+    pub fn cost(&self, col: usize) -> f64 {
+        self.cost[col]
+    }
+
+    /// Change an existing element of the constraint matrix.
+    pub fn set_coefficient(&mut self, row: usize, col: usize, value: f64) -> Result<(), SimplexError> {
+        let (start, end) = (self.row_start[row], self.row_start[row + 1]);
+        let k = self.row_index[start..end]
+            .iter()
+            .position(|&c| c == col)
+            .map(|k| start + k)
+            .ok_or(SimplexError::MissingCoefficient { row, col })?;
+
+        self.row_value[k] = value;
+        self.col_value[self.row_to_col[k]] = value;
+        if self.status[col] == Status::Basic {
+            self.factor_valid = false;
+        }
+        Ok(())
+    }
+
+    /// The values of the structural variables.
+    pub fn primal_solution(&self) -> &[f64] {
+        &self.x[..self.num_cols]
+    }
+
+    pub fn objective_value(&self) -> f64 {
+        self.x[..self.num_cols].iter().zip(&self.cost).map(|(x, c)| x * c).sum()
+    }
+
+    pub fn snapshot(&self) -> BasisSnapshot {
+        BasisSnapshot {
+            basis: self.basis.clone(),
+            status: self.status.clone(),
+        }
+    }
+
+    pub fn restore(&mut self, snapshot: &BasisSnapshot) {
+        if self.basis != snapshot.basis {
+            self.basis.clone_from(&snapshot.basis);
+            self.factor_valid = false;
+        }
+        self.status.clone_from(&snapshot.status);
+    }
+
+    /// Solve the problem starting from the current basis.
+    pub fn solve(&mut self, settings: &Settings) -> Result<(), SimplexError> {
+        let ptol = settings.primal_tolerance;
+        if (0..self.num_cols + self.num_rows).any(|j| self.lower[j] > self.upper[j] + ptol) {
+            return Err(SimplexError::Infeasible);
+        }
+
+        let mut limits = Limits::new(settings);
+
+        if !self.factor_valid {
+            self.refactor();
+        }
+        self.reset_nonbasic();
+        self.compute_dual(false, ptol);
+
+        let result = match settings.strategy {
+            Strategy::Primal => {
+                self.compute_primal();
+                self.primal(settings, &mut limits)
+            }
+            Strategy::Dual => {
+                let (_, infeasibilities) = self.make_dual_feasible(settings.dual_tolerance);
+                self.compute_primal();
+                if infeasibilities == 0 {
+                    match self.dual(settings, &mut limits) {
+                        Ok(DualOutcome::Optimal) => Ok(()),
+                        Ok(DualOutcome::DualInfeasible) => self.primal(settings, &mut limits),
+                        Err(e) => Err(e),
+                    }
+                } else {
+                    self.primal(settings, &mut limits)
+                }
+            }
+        };
+
+        self.iterations += limits.iterations;
+        result
+    }
+
+    /// Apply `f` to each element of the column of variable `j`.
+    fn for_each_in_column(&self, j: usize, mut f: impl FnMut(usize, f64)) {
+        if j < self.num_cols {
+            for k in self.col_start[j]..self.col_start[j + 1] {
+                f(self.col_index[k], self.col_value[k]);
+            }
+        } else {
+            f(j - self.num_cols, -1.0);
+        }
+    }
+
+    fn is_fixed(&self, j: usize) -> bool {
+        self.lower[j] == self.upper[j]
+    }
+
+    fn is_boxed(&self, j: usize) -> bool {
+        self.lower[j].is_finite() && self.upper[j].is_finite()
+    }
+
+    /// The status of a non-basic variable given its bounds; boxed variables keep their side.
+    fn nonbasic_status(&self, j: usize) -> Status {
+        match (self.lower[j].is_finite(), self.upper[j].is_finite()) {
+            (true, true) if self.status[j] == Status::AtUpper && !self.is_fixed(j) => Status::AtUpper,
+            (true, _) => Status::AtLower,
+            (false, true) => Status::AtUpper,
+            (false, false) => Status::Free,
+        }
+    }
+
+    fn nonbasic_value(&self, j: usize) -> f64 {
+        match self.status[j] {
+            Status::AtLower => self.lower[j],
+            Status::AtUpper => self.upper[j],
+            Status::Free | Status::Basic => 0.0,
+        }
+    }
+
+    /// Move the non-basic variables to their (possibly changed) bounds.
+    fn reset_nonbasic(&mut self) {
+        for j in 0..self.num_cols + self.num_rows {
+            if self.status[j] != Status::Basic {
+                self.status[j] = self.nonbasic_status(j);
+                self.x[j] = self.nonbasic_value(j);
+            }
+        }
+    }
+
+    /// Factorise the current basis, replacing any singular columns with slacks.
+    fn refactor(&mut self) {
+        self.columns.clear();
+        for p in 0..self.num_rows {
+            let j = self.basis[p];
+            if j < self.num_cols {
+                for k in self.col_start[j]..self.col_start[j + 1] {
+                    self.columns.push(self.col_index[k], self.col_value[k]);
+                }
+            } else {
+                self.columns.push(j - self.num_cols, -1.0);
+            }
+            self.columns.finish_column();
+        }
+
+        for (position, row) in self.factor.factorise(&self.columns) {
+            let leaving = self.basis[position];
+            let slack = self.num_cols + row;
+            self.basis[position] = slack;
+            self.status[slack] = Status::Basic;
+            self.status[leaving] = Status::AtLower;
+            self.status[leaving] = self.nonbasic_status(leaving);
+            self.x[leaving] = self.nonbasic_value(leaving);
+        }
+        self.factor_valid = true;
+    }
+
+    /// Compute the values of the basic variables from the non-basic variables.
+    fn compute_primal(&mut self) {
+        self.work.fill(0.0);
+        for j in 0..self.num_cols + self.num_rows {
+            if self.status[j] == Status::Basic || self.x[j] == 0.0 {
+                continue;
+            }
+            let xj = self.x[j];
+            if j < self.num_cols {
+                for k in self.col_start[j]..self.col_start[j + 1] {
+                    self.work[self.col_index[k]] -= self.col_value[k] * xj;
+                }
+            } else {
+                self.work[j - self.num_cols] += xj;
+            }
+        }
+        self.factor.ftran(&mut self.work);
+        for p in 0..self.num_rows {
+            self.x[self.basis[p]] = self.work[p];
+        }
+    }
+
+    /// The phase one cost of a basic variable; the gradient of its infeasibility.
+    fn infeasibility_cost(&self, j: usize, ptol: f64) -> f64 {
+        if self.x[j] < self.lower[j] - ptol {
+            -1.0
+        } else if self.x[j] > self.upper[j] + ptol {
+            1.0
+        } else {
+            0.0
+        }
+    }
+
+    fn has_primal_infeasibility(&self, ptol: f64) -> bool {
+        self.basis.iter().any(|&j| self.infeasibility_cost(j, ptol) != 0.0)
+    }
+
+    /// Compute the reduced costs for either the phase one (infeasibility) or true costs.
+    fn compute_dual(&mut self, phase_one: bool, ptol: f64) {
+        for p in 0..self.num_rows {
+            let j = self.basis[p];
+            self.work[p] = if phase_one {
+                self.infeasibility_cost(j, ptol)
+            } else {
+                self.cost[j]
+            };
+        }
+        self.factor.btran(&mut self.work);
+
+        let n = self.num_cols;
+        for j in 0..n + self.num_rows {
+            if self.status[j] == Status::Basic {
+                self.d[j] = 0.0;
+                continue;
+            }
+            let c = if phase_one { 0.0 } else { self.cost[j] };
+            self.d[j] = if j < n {
+                let mut dj = c;
+                for k in self.col_start[j]..self.col_start[j + 1] {
+                    dj -= self.col_value[k] * self.work[self.col_index[k]];
+                }
+                dj
+            } else {
+                c + self.work[j - n]
+            };
+        }
+    }
+
+    /// Move boxed variables to the bound that makes them dual feasible. Returns the number of
+    /// variables moved and the number of remaining dual infeasibilities.
+    fn make_dual_feasible(&mut self, dtol: f64) -> (usize, usize) {
+        let mut flips = 0;
+        let mut infeasibilities = 0;
+        for j in 0..self.num_cols + self.num_rows {
+            if self.is_fixed(j) {
+                continue;
+            }
+            let dj = self.d[j];
+            match self.status[j] {
+                Status::Basic => {}
+                Status::AtLower if dj < -dtol => {
+                    if self.is_boxed(j) {
+                        self.status[j] = Status::AtUpper;
+                        self.x[j] = self.upper[j];
+                        flips += 1;
+                    } else {
+                        infeasibilities += 1;
+                    }
+                }
+                Status::AtUpper if dj > dtol => {
+                    if self.is_boxed(j) {
+                        self.status[j] = Status::AtLower;
+                        self.x[j] = self.lower[j];
+                        flips += 1;
+                    } else {
+                        infeasibilities += 1;
+                    }
+                }
+                Status::Free if dj.abs() > dtol => infeasibilities += 1,
+                _ => {}
+            }
+        }
+        (flips, infeasibilities)
+    }
+
+    /// Compute the column of the entering variable in terms of the current basis.
+    fn compute_alpha(&mut self, q: usize) {
+        self.alpha.fill(0.0);
+        let mut alpha = std::mem::take(&mut self.alpha);
+        self.for_each_in_column(q, |i, v| alpha[i] = v);
+        self.factor.ftran(&mut alpha);
+        self.alpha = alpha;
+    }
+
+    /// Refactorise and recompute the primal values if there have been any updates. Returns
+    /// `true` if the factorisation was recomputed.
+    fn refresh(&mut self) -> bool {
+        if self.factor.num_updates() == 0 && self.factor_valid {
+            return false;
+        }
+        self.refactor();
+        self.compute_primal();
+        true
+    }
+
+    /// The primal simplex method, using the sum of infeasibilities as the objective in phase one.
+    fn primal(&mut self, settings: &Settings, limits: &mut Limits) -> Result<(), SimplexError> {
+        let ptol = settings.primal_tolerance;
+        let dtol = settings.dual_tolerance;
+        let mut degenerate = 0;
+
+        loop {
+            if self.factor.num_updates() >= settings.refactor_frequency {
+                self.refactor();
+                self.compute_primal();
+            }
+
+            let phase_one = self.has_primal_infeasibility(ptol);
+            self.compute_dual(phase_one, ptol);
+
+            let bland = degenerate > DEGENERATE_ITERATIONS;
+            let Some((q, direction)) = self.price_primal(dtol, bland) else {
+                if self.refresh() {
+                    continue;
+                }
+                return if phase_one {
+                    Err(SimplexError::Infeasible)
+                } else {
+                    Ok(())
+                };
+            };
+
+            limits.iterate()?;
+            self.compute_alpha(q);
+
+            let theta = match self.primal_ratio_test(q, direction, ptol, bland) {
+                PrimalStep::Unbounded => {
+                    if self.refresh() {
+                        continue;
+                    }
+                    return Err(if phase_one {
+                        SimplexError::NumericalDifficulties
+                    } else {
+                        SimplexError::Unbounded
+                    });
+                }
+                PrimalStep::Flip(theta) => {
+                    self.update_primal(q, direction * theta);
+                    if direction > 0.0 {
+                        self.status[q] = Status::AtUpper;
+                        self.x[q] = self.upper[q];
+                    } else {
+                        self.status[q] = Status::AtLower;
+                        self.x[q] = self.lower[q];
+                    }
+                    theta
+                }
+                PrimalStep::Pivot {
+                    position,
+                    theta,
+                    to_upper,
+                } => {
+                    self.update_primal(q, direction * theta);
+                    let leaving = self.basis[position];
+                    if to_upper {
+                        self.status[leaving] = Status::AtUpper;
+                        self.x[leaving] = self.upper[leaving];
+                    } else {
+                        self.status[leaving] = Status::AtLower;
+                        self.x[leaving] = self.lower[leaving];
+                    }
+                    self.basis[position] = q;
+                    self.status[q] = Status::Basic;
+                    self.factor.update(position, &self.alpha);
+                    theta
+                }
+            };
+
+            if theta > ptol {
+                degenerate = 0;
+            } else {
+                degenerate += 1;
+            }
+        }
+    }
+
+    /// Move the entering variable `q` by `step` and update the basic variables.
+    fn update_primal(&mut self, q: usize, step: f64) {
+        for p in 0..self.num_rows {
+            self.x[self.basis[p]] -= step * self.alpha[p];
+        }
+        self.x[q] += step;
+    }
+
+    /// Choose the entering variable with the largest reduced cost, or the first eligible
+    /// variable with Bland's rule.
+    fn price_primal(&self, dtol: f64, bland: bool) -> Option<(usize, f64)> {
+        let mut best: Option<(usize, f64)> = None;
+        let mut best_score = 0.0;
+
+        for j in 0..self.num_cols + self.num_rows {
+            if self.is_fixed(j) {
+                continue;
+            }
+            let dj = self.d[j];
+            let direction = match self.status[j] {
+                Status::AtLower if dj < -dtol => 1.0,
+                Status::AtUpper if dj > dtol => -1.0,
+                Status::Free if dj.abs() > dtol => -dj.signum(),
+                _ => continue,
+            };
+            if bland {
+                return Some((j, direction));
+            }
+            if dj * dj > best_score {
+                best_score = dj * dj;
+                best = Some((j, direction));
+            }
+        }
+        best
+    }
+
+    /// The ratio test of the primal simplex using Harris' two pass method.
+    fn primal_ratio_test(&self, q: usize, direction: f64, ptol: f64, bland: bool) -> PrimalStep {
+        // The step at which a basic variable reaches a bound, with the bounds relaxed by `tol`
+        let limit = |p: usize, tol: f64| -> Option<(f64, bool)> {
+            let a = self.alpha[p];
+            if a.abs() <= PIVOT_TOLERANCE {
+                return None;
+            }
+            let rate = -direction * a;
+            let j = self.basis[p];
+            let (x, l, u) = (self.x[j], self.lower[j], self.upper[j]);
+            if rate < 0.0 {
+                if x > u + ptol {
+                    // Infeasible above its upper bound; it becomes feasible at the upper bound
+                    Some(((x - u + tol) / -rate, true))
+                } else if x < l - ptol || !l.is_finite() {
+                    None
+                } else {
+                    Some(((x - l + tol) / -rate, false))
+                }
+            } else if x < l - ptol {
+                Some(((l - x + tol) / rate, false))
+            } else if x > u + ptol || !u.is_finite() {
+                None
+            } else {
+                Some(((u - x + tol) / rate, true))
+            }
+        };
+
+        let mut theta_max = f64::INFINITY;
+        for p in 0..self.num_rows {
+            if let Some((theta, _)) = limit(p, ptol) {
+                theta_max = theta_max.min(theta);
+            }
+        }
+
+        if self.is_boxed(q) {
+            let range = self.upper[q] - self.lower[q];
+            if range <= theta_max {
+                return PrimalStep::Flip(range);
+            }
+        }
+
+        if theta_max.is_infinite() {
+            return PrimalStep::Unbounded;
+        }
+
+        let mut best: Option<(usize, f64, bool)> = None;
+        for p in 0..self.num_rows {
+            let Some((theta, to_upper)) = limit(p, 0.0) else {
+                continue;
+            };
+            if theta > theta_max {
+                continue;
+            }
+            let better = match best {
+                None => true,
+                Some((b, _, _)) if bland => self.basis[p] < self.basis[b],
+                Some((b, _, _)) => self.alpha[p].abs() > self.alpha[b].abs(),
+            };
+            if better {
+                best = Some((p, theta.max(0.0), to_upper));
+            }
+        }
+
+        match best {
+            Some((position, theta, to_upper)) => PrimalStep::Pivot {
+                position,
+                theta,
+                to_upper,
+            },
+            None => PrimalStep::Unbounded,
+        }
+    }
+
+    /// Choose the leaving basic variable with the largest primal infeasibility, or the
+    /// infeasible variable with the smallest index with Bland's rule.
+    fn price_dual(&self, ptol: f64, bland: bool) -> Option<usize> {
+        let mut best = None;
+        let mut best_infeasibility = 0.0;
+        for p in 0..self.num_rows {
+            let j = self.basis[p];
+            let infeasibility = (self.lower[j] - self.x[j]).max(self.x[j] - self.upper[j]);
+            if infeasibility <= ptol {
+                continue;
+            }
+            let better = if bland {
+                best.is_none_or(|b: usize| j < self.basis[b])
+            } else {
+                infeasibility > best_infeasibility
+            };
+            if better {
+                best = Some(p);
+                best_infeasibility = infeasibility;
+            }
+        }
+        best
+    }
+
+    /// Compute the pivot row of the basic variable at `position` for the non-basic variables.
+    fn compute_alpha_row(&mut self, position: usize) {
+        for &j in &self.touched {
+            self.alpha_row[j] = 0.0;
+            self.is_touched[j] = false;
+        }
+        self.touched.clear();
+
+        self.work.fill(0.0);
+        self.work[position] = 1.0;
+        self.factor.btran(&mut self.work);
+
+        let n = self.num_cols;
+        for i in 0..self.num_rows {
+            let rho = self.work[i];
+            if rho == 0.0 {
+                continue;
+            }
+            for k in self.row_start[i]..self.row_start[i + 1] {
+                let j = self.row_index[k];
+                if self.status[j] == Status::Basic {
+                    continue;
+                }
+                if !self.is_touched[j] {
+                    self.is_touched[j] = true;
+                    self.touched.push(j);
+                }
+                self.alpha_row[j] += rho * self.row_value[k];
+            }
+            let slack = n + i;
+            if self.status[slack] != Status::Basic {
+                self.alpha_row[slack] = -rho;
+                self.is_touched[slack] = true;
+                self.touched.push(slack);
+            }
+        }
+    }
+
+    /// The ratio test of the dual simplex using Harris' two pass method. `sign` is positive if
+    /// the leaving variable moves to its upper bound.
+    fn dual_ratio_test(&self, sign: f64, dtol: f64, bland: bool) -> Option<usize> {
+        // The dual step at which the reduced cost of `j` changes sign, relaxed by `tol`
+        let limit = |j: usize, tol: f64| -> Option<f64> {
+            let a = self.alpha_row[j];
+            if a.abs() <= PIVOT_TOLERANCE || self.is_fixed(j) {
+                return None;
+            }
+            let dj = self.d[j];
+            match self.status[j] {
+                Status::AtLower if sign * a > 0.0 => Some((dj.max(0.0) + tol) / a.abs()),
+                Status::AtUpper if sign * a < 0.0 => Some(((-dj).max(0.0) + tol) / a.abs()),
+                Status::Free => Some((dj.abs() + tol) / a.abs()),
+                _ => None,
+            }
+        };
+
+        let mut theta_max = f64::INFINITY;
+        for &j in &self.touched {
+            if let Some(theta) = limit(j, dtol) {
+                theta_max = theta_max.min(theta);
+            }
+        }
+        if theta_max.is_infinite() {
+            return None;
+        }
+
+        let mut best: Option<usize> = None;
+        for &j in &self.touched {
+            let Some(theta) = limit(j, 0.0) else {
+                continue;
+            };
+            if theta > theta_max {
+                continue;
+            }
+            let better = match best {
+                None => true,
+                Some(b) if bland => j < b,
+                Some(b) => self.alpha_row[j].abs() > self.alpha_row[b].abs(),
+            };
+            if better {
+                best = Some(j);
+            }
+        }
+        best
+    }
+
+    /// The dual simplex method; the basis must be dual feasible.
+    fn dual(&mut self, settings: &Settings, limits: &mut Limits) -> Result<DualOutcome, SimplexError> {
+        let ptol = settings.primal_tolerance;
+        let dtol = settings.dual_tolerance;
+        let mut degenerate = 0;
+
+        loop {
+            if self.factor.num_updates() >= settings.refactor_frequency {
+                self.refactor();
+                self.compute_primal();
+                self.compute_dual(false, ptol);
+            }
+
+            let bland = degenerate > DEGENERATE_ITERATIONS;
+            let Some(r) = self.price_dual(ptol, bland) else {
+                if self.refresh() {
+                    self.compute_dual(false, ptol);
+                    continue;
+                }
+                // Check the reduced costs have not drifted from dual feasibility
+                self.compute_dual(false, ptol);
+                let (flips, infeasibilities) = self.make_dual_feasible(dtol);
+                if flips > 0 {
+                    self.compute_primal();
+                }
+                if infeasibilities > 0 {
+                    return Ok(DualOutcome::DualInfeasible);
+                } else if flips > 0 {
+                    // The flipped variables may have made the basis primal infeasible
+                    continue;
+                }
+                return Ok(DualOutcome::Optimal);
+            };
+
+            limits.iterate()?;
+
+            let leaving = self.basis[r];
+            let to_upper = self.x[leaving] > self.upper[leaving];
+            let bound = if to_upper {
+                self.upper[leaving]
+            } else {
+                self.lower[leaving]
+            };
+            let delta = self.x[leaving] - bound;
+            let sign = delta.signum();
+
+            self.compute_alpha_row(r);
+            let Some(q) = self.dual_ratio_test(sign, dtol, bland) else {
+                if self.refresh() {
+                    self.compute_dual(false, ptol);
+                    continue;
+                }
+                return Err(SimplexError::Infeasible);
+            };
+
+            self.compute_alpha(q);
+            let pivot = self.alpha[r];
+            if (pivot - self.alpha_row[q]).abs() > 1e-6 * (1.0 + pivot.abs()) || pivot.abs() <= PIVOT_TOLERANCE {
+                // The row and column disagree; the factorisation has lost accuracy.
+                if self.refresh() {
+                    self.compute_dual(false, ptol);
+                    continue;
+                }
+                return Err(SimplexError::NumericalDifficulties);
+            }
+
+            let theta_dual = self.d[q] / self.alpha_row[q];
+            let theta_primal = delta / pivot;
+
+            self.update_primal(q, theta_primal);
+            self.x[leaving] = bound;
+
+            for &j in &self.touched {
+                self.d[j] -= theta_dual * self.alpha_row[j];
+            }
+            self.d[q] = 0.0;
+            self.d[leaving] = -theta_dual;
+
+            self.status[leaving] = if to_upper { Status::AtUpper } else { Status::AtLower };
+            self.basis[r] = q;
+            self.status[q] = Status::Basic;
+            self.factor.update(r, &self.alpha);
+
+            if theta_dual.abs() > dtol {
+                degenerate = 0;
+            } else {
+                degenerate += 1;
+            }
+
+            // Flip any boxed variables whose reduced cost now has the wrong sign
+            let mut flipped = false;
+            self.work.fill(0.0);
+            for idx in 0..self.touched.len() {
+                let j = self.touched[idx];
+                if self.status[j] == Status::Basic || !self.is_boxed(j) {
+                    continue;
+                }
+                let dj = self.d[j];
+                let step = match self.status[j] {
+                    Status::AtLower if dj < -dtol => {
+                        self.status[j] = Status::AtUpper;
+                        self.upper[j] - self.lower[j]
+                    }
+                    Status::AtUpper if dj > dtol => {
+                        self.status[j] = Status::AtLower;
+                        self.lower[j] - self.upper[j]
+                    }
+                    _ => continue,
+                };
+                self.x[j] = self.nonbasic_value(j);
+                let mut work = std::mem::take(&mut self.work);
+                self.for_each_in_column(j, |i, v| work[i] += v * step);
+                self.work = work;
+                flipped = true;
+            }
+            if flipped {
+                self.factor.ftran(&mut self.work);
+                for p in 0..self.num_rows {
+                    self.x[self.basis[p]] -= self.work[p];
+                }
+            }
+        }
+    }
+}
+
+/// Treat very large bounds as infinite.
+fn normalise_bound(value: f64) -> f64 {
+    if value >= 1e30 {
+        f64::INFINITY
+    } else if value <= -1e30 {
+        f64::NEG_INFINITY
+    } else {
+        value
+    }
+}